@@ -0,0 +1,36 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use chorus::gateway::fixtures::{CHANNEL_CREATE, GUILD_CREATE, GUILD_ROLE_CREATE};
+use serde_json::Value;
+
+/// Returns the `GUILD_CREATE` fixture, grown to a guild with the given amount of channels and
+/// roles, to resemble the payloads received for large guilds after Ready.
+pub fn large_guild_json(channels: usize, roles: usize) -> String {
+    let mut guild: Value = serde_json::from_str(GUILD_CREATE).unwrap();
+    let channel: Value = serde_json::from_str(CHANNEL_CREATE).unwrap();
+    let role: Value = serde_json::from_str::<Value>(GUILD_ROLE_CREATE).unwrap()["role"].clone();
+
+    guild["channels"] = (0..channels)
+        .map(|i| {
+            let mut channel = channel.clone();
+            channel["id"] = (1200000000000000000u64 + i as u64).to_string().into();
+            channel["name"] = format!("channel-{i}").into();
+            channel["position"] = i.into();
+            channel
+        })
+        .collect();
+
+    guild["roles"] = (0..roles)
+        .map(|i| {
+            let mut role = role.clone();
+            role["id"] = (1300000000000000000u64 + i as u64).to_string().into();
+            role["name"] = format!("role-{i}").into();
+            role["position"] = i.into();
+            role
+        })
+        .collect();
+
+    guild.to_string()
+}
@@ -0,0 +1,61 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Parsing of received gateway payloads.
+
+mod common;
+
+use chorus::gateway::fixtures::{GUILD_CREATE, MESSAGE_CREATE, READY};
+use chorus::gateway::GatewayMessage;
+use chorus::types::{GatewayReady, GuildCreate, MessageCreate};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+/// Wraps event data in a dispatch payload, as it is received from the gateway
+fn dispatch(event_name: &str, data: &str) -> GatewayMessage {
+    GatewayMessage(format!(
+        r#"{{"op":0,"s":42,"t":"{event_name}","d":{data}}}"#
+    ))
+}
+
+fn payload(c: &mut Criterion) {
+    let mut group = c.benchmark_group("gateway/payload");
+
+    let message_create = dispatch("MESSAGE_CREATE", MESSAGE_CREATE);
+    group.bench_function("message_create", |b| {
+        b.iter(|| black_box(&message_create).payload().unwrap())
+    });
+
+    let large_guild = dispatch("GUILD_CREATE", &common::large_guild_json(500, 250));
+    group.bench_function("large_guild_create", |b| {
+        b.iter(|| black_box(&large_guild).payload().unwrap())
+    });
+
+    group.finish();
+}
+
+fn deserialize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("gateway/deserialize");
+
+    group.bench_function("message_create", |b| {
+        b.iter(|| serde_json::from_str::<MessageCreate>(black_box(MESSAGE_CREATE)).unwrap())
+    });
+
+    group.bench_function("ready", |b| {
+        b.iter(|| serde_json::from_str::<GatewayReady>(black_box(READY)).unwrap())
+    });
+
+    group.bench_function("guild_create", |b| {
+        b.iter(|| serde_json::from_str::<GuildCreate>(black_box(GUILD_CREATE)).unwrap())
+    });
+
+    let large_guild = common::large_guild_json(500, 250);
+    group.bench_function("large_guild_create", |b| {
+        b.iter(|| serde_json::from_str::<GuildCreate>(black_box(&large_guild)).unwrap())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, payload, deserialize);
+criterion_main!(benches);
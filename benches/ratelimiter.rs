@@ -0,0 +1,63 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Rate limit bookkeeping done for every request.
+
+use std::sync::{Arc, RwLock};
+
+use chorus::benchmarks::{can_send_request, limits_config_to_hashmap, update_rate_limits};
+use chorus::instance::{ChorusUser, Instance};
+use chorus::types::{IntoShared, LimitType, LimitsConfiguration, Snowflake, User, UserSettings};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+
+const CHANNEL_ID: Snowflake = Snowflake(1100000000000000002);
+
+/// Creates a user on a rate limited instance, with a tracked bucket for [CHANNEL_ID]
+fn limited_user() -> ChorusUser {
+    let configuration = LimitsConfiguration::default();
+
+    let mut instance = Instance::default();
+    instance.set_limits_configuration(configuration.clone());
+
+    let mut user = ChorusUser::new_rest_only(
+        Arc::new(RwLock::new(instance)),
+        "token".to_string(),
+        Some(limits_config_to_hashmap(&configuration.rate)),
+        UserSettings::default().into_shared(),
+        User::default().into_shared(),
+    );
+    can_send_request(&mut user, &LimitType::Channel(CHANNEL_ID));
+    user
+}
+
+fn ratelimiter(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ratelimiter");
+
+    let rate_limits = LimitsConfiguration::default().rate;
+    group.bench_function("limits_config_to_hashmap", |b| {
+        b.iter(|| limits_config_to_hashmap(black_box(&rate_limits)))
+    });
+
+    let mut user = limited_user();
+    group.bench_function("can_send_request/global", |b| {
+        b.iter(|| can_send_request(&mut user, black_box(&LimitType::Global)))
+    });
+    group.bench_function("can_send_request/channel", |b| {
+        b.iter(|| can_send_request(&mut user, black_box(&LimitType::Channel(CHANNEL_ID))))
+    });
+
+    // Every iteration needs a fresh user, so the buckets never run dry
+    group.bench_function("update_rate_limits", |b| {
+        b.iter_batched_ref(
+            limited_user,
+            |user| update_rate_limits(user, &LimitType::Channel(CHANNEL_ID), false),
+            BatchSize::SmallInput,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, ratelimiter);
+criterion_main!(benches);
@@ -0,0 +1,26 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Snowflake generation and conversions.
+
+use chorus::types::Snowflake;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn snowflake(c: &mut Criterion) {
+    let mut group = c.benchmark_group("snowflake");
+
+    group.bench_function("generate", |b| b.iter(Snowflake::generate));
+
+    let snowflake = Snowflake(1100000000000000001);
+    group.bench_function("timestamp", |b| b.iter(|| black_box(snowflake).timestamp()));
+    group.bench_function("to_string", |b| b.iter(|| black_box(snowflake).to_string()));
+    group.bench_function("deserialize", |b| {
+        b.iter(|| serde_json::from_str::<Snowflake>(black_box(r#""1100000000000000001""#)).unwrap())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, snowflake);
+criterion_main!(benches);
@@ -0,0 +1,52 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Parsing update events and merging them into observed objects.
+
+mod common;
+
+use chorus::benchmarks::update_object;
+use chorus::gateway::fixtures::{CHANNEL_CREATE, CHANNEL_UPDATE, GUILD_UPDATE};
+use chorus::types::{Channel, Guild, IntoShared};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use serde_json::value::RawValue;
+
+fn bench_update_object(c: &mut Criterion) {
+    let mut group = c.benchmark_group("update_object");
+
+    let channel: Channel = serde_json::from_str(CHANNEL_CREATE).unwrap();
+    let channel_update = RawValue::from_string(CHANNEL_UPDATE.to_string()).unwrap();
+    group.bench_function("channel", |b| {
+        b.iter_batched(
+            || channel.clone().into_shared(),
+            |channel| update_object(&channel_update, channel),
+            BatchSize::SmallInput,
+        )
+    });
+
+    let partial_update = RawValue::from_string(r#"{"name": "renamed"}"#.to_string()).unwrap();
+    group.bench_function("channel_single_field", |b| {
+        b.iter_batched(
+            || channel.clone().into_shared(),
+            |channel| update_object(&partial_update, channel),
+            BatchSize::SmallInput,
+        )
+    });
+
+    // Only the fields in the event are replaced, so the guild's channels and roles shouldn't matter
+    let guild: Guild = serde_json::from_str(&common::large_guild_json(500, 250)).unwrap();
+    let guild_update = RawValue::from_string(GUILD_UPDATE.to_string()).unwrap();
+    group.bench_function("large_guild", |b| {
+        b.iter_batched(
+            || guild.clone().into_shared(),
+            |guild| update_object(&guild_update, guild),
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_update_object);
+criterion_main!(benches);
@@ -4,7 +4,7 @@
 
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, Data, DeriveInput, Field, Fields, FieldsNamed};
+use syn::{parse_macro_input, Data, DeriveInput, Field, Fields, FieldsNamed, LitStr};
 
 #[proc_macro_derive(WebSocketEvent)]
 pub fn websocket_event_macro_derive(input: TokenStream) -> TokenStream {
@@ -34,25 +34,101 @@ pub fn updateable_macro_derive(input: TokenStream) -> TokenStream {
     .into()
 }
 
-#[proc_macro_derive(JsonField)]
-pub fn jsonfield_macro_derive(input: TokenStream) -> TokenStream {
-    let ast: syn::DeriveInput = syn::parse(input).unwrap();
+/// Implements `PartialUpdate`, which overwrites the fields of an entity that were present in an
+/// update event.
+///
+/// Fields are matched by the name they are deserialized from, following their `#[serde(rename)]`
+/// and `#[serde(alias)]` attributes; skipped fields are never overwritten. Container level
+/// attributes such as `#[serde(rename_all)]` and flattened fields are not supported.
+#[proc_macro_derive(PartialUpdate)]
+pub fn partial_update_derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
 
-    let name = &ast.ident;
-    // No need for macro hygiene, we're only using this in chorus
-    quote! {
-        impl JsonField for #name {
-            fn get_json(&self) -> String {
-                self.json.clone()
+    let Data::Struct(data) = &input.data else {
+        panic!("PartialUpdate derive macro only supports structs");
+    };
+    let Fields::Named(FieldsNamed { named, .. }) = &data.fields else {
+        panic!("PartialUpdate derive macro only supports named fields");
+    };
+
+    let updates = named.iter().filter_map(|field| {
+        let field_name = &field.ident;
+        let names = serde_field_names(field);
+        if names.is_empty() {
+            return None;
+        }
+
+        Some(quote! {
+            if fields.iter().any(|field| #(field == #names)||*) {
+                self.#field_name = update.#field_name;
             }
-            fn set_json(&mut self, json: String) {
-                self.json = json;
+        })
+    });
+
+    let ident = &input.ident;
+    quote! {
+        impl PartialUpdate for #ident {
+            fn update_fields(&mut self, update: Self, fields: &[std::borrow::Cow<str>]) {
+                #(#updates)*
             }
         }
     }
     .into()
 }
 
+/// Returns the names a field is deserialized from, or nothing if it is never deserialized.
+fn serde_field_names(field: &Field) -> Vec<String> {
+    let ident = field.ident.as_ref().unwrap().to_string();
+    let mut name = ident.trim_start_matches("r#").to_string();
+    let mut aliases = Vec::new();
+    let mut deserialized = true;
+
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                if meta.input.peek(syn::Token![=]) {
+                    name = meta.value()?.parse::<LitStr>()?.value();
+                    return Ok(());
+                }
+                return meta.parse_nested_meta(|meta| {
+                    let value = meta.value()?.parse::<LitStr>()?.value();
+                    if meta.path.is_ident("deserialize") {
+                        name = value;
+                    }
+                    Ok(())
+                });
+            }
+
+            if meta.path.is_ident("alias") {
+                aliases.push(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_deserializing") {
+                deserialized = false;
+            } else if meta.path.is_ident("flatten") {
+                panic!("PartialUpdate derive macro does not support flattened fields");
+            } else if meta.input.peek(syn::Token![=]) {
+                // Other attributes, such as `default = "..."` or `with = "..."`
+                meta.value()?.parse::<syn::Expr>()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                let _content;
+                syn::parenthesized!(_content in meta.input);
+            }
+            Ok(())
+        })
+        .expect("Invalid serde attribute");
+    }
+
+    if !deserialized {
+        return Vec::new();
+    }
+
+    aliases.insert(0, name);
+    aliases
+}
+
 #[proc_macro_derive(SourceUrlField)]
 pub fn source_url_macro_derive(input: TokenStream) -> TokenStream {
     let ast: syn::DeriveInput = syn::parse(input).unwrap();
@@ -156,6 +232,14 @@ pub fn composite_derive(input: TokenStream) -> TokenStream {
     }
 }
 
+/// Implements `sqlx::Type`, `sqlx::Encode` and `sqlx::Decode` for a bitflags struct.
+///
+/// Which databases the impls are emitted for depends on the features of the crate using the
+/// derive:
+/// - `sqlx-pg-uint`: Postgres, stored as a `numeric` via `sqlx_pg_uint::PgU64`
+/// - `sqlx-mysql`: MySQL, stored as a `BIGINT UNSIGNED`
+/// - `sqlx-sqlite`: SQLite, stored as an `INTEGER`; the bits are reinterpreted as an [i64], so
+///   flags in the highest bit read back as negative numbers in raw queries
 #[proc_macro_derive(SqlxBitFlags)]
 pub fn sqlx_bitflag_derive(input: TokenStream) -> TokenStream {
     let ast: syn::DeriveInput = syn::parse(input).unwrap();
@@ -163,27 +247,78 @@ pub fn sqlx_bitflag_derive(input: TokenStream) -> TokenStream {
     let name = &ast.ident;
 
     quote!{
-        #[cfg(feature = "sqlx")]
+        #[cfg(feature = "sqlx-pg-uint")]
         impl sqlx::Type<sqlx::Postgres> for #name {
             fn type_info() -> sqlx::postgres::PgTypeInfo {
                 <sqlx_pg_uint::PgU64 as sqlx::Type<sqlx::Postgres>>::type_info()
             }
         }
 
-        #[cfg(feature = "sqlx")]
+        #[cfg(feature = "sqlx-pg-uint")]
         impl<'q> sqlx::Encode<'q, sqlx::Postgres> for #name {
             fn encode_by_ref(&self, buf: &mut <sqlx::Postgres as sqlx::Database>::ArgumentBuffer<'q>) -> Result<sqlx::encode::IsNull, sqlx::error::BoxDynError> {
                 <sqlx_pg_uint::PgU64 as sqlx::Encode<sqlx::Postgres>>::encode_by_ref(&self.bits().into(), buf)
             }
         }
 
-        #[cfg(feature = "sqlx")]
+        #[cfg(feature = "sqlx-pg-uint")]
         impl<'q> sqlx::Decode<'q, sqlx::Postgres> for #name {
             fn decode(value: <sqlx::Postgres as sqlx::Database>::ValueRef<'q>) -> Result<Self, sqlx::error::BoxDynError> {
                 <sqlx_pg_uint::PgU64 as sqlx::Decode<sqlx::Postgres>>::decode(value).map(|v| Self::from_bits_truncate(v.to_uint()))
             }
         }
 
+        #[cfg(feature = "sqlx-mysql")]
+        impl sqlx::Type<sqlx_mysql::MySql> for #name {
+            fn type_info() -> sqlx_mysql::MySqlTypeInfo {
+                <u64 as sqlx::Type<sqlx_mysql::MySql>>::type_info()
+            }
+
+            fn compatible(ty: &sqlx_mysql::MySqlTypeInfo) -> bool {
+                <u64 as sqlx::Type<sqlx_mysql::MySql>>::compatible(ty)
+            }
+        }
+
+        #[cfg(feature = "sqlx-mysql")]
+        impl<'q> sqlx::Encode<'q, sqlx_mysql::MySql> for #name {
+            fn encode_by_ref(&self, buf: &mut <sqlx_mysql::MySql as sqlx::Database>::ArgumentBuffer<'q>) -> Result<sqlx::encode::IsNull, sqlx::error::BoxDynError> {
+                <u64 as sqlx::Encode<sqlx_mysql::MySql>>::encode_by_ref(&self.bits(), buf)
+            }
+        }
+
+        #[cfg(feature = "sqlx-mysql")]
+        impl<'q> sqlx::Decode<'q, sqlx_mysql::MySql> for #name {
+            fn decode(value: <sqlx_mysql::MySql as sqlx::Database>::ValueRef<'q>) -> Result<Self, sqlx::error::BoxDynError> {
+                <u64 as sqlx::Decode<sqlx_mysql::MySql>>::decode(value).map(Self::from_bits_truncate)
+            }
+        }
+
+        #[cfg(feature = "sqlx-sqlite")]
+        impl sqlx::Type<sqlx::Sqlite> for #name {
+            fn type_info() -> sqlx::sqlite::SqliteTypeInfo {
+                <i64 as sqlx::Type<sqlx::Sqlite>>::type_info()
+            }
+
+            fn compatible(ty: &sqlx::sqlite::SqliteTypeInfo) -> bool {
+                <i64 as sqlx::Type<sqlx::Sqlite>>::compatible(ty)
+            }
+        }
+
+        #[cfg(feature = "sqlx-sqlite")]
+        impl<'q> sqlx::Encode<'q, sqlx::Sqlite> for #name {
+            fn encode_by_ref(&self, buf: &mut <sqlx::Sqlite as sqlx::Database>::ArgumentBuffer<'q>) -> Result<sqlx::encode::IsNull, sqlx::error::BoxDynError> {
+                // SQLite only has signed integers, so store the bits as they are
+                <i64 as sqlx::Encode<sqlx::Sqlite>>::encode_by_ref(&(self.bits() as i64), buf)
+            }
+        }
+
+        #[cfg(feature = "sqlx-sqlite")]
+        impl<'q> sqlx::Decode<'q, sqlx::Sqlite> for #name {
+            fn decode(value: <sqlx::Sqlite as sqlx::Database>::ValueRef<'q>) -> Result<Self, sqlx::error::BoxDynError> {
+                <i64 as sqlx::Decode<sqlx::Sqlite>>::decode(value).map(|v| Self::from_bits_truncate(v as u64))
+            }
+        }
+
         /// Converts a [Vec<u8>] to an unsigned, 64 bit integer. The [u64] is created using [u64::from_be_bytes].
         ///
         /// Empty vectors will result in an output of `0_u64`. Only the first 8 values from the vector are
@@ -218,7 +353,8 @@ pub fn serde_bitflag_derive(input: TokenStream) -> TokenStream {
             type Err = std::num::ParseIntError;
 
             fn from_str(s: &str) -> Result<#name, Self::Err> {
-                s.parse::<u64>().map(#name::from_bits).map(|f| f.unwrap_or(#name::empty()))
+                // Like deserializing, this truncates unknown flags instead of discarding all of them
+                s.parse::<u64>().map(#name::from_bits_truncate)
             }
         }
 
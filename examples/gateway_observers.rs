@@ -4,7 +4,7 @@
 
 // This example showcase how to properly use gateway observers.
 // (This assumes you have a manually created gateway, if you created
-// a ChorusUser by e.g. logging in, you can access the gateway with user.gateway)
+// a ChorusUser by e.g. logging in, you can access the gateway with user.gateway())
 //
 // To properly run it, you will need to change the token below.
 
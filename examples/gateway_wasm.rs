@@ -0,0 +1,77 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+// This example showcases how to connect to the gateway from a browser.
+//
+// Build it with
+// `cargo build --example gateway_wasm --target=wasm32-unknown-unknown --no-default-features --features="rt, client"`
+// and load it into a page with wasm-bindgen.
+//
+// To properly run it, you will need to modify the token below.
+
+const TOKEN: &str = "";
+
+/// Find the gateway websocket url of the server we want to connect to
+///
+/// Note: if the page is served over https, chorus will automatically connect via wss://
+const GATEWAY_URL: &str = "ws://gateway.old.server.spacebar.chat/";
+
+use std::time::Duration;
+
+use chorus::gateway::{Gateway, GatewayOptions, BROWSER_HEARTBEAT_JITTER};
+use chorus::{self, types::GatewayIdentifyPayload};
+
+async fn run() {
+    // The default options are already tuned for browsers when compiling to wasm32:
+    //
+    // - the connection is upgraded to wss:// if the page was loaded over https
+    // - heartbeats are sent a little early, since browsers throttle timers in background tabs
+    // - the connection is checked (and the session resumed if it was lost) as soon as the tab
+    //   becomes visible again
+    //
+    // We set them explicitly here to show what they are
+    let options = GatewayOptions {
+        heartbeat_jitter: BROWSER_HEARTBEAT_JITTER,
+        upgrade_insecure_websocket: true,
+        resume_on_visibility_change: true,
+        ..Default::default()
+    };
+
+    // Initiate the gateway connection, this spawns the listener and heartbeat tasks on the
+    // browser's event loop
+    let gateway = Gateway::spawn(GATEWAY_URL, options).await.unwrap();
+
+    let mut identify = GatewayIdentifyPayload::common();
+    identify.token = TOKEN.to_string();
+
+    gateway.send_identify(identify).await;
+
+    // Keep the connection around; in a real application you would store the handle in your
+    // application state instead
+    loop {
+        sleep(Duration::from_secs(3600)).await;
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::time::sleep;
+#[cfg(target_arch = "wasm32")]
+use wasmtimer::tokio::sleep;
+
+/// In the browser, there is no main function we can block on; instead we spawn our future
+/// on the browser's event loop once the module is loaded
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen::prelude::wasm_bindgen(start)]
+pub fn start() {
+    wasm_bindgen_futures::spawn_local(run());
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    run().await;
+}
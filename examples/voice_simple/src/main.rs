@@ -4,6 +4,9 @@
 
 // This example showcases how to use the voice udp channel.
 //
+// It wires the voice components together by hand, to show how they interact. If you just want to
+// join a voice channel, ChorusUser::join_voice does all of this for you.
+//
 // To use this to properly communicate with voice, you will need to bring your own opus bindings
 // along with potentially sending some other events, like Speaking
 //
@@ -19,17 +22,18 @@ const GATEWAY_URL: &str = "wss://gateway.discord.gg";
 
 use async_trait::async_trait;
 use simplelog::{TermLogger, Config, WriteLogger};
-use std::{net::SocketAddrV4, sync::Arc, fs::File, time::Duration};
+use std::{net::SocketAddr, sync::Arc, fs::File, time::Duration};
 
 use chorus::{
     gateway::{Gateway, GatewayOptions, Observer},
     types::{
-        GatewayIdentifyPayload, GatewayReady, SelectProtocol, SelectProtocolData, SessionDescription, Snowflake, Speaking, SpeakingBitflags, SsrcDefinition, UpdateVoiceState, VoiceEncryptionMode, VoiceIdentify, VoiceProtocol, VoiceReady, VoiceServerUpdate
+        GatewayIdentifyPayload, GatewayReady, SelectProtocol, SelectProtocolData, SessionDescription, Snowflake, Speaking, SpeakingBitflags, SsrcDefinition, UpdateVoiceState, VoiceEncryptionMode, VoiceProtocol, VoiceReady, VoiceServerUpdate
     },
     voice::{
         gateway::{VoiceGateway, VoiceGatewayHandle},
         udp::{UdpHandle, UdpHandler},
         voice_data::VoiceData,
+        VoiceOptions,
     },
 };
 use log::{info, LevelFilter};
@@ -70,8 +74,9 @@ impl Observer<VoiceServerUpdate> for VoiceHandler {
         let mut data_lock = self.data.write().await;
 
         data_lock.server_data = Some(data.clone());
-        let user_id = data_lock.user_id;
-        let session_id = data_lock.session_id.clone();
+
+        // Works for both guild voice channels and dm / group dm calls
+        let voice_identify = data_lock.voice_identify(Some(false)).unwrap();
 
         drop(data_lock);
 
@@ -80,22 +85,6 @@ impl Observer<VoiceServerUpdate> for VoiceHandler {
             .await
             .unwrap();
 
-        let server_id: Snowflake;
-
-        if data.guild_id.is_some() {
-            server_id = data.guild_id.unwrap();
-        } else {
-            server_id = data.channel_id.unwrap();
-        }
-
-        let voice_identify = VoiceIdentify {
-            server_id,
-            user_id,
-            session_id,
-            token: data.token.clone(),
-            video: Some(false),
-        };
-
         voice_gateway_handle.send_identify(voice_identify).await;
 
         let cloned_gateway_handle = voice_gateway_handle.clone();
@@ -132,8 +121,9 @@ impl Observer<VoiceReady> for VoiceHandler {
         // Create a udp connection and perform ip discovery
         let udp_handle = UdpHandler::spawn(
             self.data.clone(),
-            std::net::SocketAddr::V4(SocketAddrV4::new(data.ip, data.port)),
+            SocketAddr::new(data.ip, data.port),
             data.ssrc,
+            VoiceOptions::default(),
         )
         .await
         .unwrap();
@@ -146,13 +136,11 @@ impl Observer<VoiceReady> for VoiceHandler {
             .rtp
             .subscribe(Arc::new(self.clone()));
 
-        let ip_discovery = self.data.read().await.ip_discovery.clone().unwrap();
+        // Our external address, which may be an IPv4 or IPv6 address
+        let external_address = self.data.read().await.external_address().unwrap();
 
         *self.voice_udp_connection.lock().await = Some(udp_handle.clone());
 
-        let string_ip_address =
-            String::from_utf8(ip_discovery.address).expect("Ip discovery gave non string ip");
-
         // Send a select protocol, which tells the server where we'll be receiving data and what
         // mode to encrypt data in
         self.voice_gateway_connection
@@ -163,8 +151,8 @@ impl Observer<VoiceReady> for VoiceHandler {
             .send_select_protocol(SelectProtocol {
                 protocol: VoiceProtocol::Udp,
                 data: SelectProtocolData {
-                    address: string_ip_address,
-                    port: ip_discovery.port,
+                    address: external_address.ip().to_string(),
+                    port: external_address.port(),
                     // There are several other voice encryption modes available, though not all are
                     // implemented in chorus
                     mode: VoiceEncryptionMode::Xsalsa20Poly1305,
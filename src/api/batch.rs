@@ -0,0 +1,201 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Helpers for fetching many entities at once, such as [Guild::get_many](crate::types::Guild::get_many).
+
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::time::sleep;
+#[cfg(target_arch = "wasm32")]
+use wasmtimer::tokio::sleep;
+
+use futures_util::{stream, StreamExt};
+
+use crate::errors::{ChorusError, ChorusResult};
+use crate::instance::ChorusUser;
+use crate::types::{Limit, LimitType, Snowflake};
+
+/// How many requests batch fetches send at the same time by default.
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 4;
+
+/// How often a single id is attempted if we keep getting rate limited.
+const MAX_ATTEMPTS: usize = 3;
+
+#[derive(Debug, Default)]
+/// The result of fetching many entities at once.
+///
+/// Failing to fetch one entity does not fail the entire batch; the error is returned in
+/// [BatchResult::errors] instead.
+pub struct BatchResult<T> {
+    /// The entities which were fetched successfully, by their id
+    pub fetched: HashMap<Snowflake, T>,
+    /// The errors for the ids which could not be fetched
+    pub errors: HashMap<Snowflake, ChorusError>,
+}
+
+impl<T> BatchResult<T> {
+    /// Returns whether all requested entities were fetched successfully.
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Fetches the entities with the given `ids`, sending at most `concurrency` requests at the
+/// same time.
+///
+/// Each request is sent with a clone of `user`. The user's rate limits are shared between the
+/// clones, so that requests wait until their bucket (see `limit_type`) is available instead
+/// of failing with [ChorusError::RateLimited]. Once done, `user`'s rate limits are updated.
+pub(crate) async fn fetch_many<T, F, Fut>(
+    user: &mut ChorusUser,
+    ids: &[Snowflake],
+    concurrency: usize,
+    limit_type: fn(Snowflake) -> LimitType,
+    fetch: F,
+) -> BatchResult<T>
+where
+    F: Fn(ChorusUser, Snowflake) -> Fut,
+    Fut: Future<Output = (ChorusUser, ChorusResult<T>)>,
+{
+    let mut seen = HashSet::new();
+    let ids = ids.iter().copied().filter(|id| seen.insert(*id));
+
+    let shared_limits = Arc::new(Mutex::new(user.limits.clone()));
+    let template = user.clone();
+
+    let results = stream::iter(ids)
+        .map(|id| {
+            let mut user = template.clone();
+            let shared_limits = shared_limits.clone();
+            let fetch = &fetch;
+
+            async move {
+                let mut attempts = 0;
+                loop {
+                    attempts += 1;
+
+                    user.limits = shared_limits.lock().unwrap().clone();
+                    let delay = user.time_until_available(&limit_type(id));
+                    if !delay.is_zero() {
+                        log::debug!("Waiting {:?} before fetching {}", delay, id);
+                        sleep(delay).await;
+                        user.limits = shared_limits.lock().unwrap().clone();
+                    }
+
+                    let (returned_user, result) = fetch(user, id).await;
+                    user = returned_user;
+
+                    if let Some(limits) = user.limits.as_ref() {
+                        merge_limits(&mut shared_limits.lock().unwrap(), limits);
+                    }
+
+                    match result {
+                        Err(ChorusError::RateLimited { .. }) if attempts < MAX_ATTEMPTS => continue,
+                        result => return (id, result),
+                    }
+                }
+            }
+        })
+        .buffer_unordered(concurrency.max(1))
+        .collect::<Vec<_>>()
+        .await;
+
+    user.limits = shared_limits.lock().unwrap().take();
+
+    let mut batch_result = BatchResult {
+        fetched: HashMap::new(),
+        errors: HashMap::new(),
+    };
+    for (id, result) in results {
+        match result {
+            Ok(entity) => {
+                batch_result.fetched.insert(id, entity);
+            }
+            Err(error) => {
+                batch_result.errors.insert(id, error);
+            }
+        }
+    }
+    batch_result
+}
+
+/// Merges the rate limits a request ended with into the limits shared between all requests
+/// of a batch.
+///
+/// For every bucket, the state with the later reset wins; if both are in the same window, the
+/// one with fewer remaining requests does.
+pub(crate) fn merge_limits(
+    shared: &mut Option<HashMap<LimitType, Limit>>,
+    limits: &HashMap<LimitType, Limit>,
+) {
+    let shared = shared.get_or_insert_with(HashMap::new);
+
+    for (limit_type, limit) in limits {
+        match shared.get_mut(limit_type) {
+            Some(existing) => {
+                if limit.reset > existing.reset
+                    || (limit.reset == existing.reset && limit.remaining < existing.remaining)
+                {
+                    *existing = *limit;
+                }
+            }
+            None => {
+                shared.insert(*limit_type, *limit);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::merge_limits;
+    use crate::types::{Limit, LimitType, Snowflake};
+
+    fn limit(bucket: LimitType, remaining: u64, reset: u64) -> Limit {
+        Limit {
+            bucket,
+            limit: 10,
+            remaining,
+            reset,
+            window: 60,
+        }
+    }
+
+    #[test]
+    fn test_merge_limits() {
+        let guild = LimitType::Guild(Snowflake(1));
+        let channel = LimitType::Channel(Snowflake(2));
+        let webhook = LimitType::Webhook(Snowflake(3));
+
+        let mut shared = Some(HashMap::from([
+            (guild, limit(guild, 5, 100)),
+            (channel, limit(channel, 5, 100)),
+        ]));
+
+        let limits = HashMap::from([
+            // Same window, fewer requests remaining
+            (guild, limit(guild, 3, 100)),
+            // Older window
+            (channel, limit(channel, 1, 40)),
+            (webhook, limit(webhook, 9, 100)),
+        ]);
+
+        merge_limits(&mut shared, &limits);
+        let shared = shared.unwrap();
+
+        assert_eq!(shared[&guild].remaining, 3);
+        assert_eq!(shared[&channel].remaining, 5);
+        assert_eq!(shared[&channel].reset, 100);
+        assert_eq!(shared[&webhook].remaining, 9);
+
+        let mut empty = None;
+        merge_limits(&mut empty, &HashMap::from([(guild, limit(guild, 0, 200))]));
+        assert_eq!(empty.unwrap()[&guild].reset, 200);
+    }
+}
@@ -0,0 +1,73 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use serde_json::to_string;
+
+use crate::{
+    errors::ChorusResult,
+    instance::ChorusUser,
+    ratelimiter::ChorusRequest,
+    types::{CallRingSchema, Channel, LimitType, Snowflake},
+};
+
+impl Channel {
+    /// Rings the recipients of a DM or group DM call, inviting them to join it.
+    ///
+    /// Updates the call's ringing list, which fires a [CallUpdate](crate::types::CallUpdate)
+    /// gateway event.
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/channel#ring-channel-recipients>
+    pub async fn ring(
+        channel_id: Snowflake,
+        schema: CallRingSchema,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<()> {
+        let url = format!(
+            "{}/channels/{}/call/ring",
+            user.belongs_to.read().unwrap().urls.api,
+            channel_id
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::POST,
+            &url,
+            Some(to_string(&schema).unwrap()),
+            None,
+            Some(user),
+            LimitType::Channel(channel_id),
+        );
+
+        request.handle_request_as_result(user).await
+    }
+
+    /// Stops ringing the recipients of a DM or group DM call.
+    ///
+    /// Passing the current user's id declines an incoming call.
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/channel#stop-ringing-channel-recipients>
+    pub async fn stop_ringing(
+        channel_id: Snowflake,
+        schema: CallRingSchema,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<()> {
+        let url = format!(
+            "{}/channels/{}/call/stop-ringing",
+            user.belongs_to.read().unwrap().urls.api,
+            channel_id
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::POST,
+            &url,
+            Some(to_string(&schema).unwrap()),
+            None,
+            Some(user),
+            LimitType::Channel(channel_id),
+        );
+
+        request.handle_request_as_result(user).await
+    }
+}
@@ -5,7 +5,10 @@
 use reqwest::Client;
 use serde_json::to_string;
 
-use crate::types::{AddChannelRecipientSchema, ModifyChannelPositionsSchema};
+use crate::types::{
+    AddChannelRecipientSchema, AddFollowingChannelSchema, FollowedChannel,
+    ModifyChannelPositionsSchema, Webhook,
+};
 use crate::{
     errors::{ChorusError, ChorusResult},
     instance::ChorusUser,
@@ -15,7 +18,34 @@ use crate::{
     },
 };
 
+/// The maximum length of a channel's name, which is not configurable per instance
+pub const MAX_CHANNEL_NAME_LENGTH: u64 = 100;
+
 impl Channel {
+    /// Checks the name and topic of a channel against the
+    /// [instance's limits](crate::instance::Instance::limits_configuration).
+    pub(crate) fn check_limits(
+        user: &ChorusUser,
+        name: Option<&str>,
+        topic: Option<&str>,
+    ) -> ChorusResult<()> {
+        if let Some(name) = name {
+            user.check_limit("channel name length", name.chars().count() as u64, |_| {
+                MAX_CHANNEL_NAME_LENGTH
+            })?;
+        }
+
+        if let Some(topic) = topic {
+            user.check_limit(
+                "channel topic length",
+                topic.chars().count() as u64,
+                |limits| limits.channel.max_topic as u64,
+            )?;
+        }
+
+        Ok(())
+    }
+
     /// Retrieves a channel from the server.
     ///
     /// # Reference
@@ -87,6 +117,12 @@ impl Channel {
         audit_log_reason: Option<String>,
         user: &mut ChorusUser,
     ) -> ChorusResult<Channel> {
+        Channel::check_limits(
+            user,
+            modify_data.name.as_deref(),
+            modify_data.topic.as_deref(),
+        )?;
+
         let channel_id = self.id;
         let url = format!(
             "{}/channels/{}",
@@ -126,15 +162,15 @@ impl Channel {
             channel_id
         );
 
-        let mut chorus_request = ChorusRequest::new(
+        let chorus_request = ChorusRequest::new(
             http::Method::GET,
             &url,
             None,
             None,
             Some(user),
             Default::default(),
-        );
-        chorus_request.request = chorus_request.request.query(&range);
+        )
+        .query(&range);
 
         chorus_request
             .deserialize_response::<Vec<Message>>(user)
@@ -202,11 +238,14 @@ impl Channel {
     /// Modifies the positions of a set of channel objects for the guild. Requires the `MANAGE_CHANNELS` permission.
     /// Only channels to be modified are required.
     ///
+    /// See also [Guild::modify_channel_positions](crate::types::Guild::modify_channel_positions).
+    ///
     /// # Reference:
     /// See <https://discord-userdoccers.vercel.app/resources/channel#modify-guild-channel-positions>
     pub async fn modify_positions(
         schema: Vec<ModifyChannelPositionsSchema>,
         guild_id: Snowflake,
+        audit_log_reason: Option<String>,
         user: &mut ChorusUser,
     ) -> ChorusResult<()> {
         let url = format!(
@@ -219,11 +258,114 @@ impl Channel {
             http::Method::PATCH,
             &url,
             Some(to_string(&schema).unwrap()),
-            None,
+            audit_log_reason.as_deref(),
             Some(user),
             LimitType::Guild(guild_id),
         );
 
         request.handle_request_as_result(user).await
     }
+
+    /// Follows a news channel, so that its crossposted messages are sent to another channel
+    /// through a webhook.
+    ///
+    /// Requires the `MANAGE_WEBHOOKS` permission in the target channel.
+    ///
+    /// Fires a [WebhooksUpdate](crate::types::WebhooksUpdate) gateway event for the target
+    /// channel.
+    ///
+    /// See also [Message::crosspost].
+    ///
+    /// # Reference:
+    /// See <https://docs.discord.sex/resources/channel#follow-channel>
+    pub async fn follow(
+        channel_id: Snowflake,
+        webhook_channel_id: Snowflake,
+        audit_log_reason: Option<&str>,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<FollowedChannel> {
+        let url = format!(
+            "{}/channels/{}/followers",
+            user.belongs_to.read().unwrap().urls.api,
+            channel_id
+        );
+
+        let schema = AddFollowingChannelSchema { webhook_channel_id };
+
+        let request = ChorusRequest::new(
+            http::Method::POST,
+            &url,
+            Some(to_string(&schema).unwrap()),
+            audit_log_reason,
+            Some(user),
+            LimitType::Channel(channel_id),
+        );
+
+        request.deserialize_response::<FollowedChannel>(user).await
+    }
+
+    /// Returns the news channels a channel follows, whose messages are crossposted into it (see
+    /// [Channel::follow]).
+    ///
+    /// Requires the `MANAGE_WEBHOOKS` permission.
+    ///
+    /// # Reference:
+    /// See <https://docs.discord.sex/resources/webhook#get-channel-webhooks>
+    pub async fn get_followed_channels(
+        channel_id: Snowflake,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<Vec<FollowedChannel>> {
+        let url = format!(
+            "{}/channels/{}/webhooks",
+            user.belongs_to.read().unwrap().urls.api,
+            channel_id
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            &url,
+            None,
+            None,
+            Some(user),
+            LimitType::Channel(channel_id),
+        );
+
+        let webhooks = request.deserialize_response::<Vec<Webhook>>(user).await?;
+        Ok(webhooks
+            .iter()
+            .filter_map(Webhook::followed_channel)
+            .collect())
+    }
+
+    /// Unfollows a news channel by deleting the channel follower webhook created by
+    /// [Channel::follow], see [FollowedChannel::webhook_id].
+    ///
+    /// Requires the `MANAGE_WEBHOOKS` permission in the channel the webhook posts to.
+    ///
+    /// Fires a [WebhooksUpdate](crate::types::WebhooksUpdate) gateway event for that channel.
+    ///
+    /// # Reference:
+    /// See <https://docs.discord.sex/resources/webhook#delete-webhook>
+    pub async fn delete_follower(
+        webhook_id: Snowflake,
+        audit_log_reason: Option<&str>,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<()> {
+        let url = format!(
+            "{}/webhooks/{}",
+            user.belongs_to.read().unwrap().urls.api,
+            webhook_id
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::DELETE,
+            &url,
+            None,
+            audit_log_reason,
+            Some(user),
+            LimitType::Webhook(webhook_id),
+        );
+
+        request.handle_request_as_result(user).await
+    }
 }
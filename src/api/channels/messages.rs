@@ -8,14 +8,150 @@ use reqwest::{multipart, Client};
 use serde_json::{from_value, to_string, Value};
 
 use crate::errors::{ChorusError, ChorusResult};
-use crate::instance::ChorusUser;
+use crate::instance::{Capability, ChorusUser};
 use crate::ratelimiter::ChorusRequest;
 use crate::types::{
-    Channel, CreateGreetMessage, LimitType, Message, MessageAck, MessageModifySchema,
-    MessageSearchEndpoint, MessageSearchQuery, MessageSendSchema, Snowflake,
+    split_message_content, AllowedMentions, BulkAckSchema, Channel, CreateGreetMessage,
+    DeleteReadStateSchema, LimitType, Message, MessageAck, MessageAckResponse, MessageModifySchema,
+    MessageSearchEndpoint, MessageSearchQuery, MessageSendSchema, PartialDiscordFileAttachment,
+    Snowflake, Webhook, WebhookExecuteSchema, MAX_ALLOWED_MENTIONS,
 };
 
+/// The maximum message length assumed if the instance does not expose its limits
+pub const DEFAULT_MAX_MESSAGE_CHARACTERS: usize = 2000;
+
 impl Message {
+    /// Checks the content and attachments of a message against the
+    /// [instance's limits](crate::instance::Instance::limits_configuration).
+    fn check_limits(
+        user: &ChorusUser,
+        content: Option<&str>,
+        attachments: Option<&[PartialDiscordFileAttachment]>,
+    ) -> ChorusResult<()> {
+        if let Some(content) = content {
+            user.check_limit(
+                "message content length",
+                content.chars().count() as u64,
+                |limits| limits.message.max_characters as u64,
+            )?;
+        }
+
+        for attachment in attachments.unwrap_or_default() {
+            user.check_limit(
+                &format!("size of attachment {:?}", attachment.filename),
+                attachment.content.len() as u64,
+                |limits| limits.message.max_attachment_size,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the server will accept a message's allowed mentions, see
+    /// [AllowedMentions::is_valid].
+    fn check_allowed_mentions(allowed_mentions: Option<&AllowedMentions>) -> ChorusResult<()> {
+        match allowed_mentions {
+            Some(allowed_mentions) if !allowed_mentions.is_valid() => {
+                Err(ChorusError::InvalidArguments {
+                    error: format!(
+                        "Allowed mentions may not list users or roles which are also parsed, or more than {} of each",
+                        MAX_ALLOWED_MENTIONS
+                    ),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Builds the multipart form for a message with attachments, giving each attachment the id
+    /// of its index.
+    fn attachments_form(
+        payload_json: String,
+        attachments: Vec<PartialDiscordFileAttachment>,
+    ) -> multipart::Form {
+        let mut form = reqwest::multipart::Form::new();
+        let payload_field = reqwest::multipart::Part::text(payload_json);
+
+        form = form.part("payload_json", payload_field);
+
+        for (index, attachment) in attachments.into_iter().enumerate() {
+            let attachment_content = attachment.content;
+            let attachment_filename = attachment.filename;
+            let part_name = format!("files[{}]", index);
+            let content_disposition = format!(
+                "form-data; name=\"{}\"'; filename=\"{}\"",
+                part_name, &attachment_filename
+            );
+            let mut header_map = HeaderMap::new();
+            header_map.insert(CONTENT_DISPOSITION, content_disposition.parse().unwrap());
+
+            let part = multipart::Part::bytes(attachment_content)
+                .file_name(attachment_filename)
+                .headers(header_map);
+
+            form = form.part(part_name, part);
+        }
+
+        form
+    }
+
+    /// Sends a message whose content may exceed the instance's maximum message length, by
+    /// splitting it into multiple messages which are sent one after another.
+    ///
+    /// The content is split with [split_message_content](crate::types::split_message_content),
+    /// using the instance's [max_characters](crate::types::types::subconfigs::limits::message::MessageLimits::max_characters)
+    /// limit, or [DEFAULT_MAX_MESSAGE_CHARACTERS] if the instance's limits are unknown.
+    ///
+    /// Everything but the content, tts and allowed mentions (embeds, attachments, the message
+    /// reference, ...) is only sent with the first message.
+    ///
+    /// Returns all sent messages, in order. If sending one of them fails, the ones sent before
+    /// are not deleted.
+    pub async fn send_chunked(
+        user: &mut ChorusUser,
+        channel_id: Snowflake,
+        message: MessageSendSchema,
+    ) -> ChorusResult<Vec<Message>> {
+        let max_characters = user
+            .belongs_to
+            .read()
+            .unwrap()
+            .limits_configuration
+            .as_ref()
+            .map(|limits| limits.message.max_characters as usize)
+            .unwrap_or(DEFAULT_MAX_MESSAGE_CHARACTERS);
+
+        let chunks = match &message.content {
+            Some(content) => split_message_content(content, max_characters),
+            None => return Ok(vec![Message::send(user, channel_id, message).await?]),
+        };
+
+        let tts = message.tts;
+        let allowed_mentions = message.allowed_mentions.clone();
+
+        let mut messages = Vec::with_capacity(chunks.len());
+        let mut first = Some(message);
+
+        for chunk in chunks {
+            let schema = match first.take() {
+                Some(first) => MessageSendSchema {
+                    content: Some(chunk),
+                    ..first
+                },
+                None => MessageSendSchema {
+                    content: Some(chunk),
+                    tts,
+                    allowed_mentions: allowed_mentions.clone(),
+                    ..Default::default()
+                },
+            };
+
+            messages.push(Message::send(user, channel_id, schema).await?);
+        }
+
+        Ok(messages)
+    }
+
     #[allow(clippy::useless_conversion)]
     /// Sends a message in the channel with the provided channel_id.
     /// Returns the sent message.
@@ -27,6 +163,13 @@ impl Message {
         channel_id: Snowflake,
         mut message: MessageSendSchema,
     ) -> ChorusResult<Message> {
+        Message::check_allowed_mentions(message.allowed_mentions.as_ref())?;
+        Message::check_limits(
+            user,
+            message.content.as_deref(),
+            message.attachments.as_deref(),
+        )?;
+
         let url_api = user.belongs_to.read().unwrap().urls.api.clone();
 
         if message.attachments.is_none() {
@@ -43,29 +186,8 @@ impl Message {
             for (index, attachment) in message.attachments.iter_mut().enumerate() {
                 attachment.get_mut(index).unwrap().id = Some((index as u64).into());
             }
-            let mut form = reqwest::multipart::Form::new();
             let payload_json = to_string(&message).unwrap();
-            let payload_field = reqwest::multipart::Part::text(payload_json);
-
-            form = form.part("payload_json", payload_field);
-
-            for (index, attachment) in message.attachments.unwrap().into_iter().enumerate() {
-                let attachment_content = attachment.content;
-                let attachment_filename = attachment.filename;
-                let part_name = format!("files[{}]", index);
-                let content_disposition = format!(
-                    "form-data; name=\"{}\"'; filename=\"{}\"",
-                    part_name, &attachment_filename
-                );
-                let mut header_map = HeaderMap::new();
-                header_map.insert(CONTENT_DISPOSITION, content_disposition.parse().unwrap());
-
-                let part = multipart::Part::bytes(attachment_content)
-                    .file_name(attachment_filename)
-                    .headers(header_map);
-
-                form = form.part(part_name, part);
-            }
+            let form = Message::attachments_form(payload_json, message.attachments.unwrap());
 
             let chorus_request = ChorusRequest {
                 request: Client::new()
@@ -93,6 +215,8 @@ impl Message {
         query: MessageSearchQuery,
         user: &mut ChorusUser,
     ) -> ChorusResult<Vec<Message>> {
+        user.check_endpoint_support(Capability::MessageSearch)?;
+
         let limit_type = match &endpoint {
             MessageSearchEndpoint::Channel(id) => LimitType::Channel(*id),
             MessageSearchEndpoint::GuildChannel(id) => LimitType::Guild(*id),
@@ -109,7 +233,8 @@ impl Message {
                 .header("Content-Type", "application/json")
                 .body(to_string(&query).unwrap()),
         };
-        let result = request.send_request(user).await?;
+        let result = request.send_request(user).await;
+        let result = user.record_endpoint_support(Capability::MessageSearch, result)?;
         let result_json = result.json::<Value>().await.unwrap();
         if !result_json.is_object() {
             return Err(search_error(result_json.to_string().as_str()));
@@ -270,6 +395,10 @@ impl Message {
     ///
     /// Returns an optional token, which can be used as the new `ack` token for following `ack`s.
     ///
+    /// Threads are acknowledged the same way, with the thread's id as the `channel_id`.
+    ///
+    /// See [ChorusUser::acknowledge_message] for a helper which keeps track of the `ack` token.
+    ///
     /// # Reference:
     /// See: <https://discord-userdoccers.vercel.app/resources/message#acknowledge-message>
     pub async fn acknowledge(
@@ -292,13 +421,43 @@ impl Message {
             Some(user),
             LimitType::Channel(channel_id),
         );
-        request.deserialize_response::<Option<String>>(user).await
+        request
+            .deserialize_response::<MessageAckResponse>(user)
+            .await
+            .map(|response| response.token)
+    }
+
+    /// Acknowledges (marks as read) the latest messages of multiple channels at once.
+    ///
+    /// Unlike [Message::acknowledge], this does not support manual acknowledgements.
+    ///
+    /// # Reference:
+    /// See <https://docs.discord.sex/resources/read-state#bulk-acknowledge-messages>
+    pub async fn acknowledge_bulk(
+        schema: BulkAckSchema,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<()> {
+        let request = ChorusRequest::new(
+            http::Method::POST,
+            format!(
+                "{}/read-states/ack-bulk",
+                user.belongs_to.read().unwrap().urls.api,
+            )
+            .as_str(),
+            Some(to_string(&schema).unwrap()),
+            None,
+            Some(user),
+            LimitType::Global,
+        );
+        request.handle_request_as_result(user).await
     }
 
     /// Crossposts a message in a News Channel to following channels.
     /// This endpoint requires the `SEND_MESSAGES` permission, if the current user sent the message,
     /// or additionally the `MANAGE_MESSAGES` permission, for all other messages, to be present for the current user.
     ///
+    /// Channels can follow a News Channel with [Channel::follow].
+    ///
     /// # Reference:
     /// See <https://discord-userdoccers.vercel.app/resources/message#crosspost-message>
     pub async fn crosspost(
@@ -365,6 +524,9 @@ impl Message {
         schema: MessageModifySchema,
         user: &mut ChorusUser,
     ) -> ChorusResult<Message> {
+        Message::check_allowed_mentions(schema.allowed_mentions.as_ref())?;
+        Message::check_limits(user, schema.content.as_deref(), None)?;
+
         let url = format!(
             "{}/channels/{}/messages/{}",
             user.belongs_to.read().unwrap().urls.api,
@@ -469,6 +631,32 @@ impl Message {
 
         chorus_request.handle_request_as_result(user).await
     }
+
+    /// Deletes the current user's read state of a channel, which makes the channel count as
+    /// never read.
+    ///
+    /// # Reference:
+    /// See <https://docs.discord.sex/resources/read-state#delete-read-state>
+    pub async fn delete_read_state(
+        channel_id: Snowflake,
+        schema: DeleteReadStateSchema,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<()> {
+        let request = ChorusRequest::new(
+            http::Method::DELETE,
+            format!(
+                "{}/channels/{}/messages/ack",
+                user.belongs_to.read().unwrap().urls.api,
+                channel_id,
+            )
+            .as_str(),
+            Some(to_string(&schema).unwrap()),
+            None,
+            Some(user),
+            LimitType::Channel(channel_id),
+        );
+        request.handle_request_as_result(user).await
+    }
 }
 
 fn search_error(result_text: &str) -> ChorusError {
@@ -496,6 +684,42 @@ impl ChorusUser {
     ) -> ChorusResult<Message> {
         Message::send(self, channel_id, message).await
     }
+
+    /// Sends a message, splitting its content into multiple messages if it is too long.
+    /// Returns all sent messages.
+    ///
+    /// # Notes
+    /// Shorthand call for [`Message::send_chunked`]
+    pub async fn send_message_chunked(
+        &mut self,
+        message: MessageSendSchema,
+        channel_id: Snowflake,
+    ) -> ChorusResult<Vec<Message>> {
+        Message::send_chunked(self, channel_id, message).await
+    }
+
+    /// Acknowledges a message (marks it as read), sending along the token returned by the
+    /// previous acknowledgement and storing the new one in [ChorusUser::ack_token].
+    ///
+    /// Reusing the token lets the server keep the read state consistent across clients.
+    ///
+    /// # Notes
+    /// Shorthand call for [`Message::acknowledge`]
+    pub async fn acknowledge_message(
+        &mut self,
+        channel_id: Snowflake,
+        message_id: Snowflake,
+    ) -> ChorusResult<()> {
+        let schema = MessageAck {
+            token: self.ack_token.clone(),
+            ..Default::default()
+        };
+        let token = Message::acknowledge(channel_id, message_id, schema, self).await?;
+        if token.is_some() {
+            self.ack_token = token;
+        }
+        Ok(())
+    }
 }
 
 impl Channel {
@@ -516,4 +740,108 @@ impl Channel {
     ) -> ChorusResult<Vec<Message>> {
         Message::search(MessageSearchEndpoint::Channel(channel_id), query, user).await
     }
+
+    /// Returns all pinned messages in the channel.
+    ///
+    /// # Notes
+    /// This function is a wrapper around [`Message::get_sticky`].
+    ///
+    /// # Reference:
+    /// See <https://discord-userdoccers.vercel.app/resources/message#get-pinned-messages>
+    pub async fn get_pinned_messages(
+        channel_id: Snowflake,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<Vec<Message>> {
+        Message::get_sticky(channel_id, user).await
+    }
+
+    /// Pins a message in the channel. Requires the `MANAGE_MESSAGES` permission.
+    ///
+    /// Fires a [ChannelPinsUpdate](crate::types::ChannelPinsUpdate) gateway event.
+    ///
+    /// # Notes
+    /// This function is a wrapper around [`Message::sticky`].
+    ///
+    /// # Reference:
+    /// See <https://discord-userdoccers.vercel.app/resources/message#pin-message>
+    pub async fn pin_message(
+        channel_id: Snowflake,
+        message_id: Snowflake,
+        audit_log_reason: Option<&str>,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<()> {
+        Message::sticky(channel_id, message_id, audit_log_reason, user).await
+    }
+
+    /// Unpins a message in the channel. Requires the `MANAGE_MESSAGES` permission.
+    ///
+    /// Fires a [ChannelPinsUpdate](crate::types::ChannelPinsUpdate) gateway event.
+    ///
+    /// # Notes
+    /// This function is a wrapper around [`Message::unsticky`].
+    ///
+    /// # Reference:
+    /// See <https://discord-userdoccers.vercel.app/resources/message#unpin-message>
+    pub async fn unpin_message(
+        channel_id: Snowflake,
+        message_id: Snowflake,
+        audit_log_reason: Option<&str>,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<()> {
+        Message::unsticky(channel_id, message_id, audit_log_reason, user).await
+    }
+}
+
+impl Webhook {
+    #[allow(clippy::useless_conversion)]
+    /// Executes a webhook, sending a message in its channel, and waits for the message to be
+    /// created.
+    ///
+    /// Webhooks are authenticated by their token, so this works for webhooks of other users as
+    /// well.
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/webhook#execute-webhook>
+    pub async fn execute(
+        webhook_id: Snowflake,
+        webhook_token: &str,
+        mut schema: WebhookExecuteSchema,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<Message> {
+        Message::check_allowed_mentions(schema.allowed_mentions.as_ref())?;
+        Message::check_limits(
+            user,
+            schema.content.as_deref(),
+            schema.attachments.as_deref(),
+        )?;
+
+        let url = format!(
+            "{}/webhooks/{}/{}",
+            user.belongs_to.read().unwrap().urls.api,
+            webhook_id,
+            webhook_token
+        );
+
+        let request = if schema.attachments.is_none() {
+            Client::new()
+                .post(url)
+                .body(to_string(&schema).unwrap())
+                .header("Content-Type", "application/json")
+        } else {
+            for (index, attachment) in schema.attachments.iter_mut().flatten().enumerate() {
+                attachment.id = Some((index as u64).into());
+            }
+            let payload_json = to_string(&schema).unwrap();
+            let form = Message::attachments_form(payload_json, schema.attachments.unwrap());
+            Client::new().post(url).multipart(form)
+        };
+
+        let chorus_request = ChorusRequest {
+            request,
+            limit_type: LimitType::Webhook(webhook_id),
+        }
+        .query_param("wait", true);
+
+        chorus_request.deserialize_response::<Message>(user).await
+    }
 }
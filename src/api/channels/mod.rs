@@ -3,12 +3,32 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 #![allow(unused_imports)]
+#[cfg(feature = "api-channels")]
+pub use call::*;
+#[cfg(feature = "api-channels")]
 pub use channels::*;
+#[cfg(feature = "api-messages")]
 pub use messages::*;
+#[cfg(feature = "api-channels")]
 pub use permissions::*;
+#[cfg(feature = "api-messages")]
+pub use polls::*;
+#[cfg(feature = "api-messages")]
 pub use reactions::*;
+#[cfg(feature = "api-channels")]
+pub use typing::*;
 
+#[cfg(feature = "api-channels")]
+pub mod call;
+#[cfg(feature = "api-channels")]
 pub mod channels;
+#[cfg(feature = "api-messages")]
 pub mod messages;
+#[cfg(feature = "api-channels")]
 pub mod permissions;
+#[cfg(feature = "api-messages")]
+pub mod polls;
+#[cfg(feature = "api-messages")]
 pub mod reactions;
+#[cfg(feature = "api-channels")]
+pub mod typing;
@@ -9,7 +9,7 @@ use crate::{
     errors::{ChorusError, ChorusResult},
     instance::ChorusUser,
     ratelimiter::ChorusRequest,
-    types::{self, LimitType, PermissionOverwrite, Snowflake},
+    types::{self, ChannelModifySchema, LimitType, PermissionOverwrite, Snowflake},
 };
 
 impl types::Channel {
@@ -43,21 +43,36 @@ impl types::Channel {
                 });
             }
         };
-        let mut request = Client::new()
+        let request = Client::new()
             .put(url)
             .header("Authorization", user.token())
             .header("Content-Type", "application/json")
             .body(body);
-        if let Some(reason) = audit_log_reason {
-            request = request.header("X-Audit-Log-Reason", reason);
-        }
         let chorus_request = ChorusRequest {
             request,
             limit_type: LimitType::Channel(channel_id),
-        };
+        }
+        .with_maybe_audit_log_reason(audit_log_reason.as_deref());
         chorus_request.handle_request_as_result(user).await
     }
 
+    /// Deletes a permission overwrite for a user or role in a channel.
+    #[deprecated(note = "Renamed to delete_permission_overwrite")]
+    pub async fn delete_permission(
+        user: &mut ChorusUser,
+        channel_id: Snowflake,
+        overwrite_id: Snowflake,
+        audit_log_reason: Option<String>,
+    ) -> ChorusResult<()> {
+        types::Channel::delete_permission_overwrite(
+            user,
+            channel_id,
+            overwrite_id,
+            audit_log_reason,
+        )
+        .await
+    }
+
     /// Deletes a permission overwrite for a user or role in a channel.
     ///
     /// Only usable for guild channels.
@@ -66,10 +81,11 @@ impl types::Channel {
     ///
     /// # Reference
     /// See <https://discord-userdoccers.vercel.app/resources/channel#delete-channel-permission>
-    pub async fn delete_permission(
+    pub async fn delete_permission_overwrite(
         user: &mut ChorusUser,
         channel_id: Snowflake,
         overwrite_id: Snowflake,
+        audit_log_reason: Option<String>,
     ) -> ChorusResult<()> {
         let url = format!(
             "{}/channels/{}/permissions/{}",
@@ -82,11 +98,53 @@ impl types::Channel {
             http::Method::DELETE,
             &url,
             None,
-            None,
+            audit_log_reason.as_deref(),
             Some(user),
             LimitType::Channel(channel_id),
         );
 
         request.handle_request_as_result(user).await
     }
+
+    /// Replaces the permission overwrites of a channel with the ones of its parent category,
+    /// like the "Sync Now" button in the Discord client.
+    ///
+    /// Only usable for guild channels which are in a category.
+    ///
+    /// Requires the [`MANAGE_ROLES`](crate::types::PermissionFlags::MANAGE_ROLES) permission.
+    ///
+    /// # Errors
+    /// Returns [ChorusError::InvalidArguments] if the channel is not in a category.
+    pub async fn sync_permissions_with_parent(
+        user: &mut ChorusUser,
+        channel_id: Snowflake,
+        audit_log_reason: Option<String>,
+    ) -> ChorusResult<types::Channel> {
+        let channel = types::Channel::get(user, channel_id).await?;
+        let Some(parent_id) = channel.parent_id else {
+            return Err(ChorusError::InvalidArguments {
+                error: format!("Channel {} is not in a category", channel_id),
+            });
+        };
+        let parent = types::Channel::get(user, parent_id).await?;
+
+        #[cfg(not(feature = "sqlx"))]
+        let permission_overwrites = parent
+            .permission_overwrites
+            .unwrap_or_default()
+            .iter()
+            .map(|overwrite| overwrite.read().unwrap().clone())
+            .collect();
+        #[cfg(feature = "sqlx")]
+        let permission_overwrites = parent
+            .permission_overwrites
+            .map(|overwrites| overwrites.0)
+            .unwrap_or_default();
+
+        let schema = ChannelModifySchema {
+            permission_overwrites: Some(permission_overwrites),
+            ..Default::default()
+        };
+        channel.modify(schema, audit_log_reason, user).await
+    }
 }
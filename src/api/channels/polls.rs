@@ -0,0 +1,70 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::errors::ChorusResult;
+use crate::instance::ChorusUser;
+use crate::ratelimiter::ChorusRequest;
+use crate::types::{LimitType, Message, PollAnswerVoters, PollAnswerVotersQuery, Snowflake};
+
+impl Message {
+    /// Returns the users who voted for an answer of a poll.
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/message#get-answer-voters>
+    pub async fn get_poll_answer_voters(
+        user: &mut ChorusUser,
+        channel_id: Snowflake,
+        message_id: Snowflake,
+        answer_id: u8,
+        query: Option<PollAnswerVotersQuery>,
+    ) -> ChorusResult<PollAnswerVoters> {
+        let url = format!(
+            "{}/channels/{}/polls/{}/answers/{}",
+            user.belongs_to.read().unwrap().urls.api,
+            channel_id,
+            message_id,
+            answer_id
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            &url,
+            None,
+            None,
+            Some(user),
+            LimitType::Channel(channel_id),
+        )
+        .query(&query);
+        request.deserialize_response::<PollAnswerVoters>(user).await
+    }
+
+    /// Immediately ends a poll, returning the message with the finalized results.
+    ///
+    /// Only polls created by the current user can be ended.
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/message#expire-poll>
+    pub async fn end_poll(
+        user: &mut ChorusUser,
+        channel_id: Snowflake,
+        message_id: Snowflake,
+    ) -> ChorusResult<Message> {
+        let url = format!(
+            "{}/channels/{}/polls/{}/expire",
+            user.belongs_to.read().unwrap().urls.api,
+            channel_id,
+            message_id
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::POST,
+            &url,
+            None,
+            None,
+            Some(user),
+            LimitType::Channel(channel_id),
+        );
+        request.deserialize_response::<Message>(user).await
+    }
+}
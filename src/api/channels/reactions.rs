@@ -2,11 +2,16 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::time::sleep;
+#[cfg(target_arch = "wasm32")]
+use wasmtimer::tokio::sleep;
+
 use crate::{
     errors::ChorusResult,
     instance::ChorusUser,
     ratelimiter::ChorusRequest,
-    types::{self, LimitType, PublicUser, Snowflake},
+    types::{self, LimitType, Message, PublicUser, Snowflake},
 };
 
 /// Useful metadata for working with [`types::Reaction`], bundled together nicely.
@@ -198,3 +203,52 @@ impl ReactionMeta {
         request.handle_request_as_result(user).await
     }
 }
+
+impl Message {
+    /// Adds reactions to a message one after another, so they appear in the given order.
+    ///
+    /// Before each reaction, this waits until the channel's rate limit bucket has requests
+    /// remaining, instead of failing with [ChorusError::RateLimited](crate::errors::ChorusError::RateLimited).
+    ///
+    /// If adding a reaction fails, the reactions added by this call are removed again and the
+    /// error is returned.
+    ///
+    /// See [ReactionMeta::create] for the required permissions and the emoji format.
+    pub async fn add_reactions(
+        channel_id: Snowflake,
+        message_id: Snowflake,
+        emojis: &[&str],
+        user: &mut ChorusUser,
+    ) -> ChorusResult<()> {
+        let meta = ReactionMeta {
+            message_id,
+            channel_id,
+        };
+        let limit_type = LimitType::Channel(channel_id);
+
+        for (index, emoji) in emojis.iter().enumerate() {
+            sleep(ChorusRequest::time_until_available(user, &limit_type)).await;
+
+            let Err(error) = meta.create(emoji, user).await else {
+                continue;
+            };
+
+            for added in emojis[..index].iter().rev() {
+                sleep(ChorusRequest::time_until_available(user, &limit_type)).await;
+
+                if let Err(e) = meta.remove(added, user).await {
+                    log::warn!(
+                        "Failed to remove reaction {} from message {} while rolling back: {}",
+                        added,
+                        message_id,
+                        e
+                    );
+                }
+            }
+
+            return Err(error);
+        }
+
+        Ok(())
+    }
+}
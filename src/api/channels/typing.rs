@@ -0,0 +1,127 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::Mutex;
+
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::time::sleep;
+#[cfg(target_arch = "wasm32")]
+use wasmtimer::tokio::sleep;
+
+use crate::{
+    errors::ChorusResult,
+    instance::ChorusUser,
+    ratelimiter::ChorusRequest,
+    types::{Channel, LimitType, Snowflake},
+};
+
+/// How often a [TypingIndicator] re-triggers the typing indicator.
+///
+/// Clients stop showing the indicator about 10 seconds after it was last triggered.
+pub const TYPING_INDICATOR_INTERVAL: Duration = Duration::from_secs(8);
+
+impl Channel {
+    /// Triggers the typing indicator for the current user in a channel.
+    ///
+    /// The indicator is shown for about 10 seconds, or until the user sends a message.
+    /// To keep it alive for longer, see [Channel::keep_typing].
+    ///
+    /// Fires a [TypingStartEvent](crate::types::TypingStartEvent) gateway event.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/channel#trigger-typing-indicator>
+    pub async fn start_typing(channel_id: Snowflake, user: &mut ChorusUser) -> ChorusResult<()> {
+        let url = format!(
+            "{}/channels/{}/typing",
+            user.belongs_to.read().unwrap().urls.api,
+            channel_id
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::POST,
+            &url,
+            None,
+            None,
+            Some(user),
+            LimitType::Channel(channel_id),
+        );
+
+        request.handle_request_as_result(user).await
+    }
+
+    /// Triggers the typing indicator for the current user in a channel and keeps it alive
+    /// until the returned [TypingIndicator] is stopped or dropped.
+    ///
+    /// Useful while performing a long operation before sending a message.
+    ///
+    /// The user is shared with the task re-triggering the indicator, so that the rate limits
+    /// it updates (and tokens it refreshes) are not lost.
+    ///
+    /// Only the first trigger is awaited; if a later one fails, the indicator silently expires.
+    pub async fn keep_typing(
+        channel_id: Snowflake,
+        user: Arc<Mutex<ChorusUser>>,
+    ) -> ChorusResult<TypingIndicator> {
+        Channel::start_typing(channel_id, &mut *user.lock().await).await?;
+
+        let (stop_send, stop_receive) = tokio::sync::oneshot::channel();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        tokio::task::spawn(TypingIndicator::typing_task(channel_id, user, stop_receive));
+        #[cfg(target_arch = "wasm32")]
+        wasm_bindgen_futures::spawn_local(TypingIndicator::typing_task(
+            channel_id,
+            user,
+            stop_receive,
+        ));
+
+        Ok(TypingIndicator {
+            channel_id,
+            _stop: stop_send,
+        })
+    }
+}
+
+/// Keeps the typing indicator in a channel alive, see [Channel::keep_typing].
+///
+/// The indicator is no longer re-triggered once this is stopped or dropped. Note that clients
+/// may still show it for a few seconds afterwards, unless a message is sent.
+#[derive(Debug)]
+pub struct TypingIndicator {
+    pub channel_id: Snowflake,
+    /// Dropping the sender stops the typing task
+    _stop: tokio::sync::oneshot::Sender<()>,
+}
+
+impl TypingIndicator {
+    /// Stops re-triggering the typing indicator.
+    ///
+    /// Equivalent to dropping the [TypingIndicator].
+    pub fn stop(self) {}
+
+    async fn typing_task(
+        channel_id: Snowflake,
+        user: Arc<Mutex<ChorusUser>>,
+        mut stop_receive: tokio::sync::oneshot::Receiver<()>,
+    ) {
+        loop {
+            tokio::select! {
+                _ = &mut stop_receive => break,
+                () = sleep(TYPING_INDICATOR_INTERVAL) => {}
+            }
+
+            if let Err(e) = Channel::start_typing(channel_id, &mut *user.lock().await).await {
+                log::warn!(
+                    "Failed to re-trigger typing indicator in channel {}: {}",
+                    channel_id,
+                    e
+                );
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The instance's proxy of the Tenor GIF search, used by GIF pickers.
+
+use crate::{
+    errors::ChorusResult,
+    instance::{Capability, ChorusUser},
+    ratelimiter::ChorusRequest,
+    types::{Gif, GifSearchQuery, GifSuggestQuery, GifTrending, GifTrendingQuery, LimitType},
+};
+
+impl Gif {
+    /// Searches for GIFs.
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/integration#search-gifs>
+    pub async fn search(query: GifSearchQuery, user: &mut ChorusUser) -> ChorusResult<Vec<Gif>> {
+        user.check_endpoint_support(Capability::Gifs)?;
+
+        let url = format!("{}/gifs/search", user.belongs_to.read().unwrap().urls.api);
+
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            &url,
+            None,
+            None,
+            Some(user),
+            LimitType::Global,
+        )
+        .query(&query);
+
+        let result = request.deserialize_response::<Vec<Gif>>(user).await;
+        user.record_endpoint_support(Capability::Gifs, result)
+    }
+
+    /// Fetches the trending GIF categories, along with some trending GIFs.
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/integration#get-trending-gifs>
+    pub async fn trending(
+        query: GifTrendingQuery,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<GifTrending> {
+        user.check_endpoint_support(Capability::Gifs)?;
+
+        let url = format!("{}/gifs/trending", user.belongs_to.read().unwrap().urls.api);
+
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            &url,
+            None,
+            None,
+            Some(user),
+            LimitType::Global,
+        )
+        .query(&query);
+
+        let result = request.deserialize_response::<GifTrending>(user).await;
+        user.record_endpoint_support(Capability::Gifs, result)
+    }
+
+    /// Fetches search terms to suggest for a (partial) search term, e.g. to autocomplete it.
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/integration#get-gif-search-suggestions>
+    pub async fn suggest(
+        query: GifSuggestQuery,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<Vec<String>> {
+        user.check_endpoint_support(Capability::Gifs)?;
+
+        let url = format!("{}/gifs/suggest", user.belongs_to.read().unwrap().urls.api);
+
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            &url,
+            None,
+            None,
+            Some(user),
+            LimitType::Global,
+        )
+        .query(&query);
+
+        let result = request.deserialize_response::<Vec<String>>(user).await;
+        user.record_endpoint_support(Capability::Gifs, result)
+    }
+}
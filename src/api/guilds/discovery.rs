@@ -0,0 +1,126 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::{
+    errors::ChorusResult,
+    instance::ChorusUser,
+    ratelimiter::ChorusRequest,
+    types::{
+        DiscoverableGuilds, DiscoverableGuildsQuery, DiscoveryCategoriesQuery, DiscoveryCategory,
+        Guild, GuildDiscoveryRequirements, LimitType, Snowflake,
+    },
+};
+
+impl Guild {
+    /// Fetches a page of the public guilds listed in the instance's discovery.
+    ///
+    /// # Reference
+    /// See <https://docs.spacebar.chat/routes/#get-/discoverable-guilds/>
+    pub async fn get_discoverable(
+        query: DiscoverableGuildsQuery,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<DiscoverableGuilds> {
+        let url = format!(
+            "{}/discoverable-guilds",
+            user.belongs_to.read().unwrap().urls.api
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            &url,
+            None,
+            None,
+            Some(user),
+            LimitType::Global,
+        )
+        .query(&query);
+
+        request
+            .deserialize_response::<DiscoverableGuilds>(user)
+            .await
+    }
+
+    /// Fetches a guild's progress on meeting the requirements of being listed in discovery.
+    ///
+    /// Requires the [MANAGE_GUILD](crate::types::PermissionFlags::MANAGE_GUILD) permission.
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/discovery#get-guild-discovery-requirements>
+    pub async fn get_discovery_requirements(
+        guild_id: Snowflake,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<GuildDiscoveryRequirements> {
+        let url = format!(
+            "{}/guilds/{}/discovery-requirements",
+            user.belongs_to.read().unwrap().urls.api,
+            guild_id
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            &url,
+            None,
+            None,
+            Some(user),
+            LimitType::Guild(guild_id),
+        );
+
+        request
+            .deserialize_response::<GuildDiscoveryRequirements>(user)
+            .await
+    }
+
+    /// Joins a guild listed in discovery, without needing an invite.
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/guild#join-guild>
+    pub async fn join_discoverable(guild_id: Snowflake, user: &mut ChorusUser) -> ChorusResult<()> {
+        let url = format!(
+            "{}/guilds/{}/members/@me",
+            user.belongs_to.read().unwrap().urls.api,
+            guild_id
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::PUT,
+            &url,
+            None,
+            None,
+            Some(user),
+            LimitType::Guild(guild_id),
+        );
+
+        request.handle_request_as_result(user).await
+    }
+}
+
+impl DiscoveryCategory {
+    /// Fetches the categories guilds can be listed under in discovery.
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/discovery#get-discovery-categories>
+    pub async fn get_all(
+        query: DiscoveryCategoriesQuery,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<Vec<DiscoveryCategory>> {
+        let url = format!(
+            "{}/discovery/categories",
+            user.belongs_to.read().unwrap().urls.api
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            &url,
+            None,
+            None,
+            Some(user),
+            LimitType::Global,
+        )
+        .query(&query);
+
+        request
+            .deserialize_response::<Vec<DiscoveryCategory>>(user)
+            .await
+    }
+}
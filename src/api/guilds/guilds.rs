@@ -6,14 +6,18 @@ use reqwest::Client;
 use serde_json::from_str;
 use serde_json::to_string;
 
+use crate::api::batch::{fetch_many, BatchResult, DEFAULT_BATCH_CONCURRENCY};
 use crate::errors::ChorusError;
 use crate::errors::ChorusResult;
 use crate::instance::ChorusUser;
 use crate::ratelimiter::ChorusRequest;
 use crate::types::{
-    Channel, ChannelCreateSchema, Guild, GuildBanCreateSchema, GuildBansQuery, GuildCreateSchema,
-    GuildMember, GuildMemberSearchSchema, GuildModifySchema, GuildPreview, LimitType,
-    ModifyGuildMemberProfileSchema, ModifyGuildMemberSchema, UserProfileMetadata,
+    Channel, ChannelCreateSchema, Guild, GuildBanBulkCreateResult, GuildBanBulkCreateSchema,
+    GuildBanCreateSchema, GuildBansQuery, GuildCreateSchema, GuildCreateVanitySchema, GuildMember,
+    GuildMemberSearchSchema, GuildModifySchema, GuildPreview, GuildPruneQuerySchema,
+    GuildPruneResult, GuildVanityInviteResponse, GuildWidget, GuildWidgetImageStyle,
+    GuildWidgetSettings, LimitType, ModifyChannelPositionsSchema, ModifyGuildMemberProfileSchema,
+    ModifyGuildMemberSchema, UserProfileMetadata,
 };
 use crate::types::{GuildBan, Snowflake};
 
@@ -37,6 +41,37 @@ impl Guild {
         Ok(response)
     }
 
+    /// Fetches many guilds by their ids, sending up to [DEFAULT_BATCH_CONCURRENCY] requests at
+    /// the same time.
+    ///
+    /// Guilds which could not be fetched are returned in [BatchResult::errors].
+    ///
+    /// See [Guild::get_many_with_concurrency] to configure the concurrency.
+    pub async fn get_many(user: &mut ChorusUser, guild_ids: &[Snowflake]) -> BatchResult<Guild> {
+        Guild::get_many_with_concurrency(user, guild_ids, DEFAULT_BATCH_CONCURRENCY).await
+    }
+
+    /// Fetches many guilds by their ids, sending up to `concurrency` requests at the same time.
+    ///
+    /// Requests wait for the rate limit buckets they would exhaust to be available.
+    pub async fn get_many_with_concurrency(
+        user: &mut ChorusUser,
+        guild_ids: &[Snowflake],
+        concurrency: usize,
+    ) -> BatchResult<Guild> {
+        fetch_many(
+            user,
+            guild_ids,
+            concurrency,
+            LimitType::Guild,
+            |mut user, guild_id| async move {
+                let result = Guild::get(guild_id, &mut user).await;
+                (user, result)
+            },
+        )
+        .await
+    }
+
     /// Creates a new guild.
     ///
     /// # Reference
@@ -45,6 +80,15 @@ impl Guild {
         user: &mut ChorusUser,
         guild_create_schema: GuildCreateSchema,
     ) -> ChorusResult<Guild> {
+        // The guilds the user is in are only known if they are connected to the gateway
+        if let Some(gateway) = &user.gateway {
+            user.check_limit(
+                "number of guilds",
+                gateway.guild_ids().len() as u64 + 1,
+                |limits| limits.user.max_guilds,
+            )?;
+        }
+
         let url = format!("{}/guilds", user.belongs_to.read().unwrap().urls.api);
         let chorus_request = ChorusRequest {
             request: Client::new()
@@ -71,6 +115,7 @@ impl Guild {
     pub async fn modify(
         guild_id: Snowflake,
         schema: GuildModifySchema,
+        audit_log_reason: Option<String>,
         user: &mut ChorusUser,
     ) -> ChorusResult<Guild> {
         let chorus_request = ChorusRequest {
@@ -85,6 +130,7 @@ impl Guild {
                 .body(to_string(&schema).unwrap()),
             limit_type: LimitType::Guild(guild_id),
         }
+        .with_maybe_audit_log_reason(audit_log_reason.as_deref())
         .with_maybe_mfa(&user.mfa_token);
 
         let response = chorus_request.deserialize_response::<Guild>(user).await?;
@@ -185,6 +231,25 @@ impl Guild {
         };
     }
 
+    /// Moves multiple channels of the guild at once, e.g. after a drag-and-drop in a channel
+    /// list.
+    ///
+    /// Each entry can change a channel's position, move it into or out of a category, and sync
+    /// its permissions with the new category. Only channels which are moved need to be included.
+    ///
+    /// Requires the [MANAGE_CHANNELS](crate::types::PermissionFlags::MANAGE_CHANNELS) permission.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/channel#modify-guild-channel-positions>
+    pub async fn modify_channel_positions(
+        guild_id: Snowflake,
+        positions: Vec<ModifyChannelPositionsSchema>,
+        audit_log_reason: Option<String>,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<()> {
+        Channel::modify_positions(positions, guild_id, audit_log_reason, user).await
+    }
+
     /// Returns a guild preview object for the given guild ID.
     ///
     /// If the user is not in the guild, the guild must be discoverable.
@@ -245,7 +310,7 @@ impl Guild {
         query: GuildMemberSearchSchema,
         user: &mut ChorusUser,
     ) -> ChorusResult<Vec<GuildMember>> {
-        let mut request = ChorusRequest::new(
+        let request = ChorusRequest::new(
             http::Method::GET,
             format!(
                 "{}/guilds/{}/members/search",
@@ -257,10 +322,8 @@ impl Guild {
             None,
             Some(user),
             LimitType::Guild(guild_id),
-        );
-        request.request = request
-            .request
-            .query(&[("query", to_string(&query).unwrap())]);
+        )
+        .query(&query);
         request.deserialize_response::<Vec<GuildMember>>(user).await
     }
 
@@ -304,6 +367,25 @@ impl Guild {
         schema: ModifyGuildMemberSchema,
         audit_log_reason: Option<String>,
         user: &mut ChorusUser,
+    ) -> ChorusResult<GuildMember> {
+        Guild::modify_member_with_body(
+            guild_id,
+            member_id,
+            to_string(&schema).unwrap(),
+            audit_log_reason,
+            user,
+        )
+        .await
+    }
+
+    /// Modifies a guild member with a raw body, for modifications which must not touch the
+    /// other fields of [ModifyGuildMemberSchema], see [Guild::modify_member].
+    pub(crate) async fn modify_member_with_body(
+        guild_id: Snowflake,
+        member_id: Snowflake,
+        body: String,
+        audit_log_reason: Option<String>,
+        user: &mut ChorusUser,
     ) -> ChorusResult<GuildMember> {
         let request = ChorusRequest::new(
             http::Method::PATCH,
@@ -314,7 +396,7 @@ impl Guild {
                 member_id,
             )
             .as_str(),
-            Some(to_string(&schema).unwrap()),
+            Some(body),
             audit_log_reason.as_deref(),
             Some(user),
             LimitType::Guild(guild_id),
@@ -392,20 +474,34 @@ impl Guild {
             guild_id,
         );
 
-        let mut request = ChorusRequest::new(
+        let request = ChorusRequest::new(
             http::Method::GET,
             &url,
             None,
             None,
             Some(user),
             LimitType::Guild(guild_id),
-        );
-        if let Some(query) = query {
-            request.request = request.request.query(&to_string(&query).unwrap());
-        }
+        )
+        .query(&query);
         request.deserialize_response::<Vec<GuildBan>>(user).await
     }
 
+    /// Returns a [GuildBansIterator], which can be used to enumerate all bans of a guild
+    /// page by page.
+    ///
+    /// `page_size` is the amount of bans requested per page (1-1000). If `None`, the maximum
+    /// of 1000 is used.
+    pub fn bans_iter(guild_id: Snowflake, page_size: Option<u16>) -> GuildBansIterator {
+        GuildBansIterator {
+            guild_id,
+            page_size: page_size
+                .unwrap_or(GuildBansIterator::MAX_PAGE_SIZE)
+                .clamp(1, GuildBansIterator::MAX_PAGE_SIZE),
+            after: None,
+            exhausted: false,
+        }
+    }
+
     /// Returns a ban object for the given user.
     ///
     /// Requires the [BAN_MEMBERS](crate::types::PermissionFlags::BAN_MEMBERS) permission.
@@ -493,6 +589,359 @@ impl Guild {
         );
         request.handle_request_as_result(user).await
     }
+
+    /// Bans up to 200 users from the guild at once.
+    ///
+    /// Requires the [BAN_MEMBERS](crate::types::PermissionFlags::BAN_MEMBERS) and
+    /// [MANAGE_GUILD](crate::types::PermissionFlags::MANAGE_GUILD) permissions.
+    ///
+    /// # Reference:
+    /// See <https://docs.discord.sex/resources/guild#bulk-guild-ban>
+    pub async fn bulk_ban(
+        guild_id: Snowflake,
+        schema: GuildBanBulkCreateSchema,
+        audit_log_reason: Option<String>,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<GuildBanBulkCreateResult> {
+        let request = ChorusRequest::new(
+            http::Method::POST,
+            format!(
+                "{}/guilds/{}/bulk-ban",
+                user.belongs_to.read().unwrap().urls.api,
+                guild_id,
+            )
+            .as_str(),
+            Some(to_string(&schema).unwrap()),
+            audit_log_reason.as_deref(),
+            Some(user),
+            LimitType::Guild(guild_id),
+        );
+        request
+            .deserialize_response::<GuildBanBulkCreateResult>(user)
+            .await
+    }
+
+    /// Returns the number of members that would be removed in a prune operation with the
+    /// given query.
+    ///
+    /// [`compute_prune_count`](GuildPruneQuerySchema::compute_prune_count) is ignored.
+    ///
+    /// Requires the [KICK_MEMBERS](crate::types::PermissionFlags::KICK_MEMBERS) and
+    /// [MANAGE_GUILD](crate::types::PermissionFlags::MANAGE_GUILD) permissions.
+    ///
+    /// # Reference:
+    /// See <https://docs.discord.sex/resources/guild#get-guild-prune>
+    pub async fn get_prune_count(
+        guild_id: Snowflake,
+        query: GuildPruneQuerySchema,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<GuildPruneResult> {
+        // include_roles is sent as a comma separated list, which serde_urlencoded can't do
+        let include_roles = (!query.include_roles.is_empty()).then(|| {
+            query
+                .include_roles
+                .iter()
+                .map(|role| role.to_string())
+                .collect::<Vec<String>>()
+                .join(",")
+        });
+
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            format!(
+                "{}/guilds/{}/prune",
+                user.belongs_to.read().unwrap().urls.api,
+                guild_id,
+            )
+            .as_str(),
+            None,
+            None,
+            Some(user),
+            LimitType::Guild(guild_id),
+        )
+        .query_param("days", query.days)
+        .optional_query_param("include_roles", include_roles);
+
+        request.deserialize_response::<GuildPruneResult>(user).await
+    }
+
+    /// Removes inactive members from the guild.
+    ///
+    /// If [`compute_prune_count`](GuildPruneQuerySchema::compute_prune_count) is `false`,
+    /// the returned [`pruned`](GuildPruneResult::pruned) count is `None`; this is recommended
+    /// for large guilds.
+    ///
+    /// Requires the [KICK_MEMBERS](crate::types::PermissionFlags::KICK_MEMBERS) and
+    /// [MANAGE_GUILD](crate::types::PermissionFlags::MANAGE_GUILD) permissions.
+    ///
+    /// # Reference:
+    /// See <https://docs.discord.sex/resources/guild#prune-guild>
+    pub async fn prune(
+        guild_id: Snowflake,
+        schema: GuildPruneQuerySchema,
+        audit_log_reason: Option<String>,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<GuildPruneResult> {
+        let request = ChorusRequest::new(
+            http::Method::POST,
+            format!(
+                "{}/guilds/{}/prune",
+                user.belongs_to.read().unwrap().urls.api,
+                guild_id,
+            )
+            .as_str(),
+            Some(to_string(&schema).unwrap()),
+            audit_log_reason.as_deref(),
+            Some(user),
+            LimitType::Guild(guild_id),
+        );
+        request.deserialize_response::<GuildPruneResult>(user).await
+    }
+
+    /// Fetches the vanity invite of a guild.
+    ///
+    /// If the guild has no vanity invite, the returned [`code`](GuildVanityInviteResponse::code)
+    /// is empty.
+    ///
+    /// Requires the [MANAGE_GUILD](crate::types::PermissionFlags::MANAGE_GUILD) permission.
+    ///
+    /// # Reference:
+    /// See <https://docs.discord.sex/resources/guild#get-guild-vanity-invite>
+    pub async fn get_vanity_url(
+        guild_id: Snowflake,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<GuildVanityInviteResponse> {
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            format!(
+                "{}/guilds/{}/vanity-url",
+                user.belongs_to.read().unwrap().urls.api,
+                guild_id,
+            )
+            .as_str(),
+            None,
+            None,
+            Some(user),
+            LimitType::Guild(guild_id),
+        );
+        request
+            .deserialize_response::<GuildVanityInviteResponse>(user)
+            .await
+    }
+
+    /// Sets the vanity invite code of a guild.
+    ///
+    /// The guild must have the `VANITY_URL` feature.
+    ///
+    /// Requires the [MANAGE_GUILD](crate::types::PermissionFlags::MANAGE_GUILD) permission.
+    ///
+    /// # Reference:
+    /// See <https://docs.discord.sex/resources/guild#modify-guild-vanity-invite>
+    pub async fn set_vanity_url(
+        guild_id: Snowflake,
+        schema: GuildCreateVanitySchema,
+        audit_log_reason: Option<String>,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<GuildVanityInviteResponse> {
+        let request = ChorusRequest::new(
+            http::Method::PATCH,
+            format!(
+                "{}/guilds/{}/vanity-url",
+                user.belongs_to.read().unwrap().urls.api,
+                guild_id,
+            )
+            .as_str(),
+            Some(to_string(&schema).unwrap()),
+            audit_log_reason.as_deref(),
+            Some(user),
+            LimitType::Guild(guild_id),
+        );
+        request
+            .deserialize_response::<GuildVanityInviteResponse>(user)
+            .await
+    }
+
+    /// Fetches the public widget of a guild.
+    ///
+    /// The guild's widget must be enabled, see [Guild::modify_widget_settings].
+    ///
+    /// # Reference:
+    /// See <https://docs.discord.sex/resources/guild#get-guild-widget>
+    pub async fn get_widget(
+        guild_id: Snowflake,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<GuildWidget> {
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            format!(
+                "{}/guilds/{}/widget.json",
+                user.belongs_to.read().unwrap().urls.api,
+                guild_id,
+            )
+            .as_str(),
+            None,
+            None,
+            Some(user),
+            LimitType::Guild(guild_id),
+        );
+        request.deserialize_response::<GuildWidget>(user).await
+    }
+
+    /// Fetches the widget settings of a guild.
+    ///
+    /// Requires the [MANAGE_GUILD](crate::types::PermissionFlags::MANAGE_GUILD) permission.
+    ///
+    /// # Reference:
+    /// See <https://docs.discord.sex/resources/guild#get-guild-widget-settings>
+    pub async fn get_widget_settings(
+        guild_id: Snowflake,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<GuildWidgetSettings> {
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            format!(
+                "{}/guilds/{}/widget",
+                user.belongs_to.read().unwrap().urls.api,
+                guild_id,
+            )
+            .as_str(),
+            None,
+            None,
+            Some(user),
+            LimitType::Guild(guild_id),
+        );
+        request
+            .deserialize_response::<GuildWidgetSettings>(user)
+            .await
+    }
+
+    /// Modifies the widget settings of a guild.
+    ///
+    /// Requires the [MANAGE_GUILD](crate::types::PermissionFlags::MANAGE_GUILD) permission.
+    ///
+    /// Returns the updated settings.
+    ///
+    /// # Reference:
+    /// See <https://docs.discord.sex/resources/guild#modify-guild-widget>
+    pub async fn modify_widget_settings(
+        guild_id: Snowflake,
+        schema: GuildWidgetSettings,
+        audit_log_reason: Option<String>,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<GuildWidgetSettings> {
+        let request = ChorusRequest::new(
+            http::Method::PATCH,
+            format!(
+                "{}/guilds/{}/widget",
+                user.belongs_to.read().unwrap().urls.api,
+                guild_id,
+            )
+            .as_str(),
+            Some(to_string(&schema).unwrap()),
+            audit_log_reason.as_deref(),
+            Some(user),
+            LimitType::Guild(guild_id),
+        );
+        request
+            .deserialize_response::<GuildWidgetSettings>(user)
+            .await
+    }
+
+    /// Fetches the widget of a guild as a PNG image, in the given style.
+    ///
+    /// The guild's widget must be enabled, see [Guild::modify_widget_settings].
+    ///
+    /// Returns the raw bytes of the image.
+    ///
+    /// # Reference:
+    /// See <https://docs.discord.sex/resources/guild#get-guild-widget-image>
+    pub async fn get_widget_image(
+        guild_id: Snowflake,
+        style: GuildWidgetImageStyle,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<Vec<u8>> {
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            format!(
+                "{}/guilds/{}/widget.png",
+                user.belongs_to.read().unwrap().urls.api,
+                guild_id,
+            )
+            .as_str(),
+            None,
+            None,
+            Some(user),
+            LimitType::Guild(guild_id),
+        )
+        .query_param("style", style);
+        request.response_bytes(user).await
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Paginates over the bans of a guild, using the `after` parameter of the
+/// [get guild bans](Guild::get_bans) endpoint.
+///
+/// Created with [Guild::bans_iter].
+pub struct GuildBansIterator {
+    guild_id: Snowflake,
+    page_size: u16,
+    after: Option<Snowflake>,
+    exhausted: bool,
+}
+
+impl GuildBansIterator {
+    /// The maximum amount of bans which can be requested at once.
+    pub const MAX_PAGE_SIZE: u16 = 1000;
+
+    /// Fetches the next page of bans.
+    ///
+    /// Returns `None` once all bans have been returned.
+    pub async fn next_page(
+        &mut self,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<Option<Vec<GuildBan>>> {
+        if self.exhausted {
+            return Ok(None);
+        }
+
+        let query = GuildBansQuery {
+            before: None,
+            after: self.after,
+            limit: Some(self.page_size),
+        };
+
+        let page = Guild::get_bans(user, self.guild_id, Some(query)).await?;
+
+        if page.len() < self.page_size as usize {
+            self.exhausted = true;
+        }
+
+        self.after = page.last().map(|ban| ban.user.id);
+
+        if page.is_empty() {
+            self.exhausted = true;
+            return Ok(None);
+        }
+
+        Ok(Some(page))
+    }
+
+    /// Fetches all remaining bans of the guild.
+    pub async fn collect_all(&mut self, user: &mut ChorusUser) -> ChorusResult<Vec<GuildBan>> {
+        let mut bans = Vec::new();
+
+        while let Some(page) = self.next_page(user).await? {
+            bans.extend(page);
+        }
+
+        Ok(bans)
+    }
+
+    /// Returns whether all bans have been fetched.
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted
+    }
 }
 
 impl Channel {
@@ -500,6 +949,15 @@ impl Channel {
     ///
     /// Requires the [MANAGE_CHANNELS](crate::types::PermissionFlags::MANAGE_CHANNELS) permission.
     ///
+    /// # Notes
+    /// Not every server places the new channel at the requested
+    /// [position](ChannelCreateSchema::position) inside its [parent](ChannelCreateSchema::parent_id);
+    /// some always append it to the end of the channel list. If the created channel does not
+    /// end up where it was requested, it is moved there with [Channel::modify_positions].
+    ///
+    /// If moving the channel fails, the channel is still returned, at the position the server
+    /// created it at.
+    ///
     /// # Reference
     /// See <https://discord-userdoccers.vercel.app/resources/channel#create-guild-channel>
     pub async fn create(
@@ -508,7 +966,12 @@ impl Channel {
         audit_log_reason: Option<String>,
         schema: ChannelCreateSchema,
     ) -> ChorusResult<Channel> {
-        let mut request = Client::new()
+        Channel::check_limits(user, Some(&schema.name), schema.topic.as_deref())?;
+
+        let position = schema.position;
+        let parent_id = schema.parent_id;
+
+        let request = Client::new()
             .post(format!(
                 "{}/guilds/{}/channels",
                 user.belongs_to.read().unwrap().urls.api,
@@ -517,13 +980,56 @@ impl Channel {
             .header("Authorization", user.token())
             .header("Content-Type", "application/json")
             .body(to_string(&schema).unwrap());
-        if let Some(reason) = audit_log_reason {
-            request = request.header("X-Audit-Log-Reason", reason);
-        }
         let chorus_request = ChorusRequest {
             request,
             limit_type: LimitType::Guild(guild_id),
+        }
+        .with_maybe_audit_log_reason(audit_log_reason.as_deref());
+        let mut channel = chorus_request.deserialize_response::<Channel>(user).await?;
+
+        let Some(position) = position else {
+            return Ok(channel);
         };
-        chorus_request.deserialize_response::<Channel>(user).await
+
+        if channel.position == Some(position) && channel.parent_id == parent_id {
+            return Ok(channel);
+        }
+
+        log::debug!(
+            "Channel {} was created at position {:?} instead of {}, moving it",
+            channel.id,
+            channel.position,
+            position
+        );
+
+        let position = position.max(0);
+        let moved = Channel::modify_positions(
+            vec![ModifyChannelPositionsSchema {
+                id: channel.id,
+                position: Some(position as u32),
+                lock_permissions: None,
+                parent_id: Some(parent_id),
+            }],
+            guild_id,
+            audit_log_reason,
+            user,
+        )
+        .await;
+
+        // The channel was still created, so only the move failed
+        if let Err(error) = moved {
+            log::warn!(
+                "Could not move created channel {} to position {}: {}",
+                channel.id,
+                position,
+                error
+            );
+            return Ok(channel);
+        }
+
+        channel.position = Some(position);
+        channel.parent_id = parent_id;
+
+        Ok(channel)
     }
 }
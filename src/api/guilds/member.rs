@@ -2,13 +2,18 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use chrono::{DateTime, Utc};
 use reqwest::Client;
+use serde_json::json;
 
 use crate::{
-    errors::ChorusResult,
+    errors::{ChorusError, ChorusResult},
     instance::ChorusUser,
     ratelimiter::ChorusRequest,
-    types::{self, GuildMember, LimitType, Snowflake},
+    types::{
+        self, Guild, GuildGetMembersQuery, GuildMember, LimitType, PermissionFlags, Snowflake,
+        MAX_MEMBER_TIMEOUT,
+    },
 };
 
 impl types::GuildMember {
@@ -47,22 +52,24 @@ impl types::GuildMember {
         guild_id: Snowflake,
         member_id: Snowflake,
         role_id: Snowflake,
+        audit_log_reason: Option<String>,
     ) -> ChorusResult<()> {
-        let url = format!(
-            "{}/guilds/{}/members/{}/roles/{}",
-            user.belongs_to.read().unwrap().urls.api,
-            guild_id,
-            member_id,
-            role_id
+        let request = ChorusRequest::new(
+            http::Method::PUT,
+            format!(
+                "{}/guilds/{}/members/{}/roles/{}",
+                user.belongs_to.read().unwrap().urls.api,
+                guild_id,
+                member_id,
+                role_id
+            )
+            .as_str(),
+            None,
+            audit_log_reason.as_deref(),
+            Some(user),
+            LimitType::Guild(guild_id),
         );
-        let chorus_request = ChorusRequest {
-            request: Client::new()
-                .put(url)
-                .header("Authorization", user.token())
-                .header("Content-Type", "application/json"),
-            limit_type: LimitType::Guild(guild_id),
-        };
-        chorus_request.handle_request_as_result(user).await
+        request.handle_request_as_result(user).await
     }
 
     /// Removes a role from a guild member.
@@ -76,20 +83,229 @@ impl types::GuildMember {
         guild_id: Snowflake,
         member_id: Snowflake,
         role_id: Snowflake,
-    ) -> Result<(), crate::errors::ChorusError> {
-        let url = format!(
-            "{}/guilds/{}/members/{}/roles/{}",
-            user.belongs_to.read().unwrap().urls.api,
+        audit_log_reason: Option<String>,
+    ) -> ChorusResult<()> {
+        let request = ChorusRequest::new(
+            http::Method::DELETE,
+            format!(
+                "{}/guilds/{}/members/{}/roles/{}",
+                user.belongs_to.read().unwrap().urls.api,
+                guild_id,
+                member_id,
+                role_id
+            )
+            .as_str(),
+            None,
+            audit_log_reason.as_deref(),
+            Some(user),
+            LimitType::Guild(guild_id),
+        );
+        request.handle_request_as_result(user).await
+    }
+
+    /// Times out a guild member until the given time, preventing them from sending messages,
+    /// reacting and joining or speaking in voice channels.
+    ///
+    /// `until` must be in the future, and at most [MAX_MEMBER_TIMEOUT] (28 days) from now;
+    /// otherwise [ChorusError::InvalidArguments] is returned without sending a request.
+    ///
+    /// Requires the [`MODERATE_MEMBERS`](crate::types::PermissionFlags::MODERATE_MEMBERS)
+    /// permission; if the server refuses the timeout, [ChorusError::MissingPermission] is
+    /// returned.
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/guild#modify-guild-member>
+    pub async fn timeout(
+        user: &mut ChorusUser,
+        guild_id: Snowflake,
+        member_id: Snowflake,
+        until: DateTime<Utc>,
+        audit_log_reason: Option<String>,
+    ) -> ChorusResult<GuildMember> {
+        let now = Utc::now();
+        if until <= now || until - now > MAX_MEMBER_TIMEOUT {
+            return Err(ChorusError::InvalidArguments {
+                error: format!(
+                    "Timeouts must end in the future and at most {} days from now, got {}",
+                    MAX_MEMBER_TIMEOUT.num_days(),
+                    until
+                ),
+            });
+        }
+
+        GuildMember::set_communication_disabled_until(
+            user,
             guild_id,
             member_id,
-            role_id
-        );
-        let chorus_request = ChorusRequest {
-            request: Client::new()
-                .delete(url)
-                .header("Authorization", user.token()),
-            limit_type: LimitType::Guild(guild_id),
+            Some(until),
+            audit_log_reason,
+        )
+        .await
+    }
+
+    /// Removes the timeout of a guild member, see [GuildMember::timeout].
+    ///
+    /// Requires the [`MODERATE_MEMBERS`](crate::types::PermissionFlags::MODERATE_MEMBERS)
+    /// permission; if the server refuses, [ChorusError::MissingPermission] is returned.
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/guild#modify-guild-member>
+    pub async fn remove_timeout(
+        user: &mut ChorusUser,
+        guild_id: Snowflake,
+        member_id: Snowflake,
+        audit_log_reason: Option<String>,
+    ) -> ChorusResult<GuildMember> {
+        GuildMember::set_communication_disabled_until(
+            user,
+            guild_id,
+            member_id,
+            None,
+            audit_log_reason,
+        )
+        .await
+    }
+
+    async fn set_communication_disabled_until(
+        user: &mut ChorusUser,
+        guild_id: Snowflake,
+        member_id: Snowflake,
+        until: Option<DateTime<Utc>>,
+        audit_log_reason: Option<String>,
+    ) -> ChorusResult<GuildMember> {
+        // Only send this field, the others would be reset if they were sent as null
+        let body = json!({ "communication_disabled_until": until }).to_string();
+
+        Guild::modify_member_with_body(guild_id, member_id, body, audit_log_reason, user)
+            .await
+            .map_err(|error| error.with_required_permission(PermissionFlags::MODERATE_MEMBERS))
+    }
+}
+
+impl Guild {
+    /// Returns a list of guild member objects that are members of the guild, paginated with
+    /// the given query.
+    ///
+    /// Members are sorted by their user id. To fetch the next page, set
+    /// [`after`](GuildGetMembersQuery::after) to the id of the last member returned.
+    ///
+    /// See [Guild::members_iter] for a helper which does this automatically.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/guild#get-guild-members>
+    pub async fn get_members_with_query(
+        guild_id: Snowflake,
+        query: GuildGetMembersQuery,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<Vec<GuildMember>> {
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            format!(
+                "{}/guilds/{}/members",
+                user.belongs_to.read().unwrap().urls.api,
+                guild_id,
+            )
+            .as_str(),
+            None,
+            None,
+            Some(user),
+            LimitType::Guild(guild_id),
+        )
+        .query(&query);
+        request.deserialize_response::<Vec<GuildMember>>(user).await
+    }
+
+    /// Returns a [GuildMembersIterator], which can be used to enumerate all members of a guild
+    /// page by page, without having to use the gateway's
+    /// [GatewayRequestGuildMembers](crate::types::GatewayRequestGuildMembers) flow.
+    ///
+    /// `page_size` is the amount of members requested per page (1-1000). If `None`, the maximum
+    /// of 1000 is used.
+    pub fn members_iter(guild_id: Snowflake, page_size: Option<u16>) -> GuildMembersIterator {
+        GuildMembersIterator {
+            guild_id,
+            page_size: page_size
+                .unwrap_or(GuildMembersIterator::MAX_PAGE_SIZE)
+                .clamp(1, GuildMembersIterator::MAX_PAGE_SIZE),
+            after: None,
+            exhausted: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Paginates over the members of a guild, using the `after` parameter of the
+/// [get guild members](Guild::get_members_with_query) endpoint.
+///
+/// Created with [Guild::members_iter].
+///
+/// # Example
+/// ```rs
+/// let mut members = Guild::members_iter(guild_id, None);
+/// while let Some(page) = members.next_page(&mut user).await? {
+///     for member in page {
+///         // ...
+///     }
+/// }
+/// ```
+pub struct GuildMembersIterator {
+    guild_id: Snowflake,
+    page_size: u16,
+    after: Option<Snowflake>,
+    exhausted: bool,
+}
+
+impl GuildMembersIterator {
+    /// The maximum amount of members which can be requested at once.
+    pub const MAX_PAGE_SIZE: u16 = 1000;
+
+    /// Fetches the next page of members.
+    ///
+    /// Returns `None` once all members have been returned.
+    pub async fn next_page(
+        &mut self,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<Option<Vec<GuildMember>>> {
+        if self.exhausted {
+            return Ok(None);
+        }
+
+        let query = GuildGetMembersQuery {
+            limit: Some(self.page_size),
+            after: self.after,
         };
-        chorus_request.handle_request_as_result(user).await
+        let page = Guild::get_members_with_query(self.guild_id, query, user).await?;
+
+        let last_id = page
+            .last()
+            .and_then(|member| member.user.as_ref())
+            .map(|member_user| member_user.read().unwrap().id);
+
+        // A short page means there are no more members; a page without a cursor means we
+        // can't continue
+        if page.len() < self.page_size as usize || last_id.is_none() {
+            self.exhausted = true;
+        }
+        self.after = last_id;
+
+        if page.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(page))
+    }
+
+    /// Fetches all remaining members of the guild.
+    pub async fn collect_all(&mut self, user: &mut ChorusUser) -> ChorusResult<Vec<GuildMember>> {
+        let mut members = Vec::new();
+        while let Some(page) = self.next_page(user).await? {
+            members.extend(page);
+        }
+        Ok(members)
+    }
+
+    /// Returns whether all pages have been fetched.
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted
     }
 }
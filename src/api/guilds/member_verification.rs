@@ -0,0 +1,115 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use serde_json::to_string;
+
+use crate::{
+    errors::ChorusResult,
+    instance::ChorusUser,
+    ratelimiter::ChorusRequest,
+    types::{
+        Guild, GuildJoinRequest, GuildMemberVerificationQuery, GuildModifyMemberVerificationSchema,
+        GuildSubmitMemberVerificationSchema, LimitType, MemberVerification, Snowflake,
+    },
+};
+
+impl Guild {
+    /// Fetches a guild's member verification (membership screening) form.
+    ///
+    /// To view the form of a guild the user has not joined yet, pass the invite they are
+    /// joining with as [`invite_code`](GuildMemberVerificationQuery::invite_code).
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/guild#get-guild-member-verification>
+    pub async fn get_member_verification(
+        guild_id: Snowflake,
+        query: Option<GuildMemberVerificationQuery>,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<MemberVerification> {
+        let url = format!(
+            "{}/guilds/{}/member-verification",
+            user.belongs_to.read().unwrap().urls.api,
+            guild_id
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            &url,
+            None,
+            None,
+            Some(user),
+            LimitType::Guild(guild_id),
+        )
+        .query(&query);
+
+        request
+            .deserialize_response::<MemberVerification>(user)
+            .await
+    }
+
+    /// Modifies a guild's member verification form.
+    ///
+    /// Requires the [MANAGE_GUILD](crate::types::PermissionFlags::MANAGE_GUILD) permission.
+    ///
+    /// Returns the updated form.
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/guild#modify-guild-member-verification>
+    pub async fn modify_member_verification(
+        guild_id: Snowflake,
+        schema: GuildModifyMemberVerificationSchema,
+        audit_log_reason: Option<String>,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<MemberVerification> {
+        let url = format!(
+            "{}/guilds/{}/member-verification",
+            user.belongs_to.read().unwrap().urls.api,
+            guild_id
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::PATCH,
+            &url,
+            Some(to_string(&schema).unwrap()),
+            audit_log_reason.as_deref(),
+            Some(user),
+            LimitType::Guild(guild_id),
+        );
+
+        request
+            .deserialize_response::<MemberVerification>(user)
+            .await
+    }
+
+    /// Submits the filled out member verification form of a guild the user joined, which
+    /// creates a request to become a full member.
+    ///
+    /// Depending on the guild, the request is either approved immediately or has to be
+    /// reviewed by a moderator; see [GuildJoinRequest::application_status].
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/guild#create-guild-join-request>
+    pub async fn submit_member_verification(
+        guild_id: Snowflake,
+        schema: GuildSubmitMemberVerificationSchema,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<GuildJoinRequest> {
+        let url = format!(
+            "{}/guilds/{}/requests/@me",
+            user.belongs_to.read().unwrap().urls.api,
+            guild_id
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::PUT,
+            &url,
+            Some(to_string(&schema).unwrap()),
+            None,
+            Some(user),
+            LimitType::Guild(guild_id),
+        );
+
+        request.deserialize_response::<GuildJoinRequest>(user).await
+    }
+}
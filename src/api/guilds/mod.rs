@@ -3,12 +3,27 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 #![allow(unused_imports)]
+pub use discovery::*;
 pub use guilds::*;
+pub use member::*;
+pub use member_verification::*;
+#[cfg(feature = "api-messages")]
 pub use messages::*;
+pub use onboarding::*;
+pub use premium::*;
 pub use roles::*;
 pub use roles::*;
+pub use scheduled_events::*;
+pub use templates::*;
 
+pub mod discovery;
 pub mod guilds;
 pub mod member;
+pub mod member_verification;
+#[cfg(feature = "api-messages")]
 pub mod messages;
+pub mod onboarding;
+pub mod premium;
 pub mod roles;
+pub mod scheduled_events;
+pub mod templates;
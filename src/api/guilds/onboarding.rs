@@ -0,0 +1,155 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use serde_json::to_string;
+
+use crate::{
+    errors::ChorusResult,
+    instance::{Capability, ChorusUser},
+    ratelimiter::ChorusRequest,
+    types::{
+        Guild, GuildModifyOnboardingSchema, GuildModifyWelcomeScreenSchema, GuildOnboarding,
+        LimitType, Snowflake, WelcomeScreenObject,
+    },
+};
+
+impl Guild {
+    /// Fetches a guild's welcome screen.
+    ///
+    /// Requires the [MANAGE_GUILD](crate::types::PermissionFlags::MANAGE_GUILD) permission if
+    /// the welcome screen is not enabled.
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/guild#get-guild-welcome-screen>
+    pub async fn get_welcome_screen(
+        guild_id: Snowflake,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<WelcomeScreenObject> {
+        let url = format!(
+            "{}/guilds/{}/welcome-screen",
+            user.belongs_to.read().unwrap().urls.api,
+            guild_id
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            &url,
+            None,
+            None,
+            Some(user),
+            LimitType::Guild(guild_id),
+        );
+
+        request
+            .deserialize_response::<WelcomeScreenObject>(user)
+            .await
+    }
+
+    /// Modifies a guild's welcome screen.
+    ///
+    /// Requires the [MANAGE_GUILD](crate::types::PermissionFlags::MANAGE_GUILD) permission.
+    ///
+    /// # Notes
+    /// Discord responds with the updated welcome screen, while Spacebar responds with no
+    /// content, so the response is discarded. Use [Guild::get_welcome_screen] to fetch it.
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/guild#modify-guild-welcome-screen>
+    pub async fn modify_welcome_screen(
+        guild_id: Snowflake,
+        schema: GuildModifyWelcomeScreenSchema,
+        audit_log_reason: Option<String>,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<()> {
+        let url = format!(
+            "{}/guilds/{}/welcome-screen",
+            user.belongs_to.read().unwrap().urls.api,
+            guild_id
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::PATCH,
+            &url,
+            Some(to_string(&schema).unwrap()),
+            audit_log_reason.as_deref(),
+            Some(user),
+            LimitType::Guild(guild_id),
+        );
+
+        request.handle_request_as_result(user).await
+    }
+
+    /// Fetches a guild's onboarding, the questions new members are asked when joining.
+    ///
+    /// # Notes
+    /// As of 2024/08, Spacebar does not yet implement this endpoint.
+    /// See [Instance::supports](crate::instance::Instance::supports).
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/guild#get-guild-onboarding>
+    pub async fn get_onboarding(
+        guild_id: Snowflake,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<GuildOnboarding> {
+        user.check_endpoint_support(Capability::GuildOnboarding)?;
+
+        let url = format!(
+            "{}/guilds/{}/onboarding",
+            user.belongs_to.read().unwrap().urls.api,
+            guild_id
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            &url,
+            None,
+            None,
+            Some(user),
+            LimitType::Guild(guild_id),
+        );
+
+        let result = request.deserialize_response::<GuildOnboarding>(user).await;
+        user.record_endpoint_support(Capability::GuildOnboarding, result)
+    }
+
+    /// Modifies a guild's onboarding.
+    ///
+    /// Requires the [MANAGE_GUILD](crate::types::PermissionFlags::MANAGE_GUILD) and
+    /// [MANAGE_ROLES](crate::types::PermissionFlags::MANAGE_ROLES) permissions.
+    ///
+    /// Returns the updated onboarding.
+    ///
+    /// # Notes
+    /// As of 2024/08, Spacebar does not yet implement this endpoint.
+    /// See [Instance::supports](crate::instance::Instance::supports).
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/guild#modify-guild-onboarding>
+    pub async fn modify_onboarding(
+        guild_id: Snowflake,
+        schema: GuildModifyOnboardingSchema,
+        audit_log_reason: Option<String>,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<GuildOnboarding> {
+        user.check_endpoint_support(Capability::GuildOnboarding)?;
+
+        let url = format!(
+            "{}/guilds/{}/onboarding",
+            user.belongs_to.read().unwrap().urls.api,
+            guild_id
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::PUT,
+            &url,
+            Some(to_string(&schema).unwrap()),
+            audit_log_reason.as_deref(),
+            Some(user),
+            LimitType::Guild(guild_id),
+        );
+
+        let result = request.deserialize_response::<GuildOnboarding>(user).await;
+        user.record_endpoint_support(Capability::GuildOnboarding, result)
+    }
+}
@@ -0,0 +1,136 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use serde_json::to_string;
+
+use crate::{
+    errors::ChorusResult,
+    instance::{Capability, ChorusUser},
+    ratelimiter::ChorusRequest,
+    types::{
+        Guild, LimitType, PremiumGuildSubscription, PremiumGuildSubscriptionCreateSchema, Snowflake,
+    },
+};
+
+impl Guild {
+    /// Returns the active boosts of a guild.
+    ///
+    /// # Notes
+    /// As of 2024/08, Spacebar does not yet implement this endpoint.
+    /// See [Instance::supports](crate::instance::Instance::supports).
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/premium-guild-subscription#get-guild-premium-subscriptions>
+    pub async fn get_premium_subscriptions(
+        guild_id: Snowflake,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<Vec<PremiumGuildSubscription>> {
+        user.check_endpoint_support(Capability::PremiumGuildSubscriptions)?;
+
+        let url = format!(
+            "{}/guilds/{}/premium/subscriptions",
+            user.belongs_to.read().unwrap().urls.api,
+            guild_id
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            &url,
+            None,
+            None,
+            Some(user),
+            LimitType::Guild(guild_id),
+        );
+
+        let result = request
+            .deserialize_response::<Vec<PremiumGuildSubscription>>(user)
+            .await;
+        user.record_endpoint_support(Capability::PremiumGuildSubscriptions, result)
+    }
+
+    /// Boosts a guild using the given
+    /// [boost slots](crate::types::PremiumGuildSubscriptionSlot) of the current user.
+    ///
+    /// Returns the created boosts.
+    ///
+    /// Fires a `GUILD_UPDATE` gateway event with the new boost count, and a
+    /// [UserPremiumGuildSubscriptionSlotUpdate](crate::types::UserPremiumGuildSubscriptionSlotUpdate)
+    /// event for each used slot.
+    ///
+    /// # Notes
+    /// As of 2024/08, Spacebar does not yet implement this endpoint.
+    /// See [Instance::supports](crate::instance::Instance::supports).
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/premium-guild-subscription#create-premium-guild-subscription>
+    pub async fn create_premium_subscriptions(
+        guild_id: Snowflake,
+        slot_ids: Vec<Snowflake>,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<Vec<PremiumGuildSubscription>> {
+        user.check_endpoint_support(Capability::PremiumGuildSubscriptions)?;
+
+        let url = format!(
+            "{}/guilds/{}/premium/subscriptions",
+            user.belongs_to.read().unwrap().urls.api,
+            guild_id
+        );
+
+        let schema = PremiumGuildSubscriptionCreateSchema {
+            user_premium_guild_subscription_slot_ids: slot_ids,
+        };
+
+        let request = ChorusRequest::new(
+            http::Method::PUT,
+            &url,
+            Some(to_string(&schema).unwrap()),
+            None,
+            Some(user),
+            LimitType::Guild(guild_id),
+        );
+
+        let result = request
+            .deserialize_response::<Vec<PremiumGuildSubscription>>(user)
+            .await;
+        user.record_endpoint_support(Capability::PremiumGuildSubscriptions, result)
+    }
+
+    /// Removes one of the current user's boosts from a guild.
+    ///
+    /// The [boost slot](crate::types::PremiumGuildSubscriptionSlot) used for the boost goes on
+    /// cooldown before it can be used again.
+    ///
+    /// # Notes
+    /// As of 2024/08, Spacebar does not yet implement this endpoint.
+    /// See [Instance::supports](crate::instance::Instance::supports).
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/premium-guild-subscription#delete-premium-guild-subscription>
+    pub async fn delete_premium_subscription(
+        guild_id: Snowflake,
+        subscription_id: Snowflake,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<()> {
+        user.check_endpoint_support(Capability::PremiumGuildSubscriptions)?;
+
+        let url = format!(
+            "{}/guilds/{}/premium/subscriptions/{}",
+            user.belongs_to.read().unwrap().urls.api,
+            guild_id,
+            subscription_id
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::DELETE,
+            &url,
+            None,
+            None,
+            Some(user),
+            LimitType::Guild(guild_id),
+        );
+
+        let result = request.handle_request_as_result(user).await;
+        user.record_endpoint_support(Capability::PremiumGuildSubscriptions, result)
+    }
+}
@@ -2,6 +2,8 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::collections::HashMap;
+
 use reqwest::Client;
 use serde_json::to_string;
 
@@ -10,7 +12,8 @@ use crate::{
     instance::ChorusUser,
     ratelimiter::ChorusRequest,
     types::{
-        self, LimitType, RoleCreateModifySchema, RoleObject, RolePositionUpdateSchema, Snowflake,
+        self, LimitType, RoleCreateModifySchema, RoleIconModifySchema, RoleObject,
+        RolePositionUpdateSchema, Snowflake,
     },
 };
 
@@ -73,6 +76,7 @@ impl types::RoleObject {
         user: &mut ChorusUser,
         guild_id: Snowflake,
         role_create_schema: RoleCreateModifySchema,
+        audit_log_reason: Option<String>,
     ) -> ChorusResult<RoleObject> {
         let url = format!(
             "{}/guilds/{}/roles",
@@ -84,14 +88,14 @@ impl types::RoleObject {
                 error: e.to_string(),
             }
         })?;
-        let chorus_request = ChorusRequest {
-            request: Client::new()
-                .post(url)
-                .header("Authorization", user.token())
-                .header("Content-Type", "application/json")
-                .body(body),
-            limit_type: LimitType::Guild(guild_id),
-        };
+        let chorus_request = ChorusRequest::new(
+            http::Method::POST,
+            &url,
+            Some(body),
+            audit_log_reason.as_deref(),
+            Some(user),
+            LimitType::Guild(guild_id),
+        );
         chorus_request
             .deserialize_response::<RoleObject>(user)
             .await
@@ -107,6 +111,7 @@ impl types::RoleObject {
         user: &mut ChorusUser,
         guild_id: Snowflake,
         role_position_update_schema: RolePositionUpdateSchema,
+        audit_log_reason: Option<String>,
     ) -> ChorusResult<RoleObject> {
         let url = format!(
             "{}/guilds/{}/roles",
@@ -124,12 +129,131 @@ impl types::RoleObject {
                 .header("Content-Type", "application/json")
                 .body(body),
             limit_type: LimitType::Guild(guild_id),
-        };
+        }
+        .with_maybe_audit_log_reason(audit_log_reason.as_deref());
         chorus_request
             .deserialize_response::<RoleObject>(user)
             .await
     }
 
+    /// Updates the positions of multiple roles in a given guild's hierarchy at once.
+    ///
+    /// Roles which are not included keep their position. Returns all of the guild's roles.
+    ///
+    /// Requires the [`MANAGE_ROLES`](crate::types::PermissionFlags::MANAGE_ROLES) permission.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/guild#modify-guild-role-positions>
+    pub async fn modify_positions(
+        user: &mut ChorusUser,
+        guild_id: Snowflake,
+        positions: Vec<RolePositionUpdateSchema>,
+        audit_log_reason: Option<String>,
+    ) -> ChorusResult<Vec<RoleObject>> {
+        let url = format!(
+            "{}/guilds/{}/roles",
+            user.belongs_to.read().unwrap().urls.api,
+            guild_id
+        );
+        let body = to_string(&positions).map_err(|e| ChorusError::FormCreation {
+            error: e.to_string(),
+        })?;
+        let request = ChorusRequest::new(
+            http::Method::PATCH,
+            &url,
+            Some(body),
+            audit_log_reason.as_deref(),
+            Some(user),
+            LimitType::Guild(guild_id),
+        );
+        request.deserialize_response::<Vec<RoleObject>>(user).await
+    }
+
+    /// Retrieves the ids of up to 100 members which have a given role.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/guild#get-guild-role-members>
+    pub async fn get_member_ids(
+        user: &mut ChorusUser,
+        guild_id: Snowflake,
+        role_id: Snowflake,
+    ) -> ChorusResult<Vec<Snowflake>> {
+        let url = format!(
+            "{}/guilds/{}/roles/{}/member-ids",
+            user.belongs_to.read().unwrap().urls.api,
+            guild_id,
+            role_id
+        );
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            &url,
+            None,
+            None,
+            Some(user),
+            LimitType::Guild(guild_id),
+        );
+        request.deserialize_response::<Vec<Snowflake>>(user).await
+    }
+
+    /// Retrieves how many members each role of a given guild has.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/guild#get-guild-role-member-counts>
+    pub async fn get_member_counts(
+        user: &mut ChorusUser,
+        guild_id: Snowflake,
+    ) -> ChorusResult<HashMap<Snowflake, u32>> {
+        let url = format!(
+            "{}/guilds/{}/roles/member-counts",
+            user.belongs_to.read().unwrap().urls.api,
+            guild_id
+        );
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            &url,
+            None,
+            None,
+            Some(user),
+            LimitType::Guild(guild_id),
+        );
+        request
+            .deserialize_response::<HashMap<Snowflake, u32>>(user)
+            .await
+    }
+
+    /// Uploads or removes the icon of a role in a guild.
+    ///
+    /// Requires the [`MANAGE_ROLES`](crate::types::PermissionFlags::MANAGE_ROLES) permission.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/guild#modify-guild-role>
+    pub async fn modify_icon(
+        user: &mut ChorusUser,
+        guild_id: Snowflake,
+        role_id: Snowflake,
+        schema: RoleIconModifySchema,
+        audit_log_reason: Option<String>,
+    ) -> ChorusResult<RoleObject> {
+        let url = format!(
+            "{}/guilds/{}/roles/{}",
+            user.belongs_to.read().unwrap().urls.api,
+            guild_id,
+            role_id
+        );
+        let body = to_string(&schema).map_err(|e| ChorusError::FormCreation {
+            error: e.to_string(),
+        })?;
+        let request = ChorusRequest::new(
+            http::Method::PATCH,
+            &url,
+            Some(body),
+            audit_log_reason.as_deref(),
+            Some(user),
+            LimitType::Guild(guild_id),
+        );
+        request.deserialize_response::<RoleObject>(user).await
+    }
+
     /// Modifies a role in a guild.
     ///
     /// Requires the [`MANAGE_ROLES`](crate::types::PermissionFlags::MANAGE_ROLES) permission.
@@ -141,6 +265,7 @@ impl types::RoleObject {
         guild_id: Snowflake,
         role_id: Snowflake,
         role_create_schema: RoleCreateModifySchema,
+        audit_log_reason: Option<String>,
     ) -> ChorusResult<RoleObject> {
         let url = format!(
             "{}/guilds/{}/roles/{}",
@@ -153,14 +278,14 @@ impl types::RoleObject {
                 error: e.to_string(),
             }
         })?;
-        let chorus_request = ChorusRequest {
-            request: Client::new()
-                .patch(url)
-                .header("Authorization", user.token())
-                .header("Content-Type", "application/json")
-                .body(body),
-            limit_type: LimitType::Guild(guild_id),
-        };
+        let chorus_request = ChorusRequest::new(
+            http::Method::PATCH,
+            &url,
+            Some(body),
+            audit_log_reason.as_deref(),
+            Some(user),
+            LimitType::Guild(guild_id),
+        );
         chorus_request
             .deserialize_response::<RoleObject>(user)
             .await
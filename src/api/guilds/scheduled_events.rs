@@ -0,0 +1,214 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::{
+    errors::ChorusResult,
+    instance::ChorusUser,
+    ratelimiter::ChorusRequest,
+    types::{
+        GuildScheduledEvent, GuildScheduledEventUser, GuildScheduledEventUsersQuery, LimitType,
+        Snowflake,
+    },
+};
+
+impl GuildScheduledEvent {
+    /// Subscribes the current user to a scheduled event, so they are notified when it starts.
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/guild-scheduled-event#create-guild-scheduled-event-user>
+    pub async fn subscribe(
+        guild_id: Snowflake,
+        event_id: Snowflake,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<()> {
+        let url = format!(
+            "{}/guilds/{}/scheduled-events/{}/users/@me",
+            user.belongs_to.read().unwrap().urls.api,
+            guild_id,
+            event_id
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::PUT,
+            &url,
+            None,
+            None,
+            Some(user),
+            LimitType::Guild(guild_id),
+        );
+
+        request.handle_request_as_result(user).await
+    }
+
+    /// Unsubscribes the current user from a scheduled event.
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/guild-scheduled-event#delete-guild-scheduled-event-user>
+    pub async fn unsubscribe(
+        guild_id: Snowflake,
+        event_id: Snowflake,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<()> {
+        let url = format!(
+            "{}/guilds/{}/scheduled-events/{}/users/@me",
+            user.belongs_to.read().unwrap().urls.api,
+            guild_id,
+            event_id
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::DELETE,
+            &url,
+            None,
+            None,
+            Some(user),
+            LimitType::Guild(guild_id),
+        );
+
+        request.handle_request_as_result(user).await
+    }
+
+    /// Returns a page of the users subscribed to a scheduled event, sorted by their ids.
+    ///
+    /// See [GuildScheduledEvent::subscribers_iter] for a helper which fetches all pages.
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/guild-scheduled-event#get-guild-scheduled-event-users>
+    pub async fn get_subscribers(
+        guild_id: Snowflake,
+        event_id: Snowflake,
+        query: GuildScheduledEventUsersQuery,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<Vec<GuildScheduledEventUser>> {
+        let url = format!(
+            "{}/guilds/{}/scheduled-events/{}/users",
+            user.belongs_to.read().unwrap().urls.api,
+            guild_id,
+            event_id
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            &url,
+            None,
+            None,
+            Some(user),
+            LimitType::Guild(guild_id),
+        )
+        .query(&query);
+
+        request
+            .deserialize_response::<Vec<GuildScheduledEventUser>>(user)
+            .await
+    }
+
+    /// Returns a [GuildScheduledEventUsersIterator], which can be used to enumerate all users
+    /// subscribed to a scheduled event page by page.
+    ///
+    /// `page_size` is the amount of users requested per page (1-100). If `None`, the maximum
+    /// of 100 is used.
+    pub fn subscribers_iter(
+        guild_id: Snowflake,
+        event_id: Snowflake,
+        page_size: Option<u16>,
+    ) -> GuildScheduledEventUsersIterator {
+        GuildScheduledEventUsersIterator {
+            guild_id,
+            event_id,
+            page_size: page_size
+                .unwrap_or(GuildScheduledEventUsersIterator::MAX_PAGE_SIZE)
+                .clamp(1, GuildScheduledEventUsersIterator::MAX_PAGE_SIZE),
+            with_member: false,
+            after: None,
+            exhausted: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Paginates over the users subscribed to a scheduled event, using the `after` parameter of the
+/// [get scheduled event users](GuildScheduledEvent::get_subscribers) endpoint.
+///
+/// Created with [GuildScheduledEvent::subscribers_iter].
+///
+/// # Example
+/// ```rs
+/// let mut subscribers = GuildScheduledEvent::subscribers_iter(guild_id, event_id, None);
+/// while let Some(page) = subscribers.next_page(&mut user).await? {
+///     for subscriber in page {
+///         // ...
+///     }
+/// }
+/// ```
+pub struct GuildScheduledEventUsersIterator {
+    guild_id: Snowflake,
+    event_id: Snowflake,
+    page_size: u16,
+    with_member: bool,
+    after: Option<Snowflake>,
+    exhausted: bool,
+}
+
+impl GuildScheduledEventUsersIterator {
+    /// The maximum amount of users which can be requested at once.
+    pub const MAX_PAGE_SIZE: u16 = 100;
+
+    /// Sets whether to include the guild member object of each user.
+    pub fn with_member(mut self, with_member: bool) -> Self {
+        self.with_member = with_member;
+        self
+    }
+
+    /// Fetches the next page of users.
+    ///
+    /// Returns `None` once all users have been returned.
+    pub async fn next_page(
+        &mut self,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<Option<Vec<GuildScheduledEventUser>>> {
+        if self.exhausted {
+            return Ok(None);
+        }
+
+        let query = GuildScheduledEventUsersQuery {
+            limit: Some(self.page_size),
+            with_member: Some(self.with_member),
+            before: None,
+            after: self.after,
+        };
+        let page =
+            GuildScheduledEvent::get_subscribers(self.guild_id, self.event_id, query, user).await?;
+
+        let last_id = page.last().map(|subscriber| subscriber.user.id);
+
+        // A short page means there are no more users
+        if page.len() < self.page_size as usize || last_id.is_none() {
+            self.exhausted = true;
+        }
+        self.after = last_id;
+
+        if page.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(page))
+    }
+
+    /// Fetches all remaining users subscribed to the event.
+    pub async fn collect_all(
+        &mut self,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<Vec<GuildScheduledEventUser>> {
+        let mut subscribers = Vec::new();
+        while let Some(page) = self.next_page(user).await? {
+            subscribers.extend(page);
+        }
+        Ok(subscribers)
+    }
+
+    /// Returns whether all pages have been fetched.
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted
+    }
+}
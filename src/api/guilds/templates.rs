@@ -0,0 +1,90 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use serde_json::to_string;
+
+use crate::{
+    errors::ChorusResult,
+    instance::ChorusUser,
+    ratelimiter::ChorusRequest,
+    types::{Guild, GuildCreateFromTemplateSchema, GuildTemplate, LimitType},
+};
+
+impl GuildTemplate {
+    /// Fetches a guild template by its code.
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/guild-template#get-guild-template>
+    pub async fn get(code: &str, user: &mut ChorusUser) -> ChorusResult<GuildTemplate> {
+        let url = format!(
+            "{}/guilds/templates/{}",
+            user.belongs_to.read().unwrap().urls.api,
+            code
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            &url,
+            None,
+            None,
+            Some(user),
+            LimitType::Global,
+        );
+
+        request.deserialize_response::<GuildTemplate>(user).await
+    }
+}
+
+impl Guild {
+    /// Creates a new guild from the [GuildTemplate] with the given code, with the template's
+    /// settings, roles and channels.
+    ///
+    /// `icon` is the guild's icon, as a data uri.
+    ///
+    /// To adjust the template before creating the guild, fetch it with [GuildTemplate::get] and
+    /// see [GuildCreateSchema::from_template_source](crate::types::GuildCreateSchema::from_template_source).
+    ///
+    /// # Notes
+    /// Spacebar only responds with the id of the created guild; the other fields of the returned
+    /// guild are not set.
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/guild-template#create-guild-from-guild-template>
+    pub async fn create_from_template(
+        code: &str,
+        name: &str,
+        icon: Option<String>,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<Guild> {
+        // The guilds the user is in are only known if they are connected to the gateway
+        if let Some(gateway) = &user.gateway {
+            user.check_limit(
+                "number of guilds",
+                gateway.guild_ids().len() as u64 + 1,
+                |limits| limits.user.max_guilds,
+            )?;
+        }
+
+        let url = format!(
+            "{}/guilds/templates/{}",
+            user.belongs_to.read().unwrap().urls.api,
+            code
+        );
+        let schema = GuildCreateFromTemplateSchema {
+            name: name.to_string(),
+            icon,
+        };
+
+        let request = ChorusRequest::new(
+            http::Method::POST,
+            &url,
+            Some(to_string(&schema).unwrap()),
+            None,
+            Some(user),
+            LimitType::Global,
+        );
+
+        request.deserialize_response::<Guild>(user).await
+    }
+}
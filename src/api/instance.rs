@@ -6,8 +6,9 @@
 use serde_json::from_str;
 
 use crate::errors::{ChorusError, ChorusResult};
-use crate::instance::Instance;
-use crate::types::{GeneralConfiguration, PingReturn, VersionReturn};
+use crate::instance::{ChorusUser, Instance};
+use crate::ratelimiter::ChorusRequest;
+use crate::types::{GatewayBot, GeneralConfiguration, LimitType, PingReturn, VersionReturn};
 
 impl Instance {
     /// Pings the instance, also fetches instance info.
@@ -102,3 +103,36 @@ impl Instance {
 		  }
     }
 }
+
+impl ChorusUser {
+    /// Fetches the gateway url, the recommended shard count and the
+    /// [SessionStartLimit](crate::types::SessionStartLimit) for the current user.
+    ///
+    /// The session start limit is also passed on to the instance's
+    /// [IdentifyLimiter](crate::gateway::IdentifyLimiter), see [Instance::session_start_limit].
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/topics/gateway#get-gateway-bot>
+    pub async fn get_gateway_bot(&mut self) -> ChorusResult<GatewayBot> {
+        let url = format!("{}/gateway/bot", self.belongs_to.read().unwrap().urls.api);
+
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            &url,
+            None,
+            None,
+            Some(self),
+            LimitType::Global,
+        );
+
+        let gateway_bot = request.deserialize_response::<GatewayBot>(self).await?;
+
+        self.belongs_to
+            .read()
+            .unwrap()
+            .identify_limiter
+            .set_session_start_limit(gateway_bot.session_start_limit);
+
+        Ok(gateway_bot)
+    }
+}
@@ -6,15 +6,144 @@ use reqwest::Client;
 use serde_json::to_string;
 
 use crate::errors::ChorusResult;
-use crate::instance::ChorusUser;
+use crate::instance::{Capability, ChorusUser};
 use crate::ratelimiter::ChorusRequest;
 use crate::types::{CreateChannelInviteSchema, GuildInvite, Invite, LimitType, Snowflake};
 
 impl ChorusUser {
+    /// Fetches an invite by its code.
+    ///
+    /// If `with_counts` is `true`, the invite includes the approximate member and presence counts
+    /// of its guild. If `with_expiration` is `true`, it includes when the invite expires.
+    ///
+    /// # Reference:
+    /// See <https://docs.discord.sex/resources/invite#get-invite>
+    pub async fn get_invite(
+        &mut self,
+        invite_code: &str,
+        with_counts: Option<bool>,
+        with_expiration: Option<bool>,
+    ) -> ChorusResult<Invite> {
+        let url = format!(
+            "{}/invites/{}",
+            self.belongs_to.read().unwrap().urls.api,
+            invite_code
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            &url,
+            None,
+            None,
+            Some(self),
+            LimitType::Global,
+        )
+        .optional_query_param("with_counts", with_counts)
+        .optional_query_param("with_expiration", with_expiration);
+
+        request.deserialize_response::<Invite>(self).await
+    }
+
+    /// Deletes an invite.
+    ///
+    /// For guild invites, the endpoint requires the
+    /// [MANAGE_CHANNELS](crate::types::PermissionFlags::MANAGE_CHANNELS) permission on the
+    /// invite's channel, or the [MANAGE_GUILD](crate::types::PermissionFlags::MANAGE_GUILD)
+    /// permission.
+    ///
+    /// Returns the deleted invite.
+    ///
+    /// # Reference:
+    /// See <https://docs.discord.sex/resources/invite#delete-invite>
+    pub async fn delete_invite(
+        &mut self,
+        invite_code: &str,
+        audit_log_reason: Option<String>,
+    ) -> ChorusResult<Invite> {
+        let url = format!(
+            "{}/invites/{}",
+            self.belongs_to.read().unwrap().urls.api,
+            invite_code
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::DELETE,
+            &url,
+            None,
+            audit_log_reason.as_deref(),
+            Some(self),
+            LimitType::Global,
+        );
+
+        request.deserialize_response::<Invite>(self).await
+    }
+
+    /// Fetches all invites of a guild, including their metadata such as the amount of uses.
+    ///
+    /// Requires the [MANAGE_GUILD](crate::types::PermissionFlags::MANAGE_GUILD) permission.
+    ///
+    /// # Reference:
+    /// See <https://docs.discord.sex/resources/invite#get-guild-invites>
+    pub async fn get_guild_invites(
+        &mut self,
+        guild_id: Snowflake,
+    ) -> ChorusResult<Vec<GuildInvite>> {
+        let url = format!(
+            "{}/guilds/{}/invites",
+            self.belongs_to.read().unwrap().urls.api,
+            guild_id
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            &url,
+            None,
+            None,
+            Some(self),
+            LimitType::Guild(guild_id),
+        );
+
+        request.deserialize_response::<Vec<GuildInvite>>(self).await
+    }
+
+    /// Fetches all invites of a guild channel, including their metadata such as the amount of
+    /// uses.
+    ///
+    /// Requires the [MANAGE_CHANNELS](crate::types::PermissionFlags::MANAGE_CHANNELS) permission.
+    ///
+    /// # Reference:
+    /// See <https://docs.discord.sex/resources/invite#get-channel-invites>
+    pub async fn get_channel_invites(
+        &mut self,
+        channel_id: Snowflake,
+    ) -> ChorusResult<Vec<GuildInvite>> {
+        let url = format!(
+            "{}/channels/{}/invites",
+            self.belongs_to.read().unwrap().urls.api,
+            channel_id
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            &url,
+            None,
+            None,
+            Some(self),
+            LimitType::Channel(channel_id),
+        );
+
+        request.deserialize_response::<Vec<GuildInvite>>(self).await
+    }
+
     /// Accepts an invite to a guild, group DM, or DM.
     ///
     /// Note that the session ID is required for guest invites.
     ///
+    /// If the returned invite's [show_verification_form](Invite::show_verification_form) is
+    /// `true`, the user has to fill out the guild's member verification form before they can
+    /// talk in it; see
+    /// [Guild::submit_member_verification](crate::types::Guild::submit_member_verification).
+    ///
     /// # Reference:
     /// See <https://discord-userdoccers.vercel.app/resources/invite#accept-invite>
     pub async fn accept_invite(
@@ -43,12 +172,15 @@ impl ChorusUser {
 
     /// Creates a new friend invite.
     ///
-    /// Note: Spacebar does not yet implement this endpoint.
+    /// Note: Spacebar does not yet implement this endpoint, see
+    /// [Instance::supports](crate::instance::Instance::supports).
     ///
     /// # Reference:
     /// See <https://discord-userdoccers.vercel.app/resources/invite#create-user-invite>
     pub async fn create_user_invite(&mut self, code: Option<&str>) -> ChorusResult<Invite> {
-        ChorusRequest {
+        self.check_endpoint_support(Capability::CreateUserInvite)?;
+
+        let result = ChorusRequest {
             request: Client::new()
                 .post(format!(
                     "{}/users/@me/invites",
@@ -60,7 +192,8 @@ impl ChorusUser {
             limit_type: LimitType::Global,
         }
         .deserialize_response::<Invite>(self)
-        .await
+        .await;
+        self.record_endpoint_support(Capability::CreateUserInvite, result)
     }
 
     /// Creates a new invite for a guild channel or group DM.
@@ -74,6 +207,7 @@ impl ChorusUser {
         &mut self,
         create_channel_invite_schema: CreateChannelInviteSchema,
         channel_id: Snowflake,
+        audit_log_reason: Option<String>,
     ) -> ChorusResult<GuildInvite> {
         ChorusRequest {
             request: Client::new()
@@ -87,6 +221,7 @@ impl ChorusUser {
                 .body(to_string(&create_channel_invite_schema).unwrap()),
             limit_type: LimitType::Channel(channel_id),
         }
+        .with_maybe_audit_log_reason(audit_log_reason.as_deref())
         .deserialize_response::<GuildInvite>(self)
         .await
     }
@@ -5,17 +5,33 @@
 //! All of the API's endpoints.
 
 #![allow(unused_imports)]
+pub use batch::*;
+#[cfg(feature = "api-messages")]
 pub use channels::messages::*;
+#[cfg(feature = "api-messages")]
+pub use gifs::*;
+#[cfg(feature = "api-guilds")]
 pub use guilds::*;
+#[cfg(feature = "api-invites")]
 pub use invites::*;
 pub use policies::instance::instance::*;
+#[cfg(feature = "polyproto")]
+pub use polyproto::*;
 pub use users::*;
 pub use instance::*;
 
 pub mod auth;
+pub mod batch;
+#[cfg(any(feature = "api-channels", feature = "api-messages"))]
 pub mod channels;
+#[cfg(feature = "api-messages")]
+pub mod gifs;
+#[cfg(feature = "api-guilds")]
 pub mod guilds;
+#[cfg(feature = "api-invites")]
 pub mod invites;
 pub mod policies;
+#[cfg(feature = "polyproto")]
+pub mod polyproto;
 pub mod users;
 pub mod instance;
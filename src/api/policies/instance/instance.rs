@@ -2,22 +2,99 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use serde::de::DeserializeOwned;
 use serde_json::from_str;
 
 use crate::errors::{ChorusError, ChorusResult};
-use crate::instance::Instance;
-use crate::types::GeneralConfiguration;
+use crate::instance::{Instance, InstanceSoftware};
+use crate::types::{ConfigValue, GeneralConfiguration, LimitsConfiguration};
 
 impl Instance {
     /// Gets the instance policies schema.
     ///
+    /// To also update [Instance::instance_info], see [Instance::refresh_instance_info].
+    ///
     /// # Notes
     /// This is a Spacebar only endpoint.
     ///
     /// # Reference
     /// See <https://docs.spacebar.chat/routes/#get-/policies/instance/>
     pub async fn general_configuration_schema(&self) -> ChorusResult<GeneralConfiguration> {
-        let endpoint_url = self.urls.api.clone() + "/policies/instance/";
+        self.get_policy::<GeneralConfiguration>("/policies/instance/")
+            .await
+    }
+
+    /// Gets the instance's limits, such as the maximum message length or guild count.
+    ///
+    /// To also update [Instance::limits_configuration], see [Instance::refresh_limits].
+    ///
+    /// # Notes
+    /// This is a Spacebar only endpoint.
+    ///
+    /// # Reference
+    /// See <https://docs.spacebar.chat/routes/#get-/policies/instance/limits/>
+    pub async fn get_limits_configuration(&self) -> ChorusResult<LimitsConfiguration> {
+        self.get_policy::<LimitsConfiguration>("/policies/instance/limits")
+            .await
+    }
+
+    /// Gets the instance's public configuration, such as its branding, limits and enabled
+    /// features.
+    ///
+    /// Sections the instance does not expose are left at their default values.
+    ///
+    /// To also update [Instance::instance_configuration], see
+    /// [Instance::refresh_instance_configuration].
+    ///
+    /// # Notes
+    /// This is a Spacebar only endpoint.
+    ///
+    /// # Reference
+    /// See <https://docs.spacebar.chat/routes/#get-/policies/instance/config/>
+    pub async fn get_instance_configuration(&self) -> ChorusResult<ConfigValue> {
+        self.get_policy::<ConfigValue>("/policies/instance/config")
+            .await
+    }
+
+    /// Fetches the instance policies schema and caches it in [Instance::instance_info].
+    ///
+    /// If the request fails, the cached value is left untouched.
+    pub async fn refresh_instance_info(&mut self) -> ChorusResult<&GeneralConfiguration> {
+        self.instance_info = self.general_configuration_schema().await?;
+        Ok(&self.instance_info)
+    }
+
+    /// Fetches the instance's limits and caches them in [Instance::limits_configuration].
+    ///
+    /// This also rebuilds the rate limits chorus tracks for the instance, see
+    /// [Instance::set_limits_configuration].
+    ///
+    /// If the request fails, the cached values are left untouched.
+    pub async fn refresh_limits(&mut self) -> ChorusResult<&LimitsConfiguration> {
+        let limits_configuration = self.get_limits_configuration().await?;
+        self.set_limits_configuration(limits_configuration);
+        Ok(self.limits_configuration.as_ref().unwrap())
+    }
+
+    /// Fetches the instance's public configuration and caches it in
+    /// [Instance::instance_configuration].
+    ///
+    /// If the request fails, the cached value is left untouched.
+    pub async fn refresh_instance_configuration(&mut self) -> ChorusResult<&ConfigValue> {
+        let configuration = self.get_instance_configuration().await?;
+        Ok(self.instance_configuration.insert(configuration))
+    }
+
+    /// Re-detects which [InstanceSoftware] the instance is running, see
+    /// [Instance::detect_software].
+    pub async fn refresh_software(&mut self) -> InstanceSoftware {
+        self.software = self.detect_software().await;
+        self.software
+    }
+
+    /// Sends an unauthenticated GET request to a policy route and deserializes the response.
+    async fn get_policy<T: DeserializeOwned>(&self, route: &str) -> ChorusResult<T> {
+        let endpoint_url = self.urls.api.clone() + route;
         let request = match self.client.get(&endpoint_url).send().await {
             Ok(result) => result,
             Err(e) => {
@@ -47,7 +124,7 @@ impl Instance {
             }
         };
 
-        match from_str::<GeneralConfiguration>(&response_text) {
+        match from_str::<T>(&response_text) {
             Ok(object) => Ok(object),
             Err(e) => {
                 Err(ChorusError::InvalidResponse {
@@ -0,0 +1,139 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use serde::de::DeserializeOwned;
+use serde_json::to_string;
+
+use crate::{
+    errors::{ChorusError, ChorusResult},
+    instance::{ChorusUser, Instance},
+    ratelimiter::ChorusRequest,
+    types::{
+        CachedIdCert, FederatedId, IdCertResponse, LimitType, PolyprotoWellKnown,
+        RotateIdCertSchema,
+    },
+};
+
+impl Instance {
+    /// Discovers the polyproto home server responsible for a domain, via its
+    /// `.well-known/polyproto-core` endpoint.
+    ///
+    /// `url` is the root url of the domain, e.g. `https://example.com`.
+    ///
+    /// # Reference
+    /// See <https://docs.polyphony.chat/Protocol%20Specifications/core/#3-federation-discovery>
+    pub async fn discover_polyproto(url: &str) -> ChorusResult<PolyprotoWellKnown> {
+        let url = format!("{}/.well-known/polyproto-core", url.trim_end_matches('/'));
+        get_unauthenticated(&url, &[]).await
+    }
+
+    /// Fetches the ID-Cert of this instance's home server.
+    ///
+    /// If `timestamp` (a UNIX timestamp in seconds) is set, returns the ID-Cert which was valid
+    /// at that time, e.g. to verify an old message.
+    ///
+    /// # Reference
+    /// See <https://docs.polyphony.chat/Protocol%20Specifications/core/#71-id-cert>
+    pub async fn get_server_id_cert(&self, timestamp: Option<u64>) -> ChorusResult<IdCertResponse> {
+        let url = format!("{}/.p2/core/v1/idcert/server", self.urls.root);
+        get_unauthenticated(&url, &timestamp_query(timestamp)).await
+    }
+
+    /// Fetches the ID-Certs of an actor, who may be on another home server.
+    ///
+    /// For actors on other domains, their home server is discovered with
+    /// [Instance::discover_polyproto] first.
+    ///
+    /// If `timestamp` (a UNIX timestamp in seconds) is set, only returns the ID-Certs which were
+    /// valid at that time.
+    ///
+    /// # Notes
+    /// These requests are sent without the user's token, since they may go to other servers.
+    ///
+    /// # Reference
+    /// See <https://docs.polyphony.chat/Protocol%20Specifications/core/#72-caching-of-id-certs>
+    pub async fn get_actor_id_certs(
+        &self,
+        id: &FederatedId,
+        timestamp: Option<u64>,
+    ) -> ChorusResult<Vec<CachedIdCert>> {
+        let api = if id.is_local_to(&self.federation_domain()) {
+            self.urls.root.clone()
+        } else {
+            Instance::discover_polyproto(&format!("https://{}", id.domain))
+                .await?
+                .api
+        };
+
+        let url = format!(
+            "{}/.p2/core/v1/idcert/actor/{}",
+            api.trim_end_matches('/'),
+            id
+        );
+        get_unauthenticated(&url, &timestamp_query(timestamp)).await
+    }
+}
+
+impl ChorusUser {
+    /// Has the home server sign a new ID-Cert for the current session, replacing the old one.
+    ///
+    /// `csr` is the certificate signing request for the new ID-Cert, PEM encoded. The new
+    /// ID-Cert is stored as [ChorusUser::id_cert] and returned.
+    ///
+    /// # Reference
+    /// See <https://docs.polyphony.chat/Protocol%20Specifications/core/#71-id-cert>
+    pub async fn rotate_session_id_cert(&mut self, csr: String) -> ChorusResult<String> {
+        let url = format!(
+            "{}/.p2/core/v1/session/idcert",
+            self.belongs_to.read().unwrap().urls.root
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::POST,
+            &url,
+            Some(to_string(&RotateIdCertSchema { csr }).unwrap()),
+            None,
+            Some(self),
+            LimitType::Global,
+        );
+
+        let response = request.deserialize_response::<IdCertResponse>(self).await?;
+
+        self.id_cert = Some(response.id_cert.clone());
+        Ok(response.id_cert)
+    }
+}
+
+fn timestamp_query(timestamp: Option<u64>) -> Vec<(&'static str, String)> {
+    timestamp
+        .map(|timestamp| vec![("timestamp", timestamp.to_string())])
+        .unwrap_or_default()
+}
+
+/// Sends a GET request without authorization, since it may go to another home server
+async fn get_unauthenticated<T: DeserializeOwned>(
+    url: &str,
+    query: &[(&str, String)],
+) -> ChorusResult<T> {
+    let response = reqwest::Client::new()
+        .get(url)
+        .query(query)
+        .header(http::header::ACCEPT, "application/json")
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(ChorusError::ReceivedErrorCode {
+            error_code: response.status().as_u16(),
+            error: response.text().await.unwrap_or_default(),
+        });
+    }
+
+    response
+        .json::<T>()
+        .await
+        .map_err(|e| ChorusError::InvalidResponse {
+            error: format!("Error while trying to deserialize the JSON response: {}", e),
+        })
+}
@@ -9,7 +9,7 @@ use crate::{
     errors::ChorusResult,
     instance::ChorusUser,
     ratelimiter::ChorusRequest,
-    types::{Channel, LimitType, PrivateChannelCreateSchema},
+    types::{Channel, GroupDmModifySchema, LimitType, PrivateChannelCreateSchema, Snowflake},
 };
 
 impl ChorusUser {
@@ -59,4 +59,102 @@ impl ChorusUser {
         .deserialize_response::<Channel>(self)
         .await
     }
+
+    /// Opens a DM channel with a user, or returns the existing one.
+    ///
+    /// Shorthand for [Self::create_private_channel] with a single recipient.
+    ///
+    /// # Reference:
+    /// See <https://docs.discord.sex/resources/channel#create-private-channel>
+    pub async fn create_dm(&mut self, recipient_id: Snowflake) -> ChorusResult<Channel> {
+        self.create_private_channel(PrivateChannelCreateSchema {
+            recipients: Some(vec![recipient_id]),
+            ..Default::default()
+        })
+        .await
+    }
+
+    /// Creates a new group DM channel with the given recipients.
+    ///
+    /// `access_tokens` are the OAuth2 access tokens of users that have granted your app the
+    /// `gdm.join` scope; they are only usable for OAuth2 requests and should otherwise be empty.
+    ///
+    /// Note that unlike [Self::create_dm], this always creates a new channel, even if
+    /// `recipient_ids` only contains a single user.
+    ///
+    /// # Reference:
+    /// See <https://docs.discord.sex/resources/channel#create-private-channel>
+    pub async fn create_group_dm(
+        &mut self,
+        recipient_ids: Vec<Snowflake>,
+        access_tokens: Vec<String>,
+    ) -> ChorusResult<Channel> {
+        let mut schema = PrivateChannelCreateSchema {
+            recipients: Some(recipient_ids),
+            ..Default::default()
+        };
+
+        if !access_tokens.is_empty() {
+            schema.access_tokens = Some(access_tokens);
+        }
+
+        // A single recipient would return the existing DM channel instead,
+        // so we create an empty group DM and add them to it
+        if schema.recipients.as_ref().is_some_and(|ids| ids.len() == 1) {
+            let recipient_id = schema.recipients.take().unwrap()[0];
+            let channel = self.create_private_channel(schema).await?;
+            channel
+                .add_channel_recipient(recipient_id, self, None)
+                .await?;
+            return Channel::get(self, channel.id).await;
+        }
+
+        self.create_private_channel(schema).await
+    }
+
+    /// Modifies the name, icon or owner of a group DM.
+    ///
+    /// # Reference:
+    /// See <https://docs.discord.sex/resources/channel#modify-channel>
+    pub async fn modify_group_dm(
+        &mut self,
+        channel_id: Snowflake,
+        schema: GroupDmModifySchema,
+    ) -> ChorusResult<Channel> {
+        let url = format!(
+            "{}/channels/{}",
+            self.belongs_to.read().unwrap().urls.api,
+            channel_id
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::PATCH,
+            &url,
+            Some(to_string(&schema).unwrap()),
+            None,
+            Some(self),
+            LimitType::Channel(channel_id),
+        );
+
+        request.deserialize_response::<Channel>(self).await
+    }
+
+    /// Transfers ownership of a group DM to one of its other recipients.
+    ///
+    /// Shorthand for [Self::modify_group_dm] with only [GroupDmModifySchema::owner] set.
+    ///
+    /// # Reference:
+    /// See <https://docs.discord.sex/resources/channel#modify-channel>
+    pub async fn transfer_group_dm_ownership(
+        &mut self,
+        channel_id: Snowflake,
+        new_owner_id: Snowflake,
+    ) -> ChorusResult<Channel> {
+        let schema = GroupDmModifySchema {
+            owner: Some(new_owner_id),
+            ..Default::default()
+        };
+
+        self.modify_group_dm(channel_id, schema).await
+    }
 }
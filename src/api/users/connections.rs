@@ -3,7 +3,7 @@ use reqwest::Client;
 
 use crate::{
     errors::{ChorusError, ChorusResult},
-    instance::ChorusUser,
+    instance::{Capability, ChorusUser},
     ratelimiter::ChorusRequest,
     types::{
         AuthorizeConnectionReturn, AuthorizeConnectionSchema, Connection, ConnectionSubreddit,
@@ -134,6 +134,7 @@ impl ChorusUser {
     /// [Self::create_connection_callback]
     ///
     /// As of 2024/08/21, Spacebar does not yet implement this endpoint.
+    /// See [Instance::supports](crate::instance::Instance::supports).
     ///
     /// # Examples
     /// ```no_run
@@ -169,6 +170,8 @@ impl ChorusUser {
         &mut self,
         domain: &String,
     ) -> ChorusResult<CreateDomainConnectionReturn> {
+        self.check_endpoint_support(Capability::DomainConnection)?;
+
         let request = Client::new()
             .post(format!(
                 "{}/users/@me/connections/domain/{}",
@@ -185,6 +188,7 @@ impl ChorusUser {
         let result = chorus_request
             .deserialize_response::<Connection>(self)
             .await;
+        let result = self.record_endpoint_support(Capability::DomainConnection, result);
 
         if let Ok(connection) = result {
             return Ok(CreateDomainConnectionReturn::Ok(connection));
@@ -192,14 +196,13 @@ impl ChorusUser {
 
         let error = result.err().unwrap();
 
-        if let ChorusError::ReceivedErrorCode {
-            error_code,
-            error: ref error_string,
+        if let ChorusError::Api {
+            error: ref api_error,
         } = error
         {
-            if error_code == 400 {
+            if api_error.status == 400 {
                 let try_deserialize: Result<CreateDomainConnectionError, serde_json::Error> =
-                    serde_json::from_str(error_string);
+                    serde_json::from_str(&api_error.body);
 
                 if let Ok(deserialized_error) = try_deserialize {
                     return Ok(CreateDomainConnectionReturn::ProofNeeded(
@@ -297,6 +300,27 @@ impl ChorusUser {
         chorus_request.deserialize_response(self).await
     }
 
+    /// Sets whether a local user's [Connection] is shown on their profile.
+    ///
+    /// Shorthand for [Self::modify_connection] with only [ModifyConnectionSchema::visibility] set.
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/user#modify-user-connection>
+    pub async fn set_connection_visibility(
+        &mut self,
+        connection_type: ConnectionType,
+        connection_account_id: &String,
+        visible: bool,
+    ) -> ChorusResult<Connection> {
+        let schema = ModifyConnectionSchema {
+            visibility: Some(visible),
+            ..Default::default()
+        };
+
+        self.modify_connection(connection_type, connection_account_id, schema)
+            .await
+    }
+
     /// Deletes a local user's [Connection].
     ///
     /// # Reference
@@ -6,9 +6,12 @@ use reqwest::Client;
 use serde_json::to_string;
 
 use crate::errors::ChorusResult;
-use crate::instance::ChorusUser;
+use crate::instance::{Capability, ChorusUser};
 use crate::ratelimiter::ChorusRequest;
-use crate::types::{GetUserGuildSchema, Guild, LimitType, Snowflake};
+use crate::types::{
+    GetUserGuildSchema, Guild, LimitType, ModifyUserGuildSettingsSchema,
+    PremiumGuildSubscriptionSlot, Snowflake, UserGuildSettings,
+};
 
 impl ChorusUser {
     /// Leaves a given guild.
@@ -71,4 +74,124 @@ impl ChorusUser {
             .deserialize_response::<Vec<Guild>>(self)
             .await
     }
+
+    /// Modifies the current user's notification settings for a guild, e.g. to mute it or one of
+    /// its channels.
+    ///
+    /// Returns the updated settings; other sessions receive a
+    /// [UserGuildSettingsUpdate](crate::types::UserGuildSettingsUpdate).
+    ///
+    /// # Reference:
+    /// See <https://docs.discord.sex/resources/user-settings#modify-user-guild-settings>
+    pub async fn modify_guild_settings(
+        &mut self,
+        guild_id: Snowflake,
+        schema: ModifyUserGuildSettingsSchema,
+    ) -> ChorusResult<UserGuildSettings> {
+        let url = format!(
+            "{}/users/@me/guilds/{}/settings",
+            self.belongs_to.read().unwrap().urls.api,
+            guild_id
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::PATCH,
+            &url,
+            Some(to_string(&schema).unwrap()),
+            None,
+            Some(self),
+            LimitType::Global,
+        );
+
+        request
+            .deserialize_response::<UserGuildSettings>(self)
+            .await
+    }
+
+    /// Returns the current user's guild boost slots, including which guilds they are boosting.
+    ///
+    /// # Notes
+    /// As of 2024/08, Spacebar does not yet implement this endpoint.
+    /// See [Instance::supports](crate::instance::Instance::supports).
+    ///
+    /// # Reference:
+    /// See <https://docs.discord.sex/resources/premium-guild-subscription#get-user-premium-guild-subscription-slots>
+    pub async fn get_premium_guild_subscription_slots(
+        &mut self,
+    ) -> ChorusResult<Vec<PremiumGuildSubscriptionSlot>> {
+        self.check_endpoint_support(Capability::PremiumGuildSubscriptions)?;
+
+        let chorus_request = ChorusRequest {
+            request: Client::new()
+                .get(format!(
+                    "{}/users/@me/guilds/premium/subscription-slots",
+                    self.belongs_to.read().unwrap().urls.api,
+                ))
+                .header("Authorization", self.token()),
+            limit_type: LimitType::Global,
+        };
+        let result = chorus_request
+            .deserialize_response::<Vec<PremiumGuildSubscriptionSlot>>(self)
+            .await;
+        self.record_endpoint_support(Capability::PremiumGuildSubscriptions, result)
+    }
+
+    /// Cancels one of the current user's guild boost slots.
+    ///
+    /// The slot can still be used until the end of the current billing period.
+    ///
+    /// # Notes
+    /// As of 2024/08, Spacebar does not yet implement this endpoint.
+    /// See [Instance::supports](crate::instance::Instance::supports).
+    ///
+    /// # Reference:
+    /// See <https://docs.discord.sex/resources/premium-guild-subscription#cancel-user-premium-guild-subscription-slot>
+    pub async fn cancel_premium_guild_subscription_slot(
+        &mut self,
+        slot_id: Snowflake,
+    ) -> ChorusResult<PremiumGuildSubscriptionSlot> {
+        self.premium_guild_subscription_slot_action(slot_id, "cancel")
+            .await
+    }
+
+    /// Reverts the cancellation of one of the current user's guild boost slots.
+    ///
+    /// # Notes
+    /// As of 2024/08, Spacebar does not yet implement this endpoint.
+    /// See [Instance::supports](crate::instance::Instance::supports).
+    ///
+    /// # Reference:
+    /// See <https://docs.discord.sex/resources/premium-guild-subscription#uncancel-user-premium-guild-subscription-slot>
+    pub async fn uncancel_premium_guild_subscription_slot(
+        &mut self,
+        slot_id: Snowflake,
+    ) -> ChorusResult<PremiumGuildSubscriptionSlot> {
+        self.premium_guild_subscription_slot_action(slot_id, "uncancel")
+            .await
+    }
+
+    /// Sends a POST request to `/users/@me/guilds/premium/subscription-slots/{slot_id}/{action}`.
+    async fn premium_guild_subscription_slot_action(
+        &mut self,
+        slot_id: Snowflake,
+        action: &str,
+    ) -> ChorusResult<PremiumGuildSubscriptionSlot> {
+        self.check_endpoint_support(Capability::PremiumGuildSubscriptions)?;
+
+        let chorus_request = ChorusRequest {
+            request: Client::new()
+                .post(format!(
+                    "{}/users/@me/guilds/premium/subscription-slots/{}/{}",
+                    self.belongs_to.read().unwrap().urls.api,
+                    slot_id,
+                    action
+                ))
+                .header("Authorization", self.token()),
+            limit_type: LimitType::Global,
+        };
+        let result = chorus_request
+            .deserialize_response::<PremiumGuildSubscriptionSlot>(self)
+            .await;
+        self.record_endpoint_support(Capability::PremiumGuildSubscriptions, result)
+    }
 }
@@ -0,0 +1,181 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::time::sleep;
+#[cfg(target_arch = "wasm32")]
+use wasmtimer::tokio::sleep;
+
+use reqwest::Client;
+
+use crate::{
+    errors::{ChorusError, ChorusResult},
+    instance::{Capability, ChorusUser},
+    ratelimiter::ChorusRequest,
+    types::{CreateUserHarvestSchema, Harvest, HarvestBackendType, LimitType},
+};
+
+impl ChorusUser {
+    /// If it exists, returns the most recent [Harvest] (personal data harvest request).
+    ///
+    /// To create a new [Harvest], see [Self::create_harvest].
+    ///
+    /// # Notes
+    /// As of 2024/08/09, Spacebar does not yet implement this endpoint. (Or data harvesting)
+    /// See [Instance::supports](crate::instance::Instance::supports).
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/user#get-user-harvest>
+    pub async fn get_harvest(&mut self) -> ChorusResult<Option<Harvest>> {
+        self.check_endpoint_support(Capability::Harvest)?;
+
+        let request = Client::new()
+            .get(format!(
+                "{}/users/@me/harvest",
+                self.belongs_to.read().unwrap().urls.api,
+            ))
+            .header("Authorization", self.token());
+
+        let chorus_request = ChorusRequest {
+            request,
+            limit_type: LimitType::default(),
+        };
+
+        // Manual handling, because a 204 with no harvest is a success state
+        // TODO: Maybe make this a method on ChorusRequest if we need it a lot
+        let response = chorus_request.send_request(self).await;
+        let response = self.record_endpoint_support(Capability::Harvest, response)?;
+        log::trace!("Got response: {:?}", response);
+
+        if response.status() == http::StatusCode::NO_CONTENT {
+            return Ok(None);
+        }
+
+        let response_text = match response.text().await {
+            Ok(string) => string,
+            Err(e) => {
+                return Err(ChorusError::InvalidResponse {
+                    error: format!(
+                        "Error while trying to process the HTTP response into a String: {}",
+                        e
+                    ),
+                });
+            }
+        };
+
+        let object = match serde_json::from_str::<Harvest>(&response_text) {
+            Ok(object) => object,
+            Err(e) => {
+                return Err(ChorusError::InvalidResponse {
+                    error: format!(
+                        "Error while trying to deserialize the JSON response into requested type T: {}. JSON Response: {}",
+                        e, response_text
+                    ),
+                })
+            }
+        };
+        Ok(Some(object))
+    }
+
+    /// Creates a personal data harvest request ([Harvest]) for the current user.
+    ///
+    /// # Notes
+    /// To fetch the latest existing harvest, see [Self::get_harvest].
+    ///
+    /// Invalid options in the backends array are ignored.
+    ///
+    /// If the array is empty (after ignoring), it requests all [HarvestBackendType]s.
+    ///
+    /// As of 2024/08/09, Spacebar does not yet implement this endpoint. (Or data harvesting)
+    /// See [Instance::supports](crate::instance::Instance::supports).
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/user#create-user-harvest>
+    pub async fn create_harvest(
+        &mut self,
+        backends: Vec<HarvestBackendType>,
+    ) -> ChorusResult<Harvest> {
+        self.check_endpoint_support(Capability::Harvest)?;
+
+        let schema = if backends.is_empty() {
+            CreateUserHarvestSchema { backends: None }
+        } else {
+            CreateUserHarvestSchema {
+                backends: Some(backends),
+            }
+        };
+
+        let request = Client::new()
+            .post(format!(
+                "{}/users/@me/harvest",
+                self.belongs_to.read().unwrap().urls.api,
+            ))
+            .header("Authorization", self.token())
+            .json(&schema);
+
+        let chorus_request = ChorusRequest {
+            request,
+            limit_type: LimitType::default(),
+        };
+
+        let result = chorus_request.deserialize_response(self).await;
+        self.record_endpoint_support(Capability::Harvest, result)
+    }
+
+    /// Polls the most recent [Harvest] of the current user until it has finished processing,
+    /// i.e. until it is [completed](crate::types::HarvestStatus::Completed) or has
+    /// [failed](crate::types::HarvestStatus::Failed).
+    ///
+    /// Fetches the harvest every `poll_interval`, see [Self::get_harvest]. Harvests can take
+    /// days to complete, so the interval should be generous.
+    ///
+    /// Returns [None] if the user has no harvest, e.g. because it was deleted while polling.
+    ///
+    /// # Notes
+    /// As of 2024/08/09, Spacebar does not yet implement this endpoint. (Or data harvesting)
+    /// See [Instance::supports](crate::instance::Instance::supports).
+    pub async fn wait_for_harvest(
+        &mut self,
+        poll_interval: Duration,
+    ) -> ChorusResult<Option<Harvest>> {
+        loop {
+            let harvest = self.get_harvest().await?;
+
+            match harvest {
+                Some(harvest) if !harvest.status.is_finished() => sleep(poll_interval).await,
+                _ => return Ok(harvest),
+            }
+        }
+    }
+
+    /// Deletes the current user's pending personal data harvest request ([Harvest]).
+    ///
+    /// # Notes
+    /// This endpoint is not documented for Discord.
+    ///
+    /// As of 2024/08/09, Spacebar does not yet implement this endpoint. (Or data harvesting)
+    /// See [Instance::supports](crate::instance::Instance::supports).
+    pub async fn delete_harvest(&mut self) -> ChorusResult<()> {
+        self.check_endpoint_support(Capability::Harvest)?;
+
+        let url = format!(
+            "{}/users/@me/harvest",
+            self.belongs_to.read().unwrap().urls.api
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::DELETE,
+            &url,
+            None,
+            None,
+            Some(self),
+            LimitType::default(),
+        );
+
+        let result = request.handle_request_as_result(self).await;
+        self.record_endpoint_support(Capability::Harvest, result)
+    }
+}
@@ -3,16 +3,34 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 #![allow(unused_imports)]
+#[cfg(feature = "api-channels")]
 pub use channels::*;
+#[cfg(feature = "api-users")]
 pub use connections::*;
+#[cfg(feature = "api-users")]
 pub use guilds::*;
+#[cfg(feature = "api-users")]
+pub use harvest::*;
+#[cfg(feature = "api-users")]
 pub use mfa::*;
+#[cfg(feature = "api-users")]
 pub use relationships::*;
+#[cfg(feature = "settings-proto")]
+pub use settings_proto::*;
 pub use users::*;
 
+#[cfg(feature = "api-channels")]
 pub mod channels;
+#[cfg(feature = "api-users")]
 pub mod connections;
+#[cfg(feature = "api-users")]
 pub mod guilds;
+#[cfg(feature = "api-users")]
+pub mod harvest;
+#[cfg(feature = "api-users")]
 pub mod mfa;
+#[cfg(feature = "api-users")]
 pub mod relationships;
+#[cfg(feature = "settings-proto")]
+pub mod settings_proto;
 pub mod users;
@@ -0,0 +1,92 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use serde_json::to_string;
+
+use crate::{
+    errors::ChorusResult,
+    instance::{Capability, ChorusUser},
+    ratelimiter::ChorusRequest,
+    types::{
+        LimitType, ModifyUserSettingsProtoSchema, UserSettingsProtoResponse, UserSettingsProtoType,
+    },
+};
+
+impl ChorusUser {
+    /// Fetches one of the current user's settings protos.
+    ///
+    /// The settings are returned as a base-64 encoded protobuf, which chorus does not decode.
+    ///
+    /// # Notes
+    /// As of 2024/08, Spacebar does not yet implement this endpoint.
+    /// See [Instance::supports](crate::instance::Instance::supports).
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/user-settings#get-user-settings-proto>
+    pub async fn get_settings_proto(
+        &mut self,
+        proto_type: UserSettingsProtoType,
+    ) -> ChorusResult<UserSettingsProtoResponse> {
+        self.check_endpoint_support(Capability::SettingsProto)?;
+
+        let url = format!(
+            "{}/users/@me/settings-proto/{}",
+            self.belongs_to.read().unwrap().urls.api,
+            proto_type
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            &url,
+            None,
+            None,
+            Some(self),
+            LimitType::Global,
+        );
+
+        let result = request
+            .deserialize_response::<UserSettingsProtoResponse>(self)
+            .await;
+        self.record_endpoint_support(Capability::SettingsProto, result)
+    }
+
+    /// Modifies one of the current user's settings protos.
+    ///
+    /// Returns the updated settings, and fires a
+    /// [UserSettingsProtoUpdate](crate::types::UserSettingsProtoUpdate) gateway event.
+    ///
+    /// # Notes
+    /// As of 2024/08, Spacebar does not yet implement this endpoint.
+    /// See [Instance::supports](crate::instance::Instance::supports).
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/user-settings#modify-user-settings-proto>
+    pub async fn modify_settings_proto(
+        &mut self,
+        proto_type: UserSettingsProtoType,
+        schema: ModifyUserSettingsProtoSchema,
+    ) -> ChorusResult<UserSettingsProtoResponse> {
+        self.check_endpoint_support(Capability::SettingsProto)?;
+
+        let url = format!(
+            "{}/users/@me/settings-proto/{}",
+            self.belongs_to.read().unwrap().urls.api,
+            proto_type
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::PATCH,
+            &url,
+            Some(to_string(&schema).unwrap()),
+            None,
+            Some(self),
+            LimitType::Global,
+        );
+
+        let result = request
+            .deserialize_response::<UserSettingsProtoResponse>(self)
+            .await;
+        self.record_endpoint_support(Capability::SettingsProto, result)
+    }
+}
@@ -11,17 +11,17 @@ use reqwest::Client;
 use serde_json::to_string;
 
 use crate::{
+    api::batch::{fetch_many, BatchResult, DEFAULT_BATCH_CONCURRENCY},
     errors::{ChorusError, ChorusResult},
-    instance::{ChorusUser, Instance},
+    instance::{Capability, ChorusUser, Instance},
     ratelimiter::ChorusRequest,
     types::{
-        AuthorizeConnectionSchema, BurstCreditsInfo, ConnectionType, CreateUserHarvestSchema,
-        DeleteDisableUserSchema, GetPomeloEligibilityReturn, GetPomeloSuggestionsReturn,
-        GetRecentMentionsSchema, GetUserProfileSchema, GuildAffinities, Harvest,
-        HarvestBackendType, LimitType, ModifyUserNoteSchema, PremiumUsage, PublicUser, Snowflake,
-        User, UserAffinities, UserModifyProfileSchema, UserModifySchema, UserNote, UserProfile,
-        UserProfileMetadata, UserSettings, VerifyUserEmailChangeResponse,
-        VerifyUserEmailChangeSchema,
+        AuthorizeConnectionSchema, BurstCreditsInfo, ConnectionType, DeleteDisableUserSchema,
+        GetPomeloEligibilityReturn, GetPomeloSuggestionsReturn, GetRecentMentionsSchema,
+        GetUserProfileSchema, GuildAffinities, LimitType, ModifyUserNoteSchema, PremiumUsage,
+        PublicUser, Snowflake, User, UserAffinities, UserModifyProfileSchema, UserModifySchema,
+        UserModifySettingsSchema, UserNote, UserProfile, UserProfileMetadata, UserSettings,
+        VerifyUserEmailChangeResponse, VerifyUserEmailChangeSchema,
     },
 };
 
@@ -51,6 +51,7 @@ impl ChorusUser {
     /// Gets a non-local user by their unique username.
     ///
     /// As of 2024/07/28, Spacebar does not yet implement this endpoint.
+    /// See [Instance::supports](crate::instance::Instance::supports).
     ///
     /// If fetching with a pomelo username, discriminator should be set to None.
     ///
@@ -85,6 +86,24 @@ impl ChorusUser {
         User::get_settings(self).await
     }
 
+    /// Modifies the current user's settings.
+    ///
+    /// Returns the updated settings, which are also stored in [ChorusUser::settings].
+    ///
+    /// # Notes
+    /// This function is a wrapper around [`User::modify_settings`].
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/user-settings#modify-user-settings>
+    pub async fn modify_settings(
+        &mut self,
+        schema: UserModifySettingsSchema,
+    ) -> ChorusResult<UserSettings> {
+        let settings = User::modify_settings(self, &schema).await?;
+        *self.settings.write().unwrap() = settings.clone();
+        Ok(settings)
+    }
+
     /// Modifies the current user's representation. (See [`User`])
     ///
     /// # Notes
@@ -248,6 +267,7 @@ impl ChorusUser {
     /// Should be the follow-up to [Self::initiate_email_change]
     ///
     /// As of 2024/08/08, Spacebar does not yet implement this endpoint.
+    /// See [Instance::supports](crate::instance::Instance::supports).
     // FIXME: Does this mean PUT users/@me/email is different?
     ///
     /// # Reference
@@ -256,6 +276,8 @@ impl ChorusUser {
         &mut self,
         schema: VerifyUserEmailChangeSchema,
     ) -> ChorusResult<VerifyUserEmailChangeResponse> {
+        self.check_endpoint_support(Capability::VerifyEmailChange)?;
+
         let request = Client::new()
             .post(format!(
                 "{}/users/@me/email/verify-code",
@@ -267,9 +289,10 @@ impl ChorusUser {
             request,
             limit_type: LimitType::default(),
         };
-        chorus_request
+        let result = chorus_request
             .deserialize_response::<VerifyUserEmailChangeResponse>(self)
-            .await
+            .await;
+        self.record_endpoint_support(Capability::VerifyEmailChange, result)
     }
 
     /// Returns a suggested unique username based on the current user's username.
@@ -280,13 +303,16 @@ impl ChorusUser {
     /// The user must be in the rollout to use this endpoint."
     ///
     /// If a user has already migrated, this endpoint will likely return a 401 Unauthorized
-    /// ([ChorusError::NoPermission])
+    /// ([ChorusError::Api] with status 401)
     ///
     /// As of 2024/08/08, Spacebar does not yet implement this endpoint.
+    /// See [Instance::supports](crate::instance::Instance::supports).
     ///
     /// # Reference
     /// See <https://docs.discord.sex/resources/user#get-pomelo-suggestions>
     pub async fn get_pomelo_suggestions(&mut self) -> ChorusResult<String> {
+        self.check_endpoint_support(Capability::PomeloSuggestions)?;
+
         let request = Client::new()
             .get(format!(
                 "{}/users/@me/pomelo-suggestions",
@@ -298,9 +324,10 @@ impl ChorusUser {
             request,
             limit_type: LimitType::default(),
         };
-        chorus_request
+        let result = chorus_request
             .deserialize_response::<GetPomeloSuggestionsReturn>(self)
-            .await
+            .await;
+        self.record_endpoint_support(Capability::PomeloSuggestions, result)
             .map(|returned| returned.username)
     }
 
@@ -310,10 +337,13 @@ impl ChorusUser {
     ///
     /// # Notes
     /// As of 2024/08/08, Spacebar does not yet implement this endpoint.
+    /// See [Instance::supports](crate::instance::Instance::supports).
     ///
     /// # Reference
     /// See <https://docs.discord.sex/resources/user#get-pomelo-eligibility>
     pub async fn get_pomelo_eligibility(&mut self, username: &String) -> ChorusResult<bool> {
+        self.check_endpoint_support(Capability::PomeloEligibility)?;
+
         let request = Client::new()
             .post(format!(
                 "{}/users/@me/pomelo-attempt",
@@ -328,9 +358,10 @@ impl ChorusUser {
             request,
             limit_type: LimitType::default(),
         };
-        chorus_request
+        let result = chorus_request
             .deserialize_response::<GetPomeloEligibilityReturn>(self)
-            .await
+            .await;
+        self.record_endpoint_support(Capability::PomeloEligibility, result)
             .map(|returned| !returned.taken)
     }
 
@@ -347,13 +378,16 @@ impl ChorusUser {
     /// The user must be in the rollout to use this endpoint."
     ///
     /// If a user has already migrated, this endpoint will likely return a 401 Unauthorized
-    /// ([ChorusError::NoPermission])
+    /// ([ChorusError::Api] with status 401)
     //
     /// As of 2024/08/08, Spacebar does not yet implement this endpoint.
+    /// See [Instance::supports](crate::instance::Instance::supports).
     ///
     /// # Reference
     /// See <https://docs.discord.sex/resources/user#create-pomelo-migration>
     pub async fn create_pomelo_migration(&mut self, username: &String) -> ChorusResult<()> {
+        self.check_endpoint_support(Capability::PomeloMigration)?;
+
         let request = Client::new()
             .post(format!(
                 "{}/users/@me/pomelo",
@@ -370,6 +404,7 @@ impl ChorusUser {
         };
 
         let result = chorus_request.deserialize_response::<User>(self).await;
+        let result = self.record_endpoint_support(Capability::PomeloMigration, result);
 
         // FIXME: Does UserUpdate do this automatically? or would a user need to manually observe ChorusUser::object
         if let Ok(new_object) = result {
@@ -385,6 +420,7 @@ impl ChorusUser {
     ///
     /// # Notes
     /// As of 2024/08/09, Spacebar does not yet implement this endpoint.
+    /// See [Instance::supports](crate::instance::Instance::supports).
     ///
     /// # Reference
     /// See <https://docs.discord.sex/resources/user#get-recent-mentions>
@@ -392,6 +428,8 @@ impl ChorusUser {
         &mut self,
         query_parameters: GetRecentMentionsSchema,
     ) -> ChorusResult<Vec<crate::types::Message>> {
+        self.check_endpoint_support(Capability::RecentMentions)?;
+
         let request = Client::new()
             .get(format!(
                 "{}/users/@me/mentions",
@@ -405,9 +443,10 @@ impl ChorusUser {
             limit_type: LimitType::default(),
         };
 
-        chorus_request
+        let result = chorus_request
             .deserialize_response::<Vec<crate::types::Message>>(self)
-            .await
+            .await;
+        self.record_endpoint_support(Capability::RecentMentions, result)
     }
 
     /// Acknowledges a message the current user has been mentioned in.
@@ -416,10 +455,13 @@ impl ChorusUser {
     ///
     /// # Notes
     /// As of 2024/08/09, Spacebar does not yet implement this endpoint.
+    /// See [Instance::supports](crate::instance::Instance::supports).
     ///
     /// # Reference
     /// See <https://docs.discord.sex/resources/user#delete-recent-mention>
     pub async fn delete_recent_mention(&mut self, message_id: Snowflake) -> ChorusResult<()> {
+        self.check_endpoint_support(Capability::RecentMentions)?;
+
         let request = Client::new()
             .delete(format!(
                 "{}/users/@me/mentions/{}",
@@ -433,115 +475,21 @@ impl ChorusUser {
             limit_type: LimitType::default(),
         };
 
-        chorus_request.handle_request_as_result(self).await
-    }
-
-    /// If it exists, returns the most recent [Harvest] (personal data harvest request).
-    ///
-    /// To create a new [Harvest], see [Self::create_harvest].
-    ///
-    /// # Notes
-    /// As of 2024/08/09, Spacebar does not yet implement this endpoint. (Or data harvesting)
-    ///
-    /// # Reference
-    /// See <https://docs.discord.sex/resources/user#get-user-harvest>
-    pub async fn get_harvest(&mut self) -> ChorusResult<Option<Harvest>> {
-        let request = Client::new()
-            .get(format!(
-                "{}/users/@me/harvest",
-                self.belongs_to.read().unwrap().urls.api,
-            ))
-            .header("Authorization", self.token());
-
-        let chorus_request = ChorusRequest {
-            request,
-            limit_type: LimitType::default(),
-        };
-
-        // Manual handling, because a 204 with no harvest is a success state
-        // TODO: Maybe make this a method on ChorusRequest if we need it a lot
-        let response = chorus_request.send_request(self).await?;
-        log::trace!("Got response: {:?}", response);
-
-        if response.status() == http::StatusCode::NO_CONTENT {
-            return Ok(None);
-        }
-
-        let response_text = match response.text().await {
-            Ok(string) => string,
-            Err(e) => {
-                return Err(ChorusError::InvalidResponse {
-                    error: format!(
-                        "Error while trying to process the HTTP response into a String: {}",
-                        e
-                    ),
-                });
-            }
-        };
-
-        let object = match serde_json::from_str::<Harvest>(&response_text) {
-            Ok(object) => object,
-            Err(e) => {
-                return Err(ChorusError::InvalidResponse {
-                    error: format!(
-                        "Error while trying to deserialize the JSON response into requested type T: {}. JSON Response: {}",
-                        e, response_text
-                    ),
-                })
-            }
-        };
-        Ok(Some(object))
-    }
-
-    /// Creates a personal data harvest request ([Harvest]) for the current user.
-    ///
-    /// # Notes
-    /// To fetch the latest existing harvest, see [Self::get_harvest].
-    ///
-    /// Invalid options in the backends array are ignored.
-    ///
-    /// If the array is empty (after ignoring), it requests all [HarvestBackendType]s.
-    ///
-    /// As of 2024/08/09, Spacebar does not yet implement this endpoint. (Or data harvesting)
-    ///
-    /// # Reference
-    /// See <https://docs.discord.sex/resources/user#create-user-harvest>
-    pub async fn create_harvest(
-        &mut self,
-        backends: Vec<HarvestBackendType>,
-    ) -> ChorusResult<Harvest> {
-        let schema = if backends.is_empty() {
-            CreateUserHarvestSchema { backends: None }
-        } else {
-            CreateUserHarvestSchema {
-                backends: Some(backends),
-            }
-        };
-
-        let request = Client::new()
-            .post(format!(
-                "{}/users/@me/harvest",
-                self.belongs_to.read().unwrap().urls.api,
-            ))
-            .header("Authorization", self.token())
-            .json(&schema);
-
-        let chorus_request = ChorusRequest {
-            request,
-            limit_type: LimitType::default(),
-        };
-
-        chorus_request.deserialize_response(self).await
+        let result = chorus_request.handle_request_as_result(self).await;
+        self.record_endpoint_support(Capability::RecentMentions, result)
     }
 
     /// Returns a mapping of user IDs ([Snowflake]s) to notes ([String]s) for the current user.
     ///
     /// # Notes
     /// As of 2024/08/21, Spacebar does not yet implement this endpoint.
+    /// See [Instance::supports](crate::instance::Instance::supports).
     ///
     /// # Reference
     /// See <https://docs.discord.sex/resources/user#get-user-notes>
     pub async fn get_user_notes(&mut self) -> ChorusResult<HashMap<Snowflake, String>> {
+        self.check_endpoint_support(Capability::UserNotes)?;
+
         let request = Client::new()
             .get(format!(
                 "{}/users/@me/notes",
@@ -554,7 +502,8 @@ impl ChorusUser {
             limit_type: LimitType::default(),
         };
 
-        chorus_request.deserialize_response(self).await
+        let result = chorus_request.deserialize_response(self).await;
+        self.record_endpoint_support(Capability::UserNotes, result)
     }
 
     /// Fetches the note ([UserNote]) for the given user.
@@ -637,10 +586,13 @@ impl ChorusUser {
     ///
     /// # Notes
     /// As of 2024/08/16, Spacebar does not yet implement this endpoint.
+    /// See [Instance::supports](crate::instance::Instance::supports).
     ///
     /// # Reference
     /// See <https://docs.discord.sex/resources/user#get-user-premium-usage>
     pub async fn get_premium_usage(&mut self) -> ChorusResult<PremiumUsage> {
+        self.check_endpoint_support(Capability::PremiumUsage)?;
+
         let request = Client::new()
             .get(format!(
                 "{}/users/@me/premium-usage",
@@ -653,7 +605,8 @@ impl ChorusUser {
             limit_type: LimitType::default(),
         };
 
-        chorus_request.deserialize_response(self).await
+        let result = chorus_request.deserialize_response(self).await;
+        self.record_endpoint_support(Capability::PremiumUsage, result)
     }
 
     /// Fetches info about the current user's burst credits
@@ -663,7 +616,10 @@ impl ChorusUser {
     ///
     /// # Notes
     /// As of 2024/08/18, Spacebar does not yet implement this endpoint.
+    /// See [Instance::supports](crate::instance::Instance::supports).
     pub async fn get_burst_credits(&mut self) -> ChorusResult<BurstCreditsInfo> {
+        self.check_endpoint_support(Capability::BurstCredits)?;
+
         let request = Client::new()
             .get(format!(
                 "{}/users/@me/burst-credits",
@@ -676,7 +632,8 @@ impl ChorusUser {
             limit_type: LimitType::default(),
         };
 
-        chorus_request.deserialize_response(self).await
+        let result = chorus_request.deserialize_response(self).await;
+        self.record_endpoint_support(Capability::BurstCredits, result)
     }
 }
 
@@ -717,9 +674,42 @@ impl User {
             .await
     }
 
+    /// Gets many non-local users by their ids, sending up to [DEFAULT_BATCH_CONCURRENCY]
+    /// requests at the same time.
+    ///
+    /// Users who could not be fetched are returned in [BatchResult::errors].
+    ///
+    /// See [User::get_many_with_concurrency] to configure the concurrency.
+    pub async fn get_many(user: &mut ChorusUser, ids: &[Snowflake]) -> BatchResult<PublicUser> {
+        User::get_many_with_concurrency(user, ids, DEFAULT_BATCH_CONCURRENCY).await
+    }
+
+    /// Gets many non-local users by their ids, sending up to `concurrency` requests at the same
+    /// time.
+    ///
+    /// Requests wait for the rate limit buckets they would exhaust to be available.
+    pub async fn get_many_with_concurrency(
+        user: &mut ChorusUser,
+        ids: &[Snowflake],
+        concurrency: usize,
+    ) -> BatchResult<PublicUser> {
+        fetch_many(
+            user,
+            ids,
+            concurrency,
+            |_| LimitType::Global,
+            |mut user, id| async move {
+                let result = User::get(&mut user, id).await;
+                (user, result)
+            },
+        )
+        .await
+    }
+
     /// Gets a user by their unique username.
     ///
     /// As of 2024/07/28, Spacebar does not yet implement this endpoint.
+    /// See [Instance::supports](crate::instance::Instance::supports).
     ///
     /// If fetching with a pomelo username, discriminator should be set to None.
     ///
@@ -740,6 +730,8 @@ impl User {
         username: &String,
         discriminator: Option<&String>,
     ) -> ChorusResult<PublicUser> {
+        user.check_endpoint_support(Capability::GetUserByUsername)?;
+
         let url_api = user.belongs_to.read().unwrap().urls.api.clone();
         let url = format!("{}/users/username/{username}", url_api);
         let mut request = reqwest::Client::new()
@@ -754,9 +746,10 @@ impl User {
             request,
             limit_type: LimitType::Global,
         };
-        chorus_request
+        let result = chorus_request
             .deserialize_response::<PublicUser>(user)
-            .await
+            .await;
+        user.record_endpoint_support(Capability::GetUserByUsername, result)
     }
 
     /// Gets the current user's settings.
@@ -777,6 +770,33 @@ impl User {
             .await
     }
 
+    /// Modifies the current user's settings.
+    ///
+    /// Returns the updated settings.
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/resources/user-settings#modify-user-settings>
+    pub async fn modify_settings(
+        user: &mut ChorusUser,
+        schema: &UserModifySettingsSchema,
+    ) -> ChorusResult<UserSettings> {
+        let url = format!(
+            "{}/users/@me/settings",
+            user.belongs_to.read().unwrap().urls.api
+        );
+
+        let request = ChorusRequest::new(
+            http::Method::PATCH,
+            &url,
+            Some(to_string(schema).unwrap()),
+            None,
+            Some(user),
+            LimitType::Global,
+        );
+
+        request.deserialize_response::<UserSettings>(user).await
+    }
+
     /// Gets a user's profile object by their id.
     ///
     /// This endpoint requires one of the following:
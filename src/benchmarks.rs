@@ -0,0 +1,41 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Crate internals, exposed so they can be measured by the benches in `benches/`.
+//!
+//! Only available with the `benchmarks` feature. Nothing in here is part of the public api,
+//! and it may change or disappear in any release.
+
+use std::collections::HashMap;
+
+use serde::de::DeserializeOwned;
+use serde_json::value::RawValue;
+
+use crate::gateway::PartialUpdate;
+use crate::instance::ChorusUser;
+use crate::ratelimiter::ChorusRequest;
+use crate::types::types::subconfigs::limits::rates::RateLimits;
+use crate::types::{Limit, LimitType, Shared};
+
+/// Parses an update event and merges its fields into an observed object, like the gateway does
+/// for events which only contain the updated entity.
+pub fn update_object<T: PartialUpdate + DeserializeOwned>(json: &RawValue, object: Shared<T>) {
+    let (update, fields) = crate::types::from_str_with_fields(json.get()).unwrap();
+    crate::types::update_object(object, update, &fields)
+}
+
+/// Checks whether a request in the bucket of `limit_type` may be sent right now.
+pub fn can_send_request(user: &mut ChorusUser, limit_type: &LimitType) -> bool {
+    ChorusRequest::can_send_request(user, limit_type)
+}
+
+/// Counts a sent request against the buckets relevant for `limit_type`.
+pub fn update_rate_limits(user: &mut ChorusUser, limit_type: &LimitType, response_was_err: bool) {
+    ChorusRequest::update_rate_limits(user, limit_type, response_was_err)
+}
+
+/// Builds the tracked rate limits for an instance's rate limit configuration.
+pub fn limits_config_to_hashmap(configuration: &RateLimits) -> HashMap<LimitType, Limit> {
+    ChorusRequest::limits_config_to_hashmap(configuration)
+}
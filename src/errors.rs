@@ -5,7 +5,13 @@
 //! Contains all the errors that can be returned by the library.
 use custom_error::custom_error;
 
-use crate::types::{CloseCode, MfaRequiredSchema, VoiceCloseCode, WebSocketEvent};
+use serde::Deserialize;
+
+use crate::types::{
+    CloseCode, JsonErrorCode, MfaRequiredSchema, PermissionFlags, SessionRecovery, VoiceCloseCode,
+    WebSocketEvent,
+};
+use crate::instance::{Capability, InstanceSoftware};
 use chorus_macros::WebSocketEvent;
 
 custom_error! {
@@ -23,6 +29,10 @@ custom_error! {
     NoResponse = "Did not receive a response from the Server.",
     /// Reqwest returned an Error instead of a Response object.
     RequestFailed{url:String, error: String} = "An error occurred while trying to GET from {url}: {error}",
+    /// The server responded with a JSON error, which tells what went wrong.
+    ///
+    /// Match on [ApiError::code] to handle specific failures.
+    Api{error: ApiError} = "{error}",
     /// Response received, however, it was not of the successful responses type. Used when no other, special case applies.
     ReceivedErrorCode{error_code: u16, error: String} = "Received the following error code while requesting from the route: {error_code}",
     /// Used when there is likely something wrong with the instance, the request was directed to.
@@ -39,6 +49,18 @@ custom_error! {
     TokenExpired = "Token expired, invalid or not found.",
     /// No permission
     NoPermission = "You do not have the permissions needed to perform this action.",
+    /// The server refused an action which requires a specific permission.
+    ///
+    /// `permission` is the required permission, such as [PermissionFlags::MODERATE_MEMBERS], and
+    /// `error` the original error, usually a [ChorusError::Api] (see [ChorusError::api_error]).
+    /// Note that this is also returned if the user has the permission, but the action is not
+    /// allowed for other reasons, such as the role hierarchy.
+    MissingPermission{permission: PermissionFlags, error: Box<ChorusError>} = @{
+        format!(
+            "Missing the {} permission needed to perform this action",
+            permission.iter_names().map(|(name, _)| name).collect::<Vec<_>>().join(" | ")
+        )
+    },
     /// Resource not found
     NotFound{error: String} = "The provided resource hasn't been found: {error}",
     /// Used when you, for example, try to change your spacebar account password without providing your old password for verification.
@@ -55,7 +77,217 @@ custom_error! {
      /// After verifying, the same request can be retried.
     MfaRequired {error: MfaRequiredSchema} = "Mfa verification is required to perform this action",
     /// The user's account is suspended
-    SuspendUser { token: String }  = "Your account has been suspended"
+    SuspendUser { token: String }  = "Your account has been suspended",
+    /// A request could not be signed, or the signature of a response could not be verified.
+    InvalidSignature{error: String} = "Signing or verifying a signature failed: {error}",
+    /// The action requires a gateway connection, but the user was created in REST-only mode.
+    ///
+    /// See [crate::instance::Instance::set_rest_only] and [crate::instance::ChorusUser::connect_gateway].
+    NoGateway = "This action requires a gateway connection, but the user does not have one",
+    /// Connecting to the gateway failed.
+    Gateway{error: GatewayError} = "Could not connect to the gateway: {error}",
+    /// The instance is known not to implement the endpoint, either because of its software or
+    /// because it answered an earlier request to it with a 404, so the request was not sent.
+    ///
+    /// If the instance has been updated to support it, see
+    /// [Instance::set_supports](crate::instance::Instance::set_supports).
+    UnsupportedByInstance{capability: Capability, software: InstanceSoftware} = "The instance software ({software}) does not support this endpoint ({capability})",
+    /// Loading or storing tokens in a [TokenStore](crate::token_store::TokenStore) failed.
+    TokenStore{error: String} = "Could not access the token store: {error}",
+    /// The request would exceed one of the instance's limits, so it was not sent.
+    ///
+    /// See `Instance::limits_configuration`.
+    LimitExceeded{limit: String, max: u64, actual: u64} = "The {limit} ({actual}) exceeds the instance's limit of {max}",
+    /// A url could not be parsed, or uses a scheme which can't be used for its purpose, such as
+    /// an `http://` gateway url.
+    InvalidUrl{url: String, error: String} = "Invalid url {url}: {error}"
+}
+
+impl ChorusError {
+    /// Returns the error the server responded with, if the error is a [ChorusError::Api] or a
+    /// [ChorusError::MissingPermission] caused by one.
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            ChorusError::Api { error } => Some(error),
+            ChorusError::MissingPermission { error, .. } => error.api_error(),
+            _ => None,
+        }
+    }
+
+    /// Turns the server refusing an action because of missing permissions into a
+    /// [ChorusError::MissingPermission] naming `permission`, the permission the action requires.
+    ///
+    /// Other errors are returned unchanged.
+    pub(crate) fn with_required_permission(self, permission: PermissionFlags) -> ChorusError {
+        let missing_permission = match &self {
+            ChorusError::NoPermission => true,
+            ChorusError::Api { error } => error.code == JsonErrorCode::MissingPermissions,
+            _ => false,
+        };
+
+        if !missing_permission {
+            return self;
+        }
+
+        ChorusError::MissingPermission {
+            permission,
+            error: Box::new(self),
+        }
+    }
+
+    /// Returns whether the error means the server does not know the requested route at all, as
+    /// opposed to it not knowing the requested entity.
+    ///
+    /// Missing entities are reported with a specific [JsonErrorCode] (such as
+    /// [JsonErrorCode::UnknownChannel]), while unknown routes are answered with a 404 without
+    /// one, or without a JSON body.
+    pub fn is_unknown_route(&self) -> bool {
+        match self {
+            ChorusError::NotFound { .. } => true,
+            ChorusError::Api { error } => {
+                error.status == 404 && error.code == JsonErrorCode::General
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+/// An error returned by the HTTP API, parsed from the response's JSON body.
+///
+/// # Example
+/// ```no_run
+/// # use chorus::errors::ChorusError;
+/// # use chorus::types::{Channel, JsonErrorCode, Snowflake};
+/// # async fn example(user: &mut chorus::instance::ChorusUser, channel_id: Snowflake) {
+/// match Channel::get(user, channel_id).await {
+///     Ok(channel) => println!("Found {:?}", channel.name),
+///     Err(ChorusError::Api { error }) if error.code == JsonErrorCode::UnknownChannel => {
+///         println!("The channel does not exist")
+///     }
+///     Err(error) => println!("Something else went wrong: {error}"),
+/// }
+/// # }
+/// ```
+///
+/// # Reference
+/// See <https://docs.discord.sex/topics/opcodes-and-status-codes#json-error-codes>
+pub struct ApiError {
+    /// The HTTP status code of the response
+    pub status: u16,
+    pub code: JsonErrorCode,
+    /// A human readable description of the error
+    pub message: String,
+    /// Why individual fields of the request were invalid, for example with
+    /// [JsonErrorCode::InvalidFormBody]
+    pub field_errors: Vec<FieldError>,
+    /// The `X-RateLimit-Scope` header of the response, if the server sent one
+    pub ratelimit_scope: Option<String>,
+    /// The raw response body, for fields of specific endpoints which are not parsed into the
+    /// other fields
+    pub body: String,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+/// Why a single field of a request was invalid.
+pub struct FieldError {
+    /// The path to the field, with the keys of nested objects and indices of arrays joined
+    /// by dots, such as `embeds.0.title`
+    pub path: String,
+    /// A machine readable code, such as `BASE_TYPE_MAX_LENGTH`
+    pub code: String,
+    /// A human readable description of what is wrong with the field
+    pub message: String,
+}
+
+#[derive(Deserialize)]
+struct ApiErrorBody {
+    code: u32,
+    message: String,
+    #[serde(default)]
+    errors: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct FieldErrorBody {
+    #[serde(default)]
+    code: String,
+    message: String,
+}
+
+impl ApiError {
+    /// Parses the body of an error response.
+    ///
+    /// Returns [None] if the body is not a JSON error.
+    pub fn from_body(status: u16, body: &str) -> Option<ApiError> {
+        let parsed = serde_json::from_str::<ApiErrorBody>(body).ok()?;
+
+        let mut field_errors = Vec::new();
+        if let Some(errors) = parsed.errors {
+            collect_field_errors(String::new(), errors, &mut field_errors);
+        }
+
+        Some(ApiError {
+            status,
+            code: parsed.code.into(),
+            message: parsed.message,
+            field_errors,
+            ratelimit_scope: None,
+            body: body.to_string(),
+        })
+    }
+}
+
+/// Flattens the nested `errors` object of an error response into a list of field errors.
+///
+/// Supports both `{"field": {"_errors": [..]}}` and the shorter `{"field": {"code", "message"}}`.
+fn collect_field_errors(path: String, value: serde_json::Value, errors: &mut Vec<FieldError>) {
+    let serde_json::Value::Object(mut map) = value else {
+        return;
+    };
+
+    if let Some(field_errors) = map.remove("_errors") {
+        let field_errors =
+            serde_json::from_value::<Vec<FieldErrorBody>>(field_errors).unwrap_or_default();
+        errors.extend(field_errors.into_iter().map(|error| FieldError {
+            path: path.clone(),
+            code: error.code,
+            message: error.message,
+        }));
+    } else if map.get("message").is_some_and(serde_json::Value::is_string) {
+        if let Ok(error) = serde_json::from_value::<FieldErrorBody>(map.into()) {
+            errors.push(FieldError {
+                path,
+                code: error.code,
+                message: error.message,
+            });
+        }
+        return;
+    }
+
+    for (key, value) in map {
+        let path = match path.is_empty() {
+            true => key,
+            false => format!("{}.{}", path, key),
+        };
+        collect_field_errors(path, value, errors);
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The server responded with {} (code {}, status {})",
+            self.message,
+            u32::from(self.code),
+            self.status
+        )?;
+        for error in self.field_errors.iter() {
+            write!(f, "; {}: {}", error.path, error.message)?;
+        }
+        Ok(())
+    }
 }
 
 impl From<reqwest::Error> for ChorusError {
@@ -70,6 +302,43 @@ impl From<reqwest::Error> for ChorusError {
     }
 }
 
+custom_error! {
+    /// Errors when building an [Embed](crate::types::Embed) which exceeds the embed limits, see
+    /// [EmbedBuilder](crate::types::EmbedBuilder).
+    #[derive(Clone, Hash, PartialEq, Eq)]
+    pub EmbedError
+    /// A single text field of the embed is too long.
+    TooLong{field: String, length: usize, max: usize} = "The embed {field} is {length} characters long, but may be at most {max} characters long",
+    /// The embed has too many fields.
+    TooManyFields{count: usize, max: usize} = "The embed has {count} fields, but may have at most {max}",
+    /// All text in the embed combined is too long.
+    TotalTooLong{length: usize, max: usize} = "The embed contains {length} characters in total, but may contain at most {max}",
+}
+
+custom_error! {
+    /// Errors when talking to a locally running client over RPC, see
+    /// [RpcClient](crate::rpc::RpcClient).
+    #[derive(Clone, Hash, PartialEq, Eq)]
+    pub RpcError
+    /// No client is listening on any of the IPC sockets.
+    NoClient = "Could not find a locally running client to connect to",
+    Io{error: String} = "Could not read from / write to the IPC socket: {error}",
+    /// The client sent a payload we could not process.
+    InvalidPayload{error: String} = "Received an invalid RPC payload: {error}",
+    /// The client closed the connection, e.g. because it rejected the handshake.
+    Closed{code: i64, message: String} = "The client closed the RPC connection ({code}): {message}",
+    /// The client responded to a command with an error.
+    Command{code: i64, message: String} = "The client rejected the RPC command ({code}): {message}",
+}
+
+impl From<std::io::Error> for RpcError {
+    fn from(error: std::io::Error) -> Self {
+        RpcError::Io {
+            error: error.to_string(),
+        }
+    }
+}
+
 custom_error! {
     #[derive(PartialEq, Eq)]
     pub ObserverError
@@ -82,7 +351,7 @@ custom_error! {
     /// Supposed to be sent as numbers, though they are sent as string most of the time?
     ///
     /// Also includes errors when initiating a connection and unexpected opcodes
-    #[derive(PartialEq, Eq, Default, Clone, WebSocketEvent)]
+    #[derive(PartialEq, Eq, Default, Clone, Hash, WebSocketEvent)]
     pub GatewayError
     // Errors we have received from the gateway
     #[default]
@@ -92,6 +361,7 @@ custom_error! {
     NotAuthenticated = "You sent a payload prior to identifying",
     AuthenticationFailed = "The account token sent with your identify payload is invalid",
     AlreadyAuthenticated = "You've already identified, no need to reauthenticate",
+    SessionNoLongerValid = "Your session is no longer valid. Reconnect and start a new one",
     InvalidSequenceNumber = "The sequence number sent when resuming the session was invalid. Reconnect and start a new session",
     RateLimited = "You are being rate limited!",
     SessionTimedOut = "Your session timed out. Reconnect and start a new one",
@@ -107,6 +377,13 @@ custom_error! {
 
     // Other misc errors
     UnexpectedOpcodeReceived{opcode: u8} = "Received an opcode we weren't expecting to receive: {opcode}",
+    NoResponse = "The gateway did not respond to our request in time",
+    /// The connection died without being closed, see
+    /// [GatewayKeepalive](crate::gateway::GatewayKeepalive)
+    ConnectionLost = "The gateway did not respond to a ping in time, the connection was lost",
+    /// A payload we received could not be decompressed or decoded, so it was skipped
+    InvalidPayload{error: String} = "Received a payload which could not be decoded: {error}",
+    PayloadTooLarge{size: usize, limit: usize} = "The payload is too large to send to the gateway ({size} bytes, the limit is {limit} bytes)",
 }
 
 impl From<CloseCode> for GatewayError {
@@ -125,7 +402,7 @@ impl From<CloseCode> for GatewayError {
             // deprecated, it
             // should never actually
             // be received anymore
-            CloseCode::SessionNoLongerValid => GatewayError::SessionTimedOut,
+            CloseCode::SessionNoLongerValid => GatewayError::SessionNoLongerValid,
             CloseCode::InvalidShard => GatewayError::InvalidShard,
             CloseCode::ShardingRequired => GatewayError::ShardingRequired,
             CloseCode::InvalidApiVersion => GatewayError::InvalidAPIVersion,
@@ -135,6 +412,48 @@ impl From<CloseCode> for GatewayError {
     }
 }
 
+impl GatewayError {
+    /// Returns the close code the gateway closed the connection with, if the error is one
+    pub fn close_code(&self) -> Option<CloseCode> {
+        match self {
+            GatewayError::Unknown => Some(CloseCode::UnknownError),
+            GatewayError::UnknownOpcode => Some(CloseCode::UnknownOpcode),
+            GatewayError::Decode => Some(CloseCode::DecodeError),
+            GatewayError::NotAuthenticated => Some(CloseCode::NotAuthenticated),
+            GatewayError::AuthenticationFailed => Some(CloseCode::AuthenticationFailed),
+            GatewayError::AlreadyAuthenticated => Some(CloseCode::AlreadyAuthenticated),
+            GatewayError::SessionNoLongerValid => Some(CloseCode::SessionNoLongerValid),
+            GatewayError::InvalidSequenceNumber => Some(CloseCode::InvalidSeq),
+            GatewayError::RateLimited => Some(CloseCode::RateLimited),
+            GatewayError::SessionTimedOut => Some(CloseCode::SessionTimeout),
+            GatewayError::InvalidShard => Some(CloseCode::InvalidShard),
+            GatewayError::ShardingRequired => Some(CloseCode::ShardingRequired),
+            GatewayError::InvalidAPIVersion => Some(CloseCode::InvalidApiVersion),
+            GatewayError::InvalidIntents => Some(CloseCode::InvalidIntents),
+            GatewayError::DisallowedIntents => Some(CloseCode::DisallowedIntents),
+            _ => None,
+        }
+    }
+
+    /// Returns how to recover from the error, see [CloseCode::recovery].
+    ///
+    /// Connecting may be retried for errors which are not close codes, except for
+    /// [GatewayError::NonHelloOnInitiate], which means the url is not a gateway.
+    pub fn recovery(&self) -> SessionRecovery {
+        match self {
+            GatewayError::NonHelloOnInitiate { .. } => SessionRecovery::Abort,
+            error => error
+                .close_code()
+                .map_or(SessionRecovery::Resume, |code| code.recovery()),
+        }
+    }
+
+    /// Whether the session can be resumed after the error, see [GatewayError::recovery]
+    pub fn is_resumable(&self) -> bool {
+        self.recovery() == SessionRecovery::Resume
+    }
+}
+
 custom_error! {
     /// Voice Gateway errors
     ///
@@ -185,6 +504,31 @@ impl From<VoiceCloseCode> for VoiceGatewayError {
     }
 }
 
+custom_error! {
+    /// Errors when joining a voice channel with
+    /// [ChorusUser::join_voice](crate::instance::ChorusUser::join_voice).
+    #[derive(Clone, PartialEq, Eq)]
+    pub VoiceConnectionError
+    Timeout{waiting_for: String} = "Timed out while waiting for {waiting_for}",
+    NoEndpoint = "The server did not send a voice server endpoint, so there is no voice server to connect to",
+    NoGateway = "The user has no gateway connection to update its voice state with",
+    NoSupportedEncryptionMode{modes: String} = "The voice server does not support any encryption mode chorus implements (supported by the server: {modes})",
+    Gateway{error: VoiceGatewayError} = "Voice gateway error: {error}",
+    Udp{error: VoiceUdpError} = "Voice UDP error: {error}",
+}
+
+impl From<VoiceGatewayError> for VoiceConnectionError {
+    fn from(error: VoiceGatewayError) -> Self {
+        VoiceConnectionError::Gateway { error }
+    }
+}
+
+impl From<VoiceUdpError> for VoiceConnectionError {
+    fn from(error: VoiceUdpError) -> Self {
+        VoiceConnectionError::Udp { error }
+    }
+}
+
 custom_error! {
     /// Voice UDP errors.
     #[derive(Clone, PartialEq, Eq, WebSocketEvent)]
@@ -193,6 +537,7 @@ custom_error! {
     // General errors
     BrokenSocket{error: String} = "Could not write / read from UDP socket: {error}",
     NoData = "We have not set received the necessary data to perform this operation.",
+    InvalidIpDiscovery{address: String} = "Received an invalid address from IP discovery: {address}",
 
     // Encryption errors
     EncryptionModeNotImplemented{encryption_mode: String} = "Voice encryption mode {encryption_mode} is not yet implemented.",
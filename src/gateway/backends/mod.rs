@@ -2,26 +2,28 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-#[cfg(all(not(target_arch = "wasm32"), feature = "client"))]
+#[cfg(all(not(target_arch = "wasm32"), feature = "client-core"))]
 pub mod tungstenite;
-#[cfg(all(not(target_arch = "wasm32"), feature = "client"))]
+#[cfg(all(not(target_arch = "wasm32"), feature = "client-core"))]
 pub use tungstenite::*;
 
-#[cfg(all(target_arch = "wasm32", feature = "client"))]
+#[cfg(all(target_arch = "wasm32", feature = "client-core"))]
 pub mod wasm;
-#[cfg(all(target_arch = "wasm32", feature = "client"))]
+#[cfg(all(target_arch = "wasm32", feature = "client-core"))]
 pub use wasm::*;
 
-#[cfg(all(not(target_arch = "wasm32"), feature = "client"))]
+#[cfg(all(not(target_arch = "wasm32"), feature = "client-core"))]
 pub type Sink = tungstenite::TungsteniteSink;
-#[cfg(all(not(target_arch = "wasm32"), feature = "client"))]
+#[cfg(all(not(target_arch = "wasm32"), feature = "client-core"))]
 pub type Stream = tungstenite::TungsteniteStream;
-#[cfg(all(not(target_arch = "wasm32"), feature = "client"))]
+#[cfg(all(not(target_arch = "wasm32"), feature = "client-core"))]
 pub type WebSocketBackend = tungstenite::TungsteniteBackend;
+#[cfg(all(not(target_arch = "wasm32"), feature = "client-core"))]
+pub type WebSocketConnector = tungstenite::TungsteniteConnector;
 
-#[cfg(all(target_arch = "wasm32", feature = "client"))]
+#[cfg(all(target_arch = "wasm32", feature = "client-core"))]
 pub type Sink = wasm::WasmSink;
-#[cfg(all(target_arch = "wasm32", feature = "client"))]
+#[cfg(all(target_arch = "wasm32", feature = "client-core"))]
 pub type Stream = wasm::WasmStream;
-#[cfg(all(target_arch = "wasm32", feature = "client"))]
+#[cfg(all(target_arch = "wasm32", feature = "client-core"))]
 pub type WebSocketBackend = wasm::WasmBackend;
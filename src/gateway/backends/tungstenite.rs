@@ -2,16 +2,19 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::fmt::Debug;
+use std::sync::Arc;
+
 use custom_error::custom_error;
 use futures_util::{
     stream::{SplitSink, SplitStream},
-    StreamExt,
+    SinkExt, StreamExt,
 };
 use tokio::net::TcpStream;
+pub use tokio_tungstenite::Connector;
 use tokio_tungstenite::{
-    connect_async_tls_with_config, connect_async_with_config,
-    tungstenite::self,
-    Connector, MaybeTlsStream, WebSocketStream,
+    client_async_tls_with_config, connect_async_tls_with_config, connect_async_with_config,
+    tungstenite, MaybeTlsStream, WebSocketStream,
 };
 use url::Url;
 
@@ -32,67 +35,184 @@ custom_error! {
     pub TungsteniteBackendError
     FailedToLoadCerts{error: std::io::Error} = "failed to load platform native certs: {error}",
     TungsteniteError{error: tungstenite::error::Error} = "encountered a tungstenite error: {error}",
+    TcpConnectorError{error: std::io::Error} = "the custom tcp connector failed to connect: {error}",
+}
+
+/// Opens the TCP connection a websocket is established over.
+///
+/// Implement this to route gateway connections through a SOCKS5 or HTTP proxy; the returned
+/// stream should already be tunneled to the requested host, so that the websocket (and, for
+/// `wss://` urls, TLS) handshake can be performed over it.
+#[async_trait::async_trait]
+pub trait TcpConnector: Send + Sync + Debug {
+    async fn connect(&self, host: &str, port: u16) -> std::io::Result<TcpStream>;
+}
+
+#[derive(Clone, Default)]
+/// Customizes how the tungstenite backend connects to a websocket.
+///
+/// The default uses a direct TCP connection and rustls with the webpki root certificates.
+pub struct TungsteniteConnector {
+    /// Opens the TCP connection, e.g. through a proxy.
+    ///
+    /// If `None`, connects directly.
+    pub tcp: Option<Arc<dyn TcpConnector>>,
+    /// The TLS connector used for `wss://` urls.
+    ///
+    /// If `None`, rustls with the webpki root certificates is used.
+    pub tls: Option<Connector>,
+}
+
+impl Debug for TungsteniteConnector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TungsteniteConnector")
+            .field("tcp", &self.tcp)
+            .field("tls", &self.tls.as_ref().map(|_| "Connector"))
+            .finish()
+    }
 }
 
 impl TungsteniteBackend {
     pub async fn connect(
         websocket_url: &str,
     ) -> Result<(TungsteniteSink, TungsteniteStream), TungsteniteBackendError> {
-        let websocket_url_parsed =
-            Url::parse(websocket_url).map_err(|_| TungsteniteBackendError::TungsteniteError {
-                error: tungstenite::error::Error::Url(
-                    tungstenite::error::UrlError::UnsupportedUrlScheme,
-                ),
-            })?;
-        if websocket_url_parsed.scheme() == "ws" {
-            let (websocket_stream, _) =
-                match connect_async_with_config(websocket_url, None, false).await {
-                    Ok(websocket_stream) => websocket_stream,
-                    Err(e) => return Err(TungsteniteBackendError::TungsteniteError { error: e }),
-                };
-
-            Ok(websocket_stream.split())
-        } else if websocket_url_parsed.scheme() == "wss" {
-            let certs = webpki_roots::TLS_SERVER_ROOTS;
-            let roots = rustls::RootCertStore {
-                roots: certs
-                    .iter()
-                    .map(|cert| {
-                        rustls::OwnedTrustAnchor::from_subject_spki_name_constraints(
-                            cert.subject.to_vec(),
-                            cert.subject_public_key_info.to_vec(),
-                            cert.name_constraints.as_ref().map(|der| der.to_vec()),
-                        )
-                    })
-                    .collect(),
-            };
-            let (websocket_stream, _) = match connect_async_tls_with_config(
-                websocket_url,
-                None,
-                false,
-                Some(Connector::Rustls(
-                    rustls::ClientConfig::builder()
-                        .with_safe_defaults()
-                        .with_root_certificates(roots)
-                        .with_no_client_auth()
-                        .into(),
-                )),
-            )
-            .await
-            {
-                Ok(websocket_stream) => websocket_stream,
-                Err(e) => return Err(TungsteniteBackendError::TungsteniteError { error: e }),
-            };
-
-            Ok(websocket_stream.split())
-        } else {
-            Err(TungsteniteBackendError::TungsteniteError {
-                error: tungstenite::error::Error::Url(
-                    tungstenite::error::UrlError::UnsupportedUrlScheme,
-                ),
-            })
+        TungsteniteBackend::connect_with(websocket_url, &TungsteniteConnector::default()).await
+    }
+
+    /// Connects to a websocket using a custom [TungsteniteConnector].
+    pub async fn connect_with(
+        websocket_url: &str,
+        connector: &TungsteniteConnector,
+    ) -> Result<(TungsteniteSink, TungsteniteStream), TungsteniteBackendError> {
+        let unsupported_scheme = || TungsteniteBackendError::TungsteniteError {
+            error: tungstenite::error::Error::Url(
+                tungstenite::error::UrlError::UnsupportedUrlScheme,
+            ),
+        };
+
+        let websocket_url_parsed = Url::parse(websocket_url).map_err(|_| unsupported_scheme())?;
+
+        let tls = match websocket_url_parsed.scheme() {
+            "ws" => Connector::Plain,
+            "wss" => connector
+                .tls
+                .clone()
+                .unwrap_or_else(TungsteniteBackend::default_tls_connector),
+            _ => return Err(unsupported_scheme()),
+        };
+
+        let result = match &connector.tcp {
+            Some(tcp) => {
+                let host = websocket_url_parsed.host_str().ok_or_else(|| {
+                    TungsteniteBackendError::TungsteniteError {
+                        error: tungstenite::error::Error::Url(
+                            tungstenite::error::UrlError::NoHostName,
+                        ),
+                    }
+                })?;
+                // Always some for ws and wss
+                let port = websocket_url_parsed.port_or_known_default().unwrap_or(443);
+
+                let stream = tcp
+                    .connect(host, port)
+                    .await
+                    .map_err(|e| TungsteniteBackendError::TcpConnectorError { error: e })?;
+
+                client_async_tls_with_config(websocket_url, stream, None, Some(tls)).await
+            }
+            None => match tls {
+                Connector::Plain => connect_async_with_config(websocket_url, None, false).await,
+                tls => connect_async_tls_with_config(websocket_url, None, false, Some(tls)).await,
+            },
+        };
+
+        match result {
+            Ok((websocket_stream, _)) => Ok(websocket_stream.split()),
+            Err(e) => Err(TungsteniteBackendError::TungsteniteError { error: e }),
         }
     }
+
+    /// Closes a websocket connection with close code 1000 (normal closure).
+    pub async fn close_normally(
+        sink: &mut TungsteniteSink,
+        _stream: &TungsteniteStream,
+    ) -> Result<(), TungsteniteBackendError> {
+        let close_frame = tungstenite::protocol::CloseFrame {
+            code: tungstenite::protocol::frame::coding::CloseCode::Normal,
+            reason: "".into(),
+        };
+
+        sink.send(tungstenite::Message::Close(Some(close_frame)))
+            .await
+            .map_err(|e| TungsteniteBackendError::TungsteniteError { error: e })?;
+        sink.close()
+            .await
+            .map_err(|e| TungsteniteBackendError::TungsteniteError { error: e })
+    }
+
+    /// Sends a websocket ping frame, which the server has to respond to with a pong.
+    pub async fn send_ping(sink: &mut TungsteniteSink) -> Result<(), TungsteniteBackendError> {
+        sink.send(tungstenite::Message::Ping(Vec::new()))
+            .await
+            .map_err(|e| TungsteniteBackendError::TungsteniteError { error: e })
+    }
+
+    /// Returns a rustls connector, which trusts the webpki root certificates
+    fn default_tls_connector() -> Connector {
+        let certs = webpki_roots::TLS_SERVER_ROOTS;
+        let roots = rustls::RootCertStore {
+            roots: certs
+                .iter()
+                .map(|cert| {
+                    rustls::OwnedTrustAnchor::from_subject_spki_name_constraints(
+                        cert.subject.to_vec(),
+                        cert.subject_public_key_info.to_vec(),
+                        cert.name_constraints.as_ref().map(|der| der.to_vec()),
+                    )
+                })
+                .collect(),
+        };
+
+        Connector::Rustls(
+            rustls::ClientConfig::builder()
+                .with_safe_defaults()
+                .with_root_certificates(roots)
+                .with_no_client_auth()
+                .into(),
+        )
+    }
+
+    /// Returns a rustls connector, which accepts any certificate, including self-signed and
+    /// expired ones.
+    ///
+    /// Only meant for local development; see
+    /// [GatewayOptions::danger_accept_invalid_certs](crate::gateway::GatewayOptions::danger_accept_invalid_certs).
+    pub(crate) fn insecure_tls_connector() -> Connector {
+        Connector::Rustls(
+            rustls::ClientConfig::builder()
+                .with_safe_defaults()
+                .with_custom_certificate_verifier(Arc::new(NoCertificateVerification))
+                .with_no_client_auth()
+                .into(),
+        )
+    }
+}
+
+/// Accepts any server certificate, see [TungsteniteBackend::insecure_tls_connector]
+struct NoCertificateVerification;
+
+impl rustls::client::ServerCertVerifier for NoCertificateVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &rustls::Certificate,
+        _intermediates: &[rustls::Certificate],
+        _server_name: &rustls::ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: std::time::SystemTime,
+    ) -> Result<rustls::client::ServerCertVerified, rustls::Error> {
+        Ok(rustls::client::ServerCertVerified::assertion())
+    }
 }
 
 impl From<GatewayMessage> for tungstenite::Message {
@@ -136,6 +256,10 @@ impl From<tungstenite::Message> for GatewayCommunication {
                     CloseCode::try_from(close_code).unwrap_or(CloseCode::UnknownError),
                 )
             }
+            // Tungstenite responds to pings itself
+            tungstenite::Message::Ping(_) | tungstenite::Message::Pong(_) => {
+                GatewayCommunication::KeepAlive
+            }
             _ => GatewayCommunication::Error(CloseCode::UnknownError),
         }
     }
@@ -29,6 +29,17 @@ impl WasmBackend {
 
         Ok((sink, (stream, meta)))
     }
+
+    /// Closes a websocket connection with close code 1000 (normal closure).
+    ///
+    /// The code can only be set through the connection's [WsMeta], hence why this needs the
+    /// stream.
+    pub async fn close_normally(
+        _sink: &mut WasmSink,
+        stream: &WasmStream,
+    ) -> Result<(), ws_stream_wasm::WsErr> {
+        stream.1.close_code(1000).await.map(|_| ())
+    }
 }
 
 impl From<GatewayMessage> for WsMessage {
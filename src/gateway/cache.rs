@@ -0,0 +1,436 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::{HashMap, VecDeque};
+
+use async_trait::async_trait;
+
+use super::{events::Events, Observer};
+use crate::types::{
+    Channel, ChannelCreate, ChannelDelete, ChannelType, ChannelUpdate, GatewayReady, Guild,
+    GuildCreate, GuildCreateDataOption, GuildDelete, IntoShared, MentionResolver, Message,
+    PassiveUpdateV1, Relationship, RelationshipAdd, RelationshipRemove, Shared, Snowflake,
+    VoiceState, VoiceStateUpdate,
+};
+
+/// Data about the connected user which the gateway sends in [GatewayReady], kept up to date by
+/// later gateway events.
+///
+/// See [GatewayHandle::dm_channels](super::GatewayHandle::dm_channels),
+/// [GatewayHandle::relationships](super::GatewayHandle::relationships) and
+/// [GatewayHandle::guild_ids](super::GatewayHandle::guild_ids).
+#[derive(Debug, Default)]
+pub struct UserCache {
+    /// The DMs and group DMs the user is participating in
+    pub private_channels: Vec<Shared<Channel>>,
+    /// The relationships the user has with other users
+    pub relationships: Vec<Relationship>,
+    /// The ids of the guilds the user is a member of
+    pub guild_ids: Vec<Snowflake>,
+}
+
+impl UserCache {
+    /// Subscribes the cache to the events which update it.
+    pub(crate) fn subscribe(cache: Shared<UserCache>, events: &mut Events) {
+        let observer = std::sync::Arc::new(UserCacheObserver { cache });
+
+        events.session.ready.subscribe(observer.clone());
+        events.channel.create.subscribe(observer.clone());
+        events.channel.update.subscribe(observer.clone());
+        events.channel.delete.subscribe(observer.clone());
+        events.relationship.add.subscribe(observer.clone());
+        events.relationship.remove.subscribe(observer.clone());
+        events.guild.create.subscribe(observer.clone());
+        events.guild.delete.subscribe(observer);
+    }
+}
+
+impl MentionResolver for UserCache {
+    /// Resolves the users the user has a relationship with (preferring the nickname they gave
+    /// them) and the recipients of their DMs.
+    fn user_name(&self, id: Snowflake) -> Option<String> {
+        let relationship_name = self
+            .relationships
+            .iter()
+            .filter(|relationship| relationship.id == id)
+            .find_map(|relationship| {
+                relationship.nickname.clone().or_else(|| {
+                    relationship
+                        .user
+                        .as_ref()
+                        .and_then(|user| user.read().unwrap().username.clone())
+                })
+            });
+
+        relationship_name.or_else(|| {
+            self.private_channels.iter().find_map(|channel| {
+                channel
+                    .read()
+                    .unwrap()
+                    .recipients
+                    .iter()
+                    .flatten()
+                    .map(|user| user.read().unwrap())
+                    .find(|user| user.id == id)
+                    .map(|user| user.username.clone())
+            })
+        })
+    }
+
+    /// Resolves named group DMs.
+    fn channel_name(&self, id: Snowflake) -> Option<String> {
+        self.private_channels
+            .iter()
+            .map(|channel| channel.read().unwrap())
+            .find(|channel| channel.id == id)
+            .and_then(|channel| channel.name.clone())
+    }
+}
+
+/// Keeps a [UserCache] up to date
+#[derive(Debug)]
+struct UserCacheObserver {
+    cache: Shared<UserCache>,
+}
+
+fn is_private(channel: &Channel) -> bool {
+    matches!(channel.channel_type, ChannelType::Dm | ChannelType::GroupDm)
+}
+
+#[async_trait]
+impl Observer<GatewayReady> for UserCacheObserver {
+    async fn update(&self, data: &GatewayReady) {
+        let mut cache = self.cache.write().unwrap();
+
+        cache.private_channels = data
+            .private_channels
+            .iter()
+            .cloned()
+            .map(IntoShared::into_shared)
+            .collect();
+        cache.relationships = data.relationships.clone();
+        cache.guild_ids = data.guilds.iter().map(|guild| guild.id).collect();
+    }
+}
+
+#[async_trait]
+impl Observer<ChannelCreate> for UserCacheObserver {
+    async fn update(&self, data: &ChannelCreate) {
+        if !is_private(&data.channel) {
+            return;
+        }
+
+        let mut cache = self.cache.write().unwrap();
+
+        let exists = cache
+            .private_channels
+            .iter()
+            .any(|channel| channel.read().unwrap().id == data.channel.id);
+
+        if !exists {
+            cache
+                .private_channels
+                .push(data.channel.clone().into_shared());
+        }
+    }
+}
+
+#[async_trait]
+impl Observer<ChannelUpdate> for UserCacheObserver {
+    async fn update(&self, data: &ChannelUpdate) {
+        if !is_private(&data.channel) {
+            return;
+        }
+
+        let cache = self.cache.read().unwrap();
+
+        if let Some(channel) = cache
+            .private_channels
+            .iter()
+            .find(|channel| channel.read().unwrap().id == data.channel.id)
+        {
+            *channel.write().unwrap() = data.channel.clone();
+        }
+    }
+}
+
+#[async_trait]
+impl Observer<ChannelDelete> for UserCacheObserver {
+    async fn update(&self, data: &ChannelDelete) {
+        self.cache
+            .write()
+            .unwrap()
+            .private_channels
+            .retain(|channel| channel.read().unwrap().id != data.channel.id);
+    }
+}
+
+#[async_trait]
+impl Observer<RelationshipAdd> for UserCacheObserver {
+    async fn update(&self, data: &RelationshipAdd) {
+        let mut cache = self.cache.write().unwrap();
+
+        cache
+            .relationships
+            .retain(|relationship| relationship.id != data.relationship.id);
+        cache.relationships.push(data.relationship.clone());
+    }
+}
+
+#[async_trait]
+impl Observer<RelationshipRemove> for UserCacheObserver {
+    async fn update(&self, data: &RelationshipRemove) {
+        self.cache
+            .write()
+            .unwrap()
+            .relationships
+            .retain(|relationship| relationship.id != data.id);
+    }
+}
+
+#[async_trait]
+impl Observer<GuildCreate> for UserCacheObserver {
+    async fn update(&self, data: &GuildCreate) {
+        let guild_id = match &data.d {
+            GuildCreateDataOption::UnavailableGuild(guild) => guild.id,
+            GuildCreateDataOption::Guild(guild) => guild.id,
+        };
+
+        let mut cache = self.cache.write().unwrap();
+
+        if !cache.guild_ids.contains(&guild_id) {
+            cache.guild_ids.push(guild_id);
+        }
+    }
+}
+
+#[async_trait]
+impl Observer<GuildDelete> for UserCacheObserver {
+    async fn update(&self, data: &GuildDelete) {
+        // The guild only became unavailable because of an outage, the user is still a member
+        if data.guild.unavailable == Some(true) {
+            return;
+        }
+
+        self.cache
+            .write()
+            .unwrap()
+            .guild_ids
+            .retain(|id| *id != data.guild.id);
+    }
+}
+
+/// The voice states of the users in voice channels of the user's guilds, kept up to date by
+/// gateway events.
+///
+/// Voice states are received with guilds in [GatewayReady] and [GuildCreate], and updated by
+/// [VoiceStateUpdate] and [PassiveUpdateV1] events.
+///
+/// See [GatewayHandle::voice_states](super::GatewayHandle::voice_states).
+#[derive(Debug, Default)]
+pub struct VoiceStateCache {
+    /// Voice states by guild id, then by user id
+    guilds: HashMap<Snowflake, HashMap<Snowflake, VoiceState>>,
+}
+
+impl VoiceStateCache {
+    /// Subscribes the cache to the events which update it.
+    pub(crate) fn subscribe(cache: Shared<VoiceStateCache>, events: &mut Events) {
+        let observer = std::sync::Arc::new(VoiceStateCacheObserver { cache });
+
+        events.session.ready.subscribe(observer.clone());
+        events.guild.create.subscribe(observer.clone());
+        events.guild.delete.subscribe(observer.clone());
+        events.guild.passive_update_v1.subscribe(observer.clone());
+        events.voice.state_update.subscribe(observer);
+    }
+
+    /// Returns the voice states of the users in voice channels of a guild.
+    pub fn guild(&self, guild_id: Snowflake) -> impl Iterator<Item = &VoiceState> {
+        self.guilds
+            .get(&guild_id)
+            .into_iter()
+            .flat_map(|states| states.values())
+    }
+
+    /// Returns the voice state of a user in a guild, if they are in one of its voice channels.
+    pub fn get(&self, guild_id: Snowflake, user_id: Snowflake) -> Option<&VoiceState> {
+        self.guilds.get(&guild_id)?.get(&user_id)
+    }
+
+    /// Updates the voice state of a user in a guild.
+    ///
+    /// Voice states without a channel mean the user left the voice channel, and are removed.
+    pub fn insert(&mut self, guild_id: Snowflake, mut state: VoiceState) {
+        if state.channel_id.is_none() {
+            self.remove(guild_id, state.user_id);
+            return;
+        }
+
+        // Voice states sent as part of a guild don't include the guild's id
+        state.guild_id = Some(guild_id);
+
+        self.guilds
+            .entry(guild_id)
+            .or_default()
+            .insert(state.user_id, state);
+    }
+
+    /// Removes the voice state of a user in a guild, returning it.
+    pub fn remove(&mut self, guild_id: Snowflake, user_id: Snowflake) -> Option<VoiceState> {
+        let states = self.guilds.get_mut(&guild_id)?;
+        let state = states.remove(&user_id);
+
+        if states.is_empty() {
+            self.guilds.remove(&guild_id);
+        }
+
+        state
+    }
+
+    /// Replaces all voice states of a guild with the voice states in it
+    fn set_guild(&mut self, guild: &Guild) {
+        self.guilds.remove(&guild.id);
+
+        for state in guild.voice_states.iter() {
+            self.insert(guild.id, state.read().unwrap().clone());
+        }
+    }
+}
+
+/// Keeps a [VoiceStateCache] up to date
+#[derive(Debug)]
+struct VoiceStateCacheObserver {
+    cache: Shared<VoiceStateCache>,
+}
+
+#[async_trait]
+impl Observer<GatewayReady> for VoiceStateCacheObserver {
+    async fn update(&self, data: &GatewayReady) {
+        let mut cache = self.cache.write().unwrap();
+
+        cache.guilds.clear();
+        for guild in data.guilds.iter() {
+            cache.set_guild(guild);
+        }
+    }
+}
+
+#[async_trait]
+impl Observer<GuildCreate> for VoiceStateCacheObserver {
+    async fn update(&self, data: &GuildCreate) {
+        if let GuildCreateDataOption::Guild(guild) = &data.d {
+            self.cache.write().unwrap().set_guild(guild);
+        }
+    }
+}
+
+#[async_trait]
+impl Observer<GuildDelete> for VoiceStateCacheObserver {
+    async fn update(&self, data: &GuildDelete) {
+        self.cache.write().unwrap().guilds.remove(&data.guild.id);
+    }
+}
+
+#[async_trait]
+impl Observer<PassiveUpdateV1> for VoiceStateCacheObserver {
+    async fn update(&self, data: &PassiveUpdateV1) {
+        let mut cache = self.cache.write().unwrap();
+
+        // Passive updates contain all voice states of the guild
+        cache.guilds.remove(&data.guild_id);
+        for state in data.voice_states.iter() {
+            cache.insert(data.guild_id, state.clone());
+        }
+    }
+}
+
+#[async_trait]
+impl Observer<VoiceStateUpdate> for VoiceStateCacheObserver {
+    async fn update(&self, data: &VoiceStateUpdate) {
+        // Voice states in private calls are not cached
+        let Some(guild_id) = data.state.guild_id else {
+            return;
+        };
+
+        self.cache
+            .write()
+            .unwrap()
+            .insert(guild_id, data.state.clone());
+    }
+}
+
+/// A bounded cache of the most recent messages received from the gateway.
+///
+/// Once it is full, the oldest messages are evicted first.
+///
+/// Enabled by setting [GatewayOptions::message_cache_size](super::GatewayOptions::message_cache_size).
+#[derive(Debug, Default)]
+pub struct MessageCache {
+    capacity: usize,
+    messages: HashMap<Snowflake, Message>,
+    /// Message ids, from oldest to newest
+    order: VecDeque<Snowflake>,
+}
+
+impl MessageCache {
+    /// Creates a cache which holds at most `capacity` messages.
+    ///
+    /// A capacity of 0 disables the cache.
+    pub fn new(capacity: usize) -> MessageCache {
+        MessageCache {
+            capacity,
+            messages: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Whether the cache stores any messages at all
+    pub fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// Returns the cached message with the given id.
+    pub fn get(&self, id: Snowflake) -> Option<&Message> {
+        self.messages.get(&id)
+    }
+
+    /// Caches a message, returning the previously cached version of it.
+    pub fn insert(&mut self, message: Message) -> Option<Message> {
+        if !self.is_enabled() {
+            return None;
+        }
+
+        let id = message.id;
+        let previous = self.messages.insert(id, message);
+
+        if previous.is_none() {
+            self.order.push_back(id);
+
+            while self.order.len() > self.capacity {
+                if let Some(oldest) = self.order.pop_front() {
+                    self.messages.remove(&oldest);
+                }
+            }
+        }
+
+        previous
+    }
+
+    /// Removes a message from the cache, returning it.
+    pub fn remove(&mut self, id: Snowflake) -> Option<Message> {
+        let message = self.messages.remove(&id)?;
+        self.order.retain(|cached_id| *cached_id != id);
+        Some(message)
+    }
+
+    /// The amount of messages which are cached
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+}
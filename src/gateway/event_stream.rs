@@ -0,0 +1,293 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::sync::{Arc, RwLock};
+
+use futures_util::stream;
+use log::warn;
+use tokio::sync::broadcast;
+
+use crate::types::{self, Shared, Snowflake, User};
+
+/// How many events [GatewayHandle::stream](super::GatewayHandle::stream) buffers for a stream
+/// which is not polled quickly enough.
+///
+/// Once a stream falls further behind, its oldest events are skipped.
+pub const EVENT_STREAM_CAPACITY: usize = 512;
+
+macro_rules! event_enum {
+    ($($event:ident),* $(,)?) => {
+        /// A dispatched gateway event, as yielded by
+        /// [GatewayHandle::stream](super::GatewayHandle::stream).
+        ///
+        /// Each variant is named after, and holds, the event type which is also published via
+        /// the corresponding publisher in [Events](super::events::Events).
+        #[derive(Debug, Clone)]
+        #[allow(clippy::large_enum_variant)]
+        pub enum Event {
+            $($event(types::$event),)*
+        }
+
+        #[cfg(any(test, feature = "fixtures"))]
+        impl Event {
+            /// Returns an example of every variant.
+            ///
+            /// See [fixtures](super::fixtures).
+            pub fn fixtures() -> Vec<Event> {
+                use super::fixtures::EventFixture;
+
+                vec![$(Event::$event(<types::$event as EventFixture>::fixture()),)*]
+            }
+        }
+
+        $(
+            impl From<types::$event> for Event {
+                fn from(value: types::$event) -> Self {
+                    Event::$event(value)
+                }
+            }
+        )*
+    };
+}
+
+event_enum!(
+    GatewayReady,
+    GatewayReadySupplemental,
+    SessionsReplace,
+    ApplicationCommandPermissionsUpdate,
+    AutoModerationRuleCreate,
+    AutoModerationRuleUpdate,
+    AutoModerationRuleDelete,
+    AutoModerationActionExecution,
+    AuthenticatorCreate,
+    AuthenticatorUpdate,
+    AuthenticatorDelete,
+    ChannelCreate,
+    ChannelUpdate,
+    ChannelUnreadUpdate,
+    ChannelDelete,
+    ChannelPinsUpdate,
+    CallCreate,
+    CallUpdate,
+    CallDelete,
+    ThreadCreate,
+    ThreadUpdate,
+    ThreadDelete,
+    ThreadListSync,
+    ThreadMemberUpdate,
+    ThreadMembersUpdate,
+    GuildCreate,
+    GuildUpdate,
+    GuildDelete,
+    GuildApplicationCommandIndexUpdate,
+    GuildAuditLogEntryCreate,
+    GuildBanAdd,
+    GuildBanRemove,
+    GuildEmojisUpdate,
+    GuildStickersUpdate,
+    GuildIntegrationsUpdate,
+    GuildMemberAdd,
+    GuildMemberRemove,
+    GuildMemberUpdate,
+    GuildMembersChunk,
+    GuildMemberListUpdate,
+    GuildRoleCreate,
+    GuildRoleUpdate,
+    GuildRoleDelete,
+    GuildScheduledEventCreate,
+    GuildScheduledEventUpdate,
+    GuildScheduledEventDelete,
+    GuildScheduledEventUserAdd,
+    GuildScheduledEventUserRemove,
+    GuildOnboardingPromptCreate,
+    GuildOnboardingPromptUpdate,
+    GuildOnboardingPromptDelete,
+    PassiveUpdateV1,
+    IntegrationCreate,
+    IntegrationUpdate,
+    IntegrationDelete,
+    InteractionCreate,
+    InviteCreate,
+    InviteDelete,
+    LastMessages,
+    MessageCreate,
+    MessageUpdate,
+    MessageUpdateCached,
+    MessageDelete,
+    MessageDeleteCached,
+    MessageDeleteBulk,
+    MessageDeleteBulkCached,
+    MessageReactionAdd,
+    MessageReactionRemove,
+    MessageReactionRemoveAll,
+    MessageReactionRemoveEmoji,
+    MessagePollVoteAdd,
+    MessagePollVoteRemove,
+    RecentMentionDelete,
+    MessageACK,
+    PresenceUpdate,
+    RelationshipAdd,
+    RelationshipRemove,
+    StageInstanceCreate,
+    StageInstanceUpdate,
+    StageInstanceDelete,
+    TypingStartEvent,
+    UserUpdate,
+    UserConnectionsUpdate,
+    UserNoteUpdate,
+    UserPremiumGuildSubscriptionSlotCreate,
+    UserPremiumGuildSubscriptionSlotUpdate,
+    UserGuildSettingsUpdate,
+    UserSettingsUpdate,
+    UserSettingsProtoUpdate,
+    VoiceStateUpdate,
+    VoiceServerUpdate,
+    WebhooksUpdate,
+    UnknownEvent,
+);
+
+/// A dispatched gateway event, along with the user whose gateway connection received it, as
+/// yielded by [Instance::events](crate::instance::Instance::events).
+#[derive(Debug, Clone)]
+pub struct InstanceEvent {
+    /// The id of the user who received the event
+    pub user_id: Snowflake,
+    pub event: Event,
+}
+
+/// Sends events to every stream created by
+/// [GatewayHandle::stream](super::GatewayHandle::stream).
+#[derive(Debug, Clone)]
+pub(crate) struct EventStreamSender {
+    sender: broadcast::Sender<Event>,
+    /// The instance wide event bus events are also sent to, see
+    /// [EventStreamSender::forward_to_instance]
+    instance: Arc<RwLock<Option<InstanceEventForwarding>>>,
+}
+
+/// Where an [EventStreamSender] forwards its events to
+#[derive(Debug, Clone)]
+struct InstanceEventForwarding {
+    sender: InstanceEventSender,
+    /// The user the gateway connection belongs to.
+    ///
+    /// When logging in, the user is only fetched after the connection was opened, so this is
+    /// read when forwarding.
+    user: Shared<User>,
+    /// The id of the user, as received in Ready
+    ready_user_id: Option<Snowflake>,
+}
+
+impl InstanceEventForwarding {
+    fn user_id(&self) -> Snowflake {
+        self.ready_user_id
+            .unwrap_or_else(|| self.user.read().unwrap().id)
+    }
+}
+
+impl EventStreamSender {
+    pub(crate) fn new() -> EventStreamSender {
+        let (sender, _) = broadcast::channel(EVENT_STREAM_CAPACITY);
+        EventStreamSender {
+            sender,
+            instance: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Whether any stream is currently open, including the ones of the instance wide event bus
+    pub(crate) fn has_streams(&self) -> bool {
+        let instance_has_streams = self
+            .instance
+            .read()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|forwarding| forwarding.sender.has_streams());
+
+        self.sender.receiver_count() > 0 || instance_has_streams
+    }
+
+    pub(crate) fn send(&self, event: impl Into<Event>) {
+        let event = event.into();
+
+        if let Some(forwarding) = self.instance.write().unwrap().as_mut() {
+            if let Event::GatewayReady(ready) = &event {
+                forwarding.ready_user_id = Some(ready.user.id);
+            }
+
+            if forwarding.sender.has_streams() {
+                forwarding.sender.send(InstanceEvent {
+                    user_id: forwarding.user_id(),
+                    event: event.clone(),
+                });
+            }
+        }
+
+        // Only fails if no stream is open, in which case nobody is interested in the event
+        let _ = self.sender.send(event);
+    }
+
+    /// Creates a new stream, which yields every event sent from now on.
+    pub(crate) fn stream(&self) -> impl futures_util::Stream<Item = Event> {
+        broadcast_stream(self.sender.subscribe())
+    }
+
+    /// Also sends all events to an instance wide event bus, tagged with the id of `user`.
+    ///
+    /// Replaces any previous forwarding.
+    pub(crate) fn forward_to_instance(&self, sender: InstanceEventSender, user: Shared<User>) {
+        *self.instance.write().unwrap() = Some(InstanceEventForwarding {
+            sender,
+            user,
+            ready_user_id: None,
+        });
+    }
+}
+
+/// Sends the events of every gateway connection on an [Instance](crate::instance::Instance) to
+/// the streams created by [Instance::events](crate::instance::Instance::events).
+#[derive(Debug, Clone)]
+pub(crate) struct InstanceEventSender {
+    sender: broadcast::Sender<InstanceEvent>,
+}
+
+impl Default for InstanceEventSender {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_STREAM_CAPACITY);
+        InstanceEventSender { sender }
+    }
+}
+
+impl InstanceEventSender {
+    /// Whether any stream is currently open
+    pub(crate) fn has_streams(&self) -> bool {
+        self.sender.receiver_count() > 0
+    }
+
+    fn send(&self, event: InstanceEvent) {
+        // Only fails if no stream is open, in which case nobody is interested in the event
+        let _ = self.sender.send(event);
+    }
+
+    /// Creates a new stream, which yields every event sent from now on.
+    pub(crate) fn stream(&self) -> impl futures_util::Stream<Item = InstanceEvent> {
+        broadcast_stream(self.sender.subscribe())
+    }
+}
+
+/// Turns a broadcast receiver into a stream, skipping events if it falls behind
+pub(crate) fn broadcast_stream<T: Clone>(
+    receiver: broadcast::Receiver<T>,
+) -> impl futures_util::Stream<Item = T> {
+    stream::unfold(receiver, |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => return Some((event, receiver)),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("GW: Event stream fell behind, skipped {skipped} events");
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    })
+}
@@ -2,10 +2,12 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use async_trait::async_trait;
 use pubserve::Publisher;
+use serde::de::DeserializeOwned;
 
 use super::*;
-use crate::types;
+use crate::types::{self, WebSocketEvent};
 
 #[derive(Default, Debug)]
 pub struct Events {
@@ -29,6 +31,14 @@ pub struct Events {
     pub gateway_identify_payload: Publisher<types::GatewayIdentifyPayload>,
     pub gateway_resume: Publisher<types::GatewayResume>,
     pub error: Publisher<GatewayError>,
+    /// Every dispatched event, before it is parsed
+    pub raw_dispatch: Publisher<types::RawGatewayDispatch>,
+    /// Dispatched events chorus does not know about and no custom event is registered for
+    pub unknown: Publisher<types::UnknownEvent>,
+    /// Events chorus doesn't know about, registered by library extensions
+    pub custom: CustomEvents,
+    /// Observers of the events of a single guild or channel
+    pub filtered: FilteredObservers,
 }
 
 #[derive(Default, Debug)]
@@ -66,10 +76,18 @@ pub struct Message {
     pub update: Publisher<types::MessageUpdate>,
     pub delete: Publisher<types::MessageDelete>,
     pub delete_bulk: Publisher<types::MessageDeleteBulk>,
+    /// Published alongside `update` if the message cache is enabled
+    pub update_cached: Publisher<types::MessageUpdateCached>,
+    /// Published alongside `delete` if the message cache is enabled
+    pub delete_cached: Publisher<types::MessageDeleteCached>,
+    /// Published alongside `delete_bulk` if the message cache is enabled
+    pub delete_bulk_cached: Publisher<types::MessageDeleteBulkCached>,
     pub reaction_add: Publisher<types::MessageReactionAdd>,
     pub reaction_remove: Publisher<types::MessageReactionRemove>,
     pub reaction_remove_all: Publisher<types::MessageReactionRemoveAll>,
     pub reaction_remove_emoji: Publisher<types::MessageReactionRemoveEmoji>,
+    pub poll_vote_add: Publisher<types::MessagePollVoteAdd>,
+    pub poll_vote_remove: Publisher<types::MessagePollVoteRemove>,
     pub recent_mention_delete: Publisher<types::RecentMentionDelete>,
     pub ack: Publisher<types::MessageACK>,
     pub last_messages: Publisher<types::LastMessages>,
@@ -80,7 +98,13 @@ pub struct User {
     pub update: Publisher<types::UserUpdate>,
     pub connections_update: Publisher<types::UserConnectionsUpdate>,
     pub note_update: Publisher<types::UserNoteUpdate>,
+    pub premium_guild_subscription_slot_create:
+        Publisher<types::UserPremiumGuildSubscriptionSlotCreate>,
+    pub premium_guild_subscription_slot_update:
+        Publisher<types::UserPremiumGuildSubscriptionSlotUpdate>,
     pub guild_settings_update: Publisher<types::UserGuildSettingsUpdate>,
+    pub settings_update: Publisher<types::UserSettingsUpdate>,
+    pub settings_proto_update: Publisher<types::UserSettingsProtoUpdate>,
     pub presence_update: Publisher<types::PresenceUpdate>,
     pub typing_start: Publisher<types::TypingStartEvent>,
 }
@@ -125,6 +149,7 @@ pub struct Guild {
     pub member_remove: Publisher<types::GuildMemberRemove>,
     pub member_update: Publisher<types::GuildMemberUpdate>,
     pub members_chunk: Publisher<types::GuildMembersChunk>,
+    pub member_list_update: Publisher<types::GuildMemberListUpdate>,
     pub role_create: Publisher<types::GuildRoleCreate>,
     pub role_update: Publisher<types::GuildRoleUpdate>,
     pub role_delete: Publisher<types::GuildRoleDelete>,
@@ -134,6 +159,10 @@ pub struct Guild {
     pub role_scheduled_event_user_add: Publisher<types::GuildScheduledEventUserAdd>,
     pub role_scheduled_event_user_remove: Publisher<types::GuildScheduledEventUserRemove>,
     pub passive_update_v1: Publisher<types::PassiveUpdateV1>,
+    pub application_command_index_update: Publisher<types::GuildApplicationCommandIndexUpdate>,
+    pub onboarding_prompt_create: Publisher<types::GuildOnboardingPromptCreate>,
+    pub onboarding_prompt_update: Publisher<types::GuildOnboardingPromptUpdate>,
+    pub onboarding_prompt_delete: Publisher<types::GuildOnboardingPromptDelete>,
 }
 
 #[derive(Default, Debug)]
@@ -178,3 +207,189 @@ pub struct Mfa {
     pub authenticator_update: Publisher<types::AuthenticatorUpdate>,
     pub authenticator_delete: Publisher<types::AuthenticatorDelete>,
 }
+
+/// Publishers for gateway events chorus does not implement itself.
+///
+/// Library extensions can register their own event types here, either by dispatch event name
+/// (the `t` field of an opcode 0 payload) or by opcode. Chorus then deserializes matching
+/// payloads into the registered type and publishes them like any other event.
+///
+/// Events chorus already handles are never published here.
+///
+/// # Example
+/// ```no_run
+/// # use chorus::gateway::GatewayHandle;
+/// use chorus::types::WebSocketEvent;
+///
+/// #[derive(Debug, serde::Deserialize)]
+/// struct FancyEvent {
+///     fanciness: u64,
+/// }
+///
+/// impl WebSocketEvent for FancyEvent {}
+///
+/// # async fn example(gateway: GatewayHandle) {
+/// let mut events = gateway.events.lock().await;
+/// events.custom.register_dispatch::<FancyEvent>("FANCY_EVENT");
+///
+/// // Subscribe to it like to any other event
+/// let publisher = events
+///     .custom
+///     .dispatch_mut::<FancyEvent>("FANCY_EVENT")
+///     .unwrap();
+/// # let _ = publisher;
+/// # }
+/// ```
+#[derive(Default)]
+pub struct CustomEvents {
+    dispatches: HashMap<String, Box<dyn CustomEventPublisher>>,
+    opcodes: HashMap<u8, Box<dyn CustomEventPublisher>>,
+}
+
+impl CustomEvents {
+    /// Registers a publisher for dispatched events named `event_name`.
+    ///
+    /// Returns `false` and keeps the existing publisher if one was already registered for the
+    /// name.
+    pub fn register_dispatch<T>(&mut self, event_name: impl Into<String>) -> bool
+    where
+        T: WebSocketEvent + DeserializeOwned + 'static,
+    {
+        match self.dispatches.entry(event_name.into()) {
+            std::collections::hash_map::Entry::Occupied(_) => false,
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                entry.insert(Box::new(Publisher::<T>::new()));
+                true
+            }
+        }
+    }
+
+    /// Registers a publisher for payloads with a custom `opcode`.
+    ///
+    /// The payload's `d` field is deserialized into `T`. Opcodes chorus already handles
+    /// cannot be registered.
+    ///
+    /// Returns `false` and keeps the existing publisher if one was already registered for the
+    /// opcode, or if chorus handles the opcode itself.
+    pub fn register_opcode<T>(&mut self, opcode: u8) -> bool
+    where
+        T: WebSocketEvent + DeserializeOwned + 'static,
+    {
+        if types::Opcode::try_from(opcode).is_ok() {
+            return false;
+        }
+
+        match self.opcodes.entry(opcode) {
+            std::collections::hash_map::Entry::Occupied(_) => false,
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                entry.insert(Box::new(Publisher::<T>::new()));
+                true
+            }
+        }
+    }
+
+    /// Returns the publisher registered for dispatched events named `event_name`.
+    ///
+    /// Returns `None` if no publisher was registered, or if it was registered with a type other
+    /// than `T`.
+    pub fn dispatch_mut<T: WebSocketEvent + 'static>(
+        &mut self,
+        event_name: &str,
+    ) -> Option<&mut Publisher<T>> {
+        self.dispatches
+            .get_mut(event_name)?
+            .as_any_mut()
+            .downcast_mut::<Publisher<T>>()
+    }
+
+    /// Returns the publisher registered for `opcode`.
+    ///
+    /// Returns `None` if no publisher was registered, or if it was registered with a type other
+    /// than `T`.
+    pub fn opcode_mut<T: WebSocketEvent + 'static>(
+        &mut self,
+        opcode: u8,
+    ) -> Option<&mut Publisher<T>> {
+        self.opcodes
+            .get_mut(&opcode)?
+            .as_any_mut()
+            .downcast_mut::<Publisher<T>>()
+    }
+
+    /// Removes the publisher registered for dispatched events named `event_name`, along with its
+    /// subscribers.
+    pub fn unregister_dispatch(&mut self, event_name: &str) -> bool {
+        self.dispatches.remove(event_name).is_some()
+    }
+
+    /// Removes the publisher registered for `opcode`, along with its subscribers.
+    pub fn unregister_opcode(&mut self, opcode: u8) -> bool {
+        self.opcodes.remove(&opcode).is_some()
+    }
+
+    /// Returns whether a publisher is registered for dispatched events named `event_name`.
+    pub fn has_dispatch(&self, event_name: &str) -> bool {
+        self.dispatches.contains_key(event_name)
+    }
+
+    /// Returns whether a publisher is registered for `opcode`.
+    pub fn has_opcode(&self, opcode: u8) -> bool {
+        self.opcodes.contains_key(&opcode)
+    }
+
+    /// Deserializes and publishes a dispatched event, if a publisher is registered for it.
+    ///
+    /// Returns `None` if no publisher is registered.
+    pub(crate) async fn publish_dispatch(
+        &self,
+        event_name: &str,
+        json: &str,
+    ) -> Option<Result<(), serde_json::Error>> {
+        Some(self.dispatches.get(event_name)?.publish_json(json).await)
+    }
+
+    /// Deserializes and publishes a payload with a custom opcode, if a publisher is registered
+    /// for it.
+    ///
+    /// Returns `None` if no publisher is registered.
+    pub(crate) async fn publish_opcode(
+        &self,
+        opcode: u8,
+        json: &str,
+    ) -> Option<Result<(), serde_json::Error>> {
+        Some(self.opcodes.get(&opcode)?.publish_json(json).await)
+    }
+}
+
+impl std::fmt::Debug for CustomEvents {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CustomEvents")
+            .field("dispatches", &self.dispatches.keys().collect::<Vec<_>>())
+            .field("opcodes", &self.opcodes.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// A type-erased [Publisher] for a custom event
+#[async_trait]
+trait CustomEventPublisher: Send + Sync {
+    async fn publish_json(&self, json: &str) -> Result<(), serde_json::Error>;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+#[async_trait]
+impl<T> CustomEventPublisher for Publisher<T>
+where
+    T: WebSocketEvent + DeserializeOwned + 'static,
+{
+    async fn publish_json(&self, json: &str) -> Result<(), serde_json::Error> {
+        let event: T = serde_json::from_str(json)?;
+        self.publish(event).await;
+        Ok(())
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
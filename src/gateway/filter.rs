@@ -0,0 +1,255 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::Observer;
+use crate::types::{ScopedEvent, Snowflake};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Which events an observer subscribed with [FilteredObservers::subscribe] is notified about.
+///
+/// A [Snowflake] converts into [EventFilter::Guild].
+pub enum EventFilter {
+    /// Only events which happened in the guild with this id
+    Guild(Snowflake),
+    /// Only events which happened in the channel (or thread) with this id
+    Channel(Snowflake),
+}
+
+impl EventFilter {
+    /// Whether an event passes the filter
+    pub fn matches(&self, event: &impl ScopedEvent) -> bool {
+        match self {
+            EventFilter::Guild(id) => event.guild_id() == Some(*id),
+            EventFilter::Channel(id) => event.channel_id() == Some(*id),
+        }
+    }
+}
+
+impl From<Snowflake> for EventFilter {
+    fn from(guild_id: Snowflake) -> Self {
+        EventFilter::Guild(guild_id)
+    }
+}
+
+/// Observers which are only notified about the events of a single guild or channel.
+///
+/// Observers are indexed by the guild or channel they are interested in. When an event is
+/// dispatched, only the observers of its guild and channel are looked up, so observers of
+/// other guilds and channels are never called.
+///
+/// # Example
+/// ```no_run
+/// # use std::sync::Arc;
+/// # use chorus::gateway::{GatewayHandle, Observer};
+/// # use chorus::types::{MessageCreate, Snowflake};
+/// #[derive(Debug)]
+/// struct MessageLogger;
+///
+/// #[async_trait::async_trait]
+/// impl Observer<MessageCreate> for MessageLogger {
+///     async fn update(&self, data: &MessageCreate) {
+///         println!("{:?}", data.message.content);
+///     }
+/// }
+///
+/// # async fn example(gateway: GatewayHandle, guild_id: Snowflake) {
+/// gateway
+///     .events
+///     .lock()
+///     .await
+///     .filtered
+///     .subscribe::<MessageCreate>(guild_id, Arc::new(MessageLogger));
+/// # }
+/// ```
+#[derive(Default)]
+pub struct FilteredObservers {
+    /// A [FilterIndex] per event type
+    indices: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl FilteredObservers {
+    /// Subscribes an observer which is only notified about events which pass the filter.
+    ///
+    /// Pass the same filter and observer to [FilteredObservers::unsubscribe] to unsubscribe it
+    /// again.
+    pub fn subscribe<T: ScopedEvent + 'static>(
+        &mut self,
+        filter: impl Into<EventFilter>,
+        observer: Arc<dyn Observer<T>>,
+    ) {
+        self.indices
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(FilterIndex::<T>::new()))
+            .downcast_mut::<FilterIndex<T>>()
+            .unwrap()
+            .observers
+            .entry(filter.into())
+            .or_default()
+            .push(observer);
+    }
+
+    /// Unsubscribes an observer which was subscribed with the given filter.
+    ///
+    /// Returns whether the observer was subscribed.
+    pub fn unsubscribe<T: 'static>(
+        &mut self,
+        filter: impl Into<EventFilter>,
+        observer: &Arc<dyn Observer<T>>,
+    ) -> bool {
+        let Some(index) = self.index_mut::<T>() else {
+            return false;
+        };
+
+        let filter = filter.into();
+        let Some(observers) = index.observers.get_mut(&filter) else {
+            return false;
+        };
+
+        let subscribed = observers.len();
+        observers.retain(|subscribed| !Arc::ptr_eq(subscribed, observer));
+        let unsubscribed = observers.len() < subscribed;
+
+        if observers.is_empty() {
+            index.observers.remove(&filter);
+        }
+
+        unsubscribed
+    }
+
+    /// Whether any observers are subscribed to events of type `T`
+    pub fn has_subscribers<T: 'static>(&self) -> bool {
+        self.index::<T>()
+            .is_some_and(|index| !index.observers.is_empty())
+    }
+
+    /// Notifies the observers of the event's guild and channel.
+    ///
+    /// Does nothing for events which can't be filtered, since nobody can subscribe to them.
+    pub(crate) async fn publish<T: 'static>(&self, event: &T) {
+        let Some(index) = self.index::<T>() else {
+            return;
+        };
+
+        let (guild_id, channel_id) = (index.scope)(event);
+        let filters = [
+            guild_id.map(EventFilter::Guild),
+            channel_id.map(EventFilter::Channel),
+        ];
+
+        for filter in filters.into_iter().flatten() {
+            for observer in index.observers.get(&filter).into_iter().flatten() {
+                observer.update(event).await;
+            }
+        }
+    }
+
+    fn index<T: 'static>(&self) -> Option<&FilterIndex<T>> {
+        self.indices.get(&TypeId::of::<T>())?.downcast_ref()
+    }
+
+    fn index_mut<T: 'static>(&mut self) -> Option<&mut FilterIndex<T>> {
+        self.indices.get_mut(&TypeId::of::<T>())?.downcast_mut()
+    }
+}
+
+impl std::fmt::Debug for FilteredObservers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FilteredObservers")
+            .field("event_types", &self.indices.len())
+            .finish()
+    }
+}
+
+/// The filtered observers of one event type
+struct FilterIndex<T> {
+    /// Returns the guild and channel id of an event, see [ScopedEvent]
+    scope: fn(&T) -> (Option<Snowflake>, Option<Snowflake>),
+    observers: HashMap<EventFilter, Vec<Arc<dyn Observer<T>>>>,
+}
+
+impl<T: ScopedEvent> FilterIndex<T> {
+    fn new() -> Self {
+        Self {
+            scope: |event| (event.guild_id(), event.channel_id()),
+            observers: HashMap::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::types::{Message, MessageCreate};
+
+    #[derive(Debug, Default)]
+    struct CountingObserver {
+        count: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl Observer<MessageCreate> for CountingObserver {
+        async fn update(&self, _data: &MessageCreate) {
+            self.count.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[derive(Debug)]
+    struct UnreachableObserver;
+
+    #[async_trait::async_trait]
+    impl Observer<MessageCreate> for UnreachableObserver {
+        async fn update(&self, data: &MessageCreate) {
+            panic!(
+                "Observer of another guild was called for {:?}",
+                data.guild_id
+            );
+        }
+    }
+
+    fn message(guild_id: u64, channel_id: u64) -> MessageCreate {
+        MessageCreate {
+            guild_id: Some(Snowflake(guild_id)),
+            message: Message {
+                channel_id: Snowflake(channel_id),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+    async fn only_matching_observers_are_called() {
+        let guild_observer = Arc::new(CountingObserver::default());
+        let channel_observer = Arc::new(CountingObserver::default());
+
+        let mut filtered = FilteredObservers::default();
+        let guild_subscriber: Arc<dyn Observer<MessageCreate>> = guild_observer.clone();
+        filtered.subscribe(Snowflake(1), guild_subscriber.clone());
+        filtered.subscribe::<MessageCreate>(
+            EventFilter::Channel(Snowflake(10)),
+            channel_observer.clone(),
+        );
+        filtered.subscribe::<MessageCreate>(Snowflake(3), Arc::new(UnreachableObserver));
+
+        filtered.publish(&message(1, 10)).await;
+        filtered.publish(&message(1, 11)).await;
+        filtered.publish(&message(2, 20)).await;
+
+        assert_eq!(guild_observer.count.load(Ordering::Relaxed), 2);
+        assert_eq!(channel_observer.count.load(Ordering::Relaxed), 1);
+
+        assert!(filtered.unsubscribe(Snowflake(1), &guild_subscriber));
+        assert!(!filtered.unsubscribe(Snowflake(1), &guild_subscriber));
+        filtered.publish(&message(1, 10)).await;
+        assert_eq!(guild_observer.count.load(Ordering::Relaxed), 2);
+        assert_eq!(channel_observer.count.load(Ordering::Relaxed), 2);
+    }
+}
@@ -0,0 +1,1043 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Example payloads for every gateway [Event](super::Event), for testing code which handles events without
+//! connecting to a server.
+//!
+//! Enabled with the `fixtures` feature.
+//!
+//! Each dispatched event has a constant holding an example of its `d` field, named after the
+//! event (e.g. [MESSAGE_CREATE]), and implements [EventFixture] to deserialize it.
+//! All fixtures refer to the same guild, channel, users and message, see [GUILD_ID] and the
+//! other id constants.
+//!
+//! # Example
+//! ```rs
+//! use chorus::gateway::fixtures::EventFixture;
+//! use chorus::types::MessageCreate;
+//!
+//! let event = MessageCreate::fixture();
+//! my_observer.update(&event).await;
+//! ```
+
+use serde_json::value::RawValue;
+
+use crate::types::{self, Snowflake};
+
+/// The id of the guild all fixtures take place in
+pub const GUILD_ID: Snowflake = Snowflake(1100000000000000001);
+/// The id of the text channel all fixtures take place in
+pub const CHANNEL_ID: Snowflake = Snowflake(1100000000000000002);
+/// The id of the current user, who receives the events
+pub const USER_ID: Snowflake = Snowflake(1100000000000000003);
+/// The id of another user, who triggers most events
+pub const OTHER_USER_ID: Snowflake = Snowflake(1100000000000000004);
+/// The id of the message fixtures refer to
+pub const MESSAGE_ID: Snowflake = Snowflake(1100000000000000005);
+/// The id of the role fixtures refer to
+pub const ROLE_ID: Snowflake = Snowflake(1100000000000000006);
+
+/// An event with an example payload.
+pub trait EventFixture: Sized {
+    /// Returns an example of the event.
+    fn fixture() -> Self;
+}
+
+macro_rules! json_fixtures {
+    ($($event:ident => $name:ident = $payload:literal;)*) => {
+        $(
+            #[doc = concat!("Example payload of a `", stringify!($name), "` event; see [", stringify!($event), "](types::", stringify!($event), ").")]
+            pub const $name: &str = $payload;
+
+            impl EventFixture for types::$event {
+                fn fixture() -> Self {
+                    serde_json::from_str($name).expect(concat!(
+                        "Invalid ",
+                        stringify!($name),
+                        " fixture, please open an issue on the chorus github: https://github.com/polyphony-chat/chorus/issues/new"
+                    ))
+                }
+            }
+        )*
+
+        /// All example payloads, as (event name, payload) pairs.
+        pub const PAYLOADS: &[(&str, &str)] = &[$((stringify!($name), $name),)*];
+
+        /// Checks that every payload survives a serde round trip.
+        #[cfg(test)]
+        fn check_payload_round_trips() {
+            $(test::check_round_trip::<types::$event>(stringify!($name), $name);)*
+        }
+    };
+}
+
+json_fixtures! {
+    GatewayReady => READY = r#"{
+        "_trace": ["[\"gateway-prd-main-abcd\",{\"micros\":12345}]"],
+        "analytics_token": "analytics-token",
+        "auth_session_id_hash": "c2Vzc2lvbi1oYXNo",
+        "country_code": "DE",
+        "v": 9,
+        "user": {
+            "id": "1100000000000000003",
+            "username": "current_user",
+            "discriminator": "0",
+            "global_name": "Current User",
+            "avatar": null,
+            "bot": false,
+            "system": false,
+            "mfa_enabled": false,
+            "verified": true,
+            "email": "current_user@example.com",
+            "flags": 0,
+            "premium_type": 0,
+            "public_flags": 0
+        },
+        "guilds": [],
+        "session_id": "9b8f1c3e5d7a4b2c9e0f1a2b3c4d5e6f",
+        "session_type": "normal",
+        "resume_gateway_url": "wss://gateway.example.com",
+        "relationships": [],
+        "friend_suggestion_count": 0,
+        "private_channels": [],
+        "notes": {},
+        "users": [],
+        "authenticator_types": [],
+        "geo_ordered_rtc_regions": ["frankfurt", "rotterdam"],
+        "api_code_version": 1,
+        "read_state": {"entries": [], "partial": false, "version": 1}
+    }"#;
+    GatewayReadySupplemental => READY_SUPPLEMENTAL = r#"{
+        "merged_presences": {"friends": [], "guilds": []},
+        "merged_members": [],
+        "lazy_private_channels": [],
+        "guilds": [],
+        "disclose": []
+    }"#;
+    SessionsReplace => SESSIONS_REPLACE = r#"{
+        "sessions": [{
+            "activities": [],
+            "client_info": {"client": "web", "os": "linux", "version": 0},
+            "session_id": "9b8f1c3e5d7a4b2c9e0f1a2b3c4d5e6f",
+            "status": "online"
+        }]
+    }"#;
+    ApplicationCommandPermissionsUpdate => APPLICATION_COMMAND_PERMISSIONS_UPDATE = r#"{
+        "id": "1100000000000000010",
+        "application_id": "1100000000000000011",
+        "guild_id": "1100000000000000001",
+        "permissions": [{"id": "1100000000000000006", "type": 1, "permission": true}]
+    }"#;
+    AutoModerationRuleCreate => AUTO_MODERATION_RULE_CREATE = r#"{
+        "id": "1100000000000000020",
+        "guild_id": "1100000000000000001",
+        "name": "Block bad words",
+        "creator_id": "1100000000000000003",
+        "event_type": 1,
+        "trigger_type": 1,
+        "trigger_metadata": {"keyword_filter": ["badword"], "regex_patterns": [], "allow_list": []},
+        "actions": [{"type": 1, "metadata": {"custom_message": "Please keep it civil"}}],
+        "enabled": true,
+        "exempt_roles": [],
+        "exempt_channels": []
+    }"#;
+    AutoModerationRuleUpdate => AUTO_MODERATION_RULE_UPDATE = r#"{
+        "id": "1100000000000000020",
+        "guild_id": "1100000000000000001",
+        "name": "Block bad words",
+        "creator_id": "1100000000000000003",
+        "event_type": 1,
+        "trigger_type": 1,
+        "trigger_metadata": {"keyword_filter": ["badword", "worseword"], "regex_patterns": [], "allow_list": []},
+        "actions": [{"type": 1, "metadata": {"custom_message": "Please keep it civil"}}],
+        "enabled": true,
+        "exempt_roles": [],
+        "exempt_channels": []
+    }"#;
+    AutoModerationRuleDelete => AUTO_MODERATION_RULE_DELETE = r#"{
+        "id": "1100000000000000020",
+        "guild_id": "1100000000000000001",
+        "name": "Block bad words",
+        "creator_id": "1100000000000000003",
+        "event_type": 1,
+        "trigger_type": 1,
+        "trigger_metadata": {"keyword_filter": ["badword"], "regex_patterns": [], "allow_list": []},
+        "actions": [{"type": 1, "metadata": {"custom_message": "Please keep it civil"}}],
+        "enabled": false,
+        "exempt_roles": [],
+        "exempt_channels": []
+    }"#;
+    AutoModerationActionExecution => AUTO_MODERATION_ACTION_EXECUTION = r#"{
+        "guild_id": "1100000000000000001",
+        "action": {"type": 1, "metadata": {"custom_message": "Please keep it civil"}},
+        "rule_id": "1100000000000000020",
+        "rule_trigger_type": 1,
+        "user_id": "1100000000000000004",
+        "channel_id": "1100000000000000002",
+        "message_id": null,
+        "alert_system_message_id": null,
+        "content": "this contains a badword",
+        "matched_keyword": "badword",
+        "matched_content": "badword"
+    }"#;
+    AuthenticatorCreate => AUTHENTICATOR_CREATE = r#"{
+        "id": "1100000000000000030",
+        "type": 2,
+        "name": "Security key"
+    }"#;
+    AuthenticatorUpdate => AUTHENTICATOR_UPDATE = r#"{
+        "id": "1100000000000000030",
+        "type": 2,
+        "name": "Renamed security key"
+    }"#;
+    AuthenticatorDelete => AUTHENTICATOR_DELETE = r#"{
+        "id": "1100000000000000030",
+        "type": 2
+    }"#;
+    ChannelCreate => CHANNEL_CREATE = r#"{
+        "id": "1100000000000000002",
+        "type": 0,
+        "guild_id": "1100000000000000001",
+        "name": "general",
+        "topic": "General chat",
+        "position": 0,
+        "permission_overwrites": [],
+        "nsfw": false,
+        "rate_limit_per_user": 0,
+        "parent_id": null,
+        "last_message_id": null,
+        "flags": 0
+    }"#;
+    ChannelUpdate => CHANNEL_UPDATE = r#"{
+        "id": "1100000000000000002",
+        "type": 0,
+        "guild_id": "1100000000000000001",
+        "name": "general",
+        "topic": "A new topic",
+        "position": 0,
+        "permission_overwrites": [],
+        "nsfw": false,
+        "rate_limit_per_user": 5,
+        "parent_id": null,
+        "last_message_id": "1100000000000000005",
+        "flags": 0
+    }"#;
+    ChannelUnreadUpdate => CHANNEL_UNREAD_UPDATE = r#"{
+        "channel_unread_updates": [{
+            "id": "1100000000000000002",
+            "last_message_id": "1100000000000000005",
+            "last_pin_timestamp": null
+        }],
+        "guild_id": "1100000000000000001"
+    }"#;
+    ChannelDelete => CHANNEL_DELETE = r#"{
+        "id": "1100000000000000002",
+        "type": 0,
+        "guild_id": "1100000000000000001",
+        "name": "general",
+        "position": 0,
+        "permission_overwrites": [],
+        "nsfw": false,
+        "parent_id": null,
+        "flags": 0
+    }"#;
+    ChannelPinsUpdate => CHANNEL_PINS_UPDATE = r#"{
+        "guild_id": "1100000000000000001",
+        "channel_id": "1100000000000000002",
+        "last_pin_timestamp": "2024-08-20T12:00:00+00:00"
+    }"#;
+    CallCreate => CALL_CREATE = r#"{
+        "channel_id": "1100000000000000040",
+        "message_id": "1100000000000000005",
+        "ringing": ["1100000000000000003"],
+        "region": "rotterdam",
+        "voice_states": []
+    }"#;
+    CallUpdate => CALL_UPDATE = r#"{
+        "channel_id": "1100000000000000040",
+        "message_id": "1100000000000000005",
+        "ringing": [],
+        "region": "rotterdam"
+    }"#;
+    CallDelete => CALL_DELETE = r#"{
+        "channel_id": "1100000000000000040"
+    }"#;
+    ThreadCreate => THREAD_CREATE = r#"{
+        "id": "1100000000000000050",
+        "type": 11,
+        "guild_id": "1100000000000000001",
+        "parent_id": "1100000000000000002",
+        "owner_id": "1100000000000000004",
+        "name": "A thread",
+        "last_message_id": null,
+        "rate_limit_per_user": 0,
+        "message_count": 0,
+        "member_count": 1,
+        "thread_metadata": {
+            "archived": false,
+            "auto_archive_duration": 1440,
+            "archive_timestamp": "2024-08-20T12:00:00+00:00",
+            "locked": false
+        },
+        "flags": 0
+    }"#;
+    ThreadUpdate => THREAD_UPDATE = r#"{
+        "id": "1100000000000000050",
+        "type": 11,
+        "guild_id": "1100000000000000001",
+        "parent_id": "1100000000000000002",
+        "owner_id": "1100000000000000004",
+        "name": "A renamed thread",
+        "last_message_id": null,
+        "rate_limit_per_user": 0,
+        "message_count": 0,
+        "member_count": 1,
+        "thread_metadata": {
+            "archived": false,
+            "auto_archive_duration": 1440,
+            "archive_timestamp": "2024-08-20T12:00:00+00:00",
+            "locked": false
+        },
+        "flags": 0
+    }"#;
+    ThreadDelete => THREAD_DELETE = r#"{
+        "id": "1100000000000000050",
+        "type": 11,
+        "guild_id": "1100000000000000001",
+        "parent_id": "1100000000000000002"
+    }"#;
+    ThreadListSync => THREAD_LIST_SYNC = r#"{
+        "guild_id": "1100000000000000001",
+        "channel_ids": ["1100000000000000002"],
+        "threads": [{
+            "id": "1100000000000000050",
+            "type": 11,
+            "guild_id": "1100000000000000001",
+            "parent_id": "1100000000000000002",
+            "name": "A thread"
+        }],
+        "members": []
+    }"#;
+    ThreadMemberUpdate => THREAD_MEMBER_UPDATE = r#"{
+        "id": "1100000000000000050",
+        "user_id": "1100000000000000003",
+        "join_timestamp": "2024-08-20T12:00:00+00:00",
+        "flags": 0,
+        "guild_id": "1100000000000000001"
+    }"#;
+    ThreadMembersUpdate => THREAD_MEMBERS_UPDATE = r#"{
+        "id": "1100000000000000050",
+        "guild_id": "1100000000000000001",
+        "member_count": 2,
+        "added_members": [{
+            "id": "1100000000000000050",
+            "user_id": "1100000000000000004",
+            "join_timestamp": "2024-08-20T12:00:00+00:00",
+            "flags": 0
+        }],
+        "removed_members": []
+    }"#;
+    GuildCreate => GUILD_CREATE = r#"{
+        "id": "1100000000000000001",
+        "name": "Fixture guild",
+        "icon": null,
+        "owner_id": "1100000000000000003",
+        "afk_timeout": 300,
+        "verification_level": 0,
+        "default_message_notifications": 0,
+        "explicit_content_filter": 0,
+        "features": [],
+        "mfa_level": 0,
+        "nsfw_level": 0,
+        "premium_tier": 0,
+        "premium_subscription_count": 0,
+        "preferred_locale": "en-US",
+        "system_channel_flags": 0,
+        "roles": [],
+        "emojis": [],
+        "stickers": [],
+        "channels": [],
+        "member_count": 2,
+        "large": false
+    }"#;
+    GuildUpdate => GUILD_UPDATE = r#"{
+        "id": "1100000000000000001",
+        "name": "Renamed fixture guild",
+        "icon": null,
+        "owner_id": "1100000000000000003",
+        "afk_timeout": 300,
+        "verification_level": 0,
+        "default_message_notifications": 0,
+        "explicit_content_filter": 0,
+        "features": [],
+        "mfa_level": 0,
+        "nsfw_level": 0,
+        "premium_tier": 0,
+        "premium_subscription_count": 0,
+        "preferred_locale": "en-US",
+        "system_channel_flags": 0
+    }"#;
+    GuildDelete => GUILD_DELETE = r#"{
+        "id": "1100000000000000001",
+        "unavailable": false
+    }"#;
+    GuildApplicationCommandIndexUpdate => GUILD_APPLICATION_COMMAND_INDEX_UPDATE = r#"{
+        "guild_id": "1100000000000000001",
+        "application_command_counts": {"1": 4, "3": 1},
+        "version": "1100000000000000060"
+    }"#;
+    GuildAuditLogEntryCreate => GUILD_AUDIT_LOG_ENTRY_CREATE = r#"{
+        "id": "1100000000000000070",
+        "guild_id": "1100000000000000001",
+        "target_id": "1100000000000000002",
+        "user_id": "1100000000000000003",
+        "action_type": 11,
+        "changes": [{"key": "topic", "old_value": "General chat", "new_value": "A new topic"}],
+        "reason": "Fixture"
+    }"#;
+    GuildBanAdd => GUILD_BAN_ADD = r#"{
+        "guild_id": "1100000000000000001",
+        "user": {"id": "1100000000000000004", "username": "other_user", "discriminator": "0", "avatar": null}
+    }"#;
+    GuildBanRemove => GUILD_BAN_REMOVE = r#"{
+        "guild_id": "1100000000000000001",
+        "user": {"id": "1100000000000000004", "username": "other_user", "discriminator": "0", "avatar": null}
+    }"#;
+    GuildEmojisUpdate => GUILD_EMOJIS_UPDATE = r#"{
+        "guild_id": "1100000000000000001",
+        "emojis": [{
+            "id": "1100000000000000080",
+            "name": "fixture",
+            "roles": [],
+            "require_colons": true,
+            "managed": false,
+            "animated": false,
+            "available": true
+        }]
+    }"#;
+    GuildStickersUpdate => GUILD_STICKERS_UPDATE = r#"{
+        "guild_id": "1100000000000000001",
+        "stickers": [{
+            "id": "1100000000000000081",
+            "guild_id": "1100000000000000001",
+            "name": "fixture",
+            "description": "A fixture sticker",
+            "tags": "fixture",
+            "type": 2,
+            "format_type": 1,
+            "available": true
+        }]
+    }"#;
+    GuildIntegrationsUpdate => GUILD_INTEGRATIONS_UPDATE = r#"{
+        "guild_id": "1100000000000000001"
+    }"#;
+    GuildMemberAdd => GUILD_MEMBER_ADD = r#"{
+        "guild_id": "1100000000000000001",
+        "user": {"id": "1100000000000000004", "username": "other_user", "discriminator": "0", "avatar": null},
+        "nick": null,
+        "avatar": null,
+        "roles": [],
+        "joined_at": "2024-08-20T12:00:00+00:00",
+        "premium_since": null,
+        "deaf": false,
+        "mute": false,
+        "flags": 0,
+        "pending": false
+    }"#;
+    GuildMemberRemove => GUILD_MEMBER_REMOVE = r#"{
+        "guild_id": "1100000000000000001",
+        "user": {"id": "1100000000000000004", "username": "other_user", "discriminator": "0", "avatar": null}
+    }"#;
+    GuildMemberUpdate => GUILD_MEMBER_UPDATE = r#"{
+        "guild_id": "1100000000000000001",
+        "roles": ["1100000000000000006"],
+        "user": {"id": "1100000000000000004", "username": "other_user", "discriminator": "0", "avatar": null},
+        "nick": "Other",
+        "avatar": null,
+        "joined_at": "2024-08-20T12:00:00+00:00",
+        "premium_since": null,
+        "deaf": false,
+        "mute": false,
+        "pending": false,
+        "communication_disabled_until": null
+    }"#;
+    GuildMembersChunk => GUILD_MEMBERS_CHUNK = r#"{
+        "guild_id": "1100000000000000001",
+        "members": [{
+            "user": {"id": "1100000000000000004", "username": "other_user", "discriminator": "0", "avatar": null},
+            "nick": null,
+            "avatar": null,
+            "roles": [],
+            "joined_at": "2024-08-20T12:00:00+00:00",
+            "premium_since": null,
+            "deaf": false,
+            "mute": false,
+            "flags": 0
+        }],
+        "chunk_index": 0,
+        "chunk_count": 1,
+        "not_found": [],
+        "nonce": "fixture"
+    }"#;
+    GuildMemberListUpdate => GUILD_MEMBER_LIST_UPDATE = r#"{
+        "guild_id": "1100000000000000001",
+        "id": "everyone",
+        "member_count": 2,
+        "online_count": 1,
+        "groups": [{"id": "online", "count": 1}, {"id": "offline", "count": 1}],
+        "ops": [{
+            "op": "SYNC",
+            "range": [0, 99],
+            "items": [
+                {"group": {"id": "online", "count": 1}},
+                {"member": {
+                    "user": {"id": "1100000000000000004", "username": "other_user", "discriminator": "0", "avatar": null},
+                    "nick": null,
+                    "avatar": null,
+                    "roles": [],
+                    "joined_at": "2024-08-20T12:00:00+00:00",
+                    "premium_since": null,
+                    "deaf": false,
+                    "mute": false,
+                    "flags": 0,
+                    "presence": {
+                        "user": {"id": "1100000000000000004"},
+                        "status": "online",
+                        "activities": [],
+                        "client_status": {"desktop": "online"}
+                    }
+                }}
+            ]
+        }]
+    }"#;
+    GuildRoleCreate => GUILD_ROLE_CREATE = r#"{
+        "guild_id": "1100000000000000001",
+        "role": {
+            "id": "1100000000000000006",
+            "name": "Moderator",
+            "color": 3447003,
+            "hoist": true,
+            "icon": null,
+            "unicode_emoji": null,
+            "position": 1,
+            "permissions": "8192",
+            "managed": false,
+            "mentionable": true,
+            "flags": 0
+        }
+    }"#;
+    GuildRoleUpdate => GUILD_ROLE_UPDATE = r#"{
+        "guild_id": "1100000000000000001",
+        "role": {
+            "id": "1100000000000000006",
+            "name": "Moderator",
+            "color": 15844367,
+            "hoist": true,
+            "icon": null,
+            "unicode_emoji": null,
+            "position": 1,
+            "permissions": "8192",
+            "managed": false,
+            "mentionable": true,
+            "flags": 0
+        }
+    }"#;
+    GuildRoleDelete => GUILD_ROLE_DELETE = r#"{
+        "guild_id": "1100000000000000001",
+        "role_id": "1100000000000000006"
+    }"#;
+    GuildScheduledEventCreate => GUILD_SCHEDULED_EVENT_CREATE = r#"{
+        "id": "1100000000000000090",
+        "guild_id": "1100000000000000001",
+        "channel_id": null,
+        "creator_id": "1100000000000000003",
+        "name": "Fixture party",
+        "description": "Everyone is invited",
+        "scheduled_start_time": "2024-09-01T18:00:00+00:00",
+        "scheduled_end_time": "2024-09-01T20:00:00+00:00",
+        "privacy_level": 2,
+        "status": 1,
+        "entity_type": 3,
+        "entity_id": null,
+        "entity_metadata": {"location": "The usual place"},
+        "user_count": 0
+    }"#;
+    GuildScheduledEventUpdate => GUILD_SCHEDULED_EVENT_UPDATE = r#"{
+        "id": "1100000000000000090",
+        "guild_id": "1100000000000000001",
+        "channel_id": null,
+        "creator_id": "1100000000000000003",
+        "name": "Fixture party",
+        "description": "Everyone is invited",
+        "scheduled_start_time": "2024-09-01T18:00:00+00:00",
+        "scheduled_end_time": "2024-09-01T20:00:00+00:00",
+        "privacy_level": 2,
+        "status": 2,
+        "entity_type": 3,
+        "entity_id": null,
+        "entity_metadata": {"location": "The usual place"},
+        "user_count": 1
+    }"#;
+    GuildScheduledEventDelete => GUILD_SCHEDULED_EVENT_DELETE = r#"{
+        "id": "1100000000000000090",
+        "guild_id": "1100000000000000001",
+        "channel_id": null,
+        "creator_id": "1100000000000000003",
+        "name": "Fixture party",
+        "description": "Everyone is invited",
+        "scheduled_start_time": "2024-09-01T18:00:00+00:00",
+        "scheduled_end_time": "2024-09-01T20:00:00+00:00",
+        "privacy_level": 2,
+        "status": 4,
+        "entity_type": 3,
+        "entity_id": null,
+        "entity_metadata": {"location": "The usual place"},
+        "user_count": 1
+    }"#;
+    GuildScheduledEventUserAdd => GUILD_SCHEDULED_EVENT_USER_ADD = r#"{
+        "guild_scheduled_event_id": "1100000000000000090",
+        "user_id": "1100000000000000004",
+        "guild_id": "1100000000000000001"
+    }"#;
+    GuildScheduledEventUserRemove => GUILD_SCHEDULED_EVENT_USER_REMOVE = r#"{
+        "guild_scheduled_event_id": "1100000000000000090",
+        "user_id": "1100000000000000004",
+        "guild_id": "1100000000000000001"
+    }"#;
+    GuildOnboardingPromptCreate => GUILD_ONBOARDING_PROMPT_CREATE = r#"{
+        "guild_id": "1100000000000000001",
+        "id": "1100000000000000100",
+        "type": 0,
+        "title": "Pick your roles",
+        "options": [],
+        "single_select": false,
+        "required": false,
+        "in_onboarding": true
+    }"#;
+    GuildOnboardingPromptUpdate => GUILD_ONBOARDING_PROMPT_UPDATE = r#"{
+        "guild_id": "1100000000000000001",
+        "id": "1100000000000000100",
+        "type": 0,
+        "title": "Pick some roles",
+        "options": [],
+        "single_select": false,
+        "required": false,
+        "in_onboarding": true
+    }"#;
+    GuildOnboardingPromptDelete => GUILD_ONBOARDING_PROMPT_DELETE = r#"{
+        "guild_id": "1100000000000000001",
+        "id": "1100000000000000100"
+    }"#;
+    PassiveUpdateV1 => PASSIVE_UPDATE_V1 = r#"{
+        "guild_id": "1100000000000000001",
+        "voice_states": [],
+        "members": [],
+        "channels": [{
+            "id": "1100000000000000002",
+            "last_message_id": "1100000000000000005",
+            "last_pin_timestamp": null
+        }]
+    }"#;
+    IntegrationCreate => INTEGRATION_CREATE = r#"{
+        "id": "1100000000000000110",
+        "guild_id": "1100000000000000001",
+        "name": "Fixture bot",
+        "type": "discord",
+        "enabled": true,
+        "account": {"id": "1100000000000000111", "name": "Fixture bot"},
+        "scopes": ["bot"]
+    }"#;
+    IntegrationUpdate => INTEGRATION_UPDATE = r#"{
+        "id": "1100000000000000110",
+        "guild_id": "1100000000000000001",
+        "name": "Fixture bot",
+        "type": "discord",
+        "enabled": false,
+        "account": {"id": "1100000000000000111", "name": "Fixture bot"},
+        "scopes": ["bot"]
+    }"#;
+    IntegrationDelete => INTEGRATION_DELETE = r#"{
+        "id": "1100000000000000110",
+        "guild_id": "1100000000000000001",
+        "application_id": "1100000000000000111"
+    }"#;
+    InteractionCreate => INTERACTION_CREATE = r#"{
+        "id": "1100000000000000120",
+        "type": 2,
+        "data": {"id": "1100000000000000121", "name": "ping", "type": 1},
+        "guild_id": "1100000000000000001",
+        "channel_id": "1100000000000000002",
+        "member_id": "1100000000000000004",
+        "token": "interaction-token",
+        "version": 1
+    }"#;
+    InviteCreate => INVITE_CREATE = r#"{
+        "code": "fixture",
+        "channel_id": "1100000000000000002",
+        "guild_id": "1100000000000000001",
+        "created_at": "2024-08-20T12:00:00+00:00",
+        "expires_at": null,
+        "inviter": {"id": "1100000000000000003", "username": "current_user", "discriminator": "0", "avatar": null},
+        "max_age": 0,
+        "max_uses": 0,
+        "temporary": false,
+        "uses": 0
+    }"#;
+    InviteDelete => INVITE_DELETE = r#"{
+        "channel_id": "1100000000000000002",
+        "guild_id": "1100000000000000001",
+        "code": "fixture"
+    }"#;
+    LastMessages => LAST_MESSAGES = r#"{
+        "guild_id": "1100000000000000001",
+        "messages": [{
+            "id": "1100000000000000005",
+            "channel_id": "1100000000000000002",
+            "author": {"id": "1100000000000000004", "username": "other_user", "discriminator": "0", "avatar": null},
+            "content": "Hello, world!",
+            "timestamp": "2024-08-20T12:00:00+00:00",
+            "edited_timestamp": null,
+            "tts": false,
+            "mention_everyone": false,
+            "mentions": [],
+            "mention_roles": [],
+            "attachments": [],
+            "embeds": [],
+            "pinned": false,
+            "type": 0,
+            "flags": 0
+        }]
+    }"#;
+    MessageCreate => MESSAGE_CREATE = r#"{
+        "id": "1100000000000000005",
+        "channel_id": "1100000000000000002",
+        "guild_id": "1100000000000000001",
+        "author": {"id": "1100000000000000004", "username": "other_user", "discriminator": "0", "avatar": null},
+        "member": {
+            "roles": [],
+            "joined_at": "2024-08-20T12:00:00+00:00",
+            "deaf": false,
+            "mute": false,
+            "flags": 0
+        },
+        "content": "Hello, world!",
+        "timestamp": "2024-08-20T12:00:00+00:00",
+        "edited_timestamp": null,
+        "tts": false,
+        "mention_everyone": false,
+        "mentions": [],
+        "mention_roles": [],
+        "attachments": [],
+        "embeds": [],
+        "pinned": false,
+        "type": 0,
+        "flags": 0,
+        "nonce": "1100000000000000009"
+    }"#;
+    MessageUpdate => MESSAGE_UPDATE = r#"{
+        "id": "1100000000000000005",
+        "channel_id": "1100000000000000002",
+        "guild_id": "1100000000000000001",
+        "author": {"id": "1100000000000000004", "username": "other_user", "discriminator": "0", "avatar": null},
+        "content": "Hello, edited world!",
+        "timestamp": "2024-08-20T12:00:00+00:00",
+        "edited_timestamp": "2024-08-20T12:05:00+00:00",
+        "tts": false,
+        "mention_everyone": false,
+        "mentions": [],
+        "mention_roles": [],
+        "attachments": [],
+        "embeds": [],
+        "pinned": false,
+        "type": 0,
+        "flags": 0
+    }"#;
+    MessageDelete => MESSAGE_DELETE = r#"{
+        "id": "1100000000000000005",
+        "channel_id": "1100000000000000002",
+        "guild_id": "1100000000000000001"
+    }"#;
+    MessageDeleteBulk => MESSAGE_DELETE_BULK = r#"{
+        "ids": ["1100000000000000005", "1100000000000000007"],
+        "channel_id": "1100000000000000002",
+        "guild_id": "1100000000000000001"
+    }"#;
+    MessageReactionAdd => MESSAGE_REACTION_ADD = r#"{
+        "user_id": "1100000000000000004",
+        "channel_id": "1100000000000000002",
+        "message_id": "1100000000000000005",
+        "guild_id": "1100000000000000001",
+        "emoji": {"id": "1100000000000000080", "name": "fixture", "animated": false}
+    }"#;
+    MessageReactionRemove => MESSAGE_REACTION_REMOVE = r#"{
+        "user_id": "1100000000000000004",
+        "channel_id": "1100000000000000002",
+        "message_id": "1100000000000000005",
+        "guild_id": "1100000000000000001",
+        "emoji": {"id": "1100000000000000080", "name": "fixture", "animated": false}
+    }"#;
+    MessageReactionRemoveAll => MESSAGE_REACTION_REMOVE_ALL = r#"{
+        "channel_id": "1100000000000000002",
+        "message_id": "1100000000000000005",
+        "guild_id": "1100000000000000001"
+    }"#;
+    MessageReactionRemoveEmoji => MESSAGE_REACTION_REMOVE_EMOJI = r#"{
+        "channel_id": "1100000000000000002",
+        "message_id": "1100000000000000005",
+        "guild_id": "1100000000000000001",
+        "emoji": {"id": "1100000000000000080", "name": "fixture", "animated": false}
+    }"#;
+    MessagePollVoteAdd => MESSAGE_POLL_VOTE_ADD = r#"{
+        "user_id": "1100000000000000004",
+        "channel_id": "1100000000000000002",
+        "message_id": "1100000000000000005",
+        "guild_id": "1100000000000000001",
+        "answer_id": 1
+    }"#;
+    MessagePollVoteRemove => MESSAGE_POLL_VOTE_REMOVE = r#"{
+        "user_id": "1100000000000000004",
+        "channel_id": "1100000000000000002",
+        "message_id": "1100000000000000005",
+        "guild_id": "1100000000000000001",
+        "answer_id": 1
+    }"#;
+    RecentMentionDelete => RECENT_MENTION_DELETE = r#"{
+        "message_id": "1100000000000000005"
+    }"#;
+    MessageACK => MESSAGE_ACK = r#"{
+        "version": 1,
+        "message_id": "1100000000000000005",
+        "last_viewed": null,
+        "flags": null,
+        "channel_id": "1100000000000000002"
+    }"#;
+    PresenceUpdate => PRESENCE_UPDATE = r#"{
+        "user": {"id": "1100000000000000004"},
+        "guild_id": "1100000000000000001",
+        "status": "online",
+        "activities": [],
+        "client_status": {"desktop": "online"}
+    }"#;
+    RelationshipAdd => RELATIONSHIP_ADD = r#"{
+        "id": "1100000000000000004",
+        "type": 1,
+        "nickname": null,
+        "user": {"id": "1100000000000000004", "username": "other_user", "discriminator": "0", "avatar": null},
+        "since": "2024-08-20T12:00:00+00:00",
+        "should_notify": false
+    }"#;
+    RelationshipRemove => RELATIONSHIP_REMOVE = r#"{
+        "id": "1100000000000000004",
+        "type": 1
+    }"#;
+    StageInstanceCreate => STAGE_INSTANCE_CREATE = r#"{
+        "id": "1100000000000000130",
+        "guild_id": "1100000000000000001",
+        "channel_id": "1100000000000000131",
+        "topic": "Fixture talk",
+        "privacy_level": 2,
+        "discoverable_disabled": false,
+        "guild_scheduled_event_id": null
+    }"#;
+    StageInstanceUpdate => STAGE_INSTANCE_UPDATE = r#"{
+        "id": "1100000000000000130",
+        "guild_id": "1100000000000000001",
+        "channel_id": "1100000000000000131",
+        "topic": "Another fixture talk",
+        "privacy_level": 2,
+        "discoverable_disabled": false,
+        "guild_scheduled_event_id": null
+    }"#;
+    StageInstanceDelete => STAGE_INSTANCE_DELETE = r#"{
+        "id": "1100000000000000130",
+        "guild_id": "1100000000000000001",
+        "channel_id": "1100000000000000131",
+        "topic": "Another fixture talk",
+        "privacy_level": 2,
+        "discoverable_disabled": false,
+        "guild_scheduled_event_id": null
+    }"#;
+    TypingStartEvent => TYPING_START = r#"{
+        "channel_id": "1100000000000000002",
+        "guild_id": "1100000000000000001",
+        "user_id": "1100000000000000004",
+        "timestamp": 1724155200
+    }"#;
+    UserUpdate => USER_UPDATE = r#"{
+        "id": "1100000000000000003",
+        "username": "current_user",
+        "discriminator": "0",
+        "global_name": "Renamed User",
+        "avatar": null,
+        "bot": false
+    }"#;
+    UserConnectionsUpdate => USER_CONNECTIONS_UPDATE = r#"{
+        "id": "fixture-account",
+        "type": "github",
+        "name": "fixture-account",
+        "verified": true,
+        "revoked": false,
+        "visibility": 1,
+        "show_activity": true,
+        "friend_sync": false,
+        "two_way_link": false,
+        "metadata_visibility": 1,
+        "integrations": []
+    }"#;
+    UserNoteUpdate => USER_NOTE_UPDATE = r#"{
+        "id": "1100000000000000004",
+        "note": "Met at the fixture party"
+    }"#;
+    UserPremiumGuildSubscriptionSlotCreate => USER_PREMIUM_GUILD_SUBSCRIPTION_SLOT_CREATE = r#"{
+        "id": "1100000000000000140",
+        "subscription_id": "1100000000000000141",
+        "premium_guild_subscription": null,
+        "canceled": false,
+        "cooldown_ends_at": null
+    }"#;
+    UserPremiumGuildSubscriptionSlotUpdate => USER_PREMIUM_GUILD_SUBSCRIPTION_SLOT_UPDATE = r#"{
+        "id": "1100000000000000140",
+        "subscription_id": "1100000000000000141",
+        "premium_guild_subscription": {
+            "id": "1100000000000000142",
+            "guild_id": "1100000000000000001",
+            "user_id": "1100000000000000003",
+            "ended": false
+        },
+        "canceled": false,
+        "cooldown_ends_at": null
+    }"#;
+    UserGuildSettingsUpdate => USER_GUILD_SETTINGS_UPDATE = r#"{
+        "version": 2,
+        "suppress_roles": false,
+        "suppress_everyone": false,
+        "notify_highlights": 0,
+        "muted": false,
+        "mute_scheduled_events": false,
+        "mute_config": null,
+        "mobile_push": true,
+        "message_notifications": 1,
+        "hide_muted_channels": false,
+        "guild_id": "1100000000000000001",
+        "flags": 0,
+        "channel_overrides": [{
+            "muted": false,
+            "mute_config": null,
+            "message_notifications": 3,
+            "flags": 4096,
+            "collapsed": false,
+            "channel_id": "1100000000000000002"
+        }]
+    }"#;
+    UserSettingsUpdate => USER_SETTINGS_UPDATE = r#"{
+        "theme": "light",
+        "status": "dnd",
+        "custom_status": null
+    }"#;
+    UserSettingsProtoUpdate => USER_SETTINGS_PROTO_UPDATE = r#"{
+        "settings": {
+            "proto": "CgIIAQ==",
+            "type": 1
+        },
+        "partial": true
+    }"#;
+    VoiceStateUpdate => VOICE_STATE_UPDATE = r#"{
+        "guild_id": "1100000000000000001",
+        "channel_id": "1100000000000000131",
+        "user_id": "1100000000000000004",
+        "session_id": "9b8f1c3e5d7a4b2c9e0f1a2b3c4d5e6f",
+        "deaf": false,
+        "mute": false,
+        "self_deaf": false,
+        "self_mute": true,
+        "self_video": false,
+        "suppress": false,
+        "request_to_speak_timestamp": null
+    }"#;
+    VoiceServerUpdate => VOICE_SERVER_UPDATE = r#"{
+        "token": "voice-token",
+        "guild_id": "1100000000000000001",
+        "channel_id": null,
+        "endpoint": "rotterdam1234.discord.media:443"
+    }"#;
+    WebhooksUpdate => WEBHOOKS_UPDATE = r#"{
+        "guild_id": "1100000000000000001",
+        "channel_id": "1100000000000000002"
+    }"#;
+}
+
+impl EventFixture for types::MessageUpdateCached {
+    fn fixture() -> Self {
+        let cached = types::MessageCreate::fixture().message;
+        types::MessageUpdateCached {
+            cached: Some(cached),
+            raw: types::MessageUpdate::fixture(),
+        }
+    }
+}
+
+impl EventFixture for types::MessageDeleteCached {
+    fn fixture() -> Self {
+        types::MessageDeleteCached {
+            cached: Some(types::MessageCreate::fixture().message),
+            raw: types::MessageDelete::fixture(),
+        }
+    }
+}
+
+impl EventFixture for types::MessageDeleteBulkCached {
+    fn fixture() -> Self {
+        types::MessageDeleteBulkCached {
+            // Only the first message was cached
+            cached: vec![types::MessageCreate::fixture().message],
+            raw: types::MessageDeleteBulk::fixture(),
+        }
+    }
+}
+
+impl EventFixture for types::UnknownEvent {
+    fn fixture() -> Self {
+        types::UnknownEvent {
+            event_name: "FIXTURE_EVENT".to_string(),
+            data: RawValue::from_string(r#"{"guild_id": "1100000000000000001"}"#.to_string())
+                .unwrap(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{de::DeserializeOwned, Serialize};
+
+    use super::*;
+    use crate::gateway::Event;
+
+    pub(super) fn check_round_trip<T: Serialize + DeserializeOwned>(name: &str, payload: &str) {
+        let event: T = serde_json::from_str(payload)
+            .unwrap_or_else(|e| panic!("{name} fixture does not deserialize: {e}"));
+        let serialized = serde_json::to_value(&event).unwrap();
+
+        let reparsed: T = serde_json::from_value(serialized.clone())
+            .unwrap_or_else(|e| panic!("Serialized {name} fixture does not deserialize: {e}"));
+        assert_eq!(
+            serde_json::to_value(&reparsed).unwrap(),
+            serialized,
+            "{name} fixture does not round trip"
+        );
+    }
+
+    #[test]
+    fn payloads_round_trip() {
+        check_payload_round_trips();
+    }
+
+    #[test]
+    fn fixture_for_every_event() {
+        let events = Event::fixtures();
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, Event::MessageDeleteBulkCached(_))));
+        assert!(events.len() > PAYLOADS.len());
+    }
+}
@@ -2,6 +2,8 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::time::Duration;
 
 use flate2::Decompress;
@@ -16,8 +18,8 @@ use super::*;
 use super::{Sink, Stream};
 use crate::types::{
     self, AutoModerationRule, AutoModerationRuleUpdate, Channel, ChannelCreate, ChannelDelete,
-    ChannelUpdate, CloseCode, GatewayInvalidSession, GatewayReconnect, Guild, GuildRoleCreate,
-    GuildRoleUpdate, JsonField, Opcode, RoleObject, SourceUrlField, ThreadUpdate, UpdateMessage,
+    ChannelPinsUpdate, ChannelUpdate, CloseCode, GatewayInvalidSession, GatewayReconnect, Guild, GuildRoleCreate,
+    GuildRoleUpdate, IntoShared, Opcode, RoleObject, Shared, SourceUrlField, ThreadUpdate, UpdateMessage,
     WebSocketEvent,
 };
 
@@ -31,18 +33,118 @@ const ZLIB_SUFFIX: [u8; 4] = [0, 0, 255, 255];
 #[derive(Debug)]
 pub struct Gateway {
     events: Arc<Mutex<Events>>,
-    heartbeat_handler: HeartbeatHandler,
     websocket_send: Arc<Mutex<Sink>>,
     websocket_receive: Stream,
     kill_send: tokio::sync::broadcast::Sender<()>,
     kill_receive: tokio::sync::broadcast::Receiver<()>,
-    store: Arc<Mutex<HashMap<Snowflake, Arc<RwLock<ObservableObject>>>>>,
-    /// Url which was used to initialize the gateway
-    url: String,
+    /// Tells the listener task to close the connection normally, see [GatewayHandle::shutdown]
+    shutdown_receive: tokio::sync::broadcast::Receiver<()>,
+    /// Dropped once the listener task stops, see [GatewayHandle::closed]
+    _task_guard: TaskGuard,
+    dispatcher: GatewayDispatcher,
+    /// Queue of dispatches waiting to be handled, if using [GatewayDispatchStrategy::Queued]
+    dispatch_queue: Option<tokio::sync::mpsc::Sender<GatewayMessage>>,
+    dispatch_metrics: Arc<DispatchCounters>,
     /// Options which were used to initialize the gateway
     options: GatewayOptions,
     zlib_inflate: Option<flate2::Decompress>,
     zlib_buffer: Option<Vec<u8>>,
+    /// Detects dead connections, see [GatewayOptions::keepalive]
+    keepalive: Option<KeepaliveTracker>,
+    #[cfg(target_arch = "wasm32")]
+    visibility_listener: Option<VisibilityChangeListener>,
+    /// Receives a message whenever the page becomes visible again, see
+    /// [GatewayOptions::resume_on_visibility_change]
+    #[cfg(target_arch = "wasm32")]
+    page_visible: tokio::sync::mpsc::Receiver<()>,
+    /// When we last received anything from the gateway
+    #[cfg(target_arch = "wasm32")]
+    last_received: wasmtimer::std::Instant,
+    #[cfg(target_arch = "wasm32")]
+    resume_info: Shared<ResumeInfo>,
+}
+
+/// What we need to know to resume the session of a gateway connection, see
+/// [GatewayOptions::resume_on_visibility_change]
+#[cfg(target_arch = "wasm32")]
+#[derive(Debug, Clone, Default)]
+pub(super) struct ResumeInfo {
+    /// The token the session was identified with, see [GatewayHandle::send_identify]
+    pub(super) token: Option<String>,
+    pub(super) session_id: Option<String>,
+    pub(super) resume_gateway_url: Option<String>,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl ResumeInfo {
+    /// Subscribes the info to the [GatewayReady] event, which starts the session.
+    fn subscribe(info: Shared<ResumeInfo>, events: &mut Events) {
+        events
+            .session
+            .ready
+            .subscribe(Arc::new(ResumeInfoObserver { info }));
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+#[derive(Debug)]
+struct ResumeInfoObserver {
+    info: Shared<ResumeInfo>,
+}
+
+#[cfg(target_arch = "wasm32")]
+#[async_trait::async_trait]
+impl Observer<types::GatewayReady> for ResumeInfoObserver {
+    async fn update(&self, data: &types::GatewayReady) {
+        let mut info = self.info.write().unwrap();
+        info.session_id = Some(data.session_id.clone());
+        info.resume_gateway_url = Some(data.resume_gateway_url.clone());
+    }
+}
+
+/// Handles gateway messages once they have been received and decompressed.
+///
+/// Depending on the [GatewayDispatchStrategy], dispatches are either handled by the gateway
+/// listener task itself or by a separate dispatch task.
+#[derive(Debug, Clone)]
+struct GatewayDispatcher {
+    events: Arc<Mutex<Events>>,
+    heartbeat_handler: HeartbeatHandler,
+    store: Arc<Mutex<HashMap<Snowflake, Arc<RwLock<ObservableObject>>>>>,
+    message_cache: Shared<MessageCache>,
+    event_stream: EventStreamSender,
+    stats: Shared<GatewayStats>,
+    /// Url which was used to initialize the gateway
+    url: String,
+}
+
+/// Counters behind [DispatchMetrics], shared between the gateway tasks and [GatewayHandle]s.
+#[derive(Debug, Default)]
+pub(crate) struct DispatchCounters {
+    queued: AtomicUsize,
+    full: AtomicU64,
+}
+
+impl DispatchCounters {
+    /// Returns a snapshot of the current counter values
+    pub(crate) fn snapshot(&self) -> DispatchMetrics {
+        DispatchMetrics {
+            queued: self.queued.load(Ordering::Relaxed),
+            full: self.full.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+/// Statistics about the dispatch queue of a gateway connection.
+///
+/// Only tracked when using [GatewayDispatchStrategy::Queued]; otherwise all values are 0.
+pub struct DispatchMetrics {
+    /// How many dispatches are currently waiting to be published
+    pub queued: usize,
+    /// How many times the queue was full since the connection was opened, meaning receiving
+    /// had to wait for the observers to catch up
+    pub full: u64,
 }
 
 impl Gateway {
@@ -51,15 +153,29 @@ impl Gateway {
     ///
     /// # Note
     /// The websocket url should begin with the prefix wss:// or ws:// (for unsecure connections)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "chorus.gateway.connect", skip_all, fields(url = %websocket_url))
+    )]
     pub async fn spawn(
         websocket_url: &str,
         options: GatewayOptions,
     ) -> Result<GatewayHandle, GatewayError> {
+        #[cfg(not(target_arch = "wasm32"))]
+        if options.danger_accept_invalid_certs {
+            return Gateway::spawn_with_connector(
+                websocket_url,
+                options,
+                WebSocketConnector::default(),
+            )
+            .await;
+        }
+
         let url = options.add_to_url(websocket_url);
 
         debug!("GW: Connecting to {}", url);
 
-        let (websocket_send, mut websocket_receive) = match WebSocketBackend::connect(&url).await {
+        let (websocket_send, websocket_receive) = match WebSocketBackend::connect(&url).await {
             Ok(streams) => streams,
             Err(e) => {
                 return Err(GatewayError::CannotConnect {
@@ -68,11 +184,63 @@ impl Gateway {
             }
         };
 
+        Gateway::start(url, options, websocket_send, websocket_receive).await
+    }
+
+    /// Creates / opens a new gateway connection, using a custom [WebSocketConnector].
+    ///
+    /// This can be used to connect through a SOCKS5 or HTTP proxy, or with a custom TLS
+    /// configuration.
+    ///
+    /// # Note
+    /// The websocket url should begin with the prefix wss:// or ws:// (for unsecure connections)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "chorus.gateway.connect", skip_all, fields(url = %websocket_url))
+    )]
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn spawn_with_connector(
+        websocket_url: &str,
+        options: GatewayOptions,
+        mut connector: WebSocketConnector,
+    ) -> Result<GatewayHandle, GatewayError> {
+        if options.danger_accept_invalid_certs && connector.tls.is_none() {
+            warn!("GW: Accepting invalid TLS certificates, the connection is not secure");
+            connector.tls = Some(WebSocketBackend::insecure_tls_connector());
+        }
+
+        let url = options.add_to_url(websocket_url);
+
+        debug!("GW: Connecting to {}", url);
+
+        let (websocket_send, websocket_receive) =
+            match WebSocketBackend::connect_with(&url, &connector).await {
+                Ok(streams) => streams,
+                Err(e) => {
+                    return Err(GatewayError::CannotConnect {
+                        error: format!("{:?}", e),
+                    });
+                }
+            };
+
+        Gateway::start(url, options, websocket_send, websocket_receive).await
+    }
+
+    /// Starts the gateway tasks on an already opened websocket connection.
+    async fn start(
+        url: String,
+        options: GatewayOptions,
+        websocket_send: Sink,
+        mut websocket_receive: Stream,
+    ) -> Result<GatewayHandle, GatewayError> {
         let shared_websocket_send = Arc::new(Mutex::new(websocket_send));
 
         // Create a shared broadcast channel for killing all gateway tasks
         let (kill_send, mut _kill_receive) = tokio::sync::broadcast::channel::<()>(16);
 
+        let (shutdown_send, shutdown_receive) = tokio::sync::broadcast::channel::<()>(1);
+        let (tasks, task_guard) = TaskTracker::new();
+
         // Wait for the first hello and then spawn both tasks so we avoid nested tasks
         // This automatically spawns the heartbeat task, but from the main thread
         #[cfg(not(target_arch = "wasm32"))]
@@ -81,75 +249,109 @@ impl Gateway {
             //
             // Hence why wasm receives straight RawGatewayMessages, and tungstenite receives
             // GatewayCommunications.
-            let communication: GatewayCommunication =
-                websocket_receive.next().await.unwrap().unwrap().into();
+            loop {
+                let communication: GatewayCommunication =
+                    websocket_receive.next().await.unwrap().unwrap().into();
 
-            match communication {
-                GatewayCommunication::Message(message) => message,
-                GatewayCommunication::Error(error) => return Err(error.into()),
+                match communication {
+                    GatewayCommunication::Message(message) => break message,
+                    GatewayCommunication::Error(error) => return Err(error.into()),
+                    GatewayCommunication::KeepAlive => continue,
+                }
             }
         };
         #[cfg(target_arch = "wasm32")]
         let received: RawGatewayMessage = websocket_receive.0.next().await.unwrap().into();
 
-        let message: GatewayMessage;
+        let (gateway_hello, zlib_inflate) =
+            Self::read_hello(received, options.transport_compression)?;
+        let zlib_buffer = zlib_inflate.as_ref().map(|_| Vec::new());
 
-        let zlib_buffer;
-        let zlib_inflate;
+        let mut events = Events::default();
 
-        match options.transport_compression {
-            GatewayTransportCompression::None => {
-                zlib_buffer = None;
-                zlib_inflate = None;
-                message = GatewayMessage::from_raw_json_message(received).unwrap();
-            }
-            GatewayTransportCompression::ZLibStream => {
-                zlib_buffer = Some(Vec::new());
-                let mut inflate = Decompress::new(true);
+        let cache = UserCache::default().into_shared();
+        UserCache::subscribe(cache.clone(), &mut events);
 
-                message =
-                    GatewayMessage::from_zlib_stream_json_message(received, &mut inflate).unwrap();
+        let voice_states = VoiceStateCache::default().into_shared();
+        VoiceStateCache::subscribe(voice_states.clone(), &mut events);
 
-                zlib_inflate = Some(inflate);
-            }
-        }
+        #[cfg(target_arch = "wasm32")]
+        let resume_info = ResumeInfo::default().into_shared();
+        #[cfg(target_arch = "wasm32")]
+        ResumeInfo::subscribe(resume_info.clone(), &mut events);
 
-        let gateway_payload: types::GatewayReceivePayload =
-            serde_json::from_str(&message.0).unwrap();
+        let shared_events = Arc::new(Mutex::new(events));
 
-        if gateway_payload.op_code != (Opcode::Hello as u8) {
-			   warn!("GW: Received a non-hello opcode ({}) on gateway init", gateway_payload.op_code);
-            return Err(GatewayError::NonHelloOnInitiate {
-                opcode: gateway_payload.op_code,
-            });
-        }
+        let message_cache = MessageCache::new(options.message_cache_size).into_shared();
 
-        debug!("GW: Received Hello");
+        let event_stream = EventStreamSender::new();
 
-        let gateway_hello: types::HelloData =
-            serde_json::from_str(gateway_payload.event_data.unwrap().get()).unwrap();
+        let store = Arc::new(Mutex::new(HashMap::new()));
 
-        let events = Events::default();
-        let shared_events = Arc::new(Mutex::new(events));
+        let dispatch_metrics = Arc::new(DispatchCounters::default());
 
-        let store = Arc::new(Mutex::new(HashMap::new()));
+        let stats = GatewayStats::default().into_shared();
+
+        let heartbeat_handler = HeartbeatHandler::new(
+            Duration::from_millis(gateway_hello.heartbeat_interval),
+            options.heartbeat_jitter,
+            shared_websocket_send.clone(),
+            kill_send.subscribe(),
+            stats.clone(),
+            task_guard.clone(),
+        );
+
+        #[cfg(target_arch = "wasm32")]
+        let (page_visible_send, page_visible) = tokio::sync::mpsc::channel(1);
+        #[cfg(target_arch = "wasm32")]
+        let visibility_listener = match options.resume_on_visibility_change {
+            true => VisibilityChangeListener::new(page_visible_send),
+            false => None,
+        };
+
+        let dispatcher = GatewayDispatcher {
+            events: shared_events.clone(),
+            heartbeat_handler,
+            store: store.clone(),
+            message_cache: message_cache.clone(),
+            event_stream: event_stream.clone(),
+            stats: stats.clone(),
+            url: url.clone(),
+        };
+
+        let dispatch_queue = match options.dispatch_strategy {
+            GatewayDispatchStrategy::Inline => None,
+            GatewayDispatchStrategy::Queued { capacity } => Some(Self::spawn_dispatch_task(
+                dispatcher.clone(),
+                dispatch_metrics.clone(),
+                capacity,
+                task_guard.clone(),
+            )),
+        };
 
         let mut gateway = Gateway {
             events: shared_events.clone(),
-            heartbeat_handler: HeartbeatHandler::new(
-                Duration::from_millis(gateway_hello.heartbeat_interval),
-                shared_websocket_send.clone(),
-                kill_send.subscribe(),
-            ),
             websocket_send: shared_websocket_send.clone(),
             websocket_receive,
             kill_send: kill_send.clone(),
             kill_receive: kill_send.subscribe(),
-            store: store.clone(),
-            url: url.clone(),
+            shutdown_receive,
+            _task_guard: task_guard,
+            dispatcher,
+            dispatch_queue,
+            dispatch_metrics: dispatch_metrics.clone(),
+            keepalive: options.keepalive.map(KeepaliveTracker::new),
             options,
             zlib_inflate,
             zlib_buffer,
+            #[cfg(target_arch = "wasm32")]
+            visibility_listener,
+            #[cfg(target_arch = "wasm32")]
+            page_visible,
+            #[cfg(target_arch = "wasm32")]
+            last_received: wasmtimer::std::Instant::now(),
+            #[cfg(target_arch = "wasm32")]
+            resume_info: resume_info.clone(),
         };
 
         // Now we can continuously check for messages in a different task, since we aren't going to receive another hello
@@ -167,24 +369,142 @@ impl Gateway {
             events: shared_events,
             websocket_send: shared_websocket_send.clone(),
             kill_send: kill_send.clone(),
+            shutdown_send,
+            tasks,
             store,
+            cache,
+            voice_states,
+            message_cache,
+            event_stream,
+            dispatch_metrics,
+            stats,
+            identify_limiter: IdentifyLimiter::new(),
+            #[cfg(target_arch = "wasm32")]
+            resume_info,
         })
     }
 
+    /// Reads the hello message a new connection starts with.
+    ///
+    /// Returns the hello data, along with the zlib stream needed to decompress the following
+    /// messages if the connection is compressed.
+    fn read_hello(
+        received: RawGatewayMessage,
+        transport_compression: GatewayTransportCompression,
+    ) -> Result<(types::HelloData, Option<Decompress>), GatewayError> {
+        let message: GatewayMessage;
+
+        let zlib_inflate;
+
+        match transport_compression {
+            GatewayTransportCompression::None => {
+                zlib_inflate = None;
+                message = GatewayMessage::from_raw_json_message(received).map_err(|e| {
+                    warn!("GW: Received a hello which is not valid utf-8: {}", e);
+                    GatewayError::Decode
+                })?;
+            }
+            GatewayTransportCompression::ZLibStream => {
+                let mut inflate = Decompress::new(true);
+
+                message = GatewayMessage::from_zlib_stream_json_message(received, &mut inflate)
+                    .map_err(|e| {
+                        warn!("GW: Could not decompress the hello: {}", e);
+                        GatewayError::Decode
+                    })?;
+
+                zlib_inflate = Some(inflate);
+            }
+        }
+
+        let gateway_payload: types::GatewayReceivePayload = serde_json::from_str(&message.0)
+            .map_err(|e| {
+                warn!("GW: Could not parse the hello payload: {}", e);
+                GatewayError::Decode
+            })?;
+
+        if gateway_payload.op_code != (Opcode::Hello as u8) {
+			   warn!("GW: Received a non-hello opcode ({}) on gateway init", gateway_payload.op_code);
+            return Err(GatewayError::NonHelloOnInitiate {
+                opcode: gateway_payload.op_code,
+            });
+        }
+
+        debug!("GW: Received Hello");
+
+        let Some(event_data) = gateway_payload.event_data else {
+            warn!("GW: Received a hello without data");
+            return Err(GatewayError::Decode);
+        };
+
+        let gateway_hello: types::HelloData =
+            serde_json::from_str(event_data.get()).map_err(|e| {
+                warn!("GW: Could not parse the hello data: {}", e);
+                GatewayError::Decode
+            })?;
+
+        Ok((gateway_hello, zlib_inflate))
+    }
+
+    /// Spawns the task which handles queued dispatches for [GatewayDispatchStrategy::Queued].
+    ///
+    /// Returns the sending half of the queue. The task stops once it is dropped.
+    fn spawn_dispatch_task(
+        dispatcher: GatewayDispatcher,
+        metrics: Arc<DispatchCounters>,
+        capacity: usize,
+        task_guard: TaskGuard,
+    ) -> tokio::sync::mpsc::Sender<GatewayMessage> {
+        // Tokio's channels panic if created with no capacity
+        let (send, mut receive) = tokio::sync::mpsc::channel::<GatewayMessage>(capacity.max(1));
+
+        let task = async move {
+            while let Some(message) = receive.recv().await {
+                metrics.queued.fetch_sub(1, Ordering::Relaxed);
+                dispatcher.handle_message(message).await;
+                yield_now().await;
+            }
+            log::trace!("GW: Closing dispatch task");
+            drop(task_guard);
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        task::spawn(task);
+        #[cfg(target_arch = "wasm32")]
+        wasm_bindgen_futures::spawn_local(task);
+
+        send
+    }
+
     /// The main gateway listener task for a tungstenite based gateway;
     #[cfg(not(target_arch = "wasm32"))]
     async fn gateway_listen_task_tungstenite(&mut self) {
         loop {
             let msg;
+            let deadline = self.keepalive.as_ref().map(KeepaliveTracker::deadline);
 
             tokio::select! {
                 Ok(_) = self.kill_receive.recv() => {
                     log::trace!("GW: Closing listener task");
                     break;
                 }
+                Ok(_) = self.shutdown_receive.recv() => {
+                    self.shutdown().await;
+                    break;
+                }
                 message = self.websocket_receive.next() => {
                     msg = message;
                 }
+                () = keepalive_deadline(deadline) => {
+                    if self.handle_keepalive_deadline().await {
+                        continue;
+                    }
+                    break;
+                }
+            }
+
+            if let Some(keepalive) = self.keepalive.as_mut() {
+                keepalive.received();
             }
 
             // Note: The tungstenite backend handles close codes as messages, while the ws_stream_wasm one handles them differently.
@@ -201,6 +521,7 @@ impl Gateway {
                     GatewayCommunication::Error(close_code) => {
                         self.handle_close_code(close_code).await
                     }
+                    GatewayCommunication::KeepAlive => {}
                 }
 
                 continue;
@@ -219,29 +540,35 @@ impl Gateway {
     #[cfg(target_arch = "wasm32")]
     async fn gateway_listen_task_wasm(&mut self) {
         // Initiate the close event listener
-        let mut close_events = self
-            .websocket_receive
-            .1
-            .observe(pharos::Filter::Pointer(ws_stream_wasm::WsEvent::is_closed).into())
-            .await
-            .unwrap();
+        let mut close_events = self.observe_close_events().await;
+        // Whether the connection closed while the page was hidden, and waits to be resumed
+        let mut connection_closed = false;
 
         loop {
             let msg;
+            let deadline = self.keepalive.as_ref().map(KeepaliveTracker::deadline);
 
             tokio::select! {
                  Ok(_) = self.kill_receive.recv() => {
                       log::trace!("GW: Closing listener task");
                       break;
                  }
-                 message = self.websocket_receive.0.next() => {
+                 Ok(_) = self.shutdown_receive.recv() => {
+                      self.shutdown().await;
+                      break;
+                 }
+                 message = self.websocket_receive.0.next(), if !connection_closed => {
                       msg = message;
                  }
-                 maybe_event = close_events.next() => {
+                 maybe_event = close_events.next(), if !connection_closed => {
                       if let Some(event) = maybe_event {
                               match event {
                                     ws_stream_wasm::WsEvent::Closed(closed_event) => {
                                         let close_code = CloseCode::try_from(closed_event.code).unwrap_or(CloseCode::UnknownError);
+                                        if self.resume_when_visible(&GatewayError::from(close_code)) {
+                                            connection_closed = true;
+                                            continue;
+                                        }
                                         self.handle_close_code(close_code).await;
                                         break;
                                     }
@@ -250,8 +577,43 @@ impl Gateway {
                       }
                       continue;
                 }
+                 Some(()) = self.page_visible.recv() => {
+                      if !connection_closed && !self.connection_is_stale() {
+                          trace!("GW: Page became visible, requesting heartbeat");
+                          self.send_keepalive_ping().await;
+                          continue;
+                      }
+
+                      info!("GW: Connection was lost while the page was hidden, resuming session");
+
+                      if let Err(error) = self.resume().await {
+                          warn!("GW: Couldn't resume session: {}", error);
+                          self.close_lost_connection().await;
+                          self.events.lock().await.error.publish(error).await;
+                          break;
+                      }
+
+                      close_events = self.observe_close_events().await;
+                      connection_closed = false;
+                      self.last_received = wasmtimer::std::Instant::now();
+                      if let Some(keepalive) = self.keepalive.as_mut() {
+                          keepalive.received();
+                      }
+                      continue;
+                 }
+                 () = keepalive_deadline(deadline), if !connection_closed => {
+                      if self.handle_keepalive_deadline().await {
+                          continue;
+                      }
+                      break;
+                 }
             }
 
+            if let Some(keepalive) = self.keepalive.as_mut() {
+                keepalive.received();
+            }
+            self.last_received = wasmtimer::std::Instant::now();
+
             // Note: The tungstenite backend handles close codes as messages, while the ws_stream_wasm one handles them as a seperate receiver.
             //
             // Hence why wasm receives RawGatewayMessages, and tungstenite receives
@@ -261,18 +623,181 @@ impl Gateway {
                 continue;
             }
 
+            if self.resume_when_visible(&GatewayError::ConnectionLost) {
+                connection_closed = true;
+                continue;
+            }
+
             // We couldn't receive the next message or it was an error, something is wrong with the websocket, close
             warn!("GW: Websocket is broken, stopping gateway");
             break;
         }
     }
 
+    /// Observes when the current websocket connection is closed
+    #[cfg(target_arch = "wasm32")]
+    async fn observe_close_events(&mut self) -> pharos::Events<ws_stream_wasm::WsEvent> {
+        self.websocket_receive
+            .1
+            .observe(pharos::Filter::Pointer(ws_stream_wasm::WsEvent::is_closed).into())
+            .await
+            .unwrap()
+    }
+
+    /// Whether the connection stopped responding, see [GatewayOptions::resume_on_visibility_change]
+    #[cfg(target_arch = "wasm32")]
+    fn connection_is_stale(&self) -> bool {
+        connection_is_stale(
+            self.last_received.elapsed(),
+            self.dispatcher.heartbeat_handler.heartbeat_interval,
+        )
+    }
+
+    /// Whether to resume the session once the page becomes visible again, instead of stopping
+    /// the gateway, after the connection closed with `error`.
+    ///
+    /// See [GatewayOptions::resume_on_visibility_change]
+    #[cfg(target_arch = "wasm32")]
+    fn resume_when_visible(&self, error: &GatewayError) -> bool {
+        let page_is_hidden = self
+            .visibility_listener
+            .as_ref()
+            .is_some_and(VisibilityChangeListener::page_is_hidden);
+
+        let resumable = page_is_hidden && error.is_resumable() && {
+            let info = self.resume_info.read().unwrap();
+            info.token.is_some() && info.session_id.is_some()
+        };
+
+        if resumable {
+            debug!(
+                "GW: Connection closed ({}) while the page is hidden, resuming once it is visible",
+                error
+            );
+        }
+
+        resumable
+    }
+
+    /// Opens a new connection to the gateway and resumes the session on it.
+    ///
+    /// See [GatewayOptions::resume_on_visibility_change]
+    #[cfg(target_arch = "wasm32")]
+    async fn resume(&mut self) -> Result<(), GatewayError> {
+        let info = self.resume_info.read().unwrap().clone();
+        let sequence_number = self.dispatcher.stats.read().unwrap().last_sequence_number;
+
+        let (Some(token), Some(session_id), Some(resume_gateway_url), Some(sequence_number)) = (
+            info.token,
+            info.session_id,
+            info.resume_gateway_url,
+            sequence_number,
+        ) else {
+            // We never identified or received READY, there is no session to resume
+            return Err(GatewayError::ConnectionLost);
+        };
+
+        let url = self.options.add_to_url(&resume_gateway_url);
+
+        debug!("GW: Resuming session on {}", url);
+
+        let (websocket_send, mut websocket_receive) = match WebSocketBackend::connect(&url).await {
+            Ok(streams) => streams,
+            Err(e) => {
+                return Err(GatewayError::CannotConnect {
+                    error: format!("{:?}", e),
+                });
+            }
+        };
+
+        let Some(received) = websocket_receive.0.next().await else {
+            return Err(GatewayError::CannotConnect {
+                error: String::from("The connection closed before the gateway sent hello"),
+            });
+        };
+
+        let (gateway_hello, zlib_inflate) =
+            Self::read_hello(received.into(), self.options.transport_compression)?;
+        let heartbeat_interval = Duration::from_millis(gateway_hello.heartbeat_interval);
+
+        let resume = types::GatewaySendPayload {
+            op_code: Opcode::Resume as u8,
+            event_data: Some(
+                serde_json::to_value(types::GatewayResume {
+                    token,
+                    session_id,
+                    seq: sequence_number.to_string(),
+                })
+                .unwrap(),
+            ),
+            sequence_number: None,
+        };
+        let resume = GatewayMessage(serde_json::to_string(&resume).unwrap());
+
+        {
+            let mut shared_websocket_send = self.websocket_send.lock().await;
+            let mut old_websocket_send =
+                std::mem::replace(&mut *shared_websocket_send, websocket_send);
+            // Browsers close the connection in the background
+            let _ = old_websocket_send.close().await;
+
+            trace!("GW: Sending Resume..");
+
+            if let Err(e) = shared_websocket_send.send(resume.into()).await {
+                return Err(GatewayError::CannotConnect {
+                    error: format!("{:?}", e),
+                });
+            }
+        }
+
+        self.websocket_receive = websocket_receive;
+        self.zlib_buffer = zlib_inflate.as_ref().map(|_| Vec::new());
+        self.zlib_inflate = zlib_inflate;
+
+        // Tells the heartbeat task to start sending heartbeats on the new connection, with the
+        // interval it asked for
+        self.dispatcher.heartbeat_handler.heartbeat_interval = heartbeat_interval;
+        let _ = self
+            .dispatcher
+            .heartbeat_handler
+            .send
+            .send(HeartbeatThreadCommunication {
+                op_code: Some(Opcode::Hello),
+                sequence_number: None,
+                heartbeat_interval: Some(heartbeat_interval),
+            })
+            .await;
+
+        Ok(())
+    }
+
     /// Closes the websocket connection and stops all tasks
     async fn close(&mut self) {
         self.kill_send.send(()).unwrap();
         self.websocket_send.lock().await.close().await.unwrap();
     }
 
+    /// Closes the websocket connection with close code 1000 and stops all other tasks, see
+    /// [GatewayHandle::shutdown]
+    async fn shutdown(&mut self) {
+        debug!("GW: Shutting down");
+
+        let close_result = WebSocketBackend::close_normally(
+            &mut *self.websocket_send.lock().await,
+            &self.websocket_receive,
+        )
+        .await;
+        if let Err(e) = close_result {
+            warn!("GW: Couldn't close the websocket cleanly: {:?}", e);
+        }
+
+        // Stops the heartbeat task; errors if it already stopped
+        let _ = self.kill_send.send(());
+
+        // The dispatch task publishes the dispatches still in the queue, then stops
+        self.dispatch_queue = None;
+    }
+
     /// Handles receiving a [CloseCode].
     ///
     /// Closes the connection and publishes an error event.
@@ -284,6 +809,84 @@ impl Gateway {
         self.events.lock().await.error.publish(error).await;
     }
 
+    /// Sends a ping or, if the last one was not responded to, declares the connection lost, see
+    /// [GatewayOptions::keepalive].
+    ///
+    /// Returns whether the connection is still usable.
+    async fn handle_keepalive_deadline(&mut self) -> bool {
+        let Some(keepalive) = self.keepalive.as_mut() else {
+            return true;
+        };
+
+        match keepalive.action() {
+            KeepaliveAction::Ping => {
+                trace!("GW: Connection is idle, sending keepalive ping");
+                keepalive.ping_sent();
+                self.send_keepalive_ping().await
+            }
+            KeepaliveAction::ConnectionLost => {
+                warn!("GW: Gateway did not respond to keepalive ping, connection was lost");
+                self.close_lost_connection().await;
+                self.events
+                    .lock()
+                    .await
+                    .error
+                    .publish(GatewayError::ConnectionLost)
+                    .await;
+                false
+            }
+        }
+    }
+
+    /// Sends a websocket ping, or a heartbeat on wasm, since browsers do not let us send pings.
+    ///
+    /// Returns whether it could be sent.
+    async fn send_keepalive_ping(&mut self) -> bool {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Err(e) = WebSocketBackend::send_ping(&mut *self.websocket_send.lock().await).await {
+            warn!(
+                "GW: Couldn't send keepalive ping, websocket seems broken: {:?}",
+                e
+            );
+            return false;
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            let communication = HeartbeatThreadCommunication {
+                op_code: Some(Opcode::Heartbeat),
+                sequence_number: None,
+                heartbeat_interval: None,
+            };
+            // If the channel is full, a heartbeat is about to be sent anyway
+            let _ = self
+                .dispatcher
+                .heartbeat_handler
+                .send
+                .try_send(communication);
+        }
+
+        true
+    }
+
+    /// Stops all tasks and closes the websocket, without waiting for the gateway, which
+    /// stopped responding
+    async fn close_lost_connection(&mut self) {
+        // Errors if the other tasks already stopped
+        let _ = self.kill_send.send(());
+
+        // Sending may block forever on a dead connection, so don't wait for the sink
+        let Ok(mut websocket_send) = self.websocket_send.try_lock() else {
+            return;
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let _ = tokio::time::timeout(Duration::from_secs(1), websocket_send.close()).await;
+        // Browsers close the connection in the background
+        #[cfg(target_arch = "wasm32")]
+        let _ = websocket_send.close().await;
+    }
+
     /// Deserializes and updates a dispatched event, when we already know its type;
     /// (Called for every event in handle_message)
     #[allow(dead_code)] // TODO: Remove this allow annotation
@@ -308,7 +911,21 @@ impl Gateway {
 
         match self.options.transport_compression {
             GatewayTransportCompression::None => {
-                message = GatewayMessage::from_raw_json_message(raw_message).unwrap()
+                match GatewayMessage::from_payload_compressed_json_message(raw_message) {
+                    Ok(decoded) => message = decoded,
+                    Err(e) => {
+                        warn!("GW: Skipping a payload which could not be decoded: {}", e);
+                        self.events
+                            .lock()
+                            .await
+                            .error
+                            .publish(GatewayError::InvalidPayload {
+                                error: e.to_string(),
+                            })
+                            .await;
+                        return;
+                    }
+                }
             }
             GatewayTransportCompression::ZLibStream => {
                 let message_bytes = raw_message.into_bytes();
@@ -332,11 +949,97 @@ impl Gateway {
             }
         };
 
-        self.handle_message(message).await;
+        self.dispatch_message(message).await;
+    }
+
+    /// Handles a [GatewayMessage] according to the [GatewayDispatchStrategy].
+    ///
+    /// Heartbeat related information is passed on to the heartbeat task before the message is
+    /// handled, so heartbeats stay on time even while large dispatches are being processed.
+    ///
+    /// When queueing, only dispatches are queued; everything else (heartbeat acks, reconnects,
+    /// ...) is still handled right away.
+    async fn dispatch_message(&mut self, message: GatewayMessage) {
+        let (op_code, sequence_number) = match message.payload() {
+            Ok(payload) => (Some(payload.op_code), payload.sequence_number),
+            Err(_) => (None, None),
+        };
+
+        self.dispatcher
+            .notify_heartbeat_handler(op_code, sequence_number)
+            .await;
+
+        let is_dispatch = op_code == Some(Opcode::Dispatch as u8);
+
+        let Some(queue) = &self.dispatch_queue else {
+            self.dispatcher.handle_message(message).await;
+
+            // Dispatches can take a while to handle; let the other tasks (most importantly the
+            // heartbeat task) run before we receive the next message
+            if is_dispatch {
+                yield_now().await;
+            }
+            return;
+        };
+
+        if !is_dispatch {
+            self.dispatcher.handle_message(message).await;
+            return;
+        }
+
+        // Count it before sending, so the dispatch task can never decrement below 0
+        self.dispatch_metrics.queued.fetch_add(1, Ordering::Relaxed);
+
+        let message = match queue.try_send(message) {
+            Ok(()) => return,
+            Err(tokio::sync::mpsc::error::TrySendError::Full(message)) => message,
+            Err(tokio::sync::mpsc::error::TrySendError::Closed(_)) => {
+                self.dispatch_metrics.queued.fetch_sub(1, Ordering::Relaxed);
+                return;
+            }
+        };
+
+        // Never drop events (they may be READY or GUILD_CREATE, which we can't do without);
+        // instead, stop receiving until the observers have caught up
+        self.dispatch_metrics.full.fetch_add(1, Ordering::Relaxed);
+        warn!("GW: Dispatch queue is full, waiting for it to drain. Some observers are too slow to keep up");
+
+        if queue.send(message).await.is_err() {
+            self.dispatch_metrics.queued.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl GatewayDispatcher {
+    /// Lets the heartbeat task know about a received message's sequence number, as well as
+    /// heartbeat requests and acks.
+    async fn notify_heartbeat_handler(&self, op_code: Option<u8>, sequence_number: Option<u64>) {
+        let op_code = match op_code.map(Opcode::try_from) {
+            Some(Ok(op_code @ (Opcode::Heartbeat | Opcode::HeartbeatAck))) => Some(op_code),
+            _ => None,
+        };
+
+        if op_code.is_none() && sequence_number.is_none() {
+            return;
+        }
+
+        let heartbeat_communication = HeartbeatThreadCommunication {
+            sequence_number,
+            op_code,
+            heartbeat_interval: None,
+        };
+
+        self.heartbeat_handler
+            .send
+            .send(heartbeat_communication)
+            .await
+            .unwrap();
     }
 
     /// This handles a message as a websocket event and updates its events along with the events' observers
-    async fn handle_message(&mut self, msg: GatewayMessage) {
+    ///
+    /// Heartbeat related information is not handled here, see [Self::notify_heartbeat_handler]
+    async fn handle_message(&self, msg: GatewayMessage) {
         if msg.0.is_empty() {
             return;
         }
@@ -352,6 +1055,28 @@ impl Gateway {
         let op_code_res = Opcode::try_from(gateway_payload.op_code);
 
         if op_code_res.is_err() {
+            let json = gateway_payload.event_data.map(|data| data.get()).unwrap_or("null");
+            let result = self
+                .events
+                .lock()
+                .await
+                .custom
+                .publish_opcode(gateway_payload.op_code, json)
+                .await;
+
+            match result {
+                Some(Ok(())) => return,
+                Some(Err(err)) => {
+                    warn!(
+                        "Failed to parse custom gateway op code {} ({err})",
+                        gateway_payload.op_code
+                    );
+                    trace!("Event data: {json}");
+                    return;
+                }
+                None => {}
+            }
+
             warn!("Received unrecognized gateway op code ({})! Please open an issue on the chorus github so we can implement it", gateway_payload.op_code);
             trace!("Event data: {:?}", gateway_payload);
             return;
@@ -369,24 +1094,55 @@ impl Gateway {
 
                 trace!("GW: Received {event_name}");
 
+                self.stats.write().unwrap().events_received += 1;
+
+                {
+                    let raw_dispatch = &mut self.events.lock().await.raw_dispatch;
+                    if raw_dispatch.has_subscribers() {
+                        raw_dispatch
+                            .publish(types::RawGatewayDispatch {
+                                event_name: event_name.clone(),
+                                data: raw_event_data(gateway_payload.event_data),
+                            })
+                            .await;
+                    }
+                }
+
+                if let Some(data) = gateway_payload.event_data {
+                    self.update_message_cache(&event_name, data.get()).await;
+                }
+
                 macro_rules! handle {
+                    // Update events also record which fields they contain, see types::update_object
+                    (@parse $json:ident) => {
+                        serde_json::from_str($json).map(|message| (message, Vec::<Cow<str>>::new()))
+                    };
+                    (@parse $json:ident $update_type:ty) => {
+                        types::from_str_with_fields($json)
+                    };
                     ($($name:literal => $($path:ident).+ $( $message_type:ty: $update_type:ty)?),*) => {
                         match event_name.as_str() {
                             $($name => {
-                                let event = &mut self.events.lock().await.$($path).+;
+                                let events = &mut *self.events.lock().await;
+                                let event = &mut events.$($path).+;
                                 let json = gateway_payload.event_data.unwrap().get();
-                                match serde_json::from_str(json) {
+                                match handle!(@parse json $($update_type)?) {
                                     Err(err) => {
                                         warn!("Failed to parse gateway event {event_name} ({err})");
                                         trace!("Event data: {json}");
                                     },
-                                    Ok(message) => {
+                                    #[allow(unused_variables)]
+                                    Ok((message, fields)) => {
+                                        if self.event_stream.has_streams() {
+                                            self.event_stream.send(Clone::clone(&message));
+                                        }
                                         $(
                                             let mut message: $message_type = message;
                                             let store = self.store.lock().await;
                                             let id = if message.id().is_some() {
                                                 message.id().unwrap()
                                             } else {
+                                                events.filtered.publish(&message).await;
                                                 event.publish(message).await;
                                                 return;
                                             };
@@ -401,14 +1157,14 @@ impl Gateway {
                                                     // - The reference count is not being modified
                                                     let downcasted = unsafe { Arc::from_raw(ptr as *const RwLock<$update_type>).clone() };
                                                     drop(inner_object);
-                                                    message.set_json(json.to_string());
                                                     message.set_source_url(self.url.clone());
-                                                    message.update(downcasted.clone());
+                                                    message.update(downcasted.clone(), &fields);
                                                 } else {
                                                     warn!("Received {} for {}, but it has been observed to be a different type!", $name, id)
                                                 }
                                             }
                                         )?
+                                        events.filtered.publish(&message).await;
                                         event.publish(message).await;
                                     }
                                 }
@@ -424,15 +1180,34 @@ impl Gateway {
                                         return;
                                     }
                                     Ok(sessions) => {
+                                        let sessions_replace = types::SessionsReplace {sessions};
+                                        self.event_stream.send(sessions_replace.clone());
                                         self.events.lock().await.session.replace.publish(
-                                            types::SessionsReplace {sessions}
+                                            sessions_replace
                                         ).await;
                                     }
                                 }
                             },
                             _ => {
-                                warn!("Received unrecognized gateway event ({event_name})! Please open an issue on the chorus github so we can implement it");
-                                trace!("Event data: {}", gateway_payload.event_data.unwrap().get());
+                                let json = gateway_payload.event_data.map(|data| data.get()).unwrap_or("null");
+                                let result = self.events.lock().await.custom.publish_dispatch(&event_name, json).await;
+                                match result {
+                                    Some(Ok(())) => (),
+                                    Some(Err(err)) => {
+                                        warn!("Failed to parse custom gateway event {event_name} ({err})");
+                                        trace!("Event data: {json}");
+                                    }
+                                    None => {
+                                        warn!("Received unrecognized gateway event ({event_name})! Please open an issue on the chorus github so we can implement it");
+                                        trace!("Event data: {json}");
+                                        let unknown = types::UnknownEvent {
+                                            event_name: event_name.clone(),
+                                            data: raw_event_data(gateway_payload.event_data),
+                                        };
+                                        self.event_stream.send(unknown.clone());
+                                        self.events.lock().await.unknown.publish(unknown).await;
+                                    }
+                                }
                             }
                         }
                     };
@@ -455,7 +1230,7 @@ impl Gateway {
                     "CHANNEL_UPDATE" => channel.update ChannelUpdate: Channel,
                     "CHANNEL_UNREAD_UPDATE" => channel.unread_update,
                     "CHANNEL_DELETE" => channel.delete ChannelDelete: Guild,
-                    "CHANNEL_PINS_UPDATE" => channel.pins_update,
+                    "CHANNEL_PINS_UPDATE" => channel.pins_update ChannelPinsUpdate: Channel,
                     "CALL_CREATE" => call.create,
                     "CALL_UPDATE" => call.update,
                     "CALL_DELETE" => call.delete,
@@ -468,6 +1243,7 @@ impl Gateway {
                     "GUILD_CREATE" => guild.create, // TODO
                     "GUILD_UPDATE" => guild.update, // TODO
                     "GUILD_DELETE" => guild.delete, // TODO
+                    "GUILD_APPLICATION_COMMAND_INDEX_UPDATE" => guild.application_command_index_update,
                     "GUILD_AUDIT_LOG_ENTRY_CREATE" => guild.audit_log_entry_create,
                     "GUILD_BAN_ADD" => guild.ban_add, // TODO
                     "GUILD_BAN_REMOVE" => guild.ban_remove, // TODO
@@ -478,6 +1254,7 @@ impl Gateway {
                     "GUILD_MEMBER_REMOVE" => guild.member_remove,
                     "GUILD_MEMBER_UPDATE" => guild.member_update, // TODO
                     "GUILD_MEMBERS_CHUNK" => guild.members_chunk, // TODO
+                    "GUILD_MEMBER_LIST_UPDATE" => guild.member_list_update,
                     "GUILD_ROLE_CREATE" => guild.role_create GuildRoleCreate: Guild,
                     "GUILD_ROLE_UPDATE" => guild.role_update GuildRoleUpdate: RoleObject,
                     "GUILD_ROLE_DELETE" => guild.role_delete, // TODO
@@ -486,6 +1263,9 @@ impl Gateway {
                     "GUILD_SCHEDULED_EVENT_DELETE" => guild.role_scheduled_event_delete, // TODO
                     "GUILD_SCHEDULED_EVENT_USER_ADD" => guild.role_scheduled_event_user_add,
                     "GUILD_SCHEDULED_EVENT_USER_REMOVE" => guild.role_scheduled_event_user_remove,
+                    "GUILD_ONBOARDING_PROMPT_CREATE" => guild.onboarding_prompt_create,
+                    "GUILD_ONBOARDING_PROMPT_UPDATE" => guild.onboarding_prompt_update,
+                    "GUILD_ONBOARDING_PROMPT_DELETE" => guild.onboarding_prompt_delete,
                     "PASSIVE_UPDATE_V1" => guild.passive_update_v1, // TODO
                     "INTEGRATION_CREATE" => integration.create, // TODO
                     "INTEGRATION_UPDATE" => integration.update, // TODO
@@ -502,6 +1282,8 @@ impl Gateway {
                     "MESSAGE_REACTION_REMOVE" => message.reaction_remove, // TODO
                     "MESSAGE_REACTION_REMOVE_ALL" => message.reaction_remove_all, // TODO
                     "MESSAGE_REACTION_REMOVE_EMOJI" => message.reaction_remove_emoji, // TODO
+                    "MESSAGE_POLL_VOTE_ADD" => message.poll_vote_add,
+                    "MESSAGE_POLL_VOTE_REMOVE" => message.poll_vote_remove,
                     "RECENT_MENTION_DELETE" => message.recent_mention_delete,
                     "MESSAGE_ACK" => message.ack,
                     "PRESENCE_UPDATE" => user.presence_update, // TODO
@@ -514,7 +1296,11 @@ impl Gateway {
                     "USER_UPDATE" => user.update, // TODO
                     "USER_CONNECTIONS_UPDATE" => user.connections_update, // TODO
                     "USER_NOTE_UPDATE" => user.note_update,
+                    "USER_PREMIUM_GUILD_SUBSCRIPTION_SLOT_CREATE" => user.premium_guild_subscription_slot_create,
+                    "USER_PREMIUM_GUILD_SUBSCRIPTION_SLOT_UPDATE" => user.premium_guild_subscription_slot_update,
                     "USER_GUILD_SETTINGS_UPDATE" => user.guild_settings_update,
+                    "USER_SETTINGS_UPDATE" => user.settings_update,
+                    "USER_SETTINGS_PROTO_UPDATE" => user.settings_proto_update,
                     "VOICE_STATE_UPDATE" => voice.state_update, // TODO
                     "VOICE_SERVER_UPDATE" => voice.server_update,
                     "WEBHOOKS_UPDATE" => webhooks.update
@@ -522,39 +1308,18 @@ impl Gateway {
             }
             // We received a heartbeat from the server
             // "Discord may send the app a Heartbeat (opcode 1) event, in which case the app should send a Heartbeat event immediately."
+            // The heartbeat handler has already been told to send a heartbeat right away
             Opcode::Heartbeat => {
                 trace!("GW: Received Heartbeat // Heartbeat Request");
-
-                // Tell the heartbeat handler it should send a heartbeat right away
-                let heartbeat_communication = HeartbeatThreadCommunication {
-                    sequence_number: gateway_payload.sequence_number,
-                    op_code: Some(Opcode::Heartbeat),
-                };
-
-                self.heartbeat_handler
-                    .send
-                    .send(heartbeat_communication)
-                    .await
-                    .unwrap();
             }
             Opcode::HeartbeatAck => {
                 trace!("GW: Received Heartbeat ACK");
-
-                // Tell the heartbeat handler we received an ack
-                let heartbeat_communication = HeartbeatThreadCommunication {
-                    sequence_number: gateway_payload.sequence_number,
-                    op_code: Some(Opcode::HeartbeatAck),
-                };
-
-                self.heartbeat_handler
-                    .send
-                    .send(heartbeat_communication)
-                    .await
-                    .unwrap();
             }
             Opcode::Reconnect => {
                 trace!("GW: Received Reconnect");
 
+                self.stats.write().unwrap().reconnects += 1;
+
                 let reconnect = GatewayReconnect {};
 
                 self.events
@@ -602,20 +1367,117 @@ impl Gateway {
                 );
             }
         }
+    }
+
+    /// Updates the message cache with a dispatched message event, publishing the previously
+    /// cached versions of updated and deleted messages.
+    ///
+    /// Does nothing if the message cache is disabled.
+    async fn update_message_cache(&self, event_name: &str, json: &str) {
+        if !self.message_cache.read().unwrap().is_enabled() {
+            return;
+        }
 
-        // If we we received a sequence number we should let the heartbeat thread know
-        if let Some(seq_num) = gateway_payload.sequence_number {
-            let heartbeat_communication = HeartbeatThreadCommunication {
-                sequence_number: Some(seq_num),
-                // Op code is irrelevant here
-                op_code: None,
-            };
+        // Parsing errors are already logged when the event itself is handled
+        match event_name {
+            "MESSAGE_CREATE" => {
+                if let Ok(event) = serde_json::from_str::<types::MessageCreate>(json) {
+                    self.message_cache.write().unwrap().insert(event.message);
+                }
+            }
+            "MESSAGE_UPDATE" => {
+                let Ok(raw) = serde_json::from_str::<types::MessageUpdate>(json) else {
+                    return;
+                };
+                let cached = self
+                    .message_cache
+                    .write()
+                    .unwrap()
+                    .insert(raw.message.clone());
 
-            self.heartbeat_handler
-                .send
-                .send(heartbeat_communication)
-                .await
-                .unwrap();
+                let event = types::MessageUpdateCached { cached, raw };
+                self.event_stream.send(event.clone());
+
+                self.events
+                    .lock()
+                    .await
+                    .message
+                    .update_cached
+                    .publish(event)
+                    .await;
+            }
+            "MESSAGE_DELETE" => {
+                let Ok(raw) = serde_json::from_str::<types::MessageDelete>(json) else {
+                    return;
+                };
+                let cached = self.message_cache.write().unwrap().remove(raw.id);
+
+                let event = types::MessageDeleteCached { cached, raw };
+                self.event_stream.send(event.clone());
+
+                self.events
+                    .lock()
+                    .await
+                    .message
+                    .delete_cached
+                    .publish(event)
+                    .await;
+            }
+            "MESSAGE_DELETE_BULK" => {
+                let Ok(raw) = serde_json::from_str::<types::MessageDeleteBulk>(json) else {
+                    return;
+                };
+                let cached = {
+                    let mut message_cache = self.message_cache.write().unwrap();
+                    raw.ids
+                        .iter()
+                        .filter_map(|id| message_cache.remove(*id))
+                        .collect()
+                };
+
+                let event = types::MessageDeleteBulkCached { cached, raw };
+                self.event_stream.send(event.clone());
+
+                self.events
+                    .lock()
+                    .await
+                    .message
+                    .delete_bulk_cached
+                    .publish(event)
+                    .await;
+            }
+            _ => (),
         }
     }
 }
+
+/// Yields once to the executor, so other tasks can make progress.
+///
+/// This matters on single threaded runtimes (and wasm), where handling a burst of large
+/// dispatches (such as the guilds sent after Ready) would otherwise delay the heartbeat task.
+async fn yield_now() {
+    #[cfg(not(target_arch = "wasm32"))]
+    task::yield_now().await;
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        let mut yielded = false;
+        std::future::poll_fn(|context| {
+            if yielded {
+                return std::task::Poll::Ready(());
+            }
+            yielded = true;
+            context.waker().wake_by_ref();
+            std::task::Poll::Pending
+        })
+        .await;
+    }
+}
+
+/// Copies a payload's event data, using `null` if it has none.
+fn raw_event_data(data: Option<&serde_json::value::RawValue>) -> Box<serde_json::value::RawValue> {
+    match data {
+        Some(data) => data.to_owned(),
+        None => serde_json::value::RawValue::from_string("null".to_string()).unwrap(),
+    }
+}
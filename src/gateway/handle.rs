@@ -4,11 +4,31 @@
 
 use futures_util::SinkExt;
 use log::*;
+use pubserve::Subscriber;
+use rand::distributions::{Alphanumeric, DistString};
 
 use std::fmt::Debug;
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::time::timeout;
+#[cfg(target_arch = "wasm32")]
+use wasmtimer::tokio::timeout;
 
 use super::{events::Events, *};
-use crate::types::{self, Composite, Opcode, Shared};
+use crate::types::{
+    self, Composite, GuildMemberListUpdate, GuildMembersChunk, IntoShared, MemberList, Opcode,
+    RequestGuildMembersQuery, RequestedGuildMembers, Shared, UserSettings, UserSettingsUpdate,
+};
+
+/// How long [GatewayHandle::request_members] waits for all member chunks to arrive
+pub const REQUEST_MEMBERS_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The maximum size in bytes of a payload sent to the gateway
+///
+/// Larger payloads make the server close the connection with
+/// [CloseCode::DecodeError](crate::types::CloseCode::DecodeError).
+pub const GATEWAY_PAYLOAD_SIZE_LIMIT: usize = 4096;
 
 /// Represents a handle to a Gateway connection.
 ///
@@ -22,12 +42,34 @@ pub struct GatewayHandle {
     pub websocket_send: Arc<Mutex<Sink>>,
     /// Tells gateway tasks to close
     pub(super) kill_send: tokio::sync::broadcast::Sender<()>,
+    /// Tells the gateway to close the connection normally, see [GatewayHandle::shutdown]
+    pub(super) shutdown_send: tokio::sync::broadcast::Sender<()>,
+    pub(super) tasks: TaskTracker,
     pub(crate) store: Arc<Mutex<HashMap<Snowflake, Arc<RwLock<ObservableObject>>>>>,
+    pub(crate) cache: Shared<UserCache>,
+    pub(crate) voice_states: Shared<VoiceStateCache>,
+    pub(crate) message_cache: Shared<MessageCache>,
+    pub(crate) event_stream: EventStreamSender,
+    pub(crate) dispatch_metrics: Arc<DispatchCounters>,
+    pub(crate) stats: Shared<GatewayStats>,
+    /// Queues [GatewayHandle::send_identify]; shared with the instance's other users if the
+    /// connection was opened by [ChorusUser::gateway](crate::instance::ChorusUser::gateway)
+    pub(crate) identify_limiter: IdentifyLimiter,
+    #[cfg(target_arch = "wasm32")]
+    pub(super) resume_info: Shared<ResumeInfo>,
 }
 
 impl GatewayHandle {
     /// Sends json to the gateway with an opcode
-    async fn send_json_event(&self, op_code: u8, to_send: serde_json::Value) {
+    ///
+    /// Returns [GatewayError::PayloadTooLarge] without sending anything if the serialized
+    /// payload is larger than [GATEWAY_PAYLOAD_SIZE_LIMIT], since the server would close the
+    /// connection instead.
+    pub async fn send_json(
+        &self,
+        op_code: u8,
+        to_send: serde_json::Value,
+    ) -> Result<(), GatewayError> {
         let gateway_payload = types::GatewaySendPayload {
             op_code,
             event_data: Some(to_send),
@@ -35,6 +77,19 @@ impl GatewayHandle {
         };
 
         let payload_json = serde_json::to_string(&gateway_payload).unwrap();
+
+        if payload_json.len() > GATEWAY_PAYLOAD_SIZE_LIMIT {
+            warn!(
+                "GW: Not sending a payload with opcode {} of {} bytes, which is over the size limit",
+                op_code,
+                payload_json.len()
+            );
+            return Err(GatewayError::PayloadTooLarge {
+                size: payload_json.len(),
+                limit: GATEWAY_PAYLOAD_SIZE_LIMIT,
+            });
+        }
+
         let message = GatewayMessage(payload_json);
 
         self.websocket_send
@@ -43,6 +98,17 @@ impl GatewayHandle {
             .send(message.into())
             .await
             .unwrap();
+
+        Ok(())
+    }
+
+    /// Sends json to the gateway with an opcode, logging instead of returning errors
+    ///
+    /// Used for events whose payloads can't realistically exceed the size limit.
+    async fn send_json_event(&self, op_code: u8, to_send: serde_json::Value) {
+        if let Err(e) = self.send_json(op_code, to_send).await {
+            error!("GW: Failed to send payload with opcode {}: {}", op_code, e);
+        }
     }
 
     /// Recursively observes a [`Shared`] object, by making sure all [`Composite `] fields within
@@ -105,16 +171,166 @@ impl GatewayHandle {
         object
     }
 
+    /// Returns the DMs and group DMs the user is participating in.
+    ///
+    /// These are received in the [GatewayReady](types::GatewayReady) event and kept up to date
+    /// by channel events; this is empty until we have received it.
+    pub fn dm_channels(&self) -> Vec<Shared<types::Channel>> {
+        self.cache.read().unwrap().private_channels.clone()
+    }
+
+    /// Returns the relationships the user has with other users.
+    ///
+    /// These are received in the [GatewayReady](types::GatewayReady) event and kept up to date
+    /// by relationship events; this is empty until we have received it.
+    pub fn relationships(&self) -> Vec<types::Relationship> {
+        self.cache.read().unwrap().relationships.clone()
+    }
+
+    /// Returns the ids of the guilds the user is a member of.
+    ///
+    /// These are received in the [GatewayReady](types::GatewayReady) event and kept up to date
+    /// by [GuildCreate](types::GuildCreate) and [GuildDelete](types::GuildDelete) events; this is
+    /// empty until we have received it.
+    pub fn guild_ids(&self) -> Vec<types::Snowflake> {
+        self.cache.read().unwrap().guild_ids.clone()
+    }
+
+    /// Returns the voice states of the users in voice channels of a guild.
+    ///
+    /// These are received along with the guild and kept up to date by
+    /// [VoiceStateUpdate](types::VoiceStateUpdate) events, see [VoiceStateCache]; this is empty
+    /// until we have received the guild.
+    pub fn voice_states(&self, guild_id: Snowflake) -> Vec<types::VoiceState> {
+        self.voice_states
+            .read()
+            .unwrap()
+            .guild(guild_id)
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the voice state of a user in a guild, if they are in one of its voice channels.
+    ///
+    /// See [GatewayHandle::voice_states].
+    pub fn voice_state(
+        &self,
+        guild_id: Snowflake,
+        user_id: Snowflake,
+    ) -> Option<types::VoiceState> {
+        self.voice_states
+            .read()
+            .unwrap()
+            .get(guild_id, user_id)
+            .cloned()
+    }
+
+    /// Returns the voice states of the users in a voice channel of a guild.
+    ///
+    /// See [GatewayHandle::voice_states].
+    pub fn voice_channel_states(
+        &self,
+        guild_id: Snowflake,
+        channel_id: Snowflake,
+    ) -> Vec<types::VoiceState> {
+        self.voice_states
+            .read()
+            .unwrap()
+            .guild(guild_id)
+            .filter(|state| state.channel_id == Some(channel_id))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns a stream of all dispatched events received from now on.
+    ///
+    /// This is an alternative to subscribing to the publishers in [Events], which allows
+    /// handling every event in a single loop:
+    ///
+    /// ```no_run
+    /// # use chorus::gateway::{Event, GatewayHandle};
+    /// use futures_util::StreamExt;
+    ///
+    /// # async fn example(gateway: GatewayHandle) {
+    /// let mut stream = Box::pin(gateway.stream());
+    ///
+    /// while let Some(event) = stream.next().await {
+    ///     match event {
+    ///         Event::MessageCreate(message) => println!("{:?}", message.message.content),
+    ///         Event::UnknownEvent(event) => println!("Unknown event {}", event.event_name),
+    ///         _ => (),
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// Events are buffered per stream; a stream which falls more than
+    /// [EVENT_STREAM_CAPACITY] events behind skips the oldest ones. The stream ends once the
+    /// gateway connection is closed and dropped.
+    pub fn stream(&self) -> impl futures_util::Stream<Item = Event> {
+        self.event_stream.stream()
+    }
+
+    /// Returns a message from the [MessageCache], if it is cached.
+    ///
+    /// Always returns `None` unless the cache was enabled with
+    /// [GatewayOptions::message_cache_size].
+    pub fn cached_message(&self, id: Snowflake) -> Option<types::Message> {
+        self.message_cache.read().unwrap().get(id).cloned()
+    }
+
+    /// Returns how many dispatches are queued and how often the queue was full.
+    ///
+    /// Only tracked when using [GatewayDispatchStrategy::Queued].
+    pub fn dispatch_metrics(&self) -> DispatchMetrics {
+        self.dispatch_metrics.snapshot()
+    }
+
+    /// Returns statistics about the health of the connection, such as its latency and how many
+    /// events have been received.
+    pub fn stats(&self) -> GatewayStats {
+        *self.stats.read().unwrap()
+    }
+
+    /// Returns the time between sending our last acknowledged heartbeat and receiving its ack.
+    ///
+    /// Returns [None] until the first heartbeat has been acknowledged.
+    pub fn latency(&self) -> Option<Duration> {
+        self.stats.read().unwrap().latency
+    }
+
     /// Sends an identify event ([types::GatewayIdentifyPayload]) to the gateway
+    ///
+    /// Waits for the connection's [IdentifyLimiter] first, so identifies never exceed the
+    /// identify ratelimit.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "chorus.gateway.identify", skip_all)
+    )]
     pub async fn send_identify(&self, to_send: types::GatewayIdentifyPayload) {
+        self.identify_limiter.wait().await;
+
         let to_send_value = serde_json::to_value(&to_send).unwrap();
 
+        #[cfg(target_arch = "wasm32")]
+        {
+            self.resume_info.write().unwrap().token = Some(to_send.token.clone());
+        }
+
         trace!("GW: Sending Identify..");
 
         self.send_json_event(Opcode::Identify as u8, to_send_value).await;
     }
 
     /// Sends a resume event ([types::GatewayResume]) to the gateway
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "chorus.gateway.resume",
+            skip_all,
+            fields(session_id = %to_send.session_id, seq = %to_send.seq)
+        )
+    )]
     pub async fn send_resume(&self, to_send: types::GatewayResume) {
         let to_send_value = serde_json::to_value(&to_send).unwrap();
 
@@ -124,23 +340,115 @@ impl GatewayHandle {
     }
 
     /// Sends an update presence event ([types::UpdatePresence]) to the gateway
-    pub async fn send_update_presence(&self, to_send: types::UpdatePresence) {
+    ///
+    /// Returns [GatewayError::PayloadTooLarge] if the presence (e.g. its activities) is too
+    /// large to send, see [GATEWAY_PAYLOAD_SIZE_LIMIT].
+    pub async fn send_update_presence(
+        &self,
+        to_send: types::UpdatePresence,
+    ) -> Result<(), GatewayError> {
         let to_send_value = serde_json::to_value(&to_send).unwrap();
 
         trace!("GW: Sending Update Presence..");
 
-        self.send_json_event(Opcode::PresenceUpdate as u8, to_send_value)
-            .await;
+        self.send_json(Opcode::PresenceUpdate as u8, to_send_value)
+            .await
+    }
+
+    /// Sets the current user's presence.
+    ///
+    /// Accepts an [types::UpdatePresence] or a [types::PresenceBuilder].
+    ///
+    /// Shorthand for [Self::send_update_presence].
+    pub async fn set_presence(
+        &self,
+        presence: impl Into<types::UpdatePresence>,
+    ) -> Result<(), GatewayError> {
+        self.send_update_presence(presence.into()).await
     }
 
     /// Sends a request guild members ([types::GatewayRequestGuildMembers]) to the server
-    pub async fn send_request_guild_members(&self, to_send: types::GatewayRequestGuildMembers) {
+    ///
+    /// Returns [GatewayError::PayloadTooLarge] if the request (e.g. its user ids) is too large
+    /// to send, see [GATEWAY_PAYLOAD_SIZE_LIMIT].
+    pub async fn send_request_guild_members(
+        &self,
+        to_send: types::GatewayRequestGuildMembers,
+    ) -> Result<(), GatewayError> {
         let to_send_value = serde_json::to_value(&to_send).unwrap();
 
         trace!("GW: Sending Request Guild Members..");
 
-        self.send_json_event(Opcode::RequestGuildMembers as u8, to_send_value)
-            .await;
+        self.send_json(Opcode::RequestGuildMembers as u8, to_send_value)
+            .await
+    }
+
+    /// Requests members of a guild and waits until all of them have been received.
+    ///
+    /// Sends a [types::GatewayRequestGuildMembers] with a unique nonce, collects all
+    /// [GuildMembersChunk]s received for it and returns their contents merged in order.
+    ///
+    /// Returns [GatewayError::NoResponse] if not all chunks arrived within
+    /// [REQUEST_MEMBERS_TIMEOUT], or [GatewayError::PayloadTooLarge] if the request could not
+    /// be sent.
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/topics/gateway-events#request-guild-members>
+    pub async fn request_members(
+        &self,
+        guild_id: Snowflake,
+        query: RequestGuildMembersQuery,
+    ) -> Result<RequestedGuildMembers, GatewayError> {
+        // Nonces may be up to 32 bytes long
+        let nonce = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+
+        let (send, receive) = tokio::sync::oneshot::channel();
+        let collector = Arc::new(MembersChunkCollector {
+            nonce: nonce.clone(),
+            state: std::sync::Mutex::new(MembersChunkCollectorState {
+                chunks: Vec::new(),
+                send: Some(send),
+            }),
+        });
+
+        self.events
+            .lock()
+            .await
+            .guild
+            .members_chunk
+            .subscribe(collector.clone());
+
+        let request = types::GatewayRequestGuildMembers {
+            guild_id: guild_id.into(),
+            user_ids: query.user_ids.map(|user_ids| user_ids.into()),
+            query: query.query,
+            limit: query.limit,
+            presences: Some(query.presences),
+            nonce: Some(nonce),
+        };
+        let sent = self.send_request_guild_members(request).await;
+
+        let result = match sent {
+            Ok(()) => Some(timeout(REQUEST_MEMBERS_TIMEOUT, receive).await),
+            Err(_) => None,
+        };
+
+        self.events
+            .lock()
+            .await
+            .guild
+            .members_chunk
+            .unsubscribe(collector);
+
+        sent?;
+
+        match result {
+            Some(Ok(Ok(chunks))) => Ok(MembersChunkCollector::merge(guild_id, chunks)),
+            _ => {
+                warn!("GW: Did not receive all member chunks for guild {}", guild_id);
+                Err(GatewayError::NoResponse)
+            }
+        }
     }
 
     /// Sends an update voice state ([types::UpdateVoiceState]) to the server
@@ -180,6 +488,101 @@ impl GatewayHandle {
             .await;
     }
 
+    /// Subscribes to the member list (sidebar) of a guild channel and keeps it in sync.
+    ///
+    /// `ranges` are the (inclusive) index ranges of the list to subscribe to, usually chunks of
+    /// 100 such as `[(0, 99), (100, 199)]`.
+    ///
+    /// Sends a [types::LazyRequest] for the channel, and applies every
+    /// [GuildMemberListUpdate] received for the guild to the returned [MemberList].
+    ///
+    /// The list is kept up to date for as long as it is referenced; once it is dropped, the
+    /// subscription is removed with the next update received. Since servers only sync the
+    /// most recently subscribed channel of a guild, subscribing to another channel of the same
+    /// guild replaces the contents of previously returned lists.
+    ///
+    /// Returns [GatewayError::PayloadTooLarge] if too many ranges were given.
+    pub async fn subscribe_member_list(
+        &self,
+        guild_id: Snowflake,
+        channel_id: Snowflake,
+        ranges: Vec<(u32, u32)>,
+    ) -> Result<Shared<MemberList>, GatewayError> {
+        let list = MemberList::new(guild_id).into_shared();
+
+        let ranges = ranges
+            .into_iter()
+            .map(|(start, end)| vec![start as u64, end as u64])
+            .collect();
+
+        let request = types::LazyRequest {
+            guild_id,
+            typing: true,
+            activities: true,
+            threads: true,
+            members: None,
+            channels: Some(HashMap::from([(channel_id, ranges)])),
+        };
+        let to_send_value = serde_json::to_value(&request).unwrap();
+
+        let synchronizer = Arc::new_cyclic(|this| MemberListSynchronizer {
+            guild_id,
+            list: Arc::downgrade(&list),
+            events: Arc::downgrade(&self.events),
+            this: this.clone(),
+            unsubscribed: std::sync::atomic::AtomicBool::new(false),
+        });
+
+        // Subscribe before sending, so we can't miss the initial sync
+        self.events
+            .lock()
+            .await
+            .guild
+            .member_list_update
+            .subscribe(synchronizer.clone());
+
+        trace!("GW: Sending Lazy Request..");
+
+        if let Err(e) = self
+            .send_json(Opcode::GuildSubscriptions as u8, to_send_value)
+            .await
+        {
+            self.events
+                .lock()
+                .await
+                .guild
+                .member_list_update
+                .unsubscribe(synchronizer);
+            return Err(e);
+        }
+
+        Ok(list)
+    }
+
+    /// Keeps the user's settings in sync, by applying every [UserSettingsUpdate] received to
+    /// them.
+    ///
+    /// The settings are kept up to date for as long as they are referenced.
+    ///
+    /// # Notes
+    /// This is done automatically for [ChorusUser::settings](crate::instance::ChorusUser::settings)
+    /// when connecting a [ChorusUser](crate::instance::ChorusUser) to the gateway.
+    pub async fn sync_settings(&self, settings: Shared<UserSettings>) {
+        let synchronizer = Arc::new_cyclic(|this| UserSettingsSynchronizer {
+            settings: Arc::downgrade(&settings),
+            events: Arc::downgrade(&self.events),
+            this: this.clone(),
+            unsubscribed: std::sync::atomic::AtomicBool::new(false),
+        });
+
+        self.events
+            .lock()
+            .await
+            .user
+            .settings_update
+            .subscribe(synchronizer);
+    }
+
 	 /// Sends a Request Last Messages ([types::RequestLastMessages]) to the server
 	 ///
 	 /// The server should respond with a [types::LastMessages] event
@@ -199,4 +602,211 @@ impl GatewayHandle {
         self.kill_send.send(()).unwrap();
         self.websocket_send.lock().await.close().await.unwrap();
     }
+
+    /// Closes the websocket connection with close code 1000 (normal closure), and waits until
+    /// all gateway tasks have stopped.
+    ///
+    /// Dispatches which were already received are published to observers before this returns.
+    ///
+    /// Unlike [GatewayHandle::close], this ends the session; it can not be resumed.
+    pub async fn shutdown(&self) {
+        // Errors if the gateway has already stopped, in which case there is nothing to close
+        let _ = self.shutdown_send.send(());
+        self.closed().await;
+    }
+
+    /// Waits until all gateway tasks have stopped, e.g. after [GatewayHandle::shutdown] or
+    /// [GatewayHandle::close], or because the connection broke.
+    pub async fn closed(&self) {
+        self.tasks.wait().await;
+    }
+}
+
+/// Resolves mentions of the user's friends and DM recipients against the [UserCache].
+impl types::MentionResolver for GatewayHandle {
+    fn user_name(&self, id: Snowflake) -> Option<String> {
+        self.cache.read().unwrap().user_name(id)
+    }
+
+    fn channel_name(&self, id: Snowflake) -> Option<String> {
+        self.cache.read().unwrap().channel_name(id)
+    }
+}
+
+/// Keeps a [MemberList] from [GatewayHandle::subscribe_member_list] in sync
+struct MemberListSynchronizer {
+    guild_id: Snowflake,
+    /// Weak, so that the list is dropped once nobody else references it
+    list: std::sync::Weak<RwLock<MemberList>>,
+    /// Used to unsubscribe once the list was dropped
+    events: std::sync::Weak<Mutex<Events>>,
+    this: std::sync::Weak<MemberListSynchronizer>,
+    unsubscribed: std::sync::atomic::AtomicBool,
+}
+
+impl MemberListSynchronizer {
+    /// Removes the synchronizer from the member list updates.
+    ///
+    /// Updates are published while the events are locked, so this has to happen in another task.
+    fn unsubscribe(&self) {
+        if self
+            .unsubscribed
+            .swap(true, std::sync::atomic::Ordering::Relaxed)
+        {
+            return;
+        }
+
+        let (Some(events), Some(this)) = (self.events.upgrade(), self.this.upgrade()) else {
+            return;
+        };
+
+        let task = async move {
+            events
+                .lock()
+                .await
+                .guild
+                .member_list_update
+                .unsubscribe(this);
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        tokio::task::spawn(task);
+        #[cfg(target_arch = "wasm32")]
+        wasm_bindgen_futures::spawn_local(task);
+    }
+}
+
+#[async_trait::async_trait]
+impl Subscriber<GuildMemberListUpdate> for MemberListSynchronizer {
+    async fn update(&self, update: &GuildMemberListUpdate) {
+        let Some(list) = self.list.upgrade() else {
+            trace!("GW: Member list was dropped, unsubscribing");
+            self.unsubscribe();
+            return;
+        };
+
+        if update.guild_id != self.guild_id {
+            return;
+        }
+
+        list.write().unwrap().apply(update);
+    }
+}
+
+/// Keeps [UserSettings] from [GatewayHandle::sync_settings] in sync
+struct UserSettingsSynchronizer {
+    /// Weak, so that the settings are dropped once nobody else references them
+    settings: std::sync::Weak<RwLock<UserSettings>>,
+    /// Used to unsubscribe once the settings were dropped
+    events: std::sync::Weak<Mutex<Events>>,
+    this: std::sync::Weak<UserSettingsSynchronizer>,
+    unsubscribed: std::sync::atomic::AtomicBool,
+}
+
+impl UserSettingsSynchronizer {
+    /// Removes the synchronizer from the settings updates.
+    ///
+    /// Updates are published while the events are locked, so this has to happen in another task.
+    fn unsubscribe(&self) {
+        if self
+            .unsubscribed
+            .swap(true, std::sync::atomic::Ordering::Relaxed)
+        {
+            return;
+        }
+
+        let (Some(events), Some(this)) = (self.events.upgrade(), self.this.upgrade()) else {
+            return;
+        };
+
+        let task = async move {
+            events
+                .lock()
+                .await
+                .user
+                .settings_update
+                .unsubscribe(this);
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        tokio::task::spawn(task);
+        #[cfg(target_arch = "wasm32")]
+        wasm_bindgen_futures::spawn_local(task);
+    }
+}
+
+#[async_trait::async_trait]
+impl Subscriber<UserSettingsUpdate> for UserSettingsSynchronizer {
+    async fn update(&self, update: &UserSettingsUpdate) {
+        let Some(settings) = self.settings.upgrade() else {
+            trace!("GW: User settings were dropped, unsubscribing");
+            self.unsubscribe();
+            return;
+        };
+
+        settings.write().unwrap().apply_update(&update.settings);
+    }
+}
+
+/// Collects the [GuildMembersChunk]s for one [GatewayHandle::request_members] call
+struct MembersChunkCollector {
+    nonce: String,
+    state: std::sync::Mutex<MembersChunkCollectorState>,
+}
+
+struct MembersChunkCollectorState {
+    chunks: Vec<GuildMembersChunk>,
+    /// Taken once all chunks have been received
+    send: Option<tokio::sync::oneshot::Sender<Vec<GuildMembersChunk>>>,
+}
+
+impl MembersChunkCollector {
+    /// Merges the received chunks, ordered by their chunk index
+    fn merge(guild_id: Snowflake, mut chunks: Vec<GuildMembersChunk>) -> RequestedGuildMembers {
+        chunks.sort_by_key(|chunk| chunk.chunk_index);
+
+        let mut merged = RequestedGuildMembers {
+            guild_id,
+            ..Default::default()
+        };
+
+        for chunk in chunks {
+            merged.members.extend(chunk.members);
+            merged.not_found.extend(chunk.not_found.unwrap_or_default());
+            merged.presences.extend(chunk.presences.unwrap_or_default());
+        }
+
+        merged
+    }
+}
+
+#[async_trait::async_trait]
+impl Subscriber<GuildMembersChunk> for MembersChunkCollector {
+    async fn update(&self, chunk: &GuildMembersChunk) {
+        if chunk.nonce.as_deref() != Some(self.nonce.as_str()) {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+
+        // Servers may resend chunks; only keep one per index
+        if state
+            .chunks
+            .iter()
+            .any(|received| received.chunk_index == chunk.chunk_index)
+        {
+            return;
+        }
+        state.chunks.push(chunk.clone());
+
+        if state.chunks.len() < chunk.chunk_count as usize {
+            return;
+        }
+
+        let chunks = std::mem::take(&mut state.chunks);
+        if let Some(send) = state.send.take() {
+            // The receiver is only gone if the request already timed out
+            let _ = send.send(chunks);
+        }
+    }
 }
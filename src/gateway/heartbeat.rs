@@ -17,20 +17,21 @@ use wasmtimer::tokio::sleep_until;
 
 use std::time::Duration;
 
+use rand::Rng;
 use tokio::sync::mpsc::{Receiver, Sender};
 
 #[cfg(not(target_arch = "wasm32"))]
 use tokio::task;
 
 use super::*;
-use crate::types::{self, Opcode};
+use crate::types::{self, Opcode, Shared};
 
 /// The amount of time we wait for a heartbeat ack before resending our heartbeat in ms
 pub const HEARTBEAT_ACK_TIMEOUT: u64 = 2000;
 
 /// Handles sending heartbeats to the gateway in another thread
 #[allow(dead_code)] // FIXME: Remove this, once HeartbeatHandler is "used"
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(super) struct HeartbeatHandler {
     /// How ofter heartbeats need to be sent at a minimum
     pub heartbeat_interval: Duration,
@@ -41,19 +42,40 @@ pub(super) struct HeartbeatHandler {
 impl HeartbeatHandler {
     pub fn new(
         heartbeat_interval: Duration,
+        heartbeat_jitter: Duration,
         websocket_tx: Arc<Mutex<Sink>>,
         kill_rc: tokio::sync::broadcast::Receiver<()>,
+        stats: Shared<GatewayStats>,
+        task_guard: TaskGuard,
     ) -> Self {
         let (send, receive) = tokio::sync::mpsc::channel(32);
         let kill_receive = kill_rc.resubscribe();
 
         #[cfg(not(target_arch = "wasm32"))]
         task::spawn(async move {
-            Self::heartbeat_task(websocket_tx, heartbeat_interval, receive, kill_receive).await;
+            Self::heartbeat_task(
+                websocket_tx,
+                heartbeat_interval,
+                heartbeat_jitter,
+                receive,
+                kill_receive,
+                stats,
+            )
+            .await;
+            drop(task_guard);
         });
         #[cfg(target_arch = "wasm32")]
         wasm_bindgen_futures::spawn_local(async move {
-            Self::heartbeat_task(websocket_tx, heartbeat_interval, receive, kill_receive).await;
+            Self::heartbeat_task(
+                websocket_tx,
+                heartbeat_interval,
+                heartbeat_jitter,
+                receive,
+                kill_receive,
+                stats,
+            )
+            .await;
+            drop(task_guard);
         });
 
         Self {
@@ -65,20 +87,27 @@ impl HeartbeatHandler {
     /// The main heartbeat task;
     ///
     /// Can be killed by the kill broadcast;
-    /// If the websocket is closed, will die out next time it tries to send a heartbeat;
+    /// If the websocket is closed, stops sending heartbeats until it is told that a new
+    /// connection was opened (with [Opcode::Hello]), and dies out once the gateway is dropped;
     pub async fn heartbeat_task(
         websocket_tx: Arc<Mutex<Sink>>,
-        heartbeat_interval: Duration,
+        mut heartbeat_interval: Duration,
+        heartbeat_jitter: Duration,
         mut receive: Receiver<HeartbeatThreadCommunication>,
         mut kill_receive: tokio::sync::broadcast::Receiver<()>,
+        stats: Shared<GatewayStats>,
     ) {
         let mut last_heartbeat_timestamp: Instant = Instant::now();
         let mut last_heartbeat_acknowledged = true;
         let mut last_seq_number: Option<u64> = None;
+        // Rolled once per heartbeat, since the loop also runs on every sequence number update
+        let mut current_jitter = Self::random_jitter(heartbeat_jitter);
+        // Whether the websocket broke and we are waiting for the session to be resumed
+        let mut connection_broken = false;
 
         loop {
             let timeout = if last_heartbeat_acknowledged {
-                heartbeat_interval
+                heartbeat_interval.saturating_sub(current_jitter)
             } else {
                 // If the server hasn't acknowledged our heartbeat we should resend it
                 Duration::from_millis(HEARTBEAT_ACK_TIMEOUT)
@@ -87,13 +116,23 @@ impl HeartbeatHandler {
             let mut should_send = false;
 
             tokio::select! {
-                () = sleep_until(last_heartbeat_timestamp + timeout) => {
+                () = sleep_until(last_heartbeat_timestamp + timeout), if !connection_broken => {
+                    if !last_heartbeat_acknowledged {
+                        stats.write().unwrap().missed_heartbeat_acks += 1;
+                    }
                     should_send = true;
                 }
-                Some(communication) = receive.recv() => {
+                communication = receive.recv() => {
+                    // The gateway was dropped
+                    let Some(communication) = communication else {
+                        log::trace!("GW: Closing heartbeat task");
+                        break;
+                    };
+
                     // If we received a seq number update, use that as the last seq number
                     if communication.sequence_number.is_some() {
                         last_seq_number = communication.sequence_number;
+                        stats.write().unwrap().last_sequence_number = last_seq_number;
                     }
 
                     if let Some(op_code) = communication.op_code {
@@ -104,8 +143,22 @@ impl HeartbeatHandler {
                             }
                             Opcode::HeartbeatAck => {
                                 // The server received our heartbeat
+                                if !last_heartbeat_acknowledged {
+                                    stats.write().unwrap().latency =
+                                        Some(last_heartbeat_timestamp.elapsed());
+                                }
                                 last_heartbeat_acknowledged = true;
                             }
+                            Opcode::Hello => {
+                                // The session was resumed on a new connection, which may
+                                // want a different interval
+                                if let Some(interval) = communication.heartbeat_interval {
+                                    heartbeat_interval = interval;
+                                }
+                                connection_broken = false;
+                                last_heartbeat_acknowledged = true;
+                                should_send = true;
+                            }
                             _ => {}
                         }
                     }
@@ -132,14 +185,105 @@ impl HeartbeatHandler {
                 if send_result.is_err() {
                     // We couldn't send, the websocket is broken
                     warn!("GW: Couldn't send heartbeat, websocket seems broken");
-                    break;
+                    connection_broken = true;
+                    continue;
                 }
 
                 last_heartbeat_timestamp = Instant::now();
                 last_heartbeat_acknowledged = false;
+                stats.write().unwrap().heartbeats_sent += 1;
+                current_jitter = Self::random_jitter(heartbeat_jitter);
             }
         }
     }
+
+    /// Returns a random duration between zero and `max_jitter`
+    fn random_jitter(max_jitter: Duration) -> Duration {
+        if max_jitter.is_zero() {
+            return Duration::ZERO;
+        }
+
+        let max_millis = max_jitter.as_millis().min(u64::MAX as u128) as u64;
+        Duration::from_millis(rand::thread_rng().gen_range(0..=max_millis))
+    }
+}
+
+/// Returns whether a connection which last received something `since_last_received` ago
+/// stopped responding.
+///
+/// The gateway acknowledges every heartbeat, so a healthy connection receives something at least
+/// once per heartbeat interval.
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+pub(super) fn connection_is_stale(
+    since_last_received: Duration,
+    heartbeat_interval: Duration,
+) -> bool {
+    since_last_received > heartbeat_interval + Duration::from_millis(HEARTBEAT_ACK_TIMEOUT)
+}
+
+/// Tells the gateway listener task whenever the page becomes visible again.
+///
+/// See [GatewayOptions::resume_on_visibility_change]
+///
+/// The event listener is removed when this is dropped.
+#[cfg(target_arch = "wasm32")]
+pub(super) struct VisibilityChangeListener {
+    document: web_sys::Document,
+    callback: wasm_bindgen::closure::Closure<dyn FnMut()>,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl VisibilityChangeListener {
+    /// Registers the listener on the current document.
+    ///
+    /// Returns [None] if we aren't running in a page (e. g. in a web worker).
+    pub fn new(page_visible_send: Sender<()>) -> Option<Self> {
+        use wasm_bindgen::JsCast;
+
+        let document = web_sys::window()?.document()?;
+
+        let listener_document = document.clone();
+        let callback = wasm_bindgen::closure::Closure::<dyn FnMut()>::new(move || {
+            if listener_document.visibility_state() != web_sys::VisibilityState::Visible {
+                return;
+            }
+
+            trace!("GW: Page became visible");
+
+            // If the channel is full, the connection is about to be checked anyway
+            let _ = page_visible_send.try_send(());
+        });
+
+        document
+            .add_event_listener_with_callback("visibilitychange", callback.as_ref().unchecked_ref())
+            .ok()?;
+
+        Some(Self { document, callback })
+    }
+
+    /// Returns whether the page is currently hidden, e. g. because its tab is in the background.
+    pub fn page_is_hidden(&self) -> bool {
+        self.document.visibility_state() == web_sys::VisibilityState::Hidden
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Drop for VisibilityChangeListener {
+    fn drop(&mut self) {
+        use wasm_bindgen::JsCast;
+
+        let _ = self.document.remove_event_listener_with_callback(
+            "visibilitychange",
+            self.callback.as_ref().unchecked_ref(),
+        );
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl std::fmt::Debug for VisibilityChangeListener {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VisibilityChangeListener").finish_non_exhaustive()
+    }
 }
 
 /// Used for communications between the heartbeat and gateway thread.
@@ -150,4 +294,46 @@ pub(super) struct HeartbeatThreadCommunication {
     pub(super) op_code: Option<Opcode>,
     /// The sequence number we got from discord, if any
     pub(super) sequence_number: Option<u64>,
+    /// The heartbeat interval of a new connection, sent along with [Opcode::Hello]
+    pub(super) heartbeat_interval: Option<Duration>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn random_jitter_is_within_bounds() {
+        assert_eq!(
+            HeartbeatHandler::random_jitter(Duration::ZERO),
+            Duration::ZERO
+        );
+
+        let max_jitter = Duration::from_millis(50);
+        for _ in 0..1000 {
+            assert!(HeartbeatHandler::random_jitter(max_jitter) <= max_jitter);
+        }
+
+        // Durations too large for u64 milliseconds must not overflow
+        assert!(HeartbeatHandler::random_jitter(Duration::MAX) <= Duration::MAX);
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn connection_is_stale_once_an_ack_is_overdue() {
+        let heartbeat_interval = Duration::from_secs(40);
+        let ack_timeout = Duration::from_millis(HEARTBEAT_ACK_TIMEOUT);
+
+        assert!(!connection_is_stale(Duration::ZERO, heartbeat_interval));
+        assert!(!connection_is_stale(heartbeat_interval, heartbeat_interval));
+        assert!(!connection_is_stale(
+            heartbeat_interval + ack_timeout,
+            heartbeat_interval
+        ));
+        assert!(connection_is_stale(
+            heartbeat_interval + ack_timeout + Duration::from_millis(1),
+            heartbeat_interval
+        ));
+    }
 }
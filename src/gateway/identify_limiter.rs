@@ -0,0 +1,190 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use log::*;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::time::{sleep_until, Instant};
+#[cfg(target_arch = "wasm32")]
+use wasmtimer::{std::Instant, tokio::sleep_until};
+
+use crate::types::SessionStartLimit;
+
+/// The window in which at most `max_concurrency` identifies may be sent
+pub const IDENTIFY_RATELIMIT_WINDOW: Duration = Duration::from_secs(5);
+
+/// Queues identifies so they respect an instance's identify ratelimit.
+///
+/// Servers only allow `max_concurrency` identifies per [IDENTIFY_RATELIMIT_WINDOW], and a
+/// limited number of session starts per day. Exceeding either gets the session invalidated,
+/// which for applications connecting many users (or shards) at once quickly turns into a loop
+/// of invalid sessions and re-identifies.
+///
+/// Each [Instance](crate::instance::Instance) has one limiter which is shared by all of its
+/// users; cloning it yields a handle to the same queue.
+#[derive(Debug, Clone, Default)]
+pub struct IdentifyLimiter {
+    state: Arc<Mutex<IdentifyLimiterState>>,
+}
+
+#[derive(Debug)]
+struct IdentifyLimiterState {
+    max_concurrency: u32,
+    /// When the identifies in the current window were (or will be) sent, oldest first
+    scheduled: VecDeque<Instant>,
+    /// The last known session start limit and when it was received
+    session_start_limit: Option<(SessionStartLimit, Instant)>,
+}
+
+impl Default for IdentifyLimiterState {
+    fn default() -> Self {
+        Self {
+            max_concurrency: 1,
+            scheduled: VecDeque::new(),
+            session_start_limit: None,
+        }
+    }
+}
+
+impl IdentifyLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Updates the limiter with a session start limit received from the server, e.g. from
+    /// [ChorusUser::get_gateway_bot](crate::instance::ChorusUser::get_gateway_bot).
+    pub fn set_session_start_limit(&self, limit: SessionStartLimit) {
+        let mut state = self.state.lock().unwrap();
+        state.max_concurrency = limit.max_concurrency.max(1);
+        state.session_start_limit = Some((limit, Instant::now()));
+    }
+
+    /// Returns the last known session start limit, with `remaining` and `reset_after` adjusted
+    /// for the identifies sent and the time passed since it was received.
+    ///
+    /// Returns `None` if the limit was never fetched.
+    pub fn session_start_limit(&self) -> Option<SessionStartLimit> {
+        let state = self.state.lock().unwrap();
+        let (limit, received_at) = state.session_start_limit?;
+
+        let reset_at = received_at + Duration::from_millis(limit.reset_after);
+        let now = Instant::now();
+        if now >= reset_at {
+            // The reset period has passed, but we don't know when the next one ends
+            return Some(SessionStartLimit {
+                remaining: limit.total,
+                reset_after: 0,
+                ..limit
+            });
+        }
+
+        Some(SessionStartLimit {
+            reset_after: (reset_at - now).as_millis() as u64,
+            ..limit
+        })
+    }
+
+    /// Waits until an identify may be sent, and counts it as sent.
+    ///
+    /// Waiters are let through in the order they called this method.
+    pub async fn wait(&self) {
+        let send_at = self.schedule();
+
+        if send_at > Instant::now() {
+            debug!(
+                "GW: Identify ratelimited, waiting {:?}",
+                send_at - Instant::now()
+            );
+            sleep_until(send_at).await;
+        }
+    }
+
+    /// Reserves the next free identify slot and returns when it is
+    fn schedule(&self) -> Instant {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+
+        while state
+            .scheduled
+            .front()
+            .is_some_and(|sent_at| *sent_at + IDENTIFY_RATELIMIT_WINDOW <= now)
+        {
+            state.scheduled.pop_front();
+        }
+
+        // The earliest time the session start limit allows us to identify
+        let mut earliest = now;
+        if let Some((limit, received_at)) = state.session_start_limit {
+            let reset_at = received_at + Duration::from_millis(limit.reset_after);
+
+            if limit.remaining == 0 && now < reset_at {
+                warn!(
+                    "GW: Session start limit exhausted, waiting {:?} before identifying",
+                    reset_at - now
+                );
+                earliest = reset_at;
+            }
+        }
+
+        // Then check the window again from there, so that waiters which were delayed until
+        // the same reset are still spread out
+        let max_concurrency = state.max_concurrency as usize;
+        let send_at = if state.scheduled.len() < max_concurrency {
+            earliest
+        } else {
+            let window_start = state.scheduled[state.scheduled.len() - max_concurrency]
+                + IDENTIFY_RATELIMIT_WINDOW;
+            earliest.max(window_start)
+        };
+
+        if let Some((limit, received_at)) = state.session_start_limit.as_mut() {
+            // Once the reset period has passed, we don't know the limit anymore
+            if send_at < *received_at + Duration::from_millis(limit.reset_after) {
+                limit.remaining = limit.remaining.saturating_sub(1);
+            }
+        }
+
+        state.scheduled.push_back(send_at);
+        send_at
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod test {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn waiters_for_an_exhausted_limit_are_spread_out() {
+        let limiter = IdentifyLimiter::new();
+        limiter.set_session_start_limit(SessionStartLimit {
+            total: 1000,
+            remaining: 0,
+            reset_after: 1000,
+            max_concurrency: 1,
+        });
+
+        let start = Instant::now();
+        let waiters = (0..3).map(|_| {
+            let limiter = limiter.clone();
+            tokio::spawn(async move {
+                limiter.wait().await;
+                Instant::now()
+            })
+        });
+
+        let mut sent_at = Vec::new();
+        for waiter in waiters.collect::<Vec<_>>() {
+            sent_at.push(waiter.await.unwrap());
+        }
+        sent_at.sort();
+
+        assert!(sent_at[0] >= start + Duration::from_millis(1000));
+        for pair in sent_at.windows(2) {
+            assert!(pair[1] - pair[0] >= IDENTIFY_RATELIMIT_WINDOW);
+        }
+    }
+}
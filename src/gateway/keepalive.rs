@@ -0,0 +1,81 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use wasmtimer::std::Instant;
+
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::time::sleep_until;
+#[cfg(target_arch = "wasm32")]
+use wasmtimer::tokio::sleep_until;
+
+use super::GatewayKeepalive;
+
+/// Keeps track of when we last heard from the gateway, to detect dead connections.
+///
+/// See [GatewayKeepalive]
+#[derive(Debug, Clone, Copy)]
+pub(super) struct KeepaliveTracker {
+    options: GatewayKeepalive,
+    /// When we last received anything from the gateway
+    last_received: Instant,
+    /// When we sent the ping we are waiting for a response to, if any
+    ping_sent: Option<Instant>,
+}
+
+/// What to do once the [KeepaliveTracker::deadline] passed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum KeepaliveAction {
+    /// The connection was idle for a while, send a ping to check whether it is still alive
+    Ping,
+    /// The gateway did not respond to our ping, the connection is dead
+    ConnectionLost,
+}
+
+impl KeepaliveTracker {
+    pub(super) fn new(options: GatewayKeepalive) -> KeepaliveTracker {
+        KeepaliveTracker {
+            options,
+            last_received: Instant::now(),
+            ping_sent: None,
+        }
+    }
+
+    /// Notes that we received something, which proves the connection is alive
+    pub(super) fn received(&mut self) {
+        self.last_received = Instant::now();
+        self.ping_sent = None;
+    }
+
+    /// Notes that we sent a ping, which has to be responded to within the timeout
+    pub(super) fn ping_sent(&mut self) {
+        self.ping_sent = Some(Instant::now());
+    }
+
+    /// Returns when we need to act next
+    pub(super) fn deadline(&self) -> Instant {
+        match self.ping_sent {
+            Some(ping_sent) => ping_sent + self.options.timeout,
+            None => self.last_received + self.options.interval,
+        }
+    }
+
+    /// Returns what to do once the deadline passed
+    pub(super) fn action(&self) -> KeepaliveAction {
+        match self.ping_sent {
+            Some(_) => KeepaliveAction::ConnectionLost,
+            None => KeepaliveAction::Ping,
+        }
+    }
+}
+
+/// Waits until the deadline of the tracker, or forever if there is none
+pub(super) async fn keepalive_deadline(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
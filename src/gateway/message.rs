@@ -17,6 +17,8 @@ use super::*;
 pub(crate) enum GatewayCommunication {
     Message(RawGatewayMessage),
     Error(CloseCode),
+    /// A websocket ping or pong, which only tells us the connection is alive
+    KeepAlive,
 }
 
 impl From<RawGatewayMessage> for GatewayCommunication {
@@ -102,6 +104,34 @@ impl GatewayMessage {
         Ok(GatewayMessage(string))
     }
 
+    /// Attempt to create self by decompressing a single zlib compressed payload
+    ///
+    /// Used for payload compression, which the server uses for large payloads if we set
+    /// [compress](crate::types::GatewayIdentifyPayload::compress) when identifying.
+    pub(crate) fn from_zlib_json_bytes(bytes: &[u8]) -> Result<GatewayMessage, std::io::Error> {
+        let mut decoder = flate2::read::ZlibDecoder::new(bytes);
+
+        let mut string = String::new();
+        std::io::Read::read_to_string(&mut decoder, &mut string)?;
+
+        Ok(GatewayMessage(string))
+    }
+
+    /// Attempt to create self from a [RawGatewayMessage] which may be a zlib compressed payload
+    ///
+    /// Compressed payloads are sent as binary, uncompressed ones as text (or binary json).
+    pub(crate) fn from_payload_compressed_json_message(
+        message: RawGatewayMessage,
+    ) -> Result<GatewayMessage, std::io::Error> {
+        match message {
+            RawGatewayMessage::Bytes(bytes) if is_zlib_payload(&bytes) => {
+                Self::from_zlib_json_bytes(&bytes)
+            }
+            message => Self::from_raw_json_message(message)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+        }
+    }
+
     /// Attempt to create self by decompressing a zlib-stream bytes raw message
     pub(crate) fn from_zlib_stream_json_message(
         message: RawGatewayMessage,
@@ -110,3 +140,12 @@ impl GatewayMessage {
         Self::from_zlib_stream_json_bytes(&message.into_bytes(), inflate)
     }
 }
+
+/// Returns whether `bytes` start with a zlib header
+///
+/// Json payloads always start with `{`, which is never a valid header.
+fn is_zlib_payload(bytes: &[u8]) -> bool {
+    // The first byte is 0x78 for deflate with a 32K window, which is what zlib uses;
+    // the header as a whole must be a multiple of 31
+    bytes.len() > 2 && bytes[0] == 0x78 && u16::from_be_bytes([bytes[0], bytes[1]]) % 31 == 0
+}
@@ -5,24 +5,41 @@
 #![allow(deprecated)] // Since Opcode variants marked as deprecated are being used here, we need to suppress the warnings about them being deprecated
 
 pub mod backends;
+pub mod cache;
+pub mod event_stream;
 pub mod events;
+pub mod filter;
+#[cfg(any(test, feature = "fixtures"))]
+pub mod fixtures;
 pub mod gateway;
 pub mod handle;
 pub mod heartbeat;
+pub mod identify_limiter;
+mod keepalive;
 pub mod message;
 pub mod options;
+pub(crate) mod shutdown;
+pub mod stats;
 
 pub use backends::*;
+pub use cache::*;
+pub use event_stream::*;
+pub use filter::*;
 pub use gateway::*;
 pub use handle::*;
 use heartbeat::*;
+pub use identify_limiter::*;
+use keepalive::*;
 pub use message::*;
 pub use options::*;
+pub(crate) use shutdown::*;
+pub use stats::*;
 
 use crate::errors::GatewayError;
 use crate::types::Snowflake;
 
 use std::any::Any;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 
@@ -38,3 +55,9 @@ pub use pubserve::Subscriber as Observer;
 pub trait Updateable: 'static + Send + Sync {
     fn id(&self) -> Snowflake;
 }
+
+/// An [Updateable] entity which update events can contain only some of the fields of. This is implemented for all such types chorus supports, implementing it for your own types is likely a mistake.
+pub trait PartialUpdate: Sized {
+    /// Overwrites the fields of `self` whose serialized names are in `fields` with those of `update`.
+    fn update_fields(&mut self, update: Self, fields: &[Cow<str>]);
+}
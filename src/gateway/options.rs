@@ -2,9 +2,17 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::time::Duration;
+
 use crate::instance::InstanceSoftware;
 
-#[derive(Clone, PartialEq, Eq, Ord, PartialOrd, Debug, Default, Copy)]
+/// The default [GatewayOptions::heartbeat_jitter] used on `wasm32`.
+///
+/// Browsers heavily throttle timers in background tabs, which can delay our heartbeats by
+/// several seconds.
+pub const BROWSER_HEARTBEAT_JITTER: Duration = Duration::from_secs(5);
+
+#[derive(Clone, PartialEq, Eq, Ord, PartialOrd, Debug, Copy)]
 /// Options passed when initializing the gateway connection.
 ///
 /// E.g. compression
@@ -21,6 +29,93 @@ use crate::instance::InstanceSoftware;
 pub struct GatewayOptions {
     pub encoding: GatewayEncoding,
     pub transport_compression: GatewayTransportCompression,
+    /// The maximum amount of time by which heartbeats are sent before the heartbeat
+    /// interval elapses.
+    ///
+    /// The exact amount is random for each heartbeat, so that many clients don't end up sending
+    /// their heartbeats in lockstep.
+    ///
+    /// Sending heartbeats early leaves some headroom for timers which fire late, such as in
+    /// throttled browser tabs.
+    ///
+    /// Defaults to [Duration::ZERO] on native targets and [BROWSER_HEARTBEAT_JITTER] on `wasm32`.
+    pub heartbeat_jitter: Duration,
+    /// Whether to connect via `wss://` instead of `ws://` if the current page was loaded over
+    /// https, since browsers block insecure websocket connections from secure pages.
+    ///
+    /// Only has an effect on `wasm32`. Defaults to `true`.
+    pub upgrade_insecure_websocket: bool,
+    /// Whether to check the connection as soon as the current page becomes visible again.
+    ///
+    /// Browsers throttle background tabs, so a session can miss heartbeats or lose its
+    /// connection while its tab is hidden. If the connection was closed in the meantime, or
+    /// nothing was received since a heartbeat ack became overdue, the session is resumed on a new
+    /// connection to the `resume_gateway_url` from [GatewayReady](crate::types::GatewayReady).
+    /// Otherwise, a heartbeat is sent right away, instead of when the next (possibly throttled)
+    /// one is due.
+    ///
+    /// Connections which are closed while the page is hidden are only resumed if they can be
+    /// (see [GatewayError::is_resumable](crate::errors::GatewayError::is_resumable)) and the
+    /// session was identified through [GatewayHandle::send_identify](super::GatewayHandle::send_identify).
+    ///
+    /// Only has an effect on `wasm32`. Defaults to `true`.
+    pub resume_on_visibility_change: bool,
+    /// How many received messages to keep in the [MessageCache](super::MessageCache).
+    ///
+    /// When enabled, the gateway also publishes
+    /// [MessageUpdateCached](crate::types::MessageUpdateCached),
+    /// [MessageDeleteCached](crate::types::MessageDeleteCached) and
+    /// [MessageDeleteBulkCached](crate::types::MessageDeleteBulkCached) events, which include the
+    /// previously cached version of the affected messages.
+    ///
+    /// Defaults to 0, which disables the cache.
+    pub message_cache_size: usize,
+    /// How received events are handed to observers.
+    ///
+    /// See [GatewayDispatchStrategy] for the available options.
+    ///
+    /// Defaults to [GatewayDispatchStrategy::Inline].
+    pub dispatch_strategy: GatewayDispatchStrategy,
+    /// Whether to accept any TLS certificate when connecting via `wss://`, including
+    /// self-signed and expired ones.
+    ///
+    /// # Security
+    /// This makes the connection vulnerable to man-in-the-middle attacks. Only enable it for
+    /// local instances during development.
+    ///
+    /// Has no effect if a custom TLS connector is passed to
+    /// [Gateway::spawn_with_connector](super::Gateway::spawn_with_connector). Only has an effect
+    /// on native targets, since browsers verify certificates themselves.
+    ///
+    /// Defaults to `false`.
+    pub danger_accept_invalid_certs: bool,
+    /// How to detect connections which died without being closed, see [GatewayKeepalive].
+    ///
+    /// If `None`, such connections are only noticed once sending to them fails.
+    ///
+    /// Defaults to [GatewayKeepalive::default].
+    pub keepalive: Option<GatewayKeepalive>,
+}
+
+impl Default for GatewayOptions {
+    fn default() -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        let heartbeat_jitter = Duration::ZERO;
+        #[cfg(target_arch = "wasm32")]
+        let heartbeat_jitter = BROWSER_HEARTBEAT_JITTER;
+
+        Self {
+            encoding: GatewayEncoding::default(),
+            transport_compression: GatewayTransportCompression::default(),
+            heartbeat_jitter,
+            upgrade_insecure_websocket: true,
+            resume_on_visibility_change: true,
+            message_cache_size: 0,
+            dispatch_strategy: GatewayDispatchStrategy::default(),
+            danger_accept_invalid_certs: false,
+            keepalive: Some(GatewayKeepalive::default()),
+        }
+    }
 }
 
 impl GatewayOptions {
@@ -38,6 +133,7 @@ impl GatewayOptions {
         GatewayOptions {
             encoding,
             transport_compression,
+            ..Default::default()
         }
     }
 
@@ -45,8 +141,26 @@ impl GatewayOptions {
     ///
     /// Returns the new url
     pub(crate) fn add_to_url(&self, url: &str) -> String {
+        #[cfg(not(target_arch = "wasm32"))]
+        let page_is_secure = false;
+        #[cfg(target_arch = "wasm32")]
+        let page_is_secure = page_is_secure();
+
+        self.add_to_url_for_page(url, page_is_secure)
+    }
+
+    /// Adds the options to an existing gateway url, when running in a page which was (or
+    /// wasn't) loaded over https
+    ///
+    /// Returns the new url
+    fn add_to_url_for_page(&self, url: &str, page_is_secure: bool) -> String {
         let mut url = url.to_string();
 
+        if self.upgrade_insecure_websocket && url.starts_with("ws://") && page_is_secure {
+            log::debug!("GW: Page was loaded over https, connecting via wss://");
+            url = url.replacen("ws://", "wss://", 1);
+        }
+
         let mut parameters = Vec::with_capacity(2);
 
         let encoding = self.encoding.to_url_parameter();
@@ -81,6 +195,64 @@ impl GatewayOptions {
     }
 }
 
+/// Returns whether the page we are running in was loaded over https.
+#[cfg(target_arch = "wasm32")]
+fn page_is_secure() -> bool {
+    web_sys::window()
+        .and_then(|window| window.location().protocol().ok())
+        .is_some_and(|protocol| protocol == "https:")
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Debug)]
+/// Detects half-open connections, e.g. after the network changed, within seconds instead of
+/// after several missed heartbeat acks.
+///
+/// Once nothing was received for `interval`, a ping is sent; a websocket ping frame on native
+/// targets, and a heartbeat on `wasm32`, since browsers don't expose websocket pings. If
+/// nothing is received within `timeout` after that, the connection is closed and a
+/// [GatewayError::ConnectionLost](crate::errors::GatewayError::ConnectionLost) is published.
+pub struct GatewayKeepalive {
+    /// How long the connection may be idle before we send a ping.
+    ///
+    /// Defaults to 10 seconds.
+    pub interval: Duration,
+    /// How long to wait for a response to the ping.
+    ///
+    /// Defaults to 10 seconds.
+    pub timeout: Duration,
+}
+
+impl Default for GatewayKeepalive {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(10),
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Debug, Default)]
+/// Possible strategies for handing received events to observers.
+pub enum GatewayDispatchStrategy {
+    /// Publish events from the task which receives them from the websocket.
+    ///
+    /// Events are received in order and never dropped, but a slow observer delays all
+    /// following messages, including heartbeat acks. If acks are delayed for too long, the
+    /// connection may be considered dead.
+    #[default]
+    Inline,
+    /// Queue dispatched events and publish them from a separate task.
+    ///
+    /// Other messages, such as heartbeat acks, are still handled as soon as they are received,
+    /// so slow observers cannot delay them.
+    ///
+    /// Events are never dropped; if `capacity` events are already waiting to be published, no
+    /// further messages are received until the queue has room again. See
+    /// [GatewayHandle::dispatch_metrics](super::GatewayHandle::dispatch_metrics) for how many
+    /// events are queued and how often the queue was full.
+    Queued { capacity: usize },
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Debug, Default)]
 /// Possible transport compression options for the gateway.
 ///
@@ -133,3 +305,55 @@ impl GatewayEncoding {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn options(upgrade_insecure_websocket: bool) -> GatewayOptions {
+        GatewayOptions {
+            transport_compression: GatewayTransportCompression::None,
+            upgrade_insecure_websocket,
+            ..Default::default()
+        }
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn insecure_websocket_is_upgraded_on_secure_pages() {
+        assert_eq!(
+            options(true).add_to_url_for_page("ws://localhost:3001", true),
+            "wss://localhost:3001/?encoding=json"
+        );
+        assert_eq!(
+            options(true).add_to_url_for_page("ws://localhost:3001", false),
+            "ws://localhost:3001/?encoding=json"
+        );
+        assert_eq!(
+            options(false).add_to_url_for_page("ws://localhost:3001", true),
+            "ws://localhost:3001/?encoding=json"
+        );
+        assert_eq!(
+            options(true).add_to_url_for_page("wss://gateway.discord.gg", true),
+            "wss://gateway.discord.gg/?encoding=json"
+        );
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn default_heartbeat_jitter_depends_on_target() {
+        #[cfg(not(target_arch = "wasm32"))]
+        assert_eq!(GatewayOptions::default().heartbeat_jitter, Duration::ZERO);
+        #[cfg(target_arch = "wasm32")]
+        assert_eq!(
+            GatewayOptions::default().heartbeat_jitter,
+            BROWSER_HEARTBEAT_JITTER
+        );
+
+        let options = GatewayOptions::for_instance_software(InstanceSoftware::Other);
+        assert_eq!(
+            options.heartbeat_jitter,
+            GatewayOptions::default().heartbeat_jitter
+        );
+    }
+}
@@ -0,0 +1,42 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::sync::Arc;
+
+use tokio::sync::{mpsc, Mutex};
+
+/// Tracks whether all tasks of a (voice) gateway connection have stopped.
+///
+/// Every task holds a [TaskGuard]; once all of them are dropped, the tasks are done.
+#[derive(Debug, Clone)]
+pub(crate) struct TaskTracker {
+    receive: Arc<Mutex<mpsc::Receiver<()>>>,
+}
+
+/// Held by a gateway task for as long as it runs, see [TaskTracker]
+#[derive(Debug, Clone)]
+pub(crate) struct TaskGuard {
+    _send: mpsc::Sender<()>,
+}
+
+impl TaskTracker {
+    /// Creates a tracker, along with the first guard to hand out to tasks
+    pub(crate) fn new() -> (TaskTracker, TaskGuard) {
+        // Nothing is ever sent; the channel only tells us when all senders are dropped
+        let (send, receive) = mpsc::channel(1);
+
+        (
+            TaskTracker {
+                receive: Arc::new(Mutex::new(receive)),
+            },
+            TaskGuard { _send: send },
+        )
+    }
+
+    /// Waits until all tasks have stopped
+    pub(crate) async fn wait(&self) {
+        // Once all senders are dropped, this keeps returning None immediately
+        self.receive.lock().await.recv().await;
+    }
+}
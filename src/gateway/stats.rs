@@ -0,0 +1,27 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::time::Duration;
+
+/// Statistics about the health of a gateway connection.
+///
+/// See [GatewayHandle::stats](super::GatewayHandle::stats) and
+/// [GatewayHandle::latency](super::GatewayHandle::latency).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct GatewayStats {
+    /// The time between sending our last acknowledged heartbeat and receiving its ack.
+    ///
+    /// [None] until the first heartbeat has been acknowledged.
+    pub latency: Option<Duration>,
+    /// How many heartbeats we have sent
+    pub heartbeats_sent: u64,
+    /// How many heartbeats the server did not acknowledge in time, so we had to resend them
+    pub missed_heartbeat_acks: u64,
+    /// How many dispatched events we have received
+    pub events_received: u64,
+    /// How many times the server asked us to reconnect
+    pub reconnects: u64,
+    /// The last sequence number we received, which is sent along with heartbeats and resumes
+    pub last_sequence_number: Option<u64>,
+}
@@ -6,6 +6,7 @@
 
 use std::collections::HashMap;
 use std::fmt;
+use std::fmt::Debug;
 
 use std::sync::{Arc, RwLock};
 use std::time::Duration;
@@ -14,16 +15,28 @@ use chrono::Utc;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
-use crate::errors::ChorusResult;
-use crate::gateway::{Gateway, GatewayHandle, GatewayOptions};
-use crate::ratelimiter::ChorusRequest;
+use crate::errors::{ChorusError, ChorusResult};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::gateway::WebSocketConnector;
+use crate::gateway::{
+    Gateway, GatewayHandle, GatewayOptions, IdentifyLimiter, InstanceEvent, InstanceEventSender,
+    Updateable,
+};
+use crate::ratelimiter::{
+    ChorusRequest, RatelimitEventSender, RatelimitExhausted, RequestMetadata, RetryPolicy,
+};
 use crate::types::types::subconfigs::limits::rates::RateLimits;
 use crate::types::{
-    GatewayIdentifyPayload, GeneralConfiguration, Limit, LimitType, LimitsConfiguration, MfaToken,
-    MfaTokenSchema, MfaVerifySchema, Shared, User, UserSettings,
+    Channel, Composite, ConfigValue, GatewayIdentifyPayload, GeneralConfiguration, Limit,
+    LimitType, LimitsConfiguration, MfaToken, MfaTokenSchema, MfaVerifySchema, Relationship,
+    SessionStartLimit, Shared, User, UserSettings,
 };
 use crate::UrlBundle;
 
+#[cfg(feature = "polyproto")]
+use crate::polyproto::RequestSigner;
+use crate::token_store::{TokenRefresher, TokenStore};
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 /// The [`Instance`]; what you will be using to perform all sorts of actions on the Spacebar server.
 ///
@@ -33,10 +46,40 @@ pub struct Instance {
     pub instance_info: GeneralConfiguration,
     pub(crate) software: InstanceSoftware,
     pub limits_information: Option<LimitsInformation>,
+    /// The instance's limits, as last fetched from `/policies/instance/limits`.
+    ///
+    /// `None` if the instance does not expose its limits.
+    #[serde(default)]
+    pub limits_configuration: Option<LimitsConfiguration>,
+    /// The instance's public configuration, as last fetched from `/policies/instance/config`.
+    ///
+    /// `None` until it is fetched with [Instance::refresh_instance_configuration].
+    #[serde(default)]
+    pub instance_configuration: Option<ConfigValue>,
     #[serde(skip)]
     pub client: Client,
     #[serde(skip)]
     pub(crate) gateway_options: GatewayOptions,
+    #[serde(skip)]
+    pub(crate) identify_limiter: IdentifyLimiter,
+    #[serde(skip)]
+    pub(crate) event_bus: InstanceEventSender,
+    #[serde(skip)]
+    pub(crate) ratelimit_events: RatelimitEventSender,
+    #[serde(skip)]
+    pub(crate) rest_only: bool,
+    #[serde(skip)]
+    pub(crate) retry_policy: RetryPolicy,
+    /// Which [Capability]s the instance is known to support or not, as detected from its
+    /// responses
+    #[serde(skip)]
+    pub(crate) capabilities: HashMap<Capability, bool>,
+    #[cfg(feature = "polyproto")]
+    #[serde(skip)]
+    pub(crate) request_signer: Option<Arc<dyn RequestSigner>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    pub(crate) gateway_connector: WebSocketConnector,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, Eq)]
@@ -82,30 +125,45 @@ impl Instance {
         urls: UrlBundle,
         options: Option<GatewayOptions>,
     ) -> ChorusResult<Instance> {
-        let is_limited: Option<LimitsConfiguration> = Instance::is_limited(&urls.api).await?;
-        let limit_information;
+        Instance::from_url_bundle_with_client(urls, options, Client::new()).await
+    }
 
-        if let Some(limits_configuration) = is_limited {
-            let limits = ChorusRequest::limits_config_to_hashmap(&limits_configuration.rate);
-            limit_information = Some(LimitsInformation {
-                ratelimits: limits,
-                configuration: limits_configuration.rate,
-            });
-        } else {
-            limit_information = None
-        }
+    /// Like [`Instance::from_url_bundle`], but sends all requests using the given client.
+    async fn from_url_bundle_with_client(
+        urls: UrlBundle,
+        options: Option<GatewayOptions>,
+        client: Client,
+    ) -> ChorusResult<Instance> {
+        let is_limited: Option<LimitsConfiguration> =
+            Instance::is_limited_with_client(&urls.api, &client).await?;
 
         let mut instance = Instance {
             urls: urls.clone(),
             // Will be overwritten in the next step
             instance_info: GeneralConfiguration::default(),
-            limits_information: limit_information,
-            client: Client::new(),
+            limits_information: None,
+            limits_configuration: None,
+            instance_configuration: None,
+            client,
             gateway_options: options.unwrap_or_default(),
+            identify_limiter: IdentifyLimiter::new(),
+            event_bus: InstanceEventSender::default(),
+            ratelimit_events: RatelimitEventSender::default(),
+            rest_only: false,
+            retry_policy: RetryPolicy::default(),
+            capabilities: HashMap::new(),
             // Will also be detected soon
             software: InstanceSoftware::Other,
+            #[cfg(feature = "polyproto")]
+            request_signer: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            gateway_connector: WebSocketConnector::default(),
         };
 
+        if let Some(limits_configuration) = is_limited {
+            instance.set_limits_configuration(limits_configuration);
+        }
+
         instance.instance_info = match instance.general_configuration_schema().await {
             Ok(schema) => schema,
             Err(e) => {
@@ -114,7 +172,8 @@ impl Instance {
             }
         };
 
-        instance.software = instance.detect_software().await;
+        (instance.software, instance.capabilities) =
+            instance.detect_software_and_capabilities().await;
 
         if options.is_none() {
             instance.gateway_options = GatewayOptions::for_instance_software(instance.software());
@@ -134,8 +193,14 @@ impl Instance {
     }
 
     pub async fn is_limited(api_url: &str) -> ChorusResult<Option<LimitsConfiguration>> {
-        let api_url = UrlBundle::parse_url(api_url);
-        let client = Client::new();
+        Instance::is_limited_with_client(api_url, &Client::new()).await
+    }
+
+    async fn is_limited_with_client(
+        api_url: &str,
+        client: &Client,
+    ) -> ChorusResult<Option<LimitsConfiguration>> {
+        let api_url = UrlBundle::parse_url(api_url)?;
         let request = client
             .get(format!("{}/policies/instance/limits", &api_url))
             .header(http::header::ACCEPT, "application/json")
@@ -152,11 +217,32 @@ impl Instance {
 
     /// Detects which [InstanceSoftware] the instance is running.
     pub async fn detect_software(&self) -> InstanceSoftware {
+        self.detect_software_and_capabilities().await.0
+    }
+
+    /// Detects which [InstanceSoftware] the instance is running, along with the [Capability]s
+    /// it reports supporting on its /version endpoint.
+    async fn detect_software_and_capabilities(
+        &self,
+    ) -> (InstanceSoftware, HashMap<Capability, bool>) {
+        if self.urls.is_discord() {
+            return (InstanceSoftware::Discord, HashMap::new());
+        }
+
+        let mut capabilities = HashMap::new();
+
         if let Ok(version) = self.get_version().await {
+            capabilities = version
+                .capabilities
+                .iter()
+                .filter_map(|name| Capability::from_name(name))
+                .map(|capability| (capability, true))
+                .collect();
+
             match version.server.to_lowercase().as_str() {
-                "symfonia" => return InstanceSoftware::Symfonia,
+                "symfonia" => return (InstanceSoftware::Symfonia, capabilities),
                 // We can dream this will be implemented one day
-                "spacebar" => return InstanceSoftware::SpacebarTypescript,
+                "spacebar" => return (InstanceSoftware::SpacebarTypescript, capabilities),
                 _ => {}
             }
         }
@@ -166,10 +252,106 @@ impl Instance {
         let ping = self.ping().await;
 
         if ping.is_ok() {
-            return InstanceSoftware::SpacebarTypescript;
+            return (InstanceSoftware::SpacebarTypescript, capabilities);
+        }
+
+        (InstanceSoftware::Other, capabilities)
+    }
+
+    /// Caches the instance's [`LimitsConfiguration`] and rebuilds the rate limits tracked for it.
+    ///
+    /// Note that this resets the remaining requests of every tracked rate limit.
+    pub fn set_limits_configuration(&mut self, limits_configuration: LimitsConfiguration) {
+        let ratelimits = ChorusRequest::limits_config_to_hashmap(&limits_configuration.rate);
+        self.limits_information = Some(LimitsInformation {
+            ratelimits,
+            configuration: limits_configuration.rate.clone(),
+        });
+        self.limits_configuration = Some(limits_configuration);
+    }
+
+    /// Returns the [`IdentifyLimiter`] which queues the identifies of all users on this instance.
+    pub fn identify_limiter(&self) -> IdentifyLimiter {
+        self.identify_limiter.clone()
+    }
+
+    /// Returns a stream of the events received by the gateway connections of all users on this
+    /// instance, each tagged with the id of the user who received it.
+    ///
+    /// This is the instance wide counterpart of [`GatewayHandle::stream`], for applications
+    /// managing multiple accounts. Users created with [`ChorusUser::new_rest_only`] are only
+    /// included once they [connect to the gateway](ChorusUser::connect_gateway).
+    ///
+    /// # Example
+    /// ```no_run
+    /// use chorus::gateway::Event;
+    /// use chorus::instance::Instance;
+    /// use futures_util::StreamExt;
+    ///
+    /// # async fn example(instance: Instance) {
+    /// let mut stream = Box::pin(instance.events());
+    ///
+    /// while let Some(instance_event) = stream.next().await {
+    ///     if let Event::MessageCreate(message) = instance_event.event {
+    ///         println!("{} received {:?}", instance_event.user_id, message.message.content);
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// Events are buffered per stream; a stream which falls more than
+    /// [`EVENT_STREAM_CAPACITY`](crate::gateway::EVENT_STREAM_CAPACITY) events behind skips the
+    /// oldest ones. Note that clones of an [`Instance`] share the same event bus.
+    pub fn events(&self) -> impl futures_util::Stream<Item = InstanceEvent> {
+        self.event_bus.stream()
+    }
+
+    /// Returns the current state of an [instance wide](LimitType::is_instance_wide) rate limit
+    /// bucket.
+    ///
+    /// Returns `None` if the instance is not rate limited, or if the bucket is tracked per user;
+    /// see [`ChorusUser::ratelimit`] for those.
+    pub fn ratelimit(&self, limit_type: &LimitType) -> Option<Limit> {
+        if !limit_type.is_instance_wide() {
+            return None;
         }
+        self.limits_information
+            .as_ref()
+            .and_then(|info| info.ratelimits.get(limit_type))
+            .copied()
+    }
 
-        InstanceSoftware::Other
+    /// Returns a stream which yields an event whenever a request of a user on this instance
+    /// exhausts a rate limit bucket.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use chorus::instance::Instance;
+    /// use futures_util::StreamExt;
+    ///
+    /// # async fn example(instance: Instance) {
+    /// let mut stream = Box::pin(instance.ratelimit_events());
+    ///
+    /// while let Some(event) = stream.next().await {
+    ///     println!(
+    ///         "Slow down! {:?} is exhausted for {:?}",
+    ///         event.limit.bucket,
+    ///         event.limit.time_until_reset()
+    ///     );
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// Like [`Instance::events`], the streams of clones of an [`Instance`] receive the same events.
+    pub fn ratelimit_events(&self) -> impl futures_util::Stream<Item = RatelimitExhausted> {
+        self.ratelimit_events.stream()
+    }
+
+    /// Returns the last known [`SessionStartLimit`], adjusted for the identifies sent since.
+    ///
+    /// Returns `None` if it was never fetched, see [`ChorusUser::get_gateway_bot`].
+    pub fn session_start_limit(&self) -> Option<SessionStartLimit> {
+        self.identify_limiter.session_start_limit()
     }
 
     /// Returns the [`GatewayOptions`] the instance uses when spawning new connections.
@@ -186,6 +368,66 @@ impl Instance {
         self.gateway_options = options;
     }
 
+    /// Returns whether users logging in or registering on this instance are created without a
+    /// gateway connection.
+    pub fn rest_only(&self) -> bool {
+        self.rest_only
+    }
+
+    /// Sets whether users logging in or registering on this instance should be created without a
+    /// gateway connection, e.g. for CLI tools or serverless functions where a WebSocket is unwanted.
+    ///
+    /// Such users can only be used for REST requests; actions which need the gateway return
+    /// [`ChorusError::NoGateway`], until
+    /// [`ChorusUser::connect_gateway`] is called.
+    pub fn set_rest_only(&mut self, rest_only: bool) {
+        self.rest_only = rest_only;
+    }
+
+    /// Returns the [`RetryPolicy`] for requests to this instance.
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy.clone()
+    }
+
+    /// Sets when and how often requests to this instance are retried after transient failures.
+    ///
+    /// By default, `GET` and `HEAD` requests are attempted up to 3 times. Use
+    /// [`RetryPolicy::none`] to disable retries.
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.retry_policy = retry_policy;
+    }
+
+    /// Returns the [`WebSocketConnector`] used when spawning new gateway connections.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn gateway_connector(&self) -> WebSocketConnector {
+        self.gateway_connector.clone()
+    }
+
+    /// Manually sets the [`WebSocketConnector`] the instance should use when spawning new
+    /// gateway connections, e.g. to connect through a proxy.
+    ///
+    /// Like [`Instance::set_gateway_options`], this is used on the gateways created when logging
+    /// in and registering.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn set_gateway_connector(&mut self, connector: WebSocketConnector) {
+        self.gateway_connector = connector;
+    }
+
+    /// Returns the [`RequestSigner`] used to sign requests to this instance, if any.
+    #[cfg(feature = "polyproto")]
+    pub fn request_signer(&self) -> Option<Arc<dyn RequestSigner>> {
+        self.request_signer.clone()
+    }
+
+    /// Sets the [`RequestSigner`] used to sign all requests sent to this instance and to
+    /// verify their responses.
+    ///
+    /// Passing `None` disables signing.
+    #[cfg(feature = "polyproto")]
+    pub fn set_request_signer(&mut self, signer: Option<Arc<dyn RequestSigner>>) {
+        self.request_signer = signer;
+    }
+
     /// Returns which [`InstanceSoftware`] the instance is running.
     pub fn software(&self) -> InstanceSoftware {
         self.software
@@ -198,12 +440,153 @@ impl Instance {
     ///
     /// Manually setting the software is generally discouraged. Chorus should automatically detect
     /// which type of software the instance is running.
+    ///
+    /// This forgets which [`Capability`]s were detected for the previous software.
     pub fn set_software(&mut self, software: InstanceSoftware) {
         self.software = software;
+        self.capabilities.clear();
+    }
+
+    /// Returns whether the instance supports a [`Capability`], so that calling code can branch
+    /// between instance softwares.
+    ///
+    /// Capabilities are detected lazily: once the instance answered a request to one of a
+    /// capability's endpoints, either successfully or with a 404 for an unknown route, that
+    /// answer is used. Until then, this returns whether the detected [`InstanceSoftware`] is
+    /// known to implement it (see [`InstanceSoftware::supports_endpoint`]).
+    pub fn supports(&self, capability: Capability) -> bool {
+        self.capabilities
+            .get(&capability)
+            .copied()
+            .unwrap_or_else(|| self.software.supports_endpoint(capability))
+    }
+
+    /// Manually sets whether the instance supports a [`Capability`], overriding what has been
+    /// detected.
+    ///
+    /// Useful if the instance has been updated to support a capability which was detected as
+    /// unsupported.
+    pub fn set_supports(&mut self, capability: Capability, supported: bool) {
+        self.capabilities.insert(capability, supported);
+    }
+
+    /// Returns the [`Capability`]s which have been detected from the instance's responses so
+    /// far, and whether they are supported.
+    pub fn detected_capabilities(&self) -> &HashMap<Capability, bool> {
+        &self.capabilities
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Builds an [`Instance`], for when the options of [`Instance::new`] are not enough.
+///
+/// # Example
+/// ```no_run
+/// use chorus::instance::InstanceBuilder;
+///
+/// # async fn example() -> chorus::errors::ChorusResult<()> {
+/// // A local instance, which uses a self-signed certificate
+/// let instance = InstanceBuilder::new("https://localhost:3001")
+///     .danger_accept_invalid_certs(true)
+///     .build()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct InstanceBuilder {
+    urls: InstanceBuilderUrls,
+    gateway_options: Option<GatewayOptions>,
+    danger_accept_invalid_certs: bool,
+}
+
+#[derive(Debug, Clone)]
+enum InstanceBuilderUrls {
+    Root(String),
+    Bundle(UrlBundle),
+}
+
+impl InstanceBuilder {
+    /// Starts building an [`Instance`], whose [urls](UrlBundle) are retrieved from a root url.
+    ///
+    /// See [`Instance::new`].
+    pub fn new(root_url: &str) -> InstanceBuilder {
+        InstanceBuilder {
+            urls: InstanceBuilderUrls::Root(root_url.to_string()),
+            gateway_options: None,
+            danger_accept_invalid_certs: false,
+        }
+    }
+
+    /// Starts building an [`Instance`] from the [relevant instance urls](UrlBundle).
+    ///
+    /// See [`Instance::from_url_bundle`].
+    pub fn from_url_bundle(urls: UrlBundle) -> InstanceBuilder {
+        InstanceBuilder {
+            urls: InstanceBuilderUrls::Bundle(urls),
+            gateway_options: None,
+            danger_accept_invalid_certs: false,
+        }
+    }
+
+    /// Sets the [`GatewayOptions`] the instance uses when spawning new connections.
+    ///
+    /// If not set, the ideal options for the detected [`InstanceSoftware`] are used.
+    pub fn gateway_options(mut self, options: GatewayOptions) -> InstanceBuilder {
+        self.gateway_options = Some(options);
+        self
+    }
+
+    /// Sets whether to accept any TLS certificate, including self-signed and expired ones, for
+    /// http requests and gateway connections.
+    ///
+    /// This also sets [`GatewayOptions::danger_accept_invalid_certs`] on the instance's gateway
+    /// options.
+    ///
+    /// # Security
+    /// This makes all connections to the instance vulnerable to man-in-the-middle attacks. Only
+    /// enable it for local instances during development.
+    ///
+    /// Only has an effect on native targets, since browsers verify certificates themselves.
+    pub fn danger_accept_invalid_certs(mut self, accept: bool) -> InstanceBuilder {
+        self.danger_accept_invalid_certs = accept;
+        self
+    }
+
+    /// Retrieves the instance's urls (if needed) and information, and creates the [`Instance`].
+    pub async fn build(self) -> ChorusResult<Instance> {
+        let client = self.build_client()?;
+
+        let urls = match self.urls {
+            InstanceBuilderUrls::Root(root_url) => {
+                UrlBundle::from_root_url_with_client(&root_url, &client).await?
+            }
+            InstanceBuilderUrls::Bundle(urls) => urls,
+        };
+
+        let mut instance =
+            Instance::from_url_bundle_with_client(urls, self.gateway_options, client).await?;
+
+        if self.danger_accept_invalid_certs {
+            instance.gateway_options.danger_accept_invalid_certs = true;
+        }
+
+        Ok(instance)
+    }
+
+    fn build_client(&self) -> ChorusResult<Client> {
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.danger_accept_invalid_certs {
+            log::warn!("Accepting invalid TLS certificates, connections are not secure");
+            return Ok(Client::builder()
+                .danger_accept_invalid_certs(true)
+                .build()?);
+        }
+
+        Ok(Client::new())
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 /// The software implementation the spacebar-compatible instance is running.
 ///
 /// This is useful since some softwares may support additional features,
@@ -221,6 +604,15 @@ pub enum InstanceSoftware {
     /// Assume it implements all features of the spacebar protocol.
     #[default]
     Other,
+    /// Discord itself, detected from the hosts of the instance's urls (see
+    /// [UrlBundle::discord](crate::UrlBundle::discord))
+    Discord,
+}
+
+impl fmt::Display for InstanceSoftware {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
 }
 
 impl InstanceSoftware {
@@ -230,6 +622,7 @@ impl InstanceSoftware {
             InstanceSoftware::SpacebarTypescript => true,
             InstanceSoftware::Symfonia => false,
             InstanceSoftware::Other => true,
+            InstanceSoftware::Discord => true,
         }
     }
 
@@ -239,10 +632,102 @@ impl InstanceSoftware {
             InstanceSoftware::SpacebarTypescript => true,
             InstanceSoftware::Symfonia => false,
             InstanceSoftware::Other => true,
+            InstanceSoftware::Discord => true,
         }
     }
+
+    /// Returns whether the software implements an endpoint which not every software does.
+    ///
+    /// Endpoints are only reported as unsupported if the software is known not to implement
+    /// them.
+    ///
+    /// See [Instance::supports], which also takes what is known about the specific instance
+    /// into account.
+    pub fn supports_endpoint(self, endpoint: Capability) -> bool {
+        match (self, endpoint) {
+            (
+                InstanceSoftware::SpacebarTypescript,
+                Capability::MessageSearch | Capability::Gifs,
+            ) => true,
+            // As of 2024/08, Spacebar implements none of the others; see the notes on the methods
+            (InstanceSoftware::SpacebarTypescript, _) => false,
+            (InstanceSoftware::Symfonia, _) => true,
+            (InstanceSoftware::Other, _) => true,
+            (InstanceSoftware::Discord, _) => true,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+/// An optional api endpoint (or group of endpoints), which not every instance implements.
+///
+/// Chorus checks these before sending the request, and returns
+/// [ChorusError::UnsupportedByInstance] instead of letting the request fail with a generic
+/// error.
+///
+/// See [Instance::supports].
+pub enum Capability {
+    /// See [Guild::search_messages](crate::types::Guild::search_messages) and
+    /// [Channel::search_messages](crate::types::Channel::search_messages)
+    MessageSearch,
+    /// See [Gif::search](crate::types::Gif::search), [Gif::trending](crate::types::Gif::trending)
+    /// and [Gif::suggest](crate::types::Gif::suggest)
+    Gifs,
+    /// See [ChorusUser::create_user_invite]
+    CreateUserInvite,
+    /// See [User::get_by_username](crate::types::User::get_by_username)
+    GetUserByUsername,
+    /// See [ChorusUser::verify_email_change]
+    VerifyEmailChange,
+    /// See [ChorusUser::get_pomelo_suggestions]
+    PomeloSuggestions,
+    /// See [ChorusUser::get_pomelo_eligibility]
+    PomeloEligibility,
+    /// See [ChorusUser::create_pomelo_migration]
+    PomeloMigration,
+    /// See [ChorusUser::get_recent_mentions] and [ChorusUser::delete_recent_mention]
+    RecentMentions,
+    /// See [ChorusUser::get_harvest], [ChorusUser::create_harvest] and
+    /// [ChorusUser::delete_harvest]
+    Harvest,
+    /// See [ChorusUser::get_user_notes]
+    UserNotes,
+    /// See [ChorusUser::get_premium_usage]
+    PremiumUsage,
+    /// See [ChorusUser::get_burst_credits]
+    BurstCredits,
+    /// See [ChorusUser::create_domain_connection]
+    DomainConnection,
+    /// See [Guild::get_premium_subscriptions](crate::types::Guild::get_premium_subscriptions)
+    /// and [ChorusUser::get_premium_guild_subscription_slots]
+    PremiumGuildSubscriptions,
+    /// See [Guild::get_onboarding](crate::types::Guild::get_onboarding) and
+    /// [Guild::modify_onboarding](crate::types::Guild::modify_onboarding)
+    GuildOnboarding,
+    /// See `ChorusUser::get_settings_proto` and `ChorusUser::modify_settings_proto`, behind the
+    /// `settings-proto` feature
+    SettingsProto,
+}
+
+impl Capability {
+    /// Parses a capability from its snake_case name, e. g. "message_search", as reported on an
+    /// instance's /version endpoint (see [VersionReturn](crate::types::VersionReturn))
+    pub fn from_name(name: &str) -> Option<Capability> {
+        serde_json::from_value(serde_json::Value::String(name.to_string())).ok()
+    }
 }
 
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+#[deprecated(note = "Renamed to Capability")]
+pub type OptionalEndpoint = Capability;
+
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Token {
     pub token: String,
@@ -257,7 +742,8 @@ impl fmt::Display for Token {
 #[derive(Debug, Clone)]
 /// A ChorusUser is a representation of an authenticated user on an [Instance].
 /// It is used for most authenticated actions on a Spacebar server.
-/// It also has its own [Gateway] connection.
+/// It also has its own [Gateway] connection, unless it was created in REST-only mode (see
+/// [Instance::set_rest_only]).
 pub struct ChorusUser {
     pub belongs_to: Shared<Instance>,
     pub token: String,
@@ -265,7 +751,26 @@ pub struct ChorusUser {
     pub limits: Option<HashMap<LimitType, Limit>>,
     pub settings: Shared<UserSettings>,
     pub object: Shared<User>,
-    pub gateway: GatewayHandle,
+    /// The user's gateway connection, `None` in REST-only mode; see [ChorusUser::gateway]
+    pub(crate) gateway: Option<GatewayHandle>,
+    /// Metadata, such as an audit log reason prefix, which is added to every request the user
+    /// sends
+    pub request_metadata: RequestMetadata,
+    /// The token returned by the last message acknowledgement, which is sent along with the next
+    /// one.
+    ///
+    /// See [ChorusUser::acknowledge_message].
+    pub ack_token: Option<String>,
+    /// The session's ID-Cert, PEM encoded, if the user logged in with a polyproto identity.
+    ///
+    /// See [Instance::login_polyproto].
+    #[cfg(feature = "polyproto")]
+    pub id_cert: Option<String>,
+    /// Where the user's tokens are persisted, see [ChorusUser::set_token_store]
+    pub token_store: Option<Arc<dyn TokenStore>>,
+    /// Obtains a new token once the current one is rejected, see
+    /// [ChorusUser::set_token_refresher]
+    pub token_refresher: Option<Arc<dyn TokenRefresher>>,
 }
 
 impl ChorusUser {
@@ -273,8 +778,13 @@ impl ChorusUser {
         self.token.clone()
     }
 
+    /// Sets the user's token, and persists it in the user's [TokenStore], if it has one.
     pub fn set_token(&mut self, token: &str) {
         self.token = token.to_string();
+
+        if let Err(e) = self.persist_tokens() {
+            log::warn!("Could not persist the user's token: {}", e);
+        }
     }
 
     /// Creates a new [ChorusUser] from existing data.
@@ -282,6 +792,8 @@ impl ChorusUser {
     /// # Notes
     /// This isn't the preferred way to create a ChorusUser.
     /// See [Instance::login_account] and [Instance::register_account] instead.
+    ///
+    /// The settings are not kept in sync with the gateway; see [GatewayHandle::sync_settings].
     pub fn new(
         belongs_to: Shared<Instance>,
         token: String,
@@ -289,6 +801,39 @@ impl ChorusUser {
         settings: Shared<UserSettings>,
         object: Shared<User>,
         gateway: GatewayHandle,
+    ) -> ChorusUser {
+        let event_bus = belongs_to.read().unwrap().event_bus.clone();
+        gateway
+            .event_stream
+            .forward_to_instance(event_bus, object.clone());
+
+        ChorusUser {
+            belongs_to,
+            token,
+            mfa_token: None,
+            limits,
+            settings,
+            object,
+            gateway: Some(gateway),
+            request_metadata: RequestMetadata::default(),
+            ack_token: None,
+            #[cfg(feature = "polyproto")]
+            id_cert: None,
+            token_store: None,
+            token_refresher: None,
+        }
+    }
+
+    /// Creates a new [ChorusUser] from existing data, without a gateway connection.
+    ///
+    /// The user can only be used for REST requests; see [ChorusUser::connect_gateway] to connect
+    /// to the gateway later.
+    pub fn new_rest_only(
+        belongs_to: Shared<Instance>,
+        token: String,
+        limits: Option<HashMap<LimitType, Limit>>,
+        settings: Shared<UserSettings>,
+        object: Shared<User>,
     ) -> ChorusUser {
         ChorusUser {
             belongs_to,
@@ -297,8 +842,228 @@ impl ChorusUser {
             limits,
             settings,
             object,
-            gateway,
+            gateway: None,
+            request_metadata: RequestMetadata::default(),
+            ack_token: None,
+            #[cfg(feature = "polyproto")]
+            id_cert: None,
+            token_store: None,
+            token_refresher: None,
+        }
+    }
+
+    /// Returns the current state of a rate limit bucket the user's requests count towards.
+    ///
+    /// [Instance wide](LimitType::is_instance_wide) buckets are shared with all users of the
+    /// instance.
+    ///
+    /// Returns `None` if the instance is not rate limited, or if the user did not send a request
+    /// in the bucket yet, in which case all of its requests are still available.
+    pub fn ratelimit(&self, limit_type: &LimitType) -> Option<Limit> {
+        if limit_type.is_instance_wide() {
+            return self.belongs_to.read().unwrap().ratelimit(limit_type);
         }
+        self.limits
+            .as_ref()
+            .and_then(|limits| limits.get(limit_type))
+            .copied()
+    }
+
+    /// Returns the current state of all rate limit buckets the user's requests count towards,
+    /// including the [instance wide](LimitType::is_instance_wide) ones.
+    ///
+    /// Empty if the instance is not rate limited.
+    pub fn ratelimits(&self) -> HashMap<LimitType, Limit> {
+        let mut ratelimits: HashMap<LimitType, Limit> = self
+            .limits
+            .iter()
+            .flatten()
+            .filter(|(limit_type, _)| !limit_type.is_instance_wide())
+            .map(|(limit_type, limit)| (*limit_type, *limit))
+            .collect();
+
+        if let Some(info) = self.belongs_to.read().unwrap().limits_information.as_ref() {
+            ratelimits.extend(
+                info.ratelimits
+                    .iter()
+                    .filter(|(limit_type, _)| limit_type.is_instance_wide())
+                    .map(|(limit_type, limit)| (*limit_type, *limit)),
+            );
+        }
+
+        ratelimits
+    }
+
+    /// Returns how long to wait until a request in the bucket of `limit_type` can be sent
+    /// without being rate limited.
+    ///
+    /// Takes the instance's global and ip buckets into account as well. Returns
+    /// [Duration::ZERO] if a request can be sent right away.
+    pub fn time_until_available(&self, limit_type: &LimitType) -> Duration {
+        ChorusRequest::time_until_available(self, limit_type)
+    }
+
+    /// Checks whether the user's instance is known to implement an endpoint.
+    ///
+    /// # Errors
+    /// Returns [ChorusError::UnsupportedByInstance] if the instance is known not to implement
+    /// it, see [Instance::supports].
+    pub(crate) fn check_endpoint_support(&self, endpoint: Capability) -> ChorusResult<()> {
+        let belongs_to = self.belongs_to.read().unwrap();
+
+        if belongs_to.supports(endpoint) {
+            return Ok(());
+        }
+
+        Err(ChorusError::UnsupportedByInstance {
+            capability: endpoint,
+            software: belongs_to.software(),
+        })
+    }
+
+    /// Records whether the user's instance supports a [`Capability`], based on the result of a
+    /// request to one of its endpoints, and returns the result.
+    ///
+    /// Successful requests mark it as supported, 404s for unknown routes (see
+    /// [`ChorusError::is_unknown_route`]) as unsupported. Other errors are not conclusive.
+    pub(crate) fn record_endpoint_support<T>(
+        &self,
+        endpoint: Capability,
+        result: ChorusResult<T>,
+    ) -> ChorusResult<T> {
+        let supported = match &result {
+            Ok(_) => true,
+            Err(error) if error.is_unknown_route() => false,
+            Err(_) => return result,
+        };
+
+        let mut belongs_to = self.belongs_to.write().unwrap();
+        if belongs_to.capabilities.get(&endpoint) != Some(&supported) {
+            log::debug!("Detected support for {:?}: {}", endpoint, supported);
+            belongs_to.capabilities.insert(endpoint, supported);
+        }
+
+        result
+    }
+
+    /// Checks a value against one of the [instance's limits](Instance::limits_configuration), so
+    /// that requests the instance would reject are not sent.
+    ///
+    /// Nothing is checked if the instance does not expose its limits.
+    ///
+    /// # Errors
+    /// Returns [ChorusError::LimitExceeded] if `actual` is larger than the limit selected by
+    /// `max`.
+    pub(crate) fn check_limit(
+        &self,
+        limit: &str,
+        actual: u64,
+        max: impl FnOnce(&LimitsConfiguration) -> u64,
+    ) -> ChorusResult<()> {
+        let Some(max) = self
+            .belongs_to
+            .read()
+            .unwrap()
+            .limits_configuration
+            .as_ref()
+            .map(max)
+        else {
+            return Ok(());
+        };
+
+        if actual <= max {
+            return Ok(());
+        }
+
+        log::debug!("Not sending request, {} {} > {}", limit, actual, max);
+        Err(ChorusError::LimitExceeded {
+            limit: limit.to_string(),
+            max,
+            actual,
+        })
+    }
+
+    /// Returns the user's gateway connection.
+    ///
+    /// # Errors
+    /// Returns [ChorusError::NoGateway] if the user was
+    /// created in REST-only mode.
+    pub fn gateway(&self) -> ChorusResult<&GatewayHandle> {
+        self.gateway.as_ref().ok_or(ChorusError::NoGateway)
+    }
+
+    /// Recursively observes a [`Shared`] object using the user's gateway connection.
+    ///
+    /// See [GatewayHandle::observe].
+    ///
+    /// # Errors
+    /// Returns [ChorusError::NoGateway] if the user was
+    /// created in REST-only mode.
+    pub async fn observe<T: Updateable + Clone + Debug + Composite<T>>(
+        &self,
+        object: Shared<T>,
+    ) -> ChorusResult<Shared<T>> {
+        Ok(self.gateway()?.observe(object).await)
+    }
+
+    /// Returns the DMs and group DMs the user is participating in, as received from the gateway.
+    ///
+    /// See [GatewayHandle::dm_channels].
+    ///
+    /// # Errors
+    /// Returns [ChorusError::NoGateway] if the user was
+    /// created in REST-only mode.
+    pub fn dm_channels(&self) -> ChorusResult<Vec<Shared<Channel>>> {
+        Ok(self.gateway()?.dm_channels())
+    }
+
+    /// Returns the relationships the user has with other users, as received from the gateway.
+    ///
+    /// See [GatewayHandle::relationships]. To fetch them via the api instead, see
+    /// [ChorusUser::get_relationships].
+    ///
+    /// # Errors
+    /// Returns [ChorusError::NoGateway] if the user was
+    /// created in REST-only mode.
+    pub fn relationships(&self) -> ChorusResult<Vec<Relationship>> {
+        Ok(self.gateway()?.relationships())
+    }
+
+    /// Connects a user to the gateway and identifies, if it does not have a gateway connection
+    /// yet.
+    ///
+    /// This is done automatically when logging in or registering, unless the instance is in
+    /// REST-only mode (see [Instance::set_rest_only]).
+    pub async fn connect_gateway(&mut self) -> ChorusResult<&GatewayHandle> {
+        if self.gateway.is_none() {
+            let instance = self.belongs_to.read().unwrap().clone();
+
+            #[cfg(not(target_arch = "wasm32"))]
+            let gateway = Gateway::spawn_with_connector(
+                &instance.urls.wss,
+                instance.gateway_options,
+                instance.gateway_connector.clone(),
+            )
+            .await;
+            #[cfg(target_arch = "wasm32")]
+            let gateway = Gateway::spawn(&instance.urls.wss, instance.gateway_options).await;
+
+            let mut gateway = gateway.map_err(|error| ChorusError::Gateway { error })?;
+            gateway.identify_limiter = instance.identify_limiter.clone();
+            gateway
+                .event_stream
+                .forward_to_instance(instance.event_bus.clone(), self.object.clone());
+            gateway.sync_settings(self.settings.clone()).await;
+
+            let mut identify = GatewayIdentifyPayload::common();
+            identify.token = self.token.clone();
+
+            gateway.send_identify(identify).await;
+
+            self.gateway = Some(gateway);
+        }
+
+        self.gateway()
     }
 
     /// Updates a shell user after the login process.
@@ -312,37 +1077,34 @@ impl ChorusUser {
         token: String,
         received_settings: Option<Shared<UserSettings>>,
     ) -> ChorusResult<()> {
-        self.token = token.clone();
-
-        let mut identify = GatewayIdentifyPayload::common();
-        identify.token = token;
-        self.gateway.send_identify(identify).await;
-
-        *self.object.write().unwrap() = self.get_current_user().await?;
+        self.set_token(&token);
 
+        // Set the settings before connecting, so the gateway keeps the right ones in sync
         if let Some(passed_settings) = received_settings {
             self.settings = passed_settings;
         } else {
             *self.settings.write().unwrap() = self.get_settings().await?;
         }
 
+        let rest_only = self.belongs_to.read().unwrap().rest_only;
+        if !rest_only {
+            self.connect_gateway().await?;
+        }
+
+        *self.object.write().unwrap() = self.get_current_user().await?;
+
         Ok(())
     }
 
     /// Creates a new 'shell' of a user. The user does not exist as an object, and exists so that you have
     /// a ChorusUser object to make Rate Limited requests with. This is useful in scenarios like
     /// registering or logging in to the Instance, where you do not yet have a User object, but still
-    /// need to make a RateLimited request. To use the [`GatewayHandle`], you will have to identify
-    /// first.
+    /// need to make a RateLimited request. The shell does not have a gateway connection; one is
+    /// created in [ChorusUser::update_with_login_data].
     pub(crate) async fn shell(instance: Shared<Instance>, token: &str) -> ChorusUser {
         let settings = Arc::new(RwLock::new(UserSettings::default()));
         let object = Arc::new(RwLock::new(User::default()));
 
-        let wss_url = &instance.read().unwrap().urls.wss.clone();
-        let gateway_options = instance.read().unwrap().gateway_options;
-
-        // Dummy gateway object
-        let gateway = Gateway::spawn(wss_url, gateway_options).await.unwrap();
         ChorusUser {
             token: token.to_string(),
             mfa_token: None,
@@ -355,7 +1117,13 @@ impl ChorusUser {
                 .map(|info| info.ratelimits.clone()),
             settings,
             object,
-            gateway,
+            gateway: None,
+            request_metadata: RequestMetadata::default(),
+            ack_token: None,
+            #[cfg(feature = "polyproto")]
+            id_cert: None,
+            token_store: None,
+            token_refresher: None,
         }
     }
 
@@ -392,6 +1160,110 @@ impl ChorusUser {
             expires_at: Utc::now() + Duration::from_secs(60 * 5),
         });
 
+        if let Err(e) = self.persist_tokens() {
+            log::warn!("Could not persist the user's MFA token: {}", e);
+        }
+
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::errors::ApiError;
+    use crate::types::{IntoShared, JsonErrorCode};
+    use crate::DiscordRelease;
+
+    fn rest_only_user(software: InstanceSoftware) -> ChorusUser {
+        let mut instance = Instance::default();
+        instance.set_software(software);
+
+        ChorusUser::new_rest_only(
+            instance.into_shared(),
+            "token".to_string(),
+            None,
+            UserSettings::default().into_shared(),
+            User::default().into_shared(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_detect_discord() {
+        let instance = Instance {
+            urls: UrlBundle::discord(DiscordRelease::Canary),
+            ..Default::default()
+        };
+        assert_eq!(instance.detect_software().await, InstanceSoftware::Discord);
+
+        let spacebar = UrlBundle::new(
+            "https://spacebar.chat",
+            "https://api.spacebar.chat/api/v9",
+            "wss://gateway.spacebar.chat",
+            "https://cdn.spacebar.chat",
+        )
+        .unwrap();
+        assert!(!spacebar.is_discord());
+    }
+
+    #[test]
+    fn test_capability_from_name() {
+        assert_eq!(
+            Capability::from_name("message_search"),
+            Some(Capability::MessageSearch)
+        );
+        assert_eq!(Capability::from_name("time_travel"), None);
+    }
+
+    #[test]
+    fn test_capabilities_default_to_software() {
+        let user = rest_only_user(InstanceSoftware::SpacebarTypescript);
+        let instance = user.belongs_to.read().unwrap();
+
+        assert!(instance.supports(Capability::MessageSearch));
+        assert!(!instance.supports(Capability::Harvest));
+        assert!(instance.detected_capabilities().is_empty());
+    }
+
+    #[test]
+    fn test_record_endpoint_support() {
+        let user = rest_only_user(InstanceSoftware::Other);
+        assert!(user.check_endpoint_support(Capability::Harvest).is_ok());
+
+        // The channel not existing says nothing about the route
+        let unknown_channel = ChorusError::Api {
+            error: ApiError {
+                status: 404,
+                code: JsonErrorCode::UnknownChannel,
+                ..Default::default()
+            },
+        };
+        let _ = user.record_endpoint_support::<()>(Capability::Harvest, Err(unknown_channel));
+        assert!(user.belongs_to.read().unwrap().detected_capabilities().is_empty());
+
+        let unknown_route = ChorusError::NotFound {
+            error: "Cannot GET /api/users/@me/harvest".to_string(),
+        };
+        let _ = user.record_endpoint_support::<()>(Capability::Harvest, Err(unknown_route));
+        assert!(matches!(
+            user.check_endpoint_support(Capability::Harvest),
+            Err(ChorusError::UnsupportedByInstance { .. })
+        ));
+
+        let _ = user.record_endpoint_support(Capability::Harvest, Ok(()));
+        assert!(user.belongs_to.read().unwrap().supports(Capability::Harvest));
+
+        user.belongs_to
+            .write()
+            .unwrap()
+            .set_supports(Capability::Harvest, false);
+        assert!(!user.belongs_to.read().unwrap().supports(Capability::Harvest));
+
+        // Detected capabilities are specific to the software
+        user.belongs_to
+            .write()
+            .unwrap()
+            .set_software(InstanceSoftware::Symfonia);
+        assert!(user.belongs_to.read().unwrap().supports(Capability::Harvest));
+    }
+}
@@ -77,15 +77,31 @@ The following features are supported on `wasm32-unknown-unknown`:
 | Feature           | WASM Support |
 | ----------------- | ------------ |
 | `client`          | ✅            |
+| `client-core`     | ✅            |
+| `api-channels`    | ✅            |
+| `api-guilds`      | ✅            |
+| `api-invites`     | ✅            |
+| `api-messages`    | ✅            |
+| `api-users`       | ✅            |
 | `rt`              | ✅            |
 | `rt-multi-thread` | ❌            |
 | `backend`         | ❌            |
 | `voice`           | ❌            |
 | `voice_udp`       | ❌            |
 | `voice_gateway`   | ✅            |
+| `polyproto`       | ✅            |
+| `fixtures`        | ✅            |
+| `tracing`         | ✅            |
 
 We recommend checking out the "examples" directory, as well as the documentation for more information.
 
+## Tracing
+
+With the `tracing` feature, Chorus creates [tracing](https://docs.rs/tracing) spans for HTTP requests
+(with their route, status and rate limit bucket), for connecting, identifying and resuming on the gateway,
+and for voice connections. Chorus' own log messages are still emitted with `log`; forward them with
+[tracing-log](https://docs.rs/tracing-log) to see them inside these spans.
+
 ## MSRV (Minimum Supported Rust Version)
 
 Rust **1.70.0**. This number might change at any point while Chorus is not yet at version 1.0.0.
@@ -147,41 +163,50 @@ use url::{ParseError, Url};
 
 use crate::errors::ChorusError;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 pub mod api;
+#[cfg(feature = "benchmarks")]
+#[doc(hidden)]
+pub mod benchmarks;
 pub mod errors;
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 pub mod gateway;
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 pub mod instance;
-#[cfg(feature = "client")]
+#[cfg(all(feature = "client-core", feature = "polyproto"))]
+pub mod polyproto;
+#[cfg(feature = "client-core")]
 pub mod ratelimiter;
+#[cfg(all(feature = "rpc", not(target_arch = "wasm32")))]
+pub mod rpc;
+#[cfg(feature = "client-core")]
+pub mod token_store;
 pub mod types;
 #[cfg(all(
-    feature = "client",
+    feature = "client-core",
     any(feature = "voice_udp", feature = "voice_gateway")
 ))]
 pub mod voice;
 
-#[cfg(not(feature = "sqlx"))]
+#[cfg(not(feature = "sqlx-pg-uint"))]
 pub type UInt128 = u128;
-#[cfg(feature = "sqlx")]
+#[cfg(feature = "sqlx-pg-uint")]
 pub type UInt128 = sqlx_pg_uint::PgU128;
-#[cfg(not(feature = "sqlx"))]
+#[cfg(not(feature = "sqlx-pg-uint"))]
 pub type UInt64 = u64;
-#[cfg(feature = "sqlx")]
+#[cfg(feature = "sqlx-pg-uint")]
 pub type UInt64 = sqlx_pg_uint::PgU64;
-#[cfg(not(feature = "sqlx"))]
+#[cfg(not(feature = "sqlx-pg-uint"))]
 pub type UInt32 = u32;
-#[cfg(feature = "sqlx")]
+#[cfg(feature = "sqlx-pg-uint")]
 pub type UInt32 = sqlx_pg_uint::PgU32;
-#[cfg(not(feature = "sqlx"))]
+#[cfg(not(feature = "sqlx-pg-uint"))]
 pub type UInt16 = u16;
-#[cfg(feature = "sqlx")]
+#[cfg(feature = "sqlx-pg-uint")]
 pub type UInt16 = sqlx_pg_uint::PgU16;
-#[cfg(not(feature = "sqlx"))]
+#[cfg(not(feature = "sqlx-pg-uint"))]
 pub type UInt8 = u8;
-#[cfg(feature = "sqlx")]
+#[cfg(feature = "sqlx-pg-uint")]
 pub type UInt8 = sqlx_pg_uint::PgU8;
 
 #[derive(Clone, Default, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -208,15 +233,101 @@ pub struct UrlBundle {
     pub cdn: String,
 }
 
+/// The schemes the root, api and cdn urls of a [UrlBundle] can use
+const HTTP_SCHEMES: &[&str] = &["http", "https"];
+
+/// The schemes the gateway url of a [UrlBundle] can use
+const WEBSOCKET_SCHEMES: &[&str] = &["ws", "wss"];
+
+/// The Discord API version chorus is built upon
+pub const DISCORD_API_VERSION: u8 = 9;
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+/// A release channel of the Discord.com client, each of which is served from its own hostname.
+///
+/// See [UrlBundle::discord].
+pub enum DiscordRelease {
+    /// `discord.com`
+    #[default]
+    Stable,
+    /// `ptb.discord.com`, the public test build
+    Ptb,
+    /// `canary.discord.com`, the alpha test build
+    Canary,
+}
+
+impl DiscordRelease {
+    /// Returns the hostname this release is served from, e.g. `canary.discord.com`.
+    pub fn hostname(self) -> &'static str {
+        match self {
+            DiscordRelease::Stable => "discord.com",
+            DiscordRelease::Ptb => "ptb.discord.com",
+            DiscordRelease::Canary => "canary.discord.com",
+        }
+    }
+}
+
 impl UrlBundle {
     /// Creates a new UrlBundle from the relevant urls.
-    pub fn new(root: &str, api: &str, wss: &str, cdn: &str) -> Self {
-        Self {
-            root: UrlBundle::parse_url(root),
-            api: UrlBundle::parse_url(api),
-            wss: UrlBundle::parse_url(wss),
-            cdn: UrlBundle::parse_url(cdn),
-        }
+    ///
+    /// # Errors
+    /// Returns [ChorusError::InvalidUrl] if any of the urls can't be parsed, if `wss` is not a
+    /// `ws://` or `wss://` url, or if any of the other urls are not `http://` or `https://` urls.
+    pub fn new(root: &str, api: &str, wss: &str, cdn: &str) -> ChorusResult<Self> {
+        Ok(Self {
+            root: UrlBundle::parse_url_with_schemes(root, HTTP_SCHEMES)?,
+            api: UrlBundle::parse_url_with_schemes(api, HTTP_SCHEMES)?,
+            wss: UrlBundle::parse_url_with_schemes(wss, WEBSOCKET_SCHEMES)?,
+            cdn: UrlBundle::parse_url_with_schemes(cdn, HTTP_SCHEMES)?,
+        })
+    }
+
+    /// Creates the UrlBundle of a Discord.com release, using API version
+    /// [DISCORD_API_VERSION].
+    ///
+    /// Useful for testing adapters against Discord.com itself, which does not serve the
+    /// `/policies/instance/domains` endpoint [UrlBundle::from_root_url] relies on.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use chorus::{instance::Instance, DiscordRelease, UrlBundle};
+    /// # async fn example() -> chorus::errors::ChorusResult<()> {
+    /// let urls = UrlBundle::discord(DiscordRelease::Canary);
+    /// let instance = Instance::from_url_bundle(urls, None).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn discord(release: DiscordRelease) -> Self {
+        let root = format!("https://{}", release.hostname());
+        let api = format!("{}/api/v{}", root, DISCORD_API_VERSION);
+
+        // The gateway and cdn are shared between all releases
+        UrlBundle::new(
+            &root,
+            &api,
+            "wss://gateway.discord.gg",
+            "https://cdn.discordapp.com",
+        )
+        // Safety: expect is justified here, since all of these urls are known to be valid
+        .expect("Discord.com urls should be valid")
+    }
+
+    /// Returns whether the urls point to Discord itself, going by the hosts of the api and gateway
+    /// urls
+    pub(crate) fn is_discord(&self) -> bool {
+        let is_discord_host = |url: &str, domains: &[&str]| {
+            Url::parse(url)
+                .ok()
+                .and_then(|url| url.host_str().map(str::to_string))
+                .is_some_and(|host| {
+                    domains
+                        .iter()
+                        .any(|domain| host == *domain || host.ends_with(&format!(".{}", domain)))
+                })
+        };
+
+        is_discord_host(&self.api, &["discord.com", "discordapp.com"])
+            || is_discord_host(&self.wss, &["discord.gg"])
     }
 
     /// Parses a URL using the Url library and formats it in a standardized way.
@@ -224,29 +335,53 @@ impl UrlBundle {
     ///
     /// # Examples:
     /// ```rs
-    /// let url = parse_url("localhost:3000");
+    /// let url = parse_url("localhost:3000")?;
     /// ```
     /// `-> Outputs "http://localhost:3000".`
-    pub fn parse_url(url: &str) -> String {
-        let url = match Url::parse(url) {
-            Ok(url) => {
-                if url.scheme() == "localhost" {
+    ///
+    /// # Errors
+    /// Returns [ChorusError::InvalidUrl] if the url can't be parsed.
+    pub fn parse_url(url: &str) -> ChorusResult<String> {
+        let parsed = match Url::parse(url) {
+            Ok(parsed) => {
+                if parsed.scheme() == "localhost" {
                     return UrlBundle::parse_url(&format!("http://{}", url));
                 }
-                url
+                parsed
             }
             Err(ParseError::RelativeUrlWithoutBase) => {
                 let url_fmt = format!("http://{}", url);
                 return UrlBundle::parse_url(&url_fmt);
             }
-            Err(_) => panic!("Invalid URL"), // TODO: should not panic here
+            Err(e) => {
+                return Err(ChorusError::InvalidUrl {
+                    url: url.to_string(),
+                    error: e.to_string(),
+                })
+            }
         };
         // if the last character of the string is a slash, remove it.
-        let mut url_string = url.to_string();
+        let mut url_string = parsed.to_string();
         if url_string.ends_with('/') {
             url_string.pop();
         }
-        url_string
+        Ok(url_string)
+    }
+
+    /// Parses a URL like [UrlBundle::parse_url], and makes sure it uses one of the given
+    /// schemes.
+    fn parse_url_with_schemes(url: &str, schemes: &[&str]) -> ChorusResult<String> {
+        let parsed = UrlBundle::parse_url(url)?;
+
+        // Parsing can't fail, we just formatted the url
+        let scheme = Url::parse(&parsed).map(|url| url.scheme().to_string());
+        match scheme {
+            Ok(scheme) if schemes.contains(&scheme.as_str()) => Ok(parsed),
+            _ => Err(ChorusError::InvalidUrl {
+                url: url.to_string(),
+                error: format!("expected a url with one of the schemes {:?}", schemes),
+            }),
+        }
     }
 
     /// Performs a few HTTP requests to try and retrieve a `UrlBundle` from an instances' root url.
@@ -260,8 +395,15 @@ impl UrlBundle {
     /// of the above approaches fail, it is very likely that the instance is misconfigured, unreachable, or that
     /// a wrong URL was provided.
     pub async fn from_root_url(url: &str) -> ChorusResult<UrlBundle> {
-        let parsed = UrlBundle::parse_url(url);
-        let client = reqwest::Client::new();
+        UrlBundle::from_root_url_with_client(url, &reqwest::Client::new()).await
+    }
+
+    /// Like [UrlBundle::from_root_url], but sends the requests using the given client.
+    pub(crate) async fn from_root_url_with_client(
+        url: &str,
+        client: &reqwest::Client,
+    ) -> ChorusResult<UrlBundle> {
+        let parsed = UrlBundle::parse_url(url)?;
         let request_wellknown = client
             .get(format!("{}/.well-known/spacebar", &parsed))
             .header(http::header::ACCEPT, "application/json")
@@ -269,15 +411,19 @@ impl UrlBundle {
         let response_wellknown = client.execute(request_wellknown).await?;
         if response_wellknown.status().is_success() {
             let body = response_wellknown.json::<WellKnownResponse>().await?.api;
-            UrlBundle::from_api_url(&body).await
+            UrlBundle::from_api_url(&body, client).await
         } else {
-            if let Ok(response_slash_api) =
-                UrlBundle::from_api_url(&format!("{}/api/policies/instance/domains", parsed)).await
+            if let Ok(response_slash_api) = UrlBundle::from_api_url(
+                &format!("{}/api/policies/instance/domains", parsed),
+                client,
+            )
+            .await
             {
                 return Ok(response_slash_api);
             }
             if let Ok(response_api) =
-                UrlBundle::from_api_url(&format!("{}/policies/instance/domains", parsed)).await
+                UrlBundle::from_api_url(&format!("{}/policies/instance/domains", parsed), client)
+                    .await
             {
                 Ok(response_api)
             } else {
@@ -286,8 +432,7 @@ impl UrlBundle {
         }
     }
 
-    async fn from_api_url(url: &str) -> ChorusResult<UrlBundle> {
-        let client = reqwest::Client::new();
+    async fn from_api_url(url: &str, client: &reqwest::Client) -> ChorusResult<UrlBundle> {
         let request = client
             .get(url)
             .header(http::header::ACCEPT, "application/json")
@@ -297,12 +442,7 @@ impl UrlBundle {
             .json::<types::types::domains_configuration::Domains>()
             .await
         {
-            Ok(UrlBundle::new(
-                url,
-                &body.api_endpoint,
-                &body.gateway,
-                &body.cdn,
-            ))
+            UrlBundle::new(url, &body.api_endpoint, &body.gateway, &body.cdn)
         } else {
             Err(ChorusError::RequestFailed {
                 url: url.to_string(),
@@ -318,13 +458,64 @@ mod lib {
 
     #[test]
     fn test_parse_url() {
-        let mut result = UrlBundle::parse_url("localhost:3000/");
+        let mut result = UrlBundle::parse_url("localhost:3000/").unwrap();
         assert_eq!(result, "http://localhost:3000");
-        result = UrlBundle::parse_url("https://some.url.com/");
+        result = UrlBundle::parse_url("https://some.url.com/").unwrap();
         assert_eq!(result, String::from("https://some.url.com"));
-        result = UrlBundle::parse_url("https://some.url.com/");
+        result = UrlBundle::parse_url("https://some.url.com/").unwrap();
         assert_eq!(result, "https://some.url.com");
-        result = UrlBundle::parse_url("https://some.url.com");
+        result = UrlBundle::parse_url("https://some.url.com").unwrap();
         assert_eq!(result, "https://some.url.com");
+
+        assert!(matches!(
+            UrlBundle::parse_url("http://[::1"),
+            Err(ChorusError::InvalidUrl { .. })
+        ));
+    }
+
+    #[test]
+    fn test_url_bundle_schemes() {
+        let urls = UrlBundle::new(
+            "https://example.com",
+            "https://example.com/api",
+            "wss://gateway.example.com",
+            "https://cdn.example.com",
+        )
+        .unwrap();
+        assert_eq!(urls.wss, "wss://gateway.example.com");
+
+        // The gateway url has to be a websocket url..
+        assert!(matches!(
+            UrlBundle::new(
+                "https://example.com",
+                "https://example.com/api",
+                "https://gateway.example.com",
+                "https://cdn.example.com",
+            ),
+            Err(ChorusError::InvalidUrl { .. })
+        ));
+
+        // ..and the cdn url can't be one
+        assert!(matches!(
+            UrlBundle::new(
+                "https://example.com",
+                "https://example.com/api",
+                "wss://gateway.example.com",
+                "wss://cdn.example.com",
+            ),
+            Err(ChorusError::InvalidUrl { .. })
+        ));
+    }
+
+    #[test]
+    fn test_discord_url_bundle() {
+        let urls = UrlBundle::discord(DiscordRelease::Canary);
+        assert_eq!(urls.root, "https://canary.discord.com");
+        assert_eq!(urls.api, "https://canary.discord.com/api/v9");
+        assert_eq!(urls.wss, "wss://gateway.discord.gg");
+        assert_eq!(urls.cdn, "https://cdn.discordapp.com");
+
+        let urls = UrlBundle::discord(DiscordRelease::Stable);
+        assert_eq!(urls.api, "https://discord.com/api/v9");
     }
 }
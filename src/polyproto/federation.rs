@@ -0,0 +1,91 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Helpers for addressing users across instances with [FederatedId]s.
+
+use url::Url;
+
+use crate::{
+    errors::{ChorusError, ChorusResult},
+    instance::{ChorusUser, Instance},
+    types::{FederatedId, Message, RelationshipType, Snowflake},
+};
+
+impl Instance {
+    /// Returns the domain this instance federates as, which is the host of its root url.
+    pub fn federation_domain(&self) -> String {
+        Url::parse(&self.urls.root)
+            .ok()
+            .and_then(|url| url.host_str().map(|host| host.to_lowercase()))
+            .unwrap_or_else(|| self.urls.root.clone())
+    }
+
+    /// Returns the [FederatedId] of an entity local to this instance.
+    pub fn federated_id_of(&self, snowflake: Snowflake) -> FederatedId {
+        FederatedId::from_snowflake(snowflake, &self.federation_domain())
+    }
+
+    /// Returns the local [Snowflake] a [FederatedId] maps to, if it belongs to this instance.
+    pub fn local_snowflake_of(&self, id: &FederatedId) -> Option<Snowflake> {
+        if !id.is_local_to(&self.federation_domain()) {
+            return None;
+        }
+        id.to_snowflake()
+    }
+}
+
+impl ChorusUser {
+    /// Returns the [FederatedId] of the current user.
+    pub fn federated_id(&self) -> FederatedId {
+        let id = self.object.read().unwrap().id;
+        self.belongs_to.read().unwrap().federated_id_of(id)
+    }
+
+    /// Sends a friend request to a local user, identified by their [FederatedId].
+    ///
+    /// This is the same as [ChorusUser::modify_user_relationship] with
+    /// [RelationshipType::Friends].
+    ///
+    /// Returns [ChorusError::InvalidArguments] if the id does not belong to a user on the current
+    /// instance.
+    pub async fn send_federated_friend_request(&mut self, id: &FederatedId) -> ChorusResult<()> {
+        let local_snowflake = self.belongs_to.read().unwrap().local_snowflake_of(id);
+
+        let Some(user_id) = local_snowflake else {
+            return Err(ChorusError::InvalidArguments {
+                error: format!("{} is not a user on this instance", id),
+            });
+        };
+
+        self.modify_user_relationship(user_id, RelationshipType::Friends)
+            .await
+    }
+
+    /// Removes the relationship between the current user and a local user, identified by their
+    /// [FederatedId].
+    ///
+    /// Returns [ChorusError::InvalidArguments] if the id does not belong to a user on the current
+    /// instance.
+    pub async fn remove_federated_relationship(&mut self, id: &FederatedId) -> ChorusResult<()> {
+        let local_snowflake = self.belongs_to.read().unwrap().local_snowflake_of(id);
+
+        let Some(user_id) = local_snowflake else {
+            return Err(ChorusError::InvalidArguments {
+                error: format!("{} is not a user on this instance", id),
+            });
+        };
+
+        self.remove_relationship(user_id).await
+    }
+}
+
+impl Message {
+    /// Returns the [FederatedId] of the message's author, given the [Instance] the message was
+    /// received from.
+    pub fn author_federated_id(&self, instance: &Instance) -> Option<FederatedId> {
+        self.author
+            .as_ref()
+            .map(|author| instance.federated_id_of(author.id))
+    }
+}
@@ -0,0 +1,96 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Logging in with a polyproto identity, instead of a password.
+//!
+//! The client proves it owns the private key of an ID-Cert by signing a challenge string the
+//! home server issued, and receives a session token in exchange.
+
+use std::fmt::Debug;
+use std::sync::{Arc, RwLock};
+
+use reqwest::Client;
+
+use crate::{
+    errors::ChorusResult,
+    instance::{ChorusUser, Instance},
+    ratelimiter::ChorusRequest,
+    types::{ChallengeString, IdentifyResponse, IdentifySchema, LimitType},
+};
+
+/// The identity of a polyproto session: its ID-Cert, along with the private key belonging to it.
+///
+/// Chorus does not implement the cryptography itself; implement this on top of your key
+/// storage, e.g. with the `polyproto` crate.
+pub trait SessionIdentity: Send + Sync + Debug {
+    /// The session's ID-Cert, PEM encoded
+    fn id_cert(&self) -> String;
+
+    /// Signs a challenge string with the private key belonging to the ID-Cert.
+    ///
+    /// Returns the signature, encoded as the home server expects it.
+    ///
+    /// Returning an error aborts the login, and should usually be
+    /// [ChorusError::InvalidSignature](crate::errors::ChorusError::InvalidSignature).
+    fn sign_challenge(&self, challenge: &str) -> ChorusResult<String>;
+}
+
+impl Instance {
+    /// Logs into an account on a polyphony home server with a polyproto identity, instead of a
+    /// password.
+    ///
+    /// Fetches a challenge string, signs it with `identity` and exchanges it for a session
+    /// token. The session's ID-Cert is stored on the returned user, see
+    /// [ChorusUser::id_cert].
+    ///
+    /// `auth_payload` is passed on to the server as is, for servers which need additional
+    /// authentication data.
+    ///
+    /// # Reference
+    /// See <https://docs.polyphony.chat/Protocol%20Specifications/core/#4-authentication>
+    pub async fn login_polyproto(
+        &mut self,
+        identity: Arc<dyn SessionIdentity>,
+        auth_payload: Option<serde_json::Value>,
+    ) -> ChorusResult<ChorusUser> {
+        let mut user = ChorusUser::shell(Arc::new(RwLock::new(self.clone())), "None").await;
+
+        let challenge = self.get_challenge_string(&mut user).await?;
+
+        let schema = IdentifySchema {
+            signature: identity.sign_challenge(&challenge.challenge)?,
+            challenge: challenge.challenge,
+            id_cert: identity.id_cert(),
+            auth_payload,
+        };
+
+        let chorus_request = ChorusRequest {
+            request: Client::new()
+                .post(self.urls.root.clone() + "/.p2/core/v1/session/identify")
+                .json(&schema),
+            limit_type: LimitType::AuthLogin,
+        };
+
+        let response = chorus_request
+            .deserialize_response::<IdentifyResponse>(&mut user)
+            .await?;
+
+        user.id_cert = Some(schema.id_cert);
+        user.update_with_login_data(response.token, None).await?;
+
+        Ok(user)
+    }
+
+    /// Fetches a challenge string to sign, see [Instance::login_polyproto].
+    async fn get_challenge_string(&self, user: &mut ChorusUser) -> ChorusResult<ChallengeString> {
+        let chorus_request = ChorusRequest {
+            request: Client::new().get(self.urls.root.clone() + "/.p2/core/v1/challenge"),
+            limit_type: LimitType::AuthLogin,
+        };
+
+        chorus_request
+            .deserialize_response::<ChallengeString>(user)
+            .await
+    }
+}
@@ -0,0 +1,15 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Support for [polyproto](https://docs.polyphony.chat/Protocol%20Specifications/core/),
+//! polyphony's federation protocol.
+//!
+//! Requires the `polyproto` feature.
+
+pub mod federation;
+pub mod identity;
+pub mod signing;
+
+pub use identity::*;
+pub use signing::*;
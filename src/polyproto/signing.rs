@@ -0,0 +1,180 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Hooks for signing outgoing requests and verifying responses of federated servers.
+
+use std::fmt::Debug;
+
+use reqwest::header::HeaderMap;
+use reqwest::{Request, Response, StatusCode};
+
+#[cfg(not(target_arch = "wasm32"))]
+use reqwest::ResponseBuilderExt;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::errors::ChorusError;
+use crate::errors::ChorusResult;
+
+/// Signs outgoing REST requests and verifies the responses of federated servers.
+///
+/// Set one on an [Instance](crate::instance::Instance) with
+/// [Instance::set_request_signer](crate::instance::Instance::set_request_signer); it is then
+/// used for every [ChorusRequest](crate::ratelimiter::ChorusRequest) sent to that instance.
+///
+/// This is where a polyproto client attaches its identity signatures and headers, and checks that
+/// responses really originate from the server it is talking to.
+pub trait RequestSigner: Send + Sync + Debug {
+    /// Signs an outgoing request, e. g. by attaching a signature header computed over the
+    /// request's method, url and body.
+    ///
+    /// Returning an error aborts the request.
+    fn sign_request(&self, request: &mut Request) -> ChorusResult<()>;
+
+    /// Verifies the response to a signed request.
+    ///
+    /// Returning an error discards the response; the request fails with that error, which
+    /// should usually be [ChorusError::InvalidSignature](crate::errors::ChorusError::InvalidSignature).
+    ///
+    /// The default implementation accepts all responses.
+    fn verify_response(&self, response: &SignedResponse) -> ChorusResult<()> {
+        let _ = response;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A received response, passed to [RequestSigner::verify_response].
+pub struct SignedResponse {
+    /// The url the request was sent to
+    pub url: String,
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    /// The response body.
+    ///
+    /// Always `None` on `wasm32`, since there the body can't be buffered and handed back
+    /// to the rest of chorus afterwards.
+    pub body: Option<Vec<u8>>,
+}
+
+/// Buffers a [Response], lets `signer` verify it and rebuilds it.
+///
+/// The rebuilt response keeps the url, http version and extensions of the original one.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn verify_response(
+    signer: &dyn RequestSigner,
+    mut response: Response,
+) -> ChorusResult<Response> {
+    let url = response.url().clone();
+    let status = response.status();
+    let version = response.version();
+    let headers = response.headers().clone();
+    let extensions = std::mem::take(response.extensions_mut());
+    let body = match response.bytes().await {
+        Ok(bytes) => bytes.to_vec(),
+        Err(e) => {
+            return Err(ChorusError::InvalidResponse {
+                error: format!(
+                    "Error while trying to read the HTTP response for verification: {}",
+                    e
+                ),
+            })
+        }
+    };
+
+    let signed_response = SignedResponse {
+        url: url.to_string(),
+        status,
+        headers,
+        body: Some(body),
+    };
+    signer.verify_response(&signed_response)?;
+
+    // Without the url, reqwest would make one up, which error messages would then refer to
+    let mut rebuilt = http::Response::builder()
+        .status(signed_response.status)
+        .version(version)
+        .url(url)
+        .body(signed_response.body.unwrap_or_default())
+        .expect("the parts of a received response are valid");
+    *rebuilt.headers_mut() = signed_response.headers;
+    rebuilt.extensions_mut().extend(extensions);
+    Ok(Response::from(rebuilt))
+}
+
+/// Lets `signer` verify a [Response], without its body.
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn verify_response(
+    signer: &dyn RequestSigner,
+    response: Response,
+) -> ChorusResult<Response> {
+    let signed_response = SignedResponse {
+        url: response.url().to_string(),
+        status: response.status(),
+        headers: response.headers().clone(),
+        body: None,
+    };
+    signer.verify_response(&signed_response)?;
+
+    Ok(response)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod test {
+    use super::*;
+
+    /// Accepts responses which carry a `x-signature` header
+    #[derive(Debug)]
+    struct HeaderSigner;
+
+    impl RequestSigner for HeaderSigner {
+        fn sign_request(&self, _request: &mut Request) -> ChorusResult<()> {
+            Ok(())
+        }
+
+        fn verify_response(&self, response: &SignedResponse) -> ChorusResult<()> {
+            match response.headers.contains_key("x-signature") {
+                true => Ok(()),
+                false => Err(ChorusError::InvalidSignature {
+                    error: format!("{} is not signed", response.url),
+                }),
+            }
+        }
+    }
+
+    fn response(signed: bool) -> Response {
+        let mut response = http::Response::builder()
+            .status(StatusCode::CREATED)
+            .version(http::Version::HTTP_2)
+            .url("https://example.com/api/users/@me".parse().unwrap());
+        if signed {
+            response = response.header("x-signature", "signature");
+        }
+        Response::from(response.body("{}").unwrap())
+    }
+
+    #[tokio::test]
+    async fn verified_response_is_kept_intact() {
+        let verified = verify_response(&HeaderSigner, response(true))
+            .await
+            .unwrap();
+
+        assert_eq!(verified.url().as_str(), "https://example.com/api/users/@me");
+        assert_eq!(verified.status(), StatusCode::CREATED);
+        assert_eq!(verified.version(), http::Version::HTTP_2);
+        assert_eq!(verified.headers()["x-signature"], "signature");
+        assert_eq!(verified.text().await.unwrap(), "{}");
+    }
+
+    #[tokio::test]
+    async fn failed_verification_is_an_error() {
+        let result = verify_response(&HeaderSigner, response(false)).await;
+
+        assert_eq!(
+            result.unwrap_err(),
+            ChorusError::InvalidSignature {
+                error: "https://example.com/api/users/@me is not signed".to_string()
+            }
+        );
+    }
+}
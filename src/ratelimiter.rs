@@ -5,15 +5,27 @@
 //! Ratelimiter and request handling functionality.
 
 use std::collections::HashMap;
+use std::time::Duration;
 
-use reqwest::{Client, RequestBuilder, Response};
-use serde::Deserialize;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::time::sleep;
+#[cfg(target_arch = "wasm32")]
+use wasmtimer::tokio::sleep;
+
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use rand::Rng;
+use reqwest::header::{HeaderValue, AUTHORIZATION};
+use reqwest::{Client, Request, RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize};
 use serde_json::from_str;
 
+use tokio::sync::broadcast;
+
 use crate::{
-    errors::{ChorusError, ChorusResult},
+    errors::{ApiError, ChorusError, ChorusResult},
+    gateway::{broadcast_stream, EVENT_STREAM_CAPACITY},
     instance::ChorusUser,
-    types::{types::subconfigs::limits::rates::RateLimits, Limit, LimitType, LimitsConfiguration, MfaRequiredSchema},
+    types::{types::subconfigs::limits::rates::RateLimits, Limit, LimitType, LimitsConfiguration, MfaRequiredSchema, Snowflake},
 };
 
 /// Chorus' request struct. This struct is used to send rate-limited requests to the Spacebar server.
@@ -62,80 +74,315 @@ impl ChorusRequest {
                 .body(body)
                 .header("Content-Type", "application/json");
         }
-        if let Some(reason) = audit_log_reason {
-            request = request.header("X-Audit-Log-Reason", reason);
-        }
 
         ChorusRequest {
             request,
             limit_type,
         }
+        .with_maybe_audit_log_reason(audit_log_reason)
+    }
+
+    /// Sets the reason shown in the guild's audit log for the action this request performs.
+    ///
+    /// Non-ASCII characters are percent-encoded, as required by the `X-Audit-Log-Reason`
+    /// header.
+    ///
+    /// # Example
+    /// ```rs
+    /// let request = ChorusRequest::new(http::Method::DELETE, &url, None, None, Some(user), limit_type)
+    ///     .with_audit_log_reason("Spam");
+    /// ```
+    pub fn with_audit_log_reason(mut self, reason: &str) -> ChorusRequest {
+        self.request = self
+            .request
+            .header("X-Audit-Log-Reason", encode_audit_log_reason(reason));
+        self
+    }
+
+    /// Sets the audit log reason of the request, if the reason is [Some].
+    ///
+    /// See [ChorusRequest::with_audit_log_reason].
+    pub fn with_maybe_audit_log_reason(self, reason: Option<&str>) -> ChorusRequest {
+        match reason {
+            Some(reason) => self.with_audit_log_reason(reason),
+            None => self,
+        }
+    }
+
+    /// Adds query parameters, serialized from a struct or a list of key-value pairs.
+    ///
+    /// Values are url encoded, and fields which are `None` are left out. Can be called multiple
+    /// times; parameters are appended, not replaced.
+    ///
+    /// # Example
+    /// ```rs
+    /// let request = ChorusRequest::new(http::Method::GET, &url, None, None, Some(user), limit_type)
+    ///     .query(&GuildBansQuery { limit: Some(10), ..Default::default() });
+    /// ```
+    pub fn query<T: Serialize + ?Sized>(mut self, query: &T) -> ChorusRequest {
+        self.request = self.request.query(query);
+        self
+    }
+
+    /// Adds a single query parameter.
+    pub fn query_param(self, key: &str, value: impl ToString) -> ChorusRequest {
+        self.query(&[(key, value.to_string())])
+    }
+
+    /// Adds a query parameter if `value` is `Some`.
+    pub fn optional_query_param(self, key: &str, value: Option<impl ToString>) -> ChorusRequest {
+        match value {
+            Some(value) => self.query_param(key, value),
+            None => self,
+        }
+    }
+
+    /// Adds a query parameter once for every value, as in `?key=a&key=b`.
+    pub fn repeated_query_param<V: ToString>(
+        self,
+        key: &str,
+        values: impl IntoIterator<Item = V>,
+    ) -> ChorusRequest {
+        let pairs: Vec<(&str, String)> = values
+            .into_iter()
+            .map(|value| (key, value.to_string()))
+            .collect();
+
+        self.query(&pairs)
     }
 
     /// Sends a [`ChorusRequest`]. Checks if the user is rate limited, and if not, sends the request.
     /// If the user is not rate limited and the instance has rate limits enabled, it will update the
     /// rate limits.
+    ///
+    /// Transient failures are retried according to the instance's [RetryPolicy].
     #[allow(clippy::await_holding_refcell_ref)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "chorus.request",
+            level = "debug",
+            skip_all,
+            fields(
+                method = tracing::field::Empty,
+                route = tracing::field::Empty,
+                bucket = ?self.limit_type,
+                status = tracing::field::Empty,
+                attempts = tracing::field::Empty,
+            )
+        )
+    )]
     pub(crate) async fn send_request(self, user: &mut ChorusUser) -> ChorusResult<Response> {
-        if !ChorusRequest::can_send_request(user, &self.limit_type) {
-            log::info!("Rate limit hit. Bucket: {:?}", self.limit_type);
+        let request = self.request.build().unwrap();
+        ChorusRequest::execute(request, self.limit_type, user, true).await
+    }
+
+    /// Sends a built request, see [ChorusRequest::send_request].
+    ///
+    /// If `refresh_token` is true and the user has a
+    /// [TokenRefresher](crate::token_store::TokenRefresher), a request rejected with a
+    /// `401 Unauthorized` is retried once with a new token.
+    async fn execute(
+        request: Request,
+        limit_type: LimitType,
+        user: &mut ChorusUser,
+        refresh_token: bool,
+    ) -> ChorusResult<Response> {
+        if !ChorusRequest::can_send_request(user, &limit_type) {
+            log::info!("Rate limit hit. Bucket: {:?}", limit_type);
             return Err(ChorusError::RateLimited {
-                bucket: format!("{:?}", self.limit_type),
+                bucket: format!("{:?}", limit_type),
             });
         }
-        let client = user.belongs_to.read().unwrap().client.clone();
-        let result = match client.execute(self.request.build().unwrap()).await {
-            Ok(result) => {
-                log::trace!("Request successful: {:?}", result);
-                result
+        let (client, retry_policy) = {
+            let instance = user.belongs_to.read().unwrap();
+            (instance.client.clone(), instance.retry_policy.clone())
+        };
+        #[cfg(feature = "polyproto")]
+        let signer = user.belongs_to.read().unwrap().request_signer.clone();
+
+        // Kept to retry the request with a new token, in case the current one is rejected
+        let mut refresh_retry = match &user.token_refresher {
+            Some(_) if refresh_token && request.headers().contains_key(AUTHORIZATION) => {
+                request.try_clone()
             }
-            Err(error) => {
-                log::warn!("Request failed: {:?}", error);
-                return Err(ChorusError::RequestFailed {
-                    url: error.url().unwrap().to_string(),
-                    error: error.to_string(),
-                });
+            _ => None,
+        };
+
+        let mut next_request = Some(request);
+        let max_attempts = retry_policy.max_attempts_for(next_request.as_ref().unwrap().method());
+        let mut attempt = 1;
+
+        #[cfg(feature = "tracing")]
+        {
+            let request = next_request.as_ref().unwrap();
+            let span = tracing::Span::current();
+            span.record("method", request.method().as_str());
+            span.record("route", request.url().path());
+        }
+
+        let result = loop {
+            let mut request = next_request.take().unwrap();
+            // Requests with streamed bodies can't be cloned, and are only sent once
+            let retry = match attempt < max_attempts {
+                true => request.try_clone(),
+                false => None,
+            };
+
+            user.request_metadata.apply(&mut request)?;
+            #[cfg(feature = "polyproto")]
+            if let Some(signer) = &signer {
+                signer.sign_request(&mut request)?;
             }
+
+            let result = match client.execute(request).await {
+                Ok(result) => {
+                    log::trace!("Request successful: {:?}", result);
+                    result
+                }
+                Err(error) if retry.is_some() && is_transient(&error) => {
+                    let delay = retry_policy.backoff(attempt);
+                    log::info!(
+                        "Request failed (attempt {}/{}), retrying in {:?}: {:?}",
+                        attempt,
+                        max_attempts,
+                        delay,
+                        error
+                    );
+                    sleep(delay).await;
+                    next_request = retry;
+                    attempt += 1;
+                    continue;
+                }
+                Err(error) => {
+                    log::warn!("Request failed: {:?}", error);
+                    return Err(ChorusError::RequestFailed {
+                        url: error.url().unwrap().to_string(),
+                        error: error.to_string(),
+                    });
+                }
+            };
+            #[cfg(feature = "polyproto")]
+            let result = match &signer {
+                Some(signer) => crate::polyproto::verify_response(signer.as_ref(), result).await?,
+                None => result,
+            };
+
+            let delay = match result.status().as_u16() {
+                429 => ChorusRequest::retry_after(&result)
+                    .unwrap_or_else(|| retry_policy.backoff(attempt)),
+                500..=599 => retry_policy.backoff(attempt),
+                _ => break result,
+            };
+            if retry.is_none() || delay > retry_policy.max_delay {
+                break result;
+            }
+
+            log::info!(
+                "Received {} (attempt {}/{}), retrying in {:?}",
+                result.status(),
+                attempt,
+                max_attempts,
+                delay
+            );
+            sleep(delay).await;
+            next_request = retry;
+            attempt += 1;
         };
-        drop(client);
+
+        #[cfg(feature = "tracing")]
+        {
+            let span = tracing::Span::current();
+            span.record("status", result.status().as_u16());
+            span.record("attempts", attempt);
+        }
+
         if !result.status().is_success() {
             if result.status().as_u16() == 429 {
-                log::warn!("Rate limit hit unexpectedly. Bucket: {:?}. Setting the instances' remaining global limit to 0 to have cooldown.", self.limit_type);
-                user.belongs_to
+                log::warn!("Rate limit hit unexpectedly. Bucket: {:?}. Setting the instances' remaining global limit to 0 to have cooldown.", limit_type);
+                let global = user
+                    .belongs_to
                     .write()
                     .unwrap()
                     .limits_information
                     .as_mut()
-                    .unwrap()
-                    .ratelimits
-                    .get_mut(&LimitType::Global)
-                    .unwrap()
-                    .remaining = 0;
+                    .and_then(|info| info.ratelimits.get_mut(&LimitType::Global))
+                    .map(|global| {
+                        global.remaining = 0;
+                        *global
+                    });
+                if let Some(global) = global {
+                    ChorusRequest::publish_exhausted(user, vec![global]);
+                }
                 return Err(ChorusError::RateLimited {
-                    bucket: format!("{:?}", self.limit_type),
+                    bucket: format!("{:?}", limit_type),
                 });
             }
+            if result.status() == StatusCode::UNAUTHORIZED {
+                if let Some(request) = refresh_retry.take() {
+                    return ChorusRequest::refresh_token_and_retry(
+                        request, limit_type, result, user,
+                    )
+                    .await;
+                }
+            }
             log::warn!("Request failed: {:?}", result);
             return Err(ChorusRequest::interpret_error(result).await);
         }
-        ChorusRequest::update_rate_limits(user, &self.limit_type, !result.status().is_success());
+        ChorusRequest::update_rate_limits(user, &limit_type, !result.status().is_success());
         Ok(result)
     }
 
-    fn can_send_request(user: &mut ChorusUser, limit_type: &LimitType) -> bool {
+    /// Obtains a new token with the user's [TokenRefresher](crate::token_store::TokenRefresher)
+    /// after `request` was rejected with a `401 Unauthorized`, and retries it once with the new
+    /// token.
+    async fn refresh_token_and_retry(
+        mut request: Request,
+        limit_type: LimitType,
+        response: Response,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<Response> {
+        let status = response.status().as_u16();
+        let body = response.text().await.unwrap_or_default();
+
+        // A new token does not help if the request requires MFA
+        let requires_mfa = from_str::<MfaRequiredSchema>(&body).is_ok();
+
+        if let (false, Some(refresher)) = (requires_mfa, user.token_refresher.clone()) {
+            log::info!("The user's token was rejected, trying to refresh it");
+
+            if let Some(token) = refresher.refresh_token(user.belongs_to.clone()).await? {
+                user.set_token(&token);
+
+                let header =
+                    HeaderValue::from_str(&token).map_err(|e| ChorusError::InvalidArguments {
+                        error: format!("The refreshed token is not a valid header value: {}", e),
+                    })?;
+                request.headers_mut().insert(AUTHORIZATION, header);
+
+                return Box::pin(ChorusRequest::execute(request, limit_type, user, false)).await;
+            }
+        }
+
+        log::warn!("Request failed with status {}: {}", status, body);
+        Err(ChorusRequest::interpret_error_body(status, None, body))
+    }
+
+    /// Returns whether a request in the bucket of `limit_type` can be sent without being rate
+    /// limited by us.
+    ///
+    /// The instance's global and ip buckets are checked as well. A bucket allows a request if it
+    /// has requests remaining, or if its reset has passed. Buckets are only replenished by
+    /// [ChorusRequest::update_rate_limits] after a request in them was sent, so an exhausted
+    /// bucket would otherwise block requests forever.
+    pub(crate) fn can_send_request(user: &mut ChorusUser, limit_type: &LimitType) -> bool {
         log::trace!("Checking if user or instance is rate-limited...");
         let mut belongs_to = user.belongs_to.write().unwrap();
         if belongs_to.limits_information.is_none() {
             log::trace!("Instance indicates no rate limits are configured. Continuing.");
             return true;
         }
-        let instance_dictated_limits = [
-            &LimitType::AuthLogin,
-            &LimitType::AuthRegister,
-            &LimitType::Global,
-            &LimitType::Ip,
-        ];
-        let limits = match instance_dictated_limits.contains(&limit_type) {
+        let limits = match limit_type.is_instance_wide() {
             true => {
                 log::trace!(
                     "Limit type {:?} is dictated by the instance. Continuing.",
@@ -180,7 +427,44 @@ impl ChorusRequest {
             .get(&LimitType::Ip)
             .unwrap();
         let limit_type_limit = limits.get(limit_type).unwrap();
-        global.remaining > 0 && ip.remaining > 0 && limit_type_limit.remaining > 0
+        let time: u64 = chrono::Utc::now().timestamp() as u64;
+        [global, ip, limit_type_limit]
+            .iter()
+            .all(|limit| limit.remaining > 0 || time > limit.reset)
+    }
+
+    /// Returns how long to wait until a request in the bucket of `limit_type` can be sent
+    /// without being rate limited by us.
+    ///
+    /// Takes the instance's global and ip buckets into account as well. Returns
+    /// [Duration::ZERO] if a request can be sent right away.
+    pub(crate) fn time_until_available(user: &ChorusUser, limit_type: &LimitType) -> Duration {
+        let belongs_to = user.belongs_to.read().unwrap();
+        let Some(limits_information) = belongs_to.limits_information.as_ref() else {
+            return Duration::ZERO;
+        };
+        let limit_type_limit = match limit_type.is_instance_wide() {
+            true => limits_information.ratelimits.get(limit_type),
+            false => user
+                .limits
+                .as_ref()
+                .and_then(|limits| limits.get(limit_type)),
+        };
+        let relevant_limits = [
+            limits_information.ratelimits.get(&LimitType::Global),
+            limits_information.ratelimits.get(&LimitType::Ip),
+            limit_type_limit,
+        ];
+        let time: u64 = chrono::Utc::now().timestamp() as u64;
+        let seconds = relevant_limits
+            .into_iter()
+            .flatten()
+            .filter(|limit| limit.remaining == 0)
+            // Limits are replenished once the current time is past their reset
+            .map(|limit| (limit.reset + 1).saturating_sub(time))
+            .max()
+            .unwrap_or(0);
+        Duration::from_secs(seconds)
     }
 
     fn ensure_limit_in_map(
@@ -263,27 +547,58 @@ impl ChorusRequest {
         }
     }
 
+    /// Returns how long a 429 response asks to wait before retrying, from its `Retry-After`
+    /// header.
+    fn retry_after(response: &Response) -> Option<Duration> {
+        let seconds = response
+            .headers()
+            .get("Retry-After")?
+            .to_str()
+            .ok()?
+            .parse::<f64>()
+            .ok()?;
+
+        Duration::try_from_secs_f64(seconds).ok()
+    }
+
     async fn interpret_error(response: reqwest::Response) -> ChorusError {
-        match response.status().as_u16() {
-            401 => {
-                let response = response.text().await.unwrap();
-                match serde_json::from_str::<MfaRequiredSchema>(&response) {
-                    Ok(response) => ChorusError::MfaRequired { error: response },
-                    Err(_) => ChorusError::NoPermission,
-                }
+        let status = response.status().as_u16();
+        let ratelimit_scope = response
+            .headers()
+            .get("X-RateLimit-Scope")
+            .and_then(|scope| scope.to_str().ok())
+            .map(String::from);
+        let body = response.text().await.unwrap_or_default();
+
+        ChorusRequest::interpret_error_body(status, ratelimit_scope, body)
+    }
+
+    /// Interprets the status and body of an error response, see [ChorusRequest::interpret_error]
+    fn interpret_error_body(
+        status: u16,
+        ratelimit_scope: Option<String>,
+        body: String,
+    ) -> ChorusError {
+        if status == 401 {
+            if let Ok(error) = serde_json::from_str::<MfaRequiredSchema>(&body) {
+                return ChorusError::MfaRequired { error };
             }
-            402..=403 | 407 => ChorusError::NoPermission,
-            404 => ChorusError::NotFound {
-                error: response.text().await.unwrap(),
-            },
-            405 | 408 | 409 => ChorusError::ReceivedErrorCode { error_code: response.status().as_u16(), error: response.text().await.unwrap() },
-            411..=421 | 426 | 428 | 431 => ChorusError::InvalidArguments {
-                error: response.text().await.unwrap(),
-            },
+        }
+
+        if let Some(mut error) = ApiError::from_body(status, &body) {
+            error.ratelimit_scope = ratelimit_scope;
+            return ChorusError::Api { error };
+        }
+
+        match status {
+            401..=403 | 407 => ChorusError::NoPermission,
+            404 => ChorusError::NotFound { error: body },
+            405 | 408 | 409 => ChorusError::ReceivedErrorCode { error_code: status, error: body },
+            411..=421 | 426 | 428 | 431 => ChorusError::InvalidArguments { error: body },
             429 => panic!("Illegal state: Rate limit exception should have been caught before this function call."),
             451 => ChorusError::NoResponse,
-            500..=599 => ChorusError::ReceivedErrorCode { error_code: response.status().as_u16(), error: response.text().await.unwrap() },
-            _ => ChorusError::ReceivedErrorCode { error_code: response.status().as_u16(), error: response.text().await.unwrap()},
+            500..=599 => ChorusError::ReceivedErrorCode { error_code: status, error: body },
+            _ => ChorusError::ReceivedErrorCode { error_code: status, error: body },
         }
     }
 
@@ -292,19 +607,17 @@ impl ChorusRequest {
     ///     set to the current unix timestamp + the rate limit window. The remaining rate limit is
     ///     reset to the rate limit limit.
     /// 2. The remaining rate limit is decreased by 1.
-    fn update_rate_limits(user: &mut ChorusUser, limit_type: &LimitType, response_was_err: bool) {
+    pub(crate) fn update_rate_limits(
+        user: &mut ChorusUser,
+        limit_type: &LimitType,
+        response_was_err: bool,
+    ) {
         if user.belongs_to.read().unwrap().limits_information.is_none() {
             return;
         }
-        let instance_dictated_limits = [
-            &LimitType::AuthLogin,
-            &LimitType::AuthRegister,
-            &LimitType::Global,
-            &LimitType::Ip,
-        ];
         // modify this to store something to look up the value with later, instead of storing a reference to the actual data itself.
         let mut relevant_limits = Vec::new();
-        if instance_dictated_limits.contains(&limit_type) {
+        if limit_type.is_instance_wide() {
             relevant_limits.push((LimitOrigin::Instance, *limit_type));
         } else {
             relevant_limits.push((LimitOrigin::User, *limit_type));
@@ -315,6 +628,7 @@ impl ChorusRequest {
             relevant_limits.push((LimitOrigin::User, LimitType::Error));
         }
         let time: u64 = chrono::Utc::now().timestamp() as u64;
+        let mut exhausted = Vec::new();
         for relevant_limit in relevant_limits.iter() {
             let mut belongs_to = user.belongs_to.write().unwrap();
             let limit = match relevant_limit.0 {
@@ -348,6 +662,28 @@ impl ChorusRequest {
                 limit.remaining = limit.limit;
             }
             limit.remaining -= 1;
+            if limit.remaining == 0 {
+                exhausted.push(*limit);
+            }
+        }
+
+        if !exhausted.is_empty() {
+            ChorusRequest::publish_exhausted(user, exhausted);
+        }
+    }
+
+    /// Sends a [RatelimitExhausted] event for every bucket in `limits` to the
+    /// [streams of the user's instance](crate::instance::Instance::ratelimit_events).
+    fn publish_exhausted(user: &ChorusUser, limits: Vec<Limit>) {
+        let user_id = user.object.read().unwrap().id;
+        let sender = user.belongs_to.read().unwrap().ratelimit_events.clone();
+        for limit in limits {
+            log::debug!(
+                "Rate limit exhausted until {}. Bucket: {:?}",
+                limit.reset,
+                limit.bucket
+            );
+            sender.send(RatelimitExhausted { user_id, limit });
         }
     }
 
@@ -524,9 +860,384 @@ impl ChorusRequest {
         };
         Ok(object)
     }
+
+    /// Sends a [`ChorusRequest`] and returns a [`ChorusResult`] that contains the raw bytes of the
+    /// response body if the request was successful, or a [`ChorusError`] if the request failed.
+    ///
+    /// Used for endpoints which do not respond with JSON, such as images.
+    pub(crate) async fn response_bytes(self, user: &mut ChorusUser) -> ChorusResult<Vec<u8>> {
+        let response = self.send_request(user).await?;
+        log::trace!("Got response: {:?}", response);
+        match response.bytes().await {
+            Ok(bytes) => Ok(bytes.to_vec()),
+            Err(e) => Err(ChorusError::InvalidResponse {
+                error: format!(
+                    "Error while trying to read the bytes of the HTTP response: {}",
+                    e
+                ),
+            }),
+        }
+    }
+}
+
+/// The characters which are percent-encoded in audit log reasons.
+///
+/// Non-ASCII characters are always encoded; additionally, control characters can't be part of a
+/// header, and `%` has to be encoded so the server doesn't decode it.
+const AUDIT_LOG_REASON_ENCODE_SET: &AsciiSet = &CONTROLS.add(b'%');
+
+/// Percent-encodes an audit log reason, so it can be sent in the `X-Audit-Log-Reason` header.
+fn encode_audit_log_reason(reason: &str) -> String {
+    utf8_percent_encode(reason, AUDIT_LOG_REASON_ENCODE_SET).to_string()
+}
+
+/// Metadata which is added to every request a [ChorusUser] sends.
+///
+/// Useful e.g. for moderation bots, which should label every automated action in the audit log.
+///
+/// See [ChorusUser::request_metadata].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RequestMetadata {
+    /// Prepended as-is to the audit log reason of every request.
+    ///
+    /// Used as the whole reason for requests which do not have one.
+    pub audit_log_reason_prefix: Option<String>,
+    /// The locale to send in the `X-Discord-Locale` header, such as `en-US`
+    pub locale: Option<String>,
+    /// The IANA timezone to send in the `X-Discord-Timezone` header, such as `Europe/Berlin`
+    pub timezone: Option<String>,
+}
+
+impl RequestMetadata {
+    /// Adds the metadata to a request.
+    ///
+    /// # Errors
+    /// Returns [ChorusError::InvalidArguments] if any of the metadata is not a valid header value.
+    pub(crate) fn apply(&self, request: &mut reqwest::Request) -> ChorusResult<()> {
+        let headers = request.headers_mut();
+
+        if let Some(prefix) = &self.audit_log_reason_prefix {
+            let prefix = encode_audit_log_reason(prefix);
+            let reason = match headers.get("X-Audit-Log-Reason") {
+                Some(reason) => format!("{}{}", prefix, reason.to_str().unwrap_or_default()),
+                None => prefix,
+            };
+            headers.insert(
+                "X-Audit-Log-Reason",
+                RequestMetadata::header_value(&reason)?,
+            );
+        }
+        if let Some(locale) = &self.locale {
+            headers.insert("X-Discord-Locale", RequestMetadata::header_value(locale)?);
+        }
+        if let Some(timezone) = &self.timezone {
+            headers.insert(
+                "X-Discord-Timezone",
+                RequestMetadata::header_value(timezone)?,
+            );
+        }
+
+        Ok(())
+    }
+
+    fn header_value(value: &str) -> ChorusResult<reqwest::header::HeaderValue> {
+        reqwest::header::HeaderValue::from_str(value).map_err(|error| {
+            ChorusError::InvalidArguments {
+                error: format!("Invalid request metadata {:?}: {}", value, error),
+            }
+        })
+    }
+}
+
+/// When and how often failed requests are retried automatically.
+///
+/// Requests are retried if sending them failed because of a timeout or connection error, or if
+/// the server responded with a 5xx status or a 429 (Too Many Requests). Between attempts,
+/// chorus waits with an exponential, jittered backoff, or as long as a 429's `Retry-After`
+/// header asks for.
+///
+/// Only requests with one of the [RetryPolicy::methods] are retried, since retrying a
+/// request which is not idempotent can repeat its effect, e.g. send a message twice.
+///
+/// See [Instance::set_retry_policy](crate::instance::Instance::set_retry_policy).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// How often a request is sent at most, including the first attempt.
+    ///
+    /// `1` disables retries.
+    pub max_attempts: u32,
+    /// The methods of requests which are safe to retry. Defaults to `GET` and `HEAD`.
+    pub methods: Vec<http::Method>,
+    /// The delay before the first retry, doubled for every further retry
+    pub base_delay: Duration,
+    /// The longest chorus waits between attempts.
+    ///
+    /// If a 429 asks to wait longer than this, the request is not retried.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            methods: vec![http::Method::GET, http::Method::HEAD],
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy which never retries requests.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Returns how often a request with the given method is sent at most.
+    fn max_attempts_for(&self, method: &http::Method) -> u32 {
+        match self.methods.contains(method) {
+            true => self.max_attempts.max(1),
+            false => 1,
+        }
+    }
+
+    /// Returns how long to wait after the given (1-based) failed attempt.
+    ///
+    /// The delay is chosen randomly between half and all of the exponential backoff, so that
+    /// clients which failed at the same time do not all retry at the same time.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exponential = self
+            .base_delay
+            .saturating_mul(2_u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_delay);
+        let half = exponential / 2;
+
+        half + half.mul_f64(rand::thread_rng().gen_range(0.0..=1.0))
+    }
+}
+
+/// Returns whether sending a request failed in a way which might not happen again.
+fn is_transient(error: &reqwest::Error) -> bool {
+    #[cfg(not(target_arch = "wasm32"))]
+    if error.is_connect() {
+        return true;
+    }
+
+    error.is_timeout() || error.is_request()
+}
+
+/// Sent to the streams created by
+/// [Instance::ratelimit_events](crate::instance::Instance::ratelimit_events) whenever a rate
+/// limit bucket runs out of requests.
+///
+/// Useful to tell the user to slow down, or to postpone background work until
+/// [the bucket is replenished](Limit::reset_at).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RatelimitExhausted {
+    /// The id of the user whose request exhausted the bucket.
+    ///
+    /// Note that [instance wide](LimitType::is_instance_wide) buckets are shared by all users.
+    pub user_id: Snowflake,
+    /// The bucket, as of when it was exhausted
+    pub limit: Limit,
+}
+
+/// Sends [RatelimitExhausted] events to every stream created by
+/// [Instance::ratelimit_events](crate::instance::Instance::ratelimit_events).
+#[derive(Debug, Clone)]
+pub(crate) struct RatelimitEventSender {
+    sender: broadcast::Sender<RatelimitExhausted>,
+}
+
+impl Default for RatelimitEventSender {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_STREAM_CAPACITY);
+        RatelimitEventSender { sender }
+    }
+}
+
+impl RatelimitEventSender {
+    fn send(&self, event: RatelimitExhausted) {
+        // Only fails if no stream is open, in which case nobody is interested in the event
+        let _ = self.sender.send(event);
+    }
+
+    /// Creates a new stream, which yields every event sent from now on.
+    pub(crate) fn stream(&self) -> impl futures_util::Stream<Item = RatelimitExhausted> {
+        broadcast_stream(self.sender.subscribe())
+    }
 }
 
 enum LimitOrigin {
     Instance,
     User,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::instance::Instance;
+    use crate::types::{GuildBansQuery, IntoShared, User, UserSettings};
+
+    fn url(request: ChorusRequest) -> String {
+        request.request.build().unwrap().url().to_string()
+    }
+
+    fn request() -> ChorusRequest {
+        ChorusRequest::new(
+            http::Method::GET,
+            "https://example.com/api",
+            None,
+            None,
+            None,
+            LimitType::Global,
+        )
+    }
+
+    #[test]
+    fn test_query_parameters() {
+        assert_eq!(
+            url(request().query(&None::<GuildBansQuery>)),
+            "https://example.com/api"
+        );
+
+        let query = GuildBansQuery {
+            limit: Some(10),
+            ..Default::default()
+        };
+        assert_eq!(
+            url(request().query(&Some(query))),
+            "https://example.com/api?limit=10"
+        );
+
+        let request = request()
+            .query_param("with_counts", true)
+            .optional_query_param("after", None::<u64>)
+            .optional_query_param("name", Some("a b&c"))
+            .repeated_query_param("id", [1, 2]);
+        assert_eq!(
+            url(request),
+            "https://example.com/api?with_counts=true&name=a+b%26c&id=1&id=2"
+        );
+    }
+
+    #[test]
+    fn test_request_metadata() {
+        let metadata = RequestMetadata {
+            audit_log_reason_prefix: Some("[bot] ".to_string()),
+            locale: Some("de".to_string()),
+            timezone: None,
+        };
+
+        let mut request = request().request.build().unwrap();
+        metadata.apply(&mut request).unwrap();
+        let headers = request.headers();
+        assert_eq!(headers["X-Audit-Log-Reason"], "[bot] ");
+        assert_eq!(headers["X-Discord-Locale"], "de");
+        assert!(headers.get("X-Discord-Timezone").is_none());
+
+        let mut request = ChorusRequest::new(
+            http::Method::DELETE,
+            "https://example.com/api",
+            None,
+            Some("spam"),
+            None,
+            LimitType::Global,
+        )
+        .request
+        .build()
+        .unwrap();
+        metadata.apply(&mut request).unwrap();
+        assert_eq!(request.headers()["X-Audit-Log-Reason"], "[bot] spam");
+
+        let invalid = RequestMetadata {
+            locale: Some("de\n".to_string()),
+            ..Default::default()
+        };
+        assert!(invalid.apply(&mut request).is_err());
+    }
+
+    #[test]
+    fn test_audit_log_reason() {
+        let headers = |request: ChorusRequest| request.request.build().unwrap().headers().clone();
+
+        let plain = headers(request().with_audit_log_reason("Spam [3/3]"));
+        assert_eq!(plain["X-Audit-Log-Reason"], "Spam [3/3]");
+
+        let encoded = headers(request().with_audit_log_reason("Beleidigung über 100%\n"));
+        assert_eq!(
+            encoded["X-Audit-Log-Reason"],
+            "Beleidigung %C3%BCber 100%25%0A"
+        );
+
+        let none = headers(request().with_maybe_audit_log_reason(None));
+        assert!(none.get("X-Audit-Log-Reason").is_none());
+    }
+
+    #[test]
+    fn test_exhausted_bucket_is_available_after_reset() {
+        let mut instance = Instance::default();
+        instance.set_limits_configuration(LimitsConfiguration::default());
+        let limits = instance
+            .limits_information
+            .as_ref()
+            .unwrap()
+            .ratelimits
+            .clone();
+        let mut user = ChorusUser::new_rest_only(
+            instance.into_shared(),
+            "token".to_string(),
+            Some(limits),
+            UserSettings::default().into_shared(),
+            User::default().into_shared(),
+        );
+        let limit_type = LimitType::Channel(Snowflake(1));
+        assert!(ChorusRequest::can_send_request(&mut user, &limit_type));
+
+        let now = chrono::Utc::now().timestamp() as u64;
+        let exhaust = |user: &mut ChorusUser, reset: u64| {
+            let limit = user.limits.as_mut().unwrap().get_mut(&limit_type).unwrap();
+            limit.remaining = 0;
+            limit.reset = reset;
+        };
+
+        exhaust(&mut user, now + 60);
+        assert!(!ChorusRequest::can_send_request(&mut user, &limit_type));
+
+        exhaust(&mut user, now - 1);
+        assert!(ChorusRequest::can_send_request(&mut user, &limit_type));
+
+        // The same applies to the instance wide buckets
+        let exhaust_global = |user: &ChorusUser, reset: u64| {
+            let mut instance = user.belongs_to.write().unwrap();
+            let limits = instance.limits_information.as_mut().unwrap();
+            let global = limits.ratelimits.get_mut(&LimitType::Global).unwrap();
+            global.remaining = 0;
+            global.reset = reset;
+        };
+
+        exhaust_global(&user, now + 60);
+        assert!(!ChorusRequest::can_send_request(&mut user, &limit_type));
+
+        exhaust_global(&user, now - 1);
+        assert!(ChorusRequest::can_send_request(&mut user, &limit_type));
+    }
+
+    #[test]
+    fn test_retry_policy() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.max_attempts_for(&http::Method::GET), 3);
+        assert_eq!(policy.max_attempts_for(&http::Method::POST), 1);
+        assert_eq!(RetryPolicy::none().max_attempts_for(&http::Method::GET), 1);
+
+        for attempt in 1..=10 {
+            let exponential = (policy.base_delay * 2_u32.pow(attempt - 1)).min(policy.max_delay);
+            let backoff = policy.backoff(attempt);
+            assert!(backoff >= exponential / 2);
+            assert!(backoff <= exponential);
+        }
+    }
+}
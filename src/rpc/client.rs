@@ -0,0 +1,317 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::ipc::{IpcConnection, IpcOpcode};
+use crate::errors::RpcError;
+use crate::types::{
+    Activity, ActivityAssets, ActivityButton, ActivityParty, ActivitySecrets, ActivityTimestamps,
+    PublicUser, Snowflake,
+};
+
+/// The version of the RPC protocol chorus implements
+pub const RPC_VERSION: u8 = 1;
+
+#[derive(Debug)]
+/// A connection to a locally running client over its RPC socket.
+///
+/// Can be used to set the rich presence of the user logged into that client, on behalf of an
+/// application.
+///
+/// # Example
+/// ```no_run
+/// use chorus::rpc::RpcClient;
+/// use chorus::types::{ActivityBuilder, ActivityType, Snowflake};
+///
+/// # async fn example() -> Result<(), chorus::errors::RpcError> {
+/// let mut client = RpcClient::connect(Snowflake(1234567890)).await?;
+///
+/// let activity = ActivityBuilder::new("Chorus", ActivityType::Playing)
+///     .details("Writing a client")
+///     .start(chrono::Utc::now())
+///     .build();
+/// client.set_activity(Some(activity)).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct RpcClient {
+    connection: IpcConnection,
+    client_id: Snowflake,
+    ready: RpcReady,
+    /// The nonce of the last command we sent
+    last_nonce: u64,
+}
+
+impl RpcClient {
+    /// Connects to the first locally running client we can find, and performs the handshake
+    /// for the application with the given id.
+    ///
+    /// # Errors
+    /// Returns [RpcError::NoClient] if no client is running, and [RpcError::Closed] if the
+    /// client rejected the handshake, e.g. because of an unknown application id.
+    pub async fn connect(client_id: Snowflake) -> Result<RpcClient, RpcError> {
+        let connection = IpcConnection::connect().await?;
+        RpcClient::handshake(connection, client_id).await
+    }
+
+    /// Connects to the client listening on the unix domain socket / named pipe at `path`, and
+    /// performs the handshake for the application with the given id.
+    ///
+    /// See [RpcClient::connect] for connecting to the first client we can find.
+    pub async fn connect_to(
+        path: impl AsRef<Path>,
+        client_id: Snowflake,
+    ) -> Result<RpcClient, RpcError> {
+        let connection = IpcConnection::connect_to(path.as_ref()).await?;
+        RpcClient::handshake(connection, client_id).await
+    }
+
+    async fn handshake(
+        mut connection: IpcConnection,
+        client_id: Snowflake,
+    ) -> Result<RpcClient, RpcError> {
+        let handshake = RpcHandshake {
+            v: RPC_VERSION,
+            client_id,
+        };
+        connection.send(IpcOpcode::Handshake, &handshake).await?;
+
+        let mut client = RpcClient {
+            connection,
+            client_id,
+            ready: RpcReady::default(),
+            last_nonce: 0,
+        };
+
+        let ready = client
+            .receive_payload(|payload| payload.evt.as_deref() == Some("READY"))
+            .await?;
+
+        client.ready = serde_json::from_value(ready.data.unwrap_or_default()).map_err(|e| {
+            RpcError::InvalidPayload {
+                error: e.to_string(),
+            }
+        })?;
+
+        log::info!("RPC: Connected to local client (v{})", client.ready.v);
+
+        Ok(client)
+    }
+
+    /// Returns the id of the application we connected as
+    pub fn client_id(&self) -> Snowflake {
+        self.client_id
+    }
+
+    /// Returns the data the client sent after the handshake, such as the user logged into it
+    pub fn ready(&self) -> &RpcReady {
+        &self.ready
+    }
+
+    /// Sets (or, if `None`, clears) the rich presence of the user logged into the client.
+    ///
+    /// Of the [Activity], only the fields rich presence supports are sent; the name is always
+    /// the name of the application.
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/topics/rpc#set-activity>
+    pub async fn set_activity(&mut self, activity: Option<Activity>) -> Result<(), RpcError> {
+        let args = SetActivityArgs {
+            pid: std::process::id(),
+            activity: activity.map(RpcActivity::from),
+        };
+
+        self.send_command("SET_ACTIVITY", args).await.map(|_| ())
+    }
+
+    /// Clears the rich presence of the user logged into the client.
+    ///
+    /// Shorthand for `set_activity(None)`.
+    pub async fn clear_activity(&mut self) -> Result<(), RpcError> {
+        self.set_activity(None).await
+    }
+
+    /// Sends a command, and waits for the client's response to it.
+    ///
+    /// Returns the `data` of the response.
+    ///
+    /// # Errors
+    /// Returns [RpcError::Command] if the client responds with an error.
+    pub async fn send_command(
+        &mut self,
+        command: &str,
+        args: impl Serialize,
+    ) -> Result<Value, RpcError> {
+        self.last_nonce += 1;
+        let nonce = self.last_nonce.to_string();
+
+        let payload = RpcCommand {
+            cmd: command,
+            args,
+            nonce: &nonce,
+        };
+        self.connection.send(IpcOpcode::Frame, &payload).await?;
+
+        let response = self
+            .receive_payload(|payload| payload.nonce.as_deref() == Some(nonce.as_str()))
+            .await?;
+
+        let data = response.data.unwrap_or_default();
+
+        if response.evt.as_deref() == Some("ERROR") {
+            let error: RpcErrorData = serde_json::from_value(data).unwrap_or_default();
+            return Err(RpcError::Command {
+                code: error.code,
+                message: error.message,
+            });
+        }
+
+        Ok(data)
+    }
+
+    /// Closes the connection, which also clears the rich presence set by it.
+    pub async fn close(mut self) -> Result<(), RpcError> {
+        let close = RpcErrorData {
+            code: 1000,
+            message: String::from("Normal closure"),
+        };
+        self.connection.send(IpcOpcode::Close, &close).await?;
+        self.connection.shutdown().await
+    }
+
+    /// Receives frames until one matches `wanted`, answering pings along the way.
+    async fn receive_payload(
+        &mut self,
+        wanted: impl Fn(&RpcPayload) -> bool,
+    ) -> Result<RpcPayload, RpcError> {
+        loop {
+            let (opcode, payload): (IpcOpcode, Value) = self.connection.receive().await?;
+
+            match opcode {
+                IpcOpcode::Frame => {
+                    let payload: RpcPayload =
+                        serde_json::from_value(payload).map_err(|e| RpcError::InvalidPayload {
+                            error: e.to_string(),
+                        })?;
+
+                    if wanted(&payload) {
+                        return Ok(payload);
+                    }
+
+                    log::trace!("RPC: Skipping {:?} {:?}", payload.cmd, payload.evt);
+                }
+                IpcOpcode::Ping => self.connection.send(IpcOpcode::Pong, &payload).await?,
+                IpcOpcode::Close => {
+                    let close: RpcErrorData = serde_json::from_value(payload).unwrap_or_default();
+                    return Err(RpcError::Closed {
+                        code: close.code,
+                        message: close.message,
+                    });
+                }
+                IpcOpcode::Handshake | IpcOpcode::Pong => {}
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+/// Sent by the client once the handshake succeeded.
+///
+/// # Reference
+/// See <https://docs.discord.sex/topics/rpc#ready>
+pub struct RpcReady {
+    /// The RPC version the client speaks
+    pub v: u8,
+    pub config: RpcServerConfiguration,
+    /// The user logged into the client
+    pub user: Option<PublicUser>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// Describes the instance the local client is connected to
+pub struct RpcServerConfiguration {
+    pub cdn_host: String,
+    pub api_endpoint: String,
+    pub environment: String,
+}
+
+#[derive(Debug, Serialize)]
+struct RpcHandshake {
+    v: u8,
+    client_id: Snowflake,
+}
+
+#[derive(Debug, Serialize)]
+struct RpcCommand<'a, T: Serialize> {
+    cmd: &'a str,
+    args: T,
+    nonce: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+/// A received [IpcOpcode::Frame]; either a response to a command or an event
+struct RpcPayload {
+    cmd: String,
+    evt: Option<String>,
+    nonce: Option<String>,
+    data: Option<Value>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+/// The data of an error response, or the payload of an [IpcOpcode::Close] frame
+struct RpcErrorData {
+    #[serde(default)]
+    code: i64,
+    #[serde(default)]
+    message: String,
+}
+
+#[derive(Debug, Serialize)]
+struct SetActivityArgs {
+    pid: u32,
+    activity: Option<RpcActivity>,
+}
+
+#[derive(Debug, Serialize)]
+/// The subset of an [Activity] which can be set over RPC
+struct RpcActivity {
+    #[serde(rename = "type")]
+    activity_type: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timestamps: Option<ActivityTimestamps>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    assets: Option<ActivityAssets>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    party: Option<ActivityParty>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    secrets: Option<ActivitySecrets>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    instance: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    buttons: Option<Vec<ActivityButton>>,
+}
+
+impl From<Activity> for RpcActivity {
+    fn from(activity: Activity) -> Self {
+        Self {
+            activity_type: activity.activity_type,
+            state: activity.state,
+            details: activity.details,
+            timestamps: activity.timestamps,
+            assets: activity.assets,
+            party: activity.party,
+            secrets: activity.secrets,
+            instance: activity.instance,
+            buttons: activity.buttons,
+        }
+    }
+}
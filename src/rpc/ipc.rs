@@ -0,0 +1,195 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The framing of the local IPC socket (unix domain socket / named pipe) the RPC protocol is
+//! spoken over.
+
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::errors::RpcError;
+
+/// How many sockets clients may listen on; the first free one of `discord-ipc-0` to
+/// `discord-ipc-9` is used
+const MAX_IPC_SOCKETS: u8 = 10;
+
+/// The largest payload we are willing to read, so a misbehaving client can't make us allocate
+/// arbitrary amounts of memory
+const MAX_PAYLOAD_SIZE: u32 = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u32)]
+/// The opcode of an IPC frame
+pub(crate) enum IpcOpcode {
+    Handshake = 0,
+    Frame = 1,
+    Close = 2,
+    Ping = 3,
+    Pong = 4,
+}
+
+impl TryFrom<u32> for IpcOpcode {
+    type Error = RpcError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(IpcOpcode::Handshake),
+            1 => Ok(IpcOpcode::Frame),
+            2 => Ok(IpcOpcode::Close),
+            3 => Ok(IpcOpcode::Ping),
+            4 => Ok(IpcOpcode::Pong),
+            _ => Err(RpcError::InvalidPayload {
+                error: format!("unknown opcode {}", value),
+            }),
+        }
+    }
+}
+
+/// A stream to a local client
+pub(crate) trait IpcStream: AsyncRead + AsyncWrite + Unpin + Send + Sync + Debug {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send + Sync + Debug> IpcStream for T {}
+
+#[derive(Debug)]
+/// A connection to a local client, which sends and receives IPC frames
+pub(crate) struct IpcConnection {
+    stream: Box<dyn IpcStream>,
+}
+
+impl IpcConnection {
+    /// Connects to the first local client we can find.
+    pub(crate) async fn connect() -> Result<IpcConnection, RpcError> {
+        for path in ipc_paths() {
+            match IpcConnection::connect_to(&path).await {
+                Ok(connection) => return Ok(connection),
+                Err(e) => log::trace!("RPC: Could not connect to {:?}: {}", path, e),
+            }
+        }
+
+        Err(RpcError::NoClient)
+    }
+
+    /// Connects to the socket / pipe at the given path.
+    pub(crate) async fn connect_to(path: &Path) -> Result<IpcConnection, RpcError> {
+        #[cfg(unix)]
+        let stream = tokio::net::UnixStream::connect(path).await?;
+        #[cfg(windows)]
+        let stream = tokio::net::windows::named_pipe::ClientOptions::new().open(path)?;
+
+        log::debug!("RPC: Connected to {:?}", path);
+
+        Ok(IpcConnection {
+            stream: Box::new(stream),
+        })
+    }
+
+    /// Sends a frame with a json payload
+    pub(crate) async fn send<T: Serialize>(
+        &mut self,
+        opcode: IpcOpcode,
+        payload: &T,
+    ) -> Result<(), RpcError> {
+        let payload = serde_json::to_vec(payload).map_err(|e| RpcError::InvalidPayload {
+            error: e.to_string(),
+        })?;
+
+        log::trace!(
+            "RPC: Sending {:?} {}",
+            opcode,
+            String::from_utf8_lossy(&payload)
+        );
+
+        // The header is the opcode and the length of the payload, both little endian
+        let mut frame = Vec::with_capacity(8 + payload.len());
+        frame.extend_from_slice(&(opcode as u32).to_le_bytes());
+        frame.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        frame.extend_from_slice(&payload);
+
+        self.stream.write_all(&frame).await?;
+        self.stream.flush().await?;
+        Ok(())
+    }
+
+    /// Receives the next frame, and deserializes its payload
+    pub(crate) async fn receive<T: DeserializeOwned>(
+        &mut self,
+    ) -> Result<(IpcOpcode, T), RpcError> {
+        let mut header = [0_u8; 8];
+        self.stream.read_exact(&mut header).await?;
+
+        let opcode = u32::from_le_bytes(header[0..4].try_into().unwrap());
+        let length = u32::from_le_bytes(header[4..8].try_into().unwrap());
+
+        if length > MAX_PAYLOAD_SIZE {
+            return Err(RpcError::InvalidPayload {
+                error: format!(
+                    "payload of {} bytes is larger than the limit of {} bytes",
+                    length, MAX_PAYLOAD_SIZE
+                ),
+            });
+        }
+
+        let mut payload = vec![0_u8; length as usize];
+        self.stream.read_exact(&mut payload).await?;
+
+        let opcode = IpcOpcode::try_from(opcode)?;
+
+        log::trace!(
+            "RPC: Received {:?} {}",
+            opcode,
+            String::from_utf8_lossy(&payload)
+        );
+
+        let payload = serde_json::from_slice(&payload).map_err(|e| RpcError::InvalidPayload {
+            error: e.to_string(),
+        })?;
+
+        Ok((opcode, payload))
+    }
+
+    /// Shuts down the connection
+    pub(crate) async fn shutdown(&mut self) -> Result<(), RpcError> {
+        self.stream.shutdown().await?;
+        Ok(())
+    }
+}
+
+/// Returns the paths local clients may listen on, in the order they should be tried
+#[cfg(unix)]
+fn ipc_paths() -> Vec<PathBuf> {
+    let mut directories: Vec<PathBuf> = ["XDG_RUNTIME_DIR", "TMPDIR", "TMP", "TEMP"]
+        .iter()
+        .filter_map(std::env::var_os)
+        .map(PathBuf::from)
+        .collect();
+    directories.push(PathBuf::from("/tmp"));
+
+    // Flatpak and snap installs create their sockets in a subdirectory
+    let subdirectories = ["", "app/com.discordapp.Discord", "snap.discord"];
+
+    let mut paths = Vec::new();
+    for directory in directories {
+        for subdirectory in subdirectories {
+            for i in 0..MAX_IPC_SOCKETS {
+                paths.push(
+                    directory
+                        .join(subdirectory)
+                        .join(format!("discord-ipc-{}", i)),
+                );
+            }
+        }
+    }
+    paths
+}
+
+/// Returns the paths local clients may listen on, in the order they should be tried
+#[cfg(windows)]
+fn ipc_paths() -> Vec<PathBuf> {
+    (0..MAX_IPC_SOCKETS)
+        .map(|i| PathBuf::from(format!(r"\\.\pipe\discord-ipc-{}", i)))
+        .collect()
+}
@@ -0,0 +1,16 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A client for the local RPC protocol, spoken over the IPC socket (a unix domain socket, or a
+//! named pipe on windows) of a client running on the same machine.
+//!
+//! This lets applications set the rich presence of the user logged into that client, see
+//! [RpcClient].
+//!
+//! Requires the `rpc` feature, and is not available on `wasm32`.
+
+pub mod client;
+mod ipc;
+
+pub use client::*;
@@ -0,0 +1,236 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Persisting a [ChorusUser]'s tokens, and obtaining new ones once they are rejected.
+
+use std::fmt::Debug;
+use std::sync::{Arc, RwLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{ChorusError, ChorusResult};
+use crate::instance::{ChorusUser, Instance};
+use crate::ratelimiter::ChorusRequest;
+use crate::types::{LimitType, LoginResult, LoginSchema, MfaToken, Shared};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// The tokens of a [ChorusUser], as persisted by a [TokenStore].
+pub struct StoredTokens {
+    pub token: String,
+    /// The user's MFA bypass token, if they recently completed an MFA challenge
+    #[serde(default)]
+    pub mfa_token: Option<MfaToken>,
+}
+
+/// Persists the tokens of a [ChorusUser], so that its session can be restored after a restart
+/// with [Instance::login_with_token_store].
+///
+/// Set one with [ChorusUser::set_token_store]; it is then updated whenever the user's tokens
+/// change.
+///
+/// See [MemoryTokenStore] and [FileTokenStore] for the provided implementations.
+pub trait TokenStore: Send + Sync + Debug {
+    /// Loads the stored tokens, if any were stored
+    fn load(&self) -> ChorusResult<Option<StoredTokens>>;
+
+    /// Stores the tokens, replacing the previously stored ones
+    fn store(&self, tokens: &StoredTokens) -> ChorusResult<()>;
+
+    /// Removes the stored tokens, e.g. after logging out
+    fn clear(&self) -> ChorusResult<()>;
+}
+
+#[derive(Debug, Default)]
+/// A [TokenStore] which keeps the tokens in memory.
+///
+/// Useful for sharing tokens between [ChorusUser]s, and in tests; the tokens are lost when the
+/// process exits.
+pub struct MemoryTokenStore {
+    tokens: RwLock<Option<StoredTokens>>,
+}
+
+impl MemoryTokenStore {
+    pub fn new() -> MemoryTokenStore {
+        MemoryTokenStore::default()
+    }
+}
+
+impl TokenStore for MemoryTokenStore {
+    fn load(&self) -> ChorusResult<Option<StoredTokens>> {
+        Ok(self.tokens.read().unwrap().clone())
+    }
+
+    fn store(&self, tokens: &StoredTokens) -> ChorusResult<()> {
+        *self.tokens.write().unwrap() = Some(tokens.clone());
+        Ok(())
+    }
+
+    fn clear(&self) -> ChorusResult<()> {
+        *self.tokens.write().unwrap() = None;
+        Ok(())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+/// A [TokenStore] which keeps the tokens in a json file.
+///
+/// Note that the tokens are stored unencrypted; anybody who can read the file can use the
+/// session.
+pub struct FileTokenStore {
+    path: std::path::PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileTokenStore {
+    /// Creates a store which keeps the tokens in the file at `path`.
+    ///
+    /// The file is created once tokens are stored.
+    pub fn new(path: impl Into<std::path::PathBuf>) -> FileTokenStore {
+        FileTokenStore { path: path.into() }
+    }
+
+    fn io_error(&self, error: impl std::fmt::Display) -> ChorusError {
+        ChorusError::TokenStore {
+            error: format!("{}: {}", self.path.display(), error),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl TokenStore for FileTokenStore {
+    fn load(&self) -> ChorusResult<Option<StoredTokens>> {
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(self.io_error(e)),
+        };
+
+        serde_json::from_str(&contents)
+            .map(Some)
+            .map_err(|e| self.io_error(e))
+    }
+
+    fn store(&self, tokens: &StoredTokens) -> ChorusResult<()> {
+        let contents = serde_json::to_string(tokens).unwrap();
+        std::fs::write(&self.path, contents).map_err(|e| self.io_error(e))
+    }
+
+    fn clear(&self) -> ChorusResult<()> {
+        match std::fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(self.io_error(e)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Obtains a new token for a [ChorusUser] once the instance rejects its current one with a
+/// `401 Unauthorized`, e.g. because the session was invalidated.
+///
+/// Set one with [ChorusUser::set_token_refresher]; the rejected request is then retried once
+/// with the new token, instead of failing with a [ChorusError::Api] (or
+/// [ChorusError::NoPermission], if the instance did not send a json error).
+///
+/// See [LoginTokenRefresher] for logging in again with a password.
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+pub trait TokenRefresher: Send + Sync + Debug {
+    /// Obtains a new token for a user of `instance`.
+    ///
+    /// Returns [None] if no new token can be obtained, in which case the request fails as
+    /// usual.
+    async fn refresh_token(&self, instance: Shared<Instance>) -> ChorusResult<Option<String>>;
+}
+
+#[derive(Debug, Clone)]
+/// A [TokenRefresher] which logs in again with the user's credentials.
+///
+/// Logins which require MFA can not be refreshed this way.
+pub struct LoginTokenRefresher {
+    login: LoginSchema,
+}
+
+impl LoginTokenRefresher {
+    pub fn new(login: LoginSchema) -> LoginTokenRefresher {
+        LoginTokenRefresher { login }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl TokenRefresher for LoginTokenRefresher {
+    async fn refresh_token(&self, instance: Shared<Instance>) -> ChorusResult<Option<String>> {
+        let endpoint_url = instance.read().unwrap().urls.api.clone() + "/auth/login";
+        let chorus_request = ChorusRequest {
+            request: reqwest::Client::new().post(endpoint_url).json(&self.login),
+            limit_type: LimitType::AuthLogin,
+        };
+
+        let mut shell = ChorusUser::shell(instance, "None").await;
+
+        // Logins which require MFA don't return a token
+        match chorus_request
+            .deserialize_response::<LoginResult>(&mut shell)
+            .await
+        {
+            Ok(login_result) => Ok(Some(login_result.token)),
+            Err(ChorusError::InvalidResponse { .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl Instance {
+    /// Restores a session from the tokens in a [TokenStore], see [Instance::login_with_token].
+    ///
+    /// The store is then kept up to date with the user's tokens.
+    ///
+    /// Returns [None] if the store contains no tokens.
+    pub async fn login_with_token_store(
+        &mut self,
+        store: Arc<dyn TokenStore>,
+    ) -> ChorusResult<Option<ChorusUser>> {
+        let Some(tokens) = store.load()? else {
+            return Ok(None);
+        };
+
+        let mut user = self.login_with_token(&tokens.token).await?;
+        user.mfa_token = tokens
+            .mfa_token
+            .filter(|mfa_token| mfa_token.expires_at > chrono::Utc::now());
+        user.set_token_store(Some(store))?;
+
+        Ok(Some(user))
+    }
+}
+
+impl ChorusUser {
+    /// Sets the [TokenStore] the user's tokens are persisted in, and stores the current
+    /// tokens in it.
+    ///
+    /// Passing `None` stops persisting the tokens; the previous store is left as is.
+    pub fn set_token_store(&mut self, store: Option<Arc<dyn TokenStore>>) -> ChorusResult<()> {
+        self.token_store = store;
+        self.persist_tokens()
+    }
+
+    /// Sets the [TokenRefresher] used to obtain a new token once the current one is rejected.
+    ///
+    /// Passing `None` disables refreshing.
+    pub fn set_token_refresher(&mut self, refresher: Option<Arc<dyn TokenRefresher>>) {
+        self.token_refresher = refresher;
+    }
+
+    /// Stores the user's current tokens in its [TokenStore], if it has one
+    pub(crate) fn persist_tokens(&self) -> ChorusResult<()> {
+        let Some(store) = &self.token_store else {
+            return Ok(());
+        };
+
+        store.store(&StoredTokens {
+            token: self.token.clone(),
+            mfa_token: self.mfa_token.clone(),
+        })
+    }
+}
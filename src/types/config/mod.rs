@@ -27,7 +27,7 @@ pub use crate::{
 pub mod types;
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct ConfigValue {
     pub gateway: EndpointConfiguration,
     pub cdn: CdnConfiguration,
@@ -189,4 +189,15 @@ mod test {
 
         assert_eq!(cfg, c)
     }
+
+    #[test]
+    fn missing_sections_are_defaulted() {
+        let default = ConfigValue::default();
+        let limits = serde_json::json!(default.limits);
+        let config: ConfigValue =
+            serde_json::from_value(serde_json::json!({ "limits": limits })).unwrap();
+
+        assert_eq!(config.limits, default.limits);
+        assert_eq!(config.api, default.api);
+    }
 }
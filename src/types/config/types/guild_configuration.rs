@@ -57,6 +57,7 @@ pub enum GuildFeatures {
     DiscoverableDisabled,
     Discoverable,
     EnabledDiscoverableBefore,
+    EnhancedRoleColors,
     ExposedToActivitiesWTPExperiment,
     GuestsEnabled,
     GuildAutomodDefaultList,
@@ -263,6 +264,7 @@ impl FromStr for GuildFeatures {
             "DISCOVERABLE_DISABLED" => Ok(GuildFeatures::DiscoverableDisabled),
             "DISCOVERABLE" => Ok(GuildFeatures::Discoverable),
             "ENABLED_DISCOVERABLE_BEFORE" => Ok(GuildFeatures::EnabledDiscoverableBefore),
+            "ENHANCED_ROLE_COLORS" => Ok(GuildFeatures::EnhancedRoleColors),
             "EXPOSED_TO_ACTIVITIES_WTP_EXPERIMENT" => {
                 Ok(GuildFeatures::ExposedToActivitiesWTPExperiment)
             }
@@ -418,6 +420,7 @@ impl GuildFeatures {
             GuildFeatures::DiscoverableDisabled => "DISCOVERABLE_DISABLED",
             GuildFeatures::Discoverable => "DISCOVERABLE",
             GuildFeatures::EnabledDiscoverableBefore => "ENABLED_DISCOVERABLE_BEFORE",
+            GuildFeatures::EnhancedRoleColors => "ENHANCED_ROLE_COLORS",
             GuildFeatures::ExposedToActivitiesWTPExperiment => {
                 "EXPOSED_TO_ACTIVITIES_WTP_EXPERIMENT"
             }
@@ -2,20 +2,20 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-#[cfg(feature = "client")]
-use crate::gateway::Updateable;
+#[cfg(feature = "client-core")]
+use crate::gateway::{PartialUpdate, Updateable};
 use crate::types::Shared;
 use crate::UInt8;
 
-#[cfg(feature = "client")]
-use chorus_macros::Updateable;
+#[cfg(feature = "client-core")]
+use chorus_macros::{PartialUpdate, Updateable};
 
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
 use crate::types::utils::Snowflake;
 
-#[cfg_attr(feature = "client", derive(Updateable))]
+#[cfg_attr(feature = "client-core", derive(Updateable, PartialUpdate))]
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 /// See <https://discord.com/developers/docs/resources/auto-moderation#auto-moderation-rule-object>
 pub struct AutoModerationRule {
@@ -15,18 +15,18 @@ use crate::types::{
     PermissionFlags, Shared,
 };
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::types::Composite;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::gateway::GatewayHandle;
 
-#[cfg(feature = "client")]
-use crate::gateway::Updateable;
+#[cfg(feature = "client-core")]
+use crate::gateway::{PartialUpdate, Updateable};
 use crate::UInt64;
 
-#[cfg(feature = "client")]
-use chorus_macros::{observe_option_vec, Composite, Updateable};
+#[cfg(feature = "client-core")]
+use chorus_macros::{observe_option_vec, Composite, PartialUpdate, Updateable};
 use serde::de::{Error, Visitor};
 
 #[cfg(feature = "sqlx")]
@@ -36,7 +36,7 @@ use super::{option_arc_rwlock_ptr_eq, option_vec_arc_rwlock_ptr_eq, Emoji};
 
 #[derive(Default, Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
-#[cfg_attr(feature = "client", derive(Updateable, Composite))]
+#[cfg_attr(feature = "client-core", derive(Updateable, Composite, PartialUpdate))]
 /// Represents a guild or private channel
 ///
 /// # Reference
@@ -81,14 +81,14 @@ pub struct Channel {
     #[cfg(feature = "sqlx")]
     pub permission_overwrites: Option<sqlx::types::Json<Vec<PermissionOverwrite>>>,
     #[cfg(not(feature = "sqlx"))]
-    #[cfg_attr(feature = "client", observe_option_vec)]
+    #[cfg_attr(feature = "client-core", observe_option_vec)]
     pub permission_overwrites: Option<Vec<Shared<PermissionOverwrite>>>,
     #[cfg_attr(feature = "sqlx", sqlx(skip))]
     pub permissions: Option<String>,
     pub position: Option<i32>,
     pub rate_limit_per_user: Option<i32>,
     #[cfg_attr(feature = "sqlx", sqlx(skip))]
-    #[cfg_attr(feature = "client", observe_option_vec)]
+    #[cfg_attr(feature = "client-core", observe_option_vec)]
     pub recipients: Option<Vec<Shared<User>>>,
     pub rtc_region: Option<String>,
     #[cfg_attr(feature = "sqlx", sqlx(skip))]
@@ -179,8 +179,9 @@ fn compare_permission_overwrites(
 ///
 /// # Reference
 /// See <https://discord-userdoccers.vercel.app/resources/channel#forum-tag-object>
-#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow, sqlx::Type))]
-#[cfg_attr(feature = "sqlx", sqlx(type_name = "interface_type"))]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+#[cfg_attr(feature = "sqlx-pg-uint", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx-pg-uint", sqlx(type_name = "interface_type"))]
 pub struct Tag {
     pub id: Snowflake,
     /// The name of the tag (max 20 characters)
@@ -192,7 +193,7 @@ pub struct Tag {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd)]
-#[cfg_attr(feature = "client", derive(Updateable, Composite))]
+#[cfg_attr(feature = "client-core", derive(Updateable, Composite))]
 pub struct PermissionOverwrite {
     pub id: Snowflake,
     #[serde(rename = "type")]
@@ -203,6 +204,84 @@ pub struct PermissionOverwrite {
     pub deny: PermissionFlags,
 }
 
+impl PermissionOverwrite {
+    /// Starts building an overwrite for the role with the given id.
+    ///
+    /// Note that the id of the `@everyone` role is the id of the guild.
+    pub fn role(role_id: Snowflake) -> PermissionOverwriteBuilder {
+        PermissionOverwriteBuilder::new(role_id, PermissionOverwriteType::Role)
+    }
+
+    /// Starts building an overwrite for the guild member with the given id.
+    pub fn member(user_id: Snowflake) -> PermissionOverwriteBuilder {
+        PermissionOverwriteBuilder::new(user_id, PermissionOverwriteType::Member)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Builds a [PermissionOverwrite] from sets of [PermissionFlags].
+///
+/// A permission is never both allowed and denied; allowing a permission removes it from the
+/// denied permissions and vice versa.
+///
+/// # Example
+/// ```rs
+/// let overwrite = PermissionOverwrite::role(guild_id)
+///     .deny(PermissionFlags::SEND_MESSAGES)
+///     .allow(PermissionFlags::VIEW_CHANNEL | PermissionFlags::READ_MESSAGE_HISTORY)
+///     .build();
+/// ```
+pub struct PermissionOverwriteBuilder {
+    overwrite: PermissionOverwrite,
+}
+
+impl PermissionOverwriteBuilder {
+    /// Starts building an overwrite which neither allows nor denies any permissions.
+    pub fn new(id: Snowflake, overwrite_type: PermissionOverwriteType) -> Self {
+        Self {
+            overwrite: PermissionOverwrite {
+                id,
+                overwrite_type,
+                allow: PermissionFlags::empty(),
+                deny: PermissionFlags::empty(),
+            },
+        }
+    }
+
+    /// Explicitly allows the given permissions.
+    pub fn allow(mut self, permissions: PermissionFlags) -> Self {
+        self.overwrite.deny.remove(permissions.clone());
+        self.overwrite.allow.insert(permissions);
+        self
+    }
+
+    /// Explicitly denies the given permissions.
+    pub fn deny(mut self, permissions: PermissionFlags) -> Self {
+        self.overwrite.allow.remove(permissions.clone());
+        self.overwrite.deny.insert(permissions);
+        self
+    }
+
+    /// Neither allows nor denies the given permissions, so that they are inherited from the
+    /// member's roles or the parent category.
+    pub fn inherit(mut self, permissions: PermissionFlags) -> Self {
+        self.overwrite.allow.remove(permissions.clone());
+        self.overwrite.deny.remove(permissions);
+        self
+    }
+
+    pub fn build(self) -> PermissionOverwrite {
+        self.overwrite
+    }
+}
+
+impl From<PermissionOverwrite> for PermissionOverwriteBuilder {
+    /// Continues building an existing overwrite, e.g. to change a channel's current overwrite.
+    fn from(overwrite: PermissionOverwrite) -> Self {
+        Self { overwrite }
+    }
+}
+
 #[derive(Debug, Serialize_repr, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Copy)]
 #[cfg_attr(not(feature = "sqlx"), repr(u8))]
 #[cfg_attr(feature = "sqlx", repr(i16))]
@@ -324,8 +403,9 @@ impl PartialEq for ThreadMember {
 ///
 /// # Reference
 /// See <https://discord-userdoccers.vercel.app/resources/channel#default-reaction-object>
-#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow, sqlx::Type))]
-#[cfg_attr(feature = "sqlx", sqlx(type_name = "interface_type"))]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+#[cfg_attr(feature = "sqlx-pg-uint", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx-pg-uint", sqlx(type_name = "interface_type"))]
 pub struct DefaultReaction {
     #[serde(default)]
     pub emoji_id: Option<Snowflake>,
@@ -410,11 +490,17 @@ pub enum ChannelType {
     Unhandled = 255,
 }
 
+/// A news channel followed by another channel, see
+/// [Channel::follow](crate::types::Channel::follow).
+///
 /// # Reference
 /// See <https://docs.discord.sex/resources/message#followed-channel-object>
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Copy, Hash, PartialOrd, Ord)]
 pub struct FollowedChannel {
+    /// The id of the followed news channel
     pub channel_id: Snowflake,
+    /// The id of the channel follower webhook which crossposts messages into the following
+    /// channel
     pub webhook_id: Snowflake,
 }
 
@@ -445,14 +531,14 @@ impl TryFrom<u8> for DefaultForumLayout {
     }
 }
 
-#[cfg(feature = "sqlx")]
+#[cfg(feature = "sqlx-pg-uint")]
 impl sqlx::Type<sqlx::Postgres> for DefaultForumLayout {
     fn type_info() -> <sqlx::Postgres as sqlx::Database>::TypeInfo {
         <sqlx_pg_uint::PgU8 as sqlx::Type<sqlx::Postgres>>::type_info()
     }
 }
 
-#[cfg(feature = "sqlx")]
+#[cfg(feature = "sqlx-pg-uint")]
 impl<'q> sqlx::Encode<'q, sqlx::Postgres> for DefaultForumLayout {
     fn encode_by_ref(
         &self,
@@ -463,7 +549,7 @@ impl<'q> sqlx::Encode<'q, sqlx::Postgres> for DefaultForumLayout {
     }
 }
 
-#[cfg(feature = "sqlx")]
+#[cfg(feature = "sqlx-pg-uint")]
 impl<'r> sqlx::Decode<'r, sqlx::Postgres> for DefaultForumLayout {
     fn decode(
         value: <sqlx::Postgres as sqlx::Database>::ValueRef<'r>,
@@ -473,6 +559,9 @@ impl<'r> sqlx::Decode<'r, sqlx::Postgres> for DefaultForumLayout {
     }
 }
 
+#[cfg(feature = "sqlx")]
+crate::types::utils::sqlx_u8_enum!(DefaultForumLayout);
+
 #[derive(
     Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Copy, Hash, PartialOrd, Ord, Default,
 )]
@@ -498,14 +587,14 @@ impl TryFrom<u8> for DefaultSortOrder {
     }
 }
 
-#[cfg(feature = "sqlx")]
+#[cfg(feature = "sqlx-pg-uint")]
 impl sqlx::Type<sqlx::Postgres> for DefaultSortOrder {
     fn type_info() -> <sqlx::Postgres as sqlx::Database>::TypeInfo {
         <sqlx_pg_uint::PgU8 as sqlx::Type<sqlx::Postgres>>::type_info()
     }
 }
 
-#[cfg(feature = "sqlx")]
+#[cfg(feature = "sqlx-pg-uint")]
 impl<'q> sqlx::Encode<'q, sqlx::Postgres> for DefaultSortOrder {
     fn encode_by_ref(
         &self,
@@ -516,7 +605,7 @@ impl<'q> sqlx::Encode<'q, sqlx::Postgres> for DefaultSortOrder {
     }
 }
 
-#[cfg(feature = "sqlx")]
+#[cfg(feature = "sqlx-pg-uint")]
 impl<'r> sqlx::Decode<'r, sqlx::Postgres> for DefaultSortOrder {
     fn decode(
         value: <sqlx::Postgres as sqlx::Database>::ValueRef<'r>,
@@ -525,3 +614,6 @@ impl<'r> sqlx::Decode<'r, sqlx::Postgres> for DefaultSortOrder {
         DefaultSortOrder::try_from(sqlx_pg_uint.to_uint()).map_err(|e| e.into())
     }
 }
+
+#[cfg(feature = "sqlx")]
+crate::types::utils::sqlx_u8_enum!(DefaultSortOrder);
@@ -10,22 +10,22 @@ use crate::types::entities::User;
 use crate::types::Snowflake;
 use crate::types::{PartialEmoji, Shared};
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::gateway::GatewayHandle;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::types::Composite;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::gateway::Updateable;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use chorus_macros::{Composite, Updateable};
 
 use super::option_arc_rwlock_ptr_eq;
 
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
-#[cfg_attr(feature = "client", derive(Updateable, Composite))]
+#[cfg_attr(feature = "client-core", derive(Updateable, Composite))]
 #[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
 /// # Reference
 /// See <https://discord-userdoccers.vercel.app/resources/emoji#emoji-object>
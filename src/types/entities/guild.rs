@@ -13,7 +13,7 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 use crate::types::types::guild_configuration::GuildFeaturesList;
 use crate::types::Shared;
 use crate::types::{
-    entities::{Channel, Emoji, RoleObject, Sticker, User, VoiceState, Webhook},
+    entities::{Channel, Emoji, GuildMember, RoleObject, Sticker, User, VoiceState, Webhook},
     interfaces::WelcomeScreenObject,
     utils::Snowflake,
 };
@@ -21,21 +21,21 @@ use crate::UInt64;
 
 use super::{option_arc_rwlock_ptr_eq, vec_arc_rwlock_ptr_eq, PublicUser};
 
-#[cfg(feature = "client")]
-use crate::gateway::Updateable;
+#[cfg(feature = "client-core")]
+use crate::gateway::{PartialUpdate, Updateable};
 
-#[cfg(feature = "client")]
-use chorus_macros::{observe_vec, Composite, Updateable};
+#[cfg(feature = "client-core")]
+use chorus_macros::{observe_vec, Composite, PartialUpdate, Updateable};
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::types::Composite;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::gateway::GatewayHandle;
 
 /// See <https://discord.com/developers/docs/resources/guild>
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
-#[cfg_attr(feature = "client", derive(Updateable, Composite))]
+#[cfg_attr(feature = "client-core", derive(Updateable, Composite, PartialUpdate))]
 #[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
 pub struct Guild {
     pub afk_channel_id: Option<Snowflake>,
@@ -51,14 +51,14 @@ pub struct Guild {
     #[serde(default)]
     pub bans: Vec<GuildBan>,
     #[cfg_attr(feature = "sqlx", sqlx(skip))]
-    #[cfg_attr(feature = "client", observe_vec)]
+    #[cfg_attr(feature = "client-core", observe_vec)]
     #[serde(default)]
     pub channels: Vec<Shared<Channel>>,
     pub default_message_notifications: Option<MessageNotificationLevel>,
     pub description: Option<String>,
     pub discovery_splash: Option<String>,
     #[cfg_attr(feature = "sqlx", sqlx(skip))]
-    #[cfg_attr(feature = "client", observe_vec)]
+    #[cfg_attr(feature = "client-core", observe_vec)]
     #[serde(default)]
     pub emojis: Vec<Shared<Emoji>>,
     pub explicit_content_filter: Option<ExplicitContentFilterLevel>,
@@ -97,7 +97,7 @@ pub struct Guild {
     pub public_updates_channel_id: Option<Snowflake>,
     pub region: Option<String>,
     #[cfg_attr(feature = "sqlx", sqlx(skip))]
-    #[cfg_attr(feature = "client", observe_vec)]
+    #[cfg_attr(feature = "client-core", observe_vec)]
     #[serde(default)]
     pub roles: Vec<Shared<RoleObject>>,
     #[cfg_attr(feature = "sqlx", sqlx(skip))]
@@ -114,11 +114,11 @@ pub struct Guild {
     pub verification_level: Option<VerificationLevel>,
     #[serde(default)]
     #[cfg_attr(feature = "sqlx", sqlx(skip))]
-    #[cfg_attr(feature = "client", observe_vec)]
+    #[cfg_attr(feature = "client-core", observe_vec)]
     pub voice_states: Vec<Shared<VoiceState>>,
     #[serde(default)]
     #[cfg_attr(feature = "sqlx", sqlx(skip))]
-    #[cfg_attr(feature = "client", observe_vec)]
+    #[cfg_attr(feature = "client-core", observe_vec)]
     pub webhooks: Vec<Shared<Webhook>>,
     #[cfg(feature = "sqlx")]
     pub welcome_screen: sqlx::types::Json<Option<WelcomeScreenObject>>,
@@ -300,6 +300,19 @@ impl PartialEq for GuildScheduledEvent {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+/// A user who is subscribed to (interested in) a [GuildScheduledEvent].
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/guild-scheduled-event#guild-scheduled-event-user-object>
+pub struct GuildScheduledEventUser {
+    pub guild_scheduled_event_id: Snowflake,
+    pub user: PublicUser,
+    /// The user's member object, if requested with
+    /// [GuildScheduledEventUsersQuery::with_member](crate::types::GuildScheduledEventUsersQuery::with_member)
+    pub member: Option<GuildMember>,
+}
+
 #[derive(Serialize_repr, Deserialize_repr, Debug, Default, Clone, PartialEq, Copy)]
 #[cfg_attr(not(feature = "sqlx"), repr(u8))]
 #[cfg_attr(feature = "sqlx", repr(i16))]
@@ -2,7 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeDelta, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::types::{entities::PublicUser, Snowflake};
@@ -10,6 +10,10 @@ use crate::types::{GuildMemberFlags, PermissionFlags, Shared};
 
 use super::option_arc_rwlock_ptr_eq;
 
+/// The longest a guild member can be timed out for, see
+/// [GuildMember::communication_disabled_until].
+pub const MAX_MEMBER_TIMEOUT: TimeDelta = TimeDelta::days(28);
+
 #[derive(Debug, Deserialize, Default, Serialize, Clone)]
 #[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
 /// Represents a participating user in a guild.
@@ -31,9 +35,21 @@ pub struct GuildMember {
     pub pending: Option<bool>,
     #[serde(default)]
     pub permissions: PermissionFlags,
+    /// Until when the member is timed out, if they are.
+    ///
+    /// Timed out members cannot send messages, react, join voice channels or speak in them.
     pub communication_disabled_until: Option<DateTime<Utc>>,
 }
 
+impl GuildMember {
+    /// Returns whether the member is currently timed out, see
+    /// [GuildMember::communication_disabled_until].
+    pub fn is_timed_out(&self) -> bool {
+        self.communication_disabled_until
+            .is_some_and(|until| until > Utc::now())
+    }
+}
+
 #[cfg(not(tarpaulin_include))]
 impl PartialEq for GuildMember {
     fn eq(&self, other: &Self) -> bool {
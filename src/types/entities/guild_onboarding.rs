@@ -0,0 +1,95 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+use crate::types::{PartialEmoji, Snowflake};
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+/// The questions new members of a guild are asked when joining it, which decide the channels
+/// and roles they get.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/guild#guild-onboarding-object>
+pub struct GuildOnboarding {
+    pub guild_id: Snowflake,
+    pub prompts: Vec<OnboardingPrompt>,
+    /// The channels members are shown by default, regardless of their answers
+    pub default_channel_ids: Vec<Snowflake>,
+    pub enabled: bool,
+    /// Whether the guild does not meet the requirements for onboarding anymore, such as enough
+    /// default channels
+    #[serde(default)]
+    pub below_requirements: bool,
+    #[serde(default)]
+    pub mode: OnboardingMode,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+/// A question asked during onboarding.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/guild#onboarding-prompt-object>
+pub struct OnboardingPrompt {
+    pub id: Snowflake,
+    #[serde(rename = "type")]
+    pub prompt_type: OnboardingPromptType,
+    /// The possible answers (1-50)
+    pub options: Vec<OnboardingPromptOption>,
+    /// The question (1-100 characters)
+    pub title: String,
+    /// Whether members may only pick one of the options
+    pub single_select: bool,
+    /// Whether the prompt must be answered to finish onboarding
+    pub required: bool,
+    /// Whether the prompt is shown during onboarding, or only later in the guild's
+    /// "Channels & Roles" tab
+    pub in_onboarding: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+/// A possible answer to an [OnboardingPrompt].
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/guild#prompt-option-object>
+pub struct OnboardingPromptOption {
+    pub id: Snowflake,
+    /// The channels members are given access to when choosing the option
+    pub channel_ids: Vec<Snowflake>,
+    /// The roles members are given when choosing the option
+    pub role_ids: Vec<Snowflake>,
+    #[serde(default)]
+    pub emoji: Option<PartialEmoji>,
+    /// The option's title (1-50 characters)
+    pub title: String,
+    /// The option's description (max 100 characters)
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Serialize_repr, Deserialize_repr, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+/// # Reference
+/// See <https://docs.discord.sex/resources/guild#onboarding-prompt-type>
+pub enum OnboardingPromptType {
+    #[default]
+    MultipleChoice = 0,
+    Dropdown = 1,
+}
+
+#[derive(Serialize_repr, Deserialize_repr, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+/// Which requirements the guild's onboarding has to meet.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/guild#onboarding-mode>
+pub enum OnboardingMode {
+    /// Only the default channels count towards the requirements
+    #[default]
+    OnboardingDefault = 0,
+    /// Both the default channels and the channels of prompt options count towards the
+    /// requirements
+    OnboardingAdvanced = 1,
+}
@@ -4,7 +4,7 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 
 use crate::types::Snowflake;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::gateway::Updateable;
 
 // FIXME: Should this type be Composite?
@@ -29,7 +29,7 @@ pub struct Harvest {
     pub completed_at: Option<DateTime<Utc>>,
 }
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 impl Updateable for Harvest {
     #[cfg(not(tarpaulin_include))]
     fn id(&self) -> Snowflake {
@@ -69,6 +69,13 @@ pub enum HarvestStatus {
     Unknown = 4,
 }
 
+impl HarvestStatus {
+    /// Whether the harvest is done being processed, successfully or not
+    pub fn is_finished(&self) -> bool {
+        matches!(self, HarvestStatus::Completed | HarvestStatus::Failed)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Hash, Copy, PartialOrd, Ord)]
 #[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
 /// A type of backend / service a harvest can be requested for.
@@ -52,6 +52,13 @@ pub struct Invite {
     pub target_user: Option<User>,
     pub temporary: Option<bool>,
     pub uses: Option<UInt32>,
+    /// Whether the user has to fill out the guild's member verification form, only sent when
+    /// accepting an invite.
+    ///
+    /// See [Guild::get_member_verification] and [Guild::submit_member_verification].
+    #[cfg_attr(feature = "sqlx", sqlx(skip))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show_verification_form: Option<bool>,
 }
 
 /// The guild an invite is for.
@@ -0,0 +1,134 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{Guild, PublicUser, Snowflake};
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+/// A guild's member verification (membership screening) form, which new members have to fill
+/// out before they can talk in the guild.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/guild#member-verification-object>
+pub struct MemberVerification {
+    /// When the form was last modified, which has to be sent back when submitting it
+    pub version: Option<DateTime<Utc>>,
+    /// The questions of the form (max 5)
+    pub form_fields: Vec<MemberVerificationFormField>,
+    /// A description of what the guild is about (max 300 characters)
+    #[serde(default)]
+    pub description: Option<String>,
+    /// The guild the form belongs to, only sent if requested with
+    /// [`with_guild`](crate::types::GuildMemberVerificationQuery::with_guild)
+    #[serde(default)]
+    pub guild: Option<Guild>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+/// A single question of a [MemberVerification] form.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/guild#member-verification-form-field-structure>
+pub struct MemberVerificationFormField {
+    pub field_type: MemberVerificationFieldType,
+    /// The question (max 300 characters)
+    pub label: String,
+    /// The possible answers, for [MemberVerificationFieldType::MultipleChoice] (1-8)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub choices: Option<Vec<String>>,
+    /// The rules members have to accept, for [MemberVerificationFieldType::Terms] (1-16)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub values: Option<Vec<String>>,
+    /// The member's answer, only set when submitting the form
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<MemberVerificationResponse>,
+    pub required: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Placeholder text shown for [MemberVerificationFieldType::TextInput] and
+    /// [MemberVerificationFieldType::Paragraph] fields
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+/// # Reference
+/// See <https://docs.discord.sex/resources/guild#member-verification-form-field-type>
+pub enum MemberVerificationFieldType {
+    /// The member has to accept the guild's rules
+    #[default]
+    Terms,
+    /// A single line text answer
+    TextInput,
+    /// A multi line text answer
+    Paragraph,
+    /// One of the field's choices
+    MultipleChoice,
+    /// The member has to verify their email address or phone number
+    Verification,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(untagged)]
+/// A member's answer to a [MemberVerificationFormField].
+pub enum MemberVerificationResponse {
+    /// Whether the rules were accepted, for [MemberVerificationFieldType::Terms]
+    Accepted(bool),
+    /// The index of the chosen answer, for [MemberVerificationFieldType::MultipleChoice]
+    Choice(u8),
+    /// The answer, for [MemberVerificationFieldType::TextInput] and
+    /// [MemberVerificationFieldType::Paragraph]
+    Text(String),
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+/// A request to join a guild, created by submitting its [MemberVerification] form.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/guild#guild-join-request-object>
+pub struct GuildJoinRequest {
+    pub id: Snowflake,
+    pub join_request_id: Snowflake,
+    pub created_at: DateTime<Utc>,
+    pub application_status: GuildJoinRequestStatus,
+    pub guild_id: Snowflake,
+    /// The submitted form, including the member's responses
+    #[serde(default)]
+    pub form_responses: Option<Vec<MemberVerificationFormField>>,
+    /// When the request was last seen by the user
+    #[serde(default)]
+    pub last_seen: Option<DateTime<Utc>>,
+    /// When the request was approved or rejected, as a snowflake
+    #[serde(default)]
+    pub actioned_at: Option<Snowflake>,
+    /// The moderator who approved or rejected the request
+    #[serde(default)]
+    pub actioned_by_user: Option<PublicUser>,
+    #[serde(default)]
+    pub rejection_reason: Option<String>,
+    pub user_id: Snowflake,
+    #[serde(default)]
+    pub user: Option<PublicUser>,
+    /// The channel a moderator opened to talk with the user about their request
+    #[serde(default)]
+    pub interview_channel_id: Option<Snowflake>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+/// # Reference
+/// See <https://docs.discord.sex/resources/guild#guild-join-request-application-status>
+pub enum GuildJoinRequestStatus {
+    /// The form was not submitted yet
+    #[default]
+    Started,
+    /// The form was submitted and awaits review
+    Submitted,
+    Rejected,
+    /// The user is now a full member of the guild
+    Approved,
+}
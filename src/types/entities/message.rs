@@ -7,13 +7,14 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
+use crate::errors::EmbedError;
 use crate::types::{
     entities::{
-        Application, Attachment, Channel, Emoji, GuildMember, PublicUser, RoleSubscriptionData,
-        Sticker, StickerItem, User,
+        Application, Attachment, Channel, ChannelType, Emoji, GuildMember, PublicUser,
+        RoleSubscriptionData, Sticker, StickerItem, User,
     },
     utils::Snowflake,
-    Shared,
+    AllowedMentions, Shared,
 };
 use crate::{UInt32, UInt8};
 
@@ -84,6 +85,9 @@ pub struct Message {
     pub stickers: Option<Vec<Sticker>>,
     #[cfg_attr(feature = "sqlx", sqlx(skip))]
     pub role_subscription_data: Option<RoleSubscriptionData>,
+    #[cfg_attr(feature = "sqlx", sqlx(skip))]
+    #[serde(default)]
+    pub poll: Option<Poll>,
 }
 
 #[cfg(not(tarpaulin_include))]
@@ -124,6 +128,7 @@ impl PartialEq for Message {
             && self.sticker_items == other.sticker_items
             && self.stickers == other.stickers
             && self.role_subscription_data == other.role_subscription_data
+            && self.poll == other.poll
     }
 }
 
@@ -171,21 +176,8 @@ impl PartialEq for MessageInteraction {
     }
 }
 
-#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Eq, PartialOrd, Ord, Hash)]
-pub struct AllowedMention {
-    parse: Vec<AllowedMentionType>,
-    roles: Vec<Snowflake>,
-    users: Vec<Snowflake>,
-    replied_user: bool,
-}
-
-#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize, Eq, PartialOrd, Ord, Hash)]
-#[serde(rename_all = "snake_case")]
-pub enum AllowedMentionType {
-    Roles,
-    Users,
-    Everyone,
-}
+#[deprecated(note = "Renamed to AllowedMentions")]
+pub type AllowedMention = AllowedMentions;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ChannelMention {
@@ -193,7 +185,7 @@ pub struct ChannelMention {
     pub guild_id: Snowflake,
     #[serde(rename = "type")]
     channel_type: i32,
-    name: String,
+    pub name: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd, Eq, Hash, Ord)]
@@ -242,7 +234,7 @@ pub struct EmbedFooter {
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, PartialOrd, Ord, Hash)]
 pub struct EmbedImage {
     url: String,
-    proxy_url: String,
+    proxy_url: Option<String>,
     height: Option<i32>,
     width: Option<i32>,
 }
@@ -284,6 +276,211 @@ pub struct EmbedField {
     inline: Option<bool>,
 }
 
+/// The maximum length of an embed's title
+pub const MAX_EMBED_TITLE_LENGTH: usize = 256;
+/// The maximum length of an embed's description
+pub const MAX_EMBED_DESCRIPTION_LENGTH: usize = 4096;
+/// The maximum amount of [EmbedField]s an embed can have
+pub const MAX_EMBED_FIELDS: usize = 25;
+/// The maximum length of an embed field's name
+pub const MAX_EMBED_FIELD_NAME_LENGTH: usize = 256;
+/// The maximum length of an embed field's value
+pub const MAX_EMBED_FIELD_VALUE_LENGTH: usize = 1024;
+/// The maximum length of an embed's footer text
+pub const MAX_EMBED_FOOTER_LENGTH: usize = 2048;
+/// The maximum length of an embed's author name
+pub const MAX_EMBED_AUTHOR_NAME_LENGTH: usize = 256;
+/// The maximum amount of characters in the title, description, field names and values, footer
+/// text and author name of an embed combined
+pub const MAX_EMBED_TOTAL_LENGTH: usize = 6000;
+
+#[derive(Debug, Clone, PartialEq)]
+/// Builds a rich [Embed], checking it against the embed limits.
+///
+/// # Example
+/// ```rs
+/// let embed = EmbedBuilder::new()
+///     .title("Release 0.18")
+///     .description("Chorus 0.18 has been released!")
+///     .field("Changes", "Too many to list", false)
+///     .build()?;
+/// ```
+pub struct EmbedBuilder {
+    embed: Embed,
+}
+
+impl Default for EmbedBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EmbedBuilder {
+    /// Starts building an empty rich embed.
+    pub fn new() -> Self {
+        Self {
+            embed: Embed {
+                title: None,
+                embed_type: Some(EmbedType::Rich),
+                description: None,
+                url: None,
+                timestamp: None,
+                color: None,
+                footer: None,
+                image: None,
+                thumbnail: None,
+                video: None,
+                provider: None,
+                author: None,
+                fields: None,
+            },
+        }
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.embed.title = Some(title.to_string());
+        self
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.embed.description = Some(description.to_string());
+        self
+    }
+
+    /// Sets the url the title links to
+    pub fn url(mut self, url: &str) -> Self {
+        self.embed.url = Some(url.to_string());
+        self
+    }
+
+    /// Sets the timestamp shown in the embed's footer
+    pub fn timestamp(mut self, timestamp: DateTime<Utc>) -> Self {
+        self.embed.timestamp = Some(timestamp.to_rfc3339());
+        self
+    }
+
+    /// Sets the color of the embed's side bar, as an RGB integer (e.g. `0xFF0000` for red)
+    pub fn color(mut self, color: u32) -> Self {
+        self.embed.color = Some(color as i32);
+        self
+    }
+
+    pub fn footer(mut self, text: &str, icon_url: Option<&str>) -> Self {
+        self.embed.footer = Some(EmbedFooter {
+            text: text.to_string(),
+            icon_url: icon_url.map(String::from),
+            proxy_icon_url: None,
+        });
+        self
+    }
+
+    pub fn image(mut self, url: &str) -> Self {
+        self.embed.image = Some(EmbedImage {
+            url: url.to_string(),
+            proxy_url: None,
+            height: None,
+            width: None,
+        });
+        self
+    }
+
+    pub fn thumbnail(mut self, url: &str) -> Self {
+        self.embed.thumbnail = Some(EmbedThumbnail {
+            url: url.to_string(),
+            proxy_url: None,
+            height: None,
+            width: None,
+        });
+        self
+    }
+
+    pub fn author(mut self, name: &str, url: Option<&str>, icon_url: Option<&str>) -> Self {
+        self.embed.author = Some(EmbedAuthor {
+            name: name.to_string(),
+            url: url.map(String::from),
+            icon_url: icon_url.map(String::from),
+            proxy_icon_url: None,
+        });
+        self
+    }
+
+    /// Adds a field; `inline` fields may be shown next to each other.
+    pub fn field(mut self, name: &str, value: &str, inline: bool) -> Self {
+        self.embed
+            .fields
+            .get_or_insert_with(Vec::new)
+            .push(EmbedField {
+                name: name.to_string(),
+                value: value.to_string(),
+                inline: Some(inline),
+            });
+        self
+    }
+
+    /// Returns the embed, or an [EmbedError] if it exceeds one of the embed limits.
+    pub fn build(self) -> Result<Embed, EmbedError> {
+        let embed = self.embed;
+        let mut total = 0;
+
+        let mut check = |field: &str, text: Option<&str>, max: usize| {
+            let length = text.map_or(0, |text| text.chars().count());
+            total += length;
+
+            if length > max {
+                return Err(EmbedError::TooLong {
+                    field: field.to_string(),
+                    length,
+                    max,
+                });
+            }
+            Ok(())
+        };
+
+        check("title", embed.title.as_deref(), MAX_EMBED_TITLE_LENGTH)?;
+        check(
+            "description",
+            embed.description.as_deref(),
+            MAX_EMBED_DESCRIPTION_LENGTH,
+        )?;
+        check(
+            "footer text",
+            embed.footer.as_ref().map(|footer| footer.text.as_str()),
+            MAX_EMBED_FOOTER_LENGTH,
+        )?;
+        check(
+            "author name",
+            embed.author.as_ref().map(|author| author.name.as_str()),
+            MAX_EMBED_AUTHOR_NAME_LENGTH,
+        )?;
+
+        let fields = embed.fields.as_deref().unwrap_or_default();
+        for field in fields {
+            check("field name", Some(&field.name), MAX_EMBED_FIELD_NAME_LENGTH)?;
+            check(
+                "field value",
+                Some(&field.value),
+                MAX_EMBED_FIELD_VALUE_LENGTH,
+            )?;
+        }
+
+        if fields.len() > MAX_EMBED_FIELDS {
+            return Err(EmbedError::TooManyFields {
+                count: fields.len(),
+                max: MAX_EMBED_FIELDS,
+            });
+        }
+
+        if total > MAX_EMBED_TOTAL_LENGTH {
+            return Err(EmbedError::TotalTooLong {
+                length: total,
+                max: MAX_EMBED_TOTAL_LENGTH,
+            });
+        }
+
+        Ok(embed)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Reaction {
     pub count: UInt32,
@@ -299,20 +496,310 @@ pub struct Reaction {
     pub user_ids: Vec<Snowflake>,
 }
 
-#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize, Eq, PartialOrd, Ord)]
-#[cfg_attr(not(feature = "sqlx"), repr(u8))]
-#[cfg_attr(feature = "sqlx", repr(i16))]
-pub enum Component {
+#[derive(
+    Debug, PartialEq, Clone, Copy, Serialize_repr, Deserialize_repr, Eq, Hash, PartialOrd, Ord,
+)]
+#[repr(u8)]
+/// # Reference
+/// See <https://docs.discord.sex/resources/components#component-type>
+pub enum ComponentType {
+    /// A container for other components
     ActionRow = 1,
     Button = 2,
+    /// A select menu for picking from defined text options
     StringSelect = 3,
+    /// A text input field, only usable in modals
     TextInput = 4,
+    /// A select menu for users
     UserSelect = 5,
+    /// A select menu for roles
     RoleSelect = 6,
+    /// A select menu for users and roles
     MentionableSelect = 7,
+    /// A select menu for channels
     ChannelSelect = 8,
 }
 
+#[derive(Debug, PartialEq, Clone)]
+/// An interactive element of a message or modal.
+///
+/// Serialized as the inner component with an additional integer `type` field.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/components>
+pub enum Component {
+    ActionRow(ActionRow),
+    Button(Button),
+    StringSelect(SelectMenu),
+    TextInput(TextInput),
+    UserSelect(SelectMenu),
+    RoleSelect(SelectMenu),
+    MentionableSelect(SelectMenu),
+    ChannelSelect(SelectMenu),
+}
+
+impl Component {
+    /// Returns the [ComponentType] of this component.
+    pub fn component_type(&self) -> ComponentType {
+        match self {
+            Component::ActionRow(_) => ComponentType::ActionRow,
+            Component::Button(_) => ComponentType::Button,
+            Component::StringSelect(_) => ComponentType::StringSelect,
+            Component::TextInput(_) => ComponentType::TextInput,
+            Component::UserSelect(_) => ComponentType::UserSelect,
+            Component::RoleSelect(_) => ComponentType::RoleSelect,
+            Component::MentionableSelect(_) => ComponentType::MentionableSelect,
+            Component::ChannelSelect(_) => ComponentType::ChannelSelect,
+        }
+    }
+}
+
+impl From<ActionRow> for Component {
+    fn from(value: ActionRow) -> Self {
+        Component::ActionRow(value)
+    }
+}
+
+impl From<Button> for Component {
+    fn from(value: Button) -> Self {
+        Component::Button(value)
+    }
+}
+
+impl From<TextInput> for Component {
+    fn from(value: TextInput) -> Self {
+        Component::TextInput(value)
+    }
+}
+
+impl Serialize for Component {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::Error;
+
+        let inner = match self {
+            Component::ActionRow(row) => serde_json::to_value(row),
+            Component::Button(button) => serde_json::to_value(button),
+            Component::TextInput(input) => serde_json::to_value(input),
+            Component::StringSelect(menu)
+            | Component::UserSelect(menu)
+            | Component::RoleSelect(menu)
+            | Component::MentionableSelect(menu)
+            | Component::ChannelSelect(menu) => serde_json::to_value(menu),
+        }
+        .map_err(S::Error::custom)?;
+
+        let mut object = match inner {
+            serde_json::Value::Object(object) => object,
+            _ => return Err(S::Error::custom("components must serialize to objects")),
+        };
+        object.insert(
+            "type".to_string(),
+            serde_json::to_value(self.component_type()).map_err(S::Error::custom)?,
+        );
+
+        object.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Component {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let component_type = value
+            .get("type")
+            .cloned()
+            .ok_or_else(|| D::Error::missing_field("type"))?;
+        let component_type: ComponentType =
+            serde_json::from_value(component_type).map_err(D::Error::custom)?;
+
+        let component = match component_type {
+            ComponentType::ActionRow => serde_json::from_value(value).map(Component::ActionRow),
+            ComponentType::Button => serde_json::from_value(value).map(Component::Button),
+            ComponentType::TextInput => serde_json::from_value(value).map(Component::TextInput),
+            ComponentType::StringSelect => {
+                serde_json::from_value(value).map(Component::StringSelect)
+            }
+            ComponentType::UserSelect => serde_json::from_value(value).map(Component::UserSelect),
+            ComponentType::RoleSelect => serde_json::from_value(value).map(Component::RoleSelect),
+            ComponentType::MentionableSelect => {
+                serde_json::from_value(value).map(Component::MentionableSelect)
+            }
+            ComponentType::ChannelSelect => {
+                serde_json::from_value(value).map(Component::ChannelSelect)
+            }
+        };
+
+        component.map_err(D::Error::custom)
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+/// A container which lays out other components in a row.
+///
+/// May hold up to 5 [Button]s, a single select menu or a single [TextInput].
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/components#action-row-object>
+pub struct ActionRow {
+    pub components: Vec<Component>,
+}
+
+impl ActionRow {
+    pub fn new(components: Vec<Component>) -> Self {
+        Self { components }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+/// # Reference
+/// See <https://docs.discord.sex/resources/components#button-object>
+pub struct Button {
+    pub style: ButtonStyle,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<PartialEmoji>,
+    /// The id sent back in the interaction when the button is clicked.
+    ///
+    /// Required for every style except [ButtonStyle::Link] and [ButtonStyle::Premium].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_id: Option<String>,
+    /// The SKU to purchase, only for [ButtonStyle::Premium]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sku_id: Option<Snowflake>,
+    /// The url to open, only for [ButtonStyle::Link]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub disabled: bool,
+}
+
+#[derive(
+    Debug,
+    Default,
+    PartialEq,
+    Clone,
+    Copy,
+    Serialize_repr,
+    Deserialize_repr,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+)]
+#[repr(u8)]
+/// # Reference
+/// See <https://docs.discord.sex/resources/components#button-style>
+pub enum ButtonStyle {
+    #[default]
+    Primary = 1,
+    Secondary = 2,
+    Success = 3,
+    Danger = 4,
+    /// Navigates to a url instead of sending an interaction
+    Link = 5,
+    /// Prompts the user to purchase an SKU instead of sending an interaction
+    Premium = 6,
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+/// A dropdown menu, used by every select menu [ComponentType].
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/components#select-menu-object>
+pub struct SelectMenu {
+    pub custom_id: String,
+    /// The options to choose from, only for [ComponentType::StringSelect]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<SelectOption>,
+    /// The channel types which can be chosen, only for [ComponentType::ChannelSelect]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channel_types: Vec<ChannelType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<String>,
+    /// The entities selected by default, for the auto-populated select menu types
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub default_values: Vec<SelectDefaultValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_values: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_values: Option<u8>,
+    #[serde(default)]
+    pub disabled: bool,
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+/// # Reference
+/// See <https://docs.discord.sex/resources/components#select-option-object>
+pub struct SelectOption {
+    pub label: String,
+    /// The value sent back in the interaction when this option is selected
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<PartialEmoji>,
+    #[serde(default)]
+    pub default: bool,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+/// # Reference
+/// See <https://docs.discord.sex/resources/components#select-default-value-object>
+pub struct SelectDefaultValue {
+    pub id: Snowflake,
+    /// One of `user`, `role` or `channel`
+    #[serde(rename = "type")]
+    pub value_type: String,
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+/// A text field, only usable in modals.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/components#text-input-object>
+pub struct TextInput {
+    pub custom_id: String,
+    pub style: TextInputStyle,
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required: Option<bool>,
+    /// The pre-filled value of the input; in a modal submission, the value the user entered
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<String>,
+}
+
+#[derive(
+    Debug,
+    Default,
+    PartialEq,
+    Clone,
+    Copy,
+    Serialize_repr,
+    Deserialize_repr,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+)]
+#[repr(u8)]
+/// # Reference
+/// See <https://docs.discord.sex/resources/components#text-input-style>
+pub enum TextInputStyle {
+    /// A single line input
+    #[default]
+    Short = 1,
+    /// A multi line input
+    Paragraph = 2,
+}
+
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 /// # Reference
 /// See <https://discord-userdoccers.vercel.app/resources/message#message-activity-object>
@@ -477,3 +964,83 @@ pub enum ReactionType {
     Normal = 0,
     Burst = 1, // The dreaded super reactions
 }
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+/// A poll attached to a message.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/message#poll-object>
+pub struct Poll {
+    /// The question of the poll; only its text is used
+    pub question: PollMedia,
+    /// The answers which can be voted for
+    pub answers: Vec<PollAnswer>,
+    /// When the poll ends
+    pub expiry: Option<DateTime<Utc>>,
+    /// Whether a user can vote for multiple answers
+    pub allow_multiselect: bool,
+    pub layout_type: PollLayoutType,
+    /// The current vote counts of the poll.
+    ///
+    /// May be missing if the counts are not known, e.g. in a partial message.
+    #[serde(default)]
+    pub results: Option<PollResults>,
+}
+
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+/// The content of a poll question or answer.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/message#poll-media-object>
+pub struct PollMedia {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// The emoji shown next to an answer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<PartialEmoji>,
+}
+
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+/// # Reference
+/// See <https://docs.discord.sex/resources/message#poll-answer-object>
+pub struct PollAnswer {
+    /// The id of the answer, assigned by the server.
+    ///
+    /// Should not be sent when creating a poll.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answer_id: Option<u8>,
+    pub poll_media: PollMedia,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
+/// # Reference
+/// See <https://docs.discord.sex/resources/message#poll-results-object>
+pub struct PollResults {
+    /// Whether the votes have been precisely counted; false while the poll is still running
+    pub is_finalized: bool,
+    /// The vote counts of every answer which has at least one vote
+    pub answer_counts: Vec<PollAnswerCount>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+/// # Reference
+/// See <https://docs.discord.sex/resources/message#poll-answer-count-object>
+pub struct PollAnswerCount {
+    /// The [PollAnswer::answer_id] this count is for
+    pub id: u8,
+    pub count: u32,
+    /// Whether the current user voted for this answer
+    pub me_voted: bool,
+}
+
+#[derive(
+    Debug, Default, PartialEq, Clone, Copy, Serialize_repr, Deserialize_repr, Eq, PartialOrd, Ord,
+)]
+#[repr(u8)]
+/// # Reference
+/// See <https://docs.discord.sex/resources/message#poll-layout-type>
+pub enum PollLayoutType {
+    #[default]
+    Default = 1,
+    ImageOnlyAnswers = 2,
+}
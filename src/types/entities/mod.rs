@@ -12,11 +12,15 @@ pub use connection::*;
 pub use emoji::*;
 pub use guild::*;
 pub use guild_member::*;
+pub use guild_onboarding::*;
 pub use harvest::*;
 pub use integration::*;
 pub use invite::*;
+pub use member_verification::*;
 pub use message::*;
+pub use premium_subscription::*;
 pub use ratelimits::*;
+pub use read_state::*;
 pub use relationship::*;
 pub use role::*;
 pub use security_key::*;
@@ -25,27 +29,28 @@ pub use sticker::*;
 pub use team::*;
 pub use template::*;
 pub use user::*;
+pub use user_guild_settings::*;
 pub use user_settings::*;
 pub use voice_state::*;
 pub use webhook::*;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 pub use mfa_token::*;
 
 use crate::types::Shared;
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use std::sync::{Arc, RwLock};
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::gateway::Updateable;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::gateway::GatewayHandle;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use async_trait::async_trait;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use std::fmt::Debug;
 
 mod application;
@@ -58,11 +63,15 @@ mod connection;
 mod emoji;
 mod guild;
 mod guild_member;
+mod guild_onboarding;
 mod harvest;
 mod integration;
 mod invite;
+mod member_verification;
 mod message;
+mod premium_subscription;
 mod ratelimits;
+mod read_state;
 mod relationship;
 mod role;
 mod security_key;
@@ -71,6 +80,7 @@ mod sticker;
 mod team;
 mod template;
 mod user;
+mod user_guild_settings;
 mod user_settings;
 mod voice_state;
 mod webhook;
@@ -79,10 +89,10 @@ mod webhook;
 //
 // For the server, you'd likely only store when it expires somewhere,
 // and give the JWT to the client to store
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 mod mfa_token;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 #[async_trait(?Send)]
 pub trait Composite<T: Updateable + Clone + Debug> {
     async fn watch_whole(self, gateway: &GatewayHandle) -> Self;
@@ -149,7 +159,7 @@ pub trait IntoShared {
     fn into_shared(self) -> Shared<Self>;
 }
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 impl<T: Sized> IntoShared for T {
     fn into_shared(self) -> Shared<Self> {
         Arc::new(RwLock::new(self))
@@ -157,13 +167,13 @@ impl<T: Sized> IntoShared for T {
 }
 
 /// Internal function to compare two `Shared<T>`s by comparing their pointers.
-#[cfg_attr(not(feature = "client"), allow(unused_variables))]
+#[cfg_attr(not(feature = "client-core"), allow(unused_variables))]
 pub(crate) fn arc_rwlock_ptr_eq<T>(a: &Shared<T>, b: &Shared<T>) -> bool {
-    #[cfg(feature = "client")]
+    #[cfg(feature = "client-core")]
     {
         Shared::ptr_eq(a, b)
     }
-    #[cfg(not(feature = "client"))]
+    #[cfg(not(feature = "client-core"))]
     {
         true
     }
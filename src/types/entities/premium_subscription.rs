@@ -0,0 +1,84 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{PublicUser, Snowflake};
+
+#[cfg(feature = "client-core")]
+use crate::gateway::Updateable;
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+/// A premium subscription to a guild, better known as a guild boost.
+///
+/// The amount of active boosts a guild has is also available as
+/// [Guild::premium_subscription_count](crate::types::Guild::premium_subscription_count).
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/premium-guild-subscription#premium-guild-subscription-object>
+pub struct PremiumGuildSubscription {
+    pub id: Snowflake,
+    /// The id of the guild being boosted
+    pub guild_id: Snowflake,
+    /// The id of the user boosting the guild
+    pub user_id: Snowflake,
+    /// The user boosting the guild
+    pub user: Option<PublicUser>,
+    /// Whether the boost has ended
+    #[serde(default)]
+    pub ended: bool,
+    /// When the boost will end, if it has been canceled
+    pub ends_at: Option<DateTime<Utc>>,
+    /// When the boost's pause will end, if it is paused
+    pub pause_ends_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(feature = "client-core")]
+impl Updateable for PremiumGuildSubscription {
+    #[cfg(not(tarpaulin_include))]
+    fn id(&self) -> Snowflake {
+        self.id
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+/// A boost slot of the current user, which can be used to boost one guild at a time.
+///
+/// Users get boost slots through their premium (nitro) subscription or by buying them separately.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/premium-guild-subscription#premium-guild-subscription-slot-object>
+pub struct PremiumGuildSubscriptionSlot {
+    pub id: Snowflake,
+    /// The id of the premium subscription the slot belongs to
+    pub subscription_id: Snowflake,
+    /// The boost currently using this slot, if any
+    pub premium_guild_subscription: Option<PremiumGuildSubscription>,
+    /// Whether the slot has been canceled, and will be removed at the end of the billing period
+    #[serde(default)]
+    pub canceled: bool,
+    /// When the slot can be used to boost another guild again, after being removed from one
+    pub cooldown_ends_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(feature = "client-core")]
+impl Updateable for PremiumGuildSubscriptionSlot {
+    #[cfg(not(tarpaulin_include))]
+    fn id(&self) -> Snowflake {
+        self.id
+    }
+}
+
+impl PremiumGuildSubscriptionSlot {
+    /// Returns whether the slot is not currently used to boost a guild.
+    ///
+    /// Note that the slot may still be on cooldown; see
+    /// [PremiumGuildSubscriptionSlot::cooldown_ends_at].
+    pub fn is_available(&self) -> bool {
+        self.premium_guild_subscription
+            .as_ref()
+            .map_or(true, |subscription| subscription.ended)
+    }
+}
@@ -3,7 +3,9 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use std::hash::Hash;
+use std::time::Duration;
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::types::Snowflake;
@@ -29,13 +31,49 @@ pub enum LimitType {
     WebhookBaseline,
 }
 
+impl LimitType {
+    /// Whether the bucket is shared by all users of an instance, instead of being tracked per
+    /// user.
+    pub fn is_instance_wide(&self) -> bool {
+        matches!(
+            self,
+            LimitType::AuthLogin | LimitType::AuthRegister | LimitType::Global | LimitType::Ip
+        )
+    }
+}
+
 /// A struct that represents the current ratelimits, either instance-wide or user-wide.
 /// See <https://discord.com/developers/docs/topics/rate-limits#rate-limits> for more information.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Copy, PartialOrd, Ord)]
 pub struct Limit {
     pub bucket: LimitType,
+    /// How many requests can be sent per window
     pub limit: u64,
+    /// How many requests can still be sent in the current window
     pub remaining: u64,
+    /// When the current window ends, as a unix timestamp in seconds
     pub reset: u64,
+    /// The length of a window, in seconds
     pub window: u64,
 }
+
+impl Limit {
+    /// Whether no more requests can be sent in the bucket until the current window ends.
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == 0 && Utc::now().timestamp() as u64 <= self.reset
+    }
+
+    /// Returns when the current window ends, and the bucket is replenished.
+    pub fn reset_at(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp(self.reset as i64, 0).unwrap_or_default()
+    }
+
+    /// Returns how long it takes until the current window ends, and the bucket is replenished.
+    ///
+    /// Returns [Duration::ZERO] if the bucket can already be replenished.
+    pub fn time_until_reset(&self) -> Duration {
+        let time = Utc::now().timestamp() as u64;
+        // Buckets are replenished once the current time is past their reset
+        Duration::from_secs((self.reset + 1).saturating_sub(time))
+    }
+}
@@ -0,0 +1,89 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+use crate::types::Snowflake;
+
+#[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// The read states of the current user, which track what they have read, and how many unread
+/// mentions they have.
+///
+/// Received in [GatewayReady](crate::types::GatewayReady) and kept up to date by
+/// [MessageACK](crate::types::MessageACK) events.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/read-state#read-state-object>
+pub struct ReadState {
+    pub entries: Vec<ReadStateEntry>,
+    /// Whether the entries only contain some of the read states
+    pub partial: bool,
+    pub version: u32,
+}
+
+#[derive(
+    Debug, Deserialize, Serialize, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Copy,
+)]
+/// The read state of a single resource, usually a channel.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/read-state#read-state-entry-structure>
+pub struct ReadStateEntry {
+    /// Spacebar servers do not have flags in this entity at all (??)
+    pub flags: Option<u32>,
+    /// The id of the resource, e.g. the channel id for [ReadStateType::Channel]
+    pub id: Snowflake,
+    /// What kind of resource the read state is for
+    #[serde(default)]
+    pub read_state_type: ReadStateType,
+    /// The id of the last acknowledged message
+    pub last_message_id: Option<Snowflake>,
+    /// The id of the last acknowledged resource, for read states which are not for channels
+    #[serde(default)]
+    pub last_acked_id: Option<Snowflake>,
+    pub last_pin_timestamp: Option<DateTime<Utc>>,
+    /// When the channel was last viewed, in days since the Discord epoch
+    pub last_viewed: Option<u32>,
+    // Temporary adding Option to fix Spacebar servers, they have mention count as a nullable
+    pub mention_count: Option<u64>,
+    /// The amount of unread notifications, for read states which are not for channels
+    #[serde(default)]
+    pub badge_count: Option<u64>,
+}
+
+#[derive(
+    Serialize_repr,
+    Deserialize_repr,
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+)]
+#[repr(u8)]
+/// What kind of resource a [ReadStateEntry] is for.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/read-state#read-state-type>
+pub enum ReadStateType {
+    /// A channel; the read state id is the channel's id
+    #[default]
+    Channel = 0,
+    /// Scheduled events of a guild; the read state id is the guild's id
+    GuildEvent = 1,
+    /// The notification center; the read state id is the user's id
+    NotificationCenter = 2,
+    /// The home feed of a guild; the read state id is the guild's id
+    GuildHome = 3,
+    /// The onboarding questions of a guild; the read state id is the guild's id
+    GuildOnboardingQuestion = 4,
+    /// Message requests; the read state id is the user's id
+    MessageRequests = 5,
+}
@@ -71,14 +71,14 @@ pub enum RelationshipType {
     None = 0,
 }
 
-#[cfg(feature = "sqlx")]
+#[cfg(feature = "sqlx-pg-uint")]
 impl sqlx::Type<sqlx::Postgres> for RelationshipType {
     fn type_info() -> <sqlx::Postgres as sqlx::Database>::TypeInfo {
         <sqlx_pg_uint::PgU8 as sqlx::Type<sqlx::Postgres>>::type_info()
     }
 }
 
-#[cfg(feature = "sqlx")]
+#[cfg(feature = "sqlx-pg-uint")]
 impl<'q> sqlx::Encode<'q, sqlx::Postgres> for RelationshipType {
     fn encode_by_ref(
         &self,
@@ -89,7 +89,7 @@ impl<'q> sqlx::Encode<'q, sqlx::Postgres> for RelationshipType {
     }
 }
 
-#[cfg(feature = "sqlx")]
+#[cfg(feature = "sqlx-pg-uint")]
 impl<'r> sqlx::Decode<'r, sqlx::Postgres> for RelationshipType {
     fn decode(
         value: <sqlx::Postgres as sqlx::Database>::ValueRef<'r>,
@@ -99,6 +99,9 @@ impl<'r> sqlx::Decode<'r, sqlx::Postgres> for RelationshipType {
     }
 }
 
+#[cfg(feature = "sqlx")]
+crate::types::utils::sqlx_u8_enum!(RelationshipType);
+
 impl TryFrom<u8> for RelationshipType {
     type Error = ChorusError;
 
@@ -10,20 +10,20 @@ use std::fmt::Debug;
 use crate::types::utils::Snowflake;
 use crate::{UInt16, UInt32};
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use chorus_macros::{Composite, Updateable};
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::gateway::Updateable;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::types::Composite;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::gateway::GatewayHandle;
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
-#[cfg_attr(feature = "client", derive(Updateable, Composite))]
+#[cfg_attr(feature = "client-core", derive(Updateable, Composite))]
 #[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
 /// See <https://discord.com/developers/docs/topics/permissions#role-object>
 pub struct RoleObject {
@@ -42,6 +42,72 @@ pub struct RoleObject {
     pub tags: Option<sqlx::types::Json<RoleTags>>,
     #[cfg(not(feature = "sqlx"))]
     pub tags: Option<RoleTags>,
+    /// The role's colors, including the gradient colors of the role's style.
+    ///
+    /// Not sent by all servers; [RoleObject::color] is always the primary color.
+    #[serde(default)]
+    #[cfg_attr(feature = "sqlx", sqlx(skip))]
+    pub colors: Option<RoleColors>,
+    #[serde(default)]
+    #[cfg_attr(feature = "sqlx", sqlx(skip))]
+    pub flags: RoleFlags,
+}
+
+#[derive(
+    Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord,
+)]
+/// The colors of a role.
+///
+/// Roles with a secondary color are displayed with a gradient, roles with a tertiary color
+/// additionally use the holographic style.
+///
+/// Setting a secondary color requires the guild to have the `ENHANCED_ROLE_COLORS` feature.
+///
+/// # Reference
+/// See <https://docs.discord.sex/topics/permissions#role-colors-object>
+pub struct RoleColors {
+    pub primary_color: u32,
+    #[serde(default)]
+    pub secondary_color: Option<u32>,
+    #[serde(default)]
+    pub tertiary_color: Option<u32>,
+}
+
+impl RoleColors {
+    /// The only colors accepted for the holographic style.
+    pub const HOLOGRAPHIC: RoleColors = RoleColors {
+        primary_color: 11127295,
+        secondary_color: Some(16759788),
+        tertiary_color: Some(16761760),
+    };
+
+    /// Creates colors for a role with a single, solid color.
+    pub fn solid(color: u32) -> Self {
+        RoleColors {
+            primary_color: color,
+            secondary_color: None,
+            tertiary_color: None,
+        }
+    }
+
+    /// Creates colors for a role with a gradient between two colors.
+    pub fn gradient(primary_color: u32, secondary_color: u32) -> Self {
+        RoleColors {
+            primary_color,
+            secondary_color: Some(secondary_color),
+            tertiary_color: None,
+        }
+    }
+
+    /// Returns whether the role is displayed with a gradient.
+    pub fn is_gradient(&self) -> bool {
+        self.secondary_color.is_some()
+    }
+
+    /// Returns whether the role uses the holographic style.
+    pub fn is_holographic(&self) -> bool {
+        self.tertiary_color.is_some()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -72,6 +138,16 @@ pub struct RoleTags {
     // guild_connections: bool,
 }
 
+bitflags! {
+    #[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq, chorus_macros::SerdeBitFlags)]
+    /// # Reference
+    /// See <https://docs.discord.sex/topics/permissions#role-flags>
+    pub struct RoleFlags: u64 {
+        /// The role can be selected by members in an onboarding prompt
+        const IN_PROMPT = 1 << 0;
+    }
+}
+
 bitflags! {
     #[derive(Debug, Default, Clone, Hash, PartialEq, Eq, PartialOrd, chorus_macros::SerdeBitFlags)]
     #[cfg_attr(feature = "sqlx", derive(chorus_macros::SqlxBitFlags))]
@@ -166,10 +242,22 @@ bitflags! {
         const VIEW_CREATOR_MONETIZATION_ANALYTICS = 1 << 41;
         /// Allows using the soundboard in a voice channel
         const USE_SOUNDBOARD = 1 << 42;
+        /// Allows creating emojis, stickers, and soundboard sounds, and editing and deleting those created by the current user
+        const CREATE_GUILD_EXPRESSIONS = 1 << 43;
+        /// Allows creating scheduled events, and editing and deleting those created by the current user
+        const CREATE_EVENTS = 1 << 44;
         /// Allows using custom soundboard sounds from other servers
         const USE_EXTERNAL_SOUNDS = 1 << 45;
         /// Allows sending voice messages
         const SEND_VOICE_MESSAGES = 1 << 46;
+        /// Allows interacting with the Clyde AI bot
+        const USE_CLYDE_AI = 1 << 47;
+        /// Allows setting the status of a voice channel
+        const SET_VOICE_CHANNEL_STATUS = 1 << 48;
+        /// Allows sending polls
+        const SEND_POLLS = 1 << 49;
+        /// Allows user-installed apps to send public responses
+        const USE_EXTERNAL_APPS = 1 << 50;
     }
 }
 
@@ -4,11 +4,13 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_aux::prelude::{deserialize_number_from_string, deserialize_option_number_from_string};
 
 use crate::types::{
     entities::{Guild, User},
     utils::Snowflake,
-    Shared,
+    ChannelType, DefaultReaction, ExplicitContentFilterLevel, MessageNotificationLevel,
+    PermissionFlags, PermissionOverwriteType, Shared, SystemChannelFlags, VerificationLevel,
 };
 use crate::UInt64;
 
@@ -21,14 +23,142 @@ pub struct GuildTemplate {
     pub description: Option<String>,
     pub usage_count: Option<UInt64>,
     pub creator_id: Snowflake,
+    #[serde(default)]
     #[cfg_attr(feature = "sqlx", sqlx(skip))]
     pub creator: Shared<User>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub source_guild_id: Snowflake,
+    #[serde(default)]
     #[cfg_attr(feature = "sqlx", sqlx(skip))]
     pub source_guild: Vec<Shared<Guild>>,
-    // Unsure how a {recursive: Guild} looks like, might be a Vec?
+    /// A snapshot of the source guild, which is used to create guilds from this template
     #[cfg_attr(feature = "sqlx", sqlx(skip))]
-    pub serialized_source_guild: Vec<Shared<Guild>>,
+    pub serialized_source_guild: TemplateSourceGuild,
+    /// Whether the template has unsynced changes
+    #[cfg_attr(feature = "sqlx", sqlx(skip))]
+    pub is_dirty: Option<bool>,
+}
+
+/// A snapshot of a guild's settings, roles and channels, as stored in a [GuildTemplate].
+///
+/// Roles and channels are identified by placeholder ids instead of [Snowflake]s; these are only
+/// unique within the template. The `@everyone` role always has the placeholder id `0`.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/guild-template#template-guild-structure>
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct TemplateSourceGuild {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub region: Option<String>,
+    pub verification_level: Option<VerificationLevel>,
+    pub default_message_notifications: Option<MessageNotificationLevel>,
+    pub explicit_content_filter: Option<ExplicitContentFilterLevel>,
+    pub preferred_locale: Option<String>,
+    pub afk_timeout: Option<i32>,
+    #[serde(default)]
+    pub roles: Vec<TemplateRole>,
+    #[serde(default)]
+    pub channels: Vec<TemplateChannel>,
+    /// The placeholder id of the afk channel
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub afk_channel_id: Option<u64>,
+    /// The placeholder id of the system channel
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub system_channel_id: Option<u64>,
+    pub system_channel_flags: Option<SystemChannelFlags>,
+    pub icon_hash: Option<String>,
+}
+
+impl TemplateSourceGuild {
+    /// Returns the role with the given placeholder id.
+    pub fn role(&self, id: u64) -> Option<&TemplateRole> {
+        self.roles.iter().find(|role| role.id == id)
+    }
+
+    /// Returns the channel with the given placeholder id.
+    pub fn channel(&self, id: u64) -> Option<&TemplateChannel> {
+        self.channels.iter().find(|channel| channel.id == id)
+    }
+
+    /// Returns the channels in the category with the given placeholder id, sorted by position.
+    pub fn category_children(&self, category_id: u64) -> Vec<&TemplateChannel> {
+        let mut children: Vec<&TemplateChannel> = self
+            .channels
+            .iter()
+            .filter(|channel| channel.parent_id == Some(category_id))
+            .collect();
+        children.sort_by_key(|channel| channel.position);
+        children
+    }
+}
+
+/// A role in a [TemplateSourceGuild].
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/guild-template#template-role-structure>
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct TemplateRole {
+    /// The placeholder id of the role
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub id: u64,
+    pub name: String,
+    #[serde(default)]
+    pub permissions: PermissionFlags,
+    #[serde(default)]
+    pub color: f64,
+    #[serde(default)]
+    pub hoist: bool,
+    #[serde(default)]
+    pub mentionable: bool,
+    pub icon: Option<String>,
+    pub unicode_emoji: Option<String>,
+}
+
+/// A channel in a [TemplateSourceGuild].
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/guild-template#template-channel-structure>
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct TemplateChannel {
+    /// The placeholder id of the channel
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub id: u64,
+    #[serde(rename = "type")]
+    pub channel_type: ChannelType,
+    pub name: Option<String>,
+    pub position: Option<i32>,
+    pub topic: Option<String>,
+    pub bitrate: Option<i32>,
+    pub user_limit: Option<i32>,
+    pub nsfw: Option<bool>,
+    pub rate_limit_per_user: Option<i32>,
+    /// The placeholder id of the parent category
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub parent_id: Option<u64>,
+    #[serde(default)]
+    pub permission_overwrites: Vec<TemplatePermissionOverwrite>,
+    pub default_auto_archive_duration: Option<i32>,
+    pub default_reaction_emoji: Option<DefaultReaction>,
+    pub default_thread_rate_limit_per_user: Option<i32>,
+    /// The template for new forum posts
+    pub template: Option<String>,
+}
+
+/// A permission overwrite of a [TemplateChannel].
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/guild-template#template-channel-structure>
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TemplatePermissionOverwrite {
+    /// The placeholder id of the role this overwrite applies to
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub id: u64,
+    #[serde(rename = "type")]
+    pub overwrite_type: PermissionOverwriteType,
+    #[serde(default)]
+    pub allow: PermissionFlags,
+    #[serde(default)]
+    pub deny: PermissionFlags,
 }
@@ -12,16 +12,16 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 use std::array::TryFromSliceError;
 use std::fmt::Debug;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::gateway::Updateable;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::types::Composite;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::gateway::GatewayHandle;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use chorus_macros::{Composite, Updateable};
 
 use super::{Emoji, GuildMember, PublicConnection};
@@ -39,7 +39,7 @@ impl User {
     }
 }
 #[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq, Hash)]
-#[cfg_attr(feature = "client", derive(Updateable, Composite))]
+#[cfg_attr(feature = "client-core", derive(Updateable, Composite))]
 #[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
 /// # Reference
 /// See <https://docs.discord.sex/resources/user#user-structure>
@@ -266,14 +266,14 @@ impl TryFrom<u8> for PremiumType {
     }
 }
 
-#[cfg(feature = "sqlx")]
+#[cfg(feature = "sqlx-pg-uint")]
 impl sqlx::Type<sqlx::Postgres> for PremiumType {
     fn type_info() -> <sqlx::Postgres as sqlx::Database>::TypeInfo {
         <sqlx_pg_uint::PgU8 as sqlx::Type<sqlx::Postgres>>::type_info()
     }
 }
 
-#[cfg(feature = "sqlx")]
+#[cfg(feature = "sqlx-pg-uint")]
 impl<'q> sqlx::Encode<'q, sqlx::Postgres> for PremiumType {
     fn encode_by_ref(
         &self,
@@ -284,7 +284,7 @@ impl<'q> sqlx::Encode<'q, sqlx::Postgres> for PremiumType {
     }
 }
 
-#[cfg(feature = "sqlx")]
+#[cfg(feature = "sqlx-pg-uint")]
 impl<'r> sqlx::Decode<'r, sqlx::Postgres> for PremiumType {
     fn decode(
         value: <sqlx::Postgres as sqlx::Database>::ValueRef<'r>,
@@ -294,6 +294,9 @@ impl<'r> sqlx::Decode<'r, sqlx::Postgres> for PremiumType {
     }
 }
 
+#[cfg(feature = "sqlx")]
+crate::types::utils::sqlx_u8_enum!(PremiumType);
+
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 /// # Reference
 /// See <https://docs.discord.sex/resources/user#profile-metadata-object>
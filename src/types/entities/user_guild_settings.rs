@@ -0,0 +1,97 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+use crate::types::Snowflake;
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+/// The current user's notification settings for a guild, such as whether it is muted.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/user-settings#user-guild-settings-object>
+pub struct UserGuildSettings {
+    /// The id of the guild, or [None] for the settings of private channels
+    pub guild_id: Option<Snowflake>,
+    /// The version of the settings, incremented with every change
+    pub version: u32,
+    pub flags: i32,
+    pub muted: bool,
+    /// How long the guild is muted for, if it is muted
+    pub mute_config: Option<MuteConfig>,
+    pub mute_scheduled_events: bool,
+    /// Which messages in the guild the user is notified about
+    pub message_notifications: UserNotificationLevel,
+    /// Whether the user is notified about highlights
+    pub notify_highlights: HighlightLevel,
+    /// Whether `@everyone` and `@here` mentions are suppressed
+    pub suppress_everyone: bool,
+    /// Whether role mentions are suppressed
+    pub suppress_roles: bool,
+    /// Whether the user gets push notifications for the guild on mobile
+    pub mobile_push: bool,
+    pub hide_muted_channels: bool,
+    /// Settings of individual channels which differ from the guild's
+    pub channel_overrides: Vec<UserGuildSettingsChannelOverride>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+/// The current user's notification settings for a channel, overriding the ones of its guild.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/user-settings#channel-override-object>
+pub struct UserGuildSettingsChannelOverride {
+    pub channel_id: Snowflake,
+    pub muted: bool,
+    /// How long the channel is muted for, if it is muted
+    pub mute_config: Option<MuteConfig>,
+    /// Which messages in the channel the user is notified about
+    pub message_notifications: UserNotificationLevel,
+    pub flags: i32,
+    /// Whether the channel (category) is collapsed in the channel list
+    pub collapsed: bool,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+/// How long a guild or channel is muted for.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/user-settings#mute-config-structure>
+pub struct MuteConfig {
+    /// When the mute ends, or [None] if it lasts until it is removed
+    pub end_time: Option<DateTime<Utc>>,
+    /// The length of the mute the user selected, in seconds; -1 for muting until it is removed
+    pub selected_time_window: Option<i64>,
+}
+
+#[derive(Serialize_repr, Deserialize_repr, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+/// Which messages the user is notified about in a guild or channel.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/user-settings#user-guild-notification-level>
+pub enum UserNotificationLevel {
+    AllMessages = 0,
+    OnlyMentions = 1,
+    NoMessages = 2,
+    /// Use the setting of the guild (or the guild's default for guild settings)
+    #[default]
+    ParentDefault = 3,
+}
+
+#[derive(Serialize_repr, Deserialize_repr, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+/// Whether the user is notified about highlights (popular messages) in a guild.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/user-settings#highlight-level>
+pub enum HighlightLevel {
+    /// Use the default, which depends on how active the guild is
+    #[default]
+    Default = 0,
+    Disabled = 1,
+    Enabled = 2,
+}
@@ -4,8 +4,9 @@
 
 use chrono::{serde::ts_milliseconds_option, Utc};
 use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
 
-use crate::types::Shared;
+use crate::types::{Shared, UserModifySettingsSchema};
 use crate::{UInt16, UInt32, UInt8};
 use serde_aux::field_attributes::deserialize_option_number_from_string;
 
@@ -122,9 +123,92 @@ impl Default for UserSettings {
     }
 }
 
+impl UserSettings {
+    /// Applies a partial update of the settings, such as the one received in a
+    /// [UserSettingsUpdate](crate::types::UserSettingsUpdate) event.
+    ///
+    /// Settings which are [None] in the update are left unchanged.
+    pub fn apply_update(&mut self, update: &UserModifySettingsSchema) {
+        fn set<T: Clone>(setting: &mut T, value: &Option<T>) {
+            if let Some(value) = value {
+                *setting = value.clone();
+            }
+        }
+
+        if update.afk_timeout.is_some() {
+            // Not Copy with the sqlx feature
+            #[allow(clippy::clone_on_copy)]
+            {
+                self.afk_timeout = update.afk_timeout.clone();
+            }
+        }
+        set(
+            &mut self.allow_accessibility_detection,
+            &update.allow_accessibility_detection,
+        );
+        set(&mut self.animate_emoji, &update.animate_emoji);
+        set(&mut self.animate_stickers, &update.animate_stickers);
+        set(&mut self.contact_sync_enabled, &update.contact_sync_enabled);
+        set(&mut self.convert_emoticons, &update.convert_emoticons);
+        set(&mut self.custom_status, &update.custom_status);
+        set(
+            &mut self.default_guilds_restricted,
+            &update.default_guilds_restricted,
+        );
+        set(
+            &mut self.detect_platform_accounts,
+            &update.detect_platform_accounts,
+        );
+        set(&mut self.developer_mode, &update.developer_mode);
+        set(&mut self.disable_games_tab, &update.disable_games_tab);
+        set(&mut self.enable_tts_command, &update.enable_tts_command);
+        set(
+            &mut self.explicit_content_filter,
+            &update.explicit_content_filter,
+        );
+        set(&mut self.friend_source_flags, &update.friend_source_flags);
+        set(&mut self.gif_auto_play, &update.gif_auto_play);
+        set(&mut self.guild_folders, &update.guild_folders);
+        set(&mut self.guild_positions, &update.guild_positions);
+        set(
+            &mut self.inline_attachment_media,
+            &update.inline_attachment_media,
+        );
+        set(&mut self.inline_embed_media, &update.inline_embed_media);
+        set(&mut self.locale, &update.locale);
+        set(
+            &mut self.message_display_compact,
+            &update.message_display_compact,
+        );
+        set(
+            &mut self.native_phone_integration_enabled,
+            &update.native_phone_integration_enabled,
+        );
+        set(&mut self.render_embeds, &update.render_embeds);
+        set(&mut self.render_reactions, &update.render_reactions);
+        set(&mut self.restricted_guilds, &update.restricted_guilds);
+        set(&mut self.show_current_game, &update.show_current_game);
+        set(
+            &mut self.stream_notifications_enabled,
+            &update.stream_notifications_enabled,
+        );
+        set(&mut self.theme, &update.theme);
+        set(&mut self.timezone_offset, &update.timezone_offset);
+
+        // The status is shared, so update it in place
+        #[cfg(feature = "client-core")]
+        if let Some(status) = update.status {
+            *self.status.write().unwrap() = status;
+        }
+        #[cfg(not(feature = "client-core"))]
+        set(&mut self.status, &update.status);
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow, sqlx::Type))]
-#[cfg_attr(feature = "sqlx", sqlx(type_name = "interface_type"))]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+#[cfg_attr(feature = "sqlx-pg-uint", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx-pg-uint", sqlx(type_name = "interface_type"))]
 pub struct CustomStatus {
     pub emoji_id: Option<String>,
     pub emoji_name: Option<String>,
@@ -136,7 +220,8 @@ pub struct CustomStatus {
 #[derive(
     Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Copy, PartialOrd, Ord, Hash,
 )]
-#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow, sqlx::Type))]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+#[cfg_attr(feature = "sqlx-pg-uint", derive(sqlx::Type))]
 pub struct FriendSourceFlags {
     pub all: bool,
 }
@@ -148,8 +233,9 @@ impl Default for FriendSourceFlags {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow, sqlx::Type))]
-#[cfg_attr(feature = "sqlx", sqlx(type_name = "interface_type"))]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+#[cfg_attr(feature = "sqlx-pg-uint", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx-pg-uint", sqlx(type_name = "interface_type"))]
 pub struct GuildFolder {
     pub color: Option<UInt32>,
     pub guild_ids: Vec<String>,
@@ -164,6 +250,27 @@ pub struct GuildFolder {
     pub name: Option<String>,
 }
 
+#[derive(Serialize_repr, Deserialize_repr, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+/// The kinds of settings protos, Discord's newer, protobuf encoded way of storing user settings.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/user-settings-proto#user-settings-type>
+pub enum UserSettingsProtoType {
+    /// General settings, such as the theme and locale
+    #[default]
+    PreloadedUserSettings = 1,
+    /// Recently and frequently used emojis, stickers, gifs, etc.
+    FrecencyUserSettings = 2,
+    TestSettings = 3,
+}
+
+impl std::fmt::Display for UserSettingsProtoType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", *self as u8)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LoginResult {
     pub token: String,
@@ -2,18 +2,18 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use chorus_macros::Composite;
 
 use crate::types::Shared;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::types::Composite;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::gateway::GatewayHandle;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::gateway::Updateable;
 
 use chrono::{DateTime, Utc};
@@ -33,7 +33,7 @@ use super::option_arc_rwlock_ptr_eq;
 /// See <https://docs.spacebar.chat/routes/#cmp--schemas-voicestate>
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 #[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
-#[cfg_attr(feature = "client", derive(Composite))]
+#[cfg_attr(feature = "client-core", derive(Composite))]
 pub struct VoiceState {
     pub guild_id: Option<Snowflake>,
     #[cfg_attr(feature = "sqlx", sqlx(skip))]
@@ -78,7 +78,7 @@ impl PartialEq for VoiceState {
     }
 }
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 impl Updateable for VoiceState {
     #[cfg(not(tarpaulin_include))]
     fn id(&self) -> Snowflake {
@@ -5,23 +5,25 @@
 use std::fmt::Debug;
 
 use serde::{Deserialize, Serialize};
+use serde_aux::prelude::deserialize_default_from_null;
+use serde_repr::{Deserialize_repr, Serialize_repr};
 
 use crate::types::Shared;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::gateway::Updateable;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use chorus_macros::{Composite, Updateable};
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::types::Composite;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::gateway::GatewayHandle;
 
 use crate::types::{
-    entities::{Guild, User},
+    entities::{FollowedChannel, Guild, User},
     utils::Snowflake,
 };
 
@@ -29,14 +31,17 @@ use super::option_arc_rwlock_ptr_eq;
 
 /// See <https://docs.spacebar.chat/routes/#cmp--schemas-webhook>
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
-#[cfg_attr(feature = "client", derive(Updateable, Composite))]
+#[cfg_attr(feature = "client-core", derive(Updateable, Composite))]
 #[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
 pub struct Webhook {
     pub id: Snowflake,
     #[serde(rename = "type")]
     pub webhook_type: WebhookType,
     pub name: String,
+    #[serde(default, deserialize_with = "deserialize_default_from_null")]
     pub avatar: String,
+    /// The secure token of the webhook, empty for [WebhookType::ChannelFollower] webhooks
+    #[serde(default, deserialize_with = "deserialize_default_from_null")]
     pub token: String,
     pub guild_id: Snowflake,
     pub channel_id: Snowflake,
@@ -47,10 +52,31 @@ pub struct Webhook {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[cfg_attr(feature = "sqlx", sqlx(skip))]
     pub source_guild: Option<Shared<Guild>>,
+    /// The announcement channel a [WebhookType::ChannelFollower] webhook crossposts from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "sqlx", sqlx(skip))]
+    pub source_channel: Option<WebhookSourceChannel>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,
 }
 
+impl Webhook {
+    /// Returns which announcement channel a [WebhookType::ChannelFollower] webhook follows,
+    /// in the form returned by [Channel::follow](crate::types::Channel::follow).
+    ///
+    /// Returns [None] for other webhooks.
+    pub fn followed_channel(&self) -> Option<FollowedChannel> {
+        if self.webhook_type != WebhookType::ChannelFollower {
+            return None;
+        }
+
+        self.source_channel.as_ref().map(|channel| FollowedChannel {
+            channel_id: channel.id,
+            webhook_id: self.id,
+        })
+    }
+}
+
 #[cfg(not(tarpaulin_include))]
 impl PartialEq for Webhook {
     fn eq(&self, other: &Self) -> bool {
@@ -64,12 +90,23 @@ impl PartialEq for Webhook {
             && self.application_id == other.application_id
             && option_arc_rwlock_ptr_eq(&self.user, &other.user)
             && option_arc_rwlock_ptr_eq(&self.source_guild, &other.source_guild)
+            && self.source_channel == other.source_channel
             && self.url == other.url
     }
 }
 
 #[derive(
-    Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
+    Serialize_repr,
+    Deserialize_repr,
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
 )]
 #[cfg_attr(not(feature = "sqlx"), repr(u8))]
 #[cfg_attr(feature = "sqlx", repr(i16))]
@@ -80,3 +117,15 @@ pub enum WebhookType {
     ChannelFollower = 2,
     Application = 3,
 }
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq, Hash)]
+/// The partial announcement channel a [WebhookType::ChannelFollower] webhook crossposts
+/// messages from.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/webhook#webhook-object>
+pub struct WebhookSourceChannel {
+    pub id: Snowflake,
+    #[serde(default)]
+    pub name: String,
+}
@@ -68,3 +68,104 @@ pub struct APIErrorPayload {
     pub message: String,
     pub code: String,
 }
+
+macro_rules! json_error_codes {
+    ($($(#[$attr:meta])* $name:ident = $code:literal,)*) => {
+        #[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+        #[serde(from = "u32", into = "u32")]
+        /// The code of an error returned by the HTTP API, telling apart failures which share an
+        /// HTTP status.
+        ///
+        /// Only the most common codes have their own variant, all others are kept as
+        /// [JsonErrorCode::Other].
+        ///
+        /// # Reference
+        /// See <https://docs.discord.sex/topics/opcodes-and-status-codes#json-error-codes>
+        pub enum JsonErrorCode {
+            $($(#[$attr])* $name,)*
+            /// A code without its own variant
+            Other(u32),
+        }
+
+        impl From<u32> for JsonErrorCode {
+            fn from(value: u32) -> Self {
+                match value {
+                    $($code => JsonErrorCode::$name,)*
+                    other => JsonErrorCode::Other(other),
+                }
+            }
+        }
+
+        impl From<JsonErrorCode> for u32 {
+            fn from(value: JsonErrorCode) -> Self {
+                match value {
+                    $(JsonErrorCode::$name => $code,)*
+                    JsonErrorCode::Other(other) => other,
+                }
+            }
+        }
+    };
+}
+
+json_error_codes! {
+    /// General error, used when no other code applies
+    #[default]
+    General = 0,
+    UnknownAccount = 10001,
+    UnknownApplication = 10002,
+    UnknownChannel = 10003,
+    UnknownGuild = 10004,
+    UnknownIntegration = 10005,
+    UnknownInvite = 10006,
+    UnknownMember = 10007,
+    UnknownMessage = 10008,
+    UnknownPermissionOverwrite = 10009,
+    UnknownRole = 10011,
+    UnknownToken = 10012,
+    UnknownUser = 10013,
+    UnknownEmoji = 10014,
+    UnknownWebhook = 10015,
+    UnknownBan = 10026,
+    UnknownGuildTemplate = 10057,
+    UnknownSticker = 10060,
+    UnknownGuildScheduledEvent = 10070,
+    BotsCannotUseEndpoint = 20001,
+    OnlyBotsCanUseEndpoint = 20002,
+    MaximumGuildsReached = 30001,
+    MaximumFriendsReached = 30002,
+    MaximumPinsReached = 30003,
+    MaximumRolesReached = 30005,
+    MaximumWebhooksReached = 30007,
+    MaximumEmojisReached = 30008,
+    MaximumReactionsReached = 30010,
+    MaximumChannelsReached = 30013,
+    MaximumInvitesReached = 30016,
+    Unauthorized = 40001,
+    AccountVerificationRequired = 40002,
+    RequestEntityTooLarge = 40005,
+    UserBannedFromGuild = 40007,
+    MessageAlreadyCrossposted = 40033,
+    MissingAccess = 50001,
+    InvalidAccountType = 50002,
+    CannotExecuteOnDmChannel = 50003,
+    GuildWidgetDisabled = 50004,
+    CannotEditOtherUsersMessage = 50005,
+    CannotSendEmptyMessage = 50006,
+    CannotSendMessagesToUser = 50007,
+    CannotSendMessagesInNonTextChannel = 50008,
+    MissingPermissions = 50013,
+    InvalidAuthenticationToken = 50014,
+    NoteTooLong = 50015,
+    CannotExecuteOnSystemMessage = 50021,
+    CannotExecuteOnChannelType = 50024,
+    InvalidRecipients = 50033,
+    MessageTooOldToBulkDelete = 50034,
+    /// The request body is invalid, see the field errors for why
+    InvalidFormBody = 50035,
+    InvalidApiVersion = 50041,
+    InvalidGuild = 50055,
+    InvalidJson = 50109,
+    TwoFactorRequired = 60003,
+    ReactionBlocked = 90001,
+    ApiResourceOverloaded = 130000,
+}
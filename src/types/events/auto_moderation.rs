@@ -2,16 +2,20 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use crate::types::{JsonField, SourceUrlField, WebSocketEvent};
-use chorus_macros::{JsonField, SourceUrlField, WebSocketEvent};
+use crate::types::{SourceUrlField, WebSocketEvent};
+use chorus_macros::{SourceUrlField, WebSocketEvent};
 use serde::{Deserialize, Serialize};
 
 use crate::types::{
     AutoModerationAction, AutoModerationRule, AutoModerationRuleTriggerType, Snowflake,
 };
 
-#[cfg(feature = "client")]
-use super::UpdateMessage;
+#[cfg(feature = "client-core")]
+use super::{update_object, UpdateMessage};
+#[cfg(feature = "client-core")]
+use crate::types::Shared;
+#[cfg(feature = "client-core")]
+use std::borrow::Cow;
 
 #[derive(Debug, Deserialize, Serialize, Default, Clone, WebSocketEvent)]
 /// See <https://discord.com/developers/docs/topics/gateway-events#auto-moderation-rule-create>
@@ -20,22 +24,22 @@ pub struct AutoModerationRuleCreate {
     pub rule: AutoModerationRule,
 }
 
-#[derive(
-    Debug, Deserialize, Serialize, Default, Clone, JsonField, SourceUrlField, WebSocketEvent,
-)]
+#[derive(Debug, Deserialize, Serialize, Default, Clone, SourceUrlField, WebSocketEvent)]
 /// See <https://discord.com/developers/docs/topics/gateway-events#auto-moderation-rule-update>
 pub struct AutoModerationRuleUpdate {
     #[serde(flatten)]
     pub rule: AutoModerationRule,
     #[serde(skip)]
-    pub json: String,
-    #[serde(skip)]
     pub source_url: String,
 }
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 #[cfg(not(tarpaulin_include))]
 impl UpdateMessage<AutoModerationRule> for AutoModerationRuleUpdate {
+    fn update(&mut self, object_to_update: Shared<AutoModerationRule>, fields: &[Cow<str>]) {
+        update_object(object_to_update, self.rule.clone(), fields)
+    }
+
     #[cfg(not(tarpaulin_include))]
     fn id(&self) -> Option<Snowflake> {
         Some(self.rule.id)
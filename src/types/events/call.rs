@@ -31,6 +31,13 @@ pub struct CallCreate {
     //pub embedded_activities: Vec<serde_json::Value>,
 }
 
+impl CallCreate {
+    /// Returns whether the given user is being rung to join the call.
+    pub fn is_ringing(&self, user_id: Snowflake) -> bool {
+        self.ringing.contains(&user_id)
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq, Eq, WebSocketEvent)]
 /// Updates the client when metadata about a call changes.
 ///
@@ -49,6 +56,13 @@ pub struct CallUpdate {
     pub region: String,
 }
 
+impl CallUpdate {
+    /// Returns whether the given user is being rung to join the call.
+    pub fn is_ringing(&self, user_id: Snowflake) -> bool {
+        self.ringing.contains(&user_id)
+    }
+}
+
 #[derive(
     Debug,
     Deserialize,
@@ -68,8 +82,8 @@ pub struct CallUpdate {
 /// See <https://docs.discord.sex/topics/gateway-events#call-delete>
 pub struct CallDelete {
     pub channel_id: Snowflake,
-	 /// Whether the call is unavailable due to an outage
-	 pub unavailable: Option<bool>,
+    /// Whether the call is unavailable due to an outage
+    pub unavailable: Option<bool>,
 }
 
 #[derive(
@@ -3,70 +3,83 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use crate::types::events::WebSocketEvent;
-use crate::types::{entities::Channel, JsonField, Snowflake, SourceUrlField};
-use chorus_macros::{JsonField, SourceUrlField};
+use crate::types::{entities::Channel, Snowflake, SourceUrlField};
+use chorus_macros::SourceUrlField;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use super::UpdateMessage;
+#[cfg(feature = "client-core")]
+use std::borrow::Cow;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::types::Shared;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::types::IntoShared;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::types::Guild;
 
-#[derive(Debug, Default, Deserialize, Serialize, WebSocketEvent, Copy, PartialEq, Clone, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Default, Deserialize, Serialize, WebSocketEvent, SourceUrlField, PartialEq, Clone, Eq, Hash, PartialOrd, Ord)]
 /// See <https://discord.com/developers/docs/topics/gateway-events#channel-pins-update>
 pub struct ChannelPinsUpdate {
     pub guild_id: Option<Snowflake>,
     pub channel_id: Snowflake,
     pub last_pin_timestamp: Option<DateTime<Utc>>,
+    #[serde(skip)]
+    pub source_url: String,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, Clone, JsonField, SourceUrlField, WebSocketEvent)]
+#[cfg(feature = "client-core")]
+impl UpdateMessage<Channel> for ChannelPinsUpdate {
+    fn update(&mut self, object_to_update: Shared<Channel>, _: &[Cow<str>]) {
+        let mut write = object_to_update.write().unwrap();
+        write.last_pin_timestamp = self.last_pin_timestamp;
+    }
+
+    #[cfg(not(tarpaulin_include))]
+    fn id(&self) -> Option<Snowflake> {
+        Some(self.channel_id)
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, SourceUrlField, WebSocketEvent)]
 /// See <https://discord.com/developers/docs/topics/gateway-events#channel-create>
 pub struct ChannelCreate {
     #[serde(flatten)]
     pub channel: Channel,
     #[serde(skip)]
-    pub json: String,
-    #[serde(skip)]
     pub source_url: String,
 }
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 impl UpdateMessage<Guild> for ChannelCreate {
     #[cfg(not(tarpaulin_include))]
     fn id(&self) -> Option<Snowflake> {
         self.channel.guild_id
     }
 
-    fn update(&mut self, object_to_update: Shared<Guild>) {
+    fn update(&mut self, object_to_update: Shared<Guild>, _: &[Cow<str>]) {
         let mut write = object_to_update.write().unwrap();
         let update = self.channel.clone().into_shared();
         write.channels.push(update);
     }
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, Clone, JsonField, SourceUrlField, WebSocketEvent)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone, SourceUrlField, WebSocketEvent)]
 /// See <https://discord.com/developers/docs/topics/gateway-events#channel-update>
 pub struct ChannelUpdate {
     #[serde(flatten)]
     pub channel: Channel,
     #[serde(skip)]
-    pub json: String,
-    #[serde(skip)]
     pub source_url: String,
 }
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 impl UpdateMessage<Channel> for ChannelUpdate {
-    fn update(&mut self, object_to_update: Shared<Channel>) {
+    fn update(&mut self, object_to_update: Shared<Channel>, _: &[Cow<str>]) {
         let mut write = object_to_update.write().unwrap();
         *write = self.channel.clone();
     }
@@ -95,25 +108,23 @@ pub struct ChannelUnreadUpdateObject {
     pub last_pin_timestamp: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, Clone, JsonField, SourceUrlField, WebSocketEvent)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone, SourceUrlField, WebSocketEvent)]
 /// See <https://discord.com/developers/docs/topics/gateway-events#channel-delete>
 pub struct ChannelDelete {
     #[serde(flatten)]
     pub channel: Channel,
     #[serde(skip)]
-    pub json: String,
-    #[serde(skip)]
     pub source_url: String,
 }
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 impl UpdateMessage<Guild> for ChannelDelete {
     #[cfg(not(tarpaulin_include))]
     fn id(&self) -> Option<Snowflake> {
         self.channel.guild_id
     }
 
-    fn update(&mut self, object_to_update: Shared<Guild>) {
+    fn update(&mut self, object_to_update: Shared<Guild>, _: &[Cow<str>]) {
         if self.id().is_none() {
             return;
         }
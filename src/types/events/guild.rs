@@ -2,36 +2,32 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use chorus_macros::{JsonField, SourceUrlField};
+use chorus_macros::SourceUrlField;
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::types::entities::{Guild, PublicUser, UnavailableGuild};
 use crate::types::events::WebSocketEvent;
 use crate::types::{
-    AuditLogEntry, Emoji, GuildMember, GuildScheduledEvent, JsonField, RoleObject, Snowflake,
-    SourceUrlField, Sticker,
+    AuditLogEntry, Emoji, GuildMember, GuildScheduledEvent, RoleObject, Snowflake, SourceUrlField,
+    Sticker,
 };
 
 use super::PresenceUpdate;
 
-#[cfg(feature = "client")]
-use super::UpdateMessage;
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
+use super::{update_object, UpdateMessage};
+#[cfg(feature = "client-core")]
 use crate::types::IntoShared;
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::types::Shared;
+#[cfg(feature = "client-core")]
+use std::borrow::Cow;
 
 #[derive(
-    Debug,
-    Deserialize,
-    Serialize,
-    Default,
-    Clone,
-    SourceUrlField,
-    JsonField,
-    WebSocketEvent,
-    PartialEq,
+    Debug, Deserialize, Serialize, Default, Clone, SourceUrlField, WebSocketEvent, PartialEq,
 )]
 /// See <https://discord.com/developers/docs/topics/gateway-events#guild-create>;
 /// Received to give data about a guild;
@@ -41,11 +37,9 @@ pub struct GuildCreate {
     pub d: GuildCreateDataOption,
     #[serde(skip)]
     pub source_url: String,
-    #[serde(skip)]
-    pub json: String,
 }
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 #[cfg(not(tarpaulin_include))]
 impl UpdateMessage<Guild> for GuildCreate {
     #[cfg(not(tarpaulin_include))]
@@ -56,7 +50,7 @@ impl UpdateMessage<Guild> for GuildCreate {
         }
     }
 
-    fn update(&mut self, _: Shared<Guild>) {}
+    fn update(&mut self, _: Shared<Guild>, _: &[Cow<str>]) {}
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
@@ -113,15 +107,7 @@ pub struct GuildBanRemove {
 }
 
 #[derive(
-    Debug,
-    Default,
-    Deserialize,
-    Serialize,
-    Clone,
-    SourceUrlField,
-    JsonField,
-    WebSocketEvent,
-    PartialEq,
+    Debug, Default, Deserialize, Serialize, Clone, SourceUrlField, WebSocketEvent, PartialEq,
 )]
 /// See <https://discord.com/developers/docs/topics/gateway-events#guild-update>;
 /// Received to give info about a guild being updated;
@@ -130,12 +116,14 @@ pub struct GuildUpdate {
     pub guild: Guild,
     #[serde(skip)]
     pub source_url: String,
-    #[serde(skip)]
-    pub json: String,
 }
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 impl UpdateMessage<Guild> for GuildUpdate {
+    fn update(&mut self, object_to_update: Shared<Guild>, fields: &[Cow<str>]) {
+        update_object(object_to_update, self.guild.clone(), fields)
+    }
+
     #[cfg(not(tarpaulin_include))]
     fn id(&self) -> Option<Snowflake> {
         Some(self.guild.id)
@@ -143,15 +131,7 @@ impl UpdateMessage<Guild> for GuildUpdate {
 }
 
 #[derive(
-    Debug,
-    Default,
-    Deserialize,
-    Serialize,
-    Clone,
-    SourceUrlField,
-    JsonField,
-    WebSocketEvent,
-    PartialEq,
+    Debug, Default, Deserialize, Serialize, Clone, SourceUrlField, WebSocketEvent, PartialEq,
 )]
 /// See <https://discord.com/developers/docs/topics/gateway-events#guild-delete>;
 /// Received to tell the client about a guild being deleted;
@@ -160,17 +140,15 @@ pub struct GuildDelete {
     pub guild: UnavailableGuild,
     #[serde(skip)]
     pub source_url: String,
-    #[serde(skip)]
-    pub json: String,
 }
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 impl UpdateMessage<Guild> for GuildDelete {
     #[cfg(not(tarpaulin_include))]
     fn id(&self) -> Option<Snowflake> {
         Some(self.guild.id)
     }
-    fn update(&mut self, _: Shared<Guild>) {}
+    fn update(&mut self, _: Shared<Guild>, _: &[Cow<str>]) {}
 }
 
 #[derive(Debug, Default, Deserialize, Serialize, Clone, WebSocketEvent, PartialEq)]
@@ -249,68 +227,48 @@ pub struct GuildMembersChunk {
 }
 
 #[derive(
-    Debug,
-    Default,
-    Deserialize,
-    Serialize,
-    Clone,
-    JsonField,
-    SourceUrlField,
-    WebSocketEvent,
-    PartialEq,
+    Debug, Default, Deserialize, Serialize, Clone, SourceUrlField, WebSocketEvent, PartialEq,
 )]
 /// See <https://discord.com/developers/docs/topics/gateway-events#guild-role-create>
 pub struct GuildRoleCreate {
     pub guild_id: Snowflake,
     pub role: RoleObject,
     #[serde(skip)]
-    pub json: String,
-    #[serde(skip)]
     pub source_url: String,
 }
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 impl UpdateMessage<Guild> for GuildRoleCreate {
     #[cfg(not(tarpaulin_include))]
     fn id(&self) -> Option<Snowflake> {
         Some(self.guild_id)
     }
 
-    fn update(&mut self, object_to_update: Shared<Guild>) {
+    fn update(&mut self, object_to_update: Shared<Guild>, _: &[Cow<str>]) {
         let mut object_to_update = object_to_update.write().unwrap();
         object_to_update.roles.push(self.role.clone().into_shared());
     }
 }
 
 #[derive(
-    Debug,
-    Default,
-    Deserialize,
-    Serialize,
-    Clone,
-    JsonField,
-    SourceUrlField,
-    WebSocketEvent,
-    PartialEq,
+    Debug, Default, Deserialize, Serialize, Clone, SourceUrlField, WebSocketEvent, PartialEq,
 )]
 /// See <https://discord.com/developers/docs/topics/gateway-events#guild-role-update>
 pub struct GuildRoleUpdate {
     pub guild_id: Snowflake,
     pub role: RoleObject,
     #[serde(skip)]
-    pub json: String,
-    #[serde(skip)]
     pub source_url: String,
 }
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 impl UpdateMessage<RoleObject> for GuildRoleUpdate {
     #[cfg(not(tarpaulin_include))]
     fn id(&self) -> Option<Snowflake> {
         Some(self.role.id)
     }
 
-    fn update(&mut self, object_to_update: Shared<RoleObject>) {
+    fn update(&mut self, object_to_update: Shared<RoleObject>, _: &[Cow<str>]) {
         let mut write = object_to_update.write().unwrap();
         *write = self.role.clone();
     }
@@ -359,3 +317,52 @@ pub struct GuildScheduledEventUserRemove {
     pub user_id: Snowflake,
     pub guild_id: Snowflake,
 }
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, WebSocketEvent, PartialEq, Eq)]
+/// Sent when the application commands available in a guild change, e.g. when an application
+/// with commands is added to or removed from the guild.
+///
+/// Clients use this to invalidate their cached application command index for the guild.
+///
+/// # Reference
+/// See <https://docs.discord.sex/topics/gateway-events#guild-application-command-index-update>
+pub struct GuildApplicationCommandIndexUpdate {
+    pub guild_id: Snowflake,
+    /// The number of application commands in the guild, by application command type
+    /// (1 for chat input, 2 for user and 3 for message commands)
+    #[serde(default)]
+    pub application_command_counts: Option<HashMap<u8, u32>>,
+    /// The new version of the guild's application command index
+    #[serde(default)]
+    pub version: Option<Snowflake>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, WebSocketEvent, PartialEq)]
+/// Sent when a prompt is added to a guild's onboarding flow.
+///
+/// The prompt itself is not typed yet and kept as raw JSON in `prompt`.
+pub struct GuildOnboardingPromptCreate {
+    pub guild_id: Snowflake,
+    #[serde(flatten)]
+    pub prompt: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, WebSocketEvent, PartialEq)]
+/// Sent when a prompt of a guild's onboarding flow is updated.
+///
+/// The prompt itself is not typed yet and kept as raw JSON in `prompt`.
+pub struct GuildOnboardingPromptUpdate {
+    pub guild_id: Snowflake,
+    #[serde(flatten)]
+    pub prompt: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, WebSocketEvent, PartialEq)]
+/// Sent when a prompt is removed from a guild's onboarding flow.
+///
+/// The prompt itself is not typed yet and kept as raw JSON in `prompt`.
+pub struct GuildOnboardingPromptDelete {
+    pub guild_id: Snowflake,
+    #[serde(flatten)]
+    pub prompt: serde_json::Map<String, serde_json::Value>,
+}
@@ -0,0 +1,194 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use serde::{Deserialize, Serialize};
+
+use super::{PresenceUpdate, WebSocketEvent};
+use crate::types::{GuildMember, Snowflake};
+
+#[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq, WebSocketEvent)]
+/// Officially Undocumented
+///
+/// Sent after subscribing to the member list (sidebar) of a guild channel with a
+/// [LazyRequest](super::LazyRequest), and whenever the subscribed part of the list changes.
+///
+/// See [MemberList] for keeping a synced copy of the list.
+///
+/// See <https://docs.discord.sex/topics/gateway-events#guild-member-list-update>
+pub struct GuildMemberListUpdate {
+    pub guild_id: Snowflake,
+    /// The id of the member list;
+    ///
+    /// "everyone" if every member can view the channel, otherwise a hash of the channel's
+    /// permission overwrites
+    pub id: String,
+    pub member_count: u32,
+    pub online_count: u32,
+    /// The groups (hoisted roles, online and offline) the list is split into
+    pub groups: Vec<MemberListGroup>,
+    /// The changes to apply to the list, in order
+    pub ops: Vec<MemberListOperation>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq, Eq)]
+/// A group of members in a member list.
+///
+/// See <https://docs.discord.sex/topics/gateway-events#member-list-group-structure>
+pub struct MemberListGroup {
+    /// The id of the hoisted role the group is for, or "online" / "offline"
+    pub id: String,
+    /// How many members are in the group
+    #[serde(default)]
+    pub count: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(tag = "op", rename_all = "SCREAMING_SNAKE_CASE")]
+/// A change to a member list, sent in a [GuildMemberListUpdate].
+///
+/// Ranges are inclusive on both ends.
+///
+/// See <https://docs.discord.sex/topics/gateway-events#member-list-operation-structure>
+pub enum MemberListOperation {
+    /// Replaces the items in the range
+    Sync {
+        range: (u32, u32),
+        items: Vec<MemberListItem>,
+    },
+    /// Removes the items in the range from the known part of the list, usually because the
+    /// range is not subscribed anymore
+    Invalidate { range: (u32, u32) },
+    /// Inserts an item at the index, moving the following items down
+    Insert { index: u32, item: MemberListItem },
+    /// Replaces the item at the index
+    Update { index: u32, item: MemberListItem },
+    /// Removes the item at the index, moving the following items up
+    Delete { index: u32 },
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
+/// An item of a member list, either the header of a group or one of its members.
+pub enum MemberListItem {
+    Group(MemberListGroup),
+    Member(MemberListMember),
+}
+
+#[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq)]
+/// A member in a member list, along with their presence.
+pub struct MemberListMember {
+    #[serde(flatten)]
+    pub member: GuildMember,
+    #[serde(default)]
+    pub presence: Option<PresenceUpdate>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+/// A synced copy of the subscribed part of a guild's member list.
+///
+/// Built by applying [GuildMemberListUpdate]s, see [MemberList::apply].
+///
+/// Usually obtained from
+/// [GatewayHandle::subscribe_member_list](crate::gateway::GatewayHandle::subscribe_member_list),
+/// which keeps it up to date.
+pub struct MemberList {
+    pub guild_id: Snowflake,
+    /// The id of the member list, see [GuildMemberListUpdate::id]
+    pub id: Option<String>,
+    pub member_count: u32,
+    pub online_count: u32,
+    pub groups: Vec<MemberListGroup>,
+    /// The items of the list, by their index.
+    ///
+    /// Items outside the subscribed ranges are not known and are [None].
+    pub items: Vec<Option<MemberListItem>>,
+}
+
+impl MemberList {
+    /// Creates an empty member list for a guild
+    pub fn new(guild_id: Snowflake) -> MemberList {
+        MemberList {
+            guild_id,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the known members in the list, in order
+    pub fn members(&self) -> impl Iterator<Item = &MemberListMember> {
+        self.items.iter().filter_map(|item| match item {
+            Some(MemberListItem::Member(member)) => Some(member),
+            _ => None,
+        })
+    }
+
+    /// Applies an update to the list.
+    ///
+    /// Updates for other guilds are ignored.
+    pub fn apply(&mut self, update: &GuildMemberListUpdate) {
+        if update.guild_id != self.guild_id {
+            return;
+        }
+
+        self.id = Some(update.id.clone());
+        self.member_count = update.member_count;
+        self.online_count = update.online_count;
+        self.groups = update.groups.clone();
+
+        for operation in update.ops.iter() {
+            self.apply_operation(operation);
+        }
+
+        // Don't keep around unknown items past the end of the known list
+        while let Some(None) = self.items.last() {
+            self.items.pop();
+        }
+    }
+
+    fn apply_operation(&mut self, operation: &MemberListOperation) {
+        match operation {
+            MemberListOperation::Sync { range, items } => {
+                self.invalidate(*range);
+
+                let start = range.0 as usize;
+                self.grow_to(start + items.len());
+                for (offset, item) in items.iter().enumerate() {
+                    self.items[start + offset] = Some(item.clone());
+                }
+            }
+            MemberListOperation::Invalidate { range } => self.invalidate(*range),
+            MemberListOperation::Insert { index, item } => {
+                let index = *index as usize;
+                self.grow_to(index);
+                self.items.insert(index, Some(item.clone()));
+            }
+            MemberListOperation::Update { index, item } => {
+                let index = *index as usize;
+                self.grow_to(index + 1);
+                self.items[index] = Some(item.clone());
+            }
+            MemberListOperation::Delete { index } => {
+                let index = *index as usize;
+                if index < self.items.len() {
+                    self.items.remove(index);
+                }
+            }
+        }
+    }
+
+    /// Marks the items in the (inclusive) range as unknown
+    fn invalidate(&mut self, range: (u32, u32)) {
+        let end = (range.1 as usize + 1).min(self.items.len());
+        for index in range.0 as usize..end {
+            self.items[index] = None;
+        }
+    }
+
+    /// Makes sure the list has at least `len` items
+    fn grow_to(&mut self, len: usize) {
+        if self.items.len() < len {
+            self.items.resize(len, None);
+        }
+    }
+}
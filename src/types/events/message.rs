@@ -2,6 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::types::{
@@ -15,13 +16,46 @@ use chorus_macros::WebSocketEvent;
 /// # Reference
 /// See <https://discord.com/developers/docs/topics/gateway-events#typing-start>
 pub struct TypingStartEvent {
+    /// The channel the user started typing in
     pub channel_id: Snowflake,
+    /// The guild the channel belongs to, if it is a guild channel
     pub guild_id: Option<Snowflake>,
+    /// The user who started typing
     pub user_id: Snowflake,
+    /// Unix time (in seconds) of when the user started typing
     pub timestamp: i64,
+    /// The member who started typing, if this happened in a guild.
+    ///
+    /// Includes the member's user object, which can be used to display who is typing without
+    /// fetching the user.
     pub member: Option<GuildMember>,
 }
 
+impl TypingStartEvent {
+    /// Returns when the user started typing, or `None` if the timestamp is out of range.
+    pub fn started_at(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(self.timestamp, 0)
+    }
+
+    /// Returns the name the typing user should be displayed with, if the event contains
+    /// enough information to know it.
+    ///
+    /// This is the member's nickname, falling back to their username.
+    pub fn display_name(&self) -> Option<String> {
+        let member = self.member.as_ref()?;
+
+        if let Some(nick) = &member.nick {
+            return Some(nick.clone());
+        }
+
+        let user = member.user.as_ref()?;
+        #[cfg(feature = "client-core")]
+        let user = user.read().unwrap();
+
+        user.username.clone()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Default, Clone, WebSocketEvent)]
 /// See <https://discord.com/developers/docs/topics/gateway-events#message-create>
 pub struct MessageCreate {
@@ -94,6 +128,39 @@ pub struct MessageDeleteBulk {
     pub guild_id: Option<Snowflake>,
 }
 
+#[derive(Debug, Clone, WebSocketEvent)]
+/// A [MessageUpdate], together with the version of the message which was cached before it.
+///
+/// Only published if the message cache is enabled, see
+/// [GatewayOptions::message_cache_size](crate::gateway::GatewayOptions::message_cache_size).
+pub struct MessageUpdateCached {
+    /// The message before the update, if it was cached
+    pub cached: Option<Message>,
+    pub raw: MessageUpdate,
+}
+
+#[derive(Debug, Clone, WebSocketEvent)]
+/// A [MessageDelete], together with the deleted message if it was cached.
+///
+/// Only published if the message cache is enabled, see
+/// [GatewayOptions::message_cache_size](crate::gateway::GatewayOptions::message_cache_size).
+pub struct MessageDeleteCached {
+    /// The deleted message, if it was cached
+    pub cached: Option<Message>,
+    pub raw: MessageDelete,
+}
+
+#[derive(Debug, Clone, WebSocketEvent)]
+/// A [MessageDeleteBulk], together with the deleted messages which were cached.
+///
+/// Only published if the message cache is enabled, see
+/// [GatewayOptions::message_cache_size](crate::gateway::GatewayOptions::message_cache_size).
+pub struct MessageDeleteBulkCached {
+    /// The deleted messages which were cached; may be fewer than were deleted
+    pub cached: Vec<Message>,
+    pub raw: MessageDeleteBulk,
+}
+
 #[derive(Debug, Serialize, Deserialize, Default, Clone, WebSocketEvent)]
 /// # Reference
 /// See <https://discord.com/developers/docs/topics/gateway-events#message-reaction-add>
@@ -149,6 +216,36 @@ pub struct MessageReactionRemoveEmoji {
     pub emoji: Emoji,
 }
 
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, WebSocketEvent)]
+/// Sent when a user votes for an answer of a poll.
+///
+/// If the poll allows multiple answers, one event is sent per answer.
+///
+/// # Reference
+/// See <https://docs.discord.sex/topics/gateway-events#message-poll-vote-add>
+pub struct MessagePollVoteAdd {
+    pub user_id: Snowflake,
+    pub channel_id: Snowflake,
+    pub message_id: Snowflake,
+    pub guild_id: Option<Snowflake>,
+    /// The [PollAnswer::answer_id](crate::types::PollAnswer::answer_id) which was voted for
+    pub answer_id: u8,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, WebSocketEvent)]
+/// Sent when a user removes their vote for an answer of a poll.
+///
+/// # Reference
+/// See <https://docs.discord.sex/topics/gateway-events#message-poll-vote-remove>
+pub struct MessagePollVoteRemove {
+    pub user_id: Snowflake,
+    pub channel_id: Snowflake,
+    pub message_id: Snowflake,
+    pub guild_id: Option<Snowflake>,
+    /// The [PollAnswer::answer_id](crate::types::PollAnswer::answer_id) the vote was removed from
+    pub answer_id: u8,
+}
+
 #[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, WebSocketEvent)]
 /// Sent when a message that mentioned the current user in the last week is acknowledged and deleted.
 ///
@@ -161,22 +258,31 @@ pub struct RecentMentionDelete {
 #[derive(Debug, Deserialize, Serialize, Default, Clone, WebSocketEvent)]
 /// Officially Undocumented
 ///
-/// Not documented anywhere unofficially
+/// Sent when the current user acknowledges (marks as read) a message, including from other
+/// clients, so that all clients can keep their [ReadState](crate::types::ReadState) in sync.
 ///
-/// Apparently "Message ACK refers to marking a message as read for Discord's API." (<https://github.com/Rapptz/discord.py/issues/1851>)
-/// I suspect this is sent and received from the gateway to let clients on other devices know the user has read a message
+/// See [Message::acknowledge](crate::types::Message::acknowledge).
 ///
 /// {"t":"MESSAGE_ACK","s":3,"op":0,"d":{"version":52,"message_id":"1107236673638633472","last_viewed":null,"flags":null,"channel_id":"967363950217936897"}}
+///
+/// # Reference
+/// See <https://docs.discord.sex/topics/gateway-events#message-ack>
 pub struct MessageACK {
-    // No ideas. See 206933
+    /// The version of the read state, incremented with every change
     pub version: u32,
+    /// The id of the acknowledged message
     pub message_id: Snowflake,
-    /// This is an integer???
-    /// Not even unix, see '3070'???
+    /// When the channel was last viewed, in days since the Discord epoch
     pub last_viewed: Option<u64>,
-    /// What flags?
+    /// The read state's flags
     pub flags: Option<serde_json::Value>,
     pub channel_id: Snowflake,
+    /// Whether the acknowledgement was made manually, e.g. by marking a message as unread
+    #[serde(default)]
+    pub manual: Option<bool>,
+    /// The amount of unread mentions after a manual acknowledgement
+    #[serde(default)]
+    pub mention_count: Option<u32>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Default, Clone, WebSocketEvent)]
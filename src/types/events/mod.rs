@@ -17,18 +17,23 @@ pub use interaction::*;
 pub use invalid_session::*;
 pub use invite::*;
 pub use lazy_request::*;
+pub use member_list::*;
 pub use message::*;
 pub use mfa::*;
 pub use passive_update::*;
 pub use presence::*;
+pub use raw::*;
 pub use ready::*;
 pub use reconnect::*;
 pub use relationship::*;
 pub use request_members::*;
 pub use resume::*;
+pub use scope::*;
 pub use session::*;
 pub use stage_instance::*;
 pub use thread::*;
+#[cfg(feature = "client-core")]
+pub(crate) use update_fields::*;
 pub use user::*;
 pub use voice::*;
 pub use voice_gateway::*;
@@ -36,23 +41,20 @@ pub use webhooks::*;
 
 use chorus_macros::WebSocketEvent;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use super::Snowflake;
 
-#[cfg(feature = "client")]
-use crate::gateway::Updateable;
+#[cfg(feature = "client-core")]
+use crate::gateway::{PartialUpdate, Updateable};
 
-#[cfg(feature = "client")]
-use serde_json::{from_str, from_value, to_value, Value};
+#[cfg(feature = "client-core")]
+use std::borrow::Cow;
 
-#[cfg(feature = "client")]
-use std::collections::HashMap;
-
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::types::Shared;
 use std::fmt::Debug;
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use serde::de::DeserializeOwned;
 
 mod application;
@@ -68,18 +70,23 @@ mod interaction;
 mod invalid_session;
 mod invite;
 mod lazy_request;
+mod member_list;
 mod message;
 mod mfa;
 mod passive_update;
 mod presence;
+mod raw;
 mod ready;
 mod reconnect;
 mod relationship;
 mod request_members;
 mod resume;
+mod scope;
 mod session;
 mod stage_instance;
 mod thread;
+#[cfg(feature = "client-core")]
+mod update_fields;
 mod user;
 mod voice;
 mod webhooks;
@@ -125,7 +132,7 @@ pub struct GatewayReceivePayload<'a> {
 
 impl<'a> WebSocketEvent for GatewayReceivePayload<'a> {}
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 /// An [`UpdateMessage<T>`] represents a received Gateway Message which contains updated
 /// information for an [`Updateable`] of Type T.
 /// # Example:
@@ -138,38 +145,73 @@ impl<'a> WebSocketEvent for GatewayReceivePayload<'a> {}
 /// This would imply, that the [`WebSocketEvent`] "[`ChannelUpdate`]" contains new/updated information
 /// about a [`Channel`]. The update method describes how this new information will be turned into
 /// a [`Channel`] object.
-pub(crate) trait UpdateMessage<T>: Clone + JsonField + SourceUrlField
+pub(crate) trait UpdateMessage<T>: Clone + SourceUrlField
 where
     T: Updateable + Serialize + DeserializeOwned + Clone,
 {
-    fn update(&mut self, object_to_update: Shared<T>) {
-        update_object(self.get_json(), object_to_update)
-    }
+    /// Applies the event to the object it updates.
+    ///
+    /// `fields` are the names of the fields the event contained, as recorded by
+    /// [from_str_with_fields]; fields missing from the event should be kept as they are.
+    fn update(&mut self, object_to_update: Shared<T>, fields: &[Cow<str>]);
     #[cfg(not(tarpaulin_include))]
     fn id(&self) -> Option<Snowflake>;
 }
 
-pub(crate) trait JsonField: Clone {
-    fn set_json(&mut self, json: String);
-    fn get_json(&self) -> String;
-}
-
 pub trait SourceUrlField: Clone {
     fn set_source_url(&mut self, url: String);
     fn get_source_url(&self) -> String;
 }
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 /// Only applicable for events where the Update struct is the same as the Entity struct
-pub(crate) fn update_object(
-    value: String,
-    object: Shared<(impl Updateable + Serialize + DeserializeOwned + Clone)>,
-) {
-    let data_from_event: HashMap<String, Value> = from_str(&value).unwrap();
-    let mut original_data: HashMap<String, Value> =
-        from_value(to_value(object.clone()).unwrap()).unwrap();
-    for (updated_entry_key, updated_entry_value) in data_from_event.into_iter() {
-        original_data.insert(updated_entry_key.clone(), updated_entry_value);
+///
+/// The `fields` of `update` which were present in the event, as recorded by
+/// [from_str_with_fields], overwrite those of the object; all others are kept.
+pub(crate) fn update_object<T: PartialUpdate>(object: Shared<T>, update: T, fields: &[Cow<str>]) {
+    object.write().unwrap().update_fields(update, fields);
+}
+
+#[cfg(test)]
+#[cfg(feature = "client-core")]
+mod test {
+    use super::*;
+    use crate::types::{Channel, ChannelType, IntoShared, ThreadUpdate};
+
+    #[test]
+    fn update_object_keeps_missing_fields() {
+        let channel = Channel {
+            id: Snowflake(1),
+            name: Some("general".to_string()),
+            topic: Some("topic".to_string()),
+            position: Some(3),
+            channel_type: ChannelType::GuildText,
+            ..Default::default()
+        }
+        .into_shared();
+
+        let (update, fields) = from_str_with_fields::<Channel>(
+            r#"{"id": "1", "type": 5, "name": "renamed", "topic": null}"#,
+        )
+        .unwrap();
+        assert_eq!(fields, ["id", "type", "name", "topic"]);
+        update_object(channel.clone(), update, &fields);
+
+        let channel = channel.read().unwrap();
+        assert_eq!(channel.id, Snowflake(1));
+        assert_eq!(channel.name.as_deref(), Some("renamed"));
+        assert_eq!(channel.topic, None);
+        assert_eq!(channel.position, Some(3));
+        assert_eq!(channel.channel_type, ChannelType::GuildNews);
+    }
+
+    #[test]
+    fn fields_of_flattened_events_are_recorded() {
+        let (update, fields) =
+            from_str_with_fields::<ThreadUpdate>(r#"{"id": "2", "type": 11, "name": "thread"}"#)
+                .unwrap();
+
+        assert_eq!(update.thread.name.as_deref(), Some("thread"));
+        assert_eq!(fields, ["id", "type", "name"]);
     }
-    *object.write().unwrap() = from_value(to_value(original_data).unwrap()).unwrap();
 }
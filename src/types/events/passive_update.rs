@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 use super::{ChannelUnreadUpdateObject, WebSocketEvent};
 use crate::types::{GuildMember, Snowflake, VoiceState};
 
-#[derive(Debug, Deserialize, Serialize, Default, WebSocketEvent)]
+#[derive(Debug, Deserialize, Serialize, Default, Clone, WebSocketEvent)]
 /// Officially Undocumented
 ///
 /// Seems to be passively set to update the client on guild details (though, why not just send the update events?)
@@ -4,6 +4,7 @@
 
 use crate::types::{events::WebSocketEvent, UserStatus};
 use crate::types::{Activity, ClientStatusObject, PublicUser, Snowflake};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DefaultOnNull};
 
@@ -20,6 +21,66 @@ pub struct UpdatePresence {
     pub afk: bool,
 }
 
+#[derive(Debug, Default, Clone)]
+/// Builds an [UpdatePresence].
+///
+/// # Example
+/// ```rs
+/// let presence = PresenceBuilder::new()
+///     .status(UserStatus::Dnd)
+///     .activity(ActivityBuilder::new("Chorus", ActivityType::Playing).build())
+///     .build();
+///
+/// user.gateway()?.set_presence(presence).await?;
+/// ```
+pub struct PresenceBuilder {
+    presence: UpdatePresence,
+}
+
+impl PresenceBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn status(mut self, status: UserStatus) -> Self {
+        self.presence.status = status;
+        self
+    }
+
+    pub fn afk(mut self, afk: bool) -> Self {
+        self.presence.afk = afk;
+        self
+    }
+
+    /// Sets since when the client has been idle
+    pub fn idle_since(mut self, since: DateTime<Utc>) -> Self {
+        self.presence.since = Some(since.timestamp_millis().max(0) as u128);
+        self
+    }
+
+    /// Adds an activity; users can have multiple activities at once
+    pub fn activity(mut self, activity: Activity) -> Self {
+        self.presence.activities.push(activity);
+        self
+    }
+
+    /// Replaces all activities
+    pub fn activities(mut self, activities: Vec<Activity>) -> Self {
+        self.presence.activities = activities;
+        self
+    }
+
+    pub fn build(self) -> UpdatePresence {
+        self.presence
+    }
+}
+
+impl From<PresenceBuilder> for UpdatePresence {
+    fn from(value: PresenceBuilder) -> Self {
+        value.build()
+    }
+}
+
 #[serde_as]
 #[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq, WebSocketEvent)]
 /// Received to tell the client that a user updated their presence / status. If you are looking for
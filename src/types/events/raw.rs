@@ -0,0 +1,46 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use chorus_macros::WebSocketEvent;
+use serde::de::DeserializeOwned;
+use serde_json::value::RawValue;
+
+use crate::types::WebSocketEvent;
+
+#[derive(Debug, Clone, WebSocketEvent)]
+/// A dispatched gateway event (opcode 0), before chorus parses it.
+///
+/// Every dispatch is published as this, whether chorus knows about the event or not.
+pub struct RawGatewayDispatch {
+    /// The name of the event (the `t` field of the payload), e.g. `MESSAGE_CREATE`
+    pub event_name: String,
+    /// The event's data (the `d` field of the payload)
+    pub data: Box<RawValue>,
+}
+
+impl RawGatewayDispatch {
+    /// Deserializes the event's data.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> serde_json::Result<T> {
+        serde_json::from_str(self.data.get())
+    }
+}
+
+#[derive(Debug, Clone, WebSocketEvent)]
+/// A dispatched gateway event which chorus does not know about and which no custom event is
+/// registered for.
+///
+/// Lets you handle new server features before chorus supports them.
+pub struct UnknownEvent {
+    /// The name of the event (the `t` field of the payload)
+    pub event_name: String,
+    /// The event's data (the `d` field of the payload)
+    pub data: Box<RawValue>,
+}
+
+impl UnknownEvent {
+    /// Deserializes the event's data.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> serde_json::Result<T> {
+        serde_json::from_str(self.data.get())
+    }
+}
@@ -4,14 +4,13 @@
 
 use std::collections::HashMap;
 
-use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::types::entities::{Guild, User};
 use crate::types::events::{Session, WebSocketEvent};
 use crate::types::{
     Activity, Channel, ClientStatusObject, GuildMember, MfaAuthenticatorType, PresenceUpdate,
-    Relationship, Snowflake, UserSettings, VoiceState,
+    ReadState, Relationship, Snowflake, UserSettings, VoiceState,
 };
 use crate::{UInt32, UInt64, UInt8};
 
@@ -235,27 +234,3 @@ pub struct SupplementalGuild {
     /// Field not documented even unofficially
     pub embedded_activities: Vec<serde_json::Value>,
 }
-
-#[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-/// Not documented even unofficially. Information about this type is likely to be partially incorrect.
-pub struct ReadState {
-    pub entries: Vec<ReadStateEntry>,
-    pub partial: bool,
-    pub version: u32,
-}
-
-#[derive(
-    Debug, Deserialize, Serialize, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Copy,
-)]
-/// Not documented even unofficially. Information about this type is likely to be partially incorrect.
-pub struct ReadStateEntry {
-    /// Spacebar servers do not have flags in this entity at all (??)
-    pub flags: Option<u32>,
-    pub id: Snowflake,
-    pub last_message_id: Option<Snowflake>,
-    pub last_pin_timestamp: Option<DateTime<Utc>>,
-	 /// A value that is incremented each time the read state is read
-    pub last_viewed: Option<u32>,
-    // Temporary adding Option to fix Spacebar servers, they have mention count as a nullable
-    pub mention_count: Option<u64>,
-}
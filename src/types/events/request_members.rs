@@ -2,7 +2,9 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use crate::types::{events::WebSocketEvent, OneOrMoreSnowflakes};
+use crate::types::{
+    events::WebSocketEvent, GuildMember, OneOrMoreSnowflakes, PresenceUpdate, Snowflake,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Default, Serialize, WebSocketEvent, Clone)]
@@ -42,3 +44,40 @@ pub struct GatewayRequestGuildMembers {
 	 /// Up to 32 bytes. If you send a longer nonce, it will be ignored
     pub nonce: Option<String>,
 }
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// Which members to request with
+/// [GatewayHandle::request_members](crate::gateway::GatewayHandle::request_members).
+///
+/// Mirrors the fields of [GatewayRequestGuildMembers], without the guild id and nonce.
+///
+/// # Notes
+/// One of `query` or `user_ids` is required.
+///
+/// If `query` is set, `limit` is required (if requesting all members, set `limit` to 0)
+pub struct RequestGuildMembersQuery {
+    /// String that the username / nickname starts with, or an empty string for all members
+    pub query: Option<String>,
+    /// The user ids to request (0 - 100)
+    pub user_ids: Option<Vec<Snowflake>>,
+    /// Maximum number of members to send matching the query (0 - 100)
+    ///
+    /// Must be 0 with an empty query
+    pub limit: u8,
+    /// Whether to return the [Presence](crate::types::events::PresenceUpdate) of the matched
+    /// members
+    pub presences: bool,
+}
+
+#[derive(Debug, Default, Clone)]
+/// All [GuildMembersChunk](crate::types::GuildMembersChunk)s received for one request made with
+/// [GatewayHandle::request_members](crate::gateway::GatewayHandle::request_members), merged
+/// in order.
+pub struct RequestedGuildMembers {
+    pub guild_id: Snowflake,
+    pub members: Vec<GuildMember>,
+    /// The requested user ids which were not found in the guild
+    pub not_found: Vec<Snowflake>,
+    /// The presences of the members, if they were requested
+    pub presences: Vec<PresenceUpdate>,
+}
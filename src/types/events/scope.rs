@@ -0,0 +1,135 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+use crate::types::Snowflake;
+
+/// A gateway event which happened in a guild and / or a channel.
+///
+/// Used to only observe the events of a single guild or channel, see
+/// [FilteredObservers](crate::gateway::FilteredObservers).
+pub trait ScopedEvent: WebSocketEvent {
+    /// The id of the guild the event happened in, if it happened in one
+    fn guild_id(&self) -> Option<Snowflake>;
+
+    /// The id of the channel the event happened in, if it happened in one
+    fn channel_id(&self) -> Option<Snowflake> {
+        None
+    }
+}
+
+/// Implements [ScopedEvent] for events which have the given `guild_id` and `channel_id`
+/// expressions, with `$event` bound to the event.
+macro_rules! scoped_event {
+    ($($type:ty => |$event:ident| ($guild_id:expr, $channel_id:expr);)*) => {
+        $(
+            // The event is unused if one of the ids is always None
+            #[allow(unused_variables)]
+            impl ScopedEvent for $type {
+                fn guild_id(&self) -> Option<Snowflake> {
+                    let $event = self;
+                    $guild_id
+                }
+
+                fn channel_id(&self) -> Option<Snowflake> {
+                    let $event = self;
+                    $channel_id
+                }
+            }
+        )*
+    };
+}
+
+scoped_event! {
+    ApplicationCommandPermissionsUpdate => |e| (Some(e.permissions.guild_id), None);
+
+    AutoModerationRuleCreate => |e| (Some(e.rule.guild_id), None);
+    AutoModerationRuleUpdate => |e| (Some(e.rule.guild_id), None);
+    AutoModerationRuleDelete => |e| (Some(e.rule.guild_id), None);
+    AutoModerationActionExecution => |e| (Some(e.guild_id), e.channel_id);
+
+    CallCreate => |e| (None, Some(e.channel_id));
+    CallUpdate => |e| (None, Some(e.channel_id));
+    CallDelete => |e| (None, Some(e.channel_id));
+
+    ChannelCreate => |e| (e.channel.guild_id, Some(e.channel.id));
+    ChannelUpdate => |e| (e.channel.guild_id, Some(e.channel.id));
+    ChannelDelete => |e| (e.channel.guild_id, Some(e.channel.id));
+    ChannelPinsUpdate => |e| (e.guild_id, Some(e.channel_id));
+    ChannelUnreadUpdate => |e| (Some(e.guild_id), None);
+
+    GuildCreate => |e| (
+        match &e.d {
+            GuildCreateDataOption::UnavailableGuild(guild) => Some(guild.id),
+            GuildCreateDataOption::Guild(guild) => Some(guild.id),
+        },
+        None
+    );
+    GuildUpdate => |e| (Some(e.guild.id), None);
+    GuildDelete => |e| (Some(e.guild.id), None);
+    GuildBanAdd => |e| (Some(e.guild_id), None);
+    GuildBanRemove => |e| (Some(e.guild_id), None);
+    GuildEmojisUpdate => |e| (Some(e.guild_id), None);
+    GuildStickersUpdate => |e| (Some(e.guild_id), None);
+    GuildIntegrationsUpdate => |e| (Some(e.guild_id), None);
+    GuildMemberAdd => |e| (Some(e.guild_id), None);
+    GuildMemberRemove => |e| (Some(e.guild_id), None);
+    GuildMemberUpdate => |e| (Some(e.guild_id), None);
+    GuildMembersChunk => |e| (Some(e.guild_id), None);
+    GuildMemberListUpdate => |e| (Some(e.guild_id), None);
+    GuildRoleCreate => |e| (Some(e.guild_id), None);
+    GuildRoleUpdate => |e| (Some(e.guild_id), None);
+    GuildRoleDelete => |e| (Some(e.guild_id), None);
+    GuildScheduledEventCreate => |e| (Some(e.event.guild_id), e.event.channel_id);
+    GuildScheduledEventUpdate => |e| (Some(e.event.guild_id), e.event.channel_id);
+    GuildScheduledEventDelete => |e| (Some(e.event.guild_id), e.event.channel_id);
+    GuildScheduledEventUserAdd => |e| (Some(e.guild_id), None);
+    GuildScheduledEventUserRemove => |e| (Some(e.guild_id), None);
+    GuildApplicationCommandIndexUpdate => |e| (Some(e.guild_id), None);
+    GuildOnboardingPromptCreate => |e| (Some(e.guild_id), None);
+    GuildOnboardingPromptUpdate => |e| (Some(e.guild_id), None);
+    GuildOnboardingPromptDelete => |e| (Some(e.guild_id), None);
+    PassiveUpdateV1 => |e| (Some(e.guild_id), None);
+
+    IntegrationCreate => |e| (Some(e.guild_id), None);
+    IntegrationUpdate => |e| (Some(e.guild_id), None);
+    IntegrationDelete => |e| (Some(e.guild_id), None);
+
+    InteractionCreate => |e| (Some(e.interaction.guild_id), Some(e.interaction.channel_id));
+
+    InviteCreate => |e| (Some(e.invite.guild_id), Some(e.invite.channel_id));
+    InviteDelete => |e| (e.guild_id, Some(e.channel_id));
+
+    MessageCreate => |e| (e.guild_id, Some(e.message.channel_id));
+    MessageUpdate => |e| (e.guild_id, Some(e.message.channel_id));
+    MessageDelete => |e| (e.guild_id, Some(e.channel_id));
+    MessageDeleteBulk => |e| (e.guild_id, Some(e.channel_id));
+    MessageReactionAdd => |e| (e.guild_id, Some(e.channel_id));
+    MessageReactionRemove => |e| (e.guild_id, Some(e.channel_id));
+    MessageReactionRemoveAll => |e| (e.guild_id, Some(e.channel_id));
+    MessageReactionRemoveEmoji => |e| (e.guild_id, Some(e.channel_id));
+    MessagePollVoteAdd => |e| (e.guild_id, Some(e.channel_id));
+    MessagePollVoteRemove => |e| (e.guild_id, Some(e.channel_id));
+    MessageACK => |e| (None, Some(e.channel_id));
+    TypingStartEvent => |e| (e.guild_id, Some(e.channel_id));
+    LastMessages => |e| (Some(e.guild_id), None);
+
+    PresenceUpdate => |e| (e.guild_id, None);
+
+    StageInstanceCreate => |e| (Some(e.stage_instance.guild_id), Some(e.stage_instance.channel_id));
+    StageInstanceUpdate => |e| (Some(e.stage_instance.guild_id), Some(e.stage_instance.channel_id));
+    StageInstanceDelete => |e| (Some(e.stage_instance.guild_id), Some(e.stage_instance.channel_id));
+
+    ThreadCreate => |e| (e.thread.guild_id, Some(e.thread.id));
+    ThreadUpdate => |e| (e.thread.guild_id, Some(e.thread.id));
+    ThreadDelete => |e| (e.thread.guild_id, Some(e.thread.id));
+    ThreadListSync => |e| (Some(e.guild_id), None);
+    ThreadMemberUpdate => |e| (Some(e.guild_id), e.member.id);
+    ThreadMembersUpdate => |e| (Some(e.guild_id), Some(e.id));
+
+    VoiceStateUpdate => |e| (e.state.guild_id, e.state.channel_id);
+    VoiceServerUpdate => |e| (e.guild_id, e.channel_id);
+
+    WebhooksUpdate => |e| (Some(e.guild_id), Some(e.channel_id));
+}
@@ -2,15 +2,19 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use chorus_macros::{JsonField, SourceUrlField};
+use chorus_macros::SourceUrlField;
 use serde::{Deserialize, Serialize};
 
 use crate::types::entities::{Channel, ThreadMember};
 use crate::types::events::WebSocketEvent;
-use crate::types::{JsonField, Snowflake, SourceUrlField};
+use crate::types::{Snowflake, SourceUrlField};
 
-#[cfg(feature = "client")]
-use super::UpdateMessage;
+#[cfg(feature = "client-core")]
+use super::{update_object, UpdateMessage};
+#[cfg(feature = "client-core")]
+use crate::types::Shared;
+#[cfg(feature = "client-core")]
+use std::borrow::Cow;
 
 #[derive(Debug, Default, Deserialize, Serialize, Clone, WebSocketEvent)]
 /// See <https://discord.com/developers/docs/topics/gateway-events#thread-create>
@@ -19,19 +23,21 @@ pub struct ThreadCreate {
     pub thread: Channel,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, Clone, JsonField, SourceUrlField, WebSocketEvent)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone, SourceUrlField, WebSocketEvent)]
 /// See <https://discord.com/developers/docs/topics/gateway-events#thread-update>
 pub struct ThreadUpdate {
     #[serde(flatten)]
     pub thread: Channel,
     #[serde(skip)]
-    pub json: String,
-    #[serde(skip)]
     pub source_url: String,
 }
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 impl UpdateMessage<Channel> for ThreadUpdate {
+    fn update(&mut self, object_to_update: Shared<Channel>, fields: &[Cow<str>]) {
+        update_object(object_to_update, self.thread.clone(), fields)
+    }
+
     #[cfg(not(tarpaulin_include))]
     fn id(&self) -> Option<Snowflake> {
         Some(self.thread.id)
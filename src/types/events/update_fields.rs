@@ -0,0 +1,164 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Recording which fields an update event contains, see [from_str_with_fields].
+
+use std::borrow::Cow;
+use std::fmt;
+
+use serde::de::{DeserializeSeed, Deserializer, IntoDeserializer, MapAccess, Visitor};
+use serde::Deserialize;
+
+/// Deserializes an event from `json`, along with the names of the top level fields it contains.
+///
+/// The names are recorded while deserializing, so the event is only parsed once. They tell
+/// [update_object](super::update_object) which fields of the event are actual updates, and which
+/// were only filled with their default values.
+pub(crate) fn from_str_with_fields<'a, T: Deserialize<'a>>(
+    json: &'a str,
+) -> serde_json::Result<(T, Vec<Cow<'a, str>>)> {
+    let mut fields = Vec::new();
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+
+    let value = T::deserialize(FieldRecorder {
+        inner: &mut deserializer,
+        recorded: &mut fields,
+    })?;
+    deserializer.end()?;
+
+    Ok((value, fields))
+}
+
+/// A [Deserializer] which records the keys of the map it deserializes
+struct FieldRecorder<'r, 'de, D> {
+    inner: D,
+    recorded: &'r mut Vec<Cow<'de, str>>,
+}
+
+impl<'r, 'de, D: Deserializer<'de>> Deserializer<'de> for FieldRecorder<'r, 'de, D> {
+    type Error = D::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.inner.deserialize_any(RecordingVisitor {
+            inner: visitor,
+            recorded: self.recorded,
+        })
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.inner.deserialize_map(RecordingVisitor {
+            inner: visitor,
+            recorded: self.recorded,
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.inner.deserialize_struct(
+            name,
+            fields,
+            RecordingVisitor {
+                inner: visitor,
+                recorded: self.recorded,
+            },
+        )
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct enum identifier ignored_any
+    }
+}
+
+/// Wraps the [MapAccess] a visitor is given in a [RecordingMapAccess]
+struct RecordingVisitor<'r, 'de, V> {
+    inner: V,
+    recorded: &'r mut Vec<Cow<'de, str>>,
+}
+
+impl<'r, 'de, V: Visitor<'de>> Visitor<'de> for RecordingVisitor<'r, 'de, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.inner.expecting(formatter)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        self.inner.visit_map(RecordingMapAccess {
+            inner: map,
+            recorded: self.recorded,
+        })
+    }
+}
+
+/// A [MapAccess] which records every key before handing it to the visitor
+struct RecordingMapAccess<'r, 'de, A> {
+    inner: A,
+    recorded: &'r mut Vec<Cow<'de, str>>,
+}
+
+impl<'r, 'de, A: MapAccess<'de>> MapAccess<'de> for RecordingMapAccess<'r, 'de, A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let Some(FieldName(key)) = self.inner.next_key()? else {
+            return Ok(None);
+        };
+
+        let value = seed.deserialize(IntoDeserializer::<Self::Error>::into_deserializer(
+            key.as_ref(),
+        ))?;
+        self.recorded.push(key);
+        Ok(Some(value))
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.inner.next_value_seed(seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+/// A map key, which borrows from the input if it can
+struct FieldName<'de>(Cow<'de, str>);
+
+impl<'de> Deserialize<'de> for FieldName<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldNameVisitor;
+
+        impl<'de> Visitor<'de> for FieldNameVisitor {
+            type Value = FieldName<'de>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a field name")
+            }
+
+            fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E> {
+                Ok(FieldName(Cow::Borrowed(v)))
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(FieldName(Cow::Owned(v.to_string())))
+            }
+
+            fn visit_string<E>(self, v: String) -> Result<Self::Value, E> {
+                Ok(FieldName(Cow::Owned(v)))
+            }
+        }
+
+        deserializer.deserialize_str(FieldNameVisitor)
+    }
+}
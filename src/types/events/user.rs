@@ -7,7 +7,10 @@ use serde::{Deserialize, Serialize};
 use crate::types::entities::PublicUser;
 use crate::types::events::WebSocketEvent;
 use crate::types::utils::Snowflake;
-use crate::types::Connection;
+use crate::types::{
+    Connection, PremiumGuildSubscriptionSlot, UserGuildSettings, UserModifySettingsSchema,
+    UserSettingsProtoType,
+};
 
 #[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq, WebSocketEvent)]
 /// See <https://discord.com/developers/docs/topics/gateway-events#user-update>;
@@ -26,6 +29,24 @@ pub struct UserConnectionsUpdate {
     pub connection: Connection,
 }
 
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq, WebSocketEvent)]
+/// See <https://docs.discord.sex/topics/gateway-events#user-premium-guild-subscription-slot-create>;
+///
+/// Sent when the current user gets a new guild boost slot.
+pub struct UserPremiumGuildSubscriptionSlotCreate {
+    #[serde(flatten)]
+    pub slot: PremiumGuildSubscriptionSlot,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq, WebSocketEvent)]
+/// See <https://docs.discord.sex/topics/gateway-events#user-premium-guild-subscription-slot-update>;
+///
+/// Sent when one of the current user's guild boost slots is used, canceled or goes off cooldown.
+pub struct UserPremiumGuildSubscriptionSlotUpdate {
+    #[serde(flatten)]
+    pub slot: PremiumGuildSubscriptionSlot,
+}
+
 #[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq, WebSocketEvent)]
 /// See <https://docs.discord.sex/topics/gateway-events#user-note-update-structure>;
 ///
@@ -39,40 +60,41 @@ pub struct UserNoteUpdate {
 }
 
 #[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq, WebSocketEvent)]
-/// Undocumented;
-///
-/// Possibly an update for muted guild / channel settings for the current user;
+/// See <https://docs.discord.sex/topics/gateway-events#user-guild-settings-update>;
 ///
-/// Ex: {"version":2,"suppress_roles":false,"suppress_everyone":false,"notify_highlights":0,"muted":false,"mute_scheduled_events":false,"mute_config":null,"mobile_push":true,"message_notifications":1,"hide_muted_channels":false,"guild_id":"848582562217590824","flags":0,"channel_overrides":[{"muted":false,"mute_config":null,"message_notifications":3,"flags":4096,"collapsed":false,"channel_id":"1042689182893604885"}]}
+/// Sent when the current user's notification settings for a guild change, e.g. when it is muted.
 pub struct UserGuildSettingsUpdate {
-    pub version: u8,
-    pub suppress_roles: bool,
-    pub suppress_everyone: bool,
-    pub notify_highlights: u8,
-    pub muted: bool,
-    pub mute_scheduled_events: bool,
-    /// ??
-    pub mute_config: Option<serde_json::Value>,
-    pub mobile_push: bool,
-    pub message_notifications: u8,
-    pub hide_muted_channels: bool,
-    pub guild_id: Snowflake,
-    pub flags: i32,
-    pub channel_overrides: Vec<UserGuildSettingsChannelOverride>,
+    #[serde(flatten)]
+    pub settings: UserGuildSettings,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
-/// Undocumented;
+#[derive(Debug, Default, Deserialize, Serialize, Clone, WebSocketEvent)]
+/// See <https://docs.discord.sex/topics/gateway-events#user-settings-update>;
+///
+/// Sent when the current user's settings change; only contains the changed settings.
 ///
-/// Received in [UserGuildSettingsUpdate];
+/// Applied to [ChorusUser::settings](crate::instance::ChorusUser::settings) automatically.
+pub struct UserSettingsUpdate {
+    #[serde(flatten)]
+    pub settings: UserModifySettingsSchema,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq, WebSocketEvent)]
+/// See <https://docs.discord.sex/topics/gateway-events#user-settings-proto-update>;
 ///
-/// Ex: {"muted":false,"mute_config":null,"message_notifications":3,"flags":4096,"collapsed":false,"channel_id":"1042689182893604885"}
-pub struct UserGuildSettingsChannelOverride {
-    pub muted: bool,
-    /// ??
-    pub mute_config: Option<serde_json::Value>,
-    pub message_notifications: u8,
-    pub flags: i32,
-    pub collapsed: bool,
-    pub channel_id: Snowflake,
+/// Sent when one of the current user's settings protos changes.
+pub struct UserSettingsProtoUpdate {
+    pub settings: UserSettingsProto,
+    /// Whether the proto only contains the changed settings, and should be merged into the
+    /// existing ones
+    pub partial: bool,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+/// A settings proto, as sent in [UserSettingsProtoUpdate].
+pub struct UserSettingsProto {
+    /// The base-64 encoded protobuf of the settings
+    pub proto: String,
+    #[serde(rename = "type")]
+    pub proto_type: UserSettingsProtoType,
 }
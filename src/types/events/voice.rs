@@ -41,3 +41,19 @@ pub struct VoiceServerUpdate {
     pub endpoint: Option<String>,
 }
 
+impl VoiceServerUpdate {
+    /// Returns the id to connect to the voice server with; the guild id for guild voice
+    /// channels, or the channel id for DM and group DM calls.
+    ///
+    /// See [VoiceIdentify::server_id](crate::types::VoiceIdentify::server_id)
+    pub fn server_id(&self) -> Option<Snowflake> {
+        self.guild_id.or(self.channel_id)
+    }
+
+    /// Returns whether this update is for a DM or group DM call, rather than a guild voice
+    /// channel.
+    pub fn is_private_call(&self) -> bool {
+        self.guild_id.is_none() && self.channel_id.is_some()
+    }
+}
+
@@ -0,0 +1,109 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Types for the DAVE end-to-end encryption protocol.
+//!
+//! Chorus does not implement DAVE yet; it declines it when identifying (see
+//! [VoiceIdentify::max_dave_protocol_version](super::VoiceIdentify::max_dave_protocol_version))
+//! and only follows transitions back to unencrypted calls.
+//!
+//! See <https://daveprotocol.com/> and
+//! <https://docs.discord.sex/topics/voice-connections#end-to-end-encryption-dave-protocol>
+
+use crate::types::WebSocketEvent;
+use chorus_macros::WebSocketEvent;
+use serde::{Deserialize, Serialize};
+
+/// The DAVE protocol version of calls which are not end-to-end encrypted.
+pub const DAVE_PROTOCOL_VERSION_NONE: u16 = 0;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq, WebSocketEvent)]
+/// Sent by the server when the call is about to transition to a different DAVE protocol
+/// version, e.g. because a member who does not support DAVE joined.
+///
+/// The client should answer with [DaveReadyForTransition] once it is ready.
+///
+/// See <https://docs.discord.sex/topics/voice-connections#dave-prepare-transition-structure>
+pub struct DavePrepareTransition {
+    /// The protocol version the call transitions to, [DAVE_PROTOCOL_VERSION_NONE] if it is
+    /// downgraded to not be end-to-end encrypted
+    pub protocol_version: u16,
+    pub transition_id: u16,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq, WebSocketEvent)]
+/// Sent by the server once the transition with the given id should be executed, i.e. media
+/// should be sent with the new protocol version from now on.
+///
+/// See <https://docs.discord.sex/topics/voice-connections#dave-execute-transition-structure>
+pub struct DaveExecuteTransition {
+    pub transition_id: u16,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq, WebSocketEvent)]
+/// Sent by the client once it is ready to execute a transition, see [DavePrepareTransition].
+///
+/// See <https://docs.discord.sex/topics/voice-connections#dave-ready-for-transition-structure>
+pub struct DaveReadyForTransition {
+    pub transition_id: u16,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq, WebSocketEvent)]
+/// Sent by the server when a new MLS group is about to be created, or when the protocol
+/// version of the call is about to be upgraded.
+///
+/// See <https://docs.discord.sex/topics/voice-connections#dave-prepare-epoch-structure>
+pub struct DavePrepareEpoch {
+    pub protocol_version: u16,
+    /// The MLS epoch; `1` means a new group is created
+    pub epoch: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq, WebSocketEvent)]
+/// Sent by the client when it received a commit or welcome it could not process, which
+/// makes the server remove and re-add it to the MLS group.
+///
+/// See <https://docs.discord.sex/topics/voice-connections#dave-mls-invalid-commit-welcome-structure>
+pub struct DaveMlsInvalidCommitWelcome {
+    pub transition_id: u16,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, WebSocketEvent)]
+/// A binary DAVE MLS message (opcodes 25 to 30), which is sent as a binary websocket frame
+/// instead of json.
+///
+/// The payload is passed through as is, since chorus does not implement MLS.
+///
+/// See <https://docs.discord.sex/topics/voice-connections#binary-websocket-messages>
+pub struct DaveMlsMessage {
+    /// The sequence number of the message; only set for messages sent by the server
+    pub sequence_number: Option<u16>,
+    pub op_code: u8,
+    pub payload: Vec<u8>,
+}
+
+impl DaveMlsMessage {
+    /// Parses a binary message received from the server, which is prefixed with a big endian
+    /// sequence number and the opcode.
+    ///
+    /// Returns `None` if the message is too short.
+    pub fn from_server_bytes(bytes: &[u8]) -> Option<DaveMlsMessage> {
+        let (header, payload) = (bytes.get(0..3)?, &bytes[3..]);
+
+        Some(DaveMlsMessage {
+            sequence_number: Some(u16::from_be_bytes([header[0], header[1]])),
+            op_code: header[2],
+            payload: payload.to_vec(),
+        })
+    }
+
+    /// Serializes the message to be sent by the client, which is only prefixed with the
+    /// opcode.
+    pub fn to_client_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(1 + self.payload.len());
+        bytes.push(self.op_code);
+        bytes.extend_from_slice(&self.payload);
+        bytes
+    }
+}
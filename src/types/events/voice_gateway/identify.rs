@@ -20,6 +20,12 @@ pub struct VoiceIdentify {
     pub token: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub video: Option<bool>,
+    /// The highest DAVE end-to-end encryption protocol version we support.
+    ///
+    /// Chorus does not implement DAVE yet, so this should be
+    /// [DAVE_PROTOCOL_VERSION_NONE](crate::types::DAVE_PROTOCOL_VERSION_NONE).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_dave_protocol_version: Option<u16>,
     // TODO: Add video streams
 }
 
@@ -8,6 +8,7 @@ use serde_json::{value::RawValue, Value};
 
 pub use client_connect::*;
 pub use client_disconnect::*;
+pub use dave::*;
 pub use hello::*;
 pub use identify::*;
 pub use media_sink_wants::*;
@@ -20,6 +21,7 @@ pub use voice_backend_version::*;
 
 mod client_connect;
 mod client_disconnect;
+mod dave;
 mod hello;
 mod identify;
 mod media_sink_wants;
@@ -138,6 +140,31 @@ pub enum VideoCodec {
     H264,
 }
 
+impl VideoCodec {
+    /// Returns the rtp payload type the codec's packets are sent with.
+    ///
+    /// These are the payload types the official client negotiates in [SelectProtocol].
+    pub fn payload_type(&self) -> u8 {
+        match self {
+            VideoCodec::H264 => 101,
+            VideoCodec::VP8 => 103,
+            VideoCodec::VP9 => 105,
+        }
+    }
+
+    /// Returns the rtp payload type of the codec's retransmission packets.
+    pub fn rtx_payload_type(&self) -> u8 {
+        self.payload_type() + 1
+    }
+
+    /// Returns the codec an rtp payload type belongs to, see [VideoCodec::payload_type].
+    pub fn from_payload_type(payload_type: u8) -> Option<VideoCodec> {
+        [VideoCodec::H264, VideoCodec::VP8, VideoCodec::VP9]
+            .into_iter()
+            .find(|codec| codec.payload_type() == payload_type)
+    }
+}
+
 // The various voice opcodes
 pub const VOICE_IDENTIFY: u8 = 0;
 pub const VOICE_SELECT_PROTOCOL: u8 = 1;
@@ -149,6 +176,7 @@ pub const VOICE_HEARTBEAT_ACK: u8 = 6;
 pub const VOICE_RESUME: u8 = 7;
 pub const VOICE_HELLO: u8 = 8;
 pub const VOICE_RESUMED: u8 = 9;
+/// Also called "Video"; announces the audio and video ssrcs of a user, see [SsrcDefinition]
 pub const VOICE_SSRC_DEFINITION: u8 = 12;
 pub const VOICE_CLIENT_DISCONNECT: u8 = 13;
 pub const VOICE_SESSION_UPDATE: u8 = 14;
@@ -166,3 +194,22 @@ pub const VOICE_BACKEND_VERSION: u8 = 16;
 // These two get simultaenously fired when a user joins, one has flags and one has a platform
 pub const VOICE_CLIENT_CONNECT_FLAGS: u8 = 18;
 pub const VOICE_CLIENT_CONNECT_PLATFORM: u8 = 20;
+
+// The DAVE end-to-end encryption opcodes, see [DavePrepareTransition] and the related types
+/// See [DavePrepareTransition]
+pub const VOICE_DAVE_PREPARE_TRANSITION: u8 = 21;
+/// See [DaveExecuteTransition]
+pub const VOICE_DAVE_EXECUTE_TRANSITION: u8 = 22;
+/// See [DaveReadyForTransition]
+pub const VOICE_DAVE_READY_FOR_TRANSITION: u8 = 23;
+/// See [DavePrepareEpoch]
+pub const VOICE_DAVE_PREPARE_EPOCH: u8 = 24;
+// Opcodes 25 to 30 are sent as binary messages, see [DaveMlsMessage]
+pub const VOICE_DAVE_MLS_EXTERNAL_SENDER: u8 = 25;
+pub const VOICE_DAVE_MLS_KEY_PACKAGE: u8 = 26;
+pub const VOICE_DAVE_MLS_PROPOSALS: u8 = 27;
+pub const VOICE_DAVE_MLS_COMMIT_WELCOME: u8 = 28;
+pub const VOICE_DAVE_MLS_ANNOUNCE_COMMIT_TRANSITION: u8 = 29;
+pub const VOICE_DAVE_MLS_WELCOME: u8 = 30;
+/// See [DaveMlsInvalidCommitWelcome]
+pub const VOICE_DAVE_MLS_INVALID_COMMIT_WELCOME: u8 = 31;
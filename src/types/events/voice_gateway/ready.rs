@@ -2,13 +2,13 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use std::net::Ipv4Addr;
+use std::net::{IpAddr, Ipv4Addr};
 
 use crate::types::WebSocketEvent;
 use chorus_macros::WebSocketEvent;
 use serde::{Deserialize, Serialize};
 
-use super::VoiceEncryptionMode;
+use super::{VideoStream, VoiceEncryptionMode};
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, WebSocketEvent)]
 /// The voice gateway's ready event;
@@ -22,13 +22,16 @@ use super::VoiceEncryptionMode;
 pub struct VoiceReady {
     /// See <https://developer.mozilla.org/en-US/docs/Web/API/RTCRtpStreamStats/ssrc>
     pub ssrc: u32,
-    pub ip: Ipv4Addr,
+    /// The address of the voice server's UDP endpoint, which can be an IPv4 or IPv6 address
+    pub ip: IpAddr,
     pub port: u16,
     /// The available encryption modes for the UDP connection
     pub modes: Vec<VoiceEncryptionMode>,
     #[serde(default)]
     pub experiments: Vec<String>,
-    // TODO: Add video streams
+    /// The video streams allocated for us to send, with their ssrcs
+    #[serde(default)]
+    pub streams: Vec<VideoStream>,
     // Heartbeat interval is also sent, but is "an erroneous field and should be ignored. The correct heartbeat_interval value comes from the Hello payload."
 }
 
@@ -36,10 +39,11 @@ impl Default for VoiceReady {
     fn default() -> Self {
         VoiceReady {
             ssrc: 1,
-            ip: Ipv4Addr::UNSPECIFIED,
+            ip: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
             port: 0,
             modes: Vec::new(),
             experiments: Vec::new(),
+            streams: Vec::new(),
         }
     }
 }
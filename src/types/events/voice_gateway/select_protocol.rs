@@ -4,7 +4,7 @@
 
 use serde::{Deserialize, Serialize};
 
-use super::VoiceEncryptionMode;
+use super::{AudioCodec, VideoCodec, VoiceEncryptionMode};
 
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
 /// An event sent by the client to the voice gateway server,
@@ -19,7 +19,10 @@ pub struct SelectProtocol {
     ///
     /// Note: Not recommended to set this
     pub rtc_connection_id: Option<String>,
-    // TODO: Add codecs, what is a codec object
+    /// The codecs we support
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub codecs: Vec<VoiceCodec>,
     /// The possible experiments we want to enable
     #[serde(rename = "experiments")]
     pub enabled_experiments: Vec<String>,
@@ -50,3 +53,63 @@ pub struct SelectProtocolData {
     /// The mode of encryption to use
     pub mode: VoiceEncryptionMode,
 }
+
+/// A codec we support, sent in [SelectProtocol]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct VoiceCodec {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub codec_type: VoiceCodecType,
+    /// The priority of the codec, higher is preferred
+    pub priority: u16,
+    /// The rtp payload type we send the codec's packets with
+    pub payload_type: u8,
+    /// The rtp payload type of retransmissions, for video codecs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rtx_payload_type: Option<u8>,
+}
+
+impl VoiceCodec {
+    /// Returns the codec object for an audio codec
+    pub fn audio(codec: AudioCodec) -> Self {
+        match codec {
+            AudioCodec::Opus => Self {
+                name: "opus".to_string(),
+                codec_type: VoiceCodecType::Audio,
+                priority: 1000,
+                payload_type: 120,
+                rtx_payload_type: None,
+            },
+        }
+    }
+
+    /// Returns the codec object for a video codec
+    pub fn video(codec: VideoCodec) -> Self {
+        Self {
+            name: format!("{:?}", codec),
+            codec_type: VoiceCodecType::Video,
+            priority: 1000,
+            payload_type: codec.payload_type(),
+            rtx_payload_type: Some(codec.rtx_payload_type()),
+        }
+    }
+
+    /// Returns the codec objects for all codecs chorus can send and receive
+    pub fn supported() -> Vec<Self> {
+        vec![
+            Self::audio(AudioCodec::Opus),
+            Self::video(VideoCodec::H264),
+            Self::video(VideoCodec::VP8),
+            Self::video(VideoCodec::VP9),
+        ]
+    }
+}
+
+/// Whether a [VoiceCodec] is for audio or video
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VoiceCodecType {
+    #[default]
+    Audio,
+    Video,
+}
@@ -18,6 +18,10 @@ pub struct SessionDescription {
     pub secret_key: [u8; 32],
     /// The keyframe interval in milliseconds
     pub keyframe_interval: Option<u64>,
+    /// The DAVE end-to-end encryption protocol version of the call, `0` if the call is not
+    /// end-to-end encrypted
+    #[serde(default)]
+    pub dave_protocol_version: Option<u16>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, Default, WebSocketEvent)]
@@ -46,8 +46,84 @@ pub struct SsrcDefinition {
     /// Is never sent by the user and is filled in by the server
     #[serde(skip_serializing)]
     pub user_id: Option<Snowflake>,
-    // TODO: Add video streams
+    /// The video streams these ssrcs apply to
     #[serde(default)]
-    pub streams: Vec<String>,
+    pub streams: Vec<VideoStream>,
+}
+
+/// A video stream of a voice connection, sent in [SsrcDefinition] and
+/// [VoiceReady](super::VoiceReady).
+///
+/// See <https://discord-userdoccers.vercel.app/topics/voice-connections#stream-structure>
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct VideoStream {
+    #[serde(rename = "type")]
+    #[serde(default)]
+    pub stream_type: VideoStreamType,
+    /// The rtp stream id, used to tell apart streams of different qualities
+    pub rid: String,
+    /// The ssrc of the stream
+    #[serde(default)]
+    pub ssrc: u32,
+    /// The ssrc of the retransmission stream
+    #[serde(default)]
+    pub rtx_ssrc: u32,
+    /// Whether the stream is currently being sent
+    #[serde(default)]
+    pub active: bool,
+    /// The quality of the stream, from 0 to 100
+    #[serde(default)]
+    pub quality: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_bitrate: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_framerate: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_resolution: Option<VideoResolution>,
+}
+
+impl Default for VideoStream {
+    fn default() -> Self {
+        Self {
+            stream_type: VideoStreamType::default(),
+            rid: "100".to_string(),
+            ssrc: 0,
+            rtx_ssrc: 0,
+            active: false,
+            quality: 100,
+            max_bitrate: None,
+            max_framerate: None,
+            max_resolution: None,
+        }
+    }
+}
+
+/// The kind of a [VideoStream]
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VideoStreamType {
+    #[default]
+    /// A camera stream
+    Video,
+    /// A screenshare
+    Screen,
+}
+
+/// The maximum resolution of a [VideoStream]
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct VideoResolution {
+    #[serde(rename = "type")]
+    pub resolution_type: VideoResolutionType,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Whether a [VideoResolution] is fixed or follows the source
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VideoResolutionType {
+    #[default]
+    Fixed,
+    Source,
 }
 
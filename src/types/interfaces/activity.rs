@@ -2,60 +2,241 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
 
 use crate::types::{entities::Emoji, Snowflake};
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+/// An activity a user is engaging in, shown as part of their presence.
+///
+/// See [ActivityBuilder] for an easier way to create one.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/presence#activity-object>
 pub struct Activity {
-    name: String,
+    pub name: String,
+    /// See [ActivityType]
     #[serde(rename = "type")]
-    activity_type: i32,
-    url: Option<String>,
-    created_at: i64,
-    timestamps: Option<ActivityTimestamps>,
-    application_id: Option<Snowflake>,
-    details: Option<String>,
-    state: Option<String>,
-    emoji: Option<Emoji>,
-    party: Option<ActivityParty>,
-    assets: Option<ActivityAssets>,
-    secrets: Option<ActivitySecrets>,
-    instance: Option<bool>,
-    flags: Option<i32>,
-    buttons: Option<Vec<ActivityButton>>,
-}
-
-#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
-struct ActivityTimestamps {
-    start: Option<i64>,
-    end: Option<i64>,
+    pub activity_type: i32,
+    /// Stream url, only used with [ActivityType::Streaming]
+    pub url: Option<String>,
+    /// Unix timestamp (in milliseconds) of when the activity was added to the user's session
+    pub created_at: i64,
+    pub timestamps: Option<ActivityTimestamps>,
+    pub application_id: Option<Snowflake>,
+    /// What the user is currently doing
+    pub details: Option<String>,
+    /// The user's current party status
+    pub state: Option<String>,
+    pub emoji: Option<Emoji>,
+    pub party: Option<ActivityParty>,
+    pub assets: Option<ActivityAssets>,
+    pub secrets: Option<ActivitySecrets>,
+    pub instance: Option<bool>,
+    pub flags: Option<i32>,
+    /// Up to two custom buttons shown on the activity
+    pub buttons: Option<Vec<ActivityButton>>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
-struct ActivityParty {
-    id: Option<String>,
-    size: Option<Vec<(i32, i32)>>,
+#[derive(Debug, Deserialize_repr, Serialize_repr, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(u8)]
+/// # Reference
+/// See <https://docs.discord.sex/resources/presence#activity-type>
+pub enum ActivityType {
+    /// Playing {name}
+    #[default]
+    Playing = 0,
+    /// Streaming {details}
+    Streaming = 1,
+    /// Listening to {name}
+    Listening = 2,
+    /// Watching {details}
+    Watching = 3,
+    /// {emoji} {state}
+    Custom = 4,
+    /// Competing in {name}
+    Competing = 5,
+    /// Hanging out
+    Hang = 6,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
-struct ActivityAssets {
-    large_image: Option<String>,
-    large_text: Option<String>,
-    small_image: Option<String>,
-    small_text: Option<String>,
+impl From<ActivityType> for i32 {
+    fn from(value: ActivityType) -> Self {
+        value as i32
+    }
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
-struct ActivitySecrets {
-    join: Option<String>,
-    spectate: Option<String>,
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+/// Unix timestamps (in milliseconds) for the start and / or end of an activity
+pub struct ActivityTimestamps {
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Default)]
+pub struct ActivityParty {
+    pub id: Option<String>,
+    /// The party's current and maximum size
+    pub size: Option<(i32, i32)>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Default)]
+pub struct ActivityAssets {
+    pub large_image: Option<String>,
+    /// Text displayed when hovering over the large image
+    pub large_text: Option<String>,
+    pub small_image: Option<String>,
+    /// Text displayed when hovering over the small image
+    pub small_text: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Default)]
+pub struct ActivitySecrets {
+    pub join: Option<String>,
+    pub spectate: Option<String>,
     #[serde(rename = "match")]
-    match_string: Option<String>,
+    pub match_string: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
-struct ActivityButton {
-    label: String,
-    url: String,
+pub struct ActivityButton {
+    pub label: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Builds an [Activity].
+///
+/// # Example
+/// ```rs
+/// let activity = ActivityBuilder::new("Chorus", ActivityType::Playing)
+///     .details("Writing a client")
+///     .start(chrono::Utc::now())
+///     .button("Repository", "https://github.com/polyphony-chat/chorus")
+///     .build();
+/// ```
+pub struct ActivityBuilder {
+    activity: Activity,
 }
+
+impl ActivityBuilder {
+    /// Starts building an activity with the given name and type.
+    pub fn new(name: &str, activity_type: ActivityType) -> Self {
+        Self {
+            activity: Activity {
+                name: name.to_string(),
+                activity_type: activity_type.into(),
+                url: None,
+                created_at: Utc::now().timestamp_millis(),
+                timestamps: None,
+                application_id: None,
+                details: None,
+                state: None,
+                emoji: None,
+                party: None,
+                assets: None,
+                secrets: None,
+                instance: None,
+                flags: None,
+                buttons: None,
+            },
+        }
+    }
+
+    /// Sets the stream url, for [ActivityType::Streaming]
+    pub fn url(mut self, url: &str) -> Self {
+        self.activity.url = Some(url.to_string());
+        self
+    }
+
+    pub fn details(mut self, details: &str) -> Self {
+        self.activity.details = Some(details.to_string());
+        self
+    }
+
+    pub fn state(mut self, state: &str) -> Self {
+        self.activity.state = Some(state.to_string());
+        self
+    }
+
+    pub fn application_id(mut self, application_id: Snowflake) -> Self {
+        self.activity.application_id = Some(application_id);
+        self
+    }
+
+    pub fn emoji(mut self, emoji: Emoji) -> Self {
+        self.activity.emoji = Some(emoji);
+        self
+    }
+
+    /// Sets when the activity started, which clients show as elapsed time
+    pub fn start(mut self, start: DateTime<Utc>) -> Self {
+        self.activity
+            .timestamps
+            .get_or_insert_with(Default::default)
+            .start = Some(start.timestamp_millis());
+        self
+    }
+
+    /// Sets when the activity will end, which clients show as remaining time
+    pub fn end(mut self, end: DateTime<Utc>) -> Self {
+        self.activity
+            .timestamps
+            .get_or_insert_with(Default::default)
+            .end = Some(end.timestamp_millis());
+        self
+    }
+
+    /// Sets the large image and the text shown when hovering over it
+    pub fn large_image(mut self, image: &str, text: Option<&str>) -> Self {
+        let assets = self.activity.assets.get_or_insert_with(Default::default);
+        assets.large_image = Some(image.to_string());
+        assets.large_text = text.map(String::from);
+        self
+    }
+
+    /// Sets the small image and the text shown when hovering over it
+    pub fn small_image(mut self, image: &str, text: Option<&str>) -> Self {
+        let assets = self.activity.assets.get_or_insert_with(Default::default);
+        assets.small_image = Some(image.to_string());
+        assets.small_text = text.map(String::from);
+        self
+    }
+
+    /// Sets the party id and its current and maximum size
+    pub fn party(mut self, id: &str, current_size: i32, max_size: i32) -> Self {
+        self.activity.party = Some(ActivityParty {
+            id: Some(id.to_string()),
+            size: Some((current_size, max_size)),
+        });
+        self
+    }
+
+    pub fn secrets(mut self, secrets: ActivitySecrets) -> Self {
+        self.activity.secrets = Some(secrets);
+        self
+    }
+
+    /// Adds a button linking to `url`.
+    ///
+    /// Activities can have at most two buttons; further buttons are ignored.
+    pub fn button(mut self, label: &str, url: &str) -> Self {
+        let buttons = self.activity.buttons.get_or_insert_with(Vec::new);
+        if buttons.len() < MAX_ACTIVITY_BUTTONS {
+            buttons.push(ActivityButton {
+                label: label.to_string(),
+                url: url.to_string(),
+            });
+        }
+        self
+    }
+
+    pub fn build(self) -> Activity {
+        self.activity
+    }
+}
+
+/// The maximum amount of [ActivityButton]s an activity can have
+pub const MAX_ACTIVITY_BUTTONS: usize = 2;
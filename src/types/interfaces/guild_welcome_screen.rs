@@ -7,7 +7,14 @@ use serde::{Deserialize, Serialize};
 use crate::types::utils::Snowflake;
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Hash)]
+/// The screen shown to new members of a guild, which introduces its most important channels.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/guild#welcome-screen-object>
 pub struct WelcomeScreenObject {
+    /// Discord does not send this field; check for the `WELCOME_SCREEN_ENABLED` guild feature
+    /// instead
+    #[serde(default)]
     pub enabled: bool,
     pub description: Option<String>,
     pub welcome_channels: Vec<WelcomeScreenChannel>,
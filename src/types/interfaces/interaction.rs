@@ -4,9 +4,11 @@
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use serde_repr::{Deserialize_repr, Serialize_repr};
 
-use crate::types::entities::{AllowedMention, Embed};
+use crate::types::entities::{Component, ComponentType, Embed};
 use crate::types::utils::Snowflake;
+use crate::types::AllowedMentions;
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Interaction {
@@ -20,12 +22,81 @@ pub struct Interaction {
     pub version: i32,
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, Eq, PartialOrd, Ord, Hash, Copy)]
+impl Interaction {
+    /// Returns the data of a [InteractionType::MessageComponent] interaction.
+    ///
+    /// Returns [None] if the interaction is of another type or its data is malformed.
+    pub fn message_component_data(&self) -> Option<MessageComponentInteractionData> {
+        if self.r#type != InteractionType::MessageComponent {
+            return None;
+        }
+        serde_json::from_value(self.data.clone()).ok()
+    }
+
+    /// Returns the data of a [InteractionType::ModalSubmit] interaction.
+    ///
+    /// Returns [None] if the interaction is of another type or its data is malformed.
+    pub fn modal_submit_data(&self) -> Option<ModalSubmitInteractionData> {
+        if self.r#type != InteractionType::ModalSubmit {
+            return None;
+        }
+        serde_json::from_value(self.data.clone()).ok()
+    }
+}
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    PartialEq,
+    Serialize_repr,
+    Deserialize_repr,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Copy,
+)]
+#[repr(u8)]
 pub enum InteractionType {
     #[default]
     SelfCommand = 0,
     Ping = 1,
     ApplicationCommand = 2,
+    /// A user used a [Component] attached to a message
+    MessageComponent = 3,
+    ApplicationCommandAutocomplete = 4,
+    /// A user submitted a modal
+    ModalSubmit = 5,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// The data of a [InteractionType::MessageComponent] interaction.
+///
+/// # Reference
+/// See <https://docs.discord.sex/interactions/receiving-and-responding#message-component-data-structure>
+pub struct MessageComponentInteractionData {
+    /// The custom id of the component which was used
+    pub custom_id: String,
+    pub component_type: ComponentType,
+    /// The values the user selected, for select menus
+    #[serde(default)]
+    pub values: Vec<String>,
+    /// The users, roles and channels referenced in `values`, for auto-populated select menus
+    #[serde(default)]
+    pub resolved: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// The data of a [InteractionType::ModalSubmit] interaction.
+///
+/// # Reference
+/// See <https://docs.discord.sex/interactions/receiving-and-responding#modal-submit-data-structure>
+pub struct ModalSubmitInteractionData {
+    /// The custom id of the modal which was submitted
+    pub custom_id: String,
+    /// The components of the modal, with the values the user entered
+    pub components: Vec<Component>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Copy, Eq, Hash, PartialOrd, Ord)]
@@ -43,5 +114,5 @@ pub struct InteractionApplicationCommandCallbackData {
     pub tts: bool,
     pub content: String,
     pub embeds: Vec<Embed>,
-    pub allowed_mentions: AllowedMention,
+    pub allowed_mentions: AllowedMentions,
 }
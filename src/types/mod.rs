@@ -4,7 +4,7 @@
 
 //! All the types, entities, events and interfaces of the Spacebar API.
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use std::sync::{Arc, RwLock};
 
 pub use config::*;
@@ -32,7 +32,7 @@ mod utils;
 /// 
 /// When the `client` feature is disabled, this does nothing (same as just `T`),
 /// since `Composite` structures are disabled.
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 pub type Shared<T> = Arc<RwLock<T>>;
-#[cfg(not(feature = "client"))]
+#[cfg(not(feature = "client-core"))]
 pub type Shared<T> = T;
@@ -19,8 +19,13 @@ pub struct ChannelCreateSchema {
     pub bitrate: Option<i32>,
     pub user_limit: Option<i32>,
     pub rate_limit_per_user: Option<i32>,
+    /// The sorting position of the channel among the other channels in its parent category.
+    ///
+    /// If the server does not honor it, [Channel::create](crate::types::Channel::create) moves
+    /// the channel there after creating it.
     pub position: Option<i32>,
     pub permission_overwrites: Option<Vec<PermissionOverwrite>>,
+    /// The category to create the channel in
     pub parent_id: Option<Snowflake>,
     pub id: Option<Snowflake>,
     pub nsfw: Option<bool>,
@@ -171,15 +176,54 @@ pub struct AddChannelRecipientSchema {
     pub nick: Option<String>,
 }
 
-/// See <https://discord-userdoccers.vercel.app/resources/channel#add-channel-recipient>
+/// Fields of a group DM which can be modified.
+///
+/// Fields which are `None` are left unchanged.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/channel#modify-channel>
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
+pub struct GroupDmModifySchema {
+    /// The new name of the group DM (1-100 characters)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The new icon, as a [data URI](https://developer.mozilla.org/en-US/docs/Web/HTTP/Basics_of_HTTP/Data_URLs)
+    /// (e.g. `data:image/png;base64,...`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    /// The id of the recipient to transfer ownership of the group DM to.
+    ///
+    /// Only the current owner can transfer ownership.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<Snowflake>,
+}
+
+/// A single entry of a channel position update.
+///
+/// Fields which are `None` are left unchanged.
+///
+/// See <https://discord-userdoccers.vercel.app/resources/channel#modify-guild-channel-positions>
 #[derive(
     Debug, Deserialize, Serialize, Clone, Default, PartialOrd, Ord, PartialEq, Eq, Copy, Hash,
 )]
 pub struct ModifyChannelPositionsSchema {
+    /// The id of the channel to move
     pub id: Snowflake,
+    /// The new sorting position of the channel
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub position: Option<u32>,
+    /// Whether to sync the channel's permission overwrites with its new parent category
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub lock_permissions: Option<bool>,
-    pub parent_id: Option<Snowflake>,
+    /// The new parent category of the channel.
+    ///
+    /// `Some(None)` moves the channel out of its category.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    pub parent_id: Option<Option<Snowflake>>,
 }
 
 /// See <https://docs.discord.sex/resources/channel#follow-channel>
@@ -195,3 +239,13 @@ pub struct CreateWebhookSchema {
     pub name: String,
     pub avatar: Option<String>,
 }
+
+/// Used to ring or stop ringing recipients of a DM or group DM call.
+///
+/// See <https://docs.discord.sex/resources/channel#ring-channel-recipients>
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
+pub struct CallRingSchema {
+    /// The users to ring; if `None`, all recipients of the channel are rung
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipients: Option<Vec<Snowflake>>,
+}
@@ -0,0 +1,64 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Contains schema for browsing the public guilds of an instance
+//!
+//! Implementations of those routes can be found in /api/guilds/discovery.rs
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::Guild;
+
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+/// Query parameters for the /discoverable-guilds endpoint
+pub struct DiscoverableGuildsQuery {
+    /// How many guilds to skip
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u32>,
+    /// The maximum amount of guilds to return
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    /// Only return guilds in the [DiscoveryCategory] with this id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub categories: Option<u32>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+/// A page of discoverable guilds, as returned by the /discoverable-guilds endpoint
+pub struct DiscoverableGuilds {
+    /// The total amount of discoverable guilds matching the query
+    pub total: u32,
+    pub guilds: Vec<Guild>,
+    pub offset: u32,
+    pub limit: u32,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+/// Query parameters for the /discovery/categories endpoint
+pub struct DiscoveryCategoriesQuery {
+    /// The locale to return category names in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    /// Whether to only return primary categories
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub primary_only: Option<bool>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq)]
+/// A category guilds can be listed under in discovery
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/discovery#discovery-category-object>
+pub struct DiscoveryCategory {
+    pub id: u32,
+    pub name: String,
+    /// The category's name in other locales, by locale
+    #[serde(default)]
+    pub localizations: HashMap<String, String>,
+    /// Whether the category can be set as a guild's primary category
+    #[serde(default)]
+    pub is_primary: bool,
+}
@@ -0,0 +1,130 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Contains schema for the GIF search the instance proxies from Tenor
+//!
+//! Implementations of those routes can be found in /api/gifs.rs
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+/// The format of the media [Gif::src] points to.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/integration#gif-media-format>
+pub enum GifMediaFormat {
+    Gif,
+    MediumGif,
+    TinyGif,
+    NanoGif,
+    #[default]
+    Mp4,
+    LoopedMp4,
+    TinyMp4,
+    NanoMp4,
+    Webm,
+    TinyWebm,
+    NanoWebm,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+/// A GIF returned by the instance's GIF search.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/integration#gif-object>
+pub struct Gif {
+    /// The id of the GIF on Tenor
+    pub id: String,
+    pub title: String,
+    /// The url of the GIF's page on Tenor
+    pub url: String,
+    /// The url of the GIF's media, in the requested [GifMediaFormat]
+    pub src: String,
+    /// The url of the GIF's media in the [GifMediaFormat::Gif] format
+    pub gif_src: String,
+    pub width: u32,
+    pub height: u32,
+    /// The url of a still preview image of the GIF
+    pub preview: String,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+/// Query parameters for the /gifs/search endpoint
+pub struct GifSearchQuery {
+    /// The search term
+    pub q: String,
+    /// The format to return [Gif::src] in; defaults to [GifMediaFormat::Mp4]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media_format: Option<GifMediaFormat>,
+    /// The locale to search in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    /// The maximum amount of GIFs to return
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u8>,
+}
+
+impl GifSearchQuery {
+    /// Searches for GIFs matching `q` with the default options.
+    pub fn new(q: &str) -> Self {
+        Self {
+            q: q.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+/// Query parameters for the /gifs/trending endpoint
+pub struct GifTrendingQuery {
+    /// The format to return the [GifCategory::src] and [Gif::src] in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media_format: Option<GifMediaFormat>,
+    /// The locale to return category names in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+/// Query parameters for the /gifs/suggest endpoint
+pub struct GifSuggestQuery {
+    /// The (partial) search term to suggest search terms for
+    pub q: String,
+    /// The maximum amount of suggestions to return
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u8>,
+    /// The locale to suggest search terms in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+}
+
+impl GifSuggestQuery {
+    /// Suggests search terms for `q` with the default options.
+    pub fn new(q: &str) -> Self {
+        Self {
+            q: q.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+/// The trending GIF categories and GIFs, as returned by the /gifs/trending endpoint
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/integration#get-trending-gifs>
+pub struct GifTrending {
+    pub categories: Vec<GifCategory>,
+    pub gifs: Vec<Gif>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+/// A trending GIF search term, shown as a category in GIF pickers
+pub struct GifCategory {
+    /// The search term; search for it with [GifSearchQuery] to show the category's GIFs
+    pub name: String,
+    /// The url of a GIF representing the category
+    pub src: String,
+}
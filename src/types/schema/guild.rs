@@ -7,15 +7,15 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use crate::types::entities::Channel;
 use crate::types::types::guild_configuration::GuildFeatures;
 use crate::types::{
-    Emoji, ExplicitContentFilterLevel, GenericSearchQueryWithLimit, MessageNotificationLevel,
-    Snowflake, Sticker, StickerFormatType, SystemChannelFlags, VerificationLevel,
-    WelcomeScreenChannel,
+    Emoji, ExplicitContentFilterLevel, GenericSearchQueryWithLimit, MemberVerificationFormField,
+    MessageNotificationLevel, OnboardingMode, OnboardingPrompt, Snowflake, Sticker,
+    StickerFormatType, SystemChannelFlags, TemplateChannel, TemplateRole, TemplateSourceGuild,
+    UserStatus, VerificationLevel, WelcomeScreenChannel,
 };
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 #[serde(rename_all = "snake_case")]
 /// Represents the schema which needs to be sent to create a Guild.
 /// See: <https://docs.spacebar.chat/routes/#cmp--schemas-guildcreateschema>
@@ -23,12 +23,54 @@ pub struct GuildCreateSchema {
     pub name: Option<String>,
     pub region: Option<String>,
     pub icon: Option<String>,
-    pub channels: Option<Vec<Channel>>,
+    /// The channels to create the guild with, in the same structure as in a
+    /// [TemplateSourceGuild]; their placeholder ids can be referenced by other channels'
+    /// `parent_id` and by [GuildCreateSchema::system_channel_id]
+    pub channels: Option<Vec<TemplateChannel>>,
+    /// The roles to create the guild with, in the same structure as in a
+    /// [TemplateSourceGuild]; the first one is the `@everyone` role
+    pub roles: Option<Vec<TemplateRole>>,
+    /// The code of the [GuildTemplate](crate::types::GuildTemplate) to create the guild from
     pub guild_template_code: Option<String>,
+    /// The placeholder id of the system channel
     pub system_channel_id: Option<String>,
+    /// The placeholder id of the rules channel
     pub rules_channel_id: Option<String>,
 }
 
+impl GuildCreateSchema {
+    /// Creates the schema for a guild with the settings, roles and channels of a
+    /// [TemplateSourceGuild], e.g. to adjust a [GuildTemplate](crate::types::GuildTemplate)
+    /// before creating a guild from it.
+    ///
+    /// See [Guild::create_from_template](crate::types::Guild::create_from_template) to create a
+    /// guild from a template as is.
+    pub fn from_template_source(source: &TemplateSourceGuild) -> GuildCreateSchema {
+        GuildCreateSchema {
+            name: source.name.clone(),
+            region: source.region.clone(),
+            icon: None,
+            channels: Some(source.channels.clone()),
+            roles: Some(source.roles.clone()),
+            guild_template_code: None,
+            system_channel_id: source.system_channel_id.map(|id| id.to_string()),
+            rules_channel_id: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
+/// The schema for creating a guild from a [GuildTemplate](crate::types::GuildTemplate).
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/guild-template#create-guild-from-guild-template>
+pub struct GuildCreateFromTemplateSchema {
+    pub name: String,
+    /// The guild's icon, as a data uri
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+}
+
 #[cfg(not(tarpaulin_include))]
 impl PartialEq for GuildCreateSchema {
     fn eq(&self, other: &Self) -> bool {
@@ -36,6 +78,7 @@ impl PartialEq for GuildCreateSchema {
             && self.region == other.region
             && self.icon == other.icon
             && self.channels == other.channels
+            && self.roles == other.roles
             && self.guild_template_code == other.guild_template_code
             && self.system_channel_id == other.system_channel_id
             && self.rules_channel_id == other.rules_channel_id
@@ -54,13 +97,25 @@ pub struct GuildBanCreateSchema {
 
 #[derive(Debug, Deserialize, Serialize, Default, Clone, Eq, PartialEq)]
 #[serde(rename_all = "snake_case")]
-/// Represents the schema which needs to be sent to create a Guild Ban.
-/// See: <https://discord-userdoccers.vercel.app/resources/guild#create-guild-ban>
+/// Represents the schema which needs to be sent to ban multiple users at once.
+/// See: <https://docs.discord.sex/resources/guild#bulk-guild-ban>
 pub struct GuildBanBulkCreateSchema {
+    /// The users to ban (max 200)
     pub user_ids: Vec<Snowflake>,
+    /// The number of seconds to delete messages for (0-604800)
     pub delete_message_seconds: Option<u32>,
 }
 
+#[derive(Debug, Deserialize, Serialize, Default, Clone, Eq, PartialEq)]
+/// The result of a bulk guild ban.
+/// See: <https://docs.discord.sex/resources/guild#bulk-guild-ban>
+pub struct GuildBanBulkCreateResult {
+    /// The users that were successfully banned
+    pub banned_users: Vec<Snowflake>,
+    /// The users that could not be banned
+    pub failed_users: Vec<Snowflake>,
+}
+
 #[derive(Debug, Deserialize, Serialize, Default, Clone, Eq, PartialEq)]
 #[serde(rename_all = "snake_case")]
 /// Represents the schema used to modify a guild.
@@ -154,9 +209,123 @@ pub struct GuildPreview {
     pub approximate_presence_count: u32,
 }
 
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+/// The public information about a guild shown in its widget.
+///
+/// Only available if the guild's widget is enabled, see [GuildWidgetSettings].
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/guild#guild-widget-object>
+pub struct GuildWidget {
+    pub id: Snowflake,
+    pub name: String,
+    /// An invite to the guild's widget channel, if it has one
+    pub instant_invite: Option<String>,
+    /// The voice channels everyone can connect to
+    pub channels: Vec<GuildWidgetChannel>,
+    /// Up to 100 of the guild's online members
+    pub members: Vec<GuildWidgetMember>,
+    /// How many members of the guild are online
+    pub presence_count: u32,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+/// # Reference
+/// See <https://docs.discord.sex/resources/guild#widget-channel-structure>
+pub struct GuildWidgetChannel {
+    pub id: Snowflake,
+    pub name: String,
+    pub position: i32,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+/// An online member shown in a [GuildWidget].
+///
+/// To keep members anonymous, the id and discriminator are not the member's real ones.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/guild#widget-member-structure>
+pub struct GuildWidgetMember {
+    /// The member's index in the widget
+    pub id: String,
+    pub username: String,
+    #[serde(default)]
+    pub discriminator: Option<String>,
+    #[serde(default)]
+    pub avatar: Option<String>,
+    pub status: UserStatus,
+    #[serde(default)]
+    pub avatar_url: Option<String>,
+    /// The name of the member's current activity
+    #[serde(default)]
+    pub activity: Option<GuildWidgetMemberActivity>,
+    /// The voice channel the member is connected to
+    #[serde(default)]
+    pub channel_id: Option<Snowflake>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct GuildWidgetMemberActivity {
+    pub name: String,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+/// Whether a guild's widget is enabled, and which channel its invite is for.
+///
+/// Also used to modify the settings, see
+/// [Guild::modify_widget_settings](crate::types::Guild::modify_widget_settings).
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/guild#guild-widget-settings-object>
+pub struct GuildWidgetSettings {
+    pub enabled: bool,
+    /// The channel the widget's invite is for
+    pub channel_id: Option<Snowflake>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+/// The look of a guild's widget image.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/guild#widget-style-options>
+pub enum GuildWidgetImageStyle {
+    /// A small image with the guild's online member count
+    #[default]
+    Shield,
+    /// A large image with the guild's icon, name and online member count
+    Banner1,
+    /// A smaller version of banner 1, without the icon
+    Banner2,
+    /// A large image with the guild's icon, name and online member count
+    Banner3,
+    /// A large image with the guild's icon, name and online member count, and a join button
+    Banner4,
+}
+
+impl std::fmt::Display for GuildWidgetImageStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let style = match self {
+            GuildWidgetImageStyle::Shield => "shield",
+            GuildWidgetImageStyle::Banner1 => "banner1",
+            GuildWidgetImageStyle::Banner2 => "banner2",
+            GuildWidgetImageStyle::Banner3 => "banner3",
+            GuildWidgetImageStyle::Banner4 => "banner4",
+        };
+        write!(f, "{}", style)
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, PartialOrd, Eq, Ord)]
+/// Query parameters for [Guild::search_members](crate::types::Guild::search_members).
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/guild#search-guild-members>
 pub struct GuildMemberSearchSchema {
+    /// Query to match usernames and nicknames against
     pub query: String,
+    /// Max number of members to return (1-1000), defaults to 1
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<u16>,
 }
 
@@ -169,9 +338,42 @@ impl Default for GuildMemberSearchSchema {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, PartialOrd, Eq, Ord, Copy, Hash)]
+#[derive(
+    Debug, Default, Deserialize, Serialize, Clone, PartialEq, PartialOrd, Eq, Ord, Copy, Hash,
+)]
+/// Query parameters for [Guild::get_members_with_query](crate::types::Guild::get_members_with_query).
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/guild#get-guild-members>
 pub struct GuildGetMembersQuery {
+    /// Max number of members to return (1-1000), defaults to 1
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u16>,
+    /// Get members whose user id is after this id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<Snowflake>,
+}
+
+#[derive(
+    Debug, Default, Deserialize, Serialize, Clone, PartialEq, PartialOrd, Eq, Ord, Copy, Hash,
+)]
+/// Query parameters for
+/// [GuildScheduledEvent::get_subscribers](crate::types::GuildScheduledEvent::get_subscribers).
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/guild-scheduled-event#get-guild-scheduled-event-users>
+pub struct GuildScheduledEventUsersQuery {
+    /// Max number of users to return (1-100), defaults to 100
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<u16>,
+    /// Whether to include the guild member object of each user
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub with_member: Option<bool>,
+    /// Get users whose id is before this id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<Snowflake>,
+    /// Get users whose id is after this id
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub after: Option<Snowflake>,
 }
 
@@ -223,11 +425,16 @@ pub struct ModifyGuildMemberProfileSchema {
     pub emoji_id: Option<Snowflake>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, PartialOrd, Eq, Ord, Copy, Hash)]
+#[derive(
+    Debug, Default, Deserialize, Serialize, Clone, PartialEq, PartialOrd, Eq, Ord, Copy, Hash,
+)]
 /// The limit argument is a number between 1 and 1000.
 pub struct GuildBansQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub before: Option<Snowflake>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub after: Option<Snowflake>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<u16>,
 }
 
@@ -314,10 +521,12 @@ pub struct EmojiModifySchema {
 /// # Reference:
 /// See <https://docs.discord.sex/resources/guild#get-guild-prune>
 pub struct GuildPruneQuerySchema {
+    /// The number of days a member needs to be inactive for to be pruned (1-30)
     pub days: u8,
     /// Only used on POST
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub compute_prune_count: Option<bool>,
+    /// Roles whose members should also be pruned; by default, members with roles are not
     #[serde(default)]
     pub include_roles: Vec<Snowflake>,
 }
@@ -399,7 +608,7 @@ impl GuildCreateStickerSchema {
         Ok(_self)
     }
 
-    // #[cfg(feature = "client")]
+    // #[cfg(feature = "client-core")]
     pub fn to_multipart(&self) -> reqwest::multipart::Form {
         let mut form = reqwest::multipart::Form::new()
             .text("name", self.name.clone())
@@ -433,16 +642,73 @@ pub struct GuildModifyStickerSchema {
     pub tags: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
 /// # Reference:
 /// See <https://docs.discord.sex/resources/guild#modify-guild-welcome-screen>
 pub struct GuildModifyWelcomeScreenSchema {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     /// Max of 5
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub welcome_channels: Option<Vec<WelcomeScreenChannel>>,
 }
 
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+/// # Reference:
+/// See <https://docs.discord.sex/resources/guild#modify-guild-onboarding>
+pub struct GuildModifyOnboardingSchema {
+    /// Replaces all prompts of the guild's onboarding
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompts: Option<Vec<OnboardingPrompt>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_channel_ids: Option<Vec<Snowflake>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<OnboardingMode>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+/// # Reference:
+/// See <https://docs.discord.sex/resources/guild#get-guild-member-verification>
+pub struct GuildMemberVerificationQuery {
+    /// Whether to include the guild in the response
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub with_guild: Option<bool>,
+    /// The invite the user is joining the guild with, which is needed to view the form of a
+    /// guild the user is not a member of yet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invite_code: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+/// # Reference:
+/// See <https://docs.discord.sex/resources/guild#modify-guild-member-verification>
+pub struct GuildModifyMemberVerificationSchema {
+    /// Whether new members have to fill out the form
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Replaces all questions of the form (max 5)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub form_fields: Option<Vec<MemberVerificationFormField>>,
+    /// A description of what the guild is about (max 300 characters)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+/// # Reference:
+/// See <https://docs.discord.sex/resources/guild#create-guild-join-request>
+pub struct GuildSubmitMemberVerificationSchema {
+    /// The [version](crate::types::MemberVerification::version) of the form which was filled
+    /// out
+    pub version: Option<DateTime<Utc>>,
+    /// The form's fields, with their [response](MemberVerificationFormField::response) set
+    pub form_fields: Vec<MemberVerificationFormField>,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 /// # Reference:
 /// See <https://docs.discord.sex/resources/guild-template#create-guild-template>
@@ -452,3 +718,12 @@ pub struct GuildTemplateCreateSchema {
     /// Description of the template (max 120 characters)
     pub description: Option<String>,
 }
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+/// # Reference:
+/// See <https://docs.discord.sex/resources/premium-guild-subscription#create-premium-guild-subscription>
+pub struct PremiumGuildSubscriptionCreateSchema {
+    /// The ids of the [PremiumGuildSubscriptionSlot](crate::types::PremiumGuildSubscriptionSlot)s
+    /// to boost the guild with
+    pub user_premium_guild_subscription_slot_ids: Vec<Snowflake>,
+}
@@ -81,4 +81,39 @@ pub struct VersionReturn {
     pub version: String,
     /// The instance's software, e. g. "symfonia" or "spacebar"
     pub server: String,
+    /// The optional endpoints the instance reports implementing, as the snake_case names of
+    /// [Capability](crate::instance::Capability)s, e. g. "message_search"
+    ///
+    /// Unknown names are ignored.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub capabilities: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+/// The return type of the /gateway/bot endpoint
+///
+/// # Reference
+/// See <https://docs.discord.sex/topics/gateway#get-gateway-bot>
+pub struct GatewayBot {
+    /// The websocket url of the gateway
+    pub url: String,
+    /// The recommended number of shards to connect with
+    pub shards: u32,
+    pub session_start_limit: SessionStartLimit,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+/// How many gateway sessions may still be started, see [GatewayBot]
+///
+/// # Reference
+/// See <https://docs.discord.sex/topics/gateway#session-start-limit-structure>
+pub struct SessionStartLimit {
+    /// The total number of sessions which may be started per reset period
+    pub total: u32,
+    /// The number of sessions which may still be started in the current reset period
+    pub remaining: u32,
+    /// The number of milliseconds until `remaining` is reset to `total`
+    pub reset_after: u64,
+    /// The number of identifies which may be sent per 5 seconds
+    pub max_concurrency: u32,
 }
@@ -5,10 +5,12 @@
 use serde::{Deserialize, Serialize};
 
 use crate::types::entities::{
-    AllowedMention, Component, Embed, MessageReference, PartialDiscordFileAttachment,
+    Component, Embed, MessageReference, PartialDiscordFileAttachment, PollAnswer, PollLayoutType,
+    PollMedia,
 };
 use crate::types::{
-    Attachment, EmbedType, Message, MessageFlags, MessageType, ReactionType, Snowflake,
+    Attachment, EmbedType, Message, MessageFlags, MessageType, PublicUser, ReactionType,
+    ReadStateType, Snowflake,
 };
 
 #[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
@@ -20,11 +22,186 @@ pub struct MessageSendSchema {
     pub nonce: Option<String>,
     pub tts: Option<bool>,
     pub embeds: Option<Vec<Embed>>,
-    pub allowed_mentions: Option<AllowedMention>,
+    pub allowed_mentions: Option<AllowedMentions>,
     pub message_reference: Option<MessageReference>,
     pub components: Option<Vec<Component>>,
     pub sticker_ids: Option<Vec<String>>,
     pub attachments: Option<Vec<PartialDiscordFileAttachment>>,
+    /// A poll to attach to the message
+    pub poll: Option<PollCreateSchema>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+/// # Reference
+/// See <https://docs.discord.sex/resources/webhook#execute-webhook>
+pub struct WebhookExecuteSchema {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// Overrides the webhook's default username
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// Overrides the webhook's default avatar
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tts: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embeds: Option<Vec<Embed>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_mentions: Option<AllowedMentions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub components: Option<Vec<Component>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachments: Option<Vec<PartialDiscordFileAttachment>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flags: Option<MessageFlags>,
+    /// The name of the thread to create, if the webhook belongs to a forum or media channel
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll: Option<PollCreateSchema>,
+}
+
+/// The maximum amount of users or roles which can be allowed to be mentioned
+pub const MAX_ALLOWED_MENTIONS: usize = 100;
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Eq, PartialOrd, Ord, Hash)]
+/// Controls which mentions in a message's content notify the mentioned users.
+///
+/// Mentions which are not allowed are still rendered, but nobody is notified. The default value
+/// allows no mentions at all.
+///
+/// # Example
+/// ```rs
+/// // Only notify one user, never @everyone or roles
+/// let allowed_mentions = AllowedMentions::none().user(user_id);
+/// ```
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/message#allowed-mentions-object>
+pub struct AllowedMentions {
+    /// The types of mentions which are all allowed
+    #[serde(default)]
+    pub parse: Vec<AllowedMentionType>,
+    /// The roles which may be mentioned (max 100)
+    ///
+    /// Must be empty if `parse` contains [AllowedMentionType::Roles].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roles: Vec<Snowflake>,
+    /// The users which may be mentioned (max 100)
+    ///
+    /// Must be empty if `parse` contains [AllowedMentionType::Users].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub users: Vec<Snowflake>,
+    /// Whether the author of the message being replied to is notified
+    #[serde(default)]
+    pub replied_user: bool,
+}
+
+impl AllowedMentions {
+    /// Allows no mentions at all.
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// Allows all mentions, including @everyone and @here.
+    pub fn all() -> Self {
+        Self {
+            parse: vec![
+                AllowedMentionType::Users,
+                AllowedMentionType::Roles,
+                AllowedMentionType::Everyone,
+            ],
+            roles: Vec::new(),
+            users: Vec::new(),
+            replied_user: true,
+        }
+    }
+
+    /// Allows mentions of all users and roles, but not @everyone or @here.
+    pub fn all_except_everyone() -> Self {
+        Self {
+            parse: vec![AllowedMentionType::Users, AllowedMentionType::Roles],
+            roles: Vec::new(),
+            users: Vec::new(),
+            replied_user: true,
+        }
+    }
+
+    /// Allows mentioning a specific user.
+    ///
+    /// Since this is mutually exclusive with allowing all users, this removes
+    /// [AllowedMentionType::Users] from `parse`.
+    pub fn user(mut self, user_id: Snowflake) -> Self {
+        self.parse
+            .retain(|parse| *parse != AllowedMentionType::Users);
+        if !self.users.contains(&user_id) {
+            self.users.push(user_id);
+        }
+        self
+    }
+
+    /// Allows mentioning a specific role.
+    ///
+    /// Since this is mutually exclusive with allowing all roles, this removes
+    /// [AllowedMentionType::Roles] from `parse`.
+    pub fn role(mut self, role_id: Snowflake) -> Self {
+        self.parse
+            .retain(|parse| *parse != AllowedMentionType::Roles);
+        if !self.roles.contains(&role_id) {
+            self.roles.push(role_id);
+        }
+        self
+    }
+
+    /// Sets whether the author of the message being replied to is notified.
+    pub fn replied_user(mut self, replied_user: bool) -> Self {
+        self.replied_user = replied_user;
+        self
+    }
+
+    /// Returns whether the server will accept these allowed mentions; the explicit user and
+    /// role lists must not be combined with parsing all users or roles, and may contain at most
+    /// [MAX_ALLOWED_MENTIONS] entries each.
+    pub fn is_valid(&self) -> bool {
+        let conflicts = |parse: AllowedMentionType, list: &[Snowflake]| {
+            self.parse.contains(&parse) && !list.is_empty()
+        };
+
+        !conflicts(AllowedMentionType::Users, &self.users)
+            && !conflicts(AllowedMentionType::Roles, &self.roles)
+            && self.users.len() <= MAX_ALLOWED_MENTIONS
+            && self.roles.len() <= MAX_ALLOWED_MENTIONS
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AllowedMentionType {
+    /// Mentions of any role
+    Roles,
+    /// Mentions of any user
+    Users,
+    /// @everyone and @here
+    Everyone,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+/// # Reference
+/// See <https://docs.discord.sex/resources/message#poll-create-request-object>
+pub struct PollCreateSchema {
+    /// The question of the poll; only text is supported
+    pub question: PollMedia,
+    /// The answers which can be voted for (max 10)
+    pub answers: Vec<PollAnswer>,
+    /// How many hours the poll should be open for (max 768, 32 days); defaults to 24
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<u32>,
+    /// Whether a user can vote for multiple answers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_multiselect: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout_type: Option<PollLayoutType>,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -180,23 +357,99 @@ pub struct MessageSearchResponse {
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct CreateGreetMessage {
     pub sticker_ids: Vec<Snowflake>,
-    pub allowed_mentions: Option<AllowedMention>,
+    pub allowed_mentions: Option<AllowedMentions>,
     pub message_reference: Option<MessageReference>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// The schema for acknowledging a message.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/message#acknowledge-message>
 pub struct MessageAck {
+    /// The token returned by the previous acknowledgement, if any
     pub token: Option<String>,
+    /// Whether the acknowledgement was made manually by the user, e.g. by marking a message
+    /// as unread.
+    ///
+    /// Should be set when acknowledging a message older than the last acknowledged one.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub manual: Option<bool>,
+    /// The amount of unread mentions after this acknowledgement.
+    ///
+    /// Only used for manual acknowledgements, since it is not calculated by the server.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub mention_count: Option<u32>,
+    /// When the channel was last viewed, in days since the Discord epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_viewed: Option<u32>,
+}
+
+impl MessageAck {
+    /// Creates a manual acknowledgement (e.g. marking a message as unread), which leaves
+    /// `mention_count` unread mentions.
+    pub fn manual(token: Option<String>, mention_count: u32) -> MessageAck {
+        MessageAck {
+            token,
+            manual: Some(true),
+            mention_count: Some(mention_count),
+            last_viewed: None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// The schema for acknowledging multiple messages at once.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/read-state#bulk-acknowledge-messages>
+pub struct BulkAckSchema {
+    /// The read states to acknowledge (max 100)
+    pub read_states: Vec<BulkAckEntry>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+/// A single acknowledgement in a [BulkAckSchema].
+pub struct BulkAckEntry {
+    /// The id of the channel (or other resource, see [ReadStateType]) to acknowledge
+    pub channel_id: Snowflake,
+    /// The id of the last message (or other entity) to acknowledge
+    pub message_id: Snowflake,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_state_type: Option<ReadStateType>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, PartialOrd)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+/// The schema for deleting a read state.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/read-state#delete-read-state>
+pub struct DeleteReadStateSchema {
+    /// The type of the read state to delete, [ReadStateType::Channel] if not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_state_type: Option<ReadStateType>,
+    /// The version of the read state to delete
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// The response to acknowledging a message.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/message#acknowledge-message>
+pub struct MessageAckResponse {
+    /// A token to send with the next acknowledgement
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct MessageModifySchema {
     pub content: Option<String>,
     pub embeds: Option<Vec<Embed>>,
     pub embed: Option<Embed>,
-    pub allowed_mentions: Option<AllowedMention>,
+    pub allowed_mentions: Option<AllowedMentions>,
     pub components: Option<Vec<Component>>,
     pub flags: Option<MessageFlags>,
     pub files: Option<Vec<u8>>,
@@ -211,3 +464,26 @@ pub struct ReactionQuerySchema {
     #[serde(rename = "type")]
     pub reaction_type: Option<ReactionType>,
 }
+
+#[derive(
+    Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq, PartialOrd, Ord, Copy, Hash,
+)]
+/// The limit argument is a number between 1 and 100, defaults to 25.
+///
+/// # Reference
+/// See <https://docs.discord.sex/resources/message#get-answer-voters>
+pub struct PollAnswerVotersQuery {
+    /// Get users after this user id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<Snowflake>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u8>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+/// # Reference
+/// See <https://docs.discord.sex/resources/message#get-answer-voters>
+pub struct PollAnswerVoters {
+    /// The users who voted for the answer
+    pub users: Vec<PublicUser>,
+}
@@ -5,7 +5,7 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 
 use crate::{types::Snowflake, errors::ChorusError};
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 use crate::{instance::ChorusUser, ChorusResult};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -42,7 +42,7 @@ pub struct MfaChallenge {
     pub methods: Vec<MfaMethod>,
 }
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 impl MfaChallenge {
     /// Attempts to complete the [MfaChallenge] with authentication data from the user.
     ///
@@ -132,14 +132,14 @@ impl TryFrom<u8> for MfaAuthenticatorType {
     }
 }
 
-#[cfg(feature = "sqlx")]
+#[cfg(feature = "sqlx-pg-uint")]
 impl sqlx::Type<sqlx::Postgres> for MfaAuthenticatorType {
     fn type_info() -> <sqlx::Postgres as sqlx::Database>::TypeInfo {
         <sqlx_pg_uint::PgU8 as sqlx::Type<sqlx::Postgres>>::type_info()
     }
 }
 
-#[cfg(feature = "sqlx")]
+#[cfg(feature = "sqlx-pg-uint")]
 impl<'q> sqlx::Encode<'q, sqlx::Postgres> for MfaAuthenticatorType {
     fn encode_by_ref(
         &self,
@@ -150,7 +150,7 @@ impl<'q> sqlx::Encode<'q, sqlx::Postgres> for MfaAuthenticatorType {
     }
 }
 
-#[cfg(feature = "sqlx")]
+#[cfg(feature = "sqlx-pg-uint")]
 impl<'r> sqlx::Decode<'r, sqlx::Postgres> for MfaAuthenticatorType {
     fn decode(
         value: <sqlx::Postgres as sqlx::Database>::ValueRef<'r>,
@@ -160,6 +160,9 @@ impl<'r> sqlx::Decode<'r, sqlx::Postgres> for MfaAuthenticatorType {
     }
 }
 
+#[cfg(feature = "sqlx")]
+crate::types::utils::sqlx_u8_enum!(MfaAuthenticatorType);
+
 impl MfaAuthenticatorType {
     /// Converts self into [MfaAuthenticationType]
     pub fn into_authentication_type(self) -> MfaAuthenticationType {
@@ -230,7 +233,7 @@ pub struct MfaVerifySchema {
     pub data: String,
 }
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 impl MfaVerifySchema {
     /// Creates the verify schema from an [MfaChallenge] and data needed to complete it.
     ///
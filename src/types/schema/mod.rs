@@ -7,8 +7,11 @@ pub use audit_log::*;
 pub use auth::*;
 pub use mfa::*;
 pub use channel::*;
+pub use discovery::*;
+pub use gif::*;
 pub use guild::*;
 pub use message::*;
+pub use polyproto::*;
 pub use relationship::*;
 pub use role::*;
 pub use user::*;
@@ -21,8 +24,11 @@ mod audit_log;
 mod auth;
 mod mfa;
 mod channel;
+mod discovery;
+mod gif;
 mod guild;
 mod message;
+mod polyproto;
 mod relationship;
 mod role;
 mod user;
@@ -0,0 +1,86 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+/// The response of the `.well-known/polyproto-core` discovery endpoint of a domain.
+///
+/// # Reference
+/// See <https://docs.polyphony.chat/Protocol%20Specifications/core/#3-federation-discovery>
+pub struct PolyprotoWellKnown {
+    /// The url of the polyproto api of the home server responsible for the domain
+    pub api: String,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+/// A challenge string issued by a home server, which has to be signed to identify.
+///
+/// # Reference
+/// See <https://docs.polyphony.chat/Protocol%20Specifications/core/#61-challenge-strings>
+pub struct ChallengeString {
+    pub challenge: String,
+    /// When the challenge expires, as a UNIX timestamp in seconds
+    pub expires: u64,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+/// The request body for identifying with a signed [ChallengeString].
+pub struct IdentifySchema {
+    /// The challenge string which was signed
+    pub challenge: String,
+    /// The signature of the challenge string
+    pub signature: String,
+    /// The session's ID-Cert, PEM encoded
+    pub id_cert: String,
+    /// Additional, server specific authentication data
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_payload: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+/// The response to identifying with a signed [ChallengeString].
+pub struct IdentifyResponse {
+    /// The session token, used like the token of a password login
+    pub token: String,
+    /// Additional, server specific data
+    #[serde(default)]
+    pub payload: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+/// A schema used to have the home server sign a new ID-Cert for the current session.
+///
+/// # Reference
+/// See <https://docs.polyphony.chat/Protocol%20Specifications/core/#71-id-cert>
+pub struct RotateIdCertSchema {
+    /// The certificate signing request for the new ID-Cert, PEM encoded
+    pub csr: String,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+/// An ID-Cert issued by a home server.
+pub struct IdCertResponse {
+    /// The ID-Cert, PEM encoded
+    pub id_cert: String,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+/// An ID-Cert of an actor, along with how long it may be cached.
+///
+/// # Reference
+/// See <https://docs.polyphony.chat/Protocol%20Specifications/core/#72-caching-of-id-certs>
+pub struct CachedIdCert {
+    /// The ID-Cert, PEM encoded
+    pub id_cert: String,
+    /// Whether the ID-Cert was invalidated before it expired, e.g. because its key leaked
+    #[serde(default)]
+    pub invalidated: bool,
+    /// From when the ID-Cert may be cached, as a UNIX timestamp in seconds
+    pub cache_not_valid_before: u64,
+    /// Until when the ID-Cert may be cached, as a UNIX timestamp in seconds
+    pub cache_not_valid_after: u64,
+    /// The home server's signature over the ID-Cert and the cache timestamps
+    pub cache_signature: String,
+}
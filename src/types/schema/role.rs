@@ -2,7 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use crate::types::{PermissionFlags, Snowflake};
+use crate::types::{PermissionFlags, RoleColors, Snowflake};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
@@ -18,6 +18,11 @@ pub struct RoleCreateModifySchema {
     pub unicode_emoji: Option<String>,
     pub mentionable: Option<bool>,
     pub position: Option<i32>,
+    /// The role's colors; takes priority over [RoleCreateModifySchema::color].
+    ///
+    /// Not sent if `None`, since not all servers support it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub colors: Option<RoleColors>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -28,3 +33,18 @@ pub struct RolePositionUpdateSchema {
     pub id: Snowflake,
     pub position: u16,
 }
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Default)]
+/// Represents the schema which needs to be sent to change a role's icon.
+///
+/// Requires the guild to have the `ROLE_ICONS` feature.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/guild#modify-guild-role>
+pub struct RoleIconModifySchema {
+    /// The new icon, as a [data URI](https://developer.mozilla.org/en-US/docs/Web/HTTP/Basics_of_HTTP/Data_URLs)
+    /// (e.g. `data:image/png;base64,...`).
+    ///
+    /// `None` removes the current icon.
+    pub icon: Option<String>,
+}
@@ -8,9 +8,11 @@ use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::types::{
-    Connection, GuildAffinity, HarvestBackendType, Snowflake, ThemeColors, TwoWayLinkType,
-    UserAffinity,
+    Connection, CustomStatus, FriendSourceFlags, GuildAffinity, GuildFolder, HarvestBackendType,
+    HighlightLevel, MuteConfig, Snowflake, ThemeColors, TwoWayLinkType, UserAffinity,
+    UserNotificationLevel, UserStatus, UserTheme,
 };
+use crate::{UInt16, UInt8};
 
 #[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -137,7 +139,11 @@ pub struct UserModifyProfileSchema {
     pub bio: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    // TODO: Add banner -- do we have an image data struct
+    /// The user's new banner, as a [data URI](https://developer.mozilla.org/en-US/docs/Web/HTTP/Basics_of_HTTP/Data_URLs)
+    /// (e.g. `data:image/png;base64,...`).
+    pub banner: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
     /// The user's new accent color encoded as an i32 representation of a hex color code
     pub accent_color: Option<i32>,
 
@@ -463,3 +469,160 @@ pub struct BurstCreditsInfo {
     /// When the user's burst credits will automatically replenish again
     pub next_replenish_at: DateTime<Utc>,
 }
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+/// A schema used to modify the current user's notification settings for a guild.
+///
+/// Fields which are [None] are left unchanged.
+///
+/// See <https://docs.discord.sex/resources/user-settings#modify-user-guild-settings>
+pub struct ModifyUserGuildSettingsSchema {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub muted: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mute_config: Option<MuteConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mute_scheduled_events: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_notifications: Option<UserNotificationLevel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify_highlights: Option<HighlightLevel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suppress_everyone: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suppress_roles: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mobile_push: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hide_muted_channels: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flags: Option<i32>,
+    /// The channel overrides to modify, by channel id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel_overrides: Option<HashMap<Snowflake, ModifyChannelOverrideSchema>>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+/// Modifies the current user's notification settings for a channel, see
+/// [ModifyUserGuildSettingsSchema::channel_overrides].
+///
+/// Fields which are [None] are left unchanged.
+pub struct ModifyChannelOverrideSchema {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub muted: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mute_config: Option<MuteConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_notifications: Option<UserNotificationLevel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flags: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collapsed: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+/// A schema used to modify the current user's settings.
+///
+/// Fields which are [None] are left unchanged.
+///
+/// Also received in [UserSettingsUpdate](crate::types::UserSettingsUpdate) events, where only
+/// the changed settings are set.
+///
+/// See <https://docs.discord.sex/resources/user-settings#modify-user-settings>
+pub struct UserModifySettingsSchema {
+    /// How long (in seconds) the user needs to be idle before being marked as afk on mobile
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub afk_timeout: Option<UInt16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_accessibility_detection: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub animate_emoji: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub animate_stickers: Option<UInt8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contact_sync_enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub convert_emoticons: Option<bool>,
+    /// The user's new custom status.
+    ///
+    /// `Some(None)` removes the custom status.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    pub custom_status: Option<Option<CustomStatus>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_guilds_restricted: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detect_platform_accounts: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub developer_mode: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disable_games_tab: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enable_tts_command: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explicit_content_filter: Option<UInt8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub friend_source_flags: Option<FriendSourceFlags>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gif_auto_play: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guild_folders: Option<Vec<GuildFolder>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guild_positions: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inline_attachment_media: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inline_embed_media: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_display_compact: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub native_phone_integration_enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub render_embeds: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub render_reactions: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restricted_guilds: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show_current_game: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<UserStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream_notifications_enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<UserTheme>,
+    /// The user's offset from UTC, in minutes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone_offset: Option<i16>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+/// A schema used to modify one of the current user's settings protos.
+///
+/// See <https://docs.discord.sex/resources/user-settings#modify-user-settings-proto>
+pub struct ModifyUserSettingsProtoSchema {
+    /// The base-64 encoded protobuf of the settings to change
+    pub settings: String,
+    /// The version of the client's settings; if the server's are newer, the modification is
+    /// rejected
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required_data_version: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+/// The response to fetching or modifying one of the current user's settings protos.
+///
+/// See <https://docs.discord.sex/resources/user-settings#get-user-settings-proto>
+pub struct UserSettingsProtoResponse {
+    /// The base-64 encoded protobuf of the settings
+    pub settings: String,
+    /// Whether the modification did not change anything, since the server's settings were
+    /// newer than `required_data_version`
+    #[serde(default)]
+    pub out_of_date: Option<bool>,
+}
@@ -0,0 +1,76 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+/// Implements `sqlx::Type`, `sqlx::Encode` and `sqlx::Decode` for MySQL and SQLite for an enum
+/// with a [u8] representation, which also implements `TryFrom<u8>`.
+///
+/// The impls are only emitted if the `sqlx-mysql` / `sqlx-sqlite` features are enabled. The
+/// Postgres impls are written out next to each enum, since they go through `sqlx_pg_uint`.
+macro_rules! sqlx_u8_enum {
+    ($type:ty) => {
+        #[cfg(feature = "sqlx-mysql")]
+        impl ::sqlx::Type<::sqlx_mysql::MySql> for $type {
+            fn type_info() -> ::sqlx_mysql::MySqlTypeInfo {
+                <u8 as ::sqlx::Type<::sqlx_mysql::MySql>>::type_info()
+            }
+
+            fn compatible(ty: &::sqlx_mysql::MySqlTypeInfo) -> bool {
+                <u8 as ::sqlx::Type<::sqlx_mysql::MySql>>::compatible(ty)
+            }
+        }
+
+        #[cfg(feature = "sqlx-mysql")]
+        impl<'q> ::sqlx::Encode<'q, ::sqlx_mysql::MySql> for $type {
+            fn encode_by_ref(
+                &self,
+                buf: &mut <::sqlx_mysql::MySql as ::sqlx::Database>::ArgumentBuffer<'q>,
+            ) -> Result<::sqlx::encode::IsNull, ::sqlx::error::BoxDynError> {
+                <u8 as ::sqlx::Encode<'q, ::sqlx_mysql::MySql>>::encode_by_ref(&(*self as u8), buf)
+            }
+        }
+
+        #[cfg(feature = "sqlx-mysql")]
+        impl<'r> ::sqlx::Decode<'r, ::sqlx_mysql::MySql> for $type {
+            fn decode(
+                value: <::sqlx_mysql::MySql as ::sqlx::Database>::ValueRef<'r>,
+            ) -> Result<Self, ::sqlx::error::BoxDynError> {
+                let value = <u8 as ::sqlx::Decode<'r, ::sqlx_mysql::MySql>>::decode(value)?;
+                Self::try_from(value).map_err(|e| e.into())
+            }
+        }
+
+        #[cfg(feature = "sqlx-sqlite")]
+        impl ::sqlx::Type<::sqlx::Sqlite> for $type {
+            fn type_info() -> ::sqlx::sqlite::SqliteTypeInfo {
+                <u8 as ::sqlx::Type<::sqlx::Sqlite>>::type_info()
+            }
+
+            fn compatible(ty: &::sqlx::sqlite::SqliteTypeInfo) -> bool {
+                <u8 as ::sqlx::Type<::sqlx::Sqlite>>::compatible(ty)
+            }
+        }
+
+        #[cfg(feature = "sqlx-sqlite")]
+        impl<'q> ::sqlx::Encode<'q, ::sqlx::Sqlite> for $type {
+            fn encode_by_ref(
+                &self,
+                buf: &mut <::sqlx::Sqlite as ::sqlx::Database>::ArgumentBuffer<'q>,
+            ) -> Result<::sqlx::encode::IsNull, ::sqlx::error::BoxDynError> {
+                <u8 as ::sqlx::Encode<'q, ::sqlx::Sqlite>>::encode_by_ref(&(*self as u8), buf)
+            }
+        }
+
+        #[cfg(feature = "sqlx-sqlite")]
+        impl<'r> ::sqlx::Decode<'r, ::sqlx::Sqlite> for $type {
+            fn decode(
+                value: <::sqlx::Sqlite as ::sqlx::Database>::ValueRef<'r>,
+            ) -> Result<Self, ::sqlx::error::BoxDynError> {
+                let value = <u8 as ::sqlx::Decode<'r, ::sqlx::Sqlite>>::decode(value)?;
+                Self::try_from(value).map_err(|e| e.into())
+            }
+        }
+    };
+}
+
+pub(crate) use sqlx_u8_enum;
@@ -0,0 +1,174 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::types::Snowflake;
+
+/// A federated identifier of an actor, in the form of `actor@domain`.
+///
+/// Used by polyproto to identify actors across instances. On Spacebar-compatible instances, the
+/// actor part is the [Snowflake] of a user local to `domain`; [FederatedId::from_snowflake] and
+/// [FederatedId::to_snowflake] convert between the two.
+///
+/// # Reference
+/// See <https://docs.polyphony.chat/Protocol%20Specifications/core/#2-federation-ids-fids>
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FederatedId {
+    /// The local part of the id, before the `@`
+    pub actor: String,
+    /// The domain of the instance the actor belongs to
+    pub domain: String,
+}
+
+impl FederatedId {
+    /// Creates a new [FederatedId].
+    ///
+    /// Returns [FederatedIdError] if either part is empty or contains an `@`.
+    pub fn new(actor: &str, domain: &str) -> Result<Self, FederatedIdError> {
+        if actor.is_empty() || actor.contains('@') {
+            return Err(FederatedIdError::InvalidActor {
+                actor: actor.to_string(),
+            });
+        }
+
+        if domain.is_empty() || domain.contains('@') {
+            return Err(FederatedIdError::InvalidDomain {
+                domain: domain.to_string(),
+            });
+        }
+
+        Ok(Self {
+            actor: actor.to_string(),
+            domain: domain.to_lowercase(),
+        })
+    }
+
+    /// Creates a [FederatedId] for an entity with a local [Snowflake] on the instance at `domain`.
+    pub fn from_snowflake(snowflake: Snowflake, domain: &str) -> Self {
+        Self {
+            actor: snowflake.to_string(),
+            domain: domain.to_lowercase(),
+        }
+    }
+
+    /// Returns the [Snowflake] the id maps to, if the actor part is one.
+    ///
+    /// Note that the snowflake is only meaningful on the instance at [FederatedId::domain].
+    pub fn to_snowflake(&self) -> Option<Snowflake> {
+        self.actor.parse::<u64>().ok().map(Snowflake)
+    }
+
+    /// Returns whether the id belongs to the instance at `domain`.
+    pub fn is_local_to(&self, domain: &str) -> bool {
+        self.domain.eq_ignore_ascii_case(domain)
+    }
+}
+
+impl Display for FederatedId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}@{}", self.actor, self.domain)
+    }
+}
+
+impl FromStr for FederatedId {
+    type Err = FederatedIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((actor, domain)) = s.split_once('@') else {
+            return Err(FederatedIdError::MissingSeparator {
+                id: s.to_string(),
+            });
+        };
+
+        Self::new(actor, domain)
+    }
+}
+
+impl TryFrom<&str> for FederatedId {
+    type Error = FederatedIdError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::from_str(value)
+    }
+}
+
+impl From<FederatedId> for String {
+    fn from(value: FederatedId) -> Self {
+        value.to_string()
+    }
+}
+
+impl serde::Serialize for FederatedId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for FederatedId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let string = String::deserialize(deserializer)?;
+        FederatedId::from_str(&string).map_err(serde::de::Error::custom)
+    }
+}
+
+custom_error::custom_error! {
+    /// Errors which can occur while parsing a [FederatedId]
+    #[derive(PartialEq, Eq, Clone, Hash)]
+    pub FederatedIdError
+    MissingSeparator{id: String} = "Federated id {id} is not of the form actor@domain",
+    InvalidActor{actor: String} = "Invalid actor part of a federated id: {actor}",
+    InvalidDomain{domain: String} = "Invalid domain part of a federated id: {domain}",
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::FederatedId;
+    use crate::types::Snowflake;
+
+    #[test]
+    fn parse_and_display() {
+        let id = FederatedId::from_str("xenia@example.com").unwrap();
+        assert_eq!(id.actor, "xenia");
+        assert_eq!(id.domain, "example.com");
+        assert_eq!(id.to_string(), "xenia@example.com");
+
+        assert!(FederatedId::from_str("example.com").is_err());
+        assert!(FederatedId::from_str("@example.com").is_err());
+        assert!(FederatedId::from_str("xenia@").is_err());
+        assert!(FederatedId::from_str("xenia@example@com").is_err());
+    }
+
+    #[test]
+    fn snowflake_roundtrip() {
+        let snowflake = Snowflake(1303390110099968072_u64);
+        let id = FederatedId::from_snowflake(snowflake, "Example.com");
+
+        assert_eq!(id.to_string(), "1303390110099968072@example.com");
+        assert_eq!(id.to_snowflake(), Some(snowflake));
+        assert!(id.is_local_to("example.com"));
+        assert_eq!(
+            FederatedId::from_str("xenia@example.com")
+                .unwrap()
+                .to_snowflake(),
+            None
+        );
+    }
+
+    #[test]
+    fn serde() {
+        let id = FederatedId::from_str("xenia@example.com").unwrap();
+        let serialized = serde_json::to_string(&id).unwrap();
+        assert_eq!(serialized, "\"xenia@example.com\"");
+        assert_eq!(serde_json::from_str::<FederatedId>(&serialized).unwrap(), id);
+    }
+}
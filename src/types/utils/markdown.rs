@@ -0,0 +1,319 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Parsing of the markdown in message content, see [parse_markdown] and [resolve_mentions].
+
+use std::ops::Range;
+
+use regex::{Captures, Regex};
+
+use super::regexes::{
+    CHANNEL_MENTION, CUSTOM_EMOJI, EVERYONE_MENTION, HERE_MENTION, ROLE_MENTION, TIMESTAMP,
+    USER_MENTION,
+};
+use crate::types::{FormattedTimestamp, Guild, Message, PartialEmoji, Snowflake};
+
+/// The marker which opens and closes a markdown code block
+const CODE_FENCE: &str = "```";
+
+#[derive(Debug, Clone, PartialEq)]
+/// A part of message content, as returned by [parse_markdown].
+pub enum MarkdownToken {
+    /// Plain text, which may still contain formatting such as bold or italic text
+    Text(String),
+    /// A `<@id>` mention of a user
+    UserMention(Snowflake),
+    /// A `<@&id>` mention of a role
+    RoleMention(Snowflake),
+    /// A `<#id>` mention of a channel
+    ChannelMention(Snowflake),
+    /// `@everyone`
+    EveryoneMention,
+    /// `@here`
+    HereMention,
+    /// A `<:name:id>` or `<a:name:id>` custom emoji
+    CustomEmoji(PartialEmoji),
+    /// A `<t:timestamp:style>` timestamp, which clients show in the reader's time zone
+    Timestamp(FormattedTimestamp),
+    /// Text wrapped in single or double backticks
+    InlineCode(String),
+    /// A code block, wrapped in triple backticks
+    CodeBlock {
+        /// The language to highlight the code as, if one was given after the opening backticks
+        language: Option<String>,
+        code: String,
+    },
+}
+
+impl MarkdownToken {
+    /// Returns the text to display instead of this token if it is a user, role or channel
+    /// mention which `resolver` knows the name of, such as `@username` or `#channel`.
+    pub fn resolve<R: MentionResolver + ?Sized>(&self, resolver: &R) -> Option<String> {
+        match self {
+            MarkdownToken::UserMention(id) => {
+                resolver.user_name(*id).map(|name| format!("@{name}"))
+            }
+            MarkdownToken::RoleMention(id) => {
+                resolver.role_name(*id).map(|name| format!("@{name}"))
+            }
+            MarkdownToken::ChannelMention(id) => {
+                resolver.channel_name(*id).map(|name| format!("#{name}"))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Looks up the names of mentioned users, roles and channels, see [resolve_mentions].
+///
+/// Implemented for entities which carry the names, such as [Guild] (roles and channels) and
+/// [Message] (mentioned users and channels), as well as for the gateway's cache. Several
+/// resolvers can be combined in a slice, which uses the first name found:
+///
+/// ```
+/// # use chorus::types::{resolve_mentions, Guild, MentionResolver, Message};
+/// # fn example(message: &Message, guild: &Guild) -> String {
+/// let resolvers: &[&dyn MentionResolver] = &[message, guild];
+/// resolve_mentions(message.content.as_deref().unwrap_or_default(), resolvers)
+/// # }
+/// ```
+pub trait MentionResolver {
+    /// Returns the name to display for the user with the given id, if it is known.
+    fn user_name(&self, _id: Snowflake) -> Option<String> {
+        None
+    }
+
+    /// Returns the name of the role with the given id, if it is known.
+    fn role_name(&self, _id: Snowflake) -> Option<String> {
+        None
+    }
+
+    /// Returns the name of the channel with the given id, if it is known.
+    fn channel_name(&self, _id: Snowflake) -> Option<String> {
+        None
+    }
+}
+
+impl MentionResolver for [&dyn MentionResolver] {
+    fn user_name(&self, id: Snowflake) -> Option<String> {
+        self.iter().find_map(|resolver| resolver.user_name(id))
+    }
+
+    fn role_name(&self, id: Snowflake) -> Option<String> {
+        self.iter().find_map(|resolver| resolver.role_name(id))
+    }
+
+    fn channel_name(&self, id: Snowflake) -> Option<String> {
+        self.iter().find_map(|resolver| resolver.channel_name(id))
+    }
+}
+
+impl MentionResolver for Guild {
+    fn role_name(&self, id: Snowflake) -> Option<String> {
+        self.roles.iter().find_map(|role| {
+            #[cfg(feature = "client-core")]
+            let role = role.read().unwrap();
+            (role.id == id).then(|| role.name.clone())
+        })
+    }
+
+    fn channel_name(&self, id: Snowflake) -> Option<String> {
+        self.channels.iter().find_map(|channel| {
+            #[cfg(feature = "client-core")]
+            let channel = channel.read().unwrap();
+            channel.name.clone().filter(|_| channel.id == id)
+        })
+    }
+}
+
+impl MentionResolver for Message {
+    fn user_name(&self, id: Snowflake) -> Option<String> {
+        if let Some(user) = self.mentions.iter().flatten().find(|user| user.id == id) {
+            return Some(user.username.clone());
+        }
+
+        self.author
+            .as_ref()
+            .filter(|author| author.id == id)
+            .and_then(|author| author.username.clone())
+    }
+
+    fn channel_name(&self, id: Snowflake) -> Option<String> {
+        self.mention_channels
+            .iter()
+            .flatten()
+            .find(|channel| channel.id == id)
+            .map(|channel| channel.name.clone())
+    }
+}
+
+/// Parses message content into [MarkdownToken]s.
+///
+/// Mentions, emojis and timestamps inside of code are not parsed; the code is returned as is.
+/// Adjacent text (including malformed mentions) is merged into a single [MarkdownToken::Text].
+pub fn parse_markdown(content: &str) -> Vec<MarkdownToken> {
+    parse_spans(content)
+        .into_iter()
+        .map(|(_, token)| token)
+        .collect()
+}
+
+/// Replaces the user, role and channel mentions in message content with the names `resolver`
+/// knows for them, e.g. for notifications or other places which cannot render mentions.
+///
+/// Mentions which cannot be resolved, mentions in code and all other markdown are left as is.
+pub fn resolve_mentions<R: MentionResolver + ?Sized>(content: &str, resolver: &R) -> String {
+    let mut resolved = String::with_capacity(content.len());
+
+    for (span, token) in parse_spans(content) {
+        match token.resolve(resolver) {
+            Some(name) => resolved.push_str(&name),
+            None => resolved.push_str(&content[span]),
+        }
+    }
+
+    resolved
+}
+
+/// Parses message content into tokens, along with the byte range of the content each token
+/// was parsed from.
+fn parse_spans(content: &str) -> Vec<(Range<usize>, MarkdownToken)> {
+    let mut spans = Vec::new();
+    let mut text_start = 0;
+    let mut position = 0;
+
+    while let Some(offset) = content[position..].find('`') {
+        let start = position + offset;
+        let rest = &content[start..];
+
+        let code = if let Some(after_fence) = rest.strip_prefix(CODE_FENCE) {
+            after_fence
+                .find(CODE_FENCE)
+                .map(|end| (CODE_FENCE.len(), end))
+        } else {
+            let ticks = if rest.starts_with("``") { 2 } else { 1 };
+            rest[ticks..]
+                .find(&rest[..ticks])
+                .filter(|end| *end > 0)
+                .map(|end| (ticks, end))
+        };
+
+        let Some((ticks, length)) = code else {
+            // An unclosed code block or inline code is just text
+            position = start + rest.len() - rest.trim_start_matches('`').len();
+            continue;
+        };
+
+        push_text_spans(&mut spans, content, text_start..start);
+
+        let inner = &rest[ticks..ticks + length];
+        let token = if ticks == CODE_FENCE.len() {
+            code_block(inner)
+        } else {
+            MarkdownToken::InlineCode(inner.to_string())
+        };
+
+        position = start + length + 2 * ticks;
+        spans.push((start..position, token));
+        text_start = position;
+    }
+
+    push_text_spans(&mut spans, content, text_start..content.len());
+    spans
+}
+
+/// Parses the content between the fences of a code block.
+fn code_block(inner: &str) -> MarkdownToken {
+    // The language can only be given if the code starts on the next line
+    if let Some((first_line, code)) = inner.split_once('\n') {
+        let language = first_line.trim();
+        if !language.contains(char::is_whitespace) {
+            return MarkdownToken::CodeBlock {
+                language: (!language.is_empty()).then(|| language.to_string()),
+                code: code.to_string(),
+            };
+        }
+    }
+
+    MarkdownToken::CodeBlock {
+        language: None,
+        code: inner.to_string(),
+    }
+}
+
+type TokenParser = fn(&Captures) -> Option<MarkdownToken>;
+
+/// Tokenizes the mentions, emojis and timestamps in a part of the content which is not code.
+fn push_text_spans(
+    spans: &mut Vec<(Range<usize>, MarkdownToken)>,
+    content: &str,
+    range: Range<usize>,
+) {
+    let patterns: [(&Regex, TokenParser); 7] = [
+        (&USER_MENTION, |captures| {
+            parse_id(&captures[1]).map(MarkdownToken::UserMention)
+        }),
+        (&ROLE_MENTION, |captures| {
+            parse_id(&captures[1]).map(MarkdownToken::RoleMention)
+        }),
+        (&CHANNEL_MENTION, |captures| {
+            parse_id(&captures[1]).map(MarkdownToken::ChannelMention)
+        }),
+        (&EVERYONE_MENTION, |_| Some(MarkdownToken::EveryoneMention)),
+        (&HERE_MENTION, |_| Some(MarkdownToken::HereMention)),
+        (&CUSTOM_EMOJI, |captures| {
+            Some(MarkdownToken::CustomEmoji(PartialEmoji {
+                id: Some(parse_id(&captures[3])?),
+                name: captures[2].to_string(),
+                animated: !captures[1].is_empty(),
+            }))
+        }),
+        (&TIMESTAMP, |captures| {
+            captures[0].parse().ok().map(MarkdownToken::Timestamp)
+        }),
+    ];
+
+    let text = &content[range.clone()];
+    let mut text_start = 0;
+    let mut position = 0;
+
+    while position < text.len() {
+        let next = patterns
+            .iter()
+            .filter_map(|(regex, parse)| Some((regex.captures_at(text, position)?, parse)))
+            .min_by_key(|(captures, _)| captures.get(0).unwrap().start());
+
+        let Some((captures, parse)) = next else {
+            break;
+        };
+        let matched = captures.get(0).unwrap();
+
+        if let Some(token) = parse(&captures) {
+            if text_start < matched.start() {
+                spans.push((
+                    range.start + text_start..range.start + matched.start(),
+                    MarkdownToken::Text(text[text_start..matched.start()].to_string()),
+                ));
+            }
+            spans.push((
+                range.start + matched.start()..range.start + matched.end(),
+                token,
+            ));
+            text_start = matched.end();
+        }
+
+        position = matched.end();
+    }
+
+    if text_start < text.len() {
+        spans.push((
+            range.start + text_start..range.end,
+            MarkdownToken::Text(text[text_start..].to_string()),
+        ));
+    }
+}
+
+fn parse_id(id: &str) -> Option<Snowflake> {
+    id.parse::<u64>().ok().map(Snowflake)
+}
@@ -0,0 +1,184 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+/// The marker which opens and closes a markdown code block
+const CODE_FENCE: &str = "```";
+
+/// Splits message content into chunks of at most `max_characters` characters each.
+///
+/// Content is preferably broken at line boundaries, then at word boundaries; only single words
+/// longer than a whole message are broken in the middle, as are chunks which can't fit their
+/// code fences.
+///
+/// If a code block has to be broken, it is closed at the end of one chunk and reopened (with
+/// the same language) at the start of the next, so every chunk renders correctly on its own.
+///
+/// Characters are counted as unicode scalar values.
+pub fn split_message_content(content: &str, max_characters: usize) -> Vec<String> {
+    if content.chars().count() <= max_characters {
+        return vec![content.to_string()];
+    }
+
+    let mut splitter = ContentSplitter {
+        max_characters,
+        chunks: Vec::new(),
+        current: String::new(),
+        current_length: 0,
+        has_content: false,
+        open_fence: None,
+    };
+
+    for line in content.split_inclusive('\n') {
+        splitter.push_line(line);
+    }
+
+    splitter.finish()
+}
+
+struct ContentSplitter {
+    max_characters: usize,
+    chunks: Vec<String>,
+    current: String,
+    /// The length of `current`, in characters
+    current_length: usize,
+    /// Whether `current` contains more than a reopened code fence
+    has_content: bool,
+    /// The line which opened the code block we are currently in, if any
+    open_fence: Option<String>,
+}
+
+impl ContentSplitter {
+    fn push_line(&mut self, line: &str) {
+        let fence_after = if line.trim_start().starts_with(CODE_FENCE) {
+            match self.open_fence {
+                Some(_) => None,
+                None => Some(line.trim().to_string()),
+            }
+        } else {
+            self.open_fence.clone()
+        };
+
+        // If we end this line inside a code block, we need room to close it
+        let reserved = if fence_after.is_some() {
+            CODE_FENCE.len() + 1
+        } else {
+            0
+        };
+
+        let length = line.chars().count();
+
+        if !self.fits(length, reserved) && self.has_content {
+            self.flush();
+        }
+
+        if self.fits(length, reserved) {
+            self.append(line, length);
+        } else {
+            for word in line.split_inclusive(' ') {
+                self.push_word(word, reserved);
+            }
+        }
+
+        self.open_fence = fence_after;
+    }
+
+    fn push_word(&mut self, word: &str, reserved: usize) {
+        let length = word.chars().count();
+
+        if !self.fits(length, reserved) && self.has_content {
+            self.flush();
+        }
+
+        if self.fits(length, reserved) {
+            self.append(word, length);
+            return;
+        }
+
+        // The word is longer than a whole message
+        let mut chars = word.chars().peekable();
+        while chars.peek().is_some() {
+            let budget = self
+                .max_characters
+                .saturating_sub(self.current_length + reserved)
+                .max(1);
+
+            let piece: String = chars.by_ref().take(budget).collect();
+            let piece_length = piece.chars().count();
+            self.append(&piece, piece_length);
+
+            if chars.peek().is_some() {
+                self.flush();
+            }
+        }
+    }
+
+    fn fits(&self, length: usize, reserved: usize) -> bool {
+        self.current_length + length + reserved <= self.max_characters
+    }
+
+    fn append(&mut self, text: &str, length: usize) {
+        self.current.push_str(text);
+        self.current_length += length;
+        self.has_content = true;
+    }
+
+    /// Finishes the current chunk, closing and reopening the current code block if needed.
+    fn flush(&mut self) {
+        let mut chunk = std::mem::take(&mut self.current);
+        self.current_length = 0;
+        self.has_content = false;
+
+        if let Some(fence) = &self.open_fence {
+            let trimmed = chunk.trim_end();
+            let last_line_start = trimmed.rfind('\n').map_or(0, |index| index + 1);
+
+            if trimmed[last_line_start..].trim() == fence {
+                // Nothing is in the code block yet, move it to the next chunk entirely
+                chunk.truncate(last_line_start);
+            } else {
+                if !chunk.ends_with('\n') {
+                    chunk.push('\n');
+                }
+                chunk.push_str(CODE_FENCE);
+            }
+        }
+
+        if !chunk.is_empty() {
+            self.chunks.push(chunk);
+        }
+
+        if let Some(fence) = &self.open_fence {
+            self.current = format!("{}\n", fence);
+            self.current_length = self.current.chars().count();
+        }
+    }
+
+    fn finish(mut self) -> Vec<String> {
+        if self.has_content {
+            let chunk = std::mem::take(&mut self.current);
+            self.chunks.push(chunk);
+        }
+
+        // With very small limits, the code fences we close and reopen may not fit into a chunk;
+        // those chunks are broken regardless of their markdown
+        let max_characters = self.max_characters.max(1);
+        self.chunks
+            .into_iter()
+            .flat_map(|chunk| hard_split(chunk, max_characters))
+            .collect()
+    }
+}
+
+/// Breaks `chunk` into pieces of at most `max_characters` characters each.
+fn hard_split(chunk: String, max_characters: usize) -> Vec<String> {
+    if chunk.chars().count() <= max_characters {
+        return vec![chunk];
+    }
+
+    let chars: Vec<char> = chunk.chars().collect();
+    chars
+        .chunks(max_characters)
+        .map(|piece| piece.iter().collect())
+        .collect()
+}
@@ -3,14 +3,28 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 #![allow(unused_imports)]
+pub use federated_id::*;
+pub use markdown::*;
+pub use message_content::split_message_content;
 pub use opcode::*;
 pub use regexes::*;
 pub use rights::Rights;
 pub use snowflake::{Snowflake, OneOrMoreSnowflakes};
+pub use timestamp::*;
+#[cfg(feature = "backend")]
+pub use snowflake::{SnowflakeGenerator, SnowflakeGeneratorBuilder};
+#[cfg(feature = "sqlx")]
+pub(crate) use database::sqlx_u8_enum;
 
+#[cfg(feature = "sqlx")]
+mod database;
+mod federated_id;
 pub mod jwt;
+pub mod markdown;
+mod message_content;
 pub mod opcode;
 mod regexes;
 mod rights;
 pub mod serde;
 mod snowflake;
+mod timestamp;
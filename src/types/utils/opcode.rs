@@ -226,6 +226,55 @@ impl TryFrom<u16> for CloseCode {
     }
 }
 
+impl CloseCode {
+    /// Returns how to recover after the gateway closed the connection with this code.
+    ///
+    /// # Reference
+    /// See the "Reconnect" column of
+    /// <https://docs.discord.sex/topics/opcodes-and-status-codes#gateway-close-event-codes>
+    pub fn recovery(&self) -> SessionRecovery {
+        match self {
+            CloseCode::UnknownError
+            | CloseCode::UnknownOpcode
+            | CloseCode::DecodeError
+            | CloseCode::AlreadyAuthenticated
+            | CloseCode::RateLimited => SessionRecovery::Resume,
+            // We never had a session, or it is gone
+            CloseCode::NotAuthenticated
+            | CloseCode::SessionNoLongerValid
+            | CloseCode::InvalidSeq
+            | CloseCode::SessionTimeout => SessionRecovery::Reidentify,
+            CloseCode::AuthenticationFailed
+            | CloseCode::InvalidShard
+            | CloseCode::ShardingRequired
+            | CloseCode::InvalidApiVersion
+            | CloseCode::InvalidIntents
+            | CloseCode::DisallowedIntents => SessionRecovery::Abort,
+        }
+    }
+
+    /// Whether the session can be resumed after the gateway closed the connection with this
+    /// code, see [CloseCode::recovery]
+    pub fn is_resumable(&self) -> bool {
+        self.recovery() == SessionRecovery::Resume
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+/// How to recover after a gateway connection was closed.
+///
+/// See [CloseCode::recovery] and
+/// [GatewayError::recovery](crate::errors::GatewayError::recovery).
+pub enum SessionRecovery {
+    /// Reconnect and resume the session with a [GatewayResume](crate::types::GatewayResume)
+    Resume,
+    /// Reconnect and start a new session with an identify; the old session can not be resumed
+    Reidentify,
+    /// Don't reconnect; it would fail the same way until the token, shards or intents are
+    /// changed
+    Abort,
+}
+
 #[repr(u16)]
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Serialize, Deserialize)]
 /// When the voice gateway server closes your connection, it tells you what happened throught a close code.
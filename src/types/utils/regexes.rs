@@ -8,9 +8,11 @@ use regex::Regex;
 lazy_static! {
     static ref DOUBLE_WHITE_SPACE_RE: Regex = Regex::new(r"\s\s+").unwrap();
     static ref SPECIAL_CHAR: Regex = Regex::new(r"@#`:\r\n\t\f\v\p{C}").unwrap();
-    static ref CHANNEL_MENTION: Regex = Regex::new(r"<#(\d+)>").unwrap();
-    static ref USER_MENTION: Regex = Regex::new(r"<@!?(\d+)>").unwrap();
-    static ref ROLE_MENTION: Regex = Regex::new(r"<@&(\d+)>").unwrap();
-    static ref EVERYONE_MENTION: Regex = Regex::new(r"@everyone").unwrap();
-    static ref HERE_MENTION: Regex = Regex::new(r"@here").unwrap();
+    pub(crate) static ref CHANNEL_MENTION: Regex = Regex::new(r"<#(\d+)>").unwrap();
+    pub(crate) static ref USER_MENTION: Regex = Regex::new(r"<@!?(\d+)>").unwrap();
+    pub(crate) static ref ROLE_MENTION: Regex = Regex::new(r"<@&(\d+)>").unwrap();
+    pub(crate) static ref EVERYONE_MENTION: Regex = Regex::new(r"@everyone").unwrap();
+    pub(crate) static ref HERE_MENTION: Regex = Regex::new(r"@here").unwrap();
+    pub(crate) static ref CUSTOM_EMOJI: Regex = Regex::new(r"<(a?):(\w+):(\d+)>").unwrap();
+    pub(crate) static ref TIMESTAMP: Regex = Regex::new(r"<t:(-?\d+)(?::([tTdDfFR]))?>").unwrap();
 }
@@ -7,6 +7,9 @@ use std::{
     sync::atomic::{AtomicUsize, Ordering},
 };
 
+#[cfg(feature = "backend")]
+use std::sync::atomic::AtomicU16;
+
 use serde::{Serialize, Deserialize};
 
 use chrono::{DateTime, TimeZone, Utc};
@@ -14,8 +17,18 @@ use chrono::{DateTime, TimeZone, Utc};
 /// 2015-01-01
 const EPOCH: i64 = 1420070400000;
 
+const WORKER_ID_BITS: u64 = 0x3E0000;
+const PROCESS_ID_BITS: u64 = 0x1F000;
+const INCREMENT_BITS: u64 = 0xFFF;
+
 /// Unique identifier including a timestamp.
 ///
+/// A snowflake is made up of, from the most to the least significant bits:
+/// - 42 bits of milliseconds since 2015-01-01 ([Snowflake::timestamp])
+/// - 5 bits of internal worker id ([Snowflake::worker_id])
+/// - 5 bits of internal process id ([Snowflake::process_id])
+/// - 12 bits of increment, for every id generated on that process ([Snowflake::increment])
+///
 /// # Reference
 /// See <https://discord.com/developers/docs/reference#snowflakes>
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -23,17 +36,31 @@ pub struct Snowflake(pub u64);
 
 impl Snowflake {
     /// Generates a snowflake for the current timestamp, with worker id 0 and process id 1.
+    ///
+    /// To generate snowflakes with other worker or process ids, see `SnowflakeGenerator`
+    /// (requires the `backend` feature).
     pub fn generate() -> Self {
-        const WORKER_ID: u64 = 0;
-        const PROCESS_ID: u64 = 1;
+        const WORKER_ID: u8 = 0;
+        const PROCESS_ID: u8 = 1;
         static INCREMENT: AtomicUsize = AtomicUsize::new(0);
 
-        let time = (Utc::now().naive_utc().and_utc().timestamp_millis() - EPOCH) << 22;
-        let worker = WORKER_ID << 17;
-        let process = PROCESS_ID << 12;
         let increment = INCREMENT.fetch_add(1, Ordering::Relaxed) as u64 % 32;
 
-        Self(time as u64 | worker | process | increment)
+        Self::from_parts(Utc::now(), WORKER_ID, PROCESS_ID, increment)
+    }
+
+    /// Assembles a snowflake, truncating every part to its size.
+    ///
+    /// Times before the snowflake epoch are saturated to the epoch.
+    fn from_parts(timestamp: DateTime<Utc>, worker_id: u8, process_id: u8, increment: u64) -> Self {
+        let time = (timestamp.timestamp_millis() - EPOCH).max(0) as u64;
+
+        Self(
+            time << 22
+                | ((worker_id as u64) << 17) & WORKER_ID_BITS
+                | ((process_id as u64) << 12) & PROCESS_ID_BITS
+                | increment & INCREMENT_BITS,
+        )
     }
 
     /// Returns the snowflake's timestamp
@@ -41,6 +68,22 @@ impl Snowflake {
         Utc.timestamp_millis_opt((self.0 >> 22) as i64 + EPOCH)
             .unwrap()
     }
+
+    /// Returns the id of the internal worker which generated the snowflake
+    pub fn worker_id(self) -> u8 {
+        ((self.0 & WORKER_ID_BITS) >> 17) as u8
+    }
+
+    /// Returns the id of the internal process which generated the snowflake
+    pub fn process_id(self) -> u8 {
+        ((self.0 & PROCESS_ID_BITS) >> 12) as u8
+    }
+
+    /// Returns the snowflake's increment, which is raised for every snowflake generated on
+    /// its process
+    pub fn increment(self) -> u16 {
+        (self.0 & INCREMENT_BITS) as u16
+    }
 }
 
 impl Default for Snowflake {
@@ -67,6 +110,115 @@ impl From<Snowflake> for u64 {
     }
 }
 
+/// Creates the lowest snowflake with the given timestamp.
+///
+/// Useful for paginating by time, for example with a
+/// [ChannelMessagesAnchor](crate::types::ChannelMessagesAnchor).
+///
+/// Times before the snowflake epoch (2015-01-01) are saturated to the epoch.
+impl From<DateTime<Utc>> for Snowflake {
+    fn from(item: DateTime<Utc>) -> Self {
+        Self::from_parts(item, 0, 0, 0)
+    }
+}
+
+impl From<Snowflake> for DateTime<Utc> {
+    fn from(item: Snowflake) -> Self {
+        item.timestamp()
+    }
+}
+
+#[cfg(feature = "backend")]
+/// Generates snowflakes for a specific worker and process.
+///
+/// Unlike [Snowflake::generate], every generator keeps its own increment, so ids from
+/// different generators only stay unique if they use different worker or process ids.
+///
+/// # Example
+/// ```
+/// # use chorus::types::SnowflakeGenerator;
+/// let generator = SnowflakeGenerator::builder().worker_id(2).process_id(5).build();
+///
+/// let snowflake = generator.generate();
+/// assert_eq!(snowflake.worker_id(), 2);
+/// assert_eq!(snowflake.process_id(), 5);
+/// ```
+#[derive(Debug, Default)]
+pub struct SnowflakeGenerator {
+    worker_id: u8,
+    process_id: u8,
+    increment: AtomicU16,
+}
+
+#[cfg(feature = "backend")]
+impl SnowflakeGenerator {
+    /// Starts building a generator, with worker id 0 and process id 0.
+    pub fn builder() -> SnowflakeGeneratorBuilder {
+        SnowflakeGeneratorBuilder::default()
+    }
+
+    /// Generates a snowflake for the current timestamp.
+    pub fn generate(&self) -> Snowflake {
+        self.generate_at(Utc::now())
+    }
+
+    /// Generates a snowflake for the given timestamp.
+    pub fn generate_at(&self, timestamp: DateTime<Utc>) -> Snowflake {
+        let increment = self.increment.fetch_add(1, Ordering::Relaxed) as u64;
+
+        Snowflake::from_parts(timestamp, self.worker_id, self.process_id, increment)
+    }
+
+    /// Returns the worker id of generated snowflakes
+    pub fn worker_id(&self) -> u8 {
+        self.worker_id
+    }
+
+    /// Returns the process id of generated snowflakes
+    pub fn process_id(&self) -> u8 {
+        self.process_id
+    }
+}
+
+#[cfg(feature = "backend")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// Builds a [SnowflakeGenerator].
+pub struct SnowflakeGeneratorBuilder {
+    worker_id: u8,
+    process_id: u8,
+}
+
+#[cfg(feature = "backend")]
+impl SnowflakeGeneratorBuilder {
+    /// Sets the worker id of generated snowflakes.
+    ///
+    /// # Panics
+    /// If the id does not fit in 5 bits (is larger than 31)
+    pub fn worker_id(mut self, worker_id: u8) -> Self {
+        assert!(worker_id < 32, "Snowflake worker ids must be below 32");
+        self.worker_id = worker_id;
+        self
+    }
+
+    /// Sets the process id of generated snowflakes.
+    ///
+    /// # Panics
+    /// If the id does not fit in 5 bits (is larger than 31)
+    pub fn process_id(mut self, process_id: u8) -> Self {
+        assert!(process_id < 32, "Snowflake process ids must be below 32");
+        self.process_id = process_id;
+        self
+    }
+
+    pub fn build(self) -> SnowflakeGenerator {
+        SnowflakeGenerator {
+            worker_id: self.worker_id,
+            process_id: self.process_id,
+            increment: AtomicU16::new(0),
+        }
+    }
+}
+
 impl serde::Serialize for Snowflake {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -103,21 +255,21 @@ impl<'de> serde::Deserialize<'de> for Snowflake {
     }
 }
 
-#[cfg(feature = "sqlx")]
+#[cfg(feature = "sqlx-pg-uint")]
 impl sqlx::Type<sqlx::Postgres> for Snowflake {
     fn type_info() -> <sqlx::Postgres as sqlx::Database>::TypeInfo {
         <sqlx_pg_uint::PgU64 as sqlx::Type<sqlx::Postgres>>::type_info()
     }
 }
 
-#[cfg(feature = "sqlx")]
+#[cfg(feature = "sqlx-pg-uint")]
 impl sqlx::postgres::PgHasArrayType for Snowflake {
     fn array_type_info() -> sqlx::postgres::PgTypeInfo {
         <Vec<sqlx_pg_uint::PgU64> as sqlx::Type<sqlx::Postgres>>::type_info()
     }
 }
 
-#[cfg(feature = "sqlx")]
+#[cfg(feature = "sqlx-pg-uint")]
 impl<'q> sqlx::Encode<'q, sqlx::Postgres> for Snowflake {
     fn encode_by_ref(
         &self,
@@ -130,7 +282,7 @@ impl<'q> sqlx::Encode<'q, sqlx::Postgres> for Snowflake {
     }
 }
 
-#[cfg(feature = "sqlx")]
+#[cfg(feature = "sqlx-pg-uint")]
 impl<'d> sqlx::Decode<'d, sqlx::Postgres> for Snowflake {
     fn decode(
         value: <sqlx::Postgres as sqlx::Database>::ValueRef<'d>,
@@ -140,6 +292,67 @@ impl<'d> sqlx::Decode<'d, sqlx::Postgres> for Snowflake {
     }
 }
 
+#[cfg(feature = "sqlx-mysql")]
+impl sqlx::Type<sqlx_mysql::MySql> for Snowflake {
+    fn type_info() -> sqlx_mysql::MySqlTypeInfo {
+        <u64 as sqlx::Type<sqlx_mysql::MySql>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx_mysql::MySqlTypeInfo) -> bool {
+        <u64 as sqlx::Type<sqlx_mysql::MySql>>::compatible(ty)
+    }
+}
+
+#[cfg(feature = "sqlx-mysql")]
+impl<'q> sqlx::Encode<'q, sqlx_mysql::MySql> for Snowflake {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <sqlx_mysql::MySql as sqlx::Database>::ArgumentBuffer<'q>,
+    ) -> Result<sqlx::encode::IsNull, sqlx::error::BoxDynError> {
+        <u64 as sqlx::Encode<'q, sqlx_mysql::MySql>>::encode_by_ref(&self.0, buf)
+    }
+}
+
+#[cfg(feature = "sqlx-mysql")]
+impl<'d> sqlx::Decode<'d, sqlx_mysql::MySql> for Snowflake {
+    fn decode(
+        value: <sqlx_mysql::MySql as sqlx::Database>::ValueRef<'d>,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        <u64 as sqlx::Decode<'d, sqlx_mysql::MySql>>::decode(value).map(Snowflake)
+    }
+}
+
+#[cfg(feature = "sqlx-sqlite")]
+impl sqlx::Type<sqlx::Sqlite> for Snowflake {
+    fn type_info() -> sqlx::sqlite::SqliteTypeInfo {
+        <i64 as sqlx::Type<sqlx::Sqlite>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::sqlite::SqliteTypeInfo) -> bool {
+        <i64 as sqlx::Type<sqlx::Sqlite>>::compatible(ty)
+    }
+}
+
+#[cfg(feature = "sqlx-sqlite")]
+impl<'q> sqlx::Encode<'q, sqlx::Sqlite> for Snowflake {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <sqlx::Sqlite as sqlx::Database>::ArgumentBuffer<'q>,
+    ) -> Result<sqlx::encode::IsNull, sqlx::error::BoxDynError> {
+        // SQLite only has signed integers; snowflakes don't use the highest bit before 2084
+        <i64 as sqlx::Encode<'q, sqlx::Sqlite>>::encode_by_ref(&(self.0 as i64), buf)
+    }
+}
+
+#[cfg(feature = "sqlx-sqlite")]
+impl<'d> sqlx::Decode<'d, sqlx::Sqlite> for Snowflake {
+    fn decode(
+        value: <sqlx::Sqlite as sqlx::Database>::ValueRef<'d>,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        <i64 as sqlx::Decode<'d, sqlx::Sqlite>>::decode(value).map(|s| Snowflake(s as u64))
+    }
+}
+
 /// A type representing either a single [Snowflake] or a [Vec] of [Snowflake]s.
 ///
 /// Useful for e.g. [RequestGuildMembers](crate::types::events::GatewayRequestGuildMembers), to
@@ -225,6 +438,48 @@ mod test {
         assert_eq!(snow.timestamp(), timestamp);
     }
 
+    #[test]
+    fn parts() {
+        let snow = Snowflake(175928847299117063);
+        assert_eq!(snow.worker_id(), 1);
+        assert_eq!(snow.process_id(), 0);
+        assert_eq!(snow.increment(), 7);
+
+        let generated = Snowflake::generate();
+        assert_eq!(generated.worker_id(), 0);
+        assert_eq!(generated.process_id(), 1);
+    }
+
+    #[test]
+    fn datetime_conversion() {
+        let timestamp = "2016-04-30 11:18:25.796Z".parse::<DateTime<Utc>>().unwrap();
+        let snow = Snowflake::from(timestamp);
+        assert_eq!(snow, Snowflake(175928847298985984));
+        assert_eq!(DateTime::<Utc>::from(snow), timestamp);
+
+        let before_epoch = "2010-01-01 00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(Snowflake::from(before_epoch), Snowflake(0));
+    }
+
+    #[cfg(feature = "backend")]
+    #[test]
+    fn generator() {
+        use super::SnowflakeGenerator;
+
+        let generator = SnowflakeGenerator::builder()
+            .worker_id(3)
+            .process_id(31)
+            .build();
+        let timestamp = "2016-04-30 11:18:25.796Z".parse::<DateTime<Utc>>().unwrap();
+
+        let snow_1 = generator.generate_at(timestamp);
+        let snow_2 = generator.generate_at(timestamp);
+        assert_eq!(snow_1.timestamp(), timestamp);
+        assert_eq!(snow_1.worker_id(), 3);
+        assert_eq!(snow_1.process_id(), 31);
+        assert_eq!(snow_1.increment() + 1, snow_2.increment());
+    }
+
 	 #[test]
 	 fn serialize() {
 		  let snowflake = Snowflake(1303390110099968072_u64);
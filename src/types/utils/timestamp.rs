@@ -0,0 +1,204 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::{fmt::Display, str::FromStr};
+
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+
+/// A `<t:timestamp:style>` marker in message content, which clients display as a point in time
+/// in the reader's time zone and locale.
+///
+/// Create one from any [chrono] date time and embed it with [Display], or parse one from
+/// message content with [FromStr]:
+///
+/// ```
+/// # use chorus::types::{FormattedTimestamp, TimestampStyle};
+/// # use chrono::DateTime;
+/// let time = DateTime::from_timestamp(1618953630, 0).unwrap();
+/// let timestamp = FormattedTimestamp::new(time, TimestampStyle::Relative);
+/// assert_eq!(format!("Ends {timestamp}"), "Ends <t:1618953630:R>");
+///
+/// let parsed: FormattedTimestamp = "<t:1618953630:R>".parse().unwrap();
+/// assert_eq!(parsed, timestamp);
+/// ```
+///
+/// Only whole seconds are kept.
+///
+/// # Reference
+/// See <https://docs.discord.sex/reference#message-formatting>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FormattedTimestamp {
+    pub timestamp: DateTime<Utc>,
+    pub style: TimestampStyle,
+}
+
+impl FormattedTimestamp {
+    /// Creates a new [FormattedTimestamp], dropping the sub-second part of `timestamp`.
+    pub fn new<Tz: TimeZone>(timestamp: DateTime<Tz>, style: TimestampStyle) -> Self {
+        let seconds = timestamp.timestamp();
+        Self {
+            timestamp: DateTime::from_timestamp(seconds, 0)
+                .expect("whole seconds of a valid date time are valid"),
+            style,
+        }
+    }
+
+    /// Returns the timestamp with a different [TimestampStyle].
+    pub fn with_style(self, style: TimestampStyle) -> Self {
+        Self { style, ..self }
+    }
+}
+
+impl Display for FormattedTimestamp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<t:{}:{}>", self.timestamp.timestamp(), self.style)
+    }
+}
+
+impl FromStr for FormattedTimestamp {
+    type Err = FormattedTimestampError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid_format = || FormattedTimestampError::InvalidFormat {
+            timestamp: s.to_string(),
+        };
+
+        let inner = s
+            .strip_prefix("<t:")
+            .and_then(|inner| inner.strip_suffix('>'))
+            .ok_or_else(invalid_format)?;
+
+        let (seconds, style) = match inner.split_once(':') {
+            Some((seconds, style)) => (seconds, style.parse()?),
+            None => (inner, TimestampStyle::default()),
+        };
+
+        if seconds.is_empty() || seconds.starts_with('+') {
+            return Err(invalid_format());
+        }
+        let seconds = seconds.parse::<i64>().map_err(|_| invalid_format())?;
+        let timestamp = DateTime::from_timestamp(seconds, 0)
+            .ok_or(FormattedTimestampError::OutOfRange { seconds })?;
+
+        Ok(Self { timestamp, style })
+    }
+}
+
+impl TryFrom<&str> for FormattedTimestamp {
+    type Error = FormattedTimestampError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::from_str(value)
+    }
+}
+
+impl<Tz: TimeZone> From<DateTime<Tz>> for FormattedTimestamp {
+    /// Uses the default [TimestampStyle::ShortDateTime] style.
+    fn from(value: DateTime<Tz>) -> Self {
+        Self::new(value, TimestampStyle::default())
+    }
+}
+
+impl From<NaiveDateTime> for FormattedTimestamp {
+    /// Interprets the date time as UTC and uses the default [TimestampStyle::ShortDateTime]
+    /// style.
+    fn from(value: NaiveDateTime) -> Self {
+        Self::new(value.and_utc(), TimestampStyle::default())
+    }
+}
+
+impl From<FormattedTimestamp> for DateTime<Utc> {
+    fn from(value: FormattedTimestamp) -> Self {
+        value.timestamp
+    }
+}
+
+impl From<FormattedTimestamp> for String {
+    fn from(value: FormattedTimestamp) -> Self {
+        value.to_string()
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+/// How a [FormattedTimestamp] is displayed.
+///
+/// # Reference
+/// See <https://docs.discord.sex/reference#timestamp-styles>
+pub enum TimestampStyle {
+    /// `16:20`
+    ShortTime,
+    /// `16:20:30`
+    LongTime,
+    /// `20/04/2021`
+    ShortDate,
+    /// `20 April 2021`
+    LongDate,
+    /// `20 April 2021 16:20`
+    #[default]
+    ShortDateTime,
+    /// `Tuesday, 20 April 2021 16:20`
+    LongDateTime,
+    /// `2 months ago`
+    Relative,
+}
+
+impl TimestampStyle {
+    /// Returns the style with the given format character, such as `R` for
+    /// [TimestampStyle::Relative].
+    pub fn from_char(style: char) -> Option<Self> {
+        match style {
+            't' => Some(Self::ShortTime),
+            'T' => Some(Self::LongTime),
+            'd' => Some(Self::ShortDate),
+            'D' => Some(Self::LongDate),
+            'f' => Some(Self::ShortDateTime),
+            'F' => Some(Self::LongDateTime),
+            'R' => Some(Self::Relative),
+            _ => None,
+        }
+    }
+
+    /// Returns the format character of the style.
+    pub fn as_char(&self) -> char {
+        match self {
+            Self::ShortTime => 't',
+            Self::LongTime => 'T',
+            Self::ShortDate => 'd',
+            Self::LongDate => 'D',
+            Self::ShortDateTime => 'f',
+            Self::LongDateTime => 'F',
+            Self::Relative => 'R',
+        }
+    }
+}
+
+impl Display for TimestampStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_char())
+    }
+}
+
+impl FromStr for TimestampStyle {
+    type Err = FormattedTimestampError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(style), None) => Self::from_char(style),
+            _ => None,
+        }
+        .ok_or_else(|| FormattedTimestampError::InvalidStyle {
+            style: s.to_string(),
+        })
+    }
+}
+
+custom_error::custom_error! {
+    /// Errors which can occur while parsing a [FormattedTimestamp]
+    #[derive(PartialEq, Eq, Clone, Hash)]
+    pub FormattedTimestampError
+    InvalidFormat{timestamp: String} = "Timestamp {timestamp} is not of the form <t:seconds:style>",
+    InvalidStyle{style: String} = "Invalid timestamp style: {style}",
+    OutOfRange{seconds: i64} = "Timestamp {seconds} is out of the supported range",
+}
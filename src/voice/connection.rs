@@ -0,0 +1,426 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A high level voice connection, which wires the gateway, voice gateway and UDP components
+//! together.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use log::*;
+use tokio::sync::{mpsc, oneshot, RwLock};
+use tokio::time::timeout;
+
+use crate::errors::{VoiceConnectionError, VoiceUdpError};
+use crate::gateway::{GatewayHandle, Observer};
+use crate::instance::ChorusUser;
+use crate::types::{
+    SelectProtocol, SelectProtocolData, SessionDescription, Snowflake, Speaking, SpeakingBitflags,
+    SsrcDefinition, UpdateVoiceState, VideoCodec, VoiceCodec, VoiceEncryptionMode, VoiceProtocol,
+    VoiceReady, VoiceServerUpdate, VoiceStateUpdate, WebSocketEvent,
+};
+use crate::voice::gateway::{VoiceGateway, VoiceGatewayHandle};
+use crate::voice::receive::{ReceivedAudio, VoiceReceiver};
+use crate::voice::udp::{UdpHandle, UdpHandler, VoiceStatistics};
+use crate::voice::voice_data::VoiceData;
+use crate::voice::VoiceOptions;
+
+/// How long [ChorusUser::join_voice] waits for each step of the connection handshake
+pub const VOICE_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The encryption modes chorus implements, most preferred first
+const SUPPORTED_ENCRYPTION_MODES: [VoiceEncryptionMode; 3] = [
+    VoiceEncryptionMode::Xsalsa20Poly1305Lite,
+    VoiceEncryptionMode::Xsalsa20Poly1305Suffix,
+    VoiceEncryptionMode::Xsalsa20Poly1305,
+];
+
+impl ChorusUser {
+    /// Joins a voice channel and establishes a ready to use [VoiceConnection].
+    ///
+    /// `guild_id` should be `None` to join a DM or group DM call.
+    ///
+    /// This updates the user's voice state, connects to the voice gateway, performs IP discovery
+    /// over UDP and selects an encryption mode. Each of these steps is given
+    /// [VOICE_CONNECT_TIMEOUT] to complete.
+    ///
+    /// For more control over the connection, see the [gateway](crate::voice::gateway) and
+    /// [udp](crate::voice::udp) modules.
+    ///
+    /// Uses the default [VoiceOptions]; see [ChorusUser::join_voice_with_options].
+    pub async fn join_voice(
+        &self,
+        guild_id: Option<Snowflake>,
+        channel_id: Snowflake,
+    ) -> Result<VoiceConnection, VoiceConnectionError> {
+        self.join_voice_with_options(guild_id, channel_id, VoiceOptions::default())
+            .await
+    }
+
+    /// Joins a voice channel and establishes a ready to use [VoiceConnection], with the given
+    /// [VoiceOptions], e.g. to bind the UDP socket to a specific local address.
+    ///
+    /// See [ChorusUser::join_voice].
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "chorus.voice.join",
+            skip(self),
+            fields(user_id = %self.object.read().unwrap().id)
+        )
+    )]
+    pub async fn join_voice_with_options(
+        &self,
+        guild_id: Option<Snowflake>,
+        channel_id: Snowflake,
+        options: VoiceOptions,
+    ) -> Result<VoiceConnection, VoiceConnectionError> {
+        let Some(main_gateway) = self.gateway.clone() else {
+            return Err(VoiceConnectionError::NoGateway);
+        };
+
+        let user_id = self.object.read().unwrap().id;
+        let server_id = guild_id.unwrap_or(channel_id);
+
+        // Subscribe before updating the voice state, so we can't miss the responses
+        let (state_observer, state_receive) =
+            OneshotObserver::new(move |update: &VoiceStateUpdate| {
+                update.state.user_id == user_id && update.state.guild_id == guild_id
+            });
+        let (server_observer, server_receive) =
+            OneshotObserver::new(move |update: &VoiceServerUpdate| {
+                update.server_id() == Some(server_id)
+            });
+
+        {
+            let mut events = main_gateway.events.lock().await;
+            events.voice.state_update.subscribe(state_observer.clone());
+            events
+                .voice
+                .server_update
+                .subscribe(server_observer.clone());
+        }
+
+        main_gateway
+            .send_update_voice_state(UpdateVoiceState {
+                guild_id,
+                channel_id: Some(channel_id),
+                self_mute: false,
+                self_deaf: false,
+            })
+            .await;
+
+        let state_result = receive(state_receive, "the voice state update").await;
+        let server_result = receive(server_receive, "the voice server update").await;
+
+        {
+            let mut events = main_gateway.events.lock().await;
+            events.voice.state_update.unsubscribe(state_observer);
+            events.voice.server_update.unsubscribe(server_observer);
+        }
+
+        let voice_state = state_result?;
+        let server_data = server_result?;
+
+        let Some(endpoint) = server_data.endpoint.clone() else {
+            return Err(VoiceConnectionError::NoEndpoint);
+        };
+
+        let data = Arc::new(RwLock::new(VoiceData {
+            server_data: Some(server_data),
+            user_id,
+            session_id: voice_state.state.session_id,
+            ..Default::default()
+        }));
+
+        debug!("VC: Connecting to voice server {}", endpoint);
+
+        let voice_gateway = VoiceGateway::spawn(&endpoint).await?;
+
+        let result = VoiceConnection::handshake(&voice_gateway, data.clone(), options).await;
+
+        let udp = match result {
+            Ok(udp) => udp,
+            Err(e) => {
+                voice_gateway.close().await;
+                return Err(e);
+            }
+        };
+
+        let receiver = VoiceReceiver::new();
+        receiver.listen(&voice_gateway, &udp).await;
+
+        info!("VC: Connected to voice in channel {}", channel_id);
+
+        Ok(VoiceConnection {
+            guild_id,
+            channel_id,
+            gateway: voice_gateway,
+            udp,
+            data,
+            receiver,
+            main_gateway,
+        })
+    }
+}
+
+/// A connection to a voice channel, as created by [ChorusUser::join_voice].
+///
+/// Can be safely cloned and will still correspond to the same connection.
+#[derive(Debug, Clone)]
+pub struct VoiceConnection {
+    /// The guild of the voice channel, or `None` for DM and group DM calls
+    pub guild_id: Option<Snowflake>,
+    pub channel_id: Snowflake,
+    /// The voice gateway connection, for sending and receiving voice gateway events
+    pub gateway: VoiceGatewayHandle,
+    /// The UDP connection, for sending and receiving raw rtp data
+    pub udp: UdpHandle,
+    pub data: Arc<RwLock<VoiceData>>,
+    /// Demultiplexes the audio we receive per user, see [VoiceConnection::subscribe_user_audio]
+    pub receiver: VoiceReceiver,
+    /// The user's main gateway connection, used to leave the channel
+    main_gateway: GatewayHandle,
+}
+
+impl VoiceConnection {
+    /// Identifies with the voice gateway and sets up the UDP connection.
+    async fn handshake(
+        voice_gateway: &VoiceGatewayHandle,
+        data: Arc<RwLock<VoiceData>>,
+        options: VoiceOptions,
+    ) -> Result<UdpHandle, VoiceConnectionError> {
+        let (ready_observer, ready_receive) = OneshotObserver::new(|_: &VoiceReady| true);
+        let (session_observer, session_receive) =
+            OneshotObserver::new(|_: &SessionDescription| true);
+
+        {
+            let mut events = voice_gateway.events.lock().await;
+            events.voice_ready.subscribe(ready_observer.clone());
+            events
+                .session_description
+                .subscribe(session_observer.clone());
+        }
+
+        let result = async {
+            // We just set the server data, which is all voice_identify needs
+            let identify = data.read().await.voice_identify(Some(false)).unwrap();
+            voice_gateway.send_identify(identify).await;
+
+            let ready = receive(ready_receive, "voice ready").await?;
+
+            let Some(mode) = SUPPORTED_ENCRYPTION_MODES
+                .into_iter()
+                .find(|mode| ready.modes.contains(mode))
+            else {
+                return Err(VoiceConnectionError::NoSupportedEncryptionMode {
+                    modes: format!("{:?}", ready.modes),
+                });
+            };
+
+            data.write().await.ready_data = Some(ready.clone());
+
+            // Spawning the handler also performs IP discovery
+            let udp = UdpHandler::spawn(
+                data.clone(),
+                SocketAddr::new(ready.ip, ready.port),
+                ready.ssrc,
+                options,
+            )
+            .await?;
+
+            let external_address = data.read().await.external_address()?;
+
+            voice_gateway
+                .send_select_protocol(SelectProtocol {
+                    protocol: VoiceProtocol::Udp,
+                    data: SelectProtocolData {
+                        address: external_address.ip().to_string(),
+                        port: external_address.port(),
+                        mode,
+                    },
+                    codecs: VoiceCodec::supported(),
+                    ..Default::default()
+                })
+                .await;
+
+            let session_description = receive(session_receive, "the session description").await?;
+            data.write().await.session_description = Some(session_description);
+
+            Ok(udp)
+        }
+        .await;
+
+        let mut events = voice_gateway.events.lock().await;
+        events.voice_ready.unsubscribe(ready_observer);
+        events.session_description.unsubscribe(session_observer);
+
+        result
+    }
+
+    /// Sends encoded opus audio, see [UdpHandle::send_opus_data].
+    ///
+    /// Note that the server will not forward the audio unless we are marked as speaking, see
+    /// [VoiceConnection::set_speaking].
+    pub async fn send_audio(&self, timestamp: u32, payload: Vec<u8>) -> Result<(), VoiceUdpError> {
+        self.udp.send_opus_data(timestamp, payload).await
+    }
+
+    /// Sends an encoded video frame, see [UdpHandle::send_video_frame].
+    ///
+    /// Note that the server will not forward the video unless our video stream is active, see
+    /// [VoiceConnection::set_video].
+    pub async fn send_video(
+        &self,
+        codec: VideoCodec,
+        timestamp: u32,
+        frame: &[u8],
+    ) -> Result<(), VoiceUdpError> {
+        self.udp.send_video_frame(codec, timestamp, frame).await
+    }
+
+    /// Tells the server whether we are transmitting video.
+    pub async fn set_video(&self, active: bool) {
+        let Some(ready) = self.data.read().await.ready_data.clone() else {
+            return;
+        };
+
+        let streams: Vec<_> = ready
+            .streams
+            .into_iter()
+            .map(|mut stream| {
+                stream.active = active;
+                stream
+            })
+            .collect();
+
+        let (video_ssrc, rtx_ssrc) = match (active, streams.first()) {
+            (true, Some(stream)) => (stream.ssrc as usize, stream.rtx_ssrc as usize),
+            _ => (0, 0),
+        };
+
+        self.gateway
+            .send_ssrc_definition(SsrcDefinition {
+                audio_ssrc: ready.ssrc as usize,
+                video_ssrc,
+                rtx_ssrc,
+                user_id: None,
+                streams,
+            })
+            .await;
+    }
+
+    /// Tells the server whether we are transmitting audio.
+    pub async fn set_speaking(&self, speaking: bool) {
+        let Some(ssrc) = self.ssrc().await else {
+            return;
+        };
+
+        let flags = if speaking {
+            SpeakingBitflags::MICROPHONE
+        } else {
+            SpeakingBitflags::empty()
+        };
+
+        self.gateway
+            .send_speaking(Speaking {
+                speaking: flags.bits() as u8,
+                ssrc,
+                user_id: None,
+                delay: 0,
+            })
+            .await;
+    }
+
+    /// Returns the ssrc our audio is sent with
+    pub async fn ssrc(&self) -> Option<u32> {
+        Some(self.data.read().await.ready_data.as_ref()?.ssrc)
+    }
+
+    /// Returns a stream of the audio a user in the channel sends, in sequence order.
+    ///
+    /// See [VoiceReceiver::subscribe_user_audio]
+    pub fn subscribe_user_audio(&self, user_id: Snowflake) -> mpsc::Receiver<ReceivedAudio> {
+        self.receiver.subscribe_user_audio(user_id)
+    }
+
+    /// Returns a snapshot of the statistics about the audio streams we send and receive.
+    ///
+    /// Loss and jitter of our outgoing stream are only known once the server has sent an RTCP
+    /// report about it.
+    pub async fn stats(&self) -> VoiceStatistics {
+        self.data.read().await.statistics.clone()
+    }
+
+    /// Sends an RTCP report about the streams we send and receive to the server.
+    ///
+    /// See [UdpHandle::send_rtcp_report]
+    pub async fn send_rtcp_report(&self) -> Result<(), VoiceUdpError> {
+        self.udp.send_rtcp_report().await
+    }
+
+    /// Leaves the voice channel and closes the voice gateway connection.
+    pub async fn disconnect(self) {
+        self.main_gateway
+            .send_update_voice_state(UpdateVoiceState {
+                guild_id: self.guild_id,
+                channel_id: None,
+                self_mute: false,
+                self_deaf: false,
+            })
+            .await;
+
+        self.gateway.close().await;
+    }
+}
+
+/// Waits for a [OneshotObserver] to receive its event
+async fn receive<T>(
+    receiver: oneshot::Receiver<T>,
+    waiting_for: &str,
+) -> Result<T, VoiceConnectionError> {
+    match timeout(VOICE_CONNECT_TIMEOUT, receiver).await {
+        Ok(Ok(event)) => Ok(event),
+        _ => Err(VoiceConnectionError::Timeout {
+            waiting_for: waiting_for.to_string(),
+        }),
+    }
+}
+
+/// Forwards the first event matching a filter into a oneshot channel
+struct OneshotObserver<T> {
+    filter: Box<dyn Fn(&T) -> bool + Send + Sync>,
+    sender: std::sync::Mutex<Option<oneshot::Sender<T>>>,
+}
+
+impl<T: WebSocketEvent + Clone + 'static> OneshotObserver<T> {
+    #[allow(clippy::new_ret_no_self)]
+    fn new(
+        filter: impl Fn(&T) -> bool + Send + Sync + 'static,
+    ) -> (Arc<dyn Observer<T>>, oneshot::Receiver<T>) {
+        let (sender, receiver) = oneshot::channel();
+
+        let observer = OneshotObserver {
+            filter: Box::new(filter),
+            sender: std::sync::Mutex::new(Some(sender)),
+        };
+
+        (Arc::new(observer), receiver)
+    }
+}
+
+#[async_trait]
+impl<T: WebSocketEvent + Clone> Observer<T> for OneshotObserver<T> {
+    async fn update(&self, event: &T) {
+        if !(self.filter)(event) {
+            return;
+        }
+
+        if let Some(sender) = self.sender.lock().unwrap().take() {
+            let _ = sender.send(event.clone());
+        }
+    }
+}
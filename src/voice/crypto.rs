@@ -6,19 +6,20 @@
 //!
 //! All functions in this module return a 24 byte long `Vec<u8>`.
 
-/// Gets an `xsalsa20_poly1305` nonce from an rtppacket.
+/// Gets an `xsalsa20_poly1305` nonce from an rtp or rtcp packet, whose header is
+/// `header_size` bytes long.
 ///
 /// See <https://discord-userdoccers.vercel.app/topics/voice-connections#encryption-mode>
-pub(crate) fn get_xsalsa20_poly1305_nonce(packet: &[u8]) -> Vec<u8> {
-    let mut rtp_header = Vec::with_capacity(24);
-    rtp_header.append(&mut packet[0..12].to_vec());
+pub(crate) fn get_xsalsa20_poly1305_nonce(packet: &[u8], header_size: usize) -> Vec<u8> {
+    let mut header = Vec::with_capacity(24);
+    header.append(&mut packet[0..header_size].to_vec());
 
-    // The header is only 12 bytes, but the nonce has to be 24
-    while rtp_header.len() < 24 {
-        rtp_header.push(0);
+    // The header is only 12 (or 8) bytes, but the nonce has to be 24
+    while header.len() < 24 {
+        header.push(0);
     }
 
-    rtp_header
+    header
 }
 
 /// Gets an `xsalsa20_poly1305_suffix` nonce from an rtppacket.
@@ -60,7 +61,7 @@ fn test_packet_nonce_derives() {
         113, 253, 145, 36, 106, 14, 222, 128, 226, 239, 10, 39, 72, 113, 33, 113,
     ];
 
-    let nonce_1 = get_xsalsa20_poly1305_nonce(&test_packet_bytes);
+    let nonce_1 = get_xsalsa20_poly1305_nonce(&test_packet_bytes, 12);
     let nonce_1_expected = vec![
         144, 120, 98, 5, 71, 174, 52, 64, 0, 4, 85, 36, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
     ];
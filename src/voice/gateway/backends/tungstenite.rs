@@ -2,7 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use crate::types::VoiceCloseCode;
+use crate::types::{DaveMlsMessage, VoiceCloseCode};
 use crate::voice::gateway::{VoiceGatewayCommunication, VoiceGatewayMessage};
 
 impl From<VoiceGatewayMessage> for tokio_tungstenite::tungstenite::Message {
@@ -11,6 +11,12 @@ impl From<VoiceGatewayMessage> for tokio_tungstenite::tungstenite::Message {
     }
 }
 
+impl From<DaveMlsMessage> for tokio_tungstenite::tungstenite::Message {
+    fn from(message: DaveMlsMessage) -> Self {
+        Self::Binary(message.to_client_bytes())
+    }
+}
+
 impl From<tokio_tungstenite::tungstenite::Message> for VoiceGatewayMessage {
     fn from(value: tokio_tungstenite::tungstenite::Message) -> Self {
         Self(value.to_string())
@@ -23,6 +29,9 @@ impl From<tokio_tungstenite::tungstenite::Message> for VoiceGatewayCommunication
             tokio_tungstenite::tungstenite::Message::Text(text) => {
                 VoiceGatewayCommunication::Message(VoiceGatewayMessage(text))
             }
+            tokio_tungstenite::tungstenite::Message::Binary(bytes) => {
+                VoiceGatewayCommunication::Binary(bytes)
+            }
             tokio_tungstenite::tungstenite::Message::Close(close_frame) => {
                 if close_frame.is_none() {
                     // Note: there is no unknown error. This case shouldn't happen, so I'm just
@@ -2,6 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use crate::types::DaveMlsMessage;
 use crate::voice::gateway::VoiceGatewayMessage;
 use ws_stream_wasm::WsMessage;
 
@@ -11,6 +12,12 @@ impl From<VoiceGatewayMessage> for WsMessage {
     }
 }
 
+impl From<DaveMlsMessage> for WsMessage {
+    fn from(message: DaveMlsMessage) -> Self {
+        Self::Binary(message.to_client_bytes())
+    }
+}
+
 impl From<WsMessage> for VoiceGatewayMessage {
     fn from(value: WsMessage) -> Self {
         match value {
@@ -7,6 +7,7 @@ use pubserve::Publisher;
 use crate::{
     errors::VoiceGatewayError,
     types::{
+        DaveExecuteTransition, DaveMlsMessage, DavePrepareEpoch, DavePrepareTransition,
         SessionDescription, SessionUpdate, Speaking, SsrcDefinition, VoiceBackendVersion,
         VoiceClientConnectFlags, VoiceClientConnectPlatform, VoiceClientDisconnection,
         VoiceMediaSinkWants, VoiceReady,
@@ -25,5 +26,10 @@ pub struct VoiceEvents {
     pub client_connect_flags: Publisher<VoiceClientConnectFlags>,
     pub client_connect_platform: Publisher<VoiceClientConnectPlatform>,
     pub media_sink_wants: Publisher<VoiceMediaSinkWants>,
+    pub dave_prepare_transition: Publisher<DavePrepareTransition>,
+    pub dave_execute_transition: Publisher<DaveExecuteTransition>,
+    pub dave_prepare_epoch: Publisher<DavePrepareEpoch>,
+    /// Binary DAVE MLS messages, which are passed through without being processed
+    pub dave_mls_message: Publisher<DaveMlsMessage>,
     pub error: Publisher<VoiceGatewayError>,
 }
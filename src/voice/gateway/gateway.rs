@@ -15,14 +15,19 @@ use futures_util::StreamExt;
 use crate::gateway::Sink;
 use crate::gateway::Stream;
 use crate::gateway::WebSocketBackend;
+use crate::gateway::{TaskGuard, TaskTracker};
 use crate::{
     errors::VoiceGatewayError,
     types::{
-        VoiceCloseCode, VoiceGatewayReceivePayload, VoiceHelloData, WebSocketEvent,
+        DaveMlsMessage, DavePrepareTransition, DaveReadyForTransition, VoiceCloseCode,
+        VoiceGatewayReceivePayload, VoiceHelloData, WebSocketEvent, DAVE_PROTOCOL_VERSION_NONE,
         VOICE_BACKEND_VERSION, VOICE_CLIENT_CONNECT_FLAGS, VOICE_CLIENT_CONNECT_PLATFORM,
-        VOICE_CLIENT_DISCONNECT, VOICE_HEARTBEAT, VOICE_HEARTBEAT_ACK, VOICE_HELLO, VOICE_IDENTIFY,
-        VOICE_MEDIA_SINK_WANTS, VOICE_READY, VOICE_RESUME, VOICE_SELECT_PROTOCOL,
-        VOICE_SESSION_DESCRIPTION, VOICE_SESSION_UPDATE, VOICE_SPEAKING, VOICE_SSRC_DEFINITION,
+        VOICE_CLIENT_DISCONNECT, VOICE_DAVE_EXECUTE_TRANSITION,
+        VOICE_DAVE_MLS_INVALID_COMMIT_WELCOME, VOICE_DAVE_PREPARE_EPOCH,
+        VOICE_DAVE_PREPARE_TRANSITION, VOICE_DAVE_READY_FOR_TRANSITION, VOICE_HEARTBEAT,
+        VOICE_HEARTBEAT_ACK, VOICE_HELLO, VOICE_IDENTIFY, VOICE_MEDIA_SINK_WANTS, VOICE_READY,
+        VOICE_RESUME, VOICE_SELECT_PROTOCOL, VOICE_SESSION_DESCRIPTION, VOICE_SESSION_UPDATE,
+        VOICE_SPEAKING, VOICE_SSRC_DEFINITION,
     },
     voice::gateway::{
         heartbeat::VoiceHeartbeatThreadCommunication, VoiceGatewayCommunication,
@@ -30,7 +35,9 @@ use crate::{
     },
 };
 
-use super::{events::VoiceEvents, heartbeat::VoiceHeartbeatHandler, VoiceGatewayHandle};
+use super::{
+    events::VoiceEvents, handle::send_json, heartbeat::VoiceHeartbeatHandler, VoiceGatewayHandle,
+};
 
 // Needed to observe close codes
 #[cfg(target_arch = "wasm32")]
@@ -44,10 +51,23 @@ pub struct VoiceGateway {
     websocket_receive: Stream,
     kill_send: tokio::sync::broadcast::Sender<()>,
     kill_receive: tokio::sync::broadcast::Receiver<()>,
+    /// Tells the listener task to close the connection normally, see
+    /// [VoiceGatewayHandle::shutdown]
+    shutdown_receive: tokio::sync::broadcast::Receiver<()>,
+    /// Dropped once the listener task stops, see [VoiceGatewayHandle::closed]
+    _task_guard: TaskGuard,
 }
 
 impl VoiceGateway {
     #[allow(clippy::new_ret_no_self)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "chorus.voice.gateway.connect",
+            skip_all,
+            fields(url = %websocket_url)
+        )
+    )]
     pub async fn spawn(websocket_url: &str) -> Result<VoiceGatewayHandle, VoiceGatewayError> {
         // Append the needed things to the websocket url
         let processed_url = format!("wss://{}/?v=7", websocket_url);
@@ -68,6 +88,9 @@ impl VoiceGateway {
         // Create a shared broadcast channel for killing all gateway tasks
         let (kill_send, mut _kill_receive) = tokio::sync::broadcast::channel::<()>(16);
 
+        let (shutdown_send, shutdown_receive) = tokio::sync::broadcast::channel::<()>(1);
+        let (tasks, task_guard) = TaskTracker::new();
+
         // Wait for the first hello and then spawn both tasks so we avoid nested tasks
         // This automatically spawns the heartbeat task, but from the main thread
         #[cfg(not(target_arch = "wasm32"))]
@@ -81,6 +104,12 @@ impl VoiceGateway {
 
             match communication {
                 VoiceGatewayCommunication::Message(message) => message,
+                // Binary messages are always DAVE MLS messages, which can't come before hello
+                VoiceGatewayCommunication::Binary(bytes) => {
+                    return Err(VoiceGatewayError::NonHelloOnInitiate {
+                        opcode: bytes.get(2).copied().unwrap_or_default(),
+                    })
+                }
                 VoiceGatewayCommunication::Error(error) => return Err(error.into()),
             }
         };
@@ -112,11 +141,14 @@ impl VoiceGateway {
                 1, // to:do actually compute nonce
                 shared_websocket_send.clone(),
                 kill_send.subscribe(),
+                task_guard.clone(),
             ),
             websocket_send: shared_websocket_send.clone(),
             websocket_receive,
             kill_send: kill_send.clone(),
             kill_receive: kill_send.subscribe(),
+            shutdown_receive,
+            _task_guard: task_guard,
         };
 
         // Now we can continuously check for messages in a different task, since we aren't going to receive another hello
@@ -134,6 +166,8 @@ impl VoiceGateway {
             events: shared_events,
             websocket_send: shared_websocket_send.clone(),
             kill_send: kill_send.clone(),
+            shutdown_send,
+            tasks,
         })
     }
 
@@ -148,6 +182,10 @@ impl VoiceGateway {
                     log::trace!("VGW: Closing listener task");
                     break;
                 }
+                Ok(_) = self.shutdown_receive.recv() => {
+                    self.shutdown().await;
+                    break;
+                }
                 message = self.websocket_receive.next() => {
                     msg = message;
                 }
@@ -164,6 +202,9 @@ impl VoiceGateway {
                     VoiceGatewayCommunication::Message(message) => {
                         self.handle_message(message).await
                     }
+                    VoiceGatewayCommunication::Binary(bytes) => {
+                        self.handle_binary_message(&bytes).await
+                    }
                     VoiceGatewayCommunication::Error(close_code) => {
                         self.handle_close_code(close_code).await
                     }
@@ -200,6 +241,10 @@ impl VoiceGateway {
                       log::trace!("VGW: Closing listener task");
                       break;
                  }
+                 Ok(_) = self.shutdown_receive.recv() => {
+                      self.shutdown().await;
+                      break;
+                 }
                  message = self.websocket_receive.0.next() => {
                       msg = message;
                  }
@@ -223,7 +268,12 @@ impl VoiceGateway {
             // Hence why wasm receives VoiceGatewayMessages, and tungstenite receives
             // VoiceGatewayCommunications.
             if let Some(message) = msg {
-                self.handle_message(message.into()).await;
+                match message {
+                    ws_stream_wasm::WsMessage::Binary(bytes) => {
+                        self.handle_binary_message(&bytes).await
+                    }
+                    message => self.handle_message(message.into()).await,
+                }
                 continue;
             }
 
@@ -239,6 +289,24 @@ impl VoiceGateway {
         self.websocket_send.lock().await.close().await.unwrap();
     }
 
+    /// Closes the websocket connection with close code 1000 and stops the heartbeat task, see
+    /// [VoiceGatewayHandle::shutdown]
+    async fn shutdown(&mut self) {
+        debug!("VGW: Shutting down");
+
+        let close_result = WebSocketBackend::close_normally(
+            &mut *self.websocket_send.lock().await,
+            &self.websocket_receive,
+        )
+        .await;
+        if let Err(e) = close_result {
+            warn!("VGW: Couldn't close the websocket cleanly: {:?}", e);
+        }
+
+        // Errors if the heartbeat task already stopped
+        let _ = self.kill_send.send(());
+    }
+
     /// Handles receiving a [VoiceCloseCode].
     ///
     /// Closes the connection and publishes an error event.
@@ -266,6 +334,50 @@ impl VoiceGateway {
         Ok(())
     }
 
+    /// Handles a binary message, which is a [DaveMlsMessage].
+    ///
+    /// Since we don't implement DAVE, these are only published to observers.
+    pub async fn handle_binary_message(&mut self, bytes: &[u8]) {
+        let Some(message) = DaveMlsMessage::from_server_bytes(bytes) else {
+            warn!("VGW: Received invalid binary message: {:?}", bytes);
+            return;
+        };
+
+        trace!("VGW: Received DAVE MLS message ({})", message.op_code);
+
+        self.events
+            .lock()
+            .await
+            .dave_mls_message
+            .publish(message)
+            .await;
+    }
+
+    /// Handles a [DavePrepareTransition].
+    ///
+    /// We don't implement DAVE, so we can only take part in transitions to
+    /// [DAVE_PROTOCOL_VERSION_NONE]; those are acknowledged right away.
+    async fn handle_dave_prepare_transition(&mut self, transition: DavePrepareTransition) {
+        if transition.protocol_version != DAVE_PROTOCOL_VERSION_NONE {
+            warn!(
+                "VGW: Server wants to transition to DAVE protocol version {}, which chorus does not support",
+                transition.protocol_version
+            );
+            return;
+        }
+
+        let ready = DaveReadyForTransition {
+            transition_id: transition.transition_id,
+        };
+
+        send_json(
+            &self.websocket_send,
+            VOICE_DAVE_READY_FOR_TRANSITION,
+            serde_json::to_value(ready).unwrap(),
+        )
+        .await;
+    }
+
     /// This handles a message as a websocket event and updates its events along with the events' observers
     pub async fn handle_message(&mut self, msg: VoiceGatewayMessage) {
         if msg.0.is_empty() {
@@ -396,6 +508,52 @@ impl VoiceGateway {
                     );
                 }
             }
+            VOICE_DAVE_PREPARE_TRANSITION => {
+                trace!("VGW: Received DAVE Prepare Transition");
+
+                let result: Result<DavePrepareTransition, serde_json::Error> =
+                    serde_json::from_str(gateway_payload.data.get());
+                let Ok(transition) = result else {
+                    warn!(
+                        "Failed to parse VOICE_DAVE_PREPARE_TRANSITION ({})",
+                        result.err().unwrap()
+                    );
+                    return;
+                };
+
+                self.handle_dave_prepare_transition(transition).await;
+
+                self.events
+                    .lock()
+                    .await
+                    .dave_prepare_transition
+                    .publish(transition)
+                    .await;
+            }
+            VOICE_DAVE_EXECUTE_TRANSITION => {
+                trace!("VGW: Received DAVE Execute Transition");
+
+                let event = &mut self.events.lock().await.dave_execute_transition;
+                let result = VoiceGateway::handle_event(gateway_payload.data.get(), event).await;
+                if result.is_err() {
+                    warn!(
+                        "Failed to parse VOICE_DAVE_EXECUTE_TRANSITION ({})",
+                        result.err().unwrap()
+                    );
+                }
+            }
+            VOICE_DAVE_PREPARE_EPOCH => {
+                trace!("VGW: Received DAVE Prepare Epoch");
+
+                let event = &mut self.events.lock().await.dave_prepare_epoch;
+                let result = VoiceGateway::handle_event(gateway_payload.data.get(), event).await;
+                if result.is_err() {
+                    warn!(
+                        "Failed to parse VOICE_DAVE_PREPARE_EPOCH ({})",
+                        result.err().unwrap()
+                    );
+                }
+            }
             // We received a heartbeat from the server
             // "Discord may send the app a Heartbeat (opcode 1) event, in which case the app should send a Heartbeat event immediately."
             VOICE_HEARTBEAT => {
@@ -429,7 +587,11 @@ impl VoiceGateway {
                     .await
                     .unwrap();
             }
-            VOICE_IDENTIFY | VOICE_SELECT_PROTOCOL | VOICE_RESUME => {
+            VOICE_IDENTIFY
+            | VOICE_SELECT_PROTOCOL
+            | VOICE_RESUME
+            | VOICE_DAVE_READY_FOR_TRANSITION
+            | VOICE_DAVE_MLS_INVALID_COMMIT_WELCOME => {
                 info!(
                     "VGW: Received unexpected opcode ({}) for current state. This might be due to a faulty server implementation and is likely not the fault of chorus.",
                     gateway_payload.op_code
@@ -12,11 +12,12 @@ use serde_json::json;
 use tokio::sync::Mutex;
 
 use crate::{
-    gateway::Sink,
+    gateway::{Sink, TaskTracker},
     types::{
-        SelectProtocol, Speaking, SsrcDefinition, VoiceGatewaySendPayload, VoiceIdentify,
-        VOICE_BACKEND_VERSION, VOICE_IDENTIFY, VOICE_SELECT_PROTOCOL, VOICE_SPEAKING,
-        VOICE_SSRC_DEFINITION,
+        DaveMlsInvalidCommitWelcome, DaveMlsMessage, DaveReadyForTransition, SelectProtocol,
+        Speaking, SsrcDefinition, VoiceGatewaySendPayload, VoiceIdentify, VOICE_BACKEND_VERSION,
+        VOICE_DAVE_MLS_INVALID_COMMIT_WELCOME, VOICE_DAVE_READY_FOR_TRANSITION, VOICE_IDENTIFY,
+        VOICE_SELECT_PROTOCOL, VOICE_SPEAKING, VOICE_SSRC_DEFINITION,
     },
 };
 
@@ -31,25 +32,37 @@ pub struct VoiceGatewayHandle {
     pub websocket_send: Arc<Mutex<Sink>>,
     /// Tells gateway tasks to close
     pub(super) kill_send: tokio::sync::broadcast::Sender<()>,
+    /// Tells the gateway to close the connection normally, see [VoiceGatewayHandle::shutdown]
+    pub(super) shutdown_send: tokio::sync::broadcast::Sender<()>,
+    pub(super) tasks: TaskTracker,
+}
+
+/// Sends json to the gateway with an opcode
+pub(super) async fn send_json(
+    websocket_send: &Mutex<Sink>,
+    op_code: u8,
+    to_send: serde_json::Value,
+) {
+    let gateway_payload = VoiceGatewaySendPayload {
+        op_code,
+        data: to_send,
+    };
+
+    let payload_json = serde_json::to_string(&gateway_payload).unwrap();
+    let message = VoiceGatewayMessage(payload_json);
+
+    websocket_send
+        .lock()
+        .await
+        .send(message.into())
+        .await
+        .unwrap();
 }
 
 impl VoiceGatewayHandle {
     /// Sends json to the gateway with an opcode
     async fn send_json(&self, op_code: u8, to_send: serde_json::Value) {
-        let gateway_payload = VoiceGatewaySendPayload {
-            op_code,
-            data: to_send,
-        };
-
-        let payload_json = serde_json::to_string(&gateway_payload).unwrap();
-        let message = VoiceGatewayMessage(payload_json);
-
-        self.websocket_send
-            .lock()
-            .await
-            .send(message.into())
-            .await
-            .unwrap();
+        send_json(&self.websocket_send, op_code, to_send).await;
     }
 
     /// Sends a voice identify event to the gateway
@@ -98,6 +111,41 @@ impl VoiceGatewayHandle {
             .await;
     }
 
+    /// Tells the server we are ready to execute a DAVE protocol transition.
+    ///
+    /// Transitions to [DAVE_PROTOCOL_VERSION_NONE](crate::types::DAVE_PROTOCOL_VERSION_NONE)
+    /// are already acknowledged by the gateway itself.
+    pub async fn send_dave_ready_for_transition(&self, to_send: DaveReadyForTransition) {
+        let to_send_value = serde_json::to_value(to_send).unwrap();
+
+        trace!("VGW: Sending DAVE Ready For Transition");
+
+        self.send_json(VOICE_DAVE_READY_FOR_TRANSITION, to_send_value)
+            .await;
+    }
+
+    /// Tells the server we received a DAVE MLS commit or welcome we could not process
+    pub async fn send_dave_mls_invalid_commit_welcome(&self, to_send: DaveMlsInvalidCommitWelcome) {
+        let to_send_value = serde_json::to_value(to_send).unwrap();
+
+        trace!("VGW: Sending DAVE MLS Invalid Commit Welcome");
+
+        self.send_json(VOICE_DAVE_MLS_INVALID_COMMIT_WELCOME, to_send_value)
+            .await;
+    }
+
+    /// Sends a binary DAVE MLS message, such as a key package or commit
+    pub async fn send_dave_mls_message(&self, to_send: DaveMlsMessage) {
+        trace!("VGW: Sending DAVE MLS message ({})", to_send.op_code);
+
+        self.websocket_send
+            .lock()
+            .await
+            .send(to_send.into())
+            .await
+            .unwrap();
+    }
+
     /// Closes the websocket connection and stops all gateway tasks;
     ///
     /// Essentially pulls the plug on the voice gateway, leaving it possible to resume;
@@ -105,4 +153,21 @@ impl VoiceGatewayHandle {
         self.kill_send.send(()).unwrap();
         self.websocket_send.lock().await.close().await.unwrap();
     }
+
+    /// Closes the websocket connection with close code 1000 (normal closure), and waits until
+    /// all voice gateway tasks have stopped.
+    ///
+    /// Unlike [VoiceGatewayHandle::close], this ends the session; it can not be resumed.
+    pub async fn shutdown(&self) {
+        // Errors if the gateway has already stopped, in which case there is nothing to close
+        let _ = self.shutdown_send.send(());
+        self.closed().await;
+    }
+
+    /// Waits until all voice gateway tasks have stopped, e.g. after
+    /// [VoiceGatewayHandle::shutdown] or [VoiceGatewayHandle::close], or because the
+    /// connection broke.
+    pub async fn closed(&self) {
+        self.tasks.wait().await;
+    }
 }
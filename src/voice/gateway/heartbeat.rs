@@ -26,11 +26,14 @@ use tokio::sync::{
 use tokio::task;
 
 use crate::{
-    gateway::{heartbeat::HEARTBEAT_ACK_TIMEOUT, Sink},
+    gateway::{heartbeat::HEARTBEAT_ACK_TIMEOUT, Sink, TaskGuard},
     types::{VoiceGatewaySendPayload, VOICE_HEARTBEAT, VOICE_HEARTBEAT_ACK},
     voice::gateway::VoiceGatewayMessage,
 };
 
+#[cfg(target_arch = "wasm32")]
+use super::worker_timer::WorkerTimer;
+
 /// Handles sending heartbeats to the voice gateway in another thread
 #[allow(dead_code)] // FIXME: Remove this, once all fields of VoiceHeartbeatHandler are used
 #[derive(Debug)]
@@ -47,6 +50,7 @@ impl VoiceHeartbeatHandler {
         starting_nonce: u64,
         websocket_tx: Arc<Mutex<Sink>>,
         kill_rc: tokio::sync::broadcast::Receiver<()>,
+        task_guard: TaskGuard,
     ) -> Self {
         let (send, receive) = tokio::sync::mpsc::channel(32);
         let kill_receive = kill_rc.resubscribe();
@@ -61,6 +65,7 @@ impl VoiceHeartbeatHandler {
                 kill_receive,
             )
             .await;
+            drop(task_guard);
         });
         #[cfg(target_arch = "wasm32")]
         wasm_bindgen_futures::spawn_local(async move {
@@ -72,6 +77,7 @@ impl VoiceHeartbeatHandler {
                 kill_receive,
             )
             .await;
+            drop(task_guard);
         });
 
         Self {
@@ -94,19 +100,38 @@ impl VoiceHeartbeatHandler {
         let mut last_heartbeat_timestamp: Instant = Instant::now();
         let mut last_heartbeat_acknowledged = true;
         let mut nonce: u64 = starting_nonce;
+        let mut schedule = HeartbeatSchedule::new(heartbeat_interval, last_heartbeat_timestamp);
+
+        // Main thread timers are heavily throttled in background tabs, which would make us miss
+        // heartbeats and get disconnected
+        #[cfg(target_arch = "wasm32")]
+        let worker_timer = WorkerTimer::new();
+        #[cfg(target_arch = "wasm32")]
+        if worker_timer.is_none() {
+            warn!("VGW: Couldn't start a worker timer, heartbeats may be late in background tabs");
+        }
 
         loop {
-            let timeout = if last_heartbeat_acknowledged {
-                heartbeat_interval
+            let deadline = if last_heartbeat_acknowledged {
+                schedule.next_heartbeat()
             } else {
                 // If the server hasn't acknowledged our heartbeat we should resend it
-                Duration::from_millis(HEARTBEAT_ACK_TIMEOUT)
+                last_heartbeat_timestamp + Duration::from_millis(HEARTBEAT_ACK_TIMEOUT)
+            };
+
+            let sleep = async {
+                #[cfg(target_arch = "wasm32")]
+                if let Some(timer) = &worker_timer {
+                    return timer.sleep_until(deadline).await;
+                }
+
+                sleep_until(deadline).await
             };
 
             let mut should_send = false;
 
             tokio::select! {
-                () = sleep_until(last_heartbeat_timestamp + timeout) => {
+                () = sleep => {
                     should_send = true;
                 }
                 Some(communication) = receive.recv() => {
@@ -156,11 +181,54 @@ impl VoiceHeartbeatHandler {
 
                 last_heartbeat_timestamp = Instant::now();
                 last_heartbeat_acknowledged = false;
+                schedule.heartbeat_sent(last_heartbeat_timestamp);
             }
         }
     }
 }
 
+/// Keeps track of when the next heartbeat is due.
+///
+/// Heartbeats are scheduled at fixed intervals from the previous deadline rather than from when
+/// the previous heartbeat was actually sent, so that late timer wakeups (common in
+/// browsers) don't add up until the server considers us dead.
+#[derive(Debug, Clone, Copy)]
+struct HeartbeatSchedule {
+    interval: Duration,
+    next: Instant,
+}
+
+impl HeartbeatSchedule {
+    fn new(interval: Duration, start: Instant) -> Self {
+        Self {
+            interval,
+            next: start + interval,
+        }
+    }
+
+    /// When the next heartbeat should be sent
+    fn next_heartbeat(&self) -> Instant {
+        self.next
+    }
+
+    /// Schedules the next heartbeat, after one was sent at `now`
+    fn heartbeat_sent(&mut self, now: Instant) {
+        if now < self.next {
+            // Sent early, e.g. because the server requested it
+            self.next = now + self.interval;
+            return;
+        }
+
+        self.next += self.interval;
+
+        // If we slept through a whole interval (e.g. the tab was frozen), don't try to catch
+        // up with a burst of heartbeats
+        if self.next <= now {
+            self.next = now + self.interval;
+        }
+    }
+}
+
 /// Used for communications between the voice heartbeat and voice gateway thread.
 /// Either signifies a nonce update, a heartbeat ACK or a Heartbeat request by the server
 #[derive(Clone, Copy, Debug)]
@@ -170,3 +238,54 @@ pub(super) struct VoiceHeartbeatThreadCommunication {
     /// The new nonce to use, if any
     pub(super) updated_nonce: Option<u64>,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[tokio::test(start_paused = true)]
+    async fn late_ticks_catch_up_without_drift() {
+        let interval = Duration::from_secs(10);
+        let late_by = Duration::from_millis(300);
+        let start = Instant::now();
+        let mut schedule = HeartbeatSchedule::new(interval, start);
+
+        for tick in 1..=5 {
+            sleep_until(schedule.next_heartbeat()).await;
+            assert_eq!(Instant::now(), start + interval * tick);
+
+            // The timer woke us up late
+            tokio::time::advance(late_by).await;
+            schedule.heartbeat_sent(Instant::now());
+
+            // The next heartbeat is still due on the original grid
+            assert_eq!(schedule.next_heartbeat(), start + interval * (tick + 1));
+        }
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn early_heartbeats_restart_the_interval() {
+        let interval = Duration::from_secs(10);
+        let start = Instant::now();
+        let mut schedule = HeartbeatSchedule::new(interval, start);
+
+        let requested_at = start + Duration::from_secs(4);
+        schedule.heartbeat_sent(requested_at);
+        assert_eq!(schedule.next_heartbeat(), requested_at + interval);
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn missed_intervals_are_not_sent_in_a_burst() {
+        let interval = Duration::from_secs(10);
+        let start = Instant::now();
+        let mut schedule = HeartbeatSchedule::new(interval, start);
+
+        // Frozen for several intervals
+        let woke_at = start + Duration::from_secs(45);
+        schedule.heartbeat_sent(woke_at);
+        assert_eq!(schedule.next_heartbeat(), woke_at + interval);
+    }
+}
@@ -5,13 +5,15 @@
 use crate::types::{VoiceGatewayReceivePayload, VoiceCloseCode};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
-/// Defines a communication received from the gateway, being either an optionally compressed
-/// [RawGatewayMessage] or a [CloseCode].
+/// Defines a communication received from the gateway, being either a [VoiceGatewayMessage], a
+/// binary message or a [VoiceCloseCode].
 ///
 /// Used only for a tungstenite gateway, since our underlying wasm backend handles close codes
 /// differently.
 pub(crate) enum VoiceGatewayCommunication {
     Message(VoiceGatewayMessage),
+    /// A binary message, such as a [DaveMlsMessage](crate::types::DaveMlsMessage)
+    Binary(Vec<u8>),
     Error(VoiceCloseCode),
 }
 
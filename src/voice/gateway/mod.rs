@@ -8,6 +8,8 @@ pub mod gateway;
 pub mod handle;
 pub mod heartbeat;
 pub mod message;
+#[cfg(target_arch = "wasm32")]
+mod worker_timer;
 
 pub use backends::*;
 pub use gateway::*;
@@ -0,0 +1,145 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+};
+
+use tokio::sync::oneshot;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasmtimer::std::Instant;
+use web_sys::{Blob, BlobPropertyBag, MessageEvent, Url, Worker};
+
+/// The worker's source; it answers every `id:milliseconds` message with `id` once the
+/// timeout has passed
+const WORKER_SOURCE: &str = "onmessage = (event) => { \
+    const [id, ms] = event.data.split(':'); \
+    setTimeout(() => postMessage(id), Number(ms)); \
+};";
+
+/// A timer running in a dedicated Web Worker.
+///
+/// Browsers throttle timers on the main thread of background tabs (in Chromium down to once
+/// per minute), which is far longer than the voice heartbeat interval. Timers in dedicated
+/// workers are not throttled as heavily.
+pub(super) struct WorkerTimer {
+    worker: Worker,
+    /// The object url of the worker's source, revoked on drop
+    worker_url: String,
+    /// Senders for the sleeps which are currently waiting on the worker, by id
+    pending: Rc<RefCell<HashMap<u32, oneshot::Sender<()>>>>,
+    next_id: Cell<u32>,
+    /// Held so the message handler stays alive as long as the worker
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+}
+
+impl WorkerTimer {
+    /// Starts the worker.
+    ///
+    /// Returns `None` if workers are unavailable, e.g. due to a content security policy.
+    pub fn new() -> Option<Self> {
+        let parts = js_sys::Array::of1(&JsValue::from_str(WORKER_SOURCE));
+        let options = BlobPropertyBag::new();
+        options.set_type("application/javascript");
+
+        let blob = Blob::new_with_str_sequence_and_options(&parts, &options).ok()?;
+        let worker_url = Url::create_object_url_with_blob(&blob).ok()?;
+
+        let worker = match Worker::new(&worker_url) {
+            Ok(worker) => worker,
+            Err(_) => {
+                let _ = Url::revoke_object_url(&worker_url);
+                return None;
+            }
+        };
+
+        let pending: Rc<RefCell<HashMap<u32, oneshot::Sender<()>>>> =
+            Rc::new(RefCell::new(HashMap::new()));
+        let pending_clone = pending.clone();
+
+        let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+            let Some(id) = event
+                .data()
+                .as_string()
+                .and_then(|id| id.parse::<u32>().ok())
+            else {
+                return;
+            };
+
+            // The sleep might have been cancelled in the meantime, in which case nobody is
+            // listening anymore
+            if let Some(sender) = pending_clone.borrow_mut().remove(&id) {
+                let _ = sender.send(());
+            }
+        });
+        worker.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+        Some(Self {
+            worker,
+            worker_url,
+            pending,
+            next_id: Cell::new(0),
+            _on_message: on_message,
+        })
+    }
+
+    /// Waits until `deadline` has been reached.
+    pub async fn sleep_until(&self, deadline: Instant) {
+        let duration = deadline.saturating_duration_since(Instant::now());
+
+        let id = self.next_id.get();
+        self.next_id.set(id.wrapping_add(1));
+
+        let (send, receive) = oneshot::channel();
+        self.pending.borrow_mut().insert(id, send);
+
+        let message = JsValue::from_str(&format!("{}:{}", id, duration.as_millis()));
+        if self.worker.post_message(&message).is_err() {
+            self.pending.borrow_mut().remove(&id);
+            wasmtimer::tokio::sleep_until(deadline).await;
+            return;
+        }
+
+        let _ = receive.await;
+    }
+}
+
+impl Drop for WorkerTimer {
+    fn drop(&mut self) {
+        self.worker.terminate();
+        let _ = Url::revoke_object_url(&self.worker_url);
+    }
+}
+
+impl std::fmt::Debug for WorkerTimer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WorkerTimer")
+            .field("worker_url", &self.worker_url)
+            .field("pending", &self.pending.borrow().len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    async fn sleeps_until_the_deadline() {
+        let timer = WorkerTimer::new().unwrap();
+
+        let deadline = Instant::now() + Duration::from_millis(50);
+        timer.sleep_until(deadline).await;
+        assert!(Instant::now() >= deadline);
+
+        // Deadlines which have already passed resolve right away, so late ticks can catch up
+        let start = Instant::now();
+        timer.sleep_until(start - Duration::from_millis(50)).await;
+        assert!(start.elapsed() < Duration::from_millis(50));
+    }
+}
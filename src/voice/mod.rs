@@ -4,14 +4,27 @@
 
 //! Module for all voice functionality within chorus.
 
+#[cfg(all(feature = "voice_udp", feature = "voice_gateway"))]
+pub mod connection;
 mod crypto;
 #[cfg(feature = "voice_gateway")]
 pub mod gateway;
 #[cfg(feature = "voice_udp")]
+pub mod options;
+#[cfg(all(feature = "voice_udp", feature = "voice_gateway"))]
+pub mod receive;
+#[cfg(feature = "voice_udp")]
 pub mod udp;
 #[cfg(feature = "voice_udp")]
 pub mod voice_data;
 
+#[cfg(all(feature = "voice_udp", feature = "voice_gateway"))]
+pub use connection::*;
+#[cfg(feature = "voice_udp")]
+pub use options::*;
+#[cfg(all(feature = "voice_udp", feature = "voice_gateway"))]
+pub use receive::*;
+
 // Pub use this so users can interact with packet types if they want
 #[cfg(feature = "voice_udp")]
 pub use discortp;
@@ -0,0 +1,36 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+#[derive(Clone, PartialEq, Eq, Debug, Default, Copy)]
+/// Options used when establishing a voice connection.
+pub struct VoiceOptions {
+    /// The local address to bind the UDP socket to.
+    ///
+    /// Binding an IPv6 address also allows connecting to IPv4 voice endpoints (via
+    /// IPv4-mapped addresses) if the operating system supports dual-stack sockets. A socket
+    /// bound to an IPv4 address can only connect to IPv4 endpoints.
+    ///
+    /// Defaults to `None`, which binds the unspecified address of the endpoint's address family
+    /// (`0.0.0.0` or `::`) with a port assigned by the operating system.
+    pub bind_address: Option<SocketAddr>,
+}
+
+impl VoiceOptions {
+    /// Returns the local address to bind to for connecting to `endpoint`.
+    pub(crate) fn bind_address_for(&self, endpoint: SocketAddr) -> SocketAddr {
+        if let Some(bind_address) = self.bind_address {
+            return bind_address;
+        }
+
+        let ip = match endpoint {
+            SocketAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            SocketAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+        };
+
+        // A port number of 0 makes the os assign us a port
+        SocketAddr::new(ip, 0)
+    }
+}
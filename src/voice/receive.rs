@@ -0,0 +1,442 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Receiving the audio of other users in a voice channel, demultiplexed per user.
+//!
+//! With the `voice_opus` feature, the audio is also decoded to pcm.
+//!
+//! See [VoiceReceiver]
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use discortp::rtp::Rtp;
+use log::*;
+use tokio::sync::mpsc;
+
+#[cfg(feature = "voice_opus")]
+use audiopus::{coder::Decoder, packet::Packet, Channels, SampleRate};
+
+use crate::gateway::Observer;
+use crate::types::{Snowflake, Speaking, SsrcDefinition, VoiceClientDisconnection};
+use crate::voice::gateway::VoiceGatewayHandle;
+use crate::voice::udp::{rtp_payload, UdpHandle};
+
+/// How many packets each stream's jitter buffer holds while waiting for a missing packet,
+/// before skipping it.
+///
+/// At 20ms per opus frame, this delays out of order audio by at most 60ms.
+pub const JITTER_BUFFER_DEPTH: usize = 3;
+
+/// How many frames a user audio stream holds before new frames are dropped.
+pub const USER_AUDIO_CHANNEL_CAPACITY: usize = 256;
+
+/// If a packet is this many sequence numbers behind the stream, we assume the stream was
+/// restarted instead of the packet being late.
+const MAX_LATE_PACKETS: i16 = 100;
+
+/// The most samples per channel one opus packet can decode to (120ms at 48 kHz)
+#[cfg(feature = "voice_opus")]
+const MAX_OPUS_PACKET_SAMPLES: usize = 5760;
+
+/// A frame of audio received from a user in a voice channel.
+///
+/// Frames are delivered in sequence order; gaps in [ReceivedAudio::sequence] mean packets were
+/// lost or arrived too late.
+///
+/// Without the `voice_opus` feature, Chorus does not decode opus itself; since every frame
+/// belongs to one user, the payloads can be fed into one opus decoder per user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceivedAudio {
+    /// The user who sent the audio
+    pub user_id: Snowflake,
+    pub ssrc: u32,
+    /// The rtp sequence number of the frame
+    pub sequence: u16,
+    /// The rtp timestamp of the frame, in 48 kHz samples
+    pub timestamp: u32,
+    /// The opus encoded audio
+    pub opus: Vec<u8>,
+    /// The decoded audio, as interleaved 48 kHz stereo pcm.
+    ///
+    /// Every ssrc has its own decoder. Empty if the frame could not be decoded.
+    #[cfg(feature = "voice_opus")]
+    pub pcm: Vec<i16>,
+}
+
+/// Demultiplexes incoming rtp by ssrc into per-user audio streams.
+///
+/// Ssrcs are mapped to users via [SsrcDefinition] and [Speaking] events from the voice gateway.
+/// Each ssrc has its own jitter buffer, which puts reordered packets back in order.
+///
+/// Can be safely cloned and will still correspond to the same receiver.
+#[derive(Debug, Clone, Default)]
+pub struct VoiceReceiver {
+    state: Arc<Mutex<ReceiverState>>,
+}
+
+#[derive(Debug, Default)]
+struct ReceiverState {
+    users: HashMap<u32, Snowflake>,
+    buffers: HashMap<u32, JitterBuffer>,
+    #[cfg(feature = "voice_opus")]
+    decoders: HashMap<u32, OpusDecoder>,
+    subscribers: HashMap<Snowflake, Vec<mpsc::Sender<ReceivedAudio>>>,
+}
+
+impl VoiceReceiver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Subscribes to the rtp and ssrc events of a voice connection.
+    ///
+    /// This is done automatically for the receiver of a [VoiceConnection](crate::voice::VoiceConnection).
+    pub async fn listen(&self, gateway: &VoiceGatewayHandle, udp: &UdpHandle) {
+        let mut gateway_events = gateway.events.lock().await;
+        gateway_events
+            .ssrc_definition
+            .subscribe(Arc::new(self.clone()));
+        gateway_events.speaking.subscribe(Arc::new(self.clone()));
+        gateway_events
+            .client_disconnect
+            .subscribe(Arc::new(self.clone()));
+        drop(gateway_events);
+
+        udp.events
+            .lock()
+            .await
+            .rtp
+            .subscribe(Arc::new(self.clone()));
+    }
+
+    /// Returns a stream of the audio a user sends, in sequence order.
+    ///
+    /// The stream ends once the receiver is dropped. If the stream is not read from, frames
+    /// beyond [USER_AUDIO_CHANNEL_CAPACITY] are dropped.
+    pub fn subscribe_user_audio(&self, user_id: Snowflake) -> mpsc::Receiver<ReceivedAudio> {
+        let (sender, receiver) = mpsc::channel(USER_AUDIO_CHANNEL_CAPACITY);
+
+        self.state
+            .lock()
+            .unwrap()
+            .subscribers
+            .entry(user_id)
+            .or_default()
+            .push(sender);
+
+        receiver
+    }
+
+    /// Returns the user an ssrc belongs to, if known.
+    pub fn user_id(&self, ssrc: u32) -> Option<Snowflake> {
+        self.state.lock().unwrap().users.get(&ssrc).copied()
+    }
+
+    /// Manually maps an ssrc to a user.
+    pub fn map_ssrc(&self, ssrc: u32, user_id: Snowflake) {
+        trace!("VR: Mapping ssrc {} to user {}", ssrc, user_id);
+        self.state.lock().unwrap().users.insert(ssrc, user_id);
+    }
+
+    /// Buffers a received rtp packet and delivers all frames which are now in order.
+    fn receive(&self, rtp: &Rtp) {
+        let Some(opus) = rtp_payload(rtp.extension, &rtp.payload) else {
+            trace!("VR: Received rtp packet with a malformed header extension");
+            return;
+        };
+
+        let mut state = self.state.lock().unwrap();
+
+        let frames = state.buffers.entry(rtp.ssrc).or_default().push(
+            rtp.sequence.into(),
+            rtp.timestamp.into(),
+            opus.to_vec(),
+        );
+
+        let Some(user_id) = state.users.get(&rtp.ssrc).copied() else {
+            return;
+        };
+
+        if !state.subscribers.contains_key(&user_id) {
+            return;
+        }
+
+        for frame in frames {
+            #[cfg(feature = "voice_opus")]
+            let pcm = state
+                .decoders
+                .entry(rtp.ssrc)
+                .or_insert_with(OpusDecoder::new)
+                .decode(&frame.opus);
+
+            let audio = ReceivedAudio {
+                user_id,
+                ssrc: rtp.ssrc,
+                sequence: frame.sequence,
+                timestamp: frame.timestamp,
+                opus: frame.opus,
+                #[cfg(feature = "voice_opus")]
+                pcm,
+            };
+
+            let senders = state.subscribers.get_mut(&user_id).unwrap();
+            senders.retain(|sender| match sender.try_send(audio.clone()) {
+                Ok(()) => true,
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    trace!(
+                        "VR: User audio stream for {} is full, dropping frame",
+                        user_id
+                    );
+                    true
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => false,
+            });
+        }
+
+        if state
+            .subscribers
+            .get(&user_id)
+            .is_some_and(|senders| senders.is_empty())
+        {
+            state.subscribers.remove(&user_id);
+        }
+    }
+}
+
+#[async_trait]
+impl Observer<Rtp> for VoiceReceiver {
+    async fn update(&self, data: &Rtp) {
+        self.receive(data);
+    }
+}
+
+#[async_trait]
+impl Observer<SsrcDefinition> for VoiceReceiver {
+    async fn update(&self, data: &SsrcDefinition) {
+        if let Some(user_id) = data.user_id {
+            if data.audio_ssrc != 0 {
+                self.map_ssrc(data.audio_ssrc as u32, user_id);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Observer<Speaking> for VoiceReceiver {
+    async fn update(&self, data: &Speaking) {
+        if let Some(user_id) = data.user_id {
+            self.map_ssrc(data.ssrc, user_id);
+        }
+    }
+}
+
+#[async_trait]
+impl Observer<VoiceClientDisconnection> for VoiceReceiver {
+    async fn update(&self, data: &VoiceClientDisconnection) {
+        let mut state = self.state.lock().unwrap();
+
+        let ssrcs: Vec<u32> = state
+            .users
+            .iter()
+            .filter(|(_, user_id)| **user_id == data.user_id)
+            .map(|(ssrc, _)| *ssrc)
+            .collect();
+
+        for ssrc in ssrcs {
+            state.users.remove(&ssrc);
+            state.buffers.remove(&ssrc);
+            #[cfg(feature = "voice_opus")]
+            state.decoders.remove(&ssrc);
+        }
+    }
+}
+
+/// Decodes the opus frames of one rtp stream into pcm.
+#[cfg(feature = "voice_opus")]
+#[derive(Debug)]
+struct OpusDecoder {
+    decoder: Decoder,
+}
+
+#[cfg(feature = "voice_opus")]
+impl OpusDecoder {
+    fn new() -> Self {
+        Self {
+            decoder: Decoder::new(SampleRate::Hz48000, Channels::Stereo)
+                .expect("48 kHz stereo is a valid decoder configuration"),
+        }
+    }
+
+    /// Decodes a frame into interleaved stereo pcm, or returns nothing if it is invalid.
+    fn decode(&mut self, opus: &[u8]) -> Vec<i16> {
+        let mut pcm = vec![0; MAX_OPUS_PACKET_SAMPLES * 2];
+
+        let decoded = Packet::try_from(opus).and_then(|packet| {
+            let output = (&mut pcm).try_into()?;
+            self.decoder.decode(Some(packet), output, false)
+        });
+
+        match decoded {
+            Ok(samples) => {
+                pcm.truncate(samples * 2);
+                pcm
+            }
+            Err(error) => {
+                trace!("VR: Failed to decode opus frame: {}", error);
+                Vec::new()
+            }
+        }
+    }
+}
+
+/// A packet waiting in a [JitterBuffer]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BufferedPacket {
+    sequence: u16,
+    timestamp: u32,
+    opus: Vec<u8>,
+}
+
+/// Puts the packets of one rtp stream back into sequence order.
+#[derive(Debug, Default)]
+struct JitterBuffer {
+    /// The extended sequence number of the next packet to release
+    next: Option<u64>,
+    packets: BTreeMap<u64, BufferedPacket>,
+}
+
+impl JitterBuffer {
+    /// Adds a packet to the buffer, returning all packets which can now be released in order.
+    fn push(&mut self, sequence: u16, timestamp: u32, opus: Vec<u8>) -> Vec<BufferedPacket> {
+        let mut next = *self.next.get_or_insert(sequence as u64);
+        let mut distance = sequence.wrapping_sub(next as u16) as i16;
+
+        if distance < -MAX_LATE_PACKETS {
+            trace!("VR: Rtp stream restarted at sequence {}", sequence);
+            self.packets.clear();
+            next = sequence as u64;
+            distance = 0;
+        } else if distance < 0 {
+            trace!("VR: Dropping late rtp packet {}", sequence);
+            return Vec::new();
+        }
+
+        self.packets.insert(
+            next + distance as u64,
+            BufferedPacket {
+                sequence,
+                timestamp,
+                opus,
+            },
+        );
+
+        let mut released = Vec::new();
+
+        loop {
+            if let Some(packet) = self.packets.remove(&next) {
+                released.push(packet);
+                next += 1;
+                continue;
+            }
+
+            // Give up on the missing packet
+            if self.packets.len() > JITTER_BUFFER_DEPTH {
+                next = *self.packets.keys().next().unwrap();
+                continue;
+            }
+
+            break;
+        }
+
+        self.next = Some(next);
+        released
+    }
+}
+
+#[test]
+// Asserts that reordered packets are released in order and missing packets are skipped
+fn test_jitter_buffer_reordering() {
+    let mut buffer = JitterBuffer::default();
+
+    const NONE: [u16; 0] = [];
+    let sequences = |packets: Vec<BufferedPacket>| -> Vec<u16> {
+        packets.iter().map(|packet| packet.sequence).collect()
+    };
+
+    assert_eq!(sequences(buffer.push(u16::MAX, 0, Vec::new())), [u16::MAX]);
+    assert_eq!(sequences(buffer.push(1, 1920, Vec::new())), NONE);
+    assert_eq!(sequences(buffer.push(0, 960, Vec::new())), [0, 1]);
+
+    // Late packets are dropped
+    assert_eq!(sequences(buffer.push(0, 960, Vec::new())), NONE);
+
+    // 2 is lost
+    for sequence in 3..6 {
+        assert_eq!(sequences(buffer.push(sequence, 0, Vec::new())), NONE);
+    }
+    assert_eq!(sequences(buffer.push(6, 0, Vec::new())), [3, 4, 5, 6]);
+
+    // A restarted stream is not mistaken for late packets
+    assert_eq!(sequences(buffer.push(60000, 0, Vec::new())), [60000]);
+    assert_eq!(sequences(buffer.push(60001, 0, Vec::new())), [60001]);
+}
+
+#[cfg(feature = "voice_opus")]
+#[test]
+// Asserts that reordered frames are decoded per user, in sequence order
+fn test_receive_decodes_opus_in_order() {
+    use audiopus::coder::Encoder;
+
+    let receiver = VoiceReceiver::new();
+    receiver.map_ssrc(1, Snowflake(10));
+    let mut audio = receiver.subscribe_user_audio(Snowflake(10));
+
+    let encoder = Encoder::new(
+        SampleRate::Hz48000,
+        Channels::Stereo,
+        audiopus::Application::Audio,
+    )
+    .unwrap();
+
+    // 20ms of a quiet tone
+    let pcm: Vec<i16> = (0..960 * 2).map(|i| ((i % 100) * 20) as i16).collect();
+    let mut opus = vec![0; 4000];
+    let length = encoder.encode(&pcm, &mut opus).unwrap();
+    opus.truncate(length);
+
+    let rtp = |sequence: u16| Rtp {
+        version: 2,
+        padding: 0,
+        extension: 0,
+        csrc_count: 0,
+        csrc_list: Vec::new(),
+        marker: 0,
+        payload_type: discortp::rtp::RtpType::Dynamic(120),
+        sequence: sequence.into(),
+        timestamp: (sequence as u32 * 960).into(),
+        ssrc: 1,
+        payload: opus.clone(),
+    };
+
+    receiver.receive(&rtp(0));
+    receiver.receive(&rtp(2));
+    receiver.receive(&rtp(1));
+
+    for sequence in 0..3 {
+        let frame = audio.try_recv().unwrap();
+        assert_eq!(frame.user_id, Snowflake(10));
+        assert_eq!(frame.sequence, sequence);
+        assert_eq!(frame.opus, opus);
+        assert_eq!(frame.pcm.len(), 960 * 2);
+    }
+    assert!(audio.try_recv().is_err());
+
+    // Invalid frames are still delivered, just without pcm
+    let mut invalid = rtp(3);
+    invalid.payload = vec![0xFF; 3];
+    receiver.receive(&invalid);
+    assert!(audio.try_recv().unwrap().pcm.is_empty());
+}
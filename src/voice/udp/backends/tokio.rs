@@ -2,7 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 
 use crate::errors::VoiceUdpError;
 
@@ -12,9 +12,30 @@ pub struct TokioBackend;
 pub type TokioSocket = tokio::net::UdpSocket;
 
 impl TokioBackend {
-    pub async fn connect(url: SocketAddr) -> Result<TokioSocket, VoiceUdpError> {
-        // Bind with a port number of 0, so the os assigns this listener a port
-        let udp_socket_result = TokioSocket::bind("0.0.0.0:0").await;
+    /// Binds a socket to `bind_address` and connects it to `url`.
+    ///
+    /// If the socket is bound to an IPv6 address and `url` is an IPv4 address, the
+    /// IPv4-mapped IPv6 address of `url` is connected to instead.
+    pub async fn connect(
+        url: SocketAddr,
+        bind_address: SocketAddr,
+    ) -> Result<TokioSocket, VoiceUdpError> {
+        let url = match (bind_address, url) {
+            (SocketAddr::V6(_), SocketAddr::V4(v4)) => {
+                SocketAddr::new(IpAddr::V6(v4.ip().to_ipv6_mapped()), v4.port())
+            }
+            (SocketAddr::V4(_), SocketAddr::V6(_)) => {
+                return Err(VoiceUdpError::CannotConnect {
+                    error: format!(
+                        "cannot connect to IPv6 address {} from IPv4 address {}",
+                        url, bind_address
+                    ),
+                });
+            }
+            _ => url,
+        };
+
+        let udp_socket_result = TokioSocket::bind(bind_address).await;
 
         if let Err(e) = udp_socket_result {
             return Err(VoiceUdpError::CannotBind {
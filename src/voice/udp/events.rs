@@ -7,6 +7,8 @@ use pubserve::Publisher;
 
 use crate::types::WebSocketEvent;
 
+use super::VideoFrame;
+
 impl WebSocketEvent for Rtp {}
 impl WebSocketEvent for Rtcp {}
 
@@ -14,6 +16,8 @@ impl WebSocketEvent for Rtcp {}
 pub struct VoiceUDPEvents {
     pub rtp: Publisher<Rtp>,
     pub rtcp: Publisher<Rtcp>,
+    /// Complete video frames, reassembled from rtp packets with a video payload type
+    pub video_frame: Publisher<VideoFrame>,
 }
 
 impl Default for VoiceUDPEvents {
@@ -21,6 +25,7 @@ impl Default for VoiceUDPEvents {
         Self {
             rtp: Publisher::new(),
             rtcp: Publisher::new(),
+            video_frame: Publisher::new(),
         }
     }
 }
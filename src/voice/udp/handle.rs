@@ -14,15 +14,17 @@ use log::*;
 
 use tokio::{sync::Mutex, sync::RwLock};
 
-use super::UdpSocket;
+use super::{UdpSendPriority, UdpSender};
 
 use crate::{
     errors::VoiceUdpError,
-    types::VoiceEncryptionMode,
+    types::{VideoCodec, VoiceEncryptionMode},
     voice::{crypto::get_xsalsa20_poly1305_nonce, voice_data::VoiceData},
 };
 
-use super::{events::VoiceUDPEvents, RTP_HEADER_SIZE};
+use super::{
+    events::VoiceUDPEvents, statistics::RTCP_HEADER_SIZE, video::packetize, RTP_HEADER_SIZE,
+};
 
 /// Handle to a voice UDP connection
 ///
@@ -30,7 +32,7 @@ use super::{events::VoiceUDPEvents, RTP_HEADER_SIZE};
 #[derive(Debug, Clone)]
 pub struct UdpHandle {
     pub events: Arc<Mutex<VoiceUDPEvents>>,
-    pub(super) socket: Arc<UdpSocket>,
+    pub(super) sender: UdpSender,
     pub data: Arc<RwLock<VoiceData>>,
 }
 
@@ -85,7 +87,131 @@ impl UdpHandle {
         let mut rtp_packet = discortp::rtp::MutableRtpPacket::new(&mut buffer).expect("Mangled rtp packet creation buffer, something is very wrong. Please open an issue on the chorus github: https://github.com/polyphony-chat/chorus/issues/new");
         rtp_packet.populate(&rtp_data);
 
-        self.send_rtp_packet(rtp_packet).await
+        self.send_rtp_packet(rtp_packet).await?;
+
+        self.data
+            .write()
+            .await
+            .statistics
+            .record_sent(timestamp, payload_len);
+
+        Ok(())
+    }
+
+    /// Splits an encoded video frame into rtp packets, encrypts them and sends them.
+    ///
+    /// The frame is sent on the ssrc of the first video stream in
+    /// [VoiceReady::streams](crate::types::VoiceReady::streams); announce it with
+    /// [VoiceGatewayHandle::send_ssrc_definition](crate::voice::gateway::VoiceGatewayHandle::send_ssrc_definition)
+    /// before sending video.
+    ///
+    /// `timestamp` is in [VIDEO_CLOCK_RATE](super::VIDEO_CLOCK_RATE) units. H264 frames are
+    /// expected in the Annex B format.
+    ///
+    /// Frames without any data to send, such as empty frames or H264 frames which only consist of
+    /// start codes, are skipped.
+    ///
+    /// # Errors
+    /// If we do not have VoiceReady data with a video stream, this returns a
+    /// [VoiceUdpError::NoData] error.
+    ///
+    /// If we have not received an encryption key, this returns a [VoiceUdpError::NoKey] error.
+    ///
+    /// If the UDP socket is broken, this returns a [VoiceUdpError::BrokenSocket] error.
+    pub async fn send_video_frame(
+        &self,
+        codec: VideoCodec,
+        timestamp: u32,
+        frame: &[u8],
+    ) -> Result<(), VoiceUdpError> {
+        let ssrc = self
+            .data
+            .read()
+            .await
+            .ready_data
+            .as_ref()
+            .and_then(|ready| ready.streams.first())
+            .map(|stream| stream.ssrc)
+            .filter(|ssrc| *ssrc != 0)
+            .ok_or(VoiceUdpError::NoData)?;
+
+        let payloads = packetize(codec, frame);
+        let Some(last_index) = payloads.len().checked_sub(1) else {
+            debug!("VUDP: Skipping video frame without any data to send");
+            return Ok(());
+        };
+
+        for (index, payload) in payloads.into_iter().enumerate() {
+            let mut data = self.data.write().await;
+            let sequence_number = data.last_video_sequence_number.wrapping_add(1);
+            data.last_video_sequence_number = sequence_number;
+            drop(data);
+
+            let buffer_size = payload.len() + RTP_HEADER_SIZE as usize;
+
+            let rtp_data = discortp::rtp::Rtp {
+                version: 2,
+                padding: 0,
+                extension: 0,
+                csrc_count: 0,
+                csrc_list: Vec::new(),
+                // Set on the last packet of a frame
+                marker: (index == last_index) as u8,
+                payload_type: discortp::rtp::RtpType::Dynamic(codec.payload_type()),
+                sequence: sequence_number.into(),
+                timestamp: timestamp.into(),
+                ssrc,
+                payload,
+            };
+
+            let mut buffer = vec![0; buffer_size];
+
+            let mut rtp_packet = discortp::rtp::MutableRtpPacket::new(&mut buffer).expect("Mangled rtp packet creation buffer, something is very wrong. Please open an issue on the chorus github: https://github.com/polyphony-chat/chorus/issues/new");
+            rtp_packet.populate(&rtp_data);
+
+            self.send_rtp_packet(rtp_packet).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Constructs and sends an RTCP report about our connection.
+    ///
+    /// If we have sent any audio, this is a sender report, otherwise a receiver report.
+    /// Both contain report blocks about the streams we receive.
+    ///
+    /// # Errors
+    /// If we do not have VoiceReady data, which contains our ssrc, this returns a
+    /// [VoiceUdpError::NoData] error.
+    ///
+    /// If we have not received an encryption key, this returns a [VoiceUdpError::NoKey] error.
+    ///
+    /// If the UDP socket is broken, this returns a [VoiceUdpError::BrokenSocket] error.
+    pub async fn send_rtcp_report(&self) -> Result<(), VoiceUdpError> {
+        let mut data = self.data.write().await;
+
+        let Some(ssrc) = data.ready_data.as_ref().map(|ready| ready.ssrc) else {
+            return Err(VoiceUdpError::NoData);
+        };
+
+        let report = if data.statistics.outgoing.packets_sent > 0 {
+            data.statistics.sender_report(ssrc)
+        } else {
+            data.statistics.receiver_report(ssrc)
+        };
+
+        drop(data);
+
+        let encrypted = self.encrypt_payload(&report, RTCP_HEADER_SIZE).await?;
+
+        // Reports are sent before any queued media, so bursts of video don't delay them
+        self.sender
+            .send(encrypted, UdpSendPriority::Control)
+            .await?;
+
+        trace!("VUDP: Queued rtcp report!");
+
+        Ok(())
     }
 
     /// Encrypts and sends and rtp packet.
@@ -116,7 +242,19 @@ impl UdpHandle {
         &self,
         packet: &discortp::rtp::MutableRtpPacket<'_>,
     ) -> Result<Vec<u8>, VoiceUdpError> {
-        let payload = packet.payload();
+        self.encrypt_payload(packet.packet(), RTP_HEADER_SIZE as usize)
+            .await
+    }
+
+    /// Encrypts the payload of an rtp or rtcp packet, whose header is `header_size` bytes long.
+    ///
+    /// Returns a copy of the packet's bytes with an encrypted payload.
+    async fn encrypt_payload(
+        &self,
+        packet_bytes: &[u8],
+        header_size: usize,
+    ) -> Result<Vec<u8>, VoiceUdpError> {
+        let payload = &packet_bytes[header_size..];
 
         let session_description_result = self.data.read().await.session_description.clone();
 
@@ -129,7 +267,9 @@ impl UdpHandle {
         let session_description = session_description_result.unwrap();
 
         let mut nonce_bytes = match session_description.encryption_mode {
-            VoiceEncryptionMode::Xsalsa20Poly1305 => get_xsalsa20_poly1305_nonce(packet.packet()),
+            VoiceEncryptionMode::Xsalsa20Poly1305 => {
+                get_xsalsa20_poly1305_nonce(packet_bytes, header_size)
+            }
             VoiceEncryptionMode::Xsalsa20Poly1305Suffix => {
                 // Generate 24 random bytes
                 let mut random_destinaton: Vec<u8> = vec![0; 24];
@@ -220,19 +360,22 @@ impl UdpHandle {
 
         // We need to allocate a new buffer, since the old one is too small for our new encrypted
         // data
-        let buffer_size = encrypted_payload.len() + RTP_HEADER_SIZE as usize;
+        let buffer_size = encrypted_payload.len() + header_size;
 
         let mut new_buffer: Vec<u8> = Vec::with_capacity(buffer_size);
 
-        let mut rtp_header = packet.packet().to_vec()[0..RTP_HEADER_SIZE as usize].to_vec();
+        let mut header = packet_bytes[0..header_size].to_vec();
 
-        new_buffer.append(&mut rtp_header);
+        new_buffer.append(&mut header);
         new_buffer.append(&mut encrypted_payload);
 
         Ok(new_buffer)
     }
 
-    /// Sends an (already encrypted) rtp packet to the connection.
+    /// Queues an (already encrypted) rtp packet to be sent to the connection.
+    ///
+    /// Rtp packets are sent after any queued control packets (keepalives and rtcp reports).
+    /// If too many packets are queued, this waits until there is room for another one.
     ///
     /// # Errors
     /// If the Udp socket is broken, this returns a [VoiceUdpError::BrokenSocket] error.
@@ -240,16 +383,11 @@ impl UdpHandle {
         &self,
         packet: discortp::rtp::RtpPacket<'_>,
     ) -> Result<(), VoiceUdpError> {
-        let raw_bytes = packet.packet();
-
-        let send_res = self.socket.send(raw_bytes).await;
-        if let Err(e) = send_res {
-            return Err(VoiceUdpError::BrokenSocket {
-                error: format!("{:?}", e),
-            });
-        }
+        self.sender
+            .send(packet.packet().to_vec(), UdpSendPriority::Media)
+            .await?;
 
-        trace!("VUDP: Sent rtp packet!");
+        trace!("VUDP: Queued rtp packet!");
 
         Ok(())
     }
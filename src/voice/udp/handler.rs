@@ -14,22 +14,27 @@ use discortp::discord::{
     IpDiscovery, IpDiscoveryPacket, IpDiscoveryType, MutableIpDiscoveryPacket,
 };
 use discortp::rtcp::report::ReceiverReport;
+use discortp::rtcp::report::SenderInfoPacket;
 use discortp::rtcp::report::SenderReport;
+use discortp::rtp::RtpType;
 use discortp::{demux::demux, Packet};
 use tokio::sync::{Mutex, RwLock};
 
 use super::UdpBackend;
 use super::UdpSocket;
 
-use super::RTP_HEADER_SIZE;
+use super::statistics::RTCP_HEADER_SIZE;
+use super::video::VideoFrameAssembler;
+use super::{rtp_payload, RTP_HEADER_SIZE, UDP_MAX_PACKET_SIZE};
 use crate::errors::VoiceUdpError;
-use crate::types::VoiceEncryptionMode;
+use crate::types::{VideoCodec, VoiceEncryptionMode};
 use crate::voice::crypto::get_xsalsa20_poly1305_lite_nonce;
 use crate::voice::crypto::get_xsalsa20_poly1305_nonce;
 use crate::voice::crypto::get_xsalsa20_poly1305_suffix_nonce;
 use crate::voice::voice_data::VoiceData;
+use crate::voice::VoiceOptions;
 
-use super::{events::VoiceUDPEvents, UdpHandle};
+use super::{events::VoiceUDPEvents, UdpHandle, UdpSender};
 
 use log::*;
 
@@ -39,18 +44,28 @@ pub struct UdpHandler {
     events: Arc<Mutex<VoiceUDPEvents>>,
     pub data: Arc<RwLock<VoiceData>>,
     socket: Arc<UdpSocket>,
+    video_frames: VideoFrameAssembler,
 }
 
 impl UdpHandler {
     /// Spawns a new UDP handler and performs IP discovery.
     ///
-    /// Mutates the given data_reference with the IP discovery data.
+    /// Mutates the given data_reference with the IP discovery data, see
+    /// [VoiceData::external_address].
+    ///
+    /// Both IPv4 and IPv6 endpoints are supported; the local address the socket is bound to
+    /// can be set via [VoiceOptions::bind_address].
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "chorus.voice.udp.connect", skip(data_reference))
+    )]
     pub async fn spawn(
         data_reference: Arc<RwLock<VoiceData>>,
         url: SocketAddr,
         ssrc: u32,
+        options: VoiceOptions,
     ) -> Result<UdpHandle, VoiceUdpError> {
-        let udp_socket = UdpBackend::connect(url).await?;
+        let udp_socket = UdpBackend::connect(url, options.bind_address_for(url)).await?;
 
         // First perform ip discovery
         let ip_discovery = IpDiscovery {
@@ -125,6 +140,7 @@ impl UdpHandler {
             events: shared_events.clone(),
             data: data_reference.clone(),
             socket: socket.clone(),
+            video_frames: VideoFrameAssembler::default(),
         };
 
         // Now we can continuously check for messages in a different task
@@ -132,9 +148,11 @@ impl UdpHandler {
             handler.listen_task().await;
         });
 
+        let sender = UdpSender::spawn(socket, ssrc);
+
         Ok(UdpHandle {
             events: shared_events,
-            socket,
+            sender,
             data: data_reference,
         })
     }
@@ -144,15 +162,12 @@ impl UdpHandler {
     /// Receives UDP messages and parses them.
     async fn listen_task(&mut self) {
         loop {
-            // FIXME: is there a max size for these packets?
-            // Allocating 512 bytes seems a bit extreme
-            //
-            // Update: see <https://stackoverflow.com/questions/58097580/rtp-packet-maximum-size>
+            // See <https://stackoverflow.com/questions/58097580/rtp-packet-maximum-size>
             // > "The RTP standard does not set a maximum size.."
             //
-            // The theoretical max for this buffer would be 1458 bytes, but that is imo
-            // unreasonable to allocate for every message.
-            let mut buf: Vec<u8> = vec![0; 512];
+            // Audio packets are small, but video packets are sized to fill an MTU, so we need
+            // to allocate enough for a whole one.
+            let mut buf: Vec<u8> = vec![0; UDP_MAX_PACKET_SIZE];
 
             let result = self.socket.recv(&mut buf).await;
             if let Ok(size) = result {
@@ -166,7 +181,7 @@ impl UdpHandler {
     }
 
     /// Handles a message buf
-    async fn handle_message(&self, buf: &[u8]) {
+    async fn handle_message(&mut self, buf: &[u8]) {
         let parsed = demux(buf);
 
         match parsed {
@@ -196,6 +211,18 @@ impl UdpHandler {
 
                 trace!("VUDP: Successfully decrypted voice data!");
 
+                self.data.write().await.statistics.record_rtp(
+                    rtp.get_ssrc(),
+                    rtp.get_sequence().into(),
+                    rtp.get_timestamp().into(),
+                );
+
+                if let RtpType::Dynamic(payload_type) = rtp.get_payload_type() {
+                    if let Some(codec) = VideoCodec::from_payload_type(payload_type) {
+                        self.handle_video(&rtp, codec, &decrypted).await;
+                    }
+                }
+
                 let rtp_with_decrypted_data = discortp::rtp::Rtp {
                     ssrc: rtp.get_ssrc(),
                     marker: rtp.get_marker(),
@@ -220,13 +247,51 @@ impl UdpHandler {
             Demuxed::Rtcp(rtcp) => {
                 trace!("VUDP: Parsed packet as rtcp!");
 
-                let rtcp_data = match rtcp {
-                    discortp::rtcp::RtcpPacket::KnownType(knowntype) => {
-                        discortp::rtcp::Rtcp::KnownType(knowntype)
+                if let discortp::rtcp::RtcpPacket::KnownType(knowntype) = rtcp {
+                    self.events
+                        .lock()
+                        .await
+                        .rtcp
+                        .publish(discortp::rtcp::Rtcp::KnownType(knowntype))
+                        .await;
+                    return;
+                }
+
+                let payload = match self.decrypt_rtcp_packet_payload(rtcp.packet()).await {
+                    Ok(payload) => payload,
+                    Err(e) => {
+                        warn!("VUDP: Failed to decrypt rtcp data: {}", e);
+                        self.data
+                            .write()
+                            .await
+                            .statistics
+                            .undecryptable_rtcp_packets += 1;
+                        return;
                     }
+                };
+
+                let mut data = self.data.write().await;
+                let own_ssrc = data.ready_data.as_ref().map(|ready| ready.ssrc);
+
+                let rtcp_data = match rtcp {
                     discortp::rtcp::RtcpPacket::SenderReport(senderreport) => {
+                        let count = senderreport.get_rx_report_count() as usize;
+                        data.statistics
+                            .record_sender_info(senderreport.get_ssrc(), &payload);
+
+                        if let Some(own_ssrc) = own_ssrc {
+                            let sender_info_size = SenderInfoPacket::minimum_packet_size();
+                            if payload.len() >= sender_info_size {
+                                data.statistics.record_report_blocks(
+                                    own_ssrc,
+                                    &payload[sender_info_size..],
+                                    count,
+                                );
+                            }
+                        }
+
                         discortp::rtcp::Rtcp::SenderReport(SenderReport {
-                            payload: senderreport.payload().to_vec(),
+                            payload,
                             padding: senderreport.get_padding(),
                             version: senderreport.get_version(),
                             ssrc: senderreport.get_ssrc(),
@@ -236,8 +301,16 @@ impl UdpHandler {
                         })
                     }
                     discortp::rtcp::RtcpPacket::ReceiverReport(receiverreport) => {
+                        if let Some(own_ssrc) = own_ssrc {
+                            data.statistics.record_report_blocks(
+                                own_ssrc,
+                                &payload,
+                                receiverreport.get_rx_report_count() as usize,
+                            );
+                        }
+
                         discortp::rtcp::Rtcp::ReceiverReport(ReceiverReport {
-                            payload: receiverreport.payload().to_vec(),
+                            payload,
                             padding: receiverreport.get_padding(),
                             version: receiverreport.get_version(),
                             ssrc: receiverreport.get_ssrc(),
@@ -251,6 +324,8 @@ impl UdpHandler {
                     }
                 };
 
+                drop(data);
+
                 self.events.lock().await.rtcp.publish(rtcp_data).await;
             }
             Demuxed::FailedParse(e) => {
@@ -262,6 +337,33 @@ impl UdpHandler {
         }
     }
 
+    /// Adds a decrypted video packet to its frame, publishing the frame once it is complete.
+    async fn handle_video(
+        &mut self,
+        rtp: &discortp::rtp::RtpPacket<'_>,
+        codec: VideoCodec,
+        decrypted: &[u8],
+    ) {
+        let Some(payload) = rtp_payload(rtp.get_extension(), decrypted) else {
+            warn!("VUDP: Received video packet with a malformed header extension");
+            return;
+        };
+
+        let frame = self.video_frames.push(
+            rtp.get_ssrc(),
+            codec,
+            rtp.get_sequence().into(),
+            rtp.get_timestamp().into(),
+            rtp.get_marker() != 0,
+            payload.to_vec(),
+        );
+
+        if let Some(frame) = frame {
+            trace!("VUDP: Received video frame!");
+            self.events.lock().await.video_frame.publish(frame).await;
+        }
+    }
+
     /// Decrypts an encrypted rtp packet, returning a decrypted copy of the packet's payload
     /// bytes.
     ///
@@ -273,10 +375,35 @@ impl UdpHandler {
         &self,
         rtp: &discortp::rtp::RtpPacket<'_>,
     ) -> Result<Vec<u8>, VoiceUdpError> {
-        let packet_bytes = rtp.packet();
+        self.decrypt_payload(rtp.packet(), RTP_HEADER_SIZE as usize)
+            .await
+    }
+
+    /// Decrypts the body of an encrypted rtcp sender or receiver report, returning a decrypted
+    /// copy of the packet's payload bytes.
+    ///
+    /// # Errors
+    /// If we have not received an encryption key, this returns a [VoiceUdpError::NoKey] error.
+    ///
+    /// If the decryption fails, this returns a [VoiceUdpError::FailedDecryption].
+    pub async fn decrypt_rtcp_packet_payload(
+        &self,
+        packet_bytes: &[u8],
+    ) -> Result<Vec<u8>, VoiceUdpError> {
+        self.decrypt_payload(packet_bytes, RTCP_HEADER_SIZE).await
+    }
+
+    /// Decrypts the payload of an rtp or rtcp packet, whose header is `header_size` bytes long
+    async fn decrypt_payload(
+        &self,
+        packet_bytes: &[u8],
+        header_size: usize,
+    ) -> Result<Vec<u8>, VoiceUdpError> {
+        if packet_bytes.len() < header_size {
+            return Err(VoiceUdpError::FailedDecryption);
+        }
 
-        let mut ciphertext: Vec<u8> =
-            packet_bytes[(RTP_HEADER_SIZE as usize)..packet_bytes.len()].to_vec();
+        let mut ciphertext: Vec<u8> = packet_bytes[header_size..packet_bytes.len()].to_vec();
 
         let session_description_result = self.data.read().await.session_description.clone();
 
@@ -289,9 +416,14 @@ impl UdpHandler {
         let session_description = session_description_result.unwrap();
 
         let nonce_bytes = match session_description.encryption_mode {
-            VoiceEncryptionMode::Xsalsa20Poly1305 => get_xsalsa20_poly1305_nonce(packet_bytes),
+            VoiceEncryptionMode::Xsalsa20Poly1305 => {
+                get_xsalsa20_poly1305_nonce(packet_bytes, header_size)
+            }
             VoiceEncryptionMode::Xsalsa20Poly1305Suffix => {
                 // Remove the suffix from the ciphertext
+                if ciphertext.len() < 24 {
+                    return Err(VoiceUdpError::FailedDecryption);
+                }
                 ciphertext = ciphertext[0..ciphertext.len() - 24].to_vec();
                 get_xsalsa20_poly1305_suffix_nonce(packet_bytes)
             }
@@ -301,6 +433,9 @@ impl UdpHandler {
             // I have no idea how Rtpsize works.
             VoiceEncryptionMode::Xsalsa20Poly1305Lite => {
                 // Remove the suffix from the ciphertext
+                if ciphertext.len() < 4 {
+                    return Err(VoiceUdpError::FailedDecryption);
+                }
                 ciphertext = ciphertext[0..ciphertext.len() - 4].to_vec();
                 get_xsalsa20_poly1305_lite_nonce(packet_bytes)
             }
@@ -354,3 +489,118 @@ impl UdpHandler {
         Ok(decryption_result.unwrap())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Answers a single IP discovery request, claiming the client's address is `address`
+    async fn answer_ip_discovery(server: UdpSocket, address: SocketAddr) {
+        let mut buf = vec![0; IpDiscoveryPacket::minimum_packet_size() + 64];
+        let (_, client) = server.recv_from(&mut buf).await.unwrap();
+        let ssrc = IpDiscoveryPacket::new(&buf).unwrap().get_ssrc();
+
+        let mut address_bytes = address.ip().to_string().into_bytes();
+        address_bytes.resize(64, 0);
+
+        let mut response = MutableIpDiscoveryPacket::new(&mut buf).unwrap();
+        response.populate(&IpDiscovery {
+            pkt_type: IpDiscoveryType::Response,
+            ssrc,
+            length: 70,
+            address: address_bytes,
+            port: address.port(),
+            payload: Vec::new(),
+        });
+
+        server.send_to(response.packet(), client).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn ip_discovery_over_ipv6() {
+        let server = UdpSocket::bind("[::1]:0").await.unwrap();
+        let url = server.local_addr().unwrap();
+
+        let external_address: SocketAddr = "[2001:db8::1]:50000".parse().unwrap();
+        tokio::spawn(answer_ip_discovery(server, external_address));
+
+        let data = Arc::new(RwLock::new(VoiceData::default()));
+        UdpHandler::spawn(data.clone(), url, 1, VoiceOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            data.read().await.external_address().unwrap(),
+            external_address
+        );
+    }
+
+    #[tokio::test]
+    async fn connect_from_bind_address() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let url = server.local_addr().unwrap();
+
+        let options = VoiceOptions {
+            bind_address: Some("127.0.0.1:0".parse().unwrap()),
+        };
+        let socket = UdpBackend::connect(url, options.bind_address_for(url))
+            .await
+            .unwrap();
+        assert!(socket.local_addr().unwrap().ip().is_loopback());
+
+        // An IPv4 socket can't reach an IPv6 endpoint
+        let ipv6_url = "[::1]:1234".parse().unwrap();
+        assert!(matches!(
+            UdpBackend::connect(ipv6_url, options.bind_address_for(ipv6_url)).await,
+            Err(VoiceUdpError::CannotConnect { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn skip_empty_video_frames() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let url = server.local_addr().unwrap();
+
+        let external_address: SocketAddr = "127.0.0.1:50000".parse().unwrap();
+        tokio::spawn(answer_ip_discovery(server, external_address));
+
+        let data = Arc::new(RwLock::new(VoiceData::default()));
+        let handle = UdpHandler::spawn(data.clone(), url, 1, VoiceOptions::default())
+            .await
+            .unwrap();
+
+        let stream = serde_json::from_str(r#"{"type": "video", "rid": "100", "ssrc": 2}"#).unwrap();
+        data.write().await.ready_data = Some(crate::types::VoiceReady {
+            streams: vec![stream],
+            ..Default::default()
+        });
+
+        // Neither frame contains a NAL unit to packetize
+        for frame in [&[][..], &[0, 0, 0, 1, 0, 0, 1]] {
+            handle
+                .send_video_frame(VideoCodec::H264, 0, frame)
+                .await
+                .unwrap();
+        }
+        assert_eq!(data.read().await.last_video_sequence_number, 0);
+    }
+
+    #[tokio::test]
+    async fn count_undecryptable_rtcp_packets() {
+        let data = Arc::new(RwLock::new(VoiceData::default()));
+        let mut handler = UdpHandler {
+            events: Arc::new(Mutex::new(VoiceUDPEvents::default())),
+            data: data.clone(),
+            socket: Arc::new(UdpSocket::bind("127.0.0.1:0").await.unwrap()),
+            video_frames: VideoFrameAssembler::default(),
+        };
+
+        // A receiver report with one (encrypted) report block, received before we have a key
+        let mut receiver_report = vec![0x81, 201, 0, 7, 0, 0, 0, 1];
+        receiver_report.extend_from_slice(&[0; 24]);
+
+        handler.handle_message(&receiver_report).await;
+
+        assert_eq!(data.read().await.statistics.undecryptable_rtcp_packets, 1);
+    }
+}
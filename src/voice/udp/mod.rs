@@ -8,11 +8,36 @@
 /// This always adds up to 12 bytes
 const RTP_HEADER_SIZE: u8 = 12;
 
+/// The largest UDP packet we expect to receive, the size of a typical MTU
+const UDP_MAX_PACKET_SIZE: usize = 1500;
+
 pub mod backends;
 pub mod events;
 pub mod handle;
 pub mod handler;
+pub mod sender;
+pub mod statistics;
+pub mod video;
 
 pub use backends::*;
 pub use handle::*;
 pub use handler::*;
+pub use sender::*;
+pub use statistics::*;
+pub use video::*;
+
+/// Returns the payload of a decrypted rtp packet without its header extension, which is
+/// encrypted along with the payload.
+///
+/// See <https://www.rfc-editor.org/rfc/rfc3550#section-5.3.1>
+pub(crate) fn rtp_payload(extension: u8, payload: &[u8]) -> Option<&[u8]> {
+    if extension == 0 {
+        return Some(payload);
+    }
+
+    let header = payload.get(0..4)?;
+    // The length is in 32 bit words, excluding the 4 byte header itself
+    let length = u16::from_be_bytes([header[2], header[3]]) as usize * 4;
+
+    payload.get(4 + length..)
+}
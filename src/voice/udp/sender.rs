@@ -0,0 +1,184 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Sends UDP packets from a separate task, prioritizing control packets over media.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use discortp::discord::{KeepalivePacket, MutableKeepalivePacket};
+use log::*;
+use tokio::sync::mpsc;
+
+use super::UdpSocket;
+use crate::errors::VoiceUdpError;
+
+/// How often we send a keepalive packet to the voice server
+pub const UDP_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How many media (rtp) packets can be queued before sending them waits for the queue to drain
+///
+/// A large video frame is split into a few hundred packets at most.
+pub const MEDIA_QUEUE_CAPACITY: usize = 512;
+
+/// How many control (rtcp) packets can be queued before sending them waits for the queue to drain
+pub const CONTROL_QUEUE_CAPACITY: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The queue a packet is sent through.
+///
+/// Control packets are always sent before any queued media packets, so large bursts of audio or
+/// video cannot delay them.
+pub enum UdpSendPriority {
+    /// Keepalives and rtcp reports
+    Control,
+    /// Rtp packets containing audio or video
+    Media,
+}
+
+/// Queues packets for the UDP sender task.
+///
+/// Can be cloned; the task stops once all clones have been dropped or sending fails.
+#[derive(Debug, Clone)]
+pub(crate) struct UdpSender {
+    control: mpsc::Sender<Vec<u8>>,
+    media: mpsc::Sender<Vec<u8>>,
+    /// Why the sender task stopped, if it failed to send a packet
+    error: Arc<Mutex<Option<String>>>,
+}
+
+impl UdpSender {
+    /// Spawns the sender task for a socket.
+    ///
+    /// The task also sends a keepalive for `ssrc` every [UDP_KEEPALIVE_INTERVAL].
+    pub(crate) fn spawn(socket: Arc<UdpSocket>, ssrc: u32) -> UdpSender {
+        let (control, control_receive) = mpsc::channel(CONTROL_QUEUE_CAPACITY);
+        let (media, media_receive) = mpsc::channel(MEDIA_QUEUE_CAPACITY);
+        let error = Arc::new(Mutex::new(None));
+
+        tokio::spawn(Self::sender_task(
+            socket,
+            ssrc,
+            control_receive,
+            media_receive,
+            error.clone(),
+        ));
+
+        UdpSender {
+            control,
+            media,
+            error,
+        }
+    }
+
+    /// Queues an (already encrypted) packet for sending.
+    ///
+    /// Waits if the queue for the given priority is full.
+    ///
+    /// # Errors
+    /// If the sender task stopped because the UDP socket is broken, this returns a
+    /// [VoiceUdpError::BrokenSocket] error.
+    pub(crate) async fn send(
+        &self,
+        packet: Vec<u8>,
+        priority: UdpSendPriority,
+    ) -> Result<(), VoiceUdpError> {
+        let queue = match priority {
+            UdpSendPriority::Control => &self.control,
+            UdpSendPriority::Media => &self.media,
+        };
+
+        if queue.send(packet).await.is_err() {
+            let error = self
+                .error
+                .lock()
+                .unwrap()
+                .clone()
+                .unwrap_or_else(|| String::from("UDP sender task stopped"));
+            return Err(VoiceUdpError::BrokenSocket { error });
+        }
+
+        Ok(())
+    }
+
+    /// Sends queued packets, always emptying the control queue before sending media.
+    async fn sender_task(
+        socket: Arc<UdpSocket>,
+        ssrc: u32,
+        mut control: mpsc::Receiver<Vec<u8>>,
+        mut media: mpsc::Receiver<Vec<u8>>,
+        error: Arc<Mutex<Option<String>>>,
+    ) {
+        let mut keepalive = tokio::time::interval(UDP_KEEPALIVE_INTERVAL);
+        keepalive.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            let packet = tokio::select! {
+                biased;
+                packet = control.recv() => match packet {
+                    Some(packet) => packet,
+                    // All senders have been dropped
+                    None => break,
+                },
+                _ = keepalive.tick() => {
+                    trace!("VUDP: Sending keepalive");
+                    keepalive_packet(ssrc)
+                },
+                Some(packet) = media.recv() => packet,
+            };
+
+            if let Err(e) = socket.send(&packet).await {
+                warn!("VUDP: Failed to send packet, stopping sender task: {:?}", e);
+                *error.lock().unwrap() = Some(format!("{:?}", e));
+                break;
+            }
+        }
+
+        trace!("VUDP: Closing sender task");
+    }
+}
+
+/// Creates a keepalive packet for our ssrc.
+fn keepalive_packet(ssrc: u32) -> Vec<u8> {
+    let mut buffer = vec![0; KeepalivePacket::minimum_packet_size()];
+
+    // Safety: expect is justified here, the buffer is exactly as large as the packet needs.
+    let mut packet = MutableKeepalivePacket::new(&mut buffer).expect("Mangled keepalive packet creation buffer, something is very wrong. Please open an issue on the chorus github: https://github.com/polyphony-chat/chorus/issues/new");
+    packet.set_ssrc(ssrc);
+
+    buffer
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn sends_keepalives_and_queued_packets() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        client.connect(server.local_addr().unwrap()).await.unwrap();
+
+        let sender = UdpSender::spawn(Arc::new(client), 0x01020304);
+
+        // The first keepalive is sent right away
+        let mut buffer = [0; 16];
+        let size = server.recv(&mut buffer).await.unwrap();
+        assert_eq!(&buffer[..size], &[1, 2, 3, 4]);
+
+        sender
+            .send(vec![5, 6], UdpSendPriority::Media)
+            .await
+            .unwrap();
+        let size = server.recv(&mut buffer).await.unwrap();
+        assert_eq!(&buffer[..size], &[5, 6]);
+
+        sender
+            .send(vec![7], UdpSendPriority::Control)
+            .await
+            .unwrap();
+        let size = server.recv(&mut buffer).await.unwrap();
+        assert_eq!(&buffer[..size], &[7]);
+    }
+}
@@ -0,0 +1,403 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Statistics about the rtp streams of a voice connection, as reported via RTCP.
+//!
+//! See <https://www.rfc-editor.org/rfc/rfc3550#section-6.4>
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use discortp::rtcp::report::{
+    MutableReceiverReportPacket, MutableReportBlockPacket, MutableSenderInfoPacket,
+    MutableSenderReportPacket, ReceiverReport, ReportBlock, ReportBlockPacket, SenderInfo,
+    SenderInfoPacket, SenderReport,
+};
+use discortp::rtcp::RtcpType;
+
+/// The clock rate of opus rtp timestamps
+pub const OPUS_CLOCK_RATE: u32 = 48_000;
+
+/// The size of an RTCP sender or receiver report header
+pub(crate) const RTCP_HEADER_SIZE: usize = 8;
+
+/// The maximum amount of report blocks in one report
+const MAX_REPORT_BLOCKS: usize = 31;
+
+/// Seconds between the NTP epoch (1900) and the unix epoch
+const NTP_UNIX_OFFSET: u64 = 2_208_988_800;
+
+/// Statistics about all rtp streams of a voice connection.
+///
+/// Incoming streams are tracked from the rtp packets we receive, our outgoing stream from the
+/// RTCP reports the server sends about it.
+#[derive(Debug, Clone, Default)]
+pub struct VoiceStatistics {
+    /// Statistics about the streams we receive, by ssrc
+    pub incoming: HashMap<u32, SsrcStatistics>,
+    /// Statistics about the stream we send
+    pub outgoing: OutgoingStatistics,
+    /// The number of RTCP sender and receiver reports which could not be decrypted, and were
+    /// skipped
+    pub undecryptable_rtcp_packets: u64,
+}
+
+/// Statistics about an rtp stream we receive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SsrcStatistics {
+    /// The number of packets received
+    pub packets_received: u64,
+    /// The number of packets expected but not received, since we started receiving the stream
+    ///
+    /// Negative if duplicate packets were received.
+    pub packets_lost: i64,
+    /// The fraction of packets lost since the last report we sent, between 0 and 1
+    pub fraction_lost: f32,
+    /// The estimated interarrival jitter, in rtp timestamp units
+    pub jitter: f64,
+    /// When the last packet was received
+    pub last_received: Instant,
+    /// The sequence number of the first packet
+    base_sequence: u16,
+    /// The highest sequence number received
+    max_sequence: u16,
+    /// How often the sequence number wrapped around
+    cycles: u32,
+    /// The last packet's transit time, in rtp timestamp units
+    last_transit: Option<i64>,
+    /// The first time we received a packet of this stream, to calculate transit times with
+    first_received: Instant,
+    expected_at_last_report: u64,
+    received_at_last_report: u64,
+    /// The middle 32 bits of the ntp timestamp of the last sender report, and when it was received
+    last_sender_report: Option<(u32, Instant)>,
+}
+
+/// Statistics about the rtp stream we send.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OutgoingStatistics {
+    /// The number of packets sent
+    pub packets_sent: u32,
+    /// The number of payload bytes sent
+    pub bytes_sent: u32,
+    /// The rtp timestamp of the last packet sent
+    pub last_rtp_timestamp: u32,
+    /// The fraction of our packets the server lost, between 0 and 1, as last reported
+    pub fraction_lost: Option<f32>,
+    /// The total number of our packets the server lost, as last reported
+    pub packets_lost: Option<u32>,
+    /// The interarrival jitter of our packets, in rtp timestamp units, as last reported
+    pub jitter: Option<u32>,
+    /// The round trip time to the server
+    ///
+    /// Only known once the server has reported on a sender report we sent.
+    pub round_trip_time: Option<Duration>,
+}
+
+impl SsrcStatistics {
+    fn new(sequence: u16, now: Instant) -> Self {
+        Self {
+            packets_received: 0,
+            packets_lost: 0,
+            fraction_lost: 0.0,
+            jitter: 0.0,
+            last_received: now,
+            base_sequence: sequence,
+            max_sequence: sequence,
+            cycles: 0,
+            last_transit: None,
+            first_received: now,
+            expected_at_last_report: 0,
+            received_at_last_report: 0,
+            last_sender_report: None,
+        }
+    }
+
+    /// The highest sequence number received, extended with the number of wraparounds
+    pub fn extended_max_sequence(&self) -> u32 {
+        (self.cycles << 16) | self.max_sequence as u32
+    }
+
+    /// The number of packets we expected to receive so far
+    fn expected(&self) -> u64 {
+        (self.extended_max_sequence() as u64 + 1).saturating_sub(self.base_sequence as u64)
+    }
+
+    /// The estimated interarrival jitter
+    pub fn jitter_duration(&self) -> Duration {
+        Duration::from_secs_f64(self.jitter / OPUS_CLOCK_RATE as f64)
+    }
+
+    fn record_packet(&mut self, sequence: u16, rtp_timestamp: u32, now: Instant) {
+        // See RFC 3550 A.1; anything more than half the sequence space ahead is an old packet
+        let difference = sequence.wrapping_sub(self.max_sequence);
+        if difference != 0 && difference < u16::MAX / 2 {
+            if sequence < self.max_sequence {
+                self.cycles += 1;
+            }
+            self.max_sequence = sequence;
+        }
+
+        self.packets_received += 1;
+        self.packets_lost = self.expected() as i64 - self.packets_received as i64;
+        self.last_received = now;
+
+        // See RFC 3550 A.8
+        let arrival = (now - self.first_received).as_secs_f64() * OPUS_CLOCK_RATE as f64;
+        let transit = arrival as i64 - rtp_timestamp as i64;
+
+        if let Some(last_transit) = self.last_transit {
+            let difference = (transit - last_transit).abs() as f64;
+            self.jitter += (difference - self.jitter) / 16.0;
+        }
+
+        self.last_transit = Some(transit);
+    }
+
+    /// Creates a report block about this stream and starts a new report interval.
+    fn report_block(&mut self, ssrc: u32, now: Instant) -> ReportBlock {
+        // See RFC 3550 A.3
+        let expected = self.expected();
+        let expected_interval = expected - self.expected_at_last_report;
+        let received_interval = self.packets_received - self.received_at_last_report;
+        let lost_interval = expected_interval as i64 - received_interval as i64;
+
+        self.fraction_lost = if expected_interval == 0 || lost_interval <= 0 {
+            0.0
+        } else {
+            lost_interval as f32 / expected_interval as f32
+        };
+
+        self.expected_at_last_report = expected;
+        self.received_at_last_report = self.packets_received;
+
+        let (last_sr_timestamp, last_sr_delay) = match self.last_sender_report {
+            Some((timestamp, received_at)) => (
+                timestamp,
+                ((now - received_at).as_secs_f64() * 65536.0) as u32,
+            ),
+            None => (0, 0),
+        };
+
+        ReportBlock {
+            ssrc,
+            fraction_lost: (self.fraction_lost * 256.0).min(255.0) as u8,
+            // Clamped to 24 bits
+            cumulative_pkts_lost: self.packets_lost.clamp(0, 0x7F_FFFF) as u32,
+            cycles: self.cycles as u16,
+            sequence: self.max_sequence,
+            interarrival_jitter: self.jitter as u32,
+            last_sr_timestamp,
+            last_sr_delay,
+            payload: Vec::new(),
+        }
+    }
+}
+
+impl VoiceStatistics {
+    /// Records a received rtp packet.
+    pub fn record_rtp(&mut self, ssrc: u32, sequence: u16, rtp_timestamp: u32) {
+        let now = Instant::now();
+
+        self.incoming
+            .entry(ssrc)
+            .or_insert_with(|| SsrcStatistics::new(sequence, now))
+            .record_packet(sequence, rtp_timestamp, now);
+    }
+
+    /// Records an rtp packet we sent.
+    pub fn record_sent(&mut self, rtp_timestamp: u32, payload_size: usize) {
+        self.outgoing.packets_sent = self.outgoing.packets_sent.wrapping_add(1);
+        self.outgoing.bytes_sent = self.outgoing.bytes_sent.wrapping_add(payload_size as u32);
+        self.outgoing.last_rtp_timestamp = rtp_timestamp;
+    }
+
+    /// Records the sender info of a received sender report.
+    ///
+    /// `payload` is the decrypted report body.
+    pub fn record_sender_info(&mut self, ssrc: u32, payload: &[u8]) {
+        let Some(sender_info) = SenderInfoPacket::new(payload) else {
+            return;
+        };
+
+        if let Some(statistics) = self.incoming.get_mut(&ssrc) {
+            let middle = (sender_info.get_ntp_timestamp_second() << 16)
+                | (sender_info.get_ntp_timestamp_fraction() >> 16);
+            statistics.last_sender_report = Some((middle, Instant::now()));
+        }
+    }
+
+    /// Records the report blocks of a received sender or receiver report which concern our
+    /// stream.
+    ///
+    /// `blocks` are the decrypted report blocks, without the sender info of sender reports.
+    pub fn record_report_blocks(&mut self, own_ssrc: u32, blocks: &[u8], count: usize) {
+        let block_size = ReportBlockPacket::minimum_packet_size();
+
+        for block in blocks.chunks_exact(block_size).take(count) {
+            let Some(block) = ReportBlockPacket::new(block) else {
+                continue;
+            };
+
+            if block.get_ssrc() != own_ssrc {
+                continue;
+            }
+
+            self.outgoing.fraction_lost = Some(block.get_fraction_lost() as f32 / 256.0);
+            self.outgoing.packets_lost = Some(block.get_cumulative_pkts_lost());
+            self.outgoing.jitter = Some(block.get_interarrival_jitter());
+
+            // See RFC 3550 6.4.1; the round trip is only known if the server received a sender
+            // report from us
+            let last_sr_timestamp = block.get_last_sr_timestamp();
+            if last_sr_timestamp != 0 {
+                let round_trip = ntp_middle_now()
+                    .wrapping_sub(last_sr_timestamp)
+                    .wrapping_sub(block.get_last_sr_delay());
+                self.outgoing.round_trip_time =
+                    Some(Duration::from_secs_f64(round_trip as f64 / 65536.0));
+            }
+        }
+    }
+
+    /// Builds a sender report about our stream, with report blocks for up to 31 of the
+    /// streams we receive.
+    ///
+    /// This also starts a new report interval for the incoming streams, which resets
+    /// [SsrcStatistics::fraction_lost].
+    pub fn sender_report(&mut self, own_ssrc: u32) -> Vec<u8> {
+        let (ntp_timestamp_second, ntp_timestamp_fraction) = ntp_now();
+
+        let sender_info = SenderInfo {
+            ntp_timestamp_second,
+            ntp_timestamp_fraction,
+            rtp_timestamp: self.outgoing.last_rtp_timestamp,
+            pkt_count: self.outgoing.packets_sent,
+            byte_count: self.outgoing.bytes_sent,
+            payload: Vec::new(),
+        };
+
+        let mut payload = vec![0; SenderInfoPacket::minimum_packet_size()];
+        MutableSenderInfoPacket::new(&mut payload)
+            .unwrap()
+            .populate(&sender_info);
+
+        let (block_count, mut blocks) = self.report_blocks();
+        payload.append(&mut blocks);
+
+        let report = SenderReport {
+            version: 2,
+            padding: 0,
+            rx_report_count: block_count,
+            packet_type: RtcpType::SenderReport,
+            pkt_length: rtcp_length(payload.len()),
+            ssrc: own_ssrc,
+            payload,
+        };
+
+        let mut buffer = vec![0; MutableSenderReportPacket::packet_size(&report)];
+        MutableSenderReportPacket::new(&mut buffer)
+            .unwrap()
+            .populate(&report);
+        buffer
+    }
+
+    /// Builds a receiver report with report blocks for up to 31 of the streams we receive.
+    ///
+    /// This also starts a new report interval for the incoming streams, which resets
+    /// [SsrcStatistics::fraction_lost].
+    pub fn receiver_report(&mut self, own_ssrc: u32) -> Vec<u8> {
+        let (block_count, payload) = self.report_blocks();
+
+        let report = ReceiverReport {
+            version: 2,
+            padding: 0,
+            rx_report_count: block_count,
+            packet_type: RtcpType::ReceiverReport,
+            pkt_length: rtcp_length(payload.len()),
+            ssrc: own_ssrc,
+            payload,
+        };
+
+        let mut buffer = vec![0; MutableReceiverReportPacket::packet_size(&report)];
+        MutableReceiverReportPacket::new(&mut buffer)
+            .unwrap()
+            .populate(&report);
+        buffer
+    }
+
+    /// Builds report blocks for the most recently active incoming streams
+    fn report_blocks(&mut self) -> (u8, Vec<u8>) {
+        let now = Instant::now();
+
+        let mut streams: Vec<(&u32, &mut SsrcStatistics)> = self.incoming.iter_mut().collect();
+        streams.sort_by_key(|(_, statistics)| std::cmp::Reverse(statistics.last_received));
+        streams.truncate(MAX_REPORT_BLOCKS);
+
+        let block_size = ReportBlockPacket::minimum_packet_size();
+        let mut buffer = vec![0; streams.len() * block_size];
+
+        for ((ssrc, statistics), chunk) in
+            streams.iter_mut().zip(buffer.chunks_exact_mut(block_size))
+        {
+            let block = statistics.report_block(**ssrc, now);
+            MutableReportBlockPacket::new(chunk)
+                .unwrap()
+                .populate(&block);
+        }
+
+        (streams.len() as u8, buffer)
+    }
+}
+
+/// Calculates the RTCP length field; the length in 32 bit words, minus one
+fn rtcp_length(payload_size: usize) -> u16 {
+    ((RTCP_HEADER_SIZE + payload_size) / 4 - 1) as u16
+}
+
+/// The current time as an ntp timestamp (seconds and fraction)
+fn ntp_now() -> (u32, u32) {
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+
+    let seconds = (since_epoch.as_secs() + NTP_UNIX_OFFSET) as u32;
+    let fraction = ((since_epoch.subsec_nanos() as u64) << 32) / 1_000_000_000;
+
+    (seconds, fraction as u32)
+}
+
+/// The middle 32 bits of the current ntp timestamp
+fn ntp_middle_now() -> u32 {
+    let (seconds, fraction) = ntp_now();
+    (seconds << 16) | (fraction >> 16)
+}
+
+#[test]
+// Asserts that loss is tracked from sequence numbers and survives a report round trip
+fn test_receiver_report_round_trip() {
+    let mut receiver = VoiceStatistics::default();
+
+    for sequence in [10, 11, 13, 14] {
+        receiver.record_rtp(7, sequence, sequence as u32 * 960);
+    }
+
+    let incoming = receiver.incoming[&7];
+    assert_eq!(incoming.packets_received, 4);
+    assert_eq!(incoming.extended_max_sequence(), 14);
+
+    let report = receiver.receiver_report(1);
+    assert_eq!(
+        report.len(),
+        RTCP_HEADER_SIZE + ReportBlockPacket::minimum_packet_size()
+    );
+    assert_eq!(receiver.incoming[&7].packets_lost, 1);
+
+    let mut sender = VoiceStatistics::default();
+    sender.record_report_blocks(7, &report[RTCP_HEADER_SIZE..], 1);
+
+    assert_eq!(sender.outgoing.packets_lost, Some(1));
+    assert_eq!(sender.outgoing.fraction_lost, Some(51.0 / 256.0));
+    assert_eq!(sender.outgoing.round_trip_time, None);
+}
@@ -0,0 +1,435 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Splitting encoded video frames into rtp payloads and reassembling them.
+//!
+//! See <https://www.rfc-editor.org/rfc/rfc7741> (VP8), <https://www.rfc-editor.org/rfc/rfc9628>
+//! (VP9) and <https://www.rfc-editor.org/rfc/rfc6184> (H264)
+
+use std::collections::HashMap;
+
+use log::*;
+
+use crate::types::{VideoCodec, WebSocketEvent};
+
+/// The clock rate of video rtp timestamps
+pub const VIDEO_CLOCK_RATE: u32 = 90_000;
+
+/// The maximum size of a video rtp payload we send.
+///
+/// This leaves room for the rtp header and encryption overhead in a typical MTU.
+pub const VIDEO_MAX_PAYLOAD_SIZE: usize = 1100;
+
+/// The H264 Annex B start code, which precedes every NAL unit of a frame
+const H264_START_CODE: [u8; 4] = [0, 0, 0, 1];
+
+/// An encoded video frame, received from or sent to a voice connection.
+///
+/// VP8 and VP9 frames are raw encoded frames, H264 frames are NAL units in the Annex B format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VideoFrame {
+    pub ssrc: u32,
+    pub codec: VideoCodec,
+    /// The rtp timestamp of the frame, in [VIDEO_CLOCK_RATE] units
+    pub timestamp: u32,
+    pub data: Vec<u8>,
+}
+
+impl WebSocketEvent for VideoFrame {}
+
+/// Splits an encoded frame into rtp payloads, including the codec's payload descriptors.
+///
+/// The last payload should be sent with the marker bit set.
+pub(crate) fn packetize(codec: VideoCodec, frame: &[u8]) -> Vec<Vec<u8>> {
+    match codec {
+        VideoCodec::VP8 => packetize_with_descriptor(frame, |first, _| {
+            // Only the S (start of partition) bit, partition index 0
+            vec![if first { 0x10 } else { 0x00 }]
+        }),
+        VideoCodec::VP9 => packetize_with_descriptor(frame, |first, last| {
+            // The B (beginning of frame) and E (end of frame) bits
+            let mut descriptor = 0;
+            if first {
+                descriptor |= 0x08;
+            }
+            if last {
+                descriptor |= 0x04;
+            }
+            vec![descriptor]
+        }),
+        VideoCodec::H264 => packetize_h264(frame),
+    }
+}
+
+/// Splits a frame into chunks, prefixing every chunk with a descriptor built from whether it
+/// is the first and last chunk.
+fn packetize_with_descriptor(
+    frame: &[u8],
+    descriptor: impl Fn(bool, bool) -> Vec<u8>,
+) -> Vec<Vec<u8>> {
+    let chunk_size = VIDEO_MAX_PAYLOAD_SIZE - 1;
+    let chunk_count = ((frame.len() + chunk_size - 1) / chunk_size).max(1);
+
+    (0..chunk_count)
+        .map(|index| {
+            let start = index * chunk_size;
+            let end = (start + chunk_size).min(frame.len());
+
+            let mut payload = descriptor(index == 0, index == chunk_count - 1);
+            payload.extend_from_slice(&frame[start..end]);
+            payload
+        })
+        .collect()
+}
+
+/// Packetizes an Annex B H264 frame into single NAL unit and FU-A packets.
+fn packetize_h264(frame: &[u8]) -> Vec<Vec<u8>> {
+    let mut payloads = Vec::new();
+
+    for nal in h264_nal_units(frame) {
+        if nal.len() <= VIDEO_MAX_PAYLOAD_SIZE {
+            payloads.push(nal.to_vec());
+            continue;
+        }
+
+        // The FU indicator keeps the NRI bits of the NAL header, with type 28 (FU-A)
+        let indicator = (nal[0] & 0xE0) | 28;
+        let nal_type = nal[0] & 0x1F;
+
+        let chunks: Vec<&[u8]> = nal[1..].chunks(VIDEO_MAX_PAYLOAD_SIZE - 2).collect();
+        for (index, chunk) in chunks.iter().enumerate() {
+            let mut header = nal_type;
+            if index == 0 {
+                header |= 0x80;
+            }
+            if index == chunks.len() - 1 {
+                header |= 0x40;
+            }
+
+            let mut payload = vec![indicator, header];
+            payload.extend_from_slice(chunk);
+            payloads.push(payload);
+        }
+    }
+
+    payloads
+}
+
+/// Splits an Annex B stream into its NAL units, without start codes.
+fn h264_nal_units(frame: &[u8]) -> Vec<&[u8]> {
+    let mut units = Vec::new();
+    let mut start = None;
+    let mut index = 0;
+
+    while index + 3 <= frame.len() {
+        if frame[index..index + 3] == [0, 0, 1] {
+            if let Some(start) = start {
+                units.push(trim_trailing_zeros(&frame[start..index]));
+            }
+            index += 3;
+            start = Some(index);
+            continue;
+        }
+        index += 1;
+    }
+
+    match start {
+        Some(start) => units.push(&frame[start..]),
+        // Not in the Annex B format, assume it is a single NAL unit
+        None => units.push(frame),
+    }
+
+    units.retain(|unit| !unit.is_empty());
+    units
+}
+
+/// Removes the leading zero of a four byte start code from the end of the previous NAL unit
+fn trim_trailing_zeros(mut unit: &[u8]) -> &[u8] {
+    while let [rest @ .., 0] = unit {
+        unit = rest;
+    }
+    unit
+}
+
+/// Strips the codec's payload descriptor from a received rtp payload, appending the contained
+/// frame data to `frame`.
+///
+/// Returns `None` if the payload is malformed.
+pub(crate) fn depacketize(codec: VideoCodec, payload: &[u8], frame: &mut Vec<u8>) -> Option<()> {
+    match codec {
+        VideoCodec::VP8 => {
+            let size = vp8_descriptor_size(payload)?;
+            frame.extend_from_slice(payload.get(size..)?);
+        }
+        VideoCodec::VP9 => {
+            let size = vp9_descriptor_size(payload)?;
+            frame.extend_from_slice(payload.get(size..)?);
+        }
+        VideoCodec::H264 => depacketize_h264(payload, frame)?,
+    }
+
+    Some(())
+}
+
+/// Returns the size of a VP8 payload descriptor, see RFC 7741 4.2
+fn vp8_descriptor_size(payload: &[u8]) -> Option<usize> {
+    let first = *payload.first()?;
+    let mut size = 1;
+
+    // X: extended control bits present
+    if first & 0x80 != 0 {
+        let extension = *payload.get(1)?;
+        size += 1;
+
+        // I: picture id present
+        if extension & 0x80 != 0 {
+            size += picture_id_size(*payload.get(size)?);
+        }
+        // L: TL0PICIDX present
+        if extension & 0x40 != 0 {
+            size += 1;
+        }
+        // T or K: TID / KEYIDX present
+        if extension & 0x30 != 0 {
+            size += 1;
+        }
+    }
+
+    (size <= payload.len()).then_some(size)
+}
+
+/// Returns the size of a VP9 payload descriptor, see RFC 9628 4.2
+fn vp9_descriptor_size(payload: &[u8]) -> Option<usize> {
+    let first = *payload.first()?;
+    let mut size = 1;
+
+    let picture_id = first & 0x80 != 0;
+    let inter_picture = first & 0x40 != 0;
+    let layer_indices = first & 0x20 != 0;
+    let flexible = first & 0x10 != 0;
+    let scalability_structure = first & 0x02 != 0;
+
+    if picture_id {
+        size += picture_id_size(*payload.get(size)?);
+    }
+
+    if layer_indices {
+        size += 1;
+        // TL0PICIDX, only in non-flexible mode
+        if !flexible {
+            size += 1;
+        }
+    }
+
+    // Up to 3 reference indices, each with an N bit if another one follows
+    if flexible && inter_picture {
+        loop {
+            let reference = *payload.get(size)?;
+            size += 1;
+            if reference & 0x01 == 0 {
+                break;
+            }
+        }
+    }
+
+    if scalability_structure {
+        let structure = *payload.get(size)?;
+        size += 1;
+
+        let spatial_layers = ((structure >> 5) + 1) as usize;
+        // Y: resolutions present
+        if structure & 0x10 != 0 {
+            size += spatial_layers * 4;
+        }
+        // G: picture group description present
+        if structure & 0x08 != 0 {
+            let pictures = *payload.get(size)? as usize;
+            size += 1;
+
+            for _ in 0..pictures {
+                let picture = *payload.get(size)?;
+                size += 1 + ((picture >> 2) & 0x03) as usize;
+            }
+        }
+    }
+
+    (size <= payload.len()).then_some(size)
+}
+
+/// Returns the size of a VP8 or VP9 picture id, which is 15 instead of 7 bits long if the M bit
+/// of its first byte is set
+fn picture_id_size(first_byte: u8) -> usize {
+    if first_byte & 0x80 != 0 {
+        2
+    } else {
+        1
+    }
+}
+
+/// Converts an H264 rtp payload back into Annex B NAL units, see RFC 6184 5.
+fn depacketize_h264(payload: &[u8], frame: &mut Vec<u8>) -> Option<()> {
+    let first = *payload.first()?;
+
+    match first & 0x1F {
+        // Single NAL unit
+        1..=23 => {
+            frame.extend_from_slice(&H264_START_CODE);
+            frame.extend_from_slice(payload);
+        }
+        // STAP-A, several NAL units prefixed with their size
+        24 => {
+            let mut rest = &payload[1..];
+            while !rest.is_empty() {
+                let size = u16::from_be_bytes([*rest.first()?, *rest.get(1)?]) as usize;
+                let unit = rest.get(2..2 + size)?;
+                frame.extend_from_slice(&H264_START_CODE);
+                frame.extend_from_slice(unit);
+                rest = &rest[2 + size..];
+            }
+        }
+        // FU-A, a fragment of a NAL unit
+        28 => {
+            let header = *payload.get(1)?;
+            if header & 0x80 != 0 {
+                frame.extend_from_slice(&H264_START_CODE);
+                frame.push((first & 0xE0) | (header & 0x1F));
+            }
+            frame.extend_from_slice(&payload[2..]);
+        }
+        _ => return None,
+    }
+
+    Some(())
+}
+
+/// Reassembles received video rtp packets into frames.
+#[derive(Debug, Default)]
+pub(crate) struct VideoFrameAssembler {
+    frames: HashMap<u32, PartialFrame>,
+}
+
+/// The packets of a frame we have not fully received yet
+#[derive(Debug)]
+struct PartialFrame {
+    timestamp: u32,
+    /// The packets received so far, by sequence number
+    packets: Vec<(u16, Vec<u8>)>,
+}
+
+impl VideoFrameAssembler {
+    /// Adds a decrypted video rtp packet, returning the frame it completes, if any.
+    ///
+    /// Frames with missing packets are dropped.
+    pub(crate) fn push(
+        &mut self,
+        ssrc: u32,
+        codec: VideoCodec,
+        sequence: u16,
+        timestamp: u32,
+        marker: bool,
+        payload: Vec<u8>,
+    ) -> Option<VideoFrame> {
+        let partial = self.frames.entry(ssrc).or_insert_with(|| PartialFrame {
+            timestamp,
+            packets: Vec::new(),
+        });
+
+        if partial.timestamp != timestamp {
+            if !partial.packets.is_empty() {
+                debug!(
+                    "VUDP: Dropping incomplete video frame {} of ssrc {}",
+                    partial.timestamp, ssrc
+                );
+            }
+            partial.timestamp = timestamp;
+            partial.packets.clear();
+        }
+
+        partial.packets.push((sequence, payload));
+
+        // The marker bit is set on the last packet of a frame
+        if !marker {
+            return None;
+        }
+
+        let mut packets = std::mem::take(&mut partial.packets);
+        let first_sequence = packets
+            .iter()
+            .map(|(sequence, _)| *sequence)
+            .min_by_key(|packet_sequence| packet_sequence.wrapping_sub(sequence) as i16)?;
+        packets.sort_by_key(|(packet_sequence, _)| packet_sequence.wrapping_sub(first_sequence));
+
+        let expected_packets = sequence.wrapping_sub(first_sequence) as usize + 1;
+        if packets.len() != expected_packets {
+            debug!(
+                "VUDP: Dropping video frame {} of ssrc {}, {} of {} packets were received",
+                timestamp,
+                ssrc,
+                packets.len(),
+                expected_packets
+            );
+            return None;
+        }
+
+        let mut data = Vec::new();
+        for (_, payload) in packets {
+            if depacketize(codec, &payload, &mut data).is_none() {
+                debug!("VUDP: Dropping malformed video frame of ssrc {}", ssrc);
+                return None;
+            }
+        }
+
+        Some(VideoFrame {
+            ssrc,
+            codec,
+            timestamp,
+            data,
+        })
+    }
+}
+
+#[test]
+// Asserts that frames survive packetizing and reassembling with every codec
+fn test_video_packetization_round_trip() {
+    let large: Vec<u8> = (0..3000).map(|i| (i % 251) as u8 + 1).collect();
+
+    let mut h264 = Vec::new();
+    h264.extend_from_slice(&H264_START_CODE);
+    h264.extend_from_slice(&[0x67, 1, 2, 3]);
+    h264.extend_from_slice(&H264_START_CODE);
+    h264.push(0x65);
+    h264.extend_from_slice(&large);
+
+    for (codec, frame) in [
+        (VideoCodec::VP8, large.clone()),
+        (VideoCodec::VP9, large.clone()),
+        (VideoCodec::H264, h264),
+    ] {
+        let payloads = packetize(codec, &frame);
+        assert!(payloads
+            .iter()
+            .all(|payload| payload.len() <= VIDEO_MAX_PAYLOAD_SIZE));
+
+        let mut assembler = VideoFrameAssembler::default();
+        let count = payloads.len();
+
+        // Received out of order, starting just before the sequence number wraps
+        let mut packets: Vec<(u16, Vec<u8>)> = payloads
+            .into_iter()
+            .enumerate()
+            .map(|(index, payload)| ((u16::MAX - 1).wrapping_add(index as u16), payload))
+            .collect();
+        let last = packets.pop().unwrap();
+        packets.reverse();
+
+        for (sequence, payload) in packets {
+            assert_eq!(assembler.push(1, codec, sequence, 90, false, payload), None);
+        }
+
+        let received = assembler
+            .push(1, codec, last.0, 90, true, last.1)
+            .unwrap_or_else(|| panic!("{:?} frame of {} packets was not assembled", codec, count));
+        assert_eq!(received.data, frame);
+    }
+}
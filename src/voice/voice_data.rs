@@ -2,9 +2,16 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::net::{IpAddr, SocketAddr};
+
 use discortp::discord::IpDiscovery;
 
-use crate::types::{SessionDescription, Snowflake, VoiceReady, VoiceServerUpdate};
+use super::udp::statistics::VoiceStatistics;
+use crate::errors::VoiceUdpError;
+use crate::types::{
+    SessionDescription, Snowflake, VoiceIdentify, VoiceReady, VoiceServerUpdate,
+    DAVE_PROTOCOL_VERSION_NONE,
+};
 
 #[derive(Debug, Default)]
 /// Saves data shared between parts of the voice architecture;
@@ -18,8 +25,62 @@ pub struct VoiceData {
     pub session_id: String,
     /// The last sequence number we used, has to be incremented by one every time we send a message
     pub last_sequence_number: u16,
+    /// The last sequence number we used for video, incremented like [VoiceData::last_sequence_number]
+    pub last_video_sequence_number: u16,
     pub ip_discovery: Option<IpDiscovery>,
 
     /// The last UDP encryption nonce, if we are using an encryption mode with incremental nonces.
     pub last_udp_encryption_nonce: Option<u32>,
+
+    /// Statistics about the rtp streams we send and receive, see [VoiceStatistics]
+    pub statistics: VoiceStatistics,
+}
+
+impl VoiceData {
+    /// Returns the id of the guild or private channel we are connecting to, once we have
+    /// received a [VoiceServerUpdate].
+    pub fn server_id(&self) -> Option<Snowflake> {
+        self.server_data.as_ref()?.server_id()
+    }
+
+    /// Creates the [VoiceIdentify] payload for the voice gateway from the received
+    /// [VoiceServerUpdate], for both guild voice channels and DM / group DM calls.
+    ///
+    /// Returns `None` if we have not received a [VoiceServerUpdate] yet.
+    pub fn voice_identify(&self, video: Option<bool>) -> Option<VoiceIdentify> {
+        let server_data = self.server_data.as_ref()?;
+
+        Some(VoiceIdentify {
+            server_id: server_data.server_id()?,
+            user_id: self.user_id,
+            session_id: self.session_id.clone(),
+            token: server_data.token.clone(),
+            video,
+            // We don't implement DAVE yet
+            max_dave_protocol_version: Some(DAVE_PROTOCOL_VERSION_NONE),
+        })
+    }
+
+    /// Returns our external address and port, as seen by the voice server during IP discovery.
+    ///
+    /// Can be either an IPv4 or an IPv6 address.
+    ///
+    /// # Errors
+    /// Returns [VoiceUdpError::NoData] if IP discovery has not been performed yet, and
+    /// [VoiceUdpError::InvalidIpDiscovery] if the server sent an address we could not parse.
+    pub fn external_address(&self) -> Result<SocketAddr, VoiceUdpError> {
+        let ip_discovery = self.ip_discovery.as_ref().ok_or(VoiceUdpError::NoData)?;
+
+        // The address is null terminated
+        let address = String::from_utf8_lossy(&ip_discovery.address);
+        let address = address.trim_end_matches('\0');
+
+        let ip: IpAddr = address
+            .parse()
+            .map_err(|_| VoiceUdpError::InvalidIpDiscovery {
+                address: address.to_string(),
+            })?;
+
+        Ok(SocketAddr::new(ip, ip_discovery.port))
+    }
 }
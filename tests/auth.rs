@@ -4,6 +4,7 @@
 
 use std::str::FromStr;
 
+use chorus::errors::ChorusError;
 use chorus::types::{
     LoginSchema, MfaAuthenticationType, MfaVerifySchema, RegisterSchema, SendMfaSmsSchema,
 };
@@ -100,6 +101,33 @@ async fn test_login_with_token() {
     common::teardown(bundle).await;
 }
 
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn test_login_rest_only() {
+    let mut bundle = common::setup().await;
+
+    let token = bundle.user.token.clone();
+    bundle.instance.set_rest_only(true);
+    let mut other_user = bundle.instance.login_with_token(&token).await.unwrap();
+    bundle.instance.set_rest_only(false);
+
+    assert_eq!(other_user.gateway().err(), Some(ChorusError::NoGateway));
+    assert_eq!(
+        other_user.observe(bundle.guild.clone()).await.err(),
+        Some(ChorusError::NoGateway)
+    );
+
+    // REST requests still work
+    let current_user = other_user.get_current_user().await.unwrap();
+    assert_eq!(current_user.id, bundle.user.object.read().unwrap().id);
+
+    other_user.connect_gateway().await.unwrap();
+    assert!(other_user.gateway().is_ok());
+    other_user.gateway().unwrap().close().await;
+
+    common::teardown(bundle).await;
+}
+
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
 #[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
 async fn test_login_with_invalid_token() {
@@ -705,3 +733,151 @@ async fn test_send_mfa_backup_codes() {
 	 assert_ne!(result_view, result_regenerate);
 	 assert_eq!(result_regenerate, vec![MfaBackupCode {user_id: Snowflake(852892297661906993), code: "oqxk8zqs".to_string(), consumed: false}]);
 }
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "polyproto"))]
+#[derive(Debug)]
+struct TestSessionIdentity;
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "polyproto"))]
+impl chorus::polyproto::SessionIdentity for TestSessionIdentity {
+    fn id_cert(&self) -> String {
+        "test_id_cert".to_string()
+    }
+
+    fn sign_challenge(&self, challenge: &str) -> chorus::errors::ChorusResult<String> {
+        Ok(format!("signed_{}", challenge))
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+#[cfg(all(not(target_arch = "wasm32"), feature = "polyproto"))]
+async fn test_login_polyproto() {
+    use httptest::matchers::matches;
+
+    let server = common::create_mock_server();
+    let mut bundle = common::setup_with_mock_server(&server).await;
+
+    server.expect(
+        Expectation::matching(all_of![
+            request::method("GET"),
+            request::path(matches(r"/\.p2/core/v1/challenge$")),
+        ])
+        .respond_with(json_encoded(
+            json!({"challenge": "test_challenge", "expires": 4102444800u64}),
+        )),
+    );
+
+    server.expect(
+        Expectation::matching(all_of![
+            request::method("POST"),
+            request::path(matches(r"/\.p2/core/v1/session/identify$")),
+            request::body(json_decoded(eq(json!({
+                "challenge": "test_challenge",
+                "signature": "signed_test_challenge",
+                "id_cert": "test_id_cert"
+            })))),
+        ])
+        .respond_with(json_encoded(json!({"token": "faketoken"}))),
+    );
+
+    let user = bundle
+        .instance
+        .login_polyproto(std::sync::Arc::new(TestSessionIdentity), None)
+        .await
+        .unwrap();
+
+    assert_eq!(user.token, "faketoken");
+    assert_eq!(user.id_cert, Some("test_id_cert".to_string()));
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+#[cfg(not(target_arch = "wasm32"))]
+async fn test_file_token_store() {
+    use chorus::token_store::{FileTokenStore, StoredTokens, TokenStore};
+
+    let path = std::env::temp_dir().join(format!("chorus-tokens-{}.json", std::process::id()));
+    let store = FileTokenStore::new(&path);
+
+    assert_eq!(store.load().unwrap(), None);
+
+    let tokens = StoredTokens {
+        token: "testtoken".to_string(),
+        mfa_token: None,
+    };
+    store.store(&tokens).unwrap();
+    assert_eq!(store.load().unwrap(), Some(tokens));
+
+    store.clear().unwrap();
+    assert_eq!(store.load().unwrap(), None);
+    assert!(!path.exists());
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+#[cfg(not(target_arch = "wasm32"))]
+async fn test_token_store_persists_mfa_token() {
+    use chorus::token_store::{MemoryTokenStore, TokenStore};
+
+    let server = common::create_mock_server();
+    let mut bundle = common::setup_with_mock_server(&server).await;
+
+    server.expect(
+        Expectation::matching(all_of![
+            request::method("POST"),
+            request::path("/api/mfa/finish"),
+        ])
+        .respond_with(json_encoded(json!({"token": "testtoken"}))),
+    );
+
+    let store = std::sync::Arc::new(MemoryTokenStore::new());
+    bundle.user.set_token_store(Some(store.clone())).unwrap();
+    assert_eq!(store.load().unwrap().unwrap().token, "faketoken");
+
+    let schema = MfaVerifySchema {
+        ticket: "testticket".to_string(),
+        mfa_type: MfaAuthenticationType::TOTP,
+        data: "testdata".to_string(),
+    };
+    bundle.user.complete_mfa_challenge(schema).await.unwrap();
+
+    let tokens = store.load().unwrap().unwrap();
+    assert_eq!(tokens.token, "faketoken");
+    assert_eq!(tokens.mfa_token.unwrap().token, "testtoken");
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+#[cfg(not(target_arch = "wasm32"))]
+async fn test_token_refresh_on_unauthorized() {
+    use chorus::token_store::{LoginTokenRefresher, MemoryTokenStore, TokenStore};
+    use httptest::responders::status_code;
+
+    let server = common::create_mock_server();
+    let mut bundle = common::setup_with_mock_server(&server).await;
+
+    server.expect(
+        Expectation::matching(all_of![
+            request::method("GET"),
+            request::path("/api/users/@me"),
+            request::headers(contains(("authorization", "expiredtoken")))
+        ])
+        .respond_with(status_code(401).body(r#"{"code": 0, "message": "401: Unauthorized"}"#)),
+    );
+
+    let store = std::sync::Arc::new(MemoryTokenStore::new());
+    bundle.user.set_token_store(Some(store.clone())).unwrap();
+    bundle.user.set_token("expiredtoken");
+    bundle
+        .user
+        .set_token_refresher(Some(std::sync::Arc::new(LoginTokenRefresher::new(
+            LoginSchema {
+                login: "integrationtestuser".to_string(),
+                password: "mysecurepass".to_string(),
+                ..Default::default()
+            },
+        ))));
+
+    let user = bundle.user.get_current_user().await.unwrap();
+
+    assert_eq!(user.username, "integrationtestuser");
+    assert_eq!(bundle.user.token(), "faketoken");
+    assert_eq!(store.load().unwrap().unwrap().token, "faketoken");
+}
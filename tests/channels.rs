@@ -2,10 +2,11 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use chorus::errors::ChorusError;
 use chorus::types::{
-    self, Channel, GetChannelMessagesSchema, MessageSendSchema, PermissionFlags,
-    PermissionOverwrite, PermissionOverwriteType, PrivateChannelCreateSchema, RelationshipType,
-    Snowflake,
+    self, Channel, ChannelCreateSchema, GetChannelMessagesSchema, GroupDmModifySchema,
+    MessageSendSchema, PermissionFlags, PermissionOverwrite, PermissionOverwriteType,
+    PrivateChannelCreateSchema, RelationshipType, Snowflake,
 };
 
 mod common;
@@ -29,6 +30,20 @@ async fn get_channel() {
     common::teardown(bundle).await
 }
 
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn get_unknown_channel() {
+    let mut bundle = common::setup().await;
+
+    let result = Channel::get(&mut bundle.user, Snowflake::generate()).await;
+    let error = result.err().unwrap();
+    let api_error = error.api_error().unwrap();
+    assert_eq!(api_error.status, 404);
+    assert!(!api_error.message.is_empty());
+
+    common::teardown(bundle).await
+}
+
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
 #[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
 async fn delete_channel() {
@@ -39,6 +54,105 @@ async fn delete_channel() {
     common::teardown(bundle).await
 }
 
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn create_channel_at_position() {
+    let mut bundle = common::setup().await;
+    let guild = bundle.guild.read().unwrap().clone();
+
+    let channel = Channel::create(
+        &mut bundle.user,
+        guild.id,
+        None,
+        ChannelCreateSchema {
+            name: "first".to_string(),
+            channel_type: Some(types::ChannelType::GuildText),
+            position: Some(0),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(channel.position, Some(0));
+
+    let channels = guild.channels(&mut bundle.user).await.unwrap();
+    let created = channels.iter().find(|c| c.id == channel.id).unwrap();
+    assert_eq!(created.position, Some(0));
+
+    common::teardown(bundle).await
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+async fn create_channel_when_moving_it_fails() {
+    use httptest::{
+        matchers::{all_of, request},
+        responders::{json_encoded, status_code},
+        Expectation,
+    };
+    use serde_json::json;
+
+    let server = common::create_mock_server();
+    let mut instance = chorus::instance::Instance::new(server.url_str("/api").as_str(), None)
+        .await
+        .unwrap();
+    instance.set_rest_only(true);
+    let mut user = instance.login_with_token("faketoken").await.unwrap();
+
+    // The server ignores the requested position, and then refuses to move the channel
+    server.expect(
+        Expectation::matching(all_of![
+            request::method("POST"),
+            request::path("/api/guilds/20/channels"),
+        ])
+        .respond_with(json_encoded(
+            json!({"id": "30", "type": 0, "name": "first", "guild_id": "20", "position": 5}),
+        )),
+    );
+    server.expect(
+        Expectation::matching(all_of![
+            request::method("PATCH"),
+            request::path("/api/guilds/20/channels"),
+        ])
+        .respond_with(status_code(500)),
+    );
+
+    let channel = Channel::create(
+        &mut user,
+        Snowflake(20),
+        None,
+        ChannelCreateSchema {
+            name: "first".to_string(),
+            channel_type: Some(types::ChannelType::GuildText),
+            position: Some(0),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(channel.id, Snowflake(30));
+    assert_eq!(channel.position, Some(5));
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn start_typing() {
+    let mut bundle = common::setup().await;
+    let channel_id = bundle.channel.read().unwrap().id;
+
+    Channel::start_typing(channel_id, &mut bundle.user)
+        .await
+        .unwrap();
+
+    let user = std::sync::Arc::new(tokio::sync::Mutex::new(bundle.user.clone()));
+    let indicator = Channel::keep_typing(channel_id, user).await.unwrap();
+    assert_eq!(indicator.channel_id, channel_id);
+    indicator.stop();
+
+    common::teardown(bundle).await
+}
+
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
 #[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
 async fn modify_channel() {
@@ -70,14 +184,14 @@ async fn modify_channel() {
         .unwrap();
     assert_eq!(modified_channel.name, Some(CHANNEL_NAME.to_string()));
 
-    let permission_override = PermissionFlags::MANAGE_CHANNELS | PermissionFlags::MANAGE_MESSAGES;
     let user_id: types::Snowflake = bundle.user.object.read().unwrap().id;
-    let permission_override = PermissionOverwrite {
-        id: user_id,
-        overwrite_type: PermissionOverwriteType::Member,
-        allow: permission_override,
-        deny: PermissionFlags::empty(),
-    };
+    let permission_override = PermissionOverwrite::member(user_id)
+        .allow(PermissionFlags::MANAGE_CHANNELS | PermissionFlags::MANAGE_MESSAGES)
+        .build();
+    assert_eq!(
+        permission_override.overwrite_type,
+        PermissionOverwriteType::Member
+    );
     let channel_id: Snowflake = bundle.channel.read().unwrap().id;
     Channel::modify_permissions(
         &mut bundle.user,
@@ -88,10 +202,81 @@ async fn modify_channel() {
     .await
     .unwrap();
 
-    Channel::delete_permission(&mut bundle.user, channel_id, permission_override.id)
+    Channel::delete_permission_overwrite(
+        &mut bundle.user,
+        channel_id,
+        permission_override.id,
+        Some("Überschreibung entfernt".to_string()),
+    )
+    .await
+    .unwrap();
+
+    common::teardown(bundle).await
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn sync_permissions_with_parent() {
+    let mut bundle = common::setup().await;
+    let guild_id = bundle.guild.read().unwrap().id;
+
+    // The bundle channel is not in a category
+    let channel_id = bundle.channel.read().unwrap().id;
+    let result = Channel::sync_permissions_with_parent(&mut bundle.user, channel_id, None).await;
+    assert!(matches!(result, Err(ChorusError::InvalidArguments { .. })));
+
+    let category = Channel::create(
+        &mut bundle.user,
+        guild_id,
+        None,
+        ChannelCreateSchema {
+            name: "category".to_string(),
+            channel_type: Some(types::ChannelType::GuildCategory),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let channel = Channel::create(
+        &mut bundle.user,
+        guild_id,
+        None,
+        ChannelCreateSchema {
+            name: "synced".to_string(),
+            channel_type: Some(types::ChannelType::GuildText),
+            parent_id: Some(category.id),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    // Deny sending messages for @everyone in the category only
+    let overwrite = PermissionOverwrite::role(guild_id)
+        .deny(PermissionFlags::SEND_MESSAGES)
+        .build();
+    Channel::modify_permissions(&mut bundle.user, category.id, None, overwrite.clone())
         .await
         .unwrap();
 
+    let synced = Channel::sync_permissions_with_parent(
+        &mut bundle.user,
+        channel.id,
+        Some("Sync with category".to_string()),
+    )
+    .await
+    .unwrap();
+    #[cfg(not(feature = "sqlx"))]
+    let overwrites: Vec<PermissionOverwrite> = synced
+        .permission_overwrites
+        .unwrap()
+        .iter()
+        .map(|overwrite| overwrite.read().unwrap().clone())
+        .collect();
+    #[cfg(feature = "sqlx")]
+    let overwrites = synced.permission_overwrites.unwrap().0;
+    assert!(overwrites.contains(&overwrite));
+
     common::teardown(bundle).await
 }
 
@@ -197,6 +382,57 @@ async fn create_dm() {
     common::teardown(bundle).await;
 }
 
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn create_dm_with_recipient_id() {
+    let mut bundle = common::setup().await;
+    let other_user = bundle.create_user("integrationtestuser2").await;
+    let other_user_id = other_user.object.read().unwrap().id;
+
+    let dm_channel = bundle.user.create_dm(other_user_id).await.unwrap();
+    let recipient_ids: Vec<Snowflake> = dm_channel
+        .recipients
+        .as_ref()
+        .unwrap()
+        .iter()
+        .map(|recipient| recipient.read().unwrap().id)
+        .collect();
+    assert!(recipient_ids.contains(&other_user_id));
+
+    // Opening the DM again returns the same channel
+    let same_channel = bundle.user.create_dm(other_user_id).await.unwrap();
+    assert_eq!(same_channel.id, dm_channel.id);
+    common::teardown(bundle).await;
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn modify_group_dm() {
+    let mut bundle = common::setup().await;
+    let other_user = bundle.create_user("integrationtestuser2").await;
+    let third_user = bundle.create_user("integrationtestuser3").await;
+    let other_user_id = other_user.object.read().unwrap().id;
+    let third_user_id = third_user.object.read().unwrap().id;
+
+    let group_dm = bundle
+        .user
+        .create_group_dm(vec![other_user_id, third_user_id], Vec::new())
+        .await
+        .unwrap();
+
+    let schema = GroupDmModifySchema {
+        name: Some("group chat".to_string()),
+        ..Default::default()
+    };
+    let modified = bundle
+        .user
+        .modify_group_dm(group_dm.id, schema)
+        .await
+        .unwrap();
+    assert_eq!(modified.name.as_deref(), Some("group chat"));
+    common::teardown(bundle).await;
+}
+
 // #[tokio::test]
 // TODO This test currently is broken due to an issue with the Spacebar Server.
 #[allow(dead_code)]
@@ -267,3 +503,68 @@ async fn remove_add_person_from_to_dm() {
         user_id
     );
 }
+
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+#[cfg(not(target_arch = "wasm32"))]
+async fn manage_channel_followers() {
+    use httptest::{
+        matchers::{all_of, contains, eq, json_decoded, request},
+        responders::{json_encoded, status_code},
+        Expectation,
+    };
+    use serde_json::json;
+
+    let server = common::create_mock_server();
+    let mut bundle = common::setup_with_mock_server(&server).await;
+
+    server.expect(
+        Expectation::matching(all_of![
+            request::method("POST"),
+            request::path("/api/channels/50/followers"),
+            request::body(json_decoded(eq(json!({"webhook_channel_id": "30"})))),
+        ])
+        .respond_with(json_encoded(
+            json!({"channel_id": "50", "webhook_id": "10"}),
+        )),
+    );
+    let followed = Channel::follow(Snowflake(50), Snowflake(30), None, &mut bundle.user)
+        .await
+        .unwrap();
+    assert_eq!(followed.webhook_id, Snowflake(10));
+
+    let webhook = |id: &str, webhook_type: u8| {
+        json!({
+            "id": id,
+            "type": webhook_type,
+            "name": "Announcements",
+            "avatar": null,
+            "guild_id": "20",
+            "channel_id": "30",
+            "application_id": null,
+            "source_channel": {"id": "50", "name": "announcements"}
+        })
+    };
+    server.expect(
+        Expectation::matching(all_of![
+            request::method("GET"),
+            request::path("/api/channels/30/webhooks"),
+        ])
+        .respond_with(json_encoded(json!([webhook("10", 2), webhook("11", 1)]))),
+    );
+    let followed_channels = Channel::get_followed_channels(Snowflake(30), &mut bundle.user)
+        .await
+        .unwrap();
+    assert_eq!(followed_channels, vec![followed]);
+
+    server.expect(
+        Expectation::matching(all_of![
+            request::method("DELETE"),
+            request::path("/api/webhooks/10"),
+            request::headers(contains(("x-audit-log-reason", "unfollow"))),
+        ])
+        .respond_with(status_code(204)),
+    );
+    Channel::delete_follower(followed.webhook_id, Some("unfollow"), &mut bundle.user)
+        .await
+        .unwrap();
+}
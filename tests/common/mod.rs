@@ -4,7 +4,6 @@
 
 use std::str::FromStr;
 
-use chorus::gateway::{Gateway, GatewayOptions};
 use chorus::types::{DeleteDisableUserSchema, IntoShared, PermissionFlags, Snowflake};
 use chorus::{
     instance::{ChorusUser, Instance},
@@ -51,17 +50,19 @@ impl TestBundle {
             .unwrap()
     }
     pub(crate) async fn clone_user_without_gateway(&self) -> ChorusUser {
-        ChorusUser {
-            belongs_to: self.user.belongs_to.clone(),
-            token: self.user.token.clone(),
-            mfa_token: None,
-            limits: self.user.limits.clone(),
-            settings: self.user.settings.clone(),
-            object: self.user.object.clone(),
-            gateway: Gateway::spawn(&self.instance.urls.wss, GatewayOptions::default())
-                .await
-                .unwrap(),
+        let mut user = ChorusUser::new_rest_only(
+            self.user.belongs_to.clone(),
+            self.user.token.clone(),
+            self.user.limits.clone(),
+            self.user.settings.clone(),
+            self.user.object.clone(),
+        );
+        user.request_metadata = self.user.request_metadata.clone();
+        #[cfg(feature = "polyproto")]
+        {
+            user.id_cert = self.user.id_cert.clone();
         }
+        user
     }
 }
 
@@ -91,6 +92,7 @@ pub(crate) async fn setup() -> TestBundle {
         region: None,
         icon: None,
         channels: None,
+        roles: None,
         guild_template_code: None,
         system_channel_id: None,
         rules_channel_id: None,
@@ -130,8 +132,9 @@ pub(crate) async fn setup() -> TestBundle {
         mentionable: Some(true),
         position: None,
         color: None,
+        colors: None,
     };
-    let role = chorus::types::RoleObject::create(&mut user, guild.id, role_create_schema)
+    let role = chorus::types::RoleObject::create(&mut user, guild.id, role_create_schema, None)
         .await
         .unwrap();
 
@@ -140,7 +143,8 @@ pub(crate) async fn setup() -> TestBundle {
         "http://localhost:3001/api",
         "ws://localhost:3001/",
         "http://localhost:3001",
-    );
+    )
+    .unwrap();
     TestBundle {
         urls,
         user,
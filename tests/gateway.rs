@@ -8,11 +8,12 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
+use futures_util::StreamExt;
 use chorus::errors::GatewayError;
 use chorus::gateway::*;
 use chorus::types::{
     self, Channel, ChannelCreateSchema, ChannelModifySchema, GatewayReady, IntoShared,
-    RoleCreateModifySchema, RoleObject,
+    RequestGuildMembersQuery, RoleCreateModifySchema, RoleObject,
 };
 use pubserve::Subscriber;
 #[cfg(target_arch = "wasm32")]
@@ -49,6 +50,19 @@ impl Subscriber<GatewayReady> for GatewayReadyObserver {
     }
 }
 
+#[derive(Debug)]
+struct RawDispatchObserver {
+    channel: tokio::sync::mpsc::Sender<types::RawGatewayDispatch>,
+}
+
+#[async_trait]
+impl Subscriber<types::RawGatewayDispatch> for RawDispatchObserver {
+    async fn update(&self, data: &types::RawGatewayDispatch) {
+        // We only care about the first few events
+        let _ = self.channel.try_send(data.clone());
+    }
+}
+
 #[derive(Debug)]
 struct GatewayErrorObserver {
     channel: tokio::sync::mpsc::Sender<GatewayError>,
@@ -117,7 +131,7 @@ async fn test_gateway_errors() {
     //
     // Anyway, if you have a free weekend to spend debugging wasm, you're welcome to have a crack
     // at this
-    bundle.user.gateway.close().await;
+    bundle.user.gateway().unwrap().close().await;
 
     let gateway: GatewayHandle = Gateway::spawn(&bundle.urls.wss, GatewayOptions::default())
         .await
@@ -188,7 +202,8 @@ async fn test_self_updating_structs() {
 
     let received_channel = bundle
         .user
-        .gateway
+        .gateway()
+        .unwrap()
         .observe_and_into_inner(bundle.channel.clone())
         .await;
 
@@ -205,7 +220,8 @@ async fn test_self_updating_structs() {
     assert_eq!(
         bundle
             .user
-            .gateway
+            .gateway()
+            .unwrap()
             .observe_and_into_inner(bundle.channel.clone())
             .await
             .name
@@ -215,7 +231,8 @@ async fn test_self_updating_structs() {
 
     let guild = bundle
         .user
-        .gateway
+        .gateway()
+        .unwrap()
         .observe_and_into_inner(bundle.guild.clone())
         .await;
     assert!(guild.channels.is_empty());
@@ -235,7 +252,8 @@ async fn test_self_updating_structs() {
 
     let guild = bundle
         .user
-        .gateway
+        .gateway()
+        .unwrap()
         .observe_and_into_inner(guild.into_shared())
         .await;
     assert!(!guild.channels.is_empty());
@@ -244,6 +262,36 @@ async fn test_self_updating_structs() {
     common::teardown(bundle).await
 }
 
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn test_channel_pins_update() {
+    let mut bundle = common::setup().await;
+
+    let channel = bundle
+        .user
+        .gateway()
+        .unwrap()
+        .observe(bundle.channel.clone())
+        .await;
+    let channel_id = channel.read().unwrap().id;
+    assert!(channel.read().unwrap().last_pin_timestamp.is_none());
+
+    let message = types::MessageSendSchema {
+        content: Some("Pin me!".to_string()),
+        ..Default::default()
+    };
+    let message = bundle.user.send_message(message, channel_id).await.unwrap();
+    Channel::pin_message(channel_id, message.id, None, &mut bundle.user)
+        .await
+        .unwrap();
+
+    // Give the gateway some time to deliver the CHANNEL_PINS_UPDATE
+    sleep(Duration::from_millis(500)).await;
+    assert!(channel.read().unwrap().last_pin_timestamp.is_some());
+
+    common::teardown(bundle).await
+}
+
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
 #[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
 async fn test_recursive_self_updating_structs() {
@@ -251,7 +299,7 @@ async fn test_recursive_self_updating_structs() {
     let mut bundle = common::setup().await;
     let guild = bundle.guild.clone();
     // Observe Guild, make sure it has no channels
-    let guild = bundle.user.gateway.observe(guild.clone()).await;
+    let guild = bundle.user.gateway().unwrap().observe(guild.clone()).await;
     let inner_guild = guild.read().unwrap().clone();
     assert!(inner_guild.roles.is_empty());
     // Create Role
@@ -265,15 +313,22 @@ async fn test_recursive_self_updating_structs() {
         mentionable: Some(true),
         position: None,
         color: None,
+        colors: None,
     };
     let guild_id = inner_guild.id;
-    let role = RoleObject::create(&mut bundle.user, guild_id, role_create_schema.clone())
-        .await
-        .unwrap();
+    let role = RoleObject::create(
+        &mut bundle.user,
+        guild_id,
+        role_create_schema.clone(),
+        None,
+    )
+    .await
+    .unwrap();
     // Watch role;
     bundle
         .user
-        .gateway
+        .gateway()
+        .unwrap()
         .observe(role.clone().into_shared())
         .await;
     // Update Guild and check for Guild
@@ -281,20 +336,1131 @@ async fn test_recursive_self_updating_structs() {
     assert!(!inner_guild.roles.is_empty());
     // Update the Role
     role_create_schema.name = Some("yippieee".to_string());
-    RoleObject::modify(&mut bundle.user, guild_id, role.id, role_create_schema)
-        .await
-        .unwrap();
+    RoleObject::modify(
+        &mut bundle.user,
+        guild_id,
+        role.id,
+        role_create_schema,
+        Some("Renaming the role".to_string()),
+    )
+    .await
+    .unwrap();
     let role_inner = bundle
         .user
-        .gateway
+        .gateway()
+        .unwrap()
         .observe_and_into_inner(role.clone().into_shared())
         .await;
     assert_eq!(role_inner.name, "yippieee");
     // Check if the change propagated
-    let guild = bundle.user.gateway.observe(bundle.guild.clone()).await;
+    let guild = bundle
+        .user
+        .gateway()
+        .unwrap()
+        .observe(bundle.guild.clone())
+        .await;
     let inner_guild = guild.read().unwrap().clone();
     let guild_roles = inner_guild.roles;
     let guild_role_inner = guild_roles.first().unwrap().read().unwrap().clone();
     assert_eq!(guild_role_inner.name, "yippieee".to_string());
     common::teardown(bundle).await;
 }
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+/// Tests requesting guild members and aggregating the received chunks
+async fn test_request_members() {
+    let bundle = common::setup().await;
+
+    let guild_id = bundle.guild.read().unwrap().id;
+    let user_id = bundle.user.object.read().unwrap().id;
+
+    let query = RequestGuildMembersQuery {
+        query: Some(String::new()),
+        limit: 0,
+        ..Default::default()
+    };
+
+    let requested = bundle
+        .user
+        .gateway()
+        .unwrap()
+        .request_members(guild_id, query)
+        .await
+        .unwrap();
+
+    assert_eq!(requested.guild_id, guild_id);
+    assert!(requested
+        .members
+        .iter()
+        .any(|member| member.user.as_ref().unwrap().read().unwrap().id == user_id));
+
+    common::teardown(bundle).await
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+/// Tests that payloads over the size limit are refused instead of being sent
+async fn test_payload_size_limit() {
+    let bundle = common::setup().await;
+
+    let guild_id = bundle.guild.read().unwrap().id;
+
+    // Way more ids than allowed, so the payload is well over the limit
+    let query = RequestGuildMembersQuery {
+        user_ids: Some((0..1000).map(types::Snowflake).collect()),
+        ..Default::default()
+    };
+
+    let gateway = bundle.user.gateway().unwrap();
+    let result = gateway.request_members(guild_id, query).await;
+    assert!(matches!(
+        result,
+        Err(GatewayError::PayloadTooLarge {
+            limit: GATEWAY_PAYLOAD_SIZE_LIMIT,
+            ..
+        })
+    ));
+
+    // The connection is still usable afterwards
+    gateway
+        .set_presence(types::PresenceBuilder::new().build())
+        .await
+        .unwrap();
+
+    common::teardown(bundle).await
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+/// Tests subscribing to a channel's member list and receiving the initial sync
+async fn test_subscribe_member_list() {
+    let bundle = common::setup().await;
+
+    let guild_id = bundle.guild.read().unwrap().id;
+    let channel_id = bundle.channel.read().unwrap().id;
+
+    let member_list = bundle
+        .user
+        .gateway()
+        .unwrap()
+        .subscribe_member_list(guild_id, channel_id, vec![(0, 99)])
+        .await
+        .unwrap();
+
+    // Wait for the initial sync
+    for _ in 0..20 {
+        if member_list.read().unwrap().id.is_some() {
+            break;
+        }
+        sleep(Duration::from_millis(250)).await;
+    }
+
+    {
+        let member_list = member_list.read().unwrap();
+        assert_eq!(member_list.guild_id, guild_id);
+        assert!(member_list.members().count() > 0);
+    }
+
+    common::teardown(bundle).await
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct CustomEvent {
+    #[allow(dead_code)]
+    value: u64,
+}
+
+impl types::WebSocketEvent for CustomEvent {}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn test_custom_event_registration() {
+    let mut events = events::Events::default();
+
+    assert!(events.custom.register_dispatch::<CustomEvent>("CUSTOM_EVENT"));
+    assert!(!events.custom.register_dispatch::<CustomEvent>("CUSTOM_EVENT"));
+    assert!(events.custom.has_dispatch("CUSTOM_EVENT"));
+    assert!(events
+        .custom
+        .dispatch_mut::<CustomEvent>("CUSTOM_EVENT")
+        .is_some());
+    assert!(events
+        .custom
+        .dispatch_mut::<types::GatewayReady>("CUSTOM_EVENT")
+        .is_none());
+
+    // Opcodes chorus handles itself can't be registered
+    assert!(!events.custom.register_opcode::<CustomEvent>(0));
+    assert!(events.custom.register_opcode::<CustomEvent>(200));
+    assert!(events.custom.opcode_mut::<CustomEvent>(200).is_some());
+
+    assert!(events.custom.unregister_dispatch("CUSTOM_EVENT"));
+    assert!(!events.custom.has_dispatch("CUSTOM_EVENT"));
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn test_identify_limiter() {
+    let limiter = IdentifyLimiter::new();
+    assert_eq!(limiter.session_start_limit(), None);
+
+    limiter.set_session_start_limit(types::SessionStartLimit {
+        total: 1000,
+        remaining: 1,
+        reset_after: 200,
+        max_concurrency: 16,
+    });
+
+    // The first identify may be sent right away
+    #[cfg(not(target_arch = "wasm32"))]
+    let start = std::time::Instant::now();
+    #[cfg(target_arch = "wasm32")]
+    let start = wasmtimer::std::Instant::now();
+    limiter.wait().await;
+    assert!(start.elapsed() < Duration::from_millis(200));
+    assert_eq!(limiter.session_start_limit().unwrap().remaining, 0);
+
+    // The second one has to wait for the session start limit to reset
+    limiter.wait().await;
+    assert!(start.elapsed() >= Duration::from_millis(200));
+    assert_eq!(limiter.session_start_limit().unwrap().remaining, 1000);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+/// Tests that the DM channels and guilds sent in ready are cached
+async fn test_ready_dm_channels() {
+    let mut bundle = common::setup().await;
+    let other_user = bundle.create_user("integrationtestuser2").await;
+    let other_user_id = other_user.object.read().unwrap().id;
+
+    let dm_channel = bundle
+        .user
+        .create_private_channel(types::PrivateChannelCreateSchema {
+            recipients: Some(vec![other_user_id]),
+            access_tokens: None,
+            nicks: None,
+        })
+        .await
+        .unwrap();
+
+    let gateway: GatewayHandle = Gateway::spawn(&bundle.urls.wss, GatewayOptions::default())
+        .await
+        .unwrap();
+
+    let (ready_send, mut ready_receive) = tokio::sync::mpsc::channel(1);
+
+    let observer = Arc::new(GatewayReadyObserver {
+        channel: ready_send,
+    });
+
+    gateway
+        .events
+        .lock()
+        .await
+        .session
+        .ready
+        .subscribe(observer);
+
+    let mut identify = types::GatewayIdentifyPayload::common();
+    identify.token = bundle.user.token.clone();
+
+    gateway.send_identify(identify).await;
+
+    tokio::select! {
+        () = sleep(Duration::from_secs(20)) => {
+            panic!("Timed out waiting for ready");
+        }
+        Some(_) = ready_receive.recv() => {}
+    }
+
+    assert!(gateway
+        .dm_channels()
+        .iter()
+        .any(|channel| channel.read().unwrap().id == dm_channel.id));
+
+    let guild_id = bundle.guild.read().unwrap().id;
+    assert!(gateway.guild_ids().contains(&guild_id));
+
+    common::teardown(bundle).await
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+/// Tests that dispatched events are published raw, before they are parsed
+async fn test_raw_dispatch() {
+    let bundle = common::setup().await;
+
+    let gateway: GatewayHandle = Gateway::spawn(&bundle.urls.wss, GatewayOptions::default())
+        .await
+        .unwrap();
+
+    let (raw_send, mut raw_receive) = tokio::sync::mpsc::channel(8);
+
+    let observer = Arc::new(RawDispatchObserver { channel: raw_send });
+
+    gateway
+        .events
+        .lock()
+        .await
+        .raw_dispatch
+        .subscribe(observer);
+
+    let mut identify = types::GatewayIdentifyPayload::common();
+    identify.token = bundle.user.token.clone();
+
+    gateway.send_identify(identify).await;
+
+    let dispatch = tokio::select! {
+        () = sleep(Duration::from_secs(20)) => {
+            panic!("Timed out waiting for ready");
+        }
+        Some(dispatch) = raw_receive.recv() => dispatch
+    };
+
+    assert_eq!(dispatch.event_name, "READY");
+    let ready: GatewayReady = dispatch.deserialize().unwrap();
+    assert_eq!(ready.user.id, bundle.user.object.read().unwrap().id);
+
+    common::teardown(bundle).await
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn test_message_cache() {
+    let message = |id: u64, content: &str| types::Message {
+        id: types::Snowflake(id),
+        content: Some(content.to_string()),
+        ..Default::default()
+    };
+
+    let mut disabled = MessageCache::default();
+    assert!(!disabled.is_enabled());
+    assert!(disabled.insert(message(1, "one")).is_none());
+    assert!(disabled.is_empty());
+
+    let mut cache = MessageCache::new(2);
+    assert!(cache.insert(message(1, "one")).is_none());
+    assert!(cache.insert(message(2, "two")).is_none());
+
+    let previous = cache.insert(message(1, "edited")).unwrap();
+    assert_eq!(previous.content.as_deref(), Some("one"));
+    assert_eq!(cache.len(), 2);
+
+    // Evicts the oldest message
+    cache.insert(message(3, "three"));
+    assert_eq!(cache.len(), 2);
+    assert!(cache.get(types::Snowflake(1)).is_none());
+
+    let removed = cache.remove(types::Snowflake(2)).unwrap();
+    assert_eq!(removed.content.as_deref(), Some("two"));
+    assert!(cache.remove(types::Snowflake(2)).is_none());
+    assert_eq!(cache.len(), 1);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Default)]
+struct TypingCountObserver {
+    count: std::sync::atomic::AtomicUsize,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[async_trait]
+impl Subscriber<types::TypingStartEvent> for TypingCountObserver {
+    async fn update(&self, _data: &types::TypingStartEvent) {
+        self.count
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests that filtered observers are only notified about the events of their guild or channel
+async fn test_subscribe_filtered() {
+    use futures_util::SinkExt;
+    use tokio_tungstenite::tungstenite::Message;
+
+    let (subscribed_send, subscribed_receive) = tokio::sync::oneshot::channel::<()>();
+
+    let url = spawn_fake_gateway(|mut websocket| async move {
+        subscribed_receive.await.unwrap();
+
+        for (sequence, (guild_id, channel_id)) in [(1, 10), (1, 11), (2, 20)].iter().enumerate() {
+            let typing = format!(
+                r#"{{"op":0,"s":{},"t":"TYPING_START","d":{{"channel_id":"{channel_id}","guild_id":"{guild_id}","user_id":"5","timestamp":0}}}}"#,
+                sequence + 1
+            );
+            websocket.send(Message::Text(typing)).await.unwrap();
+        }
+
+        read_until_closed(websocket).await;
+    })
+    .await;
+
+    let options = GatewayOptions {
+        transport_compression: GatewayTransportCompression::None,
+        ..Default::default()
+    };
+    let gateway = Gateway::spawn(&url, options).await.unwrap();
+
+    let guild_observer = Arc::new(TypingCountObserver::default());
+    let channel_observer = Arc::new(TypingCountObserver::default());
+    let other_observer = Arc::new(TypingCountObserver::default());
+    let all_observer = Arc::new(TypingCountObserver::default());
+
+    {
+        let mut events = gateway.events.lock().await;
+        events
+            .filtered
+            .subscribe::<types::TypingStartEvent>(types::Snowflake(1), guild_observer.clone());
+        events.filtered.subscribe::<types::TypingStartEvent>(
+            EventFilter::Channel(types::Snowflake(10)),
+            channel_observer.clone(),
+        );
+        events
+            .filtered
+            .subscribe::<types::TypingStartEvent>(types::Snowflake(3), other_observer.clone());
+        events.user.typing_start.subscribe(all_observer.clone());
+    }
+    subscribed_send.send(()).unwrap();
+
+    let count =
+        |observer: &TypingCountObserver| observer.count.load(std::sync::atomic::Ordering::Relaxed);
+    for _ in 0..20 {
+        if count(&all_observer) == 3 {
+            break;
+        }
+        sleep(Duration::from_millis(50)).await;
+    }
+
+    assert_eq!(count(&all_observer), 3);
+    assert_eq!(count(&guild_observer), 2);
+    assert_eq!(count(&channel_observer), 1);
+    assert_eq!(count(&other_observer), 0);
+
+    gateway.shutdown().await;
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn test_voice_state_cache() {
+    let guild_id = types::Snowflake(1);
+    let state = |user_id: u64, channel_id: Option<u64>| types::VoiceState {
+        user_id: types::Snowflake(user_id),
+        channel_id: channel_id.map(types::Snowflake),
+        ..Default::default()
+    };
+
+    let mut cache = VoiceStateCache::default();
+    cache.insert(guild_id, state(10, Some(100)));
+    cache.insert(guild_id, state(11, Some(100)));
+
+    // Voice states from guilds don't include the guild's id
+    let cached = cache.get(guild_id, types::Snowflake(10)).unwrap();
+    assert_eq!(cached.guild_id, Some(guild_id));
+    assert_eq!(cache.guild(guild_id).count(), 2);
+    assert_eq!(cache.guild(types::Snowflake(2)).count(), 0);
+
+    // Moving to another channel replaces the voice state
+    cache.insert(guild_id, state(10, Some(101)));
+    assert_eq!(
+        cache
+            .get(guild_id, types::Snowflake(10))
+            .unwrap()
+            .channel_id,
+        Some(types::Snowflake(101))
+    );
+
+    // Leaving the voice channel removes it
+    cache.insert(guild_id, state(11, None));
+    assert!(cache.get(guild_id, types::Snowflake(11)).is_none());
+    assert_eq!(cache.guild(guild_id).count(), 1);
+
+    assert!(cache.remove(guild_id, types::Snowflake(10)).is_some());
+    assert_eq!(cache.guild(guild_id).count(), 0);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+/// Tests receiving events through the event stream instead of observers
+async fn test_event_stream() {
+    let bundle = common::setup().await;
+
+    let gateway: GatewayHandle = Gateway::spawn(&bundle.urls.wss, GatewayOptions::default())
+        .await
+        .unwrap();
+
+    let mut stream = Box::pin(gateway.stream());
+
+    let mut identify = types::GatewayIdentifyPayload::common();
+    identify.token = bundle.user.token.clone();
+
+    gateway.send_identify(identify).await;
+
+    let event = tokio::select! {
+        () = sleep(Duration::from_secs(20)) => {
+            panic!("Timed out waiting for ready");
+        }
+        Some(event) = stream.next() => event
+    };
+
+    let Event::GatewayReady(ready) = event else {
+        panic!("Expected ready as the first event, got {:?}", event);
+    };
+    assert_eq!(ready.user.id, bundle.user.object.read().unwrap().id);
+
+    common::teardown(bundle).await
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+/// Tests receiving the events of all users on an instance through one stream
+async fn test_instance_events() {
+    let mut bundle = common::setup().await;
+
+    let mut stream = Box::pin(bundle.instance.events());
+
+    let user = bundle.create_user("instanceevents").await;
+    let user_id = user.object.read().unwrap().id;
+
+    let ready = loop {
+        let instance_event = tokio::select! {
+            () = sleep(Duration::from_secs(20)) => {
+                panic!("Timed out waiting for ready");
+            }
+            Some(instance_event) = stream.next() => instance_event
+        };
+
+        if let Event::GatewayReady(ready) = instance_event.event {
+            if ready.user.id == user_id {
+                assert_eq!(instance_event.user_id, user_id);
+                break ready;
+            }
+        }
+    };
+    assert_eq!(ready.user.id, user_id);
+
+    common::teardown(bundle).await
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+/// Tests receiving events when dispatches are queued and published from a separate task
+async fn test_queued_dispatch() {
+    let bundle = common::setup().await;
+
+    let options = GatewayOptions {
+        dispatch_strategy: GatewayDispatchStrategy::Queued { capacity: 16 },
+        ..Default::default()
+    };
+
+    let gateway: GatewayHandle = Gateway::spawn(&bundle.urls.wss, options).await.unwrap();
+
+    let (ready_send, mut ready_receive) = tokio::sync::mpsc::channel(1);
+
+    let observer = Arc::new(GatewayReadyObserver {
+        channel: ready_send,
+    });
+
+    gateway
+        .events
+        .lock()
+        .await
+        .session
+        .ready
+        .subscribe(observer);
+
+    let mut identify = types::GatewayIdentifyPayload::common();
+    identify.token = bundle.user.token.clone();
+
+    gateway.send_identify(identify).await;
+
+    tokio::select! {
+        () = sleep(Duration::from_secs(20)) => {
+            panic!("Timed out waiting for ready");
+        }
+        Some(_) = ready_receive.recv() => {}
+    }
+
+    assert_eq!(gateway.dispatch_metrics().full, 0);
+
+    common::teardown(bundle).await
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+/// Tests that received events are counted in the gateway's stats
+async fn test_gateway_stats() {
+    let bundle = common::setup().await;
+
+    let gateway: GatewayHandle = Gateway::spawn(&bundle.urls.wss, GatewayOptions::default())
+        .await
+        .unwrap();
+
+    assert_eq!(gateway.stats().events_received, 0);
+
+    let (ready_send, mut ready_receive) = tokio::sync::mpsc::channel(1);
+
+    let observer = Arc::new(GatewayReadyObserver {
+        channel: ready_send,
+    });
+
+    gateway
+        .events
+        .lock()
+        .await
+        .session
+        .ready
+        .subscribe(observer);
+
+    let mut identify = types::GatewayIdentifyPayload::common();
+    identify.token = bundle.user.token.clone();
+
+    gateway.send_identify(identify).await;
+
+    tokio::select! {
+        () = sleep(Duration::from_secs(20)) => {
+            panic!("Timed out waiting for ready");
+        }
+        Some(_) = ready_receive.recv() => {}
+    }
+
+    let stats = gateway.stats();
+    assert!(stats.events_received >= 1);
+    assert_eq!(stats.reconnects, 0);
+
+    common::teardown(bundle).await
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+/// Tests that shutting down the gateway stops all of its tasks
+async fn test_gateway_shutdown() {
+    let bundle = common::setup().await;
+
+    let options = GatewayOptions {
+        dispatch_strategy: GatewayDispatchStrategy::Queued { capacity: 16 },
+        ..Default::default()
+    };
+
+    let gateway: GatewayHandle = Gateway::spawn(&bundle.urls.wss, options).await.unwrap();
+
+    tokio::select! {
+        () = sleep(Duration::from_secs(20)) => {
+            panic!("Timed out waiting for the gateway to shut down");
+        }
+        () = gateway.shutdown() => {}
+    }
+
+    // Once the tasks are stopped, these should return right away
+    tokio::select! {
+        () = sleep(Duration::from_secs(1)) => {
+            panic!("Gateway tasks did not stay stopped");
+        }
+        () = async {
+            gateway.closed().await;
+            gateway.shutdown().await;
+        } => {}
+    }
+
+    common::teardown(bundle).await
+}
+
+/// A connection to a fake gateway started with [spawn_fake_gateway], from the server's side
+#[cfg(not(target_arch = "wasm32"))]
+type FakeGatewayConnection = tokio_tungstenite::WebSocketStream<tokio::net::TcpStream>;
+
+/// Starts a fake gateway, which accepts one connection, sends a hello and then runs `script`
+/// on it.
+///
+/// Returns the url to connect to.
+#[cfg(not(target_arch = "wasm32"))]
+async fn spawn_fake_gateway<F, Fut>(script: F) -> String
+where
+    F: FnOnce(FakeGatewayConnection) -> Fut + Send + 'static,
+    Fut: std::future::Future<Output = ()> + Send + 'static,
+{
+    use futures_util::SinkExt;
+    use tokio_tungstenite::tungstenite::Message;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut websocket = tokio_tungstenite::accept_async(stream).await.unwrap();
+
+        let hello = r#"{"op":10,"d":{"heartbeat_interval":45000}}"#;
+        websocket
+            .send(Message::Text(hello.to_string()))
+            .await
+            .unwrap();
+
+        script(websocket).await;
+    });
+
+    url
+}
+
+/// Fake gateway script which keeps reading until the connection is closed, which answers
+/// websocket pings
+#[cfg(not(target_arch = "wasm32"))]
+async fn read_until_closed(mut websocket: FakeGatewayConnection) {
+    while let Some(Ok(_)) = websocket.next().await {}
+}
+
+/// Fake gateway script which stops responding entirely
+#[cfg(not(target_arch = "wasm32"))]
+async fn stop_responding(_websocket: FakeGatewayConnection) {
+    sleep(Duration::from_secs(60)).await;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn keepalive_options() -> GatewayOptions {
+    GatewayOptions {
+        transport_compression: GatewayTransportCompression::None,
+        keepalive: Some(GatewayKeepalive {
+            interval: Duration::from_millis(100),
+            timeout: Duration::from_millis(200),
+        }),
+        ..Default::default()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests that a connection which stopped responding is detected by the keepalive
+async fn test_keepalive_connection_lost() {
+    let url = spawn_fake_gateway(stop_responding).await;
+    let gateway = Gateway::spawn(&url, keepalive_options()).await.unwrap();
+
+    let (error_send, mut error_receive) = tokio::sync::mpsc::channel(1);
+    let observer = Arc::new(GatewayErrorObserver {
+        channel: error_send,
+    });
+    gateway.events.lock().await.error.subscribe(observer);
+
+    tokio::select! {
+        () = sleep(Duration::from_secs(5)) => {
+            panic!("Timed out waiting for the connection to be lost");
+        }
+        Some(error) = error_receive.recv() => {
+            assert_eq!(error, GatewayError::ConnectionLost);
+        }
+    }
+
+    tokio::select! {
+        () = sleep(Duration::from_secs(5)) => {
+            panic!("Gateway tasks did not stop");
+        }
+        () = gateway.closed() => {}
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests that an idle connection which responds to pings is kept open
+async fn test_keepalive_idle_connection() {
+    let url = spawn_fake_gateway(read_until_closed).await;
+    let gateway = Gateway::spawn(&url, keepalive_options()).await.unwrap();
+
+    let (error_send, mut error_receive) = tokio::sync::mpsc::channel(1);
+    let observer = Arc::new(GatewayErrorObserver {
+        channel: error_send,
+    });
+    gateway.events.lock().await.error.subscribe(observer);
+
+    tokio::select! {
+        () = sleep(Duration::from_secs(1)) => {}
+        Some(error) = error_receive.recv() => {
+            panic!("Idle connection was closed: {:?}", error);
+        }
+    }
+
+    gateway.shutdown().await;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests that compressed payloads which cannot be decompressed are skipped and reported,
+/// instead of stopping the gateway
+async fn test_invalid_compressed_payload() {
+    use futures_util::SinkExt;
+    use tokio_tungstenite::tungstenite::Message;
+
+    let url = spawn_fake_gateway(|mut websocket| async move {
+        // Wait until the client is listening for errors
+        websocket.next().await;
+
+        // A valid zlib header, followed by garbage
+        for _ in 0..2 {
+            websocket
+                .send(Message::Binary(vec![0x78, 0x9c, 0xff, 0xff, 0x00]))
+                .await
+                .unwrap();
+        }
+
+        read_until_closed(websocket).await;
+    })
+    .await;
+
+    let options = GatewayOptions {
+        transport_compression: GatewayTransportCompression::None,
+        ..Default::default()
+    };
+    let gateway = Gateway::spawn(&url, options).await.unwrap();
+
+    let (error_send, mut error_receive) = tokio::sync::mpsc::channel(2);
+    let observer = Arc::new(GatewayErrorObserver {
+        channel: error_send,
+    });
+    gateway.events.lock().await.error.subscribe(observer);
+    gateway
+        .send_json(types::Opcode::Heartbeat as u8, serde_json::Value::Null)
+        .await
+        .unwrap();
+
+    // The second payload is only reported if the listener survived the first one
+    for _ in 0..2 {
+        tokio::select! {
+            () = sleep(Duration::from_secs(5)) => {
+                panic!("Timed out waiting for the invalid payload to be reported");
+            }
+            Some(error) = error_receive.recv() => {
+                assert!(matches!(error, GatewayError::InvalidPayload { .. }));
+            }
+        }
+    }
+
+    gateway.shutdown().await;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests that a hello which cannot be parsed is reported as an error instead of panicking
+async fn test_invalid_hello() {
+    use futures_util::SinkExt;
+    use tokio_tungstenite::tungstenite::Message;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut websocket = tokio_tungstenite::accept_async(stream).await.unwrap();
+
+        websocket
+            .send(Message::Text(r#"{"op":10}"#.to_string()))
+            .await
+            .unwrap();
+
+        read_until_closed(websocket).await;
+    });
+
+    let options = GatewayOptions {
+        transport_compression: GatewayTransportCompression::None,
+        ..Default::default()
+    };
+    let result = Gateway::spawn(&url, options).await;
+
+    assert!(matches!(result, Err(GatewayError::Decode)));
+}
+
+/// Records which host the gateway asked to connect to, and connects to the fake gateway at
+/// `target` instead, like a proxy would
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug)]
+struct RedirectingConnector {
+    target: String,
+    requested: std::sync::Mutex<Vec<(String, u16)>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl RedirectingConnector {
+    fn new(fake_gateway_url: &str) -> Self {
+        Self {
+            target: fake_gateway_url.trim_start_matches("ws://").to_string(),
+            requested: std::sync::Mutex::new(Vec::new()),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[async_trait]
+impl TcpConnector for RedirectingConnector {
+    async fn connect(&self, host: &str, port: u16) -> std::io::Result<tokio::net::TcpStream> {
+        self.requested
+            .lock()
+            .unwrap()
+            .push((host.to_string(), port));
+        tokio::net::TcpStream::connect(&self.target).await
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests that the connections of [Gateway::spawn_with_connector] are opened by the custom tcp
+/// connector
+async fn test_custom_tcp_connector() {
+    let url = spawn_fake_gateway(read_until_closed).await;
+    let tcp = Arc::new(RedirectingConnector::new(&url));
+    let connector = WebSocketConnector {
+        tcp: Some(tcp.clone()),
+        tls: None,
+    };
+
+    // Not resolvable, so this only connects if the connector is used
+    let gateway = Gateway::spawn_with_connector(
+        "ws://gateway.invalid:1234",
+        GatewayOptions {
+            transport_compression: GatewayTransportCompression::None,
+            ..Default::default()
+        },
+        connector,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        *tcp.requested.lock().unwrap(),
+        vec![("gateway.invalid".to_string(), 1234)]
+    );
+
+    gateway.shutdown().await;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests that users connect to the gateway with the connector set on their instance
+async fn test_instance_gateway_connector() {
+    let server = common::create_mock_server();
+    let mut instance = chorus::instance::Instance::new(server.url_str("/api").as_str(), None)
+        .await
+        .unwrap();
+    instance.set_rest_only(true);
+    instance.set_gateway_options(GatewayOptions {
+        transport_compression: GatewayTransportCompression::None,
+        ..Default::default()
+    });
+
+    let url = spawn_fake_gateway(read_until_closed).await;
+    let tcp = Arc::new(RedirectingConnector::new(&url));
+    instance.set_gateway_connector(WebSocketConnector {
+        tcp: Some(tcp.clone()),
+        tls: None,
+    });
+
+    let mut user = instance.login_with_token("faketoken").await.unwrap();
+    user.connect_gateway().await.unwrap();
+
+    // The mock server's gateway url
+    assert_eq!(
+        *tcp.requested.lock().unwrap(),
+        vec![("localhost".to_string(), 3001)]
+    );
+
+    user.gateway().unwrap().shutdown().await;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests that the member list synchronizer unsubscribes once the list was dropped
+async fn test_dropped_member_list_unsubscribes() {
+    use futures_util::SinkExt;
+    use tokio_tungstenite::tungstenite::Message;
+
+    let url = spawn_fake_gateway(|mut websocket| async move {
+        let update = r#"{"op":0,"s":1,"t":"GUILD_MEMBER_LIST_UPDATE","d":{"guild_id":"1","id":"everyone","member_count":0,"online_count":0,"groups":[],"ops":[]}}"#;
+
+        // Once after the lazy request, and once after the list was dropped
+        for _ in 0..2 {
+            websocket.next().await;
+            websocket
+                .send(Message::Text(update.to_string()))
+                .await
+                .unwrap();
+        }
+
+        read_until_closed(websocket).await;
+    })
+    .await;
+
+    let options = GatewayOptions {
+        transport_compression: GatewayTransportCompression::None,
+        ..Default::default()
+    };
+    let gateway = Gateway::spawn(&url, options).await.unwrap();
+
+    let member_list = gateway
+        .subscribe_member_list(types::Snowflake(1), types::Snowflake(2), vec![(0, 99)])
+        .await
+        .unwrap();
+
+    for _ in 0..20 {
+        if member_list.read().unwrap().id.is_some() {
+            break;
+        }
+        sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(member_list.read().unwrap().id.as_deref(), Some("everyone"));
+
+    drop(member_list);
+    gateway
+        .send_json(types::Opcode::Heartbeat as u8, serde_json::Value::Null)
+        .await
+        .unwrap();
+
+    for _ in 0..20 {
+        sleep(Duration::from_millis(50)).await;
+        if !gateway
+            .events
+            .lock()
+            .await
+            .guild
+            .member_list_update
+            .has_subscribers()
+        {
+            gateway.shutdown().await;
+            return;
+        }
+    }
+
+    panic!("The member list synchronizer is still subscribed");
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests that the settings synchronizer unsubscribes once the settings were dropped
+async fn test_dropped_settings_unsubscribe() {
+    use futures_util::SinkExt;
+    use tokio_tungstenite::tungstenite::Message;
+
+    let url = spawn_fake_gateway(|mut websocket| async move {
+        let update = r#"{"op":0,"s":1,"t":"USER_SETTINGS_UPDATE","d":{"locale":"de"}}"#;
+
+        // Once while the settings are referenced, and once after they were dropped
+        for _ in 0..2 {
+            websocket.next().await;
+            websocket
+                .send(Message::Text(update.to_string()))
+                .await
+                .unwrap();
+        }
+
+        read_until_closed(websocket).await;
+    })
+    .await;
+
+    let options = GatewayOptions {
+        transport_compression: GatewayTransportCompression::None,
+        ..Default::default()
+    };
+    let gateway = Gateway::spawn(&url, options).await.unwrap();
+
+    let settings = types::UserSettings::default().into_shared();
+    gateway.sync_settings(settings.clone()).await;
+    gateway
+        .send_json(types::Opcode::Heartbeat as u8, serde_json::Value::Null)
+        .await
+        .unwrap();
+
+    for _ in 0..20 {
+        if settings.read().unwrap().locale == "de" {
+            break;
+        }
+        sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(settings.read().unwrap().locale, "de");
+
+    drop(settings);
+    gateway
+        .send_json(types::Opcode::Heartbeat as u8, serde_json::Value::Null)
+        .await
+        .unwrap();
+
+    for _ in 0..20 {
+        sleep(Duration::from_millis(50)).await;
+        if !gateway
+            .events
+            .lock()
+            .await
+            .user
+            .settings_update
+            .has_subscribers()
+        {
+            gateway.shutdown().await;
+            return;
+        }
+    }
+
+    panic!("The settings synchronizer is still subscribed");
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug)]
+struct SlowUnknownEventObserver {
+    channel: tokio::sync::mpsc::UnboundedSender<String>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[async_trait]
+impl Subscriber<types::UnknownEvent> for SlowUnknownEventObserver {
+    async fn update(&self, data: &types::UnknownEvent) {
+        sleep(Duration::from_millis(50)).await;
+        self.channel.send(data.event_name.clone()).unwrap();
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests that no dispatches are dropped when a slow observer fills up the dispatch queue
+async fn test_full_dispatch_queue() {
+    use futures_util::SinkExt;
+    use tokio_tungstenite::tungstenite::Message;
+
+    const EVENTS: usize = 5;
+
+    let url = spawn_fake_gateway(|mut websocket| async move {
+        for i in 0..EVENTS {
+            let dispatch = format!(r#"{{"op":0,"s":{},"t":"FAKE_EVENT_{i}","d":{{}}}}"#, i + 1);
+            websocket.send(Message::Text(dispatch)).await.unwrap();
+        }
+
+        read_until_closed(websocket).await;
+    })
+    .await;
+
+    let options = GatewayOptions {
+        transport_compression: GatewayTransportCompression::None,
+        dispatch_strategy: GatewayDispatchStrategy::Queued { capacity: 1 },
+        ..Default::default()
+    };
+    let gateway = Gateway::spawn(&url, options).await.unwrap();
+
+    let (event_send, mut event_receive) = tokio::sync::mpsc::unbounded_channel();
+    gateway
+        .events
+        .lock()
+        .await
+        .unknown
+        .subscribe(Arc::new(SlowUnknownEventObserver {
+            channel: event_send,
+        }));
+
+    for i in 0..EVENTS {
+        tokio::select! {
+            () = sleep(Duration::from_secs(5)) => {
+                panic!("Timed out waiting for event {i}");
+            }
+            Some(event_name) = event_receive.recv() => {
+                assert_eq!(event_name, format!("FAKE_EVENT_{i}"));
+            }
+        }
+    }
+
+    assert!(gateway.dispatch_metrics().full > 0);
+    assert_eq!(gateway.dispatch_metrics().queued, 0);
+
+    gateway.shutdown().await;
+}
@@ -0,0 +1,108 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use chorus::types::{Gif, GifMediaFormat, GifSearchQuery, GifSuggestQuery, GifTrendingQuery};
+use httptest::{
+    matchers::{all_of, contains, not, request, url_decoded},
+    responders::json_encoded,
+    Expectation,
+};
+use serde_json::json;
+
+mod common;
+
+fn gif_json() -> serde_json::Value {
+    json!({
+        "id": "1234",
+        "title": "cat typing",
+        "url": "https://tenor.com/view/cat-typing-1234",
+        "src": "https://media.tenor.com/1234/cat.mp4",
+        "gif_src": "https://media.tenor.com/1234/cat.gif",
+        "width": 498,
+        "height": 280,
+        "preview": "https://media.tenor.com/1234/cat.png"
+    })
+}
+
+#[tokio::test]
+async fn test_search_gifs() {
+    let server = common::create_mock_server();
+    let mut bundle = common::setup_with_mock_server(&server).await;
+
+    server.expect(
+        Expectation::matching(all_of![
+            request::method("GET"),
+            request::path("/api/gifs/search"),
+            request::query(url_decoded(contains(("q", "cat")))),
+            request::query(url_decoded(contains(("media_format", "tinymp4")))),
+            request::query(url_decoded(not(contains(("locale", "en-US"))))),
+        ])
+        .respond_with(json_encoded(json!([gif_json()]))),
+    );
+
+    let query = GifSearchQuery {
+        media_format: Some(GifMediaFormat::TinyMp4),
+        ..GifSearchQuery::new("cat")
+    };
+    let gifs = Gif::search(query, &mut bundle.user).await.unwrap();
+
+    assert_eq!(gifs.len(), 1);
+    assert_eq!(gifs[0].id, "1234");
+    assert_eq!(gifs[0].gif_src, "https://media.tenor.com/1234/cat.gif");
+    assert_eq!((gifs[0].width, gifs[0].height), (498, 280));
+}
+
+#[tokio::test]
+async fn test_trending_gifs() {
+    let server = common::create_mock_server();
+    let mut bundle = common::setup_with_mock_server(&server).await;
+
+    server.expect(
+        Expectation::matching(all_of![
+            request::method("GET"),
+            request::path("/api/gifs/trending"),
+            request::query(url_decoded(contains(("locale", "de")))),
+        ])
+        .respond_with(json_encoded(json!({
+            "categories": [{"name": "katze", "src": "https://media.tenor.com/1234/cat.mp4"}],
+            "gifs": [gif_json()]
+        }))),
+    );
+
+    let query = GifTrendingQuery {
+        locale: Some("de".to_string()),
+        ..Default::default()
+    };
+    let trending = Gif::trending(query, &mut bundle.user).await.unwrap();
+
+    assert_eq!(trending.categories.len(), 1);
+    assert_eq!(trending.categories[0].name, "katze");
+    assert_eq!(trending.gifs[0].title, "cat typing");
+}
+
+#[tokio::test]
+async fn test_suggest_gif_search_terms() {
+    let server = common::create_mock_server();
+    let mut bundle = common::setup_with_mock_server(&server).await;
+
+    server.expect(
+        Expectation::matching(all_of![
+            request::method("GET"),
+            request::path("/api/gifs/suggest"),
+            request::query(url_decoded(contains(("q", "ca")))),
+            request::query(url_decoded(contains(("limit", "2")))),
+        ])
+        .respond_with(json_encoded(json!(["cat", "cake"]))),
+    );
+
+    let query = GifSuggestQuery {
+        limit: Some(2),
+        ..GifSuggestQuery::new("ca")
+    };
+    let suggestions = Gif::suggest(query, &mut bundle.user).await.unwrap();
+
+    assert_eq!(suggestions, vec!["cat".to_string(), "cake".to_string()]);
+}
@@ -2,8 +2,14 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use chorus::errors::ChorusError;
+use chorus::instance::InstanceSoftware;
 use chorus::types::{
-    CreateChannelInviteSchema, Guild, GuildBanCreateSchema, GuildCreateSchema, GuildModifySchema,
+    Channel, ChannelCreateSchema, ChannelType, CreateChannelInviteSchema, DiscoverableGuildsQuery,
+    DiscoveryCategoriesQuery, DiscoveryCategory, Guild, GuildBanCreateSchema, GuildCreateSchema,
+    GuildModifyOnboardingSchema, GuildModifySchema, GuildModifyWelcomeScreenSchema,
+    GuildPruneQuerySchema, GuildWidgetImageStyle, GuildWidgetSettings,
+    ModifyChannelPositionsSchema, Snowflake, WelcomeScreenChannel,
 };
 
 mod common;
@@ -22,6 +28,7 @@ async fn guild_creation_deletion() {
         region: None,
         icon: None,
         channels: None,
+        roles: None,
         guild_template_code: None,
         system_channel_id: None,
         rules_channel_id: None,
@@ -56,7 +63,7 @@ async fn guild_create_ban() {
     let create_channel_invite_schema = CreateChannelInviteSchema::default();
     let guild = bundle.guild.read().unwrap().clone();
     let invite = user
-        .create_channel_invite(create_channel_invite_schema, channel.id)
+        .create_channel_invite(create_channel_invite_schema, channel.id, None)
         .await
         .unwrap();
     other_user.accept_invite(&invite.code, None).await.unwrap();
@@ -73,6 +80,61 @@ async fn guild_create_ban() {
     common::teardown(bundle).await
 }
 
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn guild_ban_unban() {
+    let mut bundle = common::setup().await;
+    let channel = bundle.channel.read().unwrap().clone();
+    let mut other_user = bundle.create_user("testuser1313").await;
+    let user = &mut bundle.user;
+    let guild = bundle.guild.read().unwrap().clone();
+    let invite = user
+        .create_channel_invite(CreateChannelInviteSchema::default(), channel.id, None)
+        .await
+        .unwrap();
+    other_user.accept_invite(&invite.code, None).await.unwrap();
+    let other_user_id = other_user.object.read().unwrap().id;
+
+    let schema = GuildBanCreateSchema {
+        delete_message_days: None,
+        delete_message_seconds: Some(0),
+    };
+    Guild::create_ban(guild.id, other_user_id, None, schema, &mut bundle.user)
+        .await
+        .unwrap();
+
+    let bans = Guild::bans_iter(guild.id, Some(1))
+        .collect_all(&mut bundle.user)
+        .await
+        .unwrap();
+    assert!(bans.iter().any(|ban| ban.user.id == other_user_id));
+
+    Guild::delete_ban(&mut bundle.user, guild.id, other_user_id, None)
+        .await
+        .unwrap();
+    assert!(Guild::get_ban(&mut bundle.user, guild.id, other_user_id)
+        .await
+        .is_err());
+    common::teardown(bundle).await
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn guild_prune_count() {
+    let mut bundle = common::setup().await;
+    let guild_id = bundle.guild.read().unwrap().id;
+    let query = GuildPruneQuerySchema {
+        days: 7,
+        compute_prune_count: None,
+        include_roles: Vec::new(),
+    };
+    let result = Guild::get_prune_count(guild_id, query, &mut bundle.user)
+        .await
+        .unwrap();
+    assert!(result.pruned.is_some());
+    common::teardown(bundle).await
+}
+
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
 #[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
 async fn modify_guild() {
@@ -82,9 +144,14 @@ async fn modify_guild() {
         ..Default::default()
     };
     let guild_id = bundle.guild.read().unwrap().id;
-    let result = Guild::modify(guild_id, schema, &mut bundle.user)
-        .await
-        .unwrap();
+    let result = Guild::modify(
+        guild_id,
+        schema,
+        Some("Renaming the guild".to_string()),
+        &mut bundle.user,
+    )
+    .await
+    .unwrap();
     assert_eq!(result.name.unwrap(), "Mycoolguild".to_string());
     common::teardown(bundle).await
 }
@@ -99,7 +166,7 @@ async fn guild_remove_member() {
     let create_channel_invite_schema = CreateChannelInviteSchema::default();
     let guild = bundle.guild.read().unwrap().clone();
     let invite = user
-        .create_channel_invite(create_channel_invite_schema, channel.id)
+        .create_channel_invite(create_channel_invite_schema, channel.id, None)
         .await
         .unwrap();
     other_user.accept_invite(&invite.code, None).await.unwrap();
@@ -114,3 +181,275 @@ async fn guild_remove_member() {
     );
     common::teardown(bundle).await
 }
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn modify_channel_positions() {
+    let mut bundle = common::setup().await;
+    let guild_id = bundle.guild.read().unwrap().id;
+    let channel_id = bundle.channel.read().unwrap().id;
+
+    let category_schema = ChannelCreateSchema {
+        name: "testcategory".to_string(),
+        channel_type: Some(ChannelType::GuildCategory),
+        ..Default::default()
+    };
+    let category = Channel::create(&mut bundle.user, guild_id, None, category_schema)
+        .await
+        .unwrap();
+
+    Guild::modify_channel_positions(
+        guild_id,
+        vec![ModifyChannelPositionsSchema {
+            id: channel_id,
+            position: Some(0),
+            lock_permissions: Some(true),
+            parent_id: Some(Some(category.id)),
+        }],
+        None,
+        &mut bundle.user,
+    )
+    .await
+    .unwrap();
+
+    let channel = Channel::get(&mut bundle.user, channel_id).await.unwrap();
+    assert_eq!(channel.parent_id, Some(category.id));
+    common::teardown(bundle).await
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn guild_discovery() {
+    let mut bundle = common::setup().await;
+    let guild_id = bundle.guild.read().unwrap().id;
+
+    let query = DiscoverableGuildsQuery {
+        limit: Some(10),
+        ..Default::default()
+    };
+    let discoverable = Guild::get_discoverable(query, &mut bundle.user)
+        .await
+        .unwrap();
+    assert!(discoverable.guilds.len() <= 10);
+
+    DiscoveryCategory::get_all(DiscoveryCategoriesQuery::default(), &mut bundle.user)
+        .await
+        .unwrap();
+
+    let requirements = Guild::get_discovery_requirements(guild_id, &mut bundle.user)
+        .await
+        .unwrap();
+    assert_eq!(requirements.guild_id, Some(guild_id));
+    common::teardown(bundle).await
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn premium_subscriptions_unsupported() {
+    let mut bundle = common::setup().await;
+    bundle
+        .user
+        .belongs_to
+        .write()
+        .unwrap()
+        .set_software(InstanceSoftware::SpacebarTypescript);
+
+    let guild_id = bundle.guild.read().unwrap().id;
+    let result = Guild::get_premium_subscriptions(guild_id, &mut bundle.user).await;
+    assert!(matches!(result, Err(ChorusError::UnsupportedByInstance { .. })));
+
+    let result = bundle.user.get_premium_guild_subscription_slots().await;
+    assert!(matches!(result, Err(ChorusError::UnsupportedByInstance { .. })));
+    common::teardown(bundle).await;
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn welcome_screen() {
+    let mut bundle = common::setup().await;
+    let guild_id = bundle.guild.read().unwrap().id;
+    let channel_id = bundle.channel.read().unwrap().id;
+
+    let schema = GuildModifyWelcomeScreenSchema {
+        enabled: Some(true),
+        description: Some("Welcome to the test guild".to_string()),
+        welcome_channels: Some(vec![WelcomeScreenChannel {
+            channel_id,
+            description: "Say hi".to_string(),
+            ..Default::default()
+        }]),
+    };
+    Guild::modify_welcome_screen(guild_id, schema, None, &mut bundle.user)
+        .await
+        .unwrap();
+
+    let welcome_screen = Guild::get_welcome_screen(guild_id, &mut bundle.user)
+        .await
+        .unwrap();
+    assert_eq!(
+        welcome_screen.description.as_deref(),
+        Some("Welcome to the test guild")
+    );
+    assert_eq!(welcome_screen.welcome_channels.len(), 1);
+    assert_eq!(welcome_screen.welcome_channels[0].channel_id, channel_id);
+    common::teardown(bundle).await;
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn onboarding_unsupported() {
+    let mut bundle = common::setup().await;
+    bundle
+        .user
+        .belongs_to
+        .write()
+        .unwrap()
+        .set_software(InstanceSoftware::SpacebarTypescript);
+
+    let guild_id = bundle.guild.read().unwrap().id;
+    let result = Guild::get_onboarding(guild_id, &mut bundle.user).await;
+    assert!(matches!(
+        result,
+        Err(ChorusError::UnsupportedByInstance { .. })
+    ));
+
+    let result = Guild::modify_onboarding(
+        guild_id,
+        GuildModifyOnboardingSchema::default(),
+        None,
+        &mut bundle.user,
+    )
+    .await;
+    assert!(matches!(
+        result,
+        Err(ChorusError::UnsupportedByInstance { .. })
+    ));
+    common::teardown(bundle).await;
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn guild_widget() {
+    let mut bundle = common::setup().await;
+    let guild_id = bundle.guild.read().unwrap().id;
+    let channel_id = bundle.channel.read().unwrap().id;
+
+    let schema = GuildWidgetSettings {
+        enabled: true,
+        channel_id: Some(channel_id),
+    };
+    let settings = Guild::modify_widget_settings(guild_id, schema, None, &mut bundle.user)
+        .await
+        .unwrap();
+    assert!(settings.enabled);
+    assert_eq!(settings.channel_id, Some(channel_id));
+
+    let settings = Guild::get_widget_settings(guild_id, &mut bundle.user)
+        .await
+        .unwrap();
+    assert_eq!(settings, schema);
+
+    let widget = Guild::get_widget(guild_id, &mut bundle.user).await.unwrap();
+    assert_eq!(widget.id, guild_id);
+
+    let image = Guild::get_widget_image(guild_id, GuildWidgetImageStyle::Shield, &mut bundle.user)
+        .await
+        .unwrap();
+    assert!(!image.is_empty());
+    common::teardown(bundle).await;
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn get_vanity_url() {
+    let mut bundle = common::setup().await;
+    let guild_id = bundle.guild.read().unwrap().id;
+
+    // The test guild has no vanity invite
+    let vanity_url = Guild::get_vanity_url(guild_id, &mut bundle.user).await;
+    assert!(vanity_url.is_ok());
+    common::teardown(bundle).await;
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn get_many_guilds() {
+    let mut bundle = common::setup().await;
+    let guild_id = bundle.guild.read().unwrap().id;
+    let unknown_id = Snowflake(1);
+
+    let result = Guild::get_many(&mut bundle.user, &[guild_id, unknown_id, guild_id]).await;
+    assert!(!result.is_complete());
+    assert_eq!(result.fetched.len(), 1);
+    assert_eq!(result.fetched[&guild_id].id, guild_id);
+    assert_eq!(result.errors.len(), 1);
+    assert!(result.errors.contains_key(&unknown_id));
+
+    let result = Guild::get_many_with_concurrency(&mut bundle.user, &[guild_id], 1).await;
+    assert!(result.is_complete());
+    common::teardown(bundle).await;
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+#[cfg(not(target_arch = "wasm32"))]
+async fn create_guild_from_template() {
+    use chorus::types::GuildTemplate;
+    use httptest::{
+        matchers::{all_of, contains, eq, json_decoded, request},
+        responders::{json_encoded, status_code},
+        Expectation,
+    };
+    use serde_json::json;
+
+    let server = common::create_mock_server();
+    let mut bundle = common::setup_with_mock_server(&server).await;
+
+    server.expect(
+        Expectation::matching(all_of![
+            request::method("GET"),
+            request::path("/api/guilds/templates/hgM48av5Q69A"),
+        ])
+        .respond_with(json_encoded(json!({
+            "code": "hgM48av5Q69A",
+            "name": "Friends & Family",
+            "description": null,
+            "usage_count": 0,
+            "creator_id": "123456789101112131",
+            "created_at": "2024-08-01T12:00:00+00:00",
+            "updated_at": "2024-08-01T12:00:00+00:00",
+            "source_guild_id": "123456789101112131",
+            "serialized_source_guild": {
+                "name": "Friends & Family",
+                "roles": [{ "id": 0, "name": "@everyone", "permissions": "104324161" }],
+                "channels": [{ "id": 1, "type": 0, "name": "general", "parent_id": null }],
+                "system_channel_id": 1
+            },
+            "is_dirty": null
+        }))),
+    );
+
+    server.expect(
+        Expectation::matching(all_of![
+            request::method("POST"),
+            request::path("/api/guilds/templates/hgM48av5Q69A"),
+            request::headers(contains(("authorization", "faketoken"))),
+            request::body(json_decoded(eq(json!({"name": "From template"})))),
+        ])
+        .respond_with(
+            status_code(201)
+                .append_header("Content-Type", "application/json")
+                .body(r#"{"id": "123456789101112132"}"#),
+        ),
+    );
+
+    let template = GuildTemplate::get("hgM48av5Q69A", &mut bundle.user)
+        .await
+        .unwrap();
+    assert_eq!(template.serialized_source_guild.system_channel_id, Some(1));
+
+    let guild =
+        Guild::create_from_template(&template.code, "From template", None, &mut bundle.user)
+            .await
+            .unwrap();
+    assert_eq!(guild.id, Snowflake(123456789101112132));
+}
@@ -3,7 +3,7 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 mod common;
-use chorus::instance::InstanceSoftware;
+use chorus::instance::{InstanceBuilder, InstanceSoftware};
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen_test::*;
 #[cfg(target_arch = "wasm32")]
@@ -33,3 +33,121 @@ async fn detect_instance_software() {
 
     common::teardown(bundle).await;
 }
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn refresh_instance_limits() {
+    let mut bundle = common::setup().await;
+
+    let limits = bundle.instance.get_limits_configuration().await.unwrap();
+    let refreshed = bundle.instance.refresh_limits().await.unwrap().clone();
+    assert_eq!(limits, refreshed);
+    assert_eq!(bundle.instance.limits_configuration, Some(limits));
+    assert!(bundle.instance.limits_information.is_some());
+
+    common::teardown(bundle).await;
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn refresh_instance_configuration() {
+    let mut bundle = common::setup().await;
+
+    assert_eq!(bundle.instance.instance_configuration, None);
+
+    let configuration = bundle.instance.get_instance_configuration().await.unwrap();
+    let refreshed = bundle
+        .instance
+        .refresh_instance_configuration()
+        .await
+        .unwrap()
+        .clone();
+    assert_eq!(configuration, refreshed);
+    assert_eq!(bundle.instance.instance_configuration, Some(configuration));
+
+    common::teardown(bundle).await;
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn get_gateway_bot() {
+    let mut bundle = common::setup().await;
+
+    let gateway_bot = bundle.user.get_gateway_bot().await.unwrap();
+    let session_start_limit = bundle
+        .user
+        .belongs_to
+        .read()
+        .unwrap()
+        .session_start_limit()
+        .unwrap();
+    assert_eq!(
+        session_start_limit.max_concurrency,
+        gateway_bot.session_start_limit.max_concurrency
+    );
+
+    common::teardown(bundle).await;
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn build_instance_accepting_invalid_certs() {
+    let bundle = common::setup().await;
+
+    let instance = InstanceBuilder::from_url_bundle(bundle.urls.clone())
+        .danger_accept_invalid_certs(true)
+        .build()
+        .await
+        .unwrap();
+    assert!(instance.gateway_options().danger_accept_invalid_certs);
+    assert_eq!(instance.software(), InstanceSoftware::SpacebarTypescript);
+
+    let instance = InstanceBuilder::from_url_bundle(bundle.urls.clone())
+        .build()
+        .await
+        .unwrap();
+    assert!(!instance.gateway_options().danger_accept_invalid_certs);
+
+    common::teardown(bundle).await;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+async fn detect_capabilities_from_version() {
+    use chorus::instance::{Capability, Instance};
+    use chorus::UrlBundle;
+    use httptest::matchers::request;
+    use httptest::responders::{json_encoded, status_code};
+    use httptest::{Expectation, Server};
+    use serde_json::json;
+
+    let server = Server::run();
+    for path in ["/api/policies/instance/limits", "/api/policies/instance/"] {
+        server.expect(
+            Expectation::matching(request::path(path))
+                .times(0..)
+                .respond_with(status_code(404)),
+        );
+    }
+    server.expect(
+        Expectation::matching(request::path("/api/version")).respond_with(json_encoded(json!({
+            "version": "1.0.0",
+            "server": "spacebar",
+            "capabilities": ["harvest", "not_a_capability"]
+        }))),
+    );
+
+    let urls = UrlBundle::new(
+        &server.url_str(""),
+        &server.url_str("/api"),
+        "ws://localhost:3001",
+        &server.url_str("/cdn"),
+    )
+    .unwrap();
+    let instance = Instance::from_url_bundle(urls, None).await.unwrap();
+
+    assert_eq!(instance.software(), InstanceSoftware::SpacebarTypescript);
+    assert!(instance.supports(Capability::Harvest));
+    assert!(!instance.supports(Capability::UserNotes));
+    assert_eq!(instance.detected_capabilities().len(), 1);
+}
@@ -3,6 +3,8 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 mod common;
+use chorus::errors::ChorusError;
+use chorus::instance::InstanceSoftware;
 use chorus::types::CreateChannelInviteSchema;
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen_test::*;
@@ -22,7 +24,7 @@ async fn create_accept_invite() {
         .await
         .is_err());
     let invite = user
-        .create_channel_invite(create_channel_invite_schema, channel.id)
+        .create_channel_invite(create_channel_invite_schema, channel.id, None)
         .await
         .unwrap();
 
@@ -32,3 +34,50 @@ async fn create_accept_invite() {
         .is_ok());
     common::teardown(bundle).await;
 }
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn create_user_invite_unsupported() {
+    let mut bundle = common::setup().await;
+    bundle
+        .user
+        .belongs_to
+        .write()
+        .unwrap()
+        .set_software(InstanceSoftware::SpacebarTypescript);
+
+    let result = bundle.user.create_user_invite(None).await;
+    assert!(matches!(result, Err(ChorusError::UnsupportedByInstance { .. })));
+    common::teardown(bundle).await;
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn get_list_delete_invite() {
+    let mut bundle = common::setup().await;
+    let channel_id = bundle.channel.read().unwrap().id;
+    let guild_id = bundle.guild.read().unwrap().id;
+    let user = &mut bundle.user;
+
+    let invite = user
+        .create_channel_invite(CreateChannelInviteSchema::default(), channel_id, None)
+        .await
+        .unwrap();
+
+    let fetched = user
+        .get_invite(&invite.code, Some(true), Some(true))
+        .await
+        .unwrap();
+    assert_eq!(fetched.code, invite.code);
+
+    let guild_invites = user.get_guild_invites(guild_id).await.unwrap();
+    assert!(guild_invites.iter().any(|x| x.code == invite.code));
+
+    let channel_invites = user.get_channel_invites(channel_id).await.unwrap();
+    assert!(channel_invites.iter().any(|x| x.code == invite.code));
+
+    let deleted = user.delete_invite(&invite.code, None).await.unwrap();
+    assert_eq!(deleted.code, invite.code);
+    assert!(user.get_invite(&invite.code, None, None).await.is_err());
+    common::teardown(bundle).await;
+}
@@ -2,7 +2,10 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use chorus::{errors::ChorusResult, types::GuildMember};
+use chorus::{
+    errors::ChorusResult,
+    types::{Guild, GuildMember, GuildMemberSearchSchema},
+};
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen_test::*;
 #[cfg(target_arch = "wasm32")]
@@ -17,13 +20,20 @@ async fn add_remove_role() -> ChorusResult<()> {
     let guild = bundle.guild.read().unwrap().id;
     let role = bundle.role.read().unwrap().id;
     let member_id = bundle.user.object.read().unwrap().id;
-    GuildMember::add_role(&mut bundle.user, guild, member_id, role).await?;
+    GuildMember::add_role(
+        &mut bundle.user,
+        guild,
+        member_id,
+        role,
+        Some("Testing role toggles".to_string()),
+    )
+    .await?;
     let member = GuildMember::get(&mut bundle.user, guild, member_id)
         .await
         .unwrap();
     assert!(member.roles.contains(&role));
 
-    GuildMember::remove_role(&mut bundle.user, guild, member_id, role).await?;
+    GuildMember::remove_role(&mut bundle.user, guild, member_id, role, None).await?;
     let member = GuildMember::get(&mut bundle.user, guild, member_id)
         .await
         .unwrap();
@@ -32,3 +42,140 @@ async fn add_remove_role() -> ChorusResult<()> {
     common::teardown(bundle).await;
     Ok(())
 }
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn members_iter() -> ChorusResult<()> {
+    let mut bundle = common::setup().await;
+    let guild = bundle.guild.read().unwrap().id;
+    let member_id = bundle.user.object.read().unwrap().id;
+
+    let mut members = Guild::members_iter(guild, Some(1));
+    let all_members = members.collect_all(&mut bundle.user).await?;
+    assert!(members.is_exhausted());
+    assert!(all_members
+        .iter()
+        .any(|member| member.user.as_ref().unwrap().read().unwrap().id == member_id));
+
+    common::teardown(bundle).await;
+    Ok(())
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn search_members() -> ChorusResult<()> {
+    let mut bundle = common::setup().await;
+    let guild = bundle.guild.read().unwrap().id;
+    let username = bundle.user.object.read().unwrap().username.clone();
+
+    let query = GuildMemberSearchSchema {
+        query: username[..3].to_string(),
+        limit: Some(10),
+    };
+    let members = Guild::search_members(guild, query, &mut bundle.user).await?;
+    assert!(!members.is_empty());
+
+    common::teardown(bundle).await;
+    Ok(())
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+#[cfg(not(target_arch = "wasm32"))]
+async fn timeout_member() {
+    use chorus::errors::ChorusError;
+    use chorus::types::{JsonErrorCode, PermissionFlags, Snowflake, MAX_MEMBER_TIMEOUT};
+    use chrono::{TimeDelta, Utc};
+    use httptest::{
+        matchers::{all_of, contains, eq, json_decoded, request},
+        responders::{json_encoded, status_code},
+        Expectation,
+    };
+    use serde_json::json;
+
+    let server = common::create_mock_server();
+    let mut bundle = common::setup_with_mock_server(&server).await;
+    let guild_id = Snowflake(1);
+    let member_id = Snowflake(2);
+
+    let until = Utc::now() + TimeDelta::hours(1);
+    let member_json = |until: Option<String>| {
+        json!({
+            "roles": [],
+            "joined_at": "2024-08-01T12:00:00+00:00",
+            "deaf": false,
+            "mute": false,
+            "communication_disabled_until": until
+        })
+    };
+
+    server.expect(
+        Expectation::matching(all_of![
+            request::method("PATCH"),
+            request::path("/api/guilds/1/members/2"),
+            request::headers(contains(("x-audit-log-reason", "spam"))),
+            request::body(json_decoded(eq(
+                json!({"communication_disabled_until": until})
+            ))),
+        ])
+        .respond_with(json_encoded(member_json(Some(until.to_rfc3339())))),
+    );
+    let member = GuildMember::timeout(
+        &mut bundle.user,
+        guild_id,
+        member_id,
+        until,
+        Some("spam".to_string()),
+    )
+    .await
+    .unwrap();
+    assert!(member.is_timed_out());
+
+    server.expect(
+        Expectation::matching(all_of![
+            request::method("PATCH"),
+            request::path("/api/guilds/1/members/2"),
+            request::body(json_decoded(eq(
+                json!({"communication_disabled_until": null})
+            ))),
+        ])
+        .respond_with(json_encoded(member_json(None))),
+    );
+    let member = GuildMember::remove_timeout(&mut bundle.user, guild_id, member_id, None)
+        .await
+        .unwrap();
+    assert!(!member.is_timed_out());
+
+    // Timeouts longer than 28 days and in the past are rejected without a request
+    for until in [
+        Utc::now() + MAX_MEMBER_TIMEOUT + TimeDelta::minutes(1),
+        Utc::now() - TimeDelta::minutes(1),
+    ] {
+        let result = GuildMember::timeout(&mut bundle.user, guild_id, member_id, until, None).await;
+        assert!(matches!(result, Err(ChorusError::InvalidArguments { .. })));
+    }
+
+    server.expect(
+        Expectation::matching(request::path("/api/guilds/1/members/3")).respond_with(
+            status_code(403)
+                .append_header("Content-Type", "application/json")
+                .body(r#"{"code": 50013, "message": "Missing Permissions"}"#),
+        ),
+    );
+    let result = GuildMember::remove_timeout(&mut bundle.user, guild_id, Snowflake(3), None).await;
+    let error = result.unwrap_err();
+    assert!(matches!(
+        error,
+        ChorusError::MissingPermission {
+            permission: PermissionFlags::MODERATE_MEMBERS,
+            ..
+        }
+    ));
+    assert_eq!(
+        error.api_error().unwrap().code,
+        JsonErrorCode::MissingPermissions
+    );
+    assert_eq!(
+        error.to_string(),
+        "Missing the MODERATE_MEMBERS permission needed to perform this action"
+    );
+}
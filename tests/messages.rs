@@ -5,6 +5,7 @@
 use std::fs::File;
 use std::io::{BufReader, Read};
 
+use chorus::errors::ChorusError;
 use chorus::types::{self, Guild, Message, MessageSearchQuery};
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen_test::*;
@@ -155,3 +156,191 @@ async fn test_stickies() {
     );
     common::teardown(bundle).await
 }
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn add_reactions() {
+    let mut bundle = common::setup().await;
+    let message = types::MessageSendSchema {
+        content: Some("Pick one!".to_string()),
+        ..Default::default()
+    };
+    let channel = bundle.channel.read().unwrap().clone();
+    let message = bundle.user.send_message(message, channel.id).await.unwrap();
+
+    Message::add_reactions(channel.id, message.id, &["🍎", "🍐"], &mut bundle.user)
+        .await
+        .unwrap();
+
+    let message = Message::get(channel.id, message.id, &mut bundle.user)
+        .await
+        .unwrap();
+    let reactions = message.reactions.unwrap_or_default();
+    assert_eq!(reactions.len(), 2);
+    common::teardown(bundle).await
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn acknowledge_message() {
+    let mut bundle = common::setup().await;
+    let message = types::MessageSendSchema {
+        content: Some("A Message!".to_string()),
+        ..Default::default()
+    };
+    let channel = bundle.channel.read().unwrap().clone();
+    let message = bundle.user.send_message(message, channel.id).await.unwrap();
+    bundle
+        .user
+        .acknowledge_message(channel.id, message.id)
+        .await
+        .unwrap();
+    common::teardown(bundle).await
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn bulk_acknowledge_and_delete_read_state() {
+    let mut bundle = common::setup().await;
+    let message = types::MessageSendSchema {
+        content: Some("A Message!".to_string()),
+        ..Default::default()
+    };
+    let channel = bundle.channel.read().unwrap().clone();
+    let message = bundle.user.send_message(message, channel.id).await.unwrap();
+
+    let schema = types::BulkAckSchema {
+        read_states: vec![types::BulkAckEntry {
+            channel_id: channel.id,
+            message_id: message.id,
+            read_state_type: None,
+        }],
+    };
+    Message::acknowledge_bulk(schema, &mut bundle.user)
+        .await
+        .unwrap();
+
+    Message::delete_read_state(
+        channel.id,
+        types::DeleteReadStateSchema::default(),
+        &mut bundle.user,
+    )
+    .await
+    .unwrap();
+    common::teardown(bundle).await
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn send_message_exceeding_limits() {
+    let mut bundle = common::setup().await;
+    let mut limits = bundle
+        .user
+        .belongs_to
+        .read()
+        .unwrap()
+        .limits_configuration
+        .clone()
+        .unwrap_or_default();
+    limits.message.max_characters = 10;
+    bundle.user.belongs_to.write().unwrap().limits_configuration = Some(limits);
+
+    let message = types::MessageSendSchema {
+        content: Some("A long Message!".to_string()),
+        ..Default::default()
+    };
+    let channel = bundle.channel.read().unwrap().clone();
+    let result = bundle.user.send_message(message, channel.id).await;
+    assert_eq!(
+        result.unwrap_err(),
+        ChorusError::LimitExceeded {
+            limit: "message content length".to_string(),
+            max: 10,
+            actual: 15
+        }
+    );
+
+    let message = types::MessageSendSchema {
+        content: Some("A Message!".to_string()),
+        ..Default::default()
+    };
+    bundle.user.send_message(message, channel.id).await.unwrap();
+    common::teardown(bundle).await
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+async fn send_message_with_invalid_allowed_mentions() {
+    use chorus::types::{AllowedMentions, Snowflake};
+
+    // Any request would fail, since the mock server does not expect it
+    let server = common::create_mock_server();
+    let mut instance = chorus::instance::Instance::new(server.url_str("/api").as_str(), None)
+        .await
+        .unwrap();
+    instance.set_rest_only(true);
+    let mut user = instance.login_with_token("faketoken").await.unwrap();
+
+    let mut allowed_mentions = AllowedMentions::all_except_everyone();
+    allowed_mentions.users.push(Snowflake(10));
+
+    let message = types::MessageSendSchema {
+        content: Some("<@10>".to_string()),
+        allowed_mentions: Some(allowed_mentions),
+        ..Default::default()
+    };
+    let result = Message::send(&mut user, Snowflake(20), message).await;
+
+    assert!(matches!(result, Err(ChorusError::InvalidArguments { .. })));
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+async fn execute_webhook() {
+    use chorus::types::{AllowedMentions, Snowflake, Webhook, WebhookExecuteSchema};
+    use httptest::{
+        matchers::{all_of, contains, eq, json_decoded, request, url_decoded},
+        responders::json_encoded,
+        Expectation,
+    };
+    use serde_json::json;
+
+    let server = common::create_mock_server();
+    let mut instance = chorus::instance::Instance::new(server.url_str("/api").as_str(), None)
+        .await
+        .unwrap();
+    instance.set_rest_only(true);
+    let mut user = instance.login_with_token("faketoken").await.unwrap();
+
+    server.expect(
+        Expectation::matching(all_of![
+            request::method("POST"),
+            request::path("/api/webhooks/30/webhooktoken"),
+            request::query(url_decoded(contains(("wait", "true")))),
+            request::body(json_decoded(eq(json!({
+                "content": "Hello @everyone",
+                "username": "Hook",
+                "allowed_mentions": {"parse": [], "replied_user": false}
+            })))),
+        ])
+        .respond_with(json_encoded(Message {
+            id: Snowflake(40),
+            channel_id: Snowflake(20),
+            content: Some("Hello @everyone".to_string()),
+            webhook_id: Some(Snowflake(30)),
+            ..Default::default()
+        })),
+    );
+
+    let schema = WebhookExecuteSchema {
+        content: Some("Hello @everyone".to_string()),
+        username: Some("Hook".to_string()),
+        allowed_mentions: Some(AllowedMentions::none()),
+        ..Default::default()
+    };
+    let message = Webhook::execute(Snowflake(30), "webhooktoken", schema, &mut user)
+        .await
+        .unwrap();
+
+    assert_eq!(message.id, Snowflake(40));
+}
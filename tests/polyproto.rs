@@ -0,0 +1,197 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+#![cfg(all(feature = "polyproto", not(target_arch = "wasm32")))]
+
+use std::sync::Arc;
+
+use chorus::errors::{ChorusError, ChorusResult};
+use chorus::instance::Instance;
+use chorus::polyproto::{RequestSigner, SignedResponse};
+use chorus::types::{CachedIdCert, FederatedId, PolyprotoWellKnown, Snowflake};
+use httptest::{
+    cycle,
+    matchers::{all_of, contains, eq, json_decoded, matches, request, url_decoded},
+    responders::{json_encoded, status_code},
+    Expectation,
+};
+use reqwest::header::HeaderValue;
+use serde_json::json;
+
+mod common;
+
+#[tokio::test]
+async fn test_discover_polyproto() {
+    let server = common::create_mock_server();
+
+    server.expect(
+        Expectation::matching(all_of![
+            request::method("GET"),
+            request::path("/.well-known/polyproto-core"),
+        ])
+        .respond_with(json_encoded(json!({"api": "https://p2.example.com"}))),
+    );
+
+    let well_known = Instance::discover_polyproto(&server.url_str(""))
+        .await
+        .unwrap();
+    assert_eq!(
+        well_known,
+        PolyprotoWellKnown {
+            api: "https://p2.example.com".to_string()
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_get_local_actor_id_certs() {
+    let server = common::create_mock_server();
+    let instance = Instance::new(server.url_str("/api").as_str(), None)
+        .await
+        .unwrap();
+
+    let id = instance.federated_id_of(Snowflake(123456789101112131));
+
+    server.expect(
+        Expectation::matching(all_of![
+            request::method("GET"),
+            request::path(matches(r"/\.p2/core/v1/idcert/actor/123456789101112131@")),
+            request::query(url_decoded(contains(("timestamp", "1700000000")))),
+        ])
+        .respond_with(json_encoded(json!([{
+            "id_cert": "test_id_cert",
+            "invalidated": false,
+            "cache_not_valid_before": 1600000000u64,
+            "cache_not_valid_after": 1800000000u64,
+            "cache_signature": "test_signature"
+        }]))),
+    );
+
+    let id_certs = instance
+        .get_actor_id_certs(&id, Some(1700000000))
+        .await
+        .unwrap();
+    assert_eq!(
+        id_certs,
+        vec![CachedIdCert {
+            id_cert: "test_id_cert".to_string(),
+            invalidated: false,
+            cache_not_valid_before: 1600000000,
+            cache_not_valid_after: 1800000000,
+            cache_signature: "test_signature".to_string(),
+        }]
+    );
+}
+
+/// Signs requests with a fixed header, and only accepts responses which carry one as well
+#[derive(Debug)]
+struct HeaderSigner;
+
+impl RequestSigner for HeaderSigner {
+    fn sign_request(&self, request: &mut reqwest::Request) -> ChorusResult<()> {
+        request
+            .headers_mut()
+            .insert("x-signature", HeaderValue::from_static("request-signature"));
+        Ok(())
+    }
+
+    fn verify_response(&self, response: &SignedResponse) -> ChorusResult<()> {
+        match response.headers.contains_key("x-signature") {
+            true => Ok(()),
+            false => Err(ChorusError::InvalidSignature {
+                error: "The response is not signed".to_string(),
+            }),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_request_signer() {
+    let server = common::create_mock_server();
+    let mut instance = Instance::new(server.url_str("/api").as_str(), None)
+        .await
+        .unwrap();
+    instance.set_rest_only(true);
+    let mut user = instance.login_with_token("faketoken").await.unwrap();
+    user.belongs_to
+        .write()
+        .unwrap()
+        .set_request_signer(Some(Arc::new(HeaderSigner)));
+
+    server.expect(
+        Expectation::matching(all_of![
+            request::method("GET"),
+            request::path("/api/users/@me/channels"),
+            request::headers(contains(("x-signature", "request-signature"))),
+        ])
+        .times(2)
+        .respond_with(cycle![
+            status_code(200)
+                .append_header("Content-Type", "application/json")
+                .append_header("x-signature", "response-signature")
+                .body("[]"),
+            status_code(200)
+                .append_header("Content-Type", "application/json")
+                .body("[]"),
+        ]),
+    );
+
+    let channels = user.get_private_channels().await.unwrap();
+    assert!(channels.is_empty());
+
+    let result = user.get_private_channels().await;
+    assert_eq!(
+        result.unwrap_err(),
+        ChorusError::InvalidSignature {
+            error: "The response is not signed".to_string()
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_rotate_session_id_cert() {
+    let server = common::create_mock_server();
+    let mut instance = Instance::new(server.url_str("/api").as_str(), None)
+        .await
+        .unwrap();
+    instance.set_rest_only(true);
+    let mut user = instance.login_with_token("faketoken").await.unwrap();
+    assert_eq!(user.id_cert, None);
+
+    server.expect(
+        Expectation::matching(all_of![
+            request::method("POST"),
+            request::path(matches(r"/\.p2/core/v1/session/idcert$")),
+            request::body(json_decoded(eq(json!({"csr": "test_csr"})))),
+        ])
+        .respond_with(json_encoded(json!({"id_cert": "new_id_cert"}))),
+    );
+
+    let id_cert = user
+        .rotate_session_id_cert("test_csr".to_string())
+        .await
+        .unwrap();
+    assert_eq!(id_cert, "new_id_cert");
+    assert_eq!(user.id_cert, Some("new_id_cert".to_string()));
+}
+
+#[tokio::test]
+async fn test_federated_friend_request_to_remote_user() {
+    let server = common::create_mock_server();
+    let mut instance = Instance::new(server.url_str("/api").as_str(), None)
+        .await
+        .unwrap();
+    instance.set_rest_only(true);
+    let mut user = instance.login_with_token("faketoken").await.unwrap();
+
+    let remote_id = FederatedId::new("friend", "remote.example.com").unwrap();
+
+    let result = user.send_federated_friend_request(&remote_id).await;
+    assert_eq!(
+        result.unwrap_err(),
+        ChorusError::InvalidArguments {
+            error: "friend@remote.example.com is not a user on this instance".to_string()
+        }
+    );
+}
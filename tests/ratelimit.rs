@@ -2,7 +2,11 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::time::Duration;
+
 use chorus::ratelimiter::ChorusRequest;
+use chorus::types::LimitType;
+use futures_util::StreamExt;
 
 mod common;
 
@@ -28,3 +32,170 @@ async fn get_limit_config() {
     assert!(conf.user.max_guilds > 0);
     assert!(conf.user.max_friends > 0);
 }
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn inspect_ratelimits() {
+    let mut bundle = common::setup().await;
+
+    if bundle.instance.limits_information.is_none() {
+        // The instance is not rate limited, there is nothing to inspect
+        common::teardown(bundle).await;
+        return;
+    }
+
+    let mut events = Box::pin(bundle.instance.ratelimit_events());
+
+    // Leave a single request in the global bucket
+    bundle
+        .user
+        .belongs_to
+        .write()
+        .unwrap()
+        .limits_information
+        .as_mut()
+        .unwrap()
+        .ratelimits
+        .get_mut(&LimitType::Global)
+        .unwrap()
+        .remaining = 1;
+
+    bundle.user.get_settings().await.unwrap();
+
+    let global = bundle.user.ratelimit(&LimitType::Global).unwrap();
+    assert_eq!(global.remaining, 0);
+    assert_eq!(
+        bundle
+            .user
+            .belongs_to
+            .read()
+            .unwrap()
+            .ratelimit(&LimitType::Global),
+        Some(global)
+    );
+    assert_eq!(
+        bundle.user.ratelimits().get(&LimitType::Global),
+        Some(&global)
+    );
+    assert!(bundle.user.time_until_available(&LimitType::Global) > Duration::ZERO);
+
+    let event = events.next().await.unwrap();
+    assert_eq!(event.user_id, bundle.user.object.read().unwrap().id);
+    assert_eq!(event.limit.bucket, LimitType::Global);
+
+    // Let the teardown's requests through again
+    bundle
+        .user
+        .belongs_to
+        .write()
+        .unwrap()
+        .limits_information
+        .as_mut()
+        .unwrap()
+        .ratelimits
+        .get_mut(&LimitType::Global)
+        .unwrap()
+        .remaining = 1000;
+    common::teardown(bundle).await;
+}
+
+/// Logs in a REST-only user on the mock server, which retries quickly
+#[cfg(not(target_arch = "wasm32"))]
+async fn retrying_user(server: &httptest::Server) -> chorus::instance::ChorusUser {
+    let mut instance = chorus::instance::Instance::new(server.url_str("/api").as_str(), None)
+        .await
+        .unwrap();
+    instance.set_rest_only(true);
+    instance.set_retry_policy(chorus::ratelimiter::RetryPolicy {
+        base_delay: Duration::from_millis(10),
+        max_delay: Duration::from_secs(1),
+        ..Default::default()
+    });
+    instance.login_with_token("faketoken").await.unwrap()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+async fn test_get_is_retried_after_server_error() {
+    use httptest::matchers::{all_of, request};
+    use httptest::responders::{json_encoded, status_code};
+    use httptest::{cycle, Expectation};
+
+    let server = common::create_mock_server();
+    let mut user = retrying_user(&server).await;
+
+    let guild = chorus::types::Guild {
+        id: chorus::types::Snowflake(1),
+        name: Some("Retried".to_string()),
+        ..Default::default()
+    };
+    server.expect(
+        Expectation::matching(all_of![
+            request::method("GET"),
+            request::path("/api/guilds/1"),
+        ])
+        .times(2)
+        .respond_with(cycle![status_code(503), json_encoded(guild.clone())]),
+    );
+
+    let received = chorus::types::Guild::get(guild.id, &mut user)
+        .await
+        .unwrap();
+    assert_eq!(received.name, guild.name);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+async fn test_post_is_not_retried_after_server_error() {
+    use httptest::matchers::{all_of, request};
+    use httptest::responders::status_code;
+    use httptest::Expectation;
+
+    let server = common::create_mock_server();
+    let mut user = retrying_user(&server).await;
+
+    server.expect(
+        Expectation::matching(all_of![
+            request::method("POST"),
+            request::path("/api/users/@me/channels"),
+        ])
+        .times(1)
+        .respond_with(status_code(503)),
+    );
+
+    let result = user
+        .create_private_channel(chorus::types::PrivateChannelCreateSchema {
+            recipients: Some(vec![chorus::types::Snowflake(2)]),
+            access_tokens: None,
+            nicks: None,
+        })
+        .await;
+    assert!(result.is_err());
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+async fn test_long_retry_after_is_not_waited_for() {
+    use chorus::errors::ChorusError;
+    use httptest::matchers::{all_of, request};
+    use httptest::responders::status_code;
+    use httptest::Expectation;
+
+    let server = common::create_mock_server();
+    let mut user = retrying_user(&server).await;
+
+    server.expect(
+        Expectation::matching(all_of![
+            request::method("GET"),
+            request::path("/api/guilds/1"),
+        ])
+        .times(1)
+        .respond_with(status_code(429).insert_header("Retry-After", "60")),
+    );
+
+    let start = std::time::Instant::now();
+    let result = chorus::types::Guild::get(chorus::types::Snowflake(1), &mut user).await;
+
+    assert!(matches!(result, Err(ChorusError::RateLimited { .. })));
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
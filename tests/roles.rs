@@ -2,7 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use chorus::types::{self, RoleCreateModifySchema, RoleObject};
+use chorus::types::{self, RoleCreateModifySchema, RoleObject, RolePositionUpdateSchema};
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen_test::*;
 #[cfg(target_arch = "wasm32")]
@@ -24,11 +24,17 @@ async fn create_and_get_roles() {
         mentionable: Some(true),
         position: None,
         color: None,
+        colors: None,
     };
     let guild_id = bundle.guild.read().unwrap().id;
-    let role = types::RoleObject::create(&mut bundle.user, guild_id, role_create_schema)
-        .await
-        .unwrap();
+    let role = types::RoleObject::create(
+        &mut bundle.user,
+        guild_id,
+        role_create_schema,
+        Some("Creating a role".to_string()),
+    )
+    .await
+    .unwrap();
 
     let expected = types::RoleObject::get_all(&mut bundle.user, guild_id)
         .await
@@ -69,3 +75,34 @@ async fn get_and_delete_role() {
     );
     common::teardown(bundle).await
 }
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn modify_role_positions() {
+    let mut bundle = common::setup().await;
+    let guild_id = bundle.guild.read().unwrap().id;
+    let role_id = bundle.role.read().unwrap().id;
+
+    let roles = RoleObject::modify_positions(
+        &mut bundle.user,
+        guild_id,
+        vec![RolePositionUpdateSchema {
+            id: role_id,
+            position: 1,
+        }],
+        None,
+    )
+    .await
+    .unwrap();
+    let role = roles.iter().find(|role| role.id == role_id).unwrap();
+    #[cfg(not(feature = "sqlx"))]
+    assert_eq!(role.position, 1);
+    #[cfg(feature = "sqlx")]
+    assert_eq!(role.position.to_uint(), 1);
+
+    let member_counts = RoleObject::get_member_counts(&mut bundle.user, guild_id)
+        .await
+        .unwrap();
+    assert!(member_counts.contains_key(&role_id));
+    common::teardown(bundle).await
+}
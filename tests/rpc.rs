@@ -0,0 +1,167 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+#![cfg(all(feature = "rpc", unix))]
+
+use std::path::PathBuf;
+
+use chorus::errors::RpcError;
+use chorus::rpc::RpcClient;
+use chorus::types::{ActivityBuilder, ActivityType, Snowflake};
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{UnixListener, UnixStream};
+
+/// Binds a socket for a fake local client, in a fresh temporary directory
+fn bind_fake_client(name: &str) -> (UnixListener, PathBuf) {
+    let directory =
+        std::env::temp_dir().join(format!("chorus-rpc-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    std::fs::create_dir_all(&directory).unwrap();
+
+    let path = directory.join("discord-ipc-0");
+    (UnixListener::bind(&path).unwrap(), path)
+}
+
+async fn read_frame(stream: &mut UnixStream) -> (u32, Value) {
+    let opcode = stream.read_u32_le().await.unwrap();
+    let length = stream.read_u32_le().await.unwrap();
+    let mut payload = vec![0; length as usize];
+    stream.read_exact(&mut payload).await.unwrap();
+    (opcode, serde_json::from_slice(&payload).unwrap())
+}
+
+async fn write_frame(stream: &mut UnixStream, opcode: u32, payload: Value) {
+    let payload = serde_json::to_vec(&payload).unwrap();
+    stream.write_u32_le(opcode).await.unwrap();
+    stream.write_u32_le(payload.len() as u32).await.unwrap();
+    stream.write_all(&payload).await.unwrap();
+}
+
+/// Accepts a connection and answers the handshake of the application with id 1234
+async fn accept_handshake(listener: &UnixListener) -> UnixStream {
+    let (mut stream, _) = listener.accept().await.unwrap();
+
+    let (opcode, handshake) = read_frame(&mut stream).await;
+    assert_eq!(opcode, 0);
+    assert_eq!(handshake, json!({"v": 1, "client_id": "1234"}));
+
+    let ready = json!({
+        "cmd": "DISPATCH",
+        "evt": "READY",
+        "nonce": null,
+        "data": {
+            "v": 1,
+            "config": {
+                "cdn_host": "cdn.example.com",
+                "api_endpoint": "//example.com/api",
+                "environment": "production"
+            },
+            "user": {"id": "5678", "username": "integrationtestuser", "discriminator": "0"}
+        }
+    });
+    write_frame(&mut stream, 1, ready).await;
+
+    stream
+}
+
+#[tokio::test]
+async fn test_rpc_set_activity() {
+    let (listener, path) = bind_fake_client("activity");
+
+    let fake_client = tokio::spawn(async move {
+        let mut stream = accept_handshake(&listener).await;
+
+        let (opcode, command) = read_frame(&mut stream).await;
+        assert_eq!(opcode, 1);
+        assert_eq!(command["cmd"], "SET_ACTIVITY");
+        assert_eq!(command["args"]["pid"], std::process::id());
+
+        // Only the fields rich presence supports are sent
+        let activity = &command["args"]["activity"];
+        assert_eq!(activity["details"], "Writing a client");
+        assert_eq!(activity["type"], 0);
+        assert!(activity.get("name").is_none());
+        assert!(activity.get("created_at").is_none());
+
+        // Pings have to be answered while waiting for the response
+        write_frame(&mut stream, 3, json!({"ping": 1})).await;
+        let (opcode, pong) = read_frame(&mut stream).await;
+        assert_eq!(opcode, 4);
+        assert_eq!(pong, json!({"ping": 1}));
+
+        let response = json!({
+            "cmd": "SET_ACTIVITY",
+            "evt": null,
+            "nonce": command["nonce"],
+            "data": activity
+        });
+        write_frame(&mut stream, 1, response).await;
+
+        let (opcode, command) = read_frame(&mut stream).await;
+        assert_eq!(opcode, 1);
+        assert_eq!(command["args"]["activity"], Value::Null);
+
+        let error = json!({
+            "cmd": "SET_ACTIVITY",
+            "evt": "ERROR",
+            "nonce": command["nonce"],
+            "data": {"code": 4000, "message": "Invalid activity"}
+        });
+        write_frame(&mut stream, 1, error).await;
+    });
+
+    let mut client = RpcClient::connect_to(&path, Snowflake(1234)).await.unwrap();
+    assert_eq!(client.ready().v, 1);
+    assert_eq!(client.ready().config.environment, "production");
+    assert_eq!(client.ready().user.as_ref().unwrap().id, Snowflake(5678));
+
+    let activity = ActivityBuilder::new("Chorus", ActivityType::Playing)
+        .details("Writing a client")
+        .build();
+    client.set_activity(Some(activity)).await.unwrap();
+
+    let error = client.clear_activity().await.unwrap_err();
+    assert_eq!(
+        error,
+        RpcError::Command {
+            code: 4000,
+            message: "Invalid activity".to_string()
+        }
+    );
+
+    fake_client.await.unwrap();
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[tokio::test]
+async fn test_rpc_rejected_handshake() {
+    let (listener, path) = bind_fake_client("rejected");
+
+    let fake_client = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        read_frame(&mut stream).await;
+
+        write_frame(
+            &mut stream,
+            2,
+            json!({"code": 4000, "message": "Invalid Client ID"}),
+        )
+        .await;
+    });
+
+    let error = RpcClient::connect_to(&path, Snowflake(1234))
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        RpcError::Closed {
+            code: 4000,
+            message: "Invalid Client ID".to_string()
+        }
+    );
+
+    fake_client.await.unwrap();
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
@@ -0,0 +1,105 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+#![cfg(any(feature = "sqlx-sqlite", feature = "sqlx-mysql"))]
+
+use chorus::types::{GuildMemberFlags, PermissionFlags, RelationshipType, Snowflake};
+
+#[cfg(feature = "sqlx-sqlite")]
+#[tokio::test]
+async fn sqlite_bitflags_roundtrip() {
+    let pool = sqlx::sqlite::SqlitePool::connect("sqlite::memory:")
+        .await
+        .unwrap();
+
+    let permissions = PermissionFlags::ADMINISTRATOR | PermissionFlags::MODERATE_MEMBERS;
+    let decoded: PermissionFlags = sqlx::query_scalar("SELECT ?")
+        .bind(permissions.clone())
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(decoded, permissions);
+
+    // The highest bit is stored as a negative number, but still reads back as the same flags
+    let all_bits = PermissionFlags::from_bits_retain(u64::MAX);
+    let stored: i64 = sqlx::query_scalar("SELECT ?")
+        .bind(all_bits)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(stored, -1);
+
+    let decoded: PermissionFlags = sqlx::query_scalar("SELECT ?")
+        .bind(-1_i64)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(decoded, PermissionFlags::all());
+
+    let flags = GuildMemberFlags::DID_REJOIN | GuildMemberFlags::BYPASSES_VERIFICATION;
+    let decoded: GuildMemberFlags = sqlx::query_scalar("SELECT ?")
+        .bind(flags)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(decoded, flags);
+}
+
+#[cfg(feature = "sqlx-sqlite")]
+#[tokio::test]
+async fn sqlite_snowflake_and_enum_roundtrip() {
+    let pool = sqlx::sqlite::SqlitePool::connect("sqlite::memory:")
+        .await
+        .unwrap();
+
+    let snowflake = Snowflake(1_234_567_890_123_456_789);
+    let decoded: Snowflake = sqlx::query_scalar("SELECT ?")
+        .bind(snowflake)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(decoded, snowflake);
+
+    let decoded: RelationshipType = sqlx::query_scalar("SELECT ?")
+        .bind(RelationshipType::Blocked)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(decoded, RelationshipType::Blocked);
+
+    let invalid: Result<RelationshipType, _> =
+        sqlx::query_scalar("SELECT 42").fetch_one(&pool).await;
+    assert!(invalid.is_err());
+}
+
+/// Fails to compile if a type can't be bound to or read from MySQL queries
+#[cfg(feature = "sqlx-mysql")]
+fn assert_mysql_type<T>() -> sqlx_mysql::MySqlTypeInfo
+where
+    T: sqlx::Type<sqlx_mysql::MySql>
+        + for<'q> sqlx::Encode<'q, sqlx_mysql::MySql>
+        + for<'r> sqlx::Decode<'r, sqlx_mysql::MySql>,
+{
+    T::type_info()
+}
+
+#[cfg(feature = "sqlx-mysql")]
+#[test]
+fn mysql_types_are_unsigned_integers() {
+    use sqlx::TypeInfo;
+
+    assert_eq!(assert_mysql_type::<Snowflake>().name(), "BIGINT UNSIGNED");
+    assert_eq!(
+        assert_mysql_type::<PermissionFlags>().name(),
+        "BIGINT UNSIGNED"
+    );
+    assert_eq!(
+        assert_mysql_type::<GuildMemberFlags>().name(),
+        "BIGINT UNSIGNED"
+    );
+    assert_eq!(
+        assert_mysql_type::<RelationshipType>().name(),
+        "TINYINT UNSIGNED"
+    );
+}
@@ -903,7 +903,7 @@ mod config {
 mod entities {
     use std::sync::{Arc, RwLock};
 
-    use chorus::types::{ApplicationFlags, ConfigEntity, Emoji, User};
+    use chorus::types::{ApplicationFlags, ConfigEntity, Emoji, PermissionFlags, User};
     use serde_json::json;
 
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
@@ -952,8 +952,106 @@ mod entities {
         assert_ne!(emoji, another_emoji);
     }
 
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn permission_flags() {
+        // Use soundboard, create guild expressions, send voice messages, send polls and one
+        // unknown flag
+        let permissions: PermissionFlags = "1153568017443979264".parse().unwrap();
+
+        assert_eq!(
+            permissions,
+            PermissionFlags::USE_SOUNDBOARD
+                | PermissionFlags::CREATE_GUILD_EXPRESSIONS
+                | PermissionFlags::SEND_VOICE_MESSAGES
+                | PermissionFlags::SEND_POLLS
+        );
+        assert_eq!(
+            serde_json::from_str::<PermissionFlags>("\"1153568017443979264\"").unwrap(),
+            permissions
+        );
+    }
+
+    mod channel {
+        use chorus::types::{
+            PermissionFlags, PermissionOverwrite, PermissionOverwriteBuilder,
+            PermissionOverwriteType, Snowflake,
+        };
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn permission_overwrite_builder() {
+            let overwrite = PermissionOverwrite::role(Snowflake(1))
+                .allow(PermissionFlags::VIEW_CHANNEL | PermissionFlags::SEND_MESSAGES)
+                .deny(PermissionFlags::SEND_MESSAGES | PermissionFlags::ADD_REACTIONS)
+                .build();
+
+            assert_eq!(overwrite.id, Snowflake(1));
+            assert_eq!(overwrite.overwrite_type, PermissionOverwriteType::Role);
+            assert_eq!(overwrite.allow, PermissionFlags::VIEW_CHANNEL);
+            assert_eq!(
+                overwrite.deny,
+                PermissionFlags::SEND_MESSAGES | PermissionFlags::ADD_REACTIONS
+            );
+
+            let overwrite = PermissionOverwriteBuilder::from(overwrite)
+                .inherit(PermissionFlags::ADD_REACTIONS | PermissionFlags::VIEW_CHANNEL)
+                .build();
+            assert!(overwrite.allow.is_empty());
+            assert_eq!(overwrite.deny, PermissionFlags::SEND_MESSAGES);
+
+            let overwrite = PermissionOverwrite::member(Snowflake(2)).build();
+            assert_eq!(overwrite.overwrite_type, PermissionOverwriteType::Member);
+            assert!(overwrite.allow.is_empty() && overwrite.deny.is_empty());
+        }
+    }
+
+    mod webhook {
+        use chorus::types::{FollowedChannel, Snowflake, Webhook, WebhookType};
+        use serde_json::json;
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn channel_follower_webhook() {
+            let webhook: Webhook = serde_json::from_value(json!({
+                "id": "10",
+                "type": 2,
+                "name": "Announcements",
+                "avatar": null,
+                "guild_id": "20",
+                "channel_id": "30",
+                "application_id": null,
+                "source_guild": {"id": "40", "name": "Source guild", "icon": null},
+                "source_channel": {"id": "50", "name": "announcements"}
+            }))
+            .unwrap();
+
+            assert_eq!(webhook.webhook_type, WebhookType::ChannelFollower);
+            assert!(webhook.token.is_empty());
+            assert_eq!(
+                webhook.source_channel.as_ref().unwrap().name,
+                "announcements"
+            );
+            assert_eq!(
+                webhook.followed_channel(),
+                Some(FollowedChannel {
+                    channel_id: Snowflake(50),
+                    webhook_id: Snowflake(10),
+                })
+            );
+
+            let incoming = Webhook {
+                webhook_type: WebhookType::Incoming,
+                ..webhook
+            };
+            assert_eq!(incoming.followed_channel(), None);
+            assert_eq!(serde_json::to_value(&incoming).unwrap()["type"], json!(1));
+        }
+    }
+
     mod guild {
-        use chorus::types::Guild;
+        use chorus::types::{Guild, GuildScheduledEventUser, Snowflake};
+        use serde_json::json;
 
         #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
         #[cfg_attr(not(target_arch = "wasm32"), test)]
@@ -966,10 +1064,481 @@ mod entities {
 
             assert_eq!(guild1, guild2);
         }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn guild_scheduled_event_user() {
+            let subscriber: GuildScheduledEventUser = serde_json::from_value(json!({
+                "guild_scheduled_event_id": "1",
+                "user": { "id": "2", "username": "user", "discriminator": "0" }
+            }))
+            .unwrap();
+
+            assert_eq!(subscriber.guild_scheduled_event_id, Snowflake(1));
+            assert_eq!(subscriber.user.id, Snowflake(2));
+            assert!(subscriber.member.is_none());
+        }
+    }
+
+    mod role {
+        use chorus::types::{RoleColors, RoleFlags, RoleObject};
+        use serde_json::json;
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn role_colors() {
+            let role: RoleObject = serde_json::from_value(json!({
+                "id": "1",
+                "name": "holo",
+                "color": 11127295,
+                "hoist": false,
+                "icon": null,
+                "unicode_emoji": null,
+                "position": 1,
+                "permissions": "0",
+                "managed": false,
+                "mentionable": false,
+                "colors": {
+                    "primary_color": 11127295,
+                    "secondary_color": 16759788,
+                    "tertiary_color": 16761760
+                },
+                "flags": 1
+            }))
+            .unwrap();
+
+            let colors = role.colors.unwrap();
+            assert_eq!(colors, RoleColors::HOLOGRAPHIC);
+            assert!(colors.is_gradient());
+            assert!(colors.is_holographic());
+            assert_eq!(role.flags, RoleFlags::IN_PROMPT);
+
+            // Servers which do not support role styles send neither field
+            let role: RoleObject = serde_json::from_value(json!({
+                "id": "1",
+                "name": "plain",
+                "color": 0,
+                "hoist": false,
+                "icon": null,
+                "unicode_emoji": null,
+                "position": 1,
+                "permissions": "0",
+                "managed": false,
+                "mentionable": false
+            }))
+            .unwrap();
+
+            assert!(role.colors.is_none());
+            assert!(role.flags.is_empty());
+            assert!(!RoleColors::gradient(1, 2).is_holographic());
+            assert!(!RoleColors::solid(1).is_gradient());
+        }
+    }
+
+    mod guild_onboarding {
+        use chorus::types::{GuildOnboarding, OnboardingMode, OnboardingPromptType, Snowflake};
+        use serde_json::json;
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn guild_onboarding() {
+            let onboarding: GuildOnboarding = serde_json::from_value(json!({
+                "guild_id": "1",
+                "prompts": [{
+                    "id": "2",
+                    "type": 1,
+                    "options": [{
+                        "id": "3",
+                        "channel_ids": ["4"],
+                        "role_ids": [],
+                        "emoji": {"id": null, "name": "👋", "animated": false},
+                        "title": "Say hi",
+                        "description": null
+                    }],
+                    "title": "What brings you here?",
+                    "single_select": true,
+                    "required": false,
+                    "in_onboarding": true
+                }],
+                "default_channel_ids": ["4", "5"],
+                "enabled": true,
+                "below_requirements": false,
+                "mode": 1
+            }))
+            .unwrap();
+
+            assert_eq!(onboarding.mode, OnboardingMode::OnboardingAdvanced);
+            let prompt = &onboarding.prompts[0];
+            assert_eq!(prompt.prompt_type, OnboardingPromptType::Dropdown);
+            assert_eq!(prompt.options[0].channel_ids, [Snowflake(4)]);
+            assert_eq!(prompt.options[0].emoji.as_ref().unwrap().name, "👋");
+
+            let serialized = serde_json::to_value(&onboarding).unwrap();
+            assert_eq!(serialized["prompts"][0]["type"], 1);
+            assert_eq!(serialized["mode"], 1);
+        }
+    }
+
+    mod member_verification {
+        use chorus::types::{
+            GuildJoinRequest, GuildJoinRequestStatus, MemberVerification,
+            MemberVerificationFieldType, MemberVerificationResponse, Snowflake,
+        };
+        use serde_json::json;
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn member_verification() {
+            let mut verification: MemberVerification = serde_json::from_value(json!({
+                "version": "2024-08-01T12:00:00.000000+00:00",
+                "form_fields": [
+                    {
+                        "field_type": "TERMS",
+                        "label": "Read and agree to the server rules",
+                        "values": ["Be nice"],
+                        "required": true,
+                        "description": null,
+                        "automations": null
+                    },
+                    {
+                        "field_type": "MULTIPLE_CHOICE",
+                        "label": "How did you find us?",
+                        "choices": ["A friend", "Discovery"],
+                        "required": false
+                    }
+                ],
+                "description": "A guild for testing"
+            }))
+            .unwrap();
+
+            assert!(verification.version.is_some());
+            assert!(verification.guild.is_none());
+            let fields = &verification.form_fields;
+            assert_eq!(fields[0].field_type, MemberVerificationFieldType::Terms);
+            assert_eq!(
+                fields[0].values.as_deref(),
+                Some(&["Be nice".to_string()][..])
+            );
+            assert_eq!(
+                fields[1].field_type,
+                MemberVerificationFieldType::MultipleChoice
+            );
+
+            verification.form_fields[0].response = Some(MemberVerificationResponse::Accepted(true));
+            verification.form_fields[1].response = Some(MemberVerificationResponse::Choice(1));
+
+            let serialized = serde_json::to_value(&verification.form_fields).unwrap();
+            assert_eq!(serialized[0]["response"], true);
+            assert_eq!(serialized[1]["response"], 1);
+            assert_eq!(serialized[1]["field_type"], "MULTIPLE_CHOICE");
+            assert!(serialized[1].get("values").is_none());
+
+            let response: MemberVerificationResponse =
+                serde_json::from_value(json!("Hello!")).unwrap();
+            assert_eq!(
+                response,
+                MemberVerificationResponse::Text("Hello!".to_string())
+            );
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn guild_join_request() {
+            let request: GuildJoinRequest = serde_json::from_value(json!({
+                "id": "1",
+                "join_request_id": "1",
+                "created_at": "2024-08-01T12:00:00.000000+00:00",
+                "application_status": "SUBMITTED",
+                "guild_id": "2",
+                "form_responses": [{
+                    "field_type": "TEXT_INPUT",
+                    "label": "Why do you want to join?",
+                    "response": "To test chorus",
+                    "required": true
+                }],
+                "last_seen": null,
+                "actioned_at": null,
+                "actioned_by_user": null,
+                "rejection_reason": null,
+                "user_id": "3",
+                "interview_channel_id": null
+            }))
+            .unwrap();
+
+            assert_eq!(
+                request.application_status,
+                GuildJoinRequestStatus::Submitted
+            );
+            assert_eq!(request.guild_id, Snowflake(2));
+            assert_eq!(
+                request.form_responses.unwrap()[0].response,
+                Some(MemberVerificationResponse::Text(
+                    "To test chorus".to_string()
+                ))
+            );
+        }
+    }
+
+    mod premium_subscription {
+        use chorus::types::{PremiumGuildSubscriptionSlot, Snowflake};
+        use serde_json::json;
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn premium_guild_subscription_slot() {
+            let slot: PremiumGuildSubscriptionSlot = serde_json::from_value(json!({
+                "id": "1",
+                "subscription_id": "2",
+                "premium_guild_subscription": {
+                    "id": "3",
+                    "guild_id": "4",
+                    "user_id": "5",
+                    "ended": false
+                },
+                "canceled": false,
+                "cooldown_ends_at": null
+            }))
+            .unwrap();
+
+            let subscription = slot.premium_guild_subscription.as_ref().unwrap();
+            assert_eq!(subscription.guild_id, Snowflake(4));
+            assert!(subscription.ends_at.is_none());
+            assert!(!slot.is_available());
+
+            let slot: PremiumGuildSubscriptionSlot = serde_json::from_value(json!({
+                "id": "1",
+                "subscription_id": "2",
+                "premium_guild_subscription": null,
+                "canceled": true,
+                "cooldown_ends_at": "2024-08-20T12:00:00+00:00"
+            }))
+            .unwrap();
+
+            assert!(slot.canceled);
+            assert!(slot.cooldown_ends_at.is_some());
+            assert!(slot.is_available());
+        }
+    }
+
+    mod ratelimits {
+        use std::time::Duration;
+
+        use chorus::types::{Limit, LimitType, Snowflake};
+
+        fn limit(remaining: u64, reset: i64) -> Limit {
+            Limit {
+                bucket: LimitType::Channel(Snowflake(1)),
+                limit: 5,
+                remaining,
+                reset: reset as u64,
+                window: 60,
+            }
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn limit_state() {
+            let now = chrono::Utc::now().timestamp();
+
+            let exhausted = limit(0, now + 30);
+            assert!(exhausted.is_exhausted());
+            assert!(exhausted.time_until_reset() > Duration::from_secs(29));
+            assert_eq!(exhausted.reset_at().timestamp(), now + 30);
+
+            assert!(!limit(1, now + 30).is_exhausted());
+
+            let replenishable = limit(0, now - 1);
+            assert!(!replenishable.is_exhausted());
+            assert_eq!(replenishable.time_until_reset(), Duration::ZERO);
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn instance_wide_limit_types() {
+            assert!(LimitType::Global.is_instance_wide());
+            assert!(LimitType::Ip.is_instance_wide());
+            assert!(LimitType::AuthLogin.is_instance_wide());
+            assert!(!LimitType::Error.is_instance_wide());
+            assert!(!LimitType::Guild(Snowflake(1)).is_instance_wide());
+        }
+    }
+
+    mod read_state {
+        use chorus::types::{ReadState, ReadStateType, Snowflake};
+        use serde_json::json;
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn read_state() {
+            let read_state: ReadState = serde_json::from_value(json!({
+                "entries": [
+                    {
+                        "id": "1",
+                        "last_message_id": "2",
+                        "last_pin_timestamp": null,
+                        "mention_count": 3,
+                        "flags": null,
+                        "last_viewed": null
+                    },
+                    {
+                        "id": "4",
+                        "read_state_type": 1,
+                        "last_acked_id": "5",
+                        "badge_count": 1,
+                        "last_message_id": null,
+                        "last_pin_timestamp": null,
+                        "mention_count": null,
+                        "flags": null,
+                        "last_viewed": null
+                    }
+                ],
+                "partial": false,
+                "version": 7
+            }))
+            .unwrap();
+
+            let channel = &read_state.entries[0];
+            assert_eq!(channel.read_state_type, ReadStateType::Channel);
+            assert_eq!(channel.last_message_id, Some(Snowflake(2)));
+            assert_eq!(channel.mention_count, Some(3));
+
+            let guild_events = &read_state.entries[1];
+            assert_eq!(guild_events.read_state_type, ReadStateType::GuildEvent);
+            assert_eq!(guild_events.last_acked_id, Some(Snowflake(5)));
+            assert_eq!(guild_events.badge_count, Some(1));
+        }
+    }
+
+    mod user_settings {
+        use chorus::types::{
+            CustomStatus, UserModifySettingsSchema, UserSettings, UserSettingsUpdate, UserStatus,
+            UserTheme,
+        };
+        use serde_json::json;
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn apply_update() {
+            let mut settings = UserSettings {
+                custom_status: Some(CustomStatus {
+                    emoji_id: None,
+                    emoji_name: None,
+                    expires_at: None,
+                    text: Some(String::from("busy")),
+                }),
+                ..Default::default()
+            };
+            let status = settings.status.clone();
+
+            let update: UserSettingsUpdate = serde_json::from_value(json!({
+                "theme": "light",
+                "status": "dnd",
+                "custom_status": null
+            }))
+            .unwrap();
+            settings.apply_update(&update.settings);
+
+            assert_eq!(settings.theme, UserTheme::Light);
+            assert_eq!(settings.custom_status, None);
+            // The status is updated in place
+            assert_eq!(*status.read().unwrap(), UserStatus::Dnd);
+            // Settings missing from the update are left unchanged
+            assert_eq!(settings.locale, "en-US");
+            assert!(settings.developer_mode);
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn modify_settings_schema() {
+            let schema = UserModifySettingsSchema {
+                developer_mode: Some(false),
+                custom_status: Some(None),
+                ..Default::default()
+            };
+            assert_eq!(
+                serde_json::to_value(schema).unwrap(),
+                json!({ "developer_mode": false, "custom_status": null })
+            );
+        }
+    }
+
+    mod user_guild_settings {
+        use chorus::types::{
+            HighlightLevel, ModifyUserGuildSettingsSchema, MuteConfig, Snowflake,
+            UserGuildSettingsUpdate, UserNotificationLevel,
+        };
+        use serde_json::json;
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn user_guild_settings_update() {
+            let update: UserGuildSettingsUpdate = serde_json::from_value(json!({
+                "version": 2,
+                "suppress_roles": false,
+                "suppress_everyone": true,
+                "notify_highlights": 1,
+                "muted": true,
+                "mute_scheduled_events": false,
+                "mute_config": { "end_time": null, "selected_time_window": -1 },
+                "mobile_push": true,
+                "message_notifications": 3,
+                "hide_muted_channels": false,
+                "guild_id": "1",
+                "flags": 0,
+                "channel_overrides": [{
+                    "muted": false,
+                    "mute_config": null,
+                    "message_notifications": 1,
+                    "flags": 4096,
+                    "collapsed": false,
+                    "channel_id": "2"
+                }]
+            }))
+            .unwrap();
+
+            let settings = update.settings;
+            assert_eq!(settings.guild_id, Some(Snowflake(1)));
+            assert_eq!(settings.notify_highlights, HighlightLevel::Disabled);
+            assert_eq!(
+                settings.message_notifications,
+                UserNotificationLevel::ParentDefault
+            );
+            assert_eq!(settings.mute_config.unwrap().selected_time_window, Some(-1));
+            assert_eq!(
+                settings.channel_overrides[0].message_notifications,
+                UserNotificationLevel::OnlyMentions
+            );
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn modify_user_guild_settings_schema() {
+            let schema = ModifyUserGuildSettingsSchema {
+                muted: Some(true),
+                mute_config: Some(MuteConfig {
+                    end_time: None,
+                    selected_time_window: Some(3600),
+                }),
+                ..Default::default()
+            };
+            assert_eq!(
+                serde_json::to_value(schema).unwrap(),
+                json!({
+                    "muted": true,
+                    "mute_config": { "end_time": null, "selected_time_window": 3600 }
+                })
+            );
+        }
     }
 
     mod message {
-        use chorus::types::{Message, Snowflake};
+        use chorus::errors::EmbedError;
+        use chorus::types::{
+            ActionRow, Button, ButtonStyle, Component, ComponentType, EmbedBuilder, Message, Poll,
+            PollAnswer, PollCreateSchema, PollLayoutType, PollMedia, SelectMenu, SelectOption,
+            Snowflake,
+        };
+        use serde_json::json;
 
         #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
         #[cfg_attr(not(target_arch = "wasm32"), test)]
@@ -984,5 +1553,1129 @@ mod entities {
 
             assert_eq!(message1, message2);
         }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn poll() {
+            let poll: Poll = serde_json::from_value(json!({
+                "question": { "text": "Tabs or spaces?" },
+                "answers": [
+                    { "answer_id": 1, "poll_media": { "text": "Tabs" } },
+                    { "answer_id": 2, "poll_media": { "text": "Spaces", "emoji": { "id": null, "name": "🚀" } } }
+                ],
+                "expiry": "2024-05-01T12:00:00+00:00",
+                "allow_multiselect": false,
+                "layout_type": 1,
+                "results": {
+                    "is_finalized": false,
+                    "answer_counts": [{ "id": 2, "count": 3, "me_voted": true }]
+                }
+            }))
+            .unwrap();
+
+            assert_eq!(poll.question.text.as_deref(), Some("Tabs or spaces?"));
+            assert_eq!(poll.answers[1].answer_id, Some(2));
+            assert_eq!(poll.layout_type, PollLayoutType::Default);
+            assert_eq!(poll.results.unwrap().answer_counts[0].count, 3);
+
+            let schema = PollCreateSchema {
+                question: PollMedia {
+                    text: Some("Tabs or spaces?".to_string()),
+                    emoji: None,
+                },
+                answers: vec![PollAnswer {
+                    answer_id: None,
+                    poll_media: PollMedia {
+                        text: Some("Tabs".to_string()),
+                        emoji: None,
+                    },
+                }],
+                duration: Some(24),
+                ..Default::default()
+            };
+            assert_eq!(
+                serde_json::to_value(schema).unwrap(),
+                json!({
+                    "question": { "text": "Tabs or spaces?" },
+                    "answers": [{ "poll_media": { "text": "Tabs" } }],
+                    "duration": 24
+                })
+            );
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn embed_builder() {
+            let embed = EmbedBuilder::new()
+                .title("Title")
+                .description("Description")
+                .color(0xFF0000)
+                .field("Name", "Value", true)
+                .build()
+                .unwrap();
+
+            let value = serde_json::to_value(embed).unwrap();
+            assert_eq!(value["type"], "rich");
+            assert_eq!(value["color"], 0xFF0000);
+            assert_eq!(value["fields"][0]["name"], "Name");
+
+            assert_eq!(
+                EmbedBuilder::new().title(&"a".repeat(257)).build(),
+                Err(EmbedError::TooLong {
+                    field: "title".to_string(),
+                    length: 257,
+                    max: 256
+                })
+            );
+
+            let mut too_many_fields = EmbedBuilder::new();
+            for _ in 0..26 {
+                too_many_fields = too_many_fields.field("Name", "Value", false);
+            }
+            assert_eq!(
+                too_many_fields.build(),
+                Err(EmbedError::TooManyFields { count: 26, max: 25 })
+            );
+
+            let too_long = EmbedBuilder::new()
+                .description(&"a".repeat(4096))
+                .field("Name", &"a".repeat(1024), false)
+                .field("Name", &"a".repeat(1024), false);
+            assert!(matches!(
+                too_long.build(),
+                Err(EmbedError::TotalTooLong { max: 6000, .. })
+            ));
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn components() {
+            let row = Component::from(ActionRow::new(vec![
+                Button {
+                    style: ButtonStyle::Danger,
+                    label: Some("Delete".to_string()),
+                    custom_id: Some("delete".to_string()),
+                    ..Default::default()
+                }
+                .into(),
+                Component::StringSelect(SelectMenu {
+                    custom_id: "pick".to_string(),
+                    options: vec![SelectOption {
+                        label: "One".to_string(),
+                        value: "1".to_string(),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }),
+            ]));
+
+            let value = serde_json::to_value(&row).unwrap();
+            assert_eq!(value["type"], json!(1));
+            assert_eq!(value["components"][0]["type"], json!(2));
+            assert_eq!(value["components"][0]["style"], json!(4));
+            assert_eq!(value["components"][1]["type"], json!(3));
+            assert_eq!(value["components"][1]["options"][0]["value"], json!("1"));
+
+            let deserialized: Component = serde_json::from_value(value).unwrap();
+            assert_eq!(deserialized, row);
+
+            let user_select: Component =
+                serde_json::from_value(json!({"type": 5, "custom_id": "user"})).unwrap();
+            assert_eq!(user_select.component_type(), ComponentType::UserSelect);
+
+            assert!(serde_json::from_value::<Component>(json!({"custom_id": "x"})).is_err());
+        }
+    }
+}
+
+mod template {
+    use chorus::types::{
+        ChannelType, GuildCreateSchema, GuildTemplate, PermissionFlags, Snowflake,
+        TemplateSourceGuild,
+    };
+    use serde_json::json;
+
+    fn serialized_source_guild_json() -> serde_json::Value {
+        json!({
+            "name": "Template",
+            "description": null,
+            "region": "us-west",
+            "verification_level": 0,
+            "default_message_notifications": 0,
+            "explicit_content_filter": 0,
+            "preferred_locale": "en-US",
+            "afk_timeout": 300,
+            "roles": [
+                { "id": 0, "name": "@everyone", "permissions": "104324161", "color": 0, "hoist": false, "mentionable": false },
+                { "id": 1, "name": "Moderator", "permissions": "8", "color": 3447003, "hoist": true, "mentionable": true }
+            ],
+            "channels": [
+                { "id": 2, "type": 4, "name": "Text Channels", "position": 0, "parent_id": null, "permission_overwrites": [] },
+                { "id": 4, "type": 0, "name": "rules", "position": 1, "parent_id": 2, "permission_overwrites": [
+                    { "id": 0, "type": 0, "allow": "0", "deny": "2048" }
+                ] },
+                { "id": 3, "type": 0, "name": "general", "position": 0, "parent_id": 2, "permission_overwrites": [] }
+            ],
+            "afk_channel_id": null,
+            "system_channel_id": "3",
+            "system_channel_flags": 0,
+            "icon_hash": null
+        })
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn serialized_source_guild() {
+        let json = serialized_source_guild_json();
+        // Responses are deserialized from their text, which the string-or-number ids rely on
+        let guild: TemplateSourceGuild = serde_json::from_str(&json.to_string()).unwrap();
+
+        assert_eq!(
+            guild.role(1).unwrap().permissions,
+            PermissionFlags::ADMINISTRATOR
+        );
+        assert_eq!(
+            guild.channel(2).unwrap().channel_type,
+            ChannelType::GuildCategory
+        );
+        assert_eq!(guild.system_channel_id, Some(3));
+
+        let children = guild.category_children(2);
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[0].name.as_deref(), Some("general"));
+        assert_eq!(
+            children[1].permission_overwrites[0].deny,
+            PermissionFlags::SEND_MESSAGES
+        );
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn guild_template_without_creator() {
+        // Spacebar does not include the creator and source guild
+        let json = json!({
+            "code": "hgM48av5Q69A",
+            "name": "Friends & Family",
+            "description": null,
+            "usage_count": 1,
+            "creator_id": "132608939377573888",
+            "created_at": "2024-08-01T12:00:00+00:00",
+            "updated_at": "2024-08-01T12:00:00+00:00",
+            "source_guild_id": "1150000000000000000",
+            "serialized_source_guild": serialized_source_guild_json(),
+            "is_dirty": null
+        });
+        let template: GuildTemplate = serde_json::from_str(&json.to_string()).unwrap();
+
+        assert_eq!(template.code, "hgM48av5Q69A");
+        assert_eq!(template.source_guild_id, Snowflake(1150000000000000000));
+        assert_eq!(template.serialized_source_guild.channels.len(), 3);
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn guild_create_schema_from_template_source() {
+        let source: TemplateSourceGuild =
+            serde_json::from_str(&serialized_source_guild_json().to_string()).unwrap();
+        let schema = GuildCreateSchema::from_template_source(&source);
+        assert_eq!(schema.system_channel_id.as_deref(), Some("3"));
+
+        let json = serde_json::to_value(&schema).unwrap();
+        assert_eq!(json["name"], "Template");
+        // Placeholder ids are sent as numbers, with @everyone as the first role
+        assert_eq!(json["roles"][0]["id"], 0);
+        assert_eq!(json["roles"][0]["name"], "@everyone");
+        assert_eq!(json["roles"][1]["permissions"], "8");
+        assert_eq!(json["channels"][1]["id"], 4);
+        assert_eq!(json["channels"][1]["parent_id"], 2);
+        assert_eq!(
+            json["channels"][1]["permission_overwrites"][0]["deny"],
+            "2048"
+        );
+    }
+}
+
+mod interfaces {
+    mod interaction {
+        use chorus::types::{ComponentType, Interaction, InteractionType};
+        use serde_json::json;
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn message_component_data() {
+            let interaction: Interaction = serde_json::from_value(json!({
+                "id": "1",
+                "type": 3,
+                "data": {"custom_id": "pick", "component_type": 3, "values": ["1", "2"]},
+                "guild_id": "2",
+                "channel_id": "3",
+                "member_id": "4",
+                "token": "token",
+                "version": 1
+            }))
+            .unwrap();
+
+            assert_eq!(interaction.r#type, InteractionType::MessageComponent);
+            assert!(interaction.modal_submit_data().is_none());
+
+            let data = interaction.message_component_data().unwrap();
+            assert_eq!(data.custom_id, "pick");
+            assert_eq!(data.component_type, ComponentType::StringSelect);
+            assert_eq!(data.values, vec!["1", "2"]);
+        }
+    }
+
+    mod activity {
+        use chorus::types::{ActivityBuilder, ActivityType, PresenceBuilder, UserStatus};
+        use serde_json::json;
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn presence_builder() {
+            let start = chrono::DateTime::from_timestamp_millis(1_700_000_000_000).unwrap();
+            let activity = ActivityBuilder::new("Chorus", ActivityType::Listening)
+                .details("details")
+                .start(start)
+                .large_image("image", Some("text"))
+                .party("party", 1, 4)
+                .button("one", "https://example.com/1")
+                .button("two", "https://example.com/2")
+                .button("three", "https://example.com/3")
+                .build();
+
+            let presence = PresenceBuilder::new()
+                .status(UserStatus::Dnd)
+                .activity(activity)
+                .build();
+
+            assert_eq!(presence.status, UserStatus::Dnd);
+            assert_eq!(presence.activities.len(), 1);
+
+            let activity = serde_json::to_value(&presence.activities[0]).unwrap();
+            assert_eq!(activity["type"], json!(2));
+            assert_eq!(
+                activity["timestamps"]["start"],
+                json!(1_700_000_000_000_i64)
+            );
+            assert_eq!(activity["assets"]["large_text"], json!("text"));
+            assert_eq!(activity["party"]["size"], json!([1, 4]));
+            assert_eq!(activity["buttons"].as_array().unwrap().len(), 2);
+        }
+    }
+}
+
+mod events {
+    mod voice {
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+        use chorus::types::{
+            DaveMlsMessage, DavePrepareTransition, SessionDescription, Snowflake, VoiceReady,
+            VoiceServerUpdate, VOICE_DAVE_MLS_EXTERNAL_SENDER, VOICE_DAVE_MLS_KEY_PACKAGE,
+        };
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn voice_server_update_server_id() {
+            let guild_call = VoiceServerUpdate {
+                guild_id: Some(Snowflake(1)),
+                channel_id: None,
+                ..Default::default()
+            };
+            assert_eq!(guild_call.server_id(), Some(Snowflake(1)));
+            assert!(!guild_call.is_private_call());
+
+            let private_call = VoiceServerUpdate {
+                guild_id: None,
+                channel_id: Some(Snowflake(2)),
+                ..Default::default()
+            };
+            assert_eq!(private_call.server_id(), Some(Snowflake(2)));
+            assert!(private_call.is_private_call());
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn voice_ready_ip_address() {
+            let ready: VoiceReady = serde_json::from_str(
+                r#"{"ssrc": 1, "ip": "127.0.0.1", "port": 50000, "modes": []}"#,
+            )
+            .unwrap();
+            assert_eq!(ready.ip, IpAddr::V4(Ipv4Addr::LOCALHOST));
+
+            let ready: VoiceReady =
+                serde_json::from_str(r#"{"ssrc": 1, "ip": "::1", "port": 50000, "modes": []}"#)
+                    .unwrap();
+            assert_eq!(ready.ip, IpAddr::V6(Ipv6Addr::LOCALHOST));
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn dave_mls_message_framing() {
+            let received =
+                DaveMlsMessage::from_server_bytes(&[0x01, 0x02, 25, 0xaa, 0xbb]).unwrap();
+            assert_eq!(
+                received,
+                DaveMlsMessage {
+                    sequence_number: Some(0x0102),
+                    op_code: VOICE_DAVE_MLS_EXTERNAL_SENDER,
+                    payload: vec![0xaa, 0xbb],
+                }
+            );
+            assert!(DaveMlsMessage::from_server_bytes(&[0x01, 0x02]).is_none());
+
+            let sent = DaveMlsMessage {
+                sequence_number: None,
+                op_code: VOICE_DAVE_MLS_KEY_PACKAGE,
+                payload: vec![0xcc],
+            };
+            assert_eq!(sent.to_client_bytes(), vec![26, 0xcc]);
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn dave_payloads() {
+            let transition: DavePrepareTransition =
+                serde_json::from_str(r#"{"protocol_version": 0, "transition_id": 5}"#).unwrap();
+            assert_eq!(transition.protocol_version, 0);
+            assert_eq!(transition.transition_id, 5);
+
+            let description: SessionDescription = serde_json::from_str(
+                r#"{"audio_codec": "opus", "video_codec": "VP8", "media_session_id": "a", "mode": "xsalsa20_poly1305", "secret_key": [0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0], "keyframe_interval": null, "dave_protocol_version": 1}"#,
+            )
+            .unwrap();
+            assert_eq!(description.dave_protocol_version, Some(1));
+        }
+    }
+
+    mod guild {
+        use chorus::types::{
+            GuildApplicationCommandIndexUpdate, GuildOnboardingPromptCreate, Snowflake,
+        };
+        use serde_json::json;
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn application_command_index_update() {
+            let event: GuildApplicationCommandIndexUpdate = serde_json::from_str(
+                &json!({
+                    "guild_id": "1",
+                    "application_command_counts": { "1": 4, "3": 1 },
+                    "version": "2"
+                })
+                .to_string(),
+            )
+            .unwrap();
+            assert_eq!(event.guild_id, Snowflake(1));
+            assert_eq!(event.application_command_counts.unwrap()[&1], 4);
+            assert_eq!(event.version, Some(Snowflake(2)));
+
+            // Servers may only send the guild id
+            let event: GuildApplicationCommandIndexUpdate =
+                serde_json::from_str(r#"{"guild_id": "1"}"#).unwrap();
+            assert_eq!(event.application_command_counts, None);
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn onboarding_prompt_create() {
+            let event: GuildOnboardingPromptCreate = serde_json::from_str(
+                &json!({ "guild_id": "1", "id": "2", "title": "Pick your roles" }).to_string(),
+            )
+            .unwrap();
+            assert_eq!(event.guild_id, Snowflake(1));
+            assert_eq!(event.prompt["title"], "Pick your roles");
+        }
+    }
+
+    mod member_list {
+        use chorus::types::{GuildMemberListUpdate, MemberList, MemberListItem, Snowflake};
+        use serde_json::{json, Value};
+
+        fn member(id: u64) -> Value {
+            json!({
+                "member": {
+                    "user": { "id": id.to_string(), "username": "user" },
+                    "nick": null,
+                    "avatar": null,
+                    "roles": [],
+                    "joined_at": "2024-08-20T12:00:00+00:00",
+                    "premium_since": null,
+                    "deaf": false,
+                    "mute": false
+                }
+            })
+        }
+
+        fn update(ops: Value) -> GuildMemberListUpdate {
+            serde_json::from_value(json!({
+                "guild_id": "1",
+                "id": "everyone",
+                "member_count": 3,
+                "online_count": 3,
+                "groups": [{ "id": "online", "count": 3 }],
+                "ops": ops
+            }))
+            .unwrap()
+        }
+
+        fn member_ids(list: &MemberList) -> Vec<u64> {
+            list.members()
+                .map(|member| member.member.user.as_ref().unwrap().read().unwrap().id.0)
+                .collect()
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn apply_operations() {
+            let mut list = MemberList::new(Snowflake(1));
+
+            list.apply(&update(json!([{
+                "op": "SYNC",
+                "range": [0, 99],
+                "items": [{ "group": { "id": "online", "count": 3 } }, member(2), member(3)]
+            }])));
+            assert_eq!(list.id.as_deref(), Some("everyone"));
+            assert_eq!(list.items.len(), 3);
+            assert!(matches!(list.items[0], Some(MemberListItem::Group(_))));
+            assert_eq!(member_ids(&list), vec![2, 3]);
+
+            list.apply(&update(json!([
+                { "op": "INSERT", "index": 1, "item": member(4) },
+                { "op": "DELETE", "index": 3 },
+                { "op": "UPDATE", "index": 2, "item": member(5) }
+            ])));
+            assert_eq!(member_ids(&list), vec![4, 5]);
+
+            list.apply(&update(json!([{ "op": "INVALIDATE", "range": [0, 99] }])));
+            assert!(list.items.is_empty());
+
+            // Updates for other guilds are ignored
+            let mut other_guild =
+                update(json!([{ "op": "INSERT", "index": 0, "item": member(6) }]));
+            other_guild.guild_id = Snowflake(2);
+            list.apply(&other_guild);
+            assert!(list.items.is_empty());
+        }
+    }
+
+    mod message {
+        use chorus::types::TypingStartEvent;
+        use serde_json::json;
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn typing_start_event() {
+            let event: TypingStartEvent = serde_json::from_value(json!({
+                "channel_id": "1",
+                "guild_id": "2",
+                "user_id": "3",
+                "timestamp": 1_700_000_000,
+                "member": {
+                    "user": { "id": "3", "username": "xenia" },
+                    "nick": null,
+                    "roles": [],
+                    "joined_at": "2023-11-14T22:13:20Z",
+                    "deaf": false,
+                    "mute": false
+                }
+            }))
+            .unwrap();
+
+            assert_eq!(event.started_at().unwrap().timestamp(), 1_700_000_000);
+            assert_eq!(event.display_name(), Some("xenia".to_string()));
+        }
+    }
+}
+
+mod schema {
+    mod channel {
+        use chorus::types::{ModifyChannelPositionsSchema, Snowflake};
+        use serde_json::json;
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn modify_channel_positions_schema() {
+            let unchanged_parent = ModifyChannelPositionsSchema {
+                id: Snowflake(1),
+                position: Some(2),
+                ..Default::default()
+            };
+            assert_eq!(
+                serde_json::to_value(unchanged_parent).unwrap(),
+                json!({ "id": "1", "position": 2 })
+            );
+
+            let removed_parent = ModifyChannelPositionsSchema {
+                id: Snowflake(1),
+                parent_id: Some(None),
+                ..Default::default()
+            };
+            assert_eq!(
+                serde_json::to_value(removed_parent).unwrap(),
+                json!({ "id": "1", "parent_id": null })
+            );
+        }
+    }
+
+    mod gif {
+        use chorus::types::{GifMediaFormat, GifSearchQuery, GifTrendingQuery};
+        use serde_json::json;
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn gif_queries() {
+            let query = GifSearchQuery {
+                media_format: Some(GifMediaFormat::LoopedMp4),
+                limit: Some(10),
+                ..GifSearchQuery::new("cat")
+            };
+            assert_eq!(
+                serde_json::to_value(query).unwrap(),
+                json!({ "q": "cat", "media_format": "loopedmp4", "limit": 10 })
+            );
+
+            assert_eq!(
+                serde_json::to_value(GifTrendingQuery::default()).unwrap(),
+                json!({})
+            );
+            assert_eq!(
+                serde_json::to_value(GifMediaFormat::TinyWebm).unwrap(),
+                json!("tinywebm")
+            );
+        }
+    }
+
+    mod guild {
+        use chorus::types::{
+            GuildScheduledEventUsersQuery, GuildWidget, GuildWidgetImageStyle, Snowflake,
+            UserStatus,
+        };
+        use serde_json::json;
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn guild_scheduled_event_users_query() {
+            let query = GuildScheduledEventUsersQuery {
+                limit: Some(100),
+                after: Some(Snowflake(1)),
+                ..Default::default()
+            };
+            assert_eq!(
+                serde_json::to_value(query).unwrap(),
+                json!({ "limit": 100, "after": "1" })
+            );
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn guild_widget() {
+            let widget: GuildWidget = serde_json::from_str(
+                &json!({
+                    "id": "1",
+                    "name": "Test guild",
+                    "instant_invite": null,
+                    "channels": [{ "id": "2", "name": "General", "position": 0 }],
+                    "members": [{
+                        "id": "0",
+                        "username": "user",
+                        "discriminator": "0000",
+                        "avatar": null,
+                        "status": "online",
+                        "avatar_url": "https://cdn.example.com/widget-avatars/abc"
+                    }],
+                    "presence_count": 1
+                })
+                .to_string(),
+            )
+            .unwrap();
+            assert_eq!(widget.id, Snowflake(1));
+            assert_eq!(widget.channels[0].id, Snowflake(2));
+            assert_eq!(widget.members[0].status, UserStatus::Online);
+            assert_eq!(widget.members[0].channel_id, None);
+
+            assert_eq!(GuildWidgetImageStyle::Banner2.to_string(), "banner2");
+            assert_eq!(
+                serde_json::to_value(GuildWidgetImageStyle::default()).unwrap(),
+                json!("shield")
+            );
+        }
+    }
+
+    mod message {
+        use chorus::types::{
+            AllowedMentionType, AllowedMentions, BulkAckEntry, BulkAckSchema,
+            DeleteReadStateSchema, MessageAck, MessageAckResponse, ReadStateType, Snowflake,
+        };
+        use serde_json::json;
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn message_ack() {
+            assert_eq!(
+                serde_json::to_value(MessageAck::default()).unwrap(),
+                json!({ "token": null })
+            );
+            assert_eq!(
+                serde_json::to_value(MessageAck::manual(Some("token".to_string()), 2)).unwrap(),
+                json!({ "token": "token", "manual": true, "mention_count": 2 })
+            );
+
+            let response: MessageAckResponse =
+                serde_json::from_value(json!({ "token": "new" })).unwrap();
+            assert_eq!(response.token.as_deref(), Some("new"));
+            let response: MessageAckResponse = serde_json::from_value(json!({})).unwrap();
+            assert_eq!(response.token, None);
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn read_state_schemas() {
+            let schema = BulkAckSchema {
+                read_states: vec![
+                    BulkAckEntry {
+                        channel_id: Snowflake(1),
+                        message_id: Snowflake(2),
+                        read_state_type: None,
+                    },
+                    BulkAckEntry {
+                        channel_id: Snowflake(3),
+                        message_id: Snowflake(4),
+                        read_state_type: Some(ReadStateType::GuildEvent),
+                    },
+                ],
+            };
+            assert_eq!(
+                serde_json::to_value(schema).unwrap(),
+                json!({ "read_states": [
+                    { "channel_id": "1", "message_id": "2" },
+                    { "channel_id": "3", "message_id": "4", "read_state_type": 1 }
+                ] })
+            );
+
+            assert_eq!(
+                serde_json::to_value(DeleteReadStateSchema::default()).unwrap(),
+                json!({})
+            );
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn allowed_mentions() {
+            assert_eq!(
+                serde_json::to_value(AllowedMentions::none()).unwrap(),
+                json!({ "parse": [], "replied_user": false })
+            );
+
+            let allowed_mentions = AllowedMentions::all_except_everyone().user(Snowflake(1));
+            assert_eq!(allowed_mentions.parse, [AllowedMentionType::Roles]);
+            assert!(allowed_mentions.is_valid());
+            assert_eq!(
+                serde_json::to_value(&allowed_mentions).unwrap(),
+                json!({ "parse": ["roles"], "users": ["1"], "replied_user": true })
+            );
+
+            let conflicting = AllowedMentions {
+                parse: vec![AllowedMentionType::Users],
+                users: vec![Snowflake(1)],
+                ..Default::default()
+            };
+            assert!(!conflicting.is_valid());
+        }
+    }
+}
+
+mod utils {
+    use chorus::types::split_message_content;
+
+    mod timestamp {
+        use chorus::types::{FormattedTimestamp, FormattedTimestampError, TimestampStyle};
+        use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn format_timestamps() {
+            let time = DateTime::parse_from_rfc3339("2021-04-20T23:20:30.750+02:00").unwrap();
+
+            let timestamp = FormattedTimestamp::from(time);
+            assert_eq!(timestamp.to_string(), "<t:1618953630:f>");
+            assert_eq!(
+                timestamp.timestamp,
+                DateTime::<Utc>::from_timestamp(1618953630, 0).unwrap()
+            );
+            assert_eq!(
+                timestamp.with_style(TimestampStyle::LongTime).to_string(),
+                "<t:1618953630:T>"
+            );
+
+            let naive = NaiveDate::from_ymd_opt(1969, 12, 31)
+                .unwrap()
+                .and_hms_opt(23, 59, 0)
+                .unwrap();
+            assert_eq!(
+                FormattedTimestamp::new(naive.and_utc(), TimestampStyle::Relative).to_string(),
+                "<t:-60:R>"
+            );
+            assert_eq!(
+                FormattedTimestamp::from(naive),
+                FormattedTimestamp::from(
+                    naive
+                        .and_utc()
+                        .with_timezone(&FixedOffset::east_opt(3600).unwrap())
+                )
+            );
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn parse_timestamps() {
+            let parsed: FormattedTimestamp = "<t:1618953630:d>".parse().unwrap();
+            assert_eq!(parsed.timestamp.timestamp(), 1618953630);
+            assert_eq!(parsed.style, TimestampStyle::ShortDate);
+
+            // The style is optional
+            let parsed: FormattedTimestamp = "<t:-60>".parse().unwrap();
+            assert_eq!(parsed.timestamp.timestamp(), -60);
+            assert_eq!(parsed.style, TimestampStyle::ShortDateTime);
+
+            for style in ['t', 'T', 'd', 'D', 'f', 'F', 'R'] {
+                let markup = format!("<t:0:{style}>");
+                assert_eq!(
+                    markup.parse::<FormattedTimestamp>().unwrap().to_string(),
+                    markup
+                );
+            }
+
+            assert_eq!(
+                "<t:1618953630:x>".parse::<FormattedTimestamp>(),
+                Err(FormattedTimestampError::InvalidStyle {
+                    style: "x".to_string()
+                })
+            );
+            for invalid in ["1618953630", "<t:>", "<t:+5:R>", "<t:abc>", "<t:1:R"] {
+                assert_eq!(
+                    invalid.parse::<FormattedTimestamp>(),
+                    Err(FormattedTimestampError::InvalidFormat {
+                        timestamp: invalid.to_string()
+                    })
+                );
+            }
+            assert_eq!(
+                FormattedTimestamp::try_from(format!("<t:{}>", i64::MAX).as_str()),
+                Err(FormattedTimestampError::OutOfRange { seconds: i64::MAX })
+            );
+        }
+    }
+
+    mod markdown {
+        use chorus::types::{
+            parse_markdown, resolve_mentions, Channel, FormattedTimestamp, Guild, IntoShared,
+            MarkdownToken, MentionResolver, Message, PartialEmoji, RoleObject, Snowflake,
+            TimestampStyle, User,
+        };
+        use chrono::DateTime;
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn parse_mentions_emojis_and_timestamps() {
+            let tokens =
+                parse_markdown("hi <@!1> and <@&2>, see <#3> <a:wave:4> <t:1618953630:R> @here");
+
+            assert_eq!(
+                tokens,
+                [
+                    MarkdownToken::Text("hi ".to_string()),
+                    MarkdownToken::UserMention(Snowflake(1)),
+                    MarkdownToken::Text(" and ".to_string()),
+                    MarkdownToken::RoleMention(Snowflake(2)),
+                    MarkdownToken::Text(", see ".to_string()),
+                    MarkdownToken::ChannelMention(Snowflake(3)),
+                    MarkdownToken::Text(" ".to_string()),
+                    MarkdownToken::CustomEmoji(PartialEmoji {
+                        id: Some(Snowflake(4)),
+                        name: "wave".to_string(),
+                        animated: true,
+                    }),
+                    MarkdownToken::Text(" ".to_string()),
+                    MarkdownToken::Timestamp(FormattedTimestamp::new(
+                        DateTime::from_timestamp(1618953630, 0).unwrap(),
+                        TimestampStyle::Relative,
+                    )),
+                    MarkdownToken::Text(" ".to_string()),
+                    MarkdownToken::HereMention,
+                ]
+            );
+
+            // Malformed tokens stay text
+            assert_eq!(
+                parse_markdown("<@99999999999999999999> <t:1:x>"),
+                [MarkdownToken::Text(
+                    "<@99999999999999999999> <t:1:x>".to_string()
+                )]
+            );
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn parse_code() {
+            let tokens = parse_markdown("`<@1>` then\n```rust\nlet a = \"<#2>\";\n``` and ``` `x");
+
+            assert_eq!(
+                tokens,
+                [
+                    MarkdownToken::InlineCode("<@1>".to_string()),
+                    MarkdownToken::Text(" then\n".to_string()),
+                    MarkdownToken::CodeBlock {
+                        language: Some("rust".to_string()),
+                        code: "let a = \"<#2>\";\n".to_string(),
+                    },
+                    MarkdownToken::Text(" and ``` `x".to_string()),
+                ]
+            );
+
+            assert_eq!(
+                parse_markdown("```@everyone```"),
+                [MarkdownToken::CodeBlock {
+                    language: None,
+                    code: "@everyone".to_string(),
+                }]
+            );
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn resolve_mentions_against_entities() {
+            let guild = Guild {
+                roles: vec![RoleObject {
+                    id: Snowflake(2),
+                    name: "mods".to_string(),
+                    ..Default::default()
+                }
+                .into_shared()],
+                channels: vec![Channel {
+                    id: Snowflake(3),
+                    name: Some("general".to_string()),
+                    ..Default::default()
+                }
+                .into_shared()],
+                ..Default::default()
+            };
+            let message: Message = serde_json::from_value(serde_json::json!({
+                "id": "10",
+                "channel_id": "3",
+                "content": "",
+                "timestamp": "2021-04-20T21:20:30Z",
+                "tts": false,
+                "mention_everyone": false,
+                "mentions": [serde_json::to_value(User {
+                    id: Snowflake(1),
+                    username: "alice".to_string(),
+                    ..Default::default()
+                })
+                .unwrap()],
+                "mention_channels": [{"id": "5", "guild_id": "6", "type": 0, "name": "other"}],
+                "attachments": [],
+                "pinned": false,
+                "type": 0
+            }))
+            .unwrap();
+
+            let resolvers: &[&dyn MentionResolver] = &[&message, &guild];
+            assert_eq!(
+                resolve_mentions("<@1> <@&2> in <#3>, <#5> and `<#3>` <#4>", resolvers),
+                "@alice @mods in #general, #other and `<#3>` <#4>"
+            );
+            assert_eq!(guild.user_name(Snowflake(1)), None);
+        }
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn split_message_content_boundaries() {
+        assert_eq!(split_message_content("short", 10), ["short"]);
+
+        // Lines are kept whole where possible, then words
+        assert_eq!(
+            split_message_content("first line\nsecond line", 15),
+            ["first line\n", "second line"]
+        );
+        assert_eq!(
+            split_message_content("one two three four", 9),
+            ["one two ", "three ", "four"]
+        );
+        assert_eq!(
+            split_message_content("abcdefghij", 4),
+            ["abcd", "efgh", "ij"]
+        );
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn split_message_content_code_blocks() {
+        let content = "text\n```rust\nlet a = 1;\nlet b = 2;\n```\nafter";
+        let chunks = split_message_content(content, 24);
+
+        assert_eq!(
+            chunks,
+            [
+                "text\n",
+                "```rust\nlet a = 1;\n```",
+                "```rust\nlet b = 2;\n```\n",
+                "after"
+            ]
+        );
+        assert!(chunks.iter().all(|chunk| chunk.chars().count() <= 24));
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn split_message_content_small_limit() {
+        assert_eq!(
+            split_message_content("abcdefghijkl", 5),
+            ["abcde", "fghij", "kl"]
+        );
+
+        // The code fences alone are longer than the limit
+        let content = "text\n```rust\nlet a = 1;\n```\nafter";
+        let chunks = split_message_content(content, 5);
+
+        assert!(!chunks.is_empty());
+        assert!(chunks
+            .iter()
+            .all(|chunk| !chunk.is_empty() && chunk.chars().count() <= 5));
+    }
+}
+
+mod errors {
+    use chorus::errors::{ApiError, ChorusError, GatewayError};
+    use chorus::types::{CloseCode, JsonErrorCode, SessionRecovery};
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn json_error_code_roundtrip() {
+        assert_eq!(
+            JsonErrorCode::from(50013),
+            JsonErrorCode::MissingPermissions
+        );
+        assert_eq!(u32::from(JsonErrorCode::UnknownChannel), 10003);
+        assert_eq!(JsonErrorCode::from(123456), JsonErrorCode::Other(123456));
+        assert_eq!(u32::from(JsonErrorCode::Other(123456)), 123456);
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn api_error_from_body() {
+        let body = r#"{"code": 50013, "message": "Missing Permissions"}"#;
+        let error = ApiError::from_body(403, body).unwrap();
+        assert_eq!(error.status, 403);
+        assert_eq!(error.code, JsonErrorCode::MissingPermissions);
+        assert_eq!(error.message, "Missing Permissions");
+        assert!(error.field_errors.is_empty());
+
+        assert!(ApiError::from_body(404, "<h1>Not Found</h1>").is_none());
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn unknown_route_errors() {
+        let unknown_route = ApiError::from_body(404, r#"{"code": 0, "message": "404: Not Found"}"#);
+        assert!(ChorusError::Api {
+            error: unknown_route.unwrap()
+        }
+        .is_unknown_route());
+
+        let unknown_channel =
+            ApiError::from_body(404, r#"{"code": 10003, "message": "Unknown Channel"}"#);
+        assert!(!ChorusError::Api {
+            error: unknown_channel.unwrap()
+        }
+        .is_unknown_route());
+
+        let html = ChorusError::NotFound {
+            error: "<h1>Not Found</h1>".to_string(),
+        };
+        assert!(html.is_unknown_route());
+        assert!(!ChorusError::NoPermission.is_unknown_route());
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn api_error_field_errors() {
+        let body = r#"{
+            "code": 50035,
+            "message": "Invalid Form Body",
+            "errors": {
+                "content": {"_errors": [{"code": "BASE_TYPE_MAX_LENGTH", "message": "Must be 2000 or fewer in length."}]},
+                "embeds": {"0": {"title": {"_errors": [{"code": "BASE_TYPE_REQUIRED", "message": "This field is required"}]}}},
+                "login": {"code": "INVALID_LOGIN", "message": "Login or password is invalid."}
+            }
+        }"#;
+        let error = ApiError::from_body(400, body).unwrap();
+        assert_eq!(error.code, JsonErrorCode::InvalidFormBody);
+
+        let mut fields = error
+            .field_errors
+            .iter()
+            .map(|error| (error.path.as_str(), error.code.as_str()))
+            .collect::<Vec<_>>();
+        fields.sort();
+        assert_eq!(
+            fields,
+            [
+                ("content", "BASE_TYPE_MAX_LENGTH"),
+                ("embeds.0.title", "BASE_TYPE_REQUIRED"),
+                ("login", "INVALID_LOGIN"),
+            ]
+        );
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn gateway_error_close_code_roundtrip() {
+        for code in 4000..=4014 {
+            let close_code = CloseCode::try_from(code).unwrap();
+            let error = GatewayError::from(close_code);
+
+            assert_eq!(error.close_code(), Some(close_code));
+            assert_eq!(error.recovery(), close_code.recovery());
+        }
+
+        assert_eq!(GatewayError::NoResponse.close_code(), None);
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn gateway_error_recovery() {
+        assert!(GatewayError::RateLimited.is_resumable());
+        assert!(GatewayError::Decode.is_resumable());
+        assert!(GatewayError::ConnectionLost.is_resumable());
+        assert!(GatewayError::CannotConnect {
+            error: "test".to_string()
+        }
+        .is_resumable());
+
+        assert_eq!(
+            GatewayError::InvalidSequenceNumber.recovery(),
+            SessionRecovery::Reidentify
+        );
+        assert_eq!(
+            GatewayError::SessionTimedOut.recovery(),
+            SessionRecovery::Reidentify
+        );
+        assert_eq!(
+            GatewayError::from(CloseCode::SessionNoLongerValid),
+            GatewayError::SessionNoLongerValid
+        );
+
+        for error in [
+            GatewayError::AuthenticationFailed,
+            GatewayError::InvalidShard,
+            GatewayError::ShardingRequired,
+            GatewayError::InvalidIntents,
+            GatewayError::DisallowedIntents,
+            GatewayError::NonHelloOnInitiate { opcode: 0 },
+        ] {
+            assert_eq!(error.recovery(), SessionRecovery::Abort);
+            assert!(!error.is_resumable());
+        }
     }
 }
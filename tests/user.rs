@@ -2,11 +2,15 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::time::Duration;
+
 use chorus::{
     errors::ChorusError,
+    instance::InstanceSoftware,
     types::{
-        ConnectionType, DeleteDisableUserSchema, PublicUser, Snowflake, User,
-        UserModifyProfileSchema, UserNote,
+        ConnectionType, DeleteDisableUserSchema, ModifyChannelOverrideSchema,
+        ModifyUserGuildSettingsSchema, PublicUser, Snowflake, User, UserModifyProfileSchema,
+        UserModifySettingsSchema, UserNote, UserNotificationLevel, UserTheme,
     },
 };
 #[cfg(target_arch = "wasm32")]
@@ -210,3 +214,127 @@ async fn test_get_connections() {
 
     common::teardown(bundle).await;
 }
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn test_modify_guild_settings() {
+    let mut bundle = common::setup().await;
+
+    let guild_id = bundle.guild.read().unwrap().id;
+    let channel_id = bundle.channel.read().unwrap().id;
+
+    let schema = ModifyUserGuildSettingsSchema {
+        muted: Some(true),
+        message_notifications: Some(UserNotificationLevel::OnlyMentions),
+        channel_overrides: Some(
+            [(
+                channel_id,
+                ModifyChannelOverrideSchema {
+                    message_notifications: Some(UserNotificationLevel::NoMessages),
+                    ..Default::default()
+                },
+            )]
+            .into(),
+        ),
+        ..Default::default()
+    };
+
+    let settings = bundle
+        .user
+        .modify_guild_settings(guild_id, schema)
+        .await
+        .unwrap();
+
+    assert_eq!(settings.guild_id, Some(guild_id));
+    assert!(settings.muted);
+    assert_eq!(
+        settings.message_notifications,
+        UserNotificationLevel::OnlyMentions
+    );
+    assert!(settings.channel_overrides.iter().any(|channel_override| {
+        channel_override.channel_id == channel_id
+            && channel_override.message_notifications == UserNotificationLevel::NoMessages
+    }));
+    common::teardown(bundle).await;
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn test_modify_settings() {
+    let mut bundle = common::setup().await;
+
+    let schema = UserModifySettingsSchema {
+        theme: Some(UserTheme::Light),
+        locale: Some(String::from("de")),
+        ..Default::default()
+    };
+
+    let settings = bundle.user.modify_settings(schema).await.unwrap();
+    assert_eq!(settings.theme, UserTheme::Light);
+    assert_eq!(settings.locale, "de");
+
+    // The user's shared settings are updated as well
+    {
+        let shared_settings = bundle.user.settings.read().unwrap();
+        assert_eq!(shared_settings.theme, UserTheme::Light);
+        assert_eq!(shared_settings.locale, "de");
+    }
+
+    let fetched_settings = bundle.user.get_settings().await.unwrap();
+    assert_eq!(fetched_settings.theme, UserTheme::Light);
+    common::teardown(bundle).await;
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn test_harvest_unsupported_on_spacebar() {
+    let mut bundle = common::setup().await;
+    bundle
+        .user
+        .belongs_to
+        .write()
+        .unwrap()
+        .set_software(InstanceSoftware::SpacebarTypescript);
+
+    let result = bundle.user.create_harvest(Vec::new()).await;
+    assert!(matches!(
+        result,
+        Err(ChorusError::UnsupportedByInstance { .. })
+    ));
+
+    let result = bundle.user.get_harvest().await;
+    assert!(matches!(
+        result,
+        Err(ChorusError::UnsupportedByInstance { .. })
+    ));
+
+    let result = bundle.user.wait_for_harvest(Duration::from_secs(1)).await;
+    assert!(matches!(
+        result,
+        Err(ChorusError::UnsupportedByInstance { .. })
+    ));
+
+    let result = bundle.user.delete_harvest().await;
+    assert!(matches!(
+        result,
+        Err(ChorusError::UnsupportedByInstance { .. })
+    ));
+    common::teardown(bundle).await;
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn test_get_many_users() {
+    let mut bundle = common::setup().await;
+
+    let user_id = bundle.user.object.read().unwrap().id;
+    let unknown_id = Snowflake(1);
+
+    let result = User::get_many(&mut bundle.user, &[user_id, unknown_id]).await;
+
+    assert!(!result.is_complete());
+    assert_eq!(result.fetched[&user_id].id, user_id);
+    assert!(result.errors.contains_key(&unknown_id));
+
+    common::teardown(bundle).await;
+}